@@ -0,0 +1,73 @@
+// Background picture beneath the cell grid (colors.background).
+// One quad sampling an image or gradient texture, faded toward the theme
+// background by the dim factor and drawn at the window opacity. Cells with
+// the default background are drawn clear over it by grid.wgsl.
+
+struct BackgroundUniforms {
+    // NDC position of the quad (x, y = top-left corner in NDC)
+    position: vec2<f32>,
+    // NDC size of the quad (width, height)
+    size: vec2<f32>,
+    // UV rect within the texture (u, v, w, h); beyond 1.0 repeats when tiled
+    source_uv: vec4<f32>,
+    // rgb: theme background (linear), a: dim factor
+    dim_color: vec4<f32>,
+    // x: window opacity, y: 1.0 = surface composites premultiplied alpha
+    alpha: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: BackgroundUniforms;
+
+@group(0) @binding(1)
+var picture_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var picture_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Expand vertex index to quad corners (two triangles: 0,1,2 and 2,1,3)
+    let quad_index = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),  // top-left
+        vec2<f32>(1.0, 0.0),  // top-right
+        vec2<f32>(0.0, 1.0),  // bottom-left
+        vec2<f32>(0.0, 1.0),  // bottom-left
+        vec2<f32>(1.0, 0.0),  // top-right
+        vec2<f32>(1.0, 1.0),  // bottom-right
+    );
+
+    let corner = quad_index[vertex_index];
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(
+        uniforms.position.x + corner.x * uniforms.size.x,
+        uniforms.position.y - corner.y * uniforms.size.y,
+        0.0,
+        1.0,
+    );
+    out.uv = vec2<f32>(
+        uniforms.source_uv.x + corner.x * uniforms.source_uv.z,
+        uniforms.source_uv.y + corner.y * uniforms.source_uv.w,
+    );
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The texture is sRGB, so samples are already linear
+    let texel = textureSampleLevel(picture_texture, picture_sampler, in.uv, 0.0);
+    // Transparent parts of the image show the theme background
+    let picture = mix(uniforms.dim_color.rgb, texel.rgb, texel.a);
+    let color = mix(picture, uniforms.dim_color.rgb, uniforms.dim_color.a);
+    let alpha = uniforms.alpha.x;
+    if uniforms.alpha.y > 0.5 {
+        return vec4<f32>(color * alpha, alpha);
+    }
+    return vec4<f32>(color, alpha);
+}
@@ -1,6 +1,9 @@
 // Terminal grid cell rendering shader.
 // Vertex shader expands vertex index (0-5) to quad corners.
 // Fragment shader samples glyph atlas texture and blends fg/bg colors.
+// Backgrounds are drawn at the window opacity; glyphs, decorations and the
+// cursor stay opaque over them. Over a background picture (colors.background)
+// cells with the default background are clear, so the picture shows through.
 // Supports underline, strikethrough decorations, and cursor rendering via cell flags.
 
 struct Uniforms {
@@ -9,6 +12,12 @@ struct Uniforms {
     atlas_size: vec2<f32>,   // atlas texture dimensions in pixels
     flags: vec2<f32>,        // x: 1.0 = RGBA atlas (per-channel subpixel), 0.0 = R8 (grayscale alpha)
                              // y: cursor height ratio — (font ascent+descent) / cell_height
+    filter_r: vec4<f32>,     // color filter matrix rows (linear RGB, xyz used)
+    filter_g: vec4<f32>,
+    filter_b: vec4<f32>,
+    background: vec4<f32>,   // x: background opacity (window.opacity)
+                             // y: 1.0 = surface composites premultiplied alpha
+                             // z: 1.0 = drawn over a background picture (blended)
 };
 
 @group(0) @binding(0)
@@ -29,6 +38,8 @@ struct CellInstance {
     @location(4) flags: u32,            // bit 0: has_glyph, bit 1: is_cursor,
                                         // bits 2-3: cursor shape (00=block, 01=beam, 10=underline, 11=hollow)
                                         // bit 4: underline, bit 5: strikethrough, bit 6: selected
+                                        // bit 12: default background,
+                                        // bit 13: cursor shape is the rect in atlas_uv
 };
 
 struct VertexOutput {
@@ -44,6 +55,10 @@ struct VertexOutput {
     @location(8) cursor_shape: f32,     // 0=block, 1=beam, 2=underline, 3=hollow
     @location(9) cell_x_frac: f32,      // 0.0 at left, 1.0 at right
     @location(10) is_selected: f32,     // 1.0 if selected flag set
+    @location(11) default_bg: f32,      // 1.0 if default background flag set
+    // Animated cursor: left, top, right, bottom, as fractions of the cell
+    // width and the font height (w < 0 = use cursor_shape)
+    @location(12) @interpolate(flat) cursor_rect: vec4<f32>,
 };
 
 @vertex
@@ -92,6 +107,8 @@ fn vs_main(
     out.is_cursor = f32((cell.flags >> 1u) & 1u);
     out.cursor_shape = f32((cell.flags >> 2u) & 3u);
     out.is_selected = f32((cell.flags >> 6u) & 1u);
+    out.default_bg = f32((cell.flags >> 12u) & 1u);
+    out.cursor_rect = select(vec4<f32>(-1.0), cell.atlas_uv, ((cell.flags >> 13u) & 1u) == 1u);
     return out;
 }
 
@@ -108,6 +125,42 @@ fn srgb3_to_linear(c: vec3<f32>) -> vec3<f32> {
     return vec3<f32>(srgb_to_linear(c.r), srgb_to_linear(c.g), srgb_to_linear(c.b));
 }
 
+// Apply the per-pane accessibility color filter (identity when disabled).
+fn apply_filter(c: vec3<f32>) -> vec3<f32> {
+    let filtered = vec3<f32>(
+        dot(uniforms.filter_r.xyz, c),
+        dot(uniforms.filter_g.xyz, c),
+        dot(uniforms.filter_b.xyz, c),
+    );
+    return clamp(filtered, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Output `color` at `alpha`, premultiplied if the surface composites that way
+// or the cell is blended over a background picture.
+fn with_alpha(color: vec3<f32>, alpha: f32) -> vec4<f32> {
+    if uniforms.background.y > 0.5 || uniforms.background.z > 0.5 {
+        return vec4<f32>(color * alpha, alpha);
+    }
+    return vec4<f32>(color, alpha);
+}
+
+// Sample the atlas with horizontal subpixel placement. Glyph UVs map texels
+// 1:1 to pixels, but at fractional scales (1.25x, 1.5x) a cell starts at a
+// fractional pixel offset. Snapping to the nearest texel shifts each glyph by
+// a different fraction per column and makes edges shimmer; blending the two
+// texels around the sample point places the glyph at its exact offset.
+// At integer offsets the weight is 0 and this is the plain nearest sample.
+fn sample_subpixel(uv: vec2<f32>) -> vec4<f32> {
+    let x = uv.x * uniforms.atlas_size.x - 0.5;
+    let left = floor(x);
+    let t = x - left;
+    let u0 = (left + 0.5) / uniforms.atlas_size.x;
+    let u1 = (left + 1.5) / uniforms.atlas_size.x;
+    let a = textureSampleLevel(atlas_texture, atlas_sampler, vec2<f32>(u0, uv.y), 0.0);
+    let b = textureSampleLevel(atlas_texture, atlas_sampler, vec2<f32>(u1, uv.y), 0.0);
+    return mix(a, b, t);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Cursor rendering — draw cursor shape using bg_color as cursor color
@@ -116,12 +169,22 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Glyphs are bottom-aligned in cells (CoreText draws at y=descent in Y-up context),
     // so all line-height padding is at the TOP of the cell. The cursor must match.
     if in.is_cursor > 0.5 {
-        let cursor_color = srgb3_to_linear(in.bg_color.rgb);
+        let cursor_color = apply_filter(srgb3_to_linear(in.bg_color.rgb));
         let shape = u32(in.cursor_shape + 0.5);
         let ratio = select(uniforms.flags.y, 1.0, uniforms.flags.y <= 0.0);
         let pad = 1.0 - ratio;  // all padding at top — cursor is bottom-aligned
         let in_cursor_y = in.cell_y_frac >= pad;
 
+        if in.cursor_rect.w >= 0.0 {
+            // Animated cursor: fill the rect it has eased to
+            let r = in.cursor_rect;
+            let font_y = (in.cell_y_frac - pad) / ratio;
+            if in.cell_x_frac >= r.x && in.cell_x_frac <= r.z && font_y >= r.y && font_y <= r.w {
+                return vec4<f32>(cursor_color, 1.0);
+            }
+            discard;
+        }
+
         if shape == 0u {
             // Block cursor: fill font metrics area (bottom-aligned)
             if in_cursor_y {
@@ -162,34 +225,40 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     }
 
     var color: vec3<f32>;
+    // Default-background cells let a background picture show through
+    let layered = uniforms.background.z > 0.5 && in.default_bg > 0.5 && in.is_selected < 0.5;
+    let opacity = select(uniforms.background.x, 0.0, layered);
+    var alpha = opacity;
 
     if in.has_glyph < 0.5 {
         // No glyph — start with background color
         color = bg;
     } else {
-        let sample = textureSampleLevel(atlas_texture, atlas_sampler, in.uv, 0.0);
+        let sample = sample_subpixel(in.uv);
+        var coverage: vec3<f32>;
         if uniforms.flags.x > 0.5 {
-            // RGBA atlas (CoreText subpixel AA): per-channel blending
-            color = vec3<f32>(
-                mix(bg.r, fg.r, sample.r),
-                mix(bg.g, fg.g, sample.g),
-                mix(bg.b, fg.b, sample.b),
-            );
+            // RGBA atlas (CoreText, or swash subpixel AA): per-channel blending
+            coverage = sample.rgb;
         } else {
             // R8 atlas (swash grayscale): single alpha blend
-            color = mix(bg, fg, sample.r);
+            coverage = vec3<f32>(sample.r);
         }
+        // Glyph over the translucent background, then back to straight color
+        alpha = mix(opacity, 1.0, max(coverage.r, max(coverage.g, coverage.b)));
+        color = mix(bg * opacity, fg, coverage) / max(alpha, 0.0001);
     }
 
     // Underline: draw a line at the bottom ~7% of the cell (roughly 1-2px at typical sizes)
     if in.underline > 0.5 && in.cell_y_frac > 0.9 {
         color = fg;
+        alpha = 1.0;
     }
 
     // Strikethrough: draw a line at the vertical center ~7% band
     if in.strikethrough > 0.5 && in.cell_y_frac > 0.46 && in.cell_y_frac < 0.54 {
         color = fg;
+        alpha = 1.0;
     }
 
-    return vec4<f32>(color, 1.0);
+    return with_alpha(apply_filter(color), alpha);
 }
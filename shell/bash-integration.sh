@@ -64,3 +64,16 @@ __veloterm_preexec() {
 
 PROMPT_COMMAND="__veloterm_precmd"
 trap '__veloterm_preexec' DEBUG
+
+# Theme change signal handler — VeloTerm sends SIGUSR1 on theme switches.
+# Re-export VELOTERM_THEME and COLORFGBG so programs started afterwards pick the
+# matching light/dark variant; programs already running should query OSC 11.
+__veloterm_theme_reload() {
+    if [[ -f /tmp/veloterm-theme ]]; then
+        export VELOTERM_THEME="$(< /tmp/veloterm-theme)"
+    fi
+    if [[ -f /tmp/veloterm-colorfgbg ]]; then
+        export COLORFGBG="$(< /tmp/veloterm-colorfgbg)"
+    fi
+}
+trap '__veloterm_theme_reload' USR1
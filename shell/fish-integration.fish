@@ -33,3 +33,15 @@ function __veloterm_fish_preexec --on-event fish_preexec --description "VeloTerm
     printf '\e]133;B\a'
     printf '\e]133;C\a'
 end
+
+# Theme change signal handler — VeloTerm sends SIGUSR1 on theme switches.
+# Re-export VELOTERM_THEME and COLORFGBG so programs started afterwards pick the
+# matching light/dark variant; programs already running should query OSC 11.
+function __veloterm_theme_reload --on-signal SIGUSR1 --description "VeloTerm theme change"
+    if test -f /tmp/veloterm-theme
+        set -gx VELOTERM_THEME (cat /tmp/veloterm-theme)
+    end
+    if test -f /tmp/veloterm-colorfgbg
+        set -gx COLORFGBG (cat /tmp/veloterm-colorfgbg)
+    end
+end
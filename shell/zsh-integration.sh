@@ -39,11 +39,16 @@ autoload -Uz add-zsh-hook
 add-zsh-hook precmd __veloterm_precmd
 add-zsh-hook preexec __veloterm_preexec
 
-# Theme change signal handler — re-read VELOTERM_THEME and reload p10k on SIGUSR1
+# Theme change signal handler — re-read VELOTERM_THEME and reload p10k on SIGUSR1.
+# COLORFGBG is re-exported so programs started afterwards (vim, neovim, etc.) pick
+# the matching light/dark variant; programs already running should query OSC 11.
 TRAPUSR1() {
     if [[ -f /tmp/veloterm-theme ]]; then
         export VELOTERM_THEME="$(< /tmp/veloterm-theme)"
     fi
+    if [[ -f /tmp/veloterm-colorfgbg ]]; then
+        export COLORFGBG="$(< /tmp/veloterm-colorfgbg)"
+    fi
     [[ -f ~/.p10k.zsh ]] && source ~/.p10k.zsh
     if (( $+functions[p10k] )); then
         p10k reload
@@ -0,0 +1,48 @@
+// Accessibility helpers: hand plain-text screen exports to external tools.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Build the shell invocation for a user-configured line view command.
+/// Returns (program, args); the command string is interpreted by `sh -c`
+/// so users can include arguments and pipes.
+pub fn build_line_view_command(command: &str) -> (String, Vec<String>) {
+    ("sh".to_string(), vec!["-c".to_string(), command.to_string()])
+}
+
+/// Spawn `command` and write `text` to its stdin on a background thread.
+pub fn pipe_line_view(command: &str, text: String) {
+    let (program, args) = build_line_view_command(command);
+    let child = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            std::thread::spawn(move || {
+                if let Some(mut stdin) = child.stdin.take() {
+                    if let Err(e) = stdin.write_all(text.as_bytes()) {
+                        log::warn!("Line view write error: {e}");
+                    }
+                }
+                let _ = child.wait();
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to run line view command '{command}': {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_view_command_runs_through_shell() {
+        let (program, args) = build_line_view_command("espeak -s 180");
+        assert_eq!(program, "sh");
+        assert_eq!(args, vec!["-c", "espeak -s 180"]);
+    }
+}
@@ -0,0 +1,191 @@
+// Scrollback bookmarks: marked lines in a pane, shown in the left gutter and
+// listed in an overlay for jumping back to them.
+//
+// A bookmark remembers its buffer line (0 = oldest scrollback line) and the
+// text of the logical line it marks. Resizes rewrap lines and full scrollback
+// drops old ones, both shifting line numbers; `relocate` finds each bookmark
+// again by its text, nearest the old line first.
+
+/// Characters of the marked line kept to find it again.
+pub const FINGERPRINT_CHARS: usize = 120;
+
+/// A marked logical line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// First buffer line of the logical line.
+    pub line: usize,
+    /// Its text, up to `FINGERPRINT_CHARS`, trailing blanks trimmed.
+    pub text: String,
+}
+
+/// A pane's bookmarks, sorted by line with at most one per line.
+#[derive(Debug, Clone, Default)]
+pub struct PaneBookmarks {
+    marks: Vec<Bookmark>,
+}
+
+impl PaneBookmarks {
+    pub fn marks(&self) -> &[Bookmark] {
+        &self.marks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    /// Add a bookmark on `line`, or remove the one already there. Returns
+    /// true if a bookmark was added.
+    pub fn toggle(&mut self, line: usize, text: String) -> bool {
+        match self.marks.binary_search_by_key(&line, |m| m.line) {
+            Ok(i) => {
+                self.marks.remove(i);
+                false
+            }
+            Err(i) => {
+                self.marks.insert(i, Bookmark { line, text });
+                true
+            }
+        }
+    }
+
+    /// Drop every bookmark (the scrollback they point into was cleared).
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    /// Move bookmarks whose line no longer holds their text to the nearest
+    /// line that does, dropping those no longer in the buffer. `text_at`
+    /// gives the logical line text starting at a buffer line (see
+    /// `Terminal::logical_line_text`); `total_lines` is the buffer length.
+    pub fn relocate(&mut self, total_lines: usize, text_at: impl Fn(usize) -> Option<String>) {
+        let matches = |line: usize, text: &str| text_at(line).is_some_and(|t| t == text);
+        self.marks.retain_mut(|mark| {
+            // A line still being written (a prompt being typed at) only grows
+            match text_at(mark.line) {
+                Some(text) if text == mark.text => return true,
+                Some(text) if text.starts_with(&mark.text) => {
+                    mark.text = text;
+                    return true;
+                }
+                _ => {}
+            }
+            // Nearest first; content moves up more often than down
+            for distance in 1..total_lines {
+                let above = mark.line.checked_sub(distance);
+                let below = Some(mark.line + distance).filter(|&l| l < total_lines);
+                if above.is_none() && below.is_none() {
+                    break;
+                }
+                if let Some(line) = above.into_iter().chain(below).find(|&l| matches(l, &mark.text)) {
+                    mark.line = line;
+                    return true;
+                }
+            }
+            false
+        });
+        self.marks.sort_by_key(|m| m.line);
+        self.marks.dedup_by_key(|m| m.line);
+    }
+
+    /// Bookmarks in the viewport, as viewport rows. `first_line` is the
+    /// buffer line at the top of the viewport.
+    pub fn visible(&self, first_line: usize, rows: usize) -> impl Iterator<Item = usize> + '_ {
+        self.marks
+            .iter()
+            .filter(move |m| m.line >= first_line && m.line < first_line + rows)
+            .map(move |m| m.line - first_line)
+    }
+}
+
+/// The bookmark list overlay for the focused pane.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookmarkList {
+    pub selected: usize,
+}
+
+impl BookmarkList {
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self, len: usize) {
+        if self.selected + 1 < len {
+            self.selected += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer<'a>(lines: &'a [&str]) -> impl Fn(usize) -> Option<String> + 'a {
+        move |line| lines.get(line).map(|l| l.to_string())
+    }
+
+    #[test]
+    fn toggle_adds_then_removes() {
+        let mut marks = PaneBookmarks::default();
+        assert!(marks.toggle(5, "five".into()));
+        assert!(marks.toggle(2, "two".into()));
+        assert_eq!(marks.marks().iter().map(|m| m.line).collect::<Vec<_>>(), vec![2, 5]);
+        assert!(!marks.toggle(5, "five".into()));
+        assert_eq!(marks.marks().len(), 1);
+    }
+
+    #[test]
+    fn relocate_keeps_marks_that_still_match() {
+        let lines = ["a", "b", "c"];
+        let mut marks = PaneBookmarks::default();
+        marks.toggle(1, "b".into());
+        marks.relocate(lines.len(), buffer(&lines));
+        assert_eq!(marks.marks()[0].line, 1);
+    }
+
+    #[test]
+    fn relocate_follows_a_line_being_typed() {
+        let lines = ["a", "$ cargo build"];
+        let mut marks = PaneBookmarks::default();
+        marks.toggle(1, "$".into());
+        marks.relocate(lines.len(), buffer(&lines));
+        assert_eq!(marks.marks(), &[Bookmark { line: 1, text: "$ cargo build".into() }]);
+    }
+
+    #[test]
+    fn relocate_follows_shifted_text() {
+        // Two old lines dropped off the top of the scrollback
+        let lines = ["c", "d", "error: disk full", "f"];
+        let mut marks = PaneBookmarks::default();
+        marks.toggle(4, "error: disk full".into());
+        marks.relocate(lines.len(), buffer(&lines));
+        assert_eq!(marks.marks()[0].line, 2);
+    }
+
+    #[test]
+    fn relocate_prefers_nearest_match_and_drops_missing() {
+        let lines = ["$ make", "x", "x", "$ make", "y"];
+        let mut marks = PaneBookmarks::default();
+        marks.toggle(4, "$ make".into());
+        marks.toggle(1, "gone".into());
+        marks.relocate(lines.len(), buffer(&lines));
+        assert_eq!(marks.marks(), &[Bookmark { line: 3, text: "$ make".into() }]);
+    }
+
+    #[test]
+    fn visible_maps_lines_to_rows() {
+        let mut marks = PaneBookmarks::default();
+        marks.toggle(3, String::new());
+        marks.toggle(12, String::new());
+        assert_eq!(marks.visible(10, 5).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn list_selection_stays_in_range() {
+        let mut list = BookmarkList::default();
+        list.select_prev();
+        assert_eq!(list.selected, 0);
+        list.select_next(2);
+        list.select_next(2);
+        assert_eq!(list.selected, 1);
+    }
+}
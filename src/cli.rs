@@ -0,0 +1,469 @@
+// Command-line interface: `veloterm [global flags] [command]`. With no
+// command VeloTerm opens a window, as `veloterm run` does.
+
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::control::protocol::{Request, SplitSide};
+
+/// Terminfo entry for `TERM=veloterm`, compiled by `install-terminfo`.
+pub const TERMINFO_SOURCE: &str = include_str!("../assets/terminfo/veloterm.terminfo");
+
+#[derive(Debug, Parser)]
+#[command(name = "veloterm", version, about = "A GPU-accelerated terminal emulator with native split panes")]
+pub struct Cli {
+    /// Config file to use instead of ~/.config/veloterm/config.toml.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Named config profile: ~/.config/veloterm/profiles/NAME.toml.
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "config")]
+    pub profile: Option<String>,
+
+    /// Log level (error, warn, info, debug, trace); overrides RUST_LOG.
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<log::LevelFilter>,
+
+    /// Wayland app-id / X11 WM_CLASS for this launch, overriding
+    /// `window.class`.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub class: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options for opening a window when no command is given.
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    #[command(flatten)]
+    pub legacy: LegacyFlags,
+}
+
+/// Flags from before the subcommands, kept working but hidden from
+/// `--help`. Each stands for a subcommand.
+#[derive(Debug, Default, Args)]
+pub struct LegacyFlags {
+    /// `cli print-default-config`.
+    #[arg(long, hide = true)]
+    pub print_default_config: bool,
+    /// `cli print-effective-config`.
+    #[arg(long, hide = true)]
+    pub print_effective_config: bool,
+    /// `cli print-config-diff`.
+    #[arg(long, hide = true)]
+    pub print_config_diff: bool,
+    /// `cli list-gpus`.
+    #[arg(long, hide = true)]
+    pub list_gpus: bool,
+    /// `play TRACE`.
+    #[arg(long, hide = true, value_name = "TRACE")]
+    pub replay: Option<PathBuf>,
+}
+
+impl LegacyFlags {
+    /// The subcommand an old flag stands for, if one was given.
+    fn command(self) -> Option<Command> {
+        let cli = |command| Some(Command::Cli { command });
+        if self.print_default_config {
+            cli(CliCommand::PrintDefaultConfig)
+        } else if self.print_effective_config {
+            cli(CliCommand::PrintEffectiveConfig)
+        } else if self.print_config_diff {
+            cli(CliCommand::PrintConfigDiff)
+        } else if self.list_gpus {
+            cli(CliCommand::ListGpus)
+        } else {
+            self.replay.map(|trace| Command::Play { trace, render: None, compare: None })
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Open a terminal window (the default).
+    Run(RunArgs),
+    /// Inspect the configuration and environment without opening a window.
+    Cli {
+        #[command(subcommand)]
+        command: CliCommand,
+    },
+    /// Rebuild the panes of a trace recorded with `--record-events`,
+    /// headlessly, and print their layout and screen contents as JSON.
+    Play {
+        /// Trace file written by `veloterm run --record-events`.
+        trace: PathBuf,
+        /// Render each checkpoint (before every action, and the end) and
+        /// save it as DIR/checkpoint-NNN.png.
+        #[arg(long, value_name = "DIR")]
+        render: Option<PathBuf>,
+        /// Render each checkpoint and compare it with DIR/checkpoint-NNN.png,
+        /// failing if any differ.
+        #[arg(long, value_name = "DIR", conflicts_with = "render")]
+        compare: Option<PathBuf>,
+    },
+    /// Load and validate the config file, reporting the first error.
+    CheckConfig,
+    /// Compile the `veloterm` terminfo entry into ~/.terminfo.
+    InstallTerminfo,
+    /// Open a window on the shells kept by `veloterm --daemon`, starting the
+    /// daemon if none is running. Closing the window leaves them running.
+    Attach {
+        /// List the daemon's panes instead of opening a window.
+        #[arg(long)]
+        list: bool,
+    },
+    /// Control a running window: list panes, split, send text, switch tabs,
+    /// read a screen.
+    Ctl {
+        /// Window control socket; defaults to $VELOTERM_SOCKET, else the
+        /// newest window.
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CtlCommand {
+    /// List the panes of every tab.
+    ListPanes {
+        /// Print JSON instead of a table.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Split a pane (default: the focused one) and print the new pane's ID.
+    SplitPane {
+        #[arg(long, value_name = "ID")]
+        pane: Option<u32>,
+        /// Where the new pane goes.
+        #[arg(long, value_enum, default_value_t = SplitSide::Right)]
+        side: SplitSide,
+    },
+    /// Type text into a pane (default: the focused one); reads stdin when
+    /// no text is given.
+    SendText {
+        #[arg(long, value_name = "ID")]
+        pane: Option<u32>,
+        text: Option<String>,
+    },
+    /// Switch to a tab by index, as listed by `list-panes`.
+    ActivateTab { index: usize },
+    /// Switch to a pane's tab and focus the pane.
+    ActivatePane { pane: u32 },
+    /// Open a tab and print its pane's ID.
+    NewTab {
+        /// Directory to start the shell in.
+        #[arg(long, value_name = "DIR")]
+        cwd: Option<PathBuf>,
+        /// Title the shell can't change.
+        #[arg(long, value_name = "TITLE")]
+        title: Option<String>,
+    },
+    /// Close a pane (default: the focused one).
+    ClosePane {
+        #[arg(long, value_name = "ID")]
+        pane: Option<u32>,
+    },
+    /// Print the rows on a pane's screen (default: the focused one).
+    GetText {
+        #[arg(long, value_name = "ID")]
+        pane: Option<u32>,
+    },
+    /// Rasterize the window's font at SCALE (1.0 = font.size in pixels)
+    /// instead of the detected display scale; without SCALE, go back to
+    /// `font.scale_factor`. Kept across session restores.
+    SetFontScale { scale: Option<f64> },
+}
+
+impl CtlCommand {
+    /// The request to send, with `stdin` read for `send-text` without text.
+    pub fn into_request(self, stdin: impl FnOnce() -> std::io::Result<String>) -> std::io::Result<Request> {
+        Ok(match self {
+            CtlCommand::ListPanes { .. } => Request::ListPanes,
+            CtlCommand::SplitPane { pane, side } => Request::SplitPane { pane, side },
+            CtlCommand::SendText { pane, text } => Request::SendText {
+                pane,
+                text: match text {
+                    Some(text) => text,
+                    None => stdin()?,
+                },
+            },
+            CtlCommand::ActivateTab { index } => Request::ActivateTab { index },
+            CtlCommand::ActivatePane { pane } => Request::ActivatePane { pane },
+            CtlCommand::NewTab { cwd, title } => Request::NewTab {
+                cwd: cwd.map(|dir| dir.to_string_lossy().into_owned()),
+                title,
+            },
+            CtlCommand::ClosePane { pane } => Request::ClosePane { pane },
+            CtlCommand::GetText { pane } => Request::GetText { pane },
+            CtlCommand::SetFontScale { scale } => Request::SetFontScale { scale },
+        })
+    }
+}
+
+#[derive(Debug, Default, Args)]
+pub struct RunArgs {
+    /// Reopen the last saved session even if `session.auto_restore` is off.
+    #[arg(long)]
+    pub restore_session: bool,
+
+    /// Log input, PTY output and layout changes to PATH for `veloterm play`.
+    #[arg(long, value_name = "PATH")]
+    pub record_events: Option<PathBuf>,
+
+    /// Run headless, keeping shells alive for `veloterm attach` windows.
+    #[arg(long, conflicts_with_all = ["restore_session", "record_events", "exec", "grid", "cwd", "title"])]
+    pub daemon: bool,
+
+    /// Run COMMAND and its arguments instead of the shell. Everything after
+    /// `-e` belongs to the command; the window closes when it exits.
+    #[arg(
+        short = 'e',
+        long = "command",
+        id = "exec",
+        value_name = "COMMAND",
+        num_args = 1..,
+        allow_hyphen_values = true,
+        conflicts_with = "restore_session"
+    )]
+    pub exec: Vec<String>,
+
+    /// Open the first tab as a grid of panes, each running one of the
+    /// `|`-separated commands through the shell, e.g. "htop|ping 1.1.1.1".
+    #[arg(long, value_name = "COMMANDS", conflicts_with_all = ["exec", "restore_session"])]
+    pub grid: Option<String>,
+
+    /// Start the first pane in DIR.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Window and first tab title; the shell can't change the tab's.
+    #[arg(long, value_name = "TITLE")]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Print the fully commented default config.
+    PrintDefaultConfig,
+    /// Print the defaults merged with the config file.
+    PrintEffectiveConfig,
+    /// Print only the settings the config file changes.
+    PrintConfigDiff,
+    /// List GPU adapters usable for `renderer.gpu`.
+    ListGpus,
+    /// List the config profiles usable with `--profile`.
+    ListProfiles,
+}
+
+impl RunArgs {
+    /// The `--grid` commands, blank ones dropped.
+    pub fn grid_commands(&self) -> Vec<String> {
+        self.grid
+            .iter()
+            .flat_map(|grid| grid.split('|'))
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl Cli {
+    /// The command to run: the one an old flag stands for, else `run`
+    /// when none was given.
+    pub fn command(self) -> Command {
+        let legacy = self.legacy;
+        self.command
+            .or_else(|| legacy.command())
+            .unwrap_or(Command::Run(self.run))
+    }
+}
+
+/// Compile [`TERMINFO_SOURCE`] with `tic` into `dir` (usually ~/.terminfo).
+pub fn install_terminfo(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let source = std::env::temp_dir().join(format!("veloterm-{}.terminfo", std::process::id()));
+    std::fs::write(&source, TERMINFO_SOURCE)?;
+    let status = std::process::Command::new("tic")
+        .arg("-x")
+        .arg("-o")
+        .arg(dir)
+        .arg(&source)
+        .status();
+    let _ = std::fs::remove_file(&source);
+    match status? {
+        s if s.success() => Ok(()),
+        s => Err(std::io::Error::other(format!("tic exited with {s}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("veloterm").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn no_command_runs_with_top_level_flags() {
+        let cli = parse(&["--restore-session", "--class", "work"]);
+        assert_eq!(cli.class.as_deref(), Some("work"));
+        match cli.command() {
+            Command::Run(run) => {
+                assert!(run.restore_session);
+                assert!(run.record_events.is_none());
+            }
+            other => panic!("expected run, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn global_flags_follow_subcommands() {
+        let cli = parse(&["check-config", "--config", "/tmp/demo.toml", "--log-level", "debug"]);
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/demo.toml")));
+        assert_eq!(cli.log_level, Some(log::LevelFilter::Debug));
+        assert!(matches!(cli.command(), Command::CheckConfig));
+    }
+
+    #[test]
+    fn subcommands_parse() {
+        match parse(&["play", "trace.jsonl"]).command() {
+            Command::Play { trace, render, compare } => {
+                assert_eq!(trace, PathBuf::from("trace.jsonl"));
+                assert_eq!((render, compare), (None, None));
+            }
+            other => panic!("expected play, got {other:?}"),
+        }
+        match parse(&["play", "trace.jsonl", "--render", "shots"]).command() {
+            Command::Play { render, .. } => assert_eq!(render, Some(PathBuf::from("shots"))),
+            other => panic!("expected play, got {other:?}"),
+        }
+        assert!(matches!(
+            parse(&["cli", "list-gpus"]).command(),
+            Command::Cli { command: CliCommand::ListGpus }
+        ));
+        match parse(&["run", "--record-events", "out.jsonl"]).command() {
+            Command::Run(run) => assert_eq!(run.record_events, Some(PathBuf::from("out.jsonl"))),
+            other => panic!("expected run, got {other:?}"),
+        }
+        assert!(matches!(parse(&["attach", "--list"]).command(), Command::Attach { list: true }));
+    }
+
+    #[test]
+    fn old_flags_map_to_subcommands() {
+        let cli_command = |flag: &str| match parse(&[flag]).command() {
+            Command::Cli { command } => command,
+            other => panic!("expected cli for {flag}, got {other:?}"),
+        };
+        assert!(matches!(cli_command("--print-default-config"), CliCommand::PrintDefaultConfig));
+        assert!(matches!(cli_command("--print-effective-config"), CliCommand::PrintEffectiveConfig));
+        assert!(matches!(cli_command("--print-config-diff"), CliCommand::PrintConfigDiff));
+        assert!(matches!(cli_command("--list-gpus"), CliCommand::ListGpus));
+        match parse(&["--replay", "trace.jsonl"]).command() {
+            Command::Play { trace, render, compare } => {
+                assert_eq!(trace, PathBuf::from("trace.jsonl"));
+                assert_eq!((render, compare), (None, None));
+            }
+            other => panic!("expected play, got {other:?}"),
+        }
+        // Global flags still apply
+        let cli = parse(&["--config", "a.toml", "--print-effective-config"]);
+        assert_eq!(cli.config, Some(PathBuf::from("a.toml")));
+    }
+
+    #[test]
+    fn ctl_commands_build_requests() {
+        let no_stdin = || -> std::io::Result<String> { panic!("stdin read") };
+        match parse(&["ctl", "--socket", "/tmp/ctl.sock", "split-pane", "--side", "down"]).command() {
+            Command::Ctl { socket, command } => {
+                assert_eq!(socket, Some(PathBuf::from("/tmp/ctl.sock")));
+                assert_eq!(
+                    command.into_request(no_stdin).unwrap(),
+                    Request::SplitPane { pane: None, side: SplitSide::Down }
+                );
+            }
+            other => panic!("expected ctl, got {other:?}"),
+        }
+        let Command::Ctl { command, .. } = parse(&["ctl", "send-text", "--pane", "3", "ls\n"]).command() else {
+            panic!("expected ctl");
+        };
+        assert_eq!(
+            command.into_request(no_stdin).unwrap(),
+            Request::SendText { pane: Some(3), text: "ls\n".into() }
+        );
+        let Command::Ctl { command, .. } = parse(&["ctl", "send-text"]).command() else {
+            panic!("expected ctl");
+        };
+        assert_eq!(
+            command.into_request(|| Ok("piped".into())).unwrap(),
+            Request::SendText { pane: None, text: "piped".into() }
+        );
+        assert!(Cli::try_parse_from(["veloterm", "ctl", "activate-tab"]).is_err());
+        let Command::Ctl { command, .. } = parse(&["ctl", "set-font-scale", "2"]).command() else {
+            panic!("expected ctl");
+        };
+        assert_eq!(command.into_request(no_stdin).unwrap(), Request::SetFontScale { scale: Some(2.0) });
+    }
+
+    #[test]
+    fn daemon_flag_runs_headless() {
+        match parse(&["--daemon"]).command() {
+            Command::Run(run) => assert!(run.daemon),
+            other => panic!("expected run, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["veloterm", "--daemon", "--restore-session"]).is_err());
+    }
+
+    #[test]
+    fn startup_command_takes_the_rest_of_the_line() {
+        let cli = parse(&["--cwd", "/tmp", "--title", "merge", "-e", "vimdiff", "-c", "set wrap", "a.txt"]);
+        let Command::Run(run) = cli.command() else {
+            panic!("expected run");
+        };
+        assert_eq!(run.exec, ["vimdiff", "-c", "set wrap", "a.txt"]);
+        assert_eq!(run.cwd, Some(PathBuf::from("/tmp")));
+        assert_eq!(run.title.as_deref(), Some("merge"));
+
+        let Command::Run(run) = parse(&["run", "--command", "htop"]).command() else {
+            panic!("expected run");
+        };
+        assert_eq!(run.exec, ["htop"]);
+        assert!(Cli::try_parse_from(["veloterm", "-e"]).is_err());
+        assert!(Cli::try_parse_from(["veloterm", "--daemon", "-e", "htop"]).is_err());
+    }
+
+    #[test]
+    fn grid_splits_commands_on_bars() {
+        let Command::Run(run) = parse(&["--grid", "htop| journalctl -f ||ping 1.1.1.1"]).command() else {
+            panic!("expected run");
+        };
+        assert_eq!(run.grid_commands(), ["htop", "journalctl -f", "ping 1.1.1.1"]);
+        assert!(RunArgs::default().grid_commands().is_empty());
+        assert!(Cli::try_parse_from(["veloterm", "--grid", "htop", "-e", "top"]).is_err());
+        assert!(Cli::try_parse_from(["veloterm", "--daemon", "--grid", "htop"]).is_err());
+    }
+
+    #[test]
+    fn profile_conflicts_with_config() {
+        let cli = parse(&["--profile", "demo"]);
+        assert_eq!(cli.profile.as_deref(), Some("demo"));
+        assert!(Cli::try_parse_from(["veloterm", "--profile", "demo", "--config", "a.toml"]).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_arguments() {
+        assert!(Cli::try_parse_from(["veloterm", "--bogus"]).is_err());
+        assert!(Cli::try_parse_from(["veloterm", "--log-level", "loud"]).is_err());
+    }
+
+    #[test]
+    fn terminfo_source_extends_xterm() {
+        assert!(TERMINFO_SOURCE.contains("veloterm|"));
+        assert!(TERMINFO_SOURCE.contains("use=xterm-256color"));
+    }
+}
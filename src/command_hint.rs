@@ -0,0 +1,259 @@
+// Command-not-found hints: when a command exits with status 127, suggest the
+// closest executable on PATH by edit distance ("did you mean ...?").
+
+use std::collections::BTreeSet;
+use std::sync::{Once, OnceLock};
+
+/// Exit status shells use for "command not found".
+pub const EXIT_COMMAND_NOT_FOUND: i32 = 127;
+
+/// Names of executables found on PATH, indexed once per process.
+#[derive(Debug, Clone, Default)]
+pub struct PathIndex {
+    executables: BTreeSet<String>,
+}
+
+impl PathIndex {
+    /// Index executables in a PATH-style list of directories.
+    pub fn from_path_var(path: &str) -> Self {
+        let mut executables = BTreeSet::new();
+        for dir in std::env::split_paths(path) {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if is_executable(&entry) {
+                    if let Some(name) = entry.file_name().to_str() {
+                        executables.insert(name.to_string());
+                    }
+                }
+            }
+        }
+        Self { executables }
+    }
+
+    /// Build an index from explicit names (for tests and non-PATH sources).
+    pub fn from_names<I: IntoIterator<Item = S>, S: Into<String>>(names: I) -> Self {
+        Self {
+            executables: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether `name` is a known executable.
+    pub fn contains(&self, name: &str) -> bool {
+        self.executables.contains(name)
+    }
+
+    /// Number of indexed executables.
+    pub fn len(&self) -> usize {
+        self.executables.len()
+    }
+
+    /// True if no executables were found.
+    pub fn is_empty(&self) -> bool {
+        self.executables.is_empty()
+    }
+
+    /// Closest executable to `name`, or None if nothing is close enough.
+    /// Allows one edit for short names and roughly one per three characters
+    /// beyond that. Ties go to the alphabetically first name.
+    pub fn closest(&self, name: &str) -> Option<&str> {
+        if name.is_empty() || self.contains(name) {
+            return None;
+        }
+        let len = name.chars().count();
+        let max_distance = (len / 3).max(1);
+        self.executables
+            .iter()
+            .filter(|exe| exe.chars().count().abs_diff(len) <= max_distance)
+            .map(|exe| (edit_distance(name, exe), exe))
+            .filter(|(d, _)| *d <= max_distance)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, exe)| exe.as_str())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(entry: &std::fs::DirEntry) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    entry
+        .metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(entry: &std::fs::DirEntry) -> bool {
+    entry.metadata().is_ok_and(|m| m.is_file())
+}
+
+static PATH_INDEX: OnceLock<PathIndex> = OnceLock::new();
+static PATH_INDEX_STARTED: Once = Once::new();
+static EMPTY_INDEX: PathIndex = PathIndex {
+    executables: BTreeSet::new(),
+};
+
+/// The process-wide PATH index. The first call starts indexing on a
+/// background thread; until that finishes the index is empty, so a
+/// directory scan never stalls the UI thread.
+pub fn path_index() -> &'static PathIndex {
+    if let Some(index) = PATH_INDEX.get() {
+        return index;
+    }
+    PATH_INDEX_STARTED.call_once(|| {
+        std::thread::spawn(|| {
+            let index = PathIndex::from_path_var(&std::env::var("PATH").unwrap_or_default());
+            log::info!("Indexed {} executables on PATH", index.len());
+            let _ = PATH_INDEX.set(index);
+        });
+    });
+    PATH_INDEX.get().unwrap_or(&EMPTY_INDEX)
+}
+
+/// Start indexing PATH early so the first hint has something to offer.
+pub fn prewarm_path_index() {
+    path_index();
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// adjacent transpositions ("sl" → "ls") each cost one.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, v) in d[0].iter_mut().enumerate() {
+        *v = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 1);
+            }
+            d[i][j] = best;
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// A pending "did you mean" correction for a pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandHint {
+    /// The command line that failed.
+    pub original: String,
+    /// The same line with the command name corrected.
+    pub corrected: String,
+}
+
+impl CommandHint {
+    /// Suggest a correction for `line` whose first word was not found.
+    /// Returns None when the line is empty or no executable is close enough.
+    pub fn for_command_line(line: &str, index: &PathIndex) -> Option<Self> {
+        let trimmed = line.trim();
+        let name = trimmed.split_whitespace().next()?;
+        let replacement = index.closest(name)?;
+        let corrected = format!("{replacement}{}", &trimmed[name.len()..]);
+        Some(Self {
+            original: trimmed.to_string(),
+            corrected,
+        })
+    }
+
+    /// Inline hint text shown next to the prompt.
+    pub fn label(&self) -> String {
+        format!("did you mean `{}`?", self.corrected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> PathIndex {
+        PathIndex::from_names(["git", "grep", "ls", "cargo", "python3", "make"])
+    }
+
+    // ── Edit distance ───────────────────────────────────────────────
+
+    #[test]
+    fn edit_distance_basics() {
+        assert_eq!(edit_distance("git", "git"), 0);
+        assert_eq!(edit_distance("gti", "git"), 1);
+        assert_eq!(edit_distance("carg", "cargo"), 1);
+        assert_eq!(edit_distance("mkae", "make"), 1);
+        assert_eq!(edit_distance("", "ls"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    // ── PATH index ──────────────────────────────────────────────────
+
+    #[test]
+    fn closest_finds_transposition() {
+        assert_eq!(index().closest("sl"), Some("ls"));
+        assert_eq!(index().closest("gti"), Some("git"));
+    }
+
+    #[test]
+    fn closest_rejects_distant_names() {
+        assert_eq!(index().closest("xyzzy"), None);
+    }
+
+    #[test]
+    fn closest_ignores_existing_command() {
+        assert_eq!(index().closest("git"), None);
+    }
+
+    #[test]
+    fn longer_names_allow_more_edits() {
+        assert_eq!(index().closest("pyhton"), Some("python3"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_path_var_indexes_executables_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("veloterm-path-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = dir.join("mytool");
+        let data = dir.join("notes.txt");
+        std::fs::write(&exe, "#!/bin/sh\n").unwrap();
+        std::fs::write(&data, "text").unwrap();
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::set_permissions(&data, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let index = PathIndex::from_path_var(dir.to_str().unwrap());
+        assert!(index.contains("mytool"));
+        assert!(!index.contains("notes.txt"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn path_index_fills_in_from_the_background() {
+        let expected = PathIndex::from_path_var(&std::env::var("PATH").unwrap_or_default()).len();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while path_index().len() != expected {
+            assert!(std::time::Instant::now() < deadline, "PATH index never finished");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    // ── Hints ───────────────────────────────────────────────────────
+
+    #[test]
+    fn hint_keeps_arguments() {
+        let hint = CommandHint::for_command_line("gti status -s", &index()).unwrap();
+        assert_eq!(hint.corrected, "git status -s");
+        assert_eq!(hint.original, "gti status -s");
+        assert_eq!(hint.label(), "did you mean `git status -s`?");
+    }
+
+    #[test]
+    fn no_hint_for_empty_line() {
+        assert!(CommandHint::for_command_line("   ", &index()).is_none());
+    }
+}
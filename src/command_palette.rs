@@ -1,187 +1,25 @@
 // Command palette: fuzzy-search access to all VeloTerm actions.
 
-/// An action that can be dispatched from the command palette.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PaletteAction {
-    // Pane actions
-    SplitVertical,
-    SplitHorizontal,
-    ClosePane,
-    FocusNextPane,
-    FocusPrevPane,
-    // Tab actions
-    NewTab,
-    CloseTab,
-    NextTab,
-    PrevTab,
-    // Font actions
-    IncreaseFontSize,
-    DecreaseFontSize,
-    ResetFontSize,
-    // Edit actions
-    Copy,
-    Paste,
-    SelectAll,
-    // Terminal actions
-    ClearScrollback,
-    // Search
-    OpenSearch,
-    // Vi-mode
-    ToggleViMode,
-    // Window
-    NewWindow,
-    // UI toggles
-    ToggleSidebar,
-    CycleTheme,
-    // Markdown
-    PreviewMarkdown,
-}
+use crate::input::actions::{Action, ACTIONS};
 
 /// A single entry in the command palette.
 #[derive(Debug, Clone)]
 pub struct PaletteEntry {
     pub name: &'static str,
     pub description: &'static str,
-    pub keybinding: &'static str,
-    pub action: PaletteAction,
+    pub action: Action,
 }
 
-/// Return all available command palette entries.
+/// Return all available command palette entries: every registered action.
 pub fn command_registry() -> Vec<PaletteEntry> {
-    vec![
-        PaletteEntry {
-            name: "Split Pane Right",
-            description: "Split the current pane vertically",
-            keybinding: "Cmd+D",
-            action: PaletteAction::SplitVertical,
-        },
-        PaletteEntry {
-            name: "Split Pane Down",
-            description: "Split the current pane horizontally",
-            keybinding: "Cmd+Shift+D",
-            action: PaletteAction::SplitHorizontal,
-        },
-        PaletteEntry {
-            name: "Close Pane",
-            description: "Close the focused pane",
-            keybinding: "Cmd+W",
-            action: PaletteAction::ClosePane,
-        },
-        PaletteEntry {
-            name: "Focus Next Pane",
-            description: "Move focus to the next pane",
-            keybinding: "Cmd+]",
-            action: PaletteAction::FocusNextPane,
-        },
-        PaletteEntry {
-            name: "Focus Previous Pane",
-            description: "Move focus to the previous pane",
-            keybinding: "Cmd+[",
-            action: PaletteAction::FocusPrevPane,
-        },
-        PaletteEntry {
-            name: "New Tab",
-            description: "Open a new terminal tab",
-            keybinding: "Cmd+T",
-            action: PaletteAction::NewTab,
-        },
-        PaletteEntry {
-            name: "Close Tab",
-            description: "Close the current tab",
-            keybinding: "Cmd+W",
-            action: PaletteAction::CloseTab,
-        },
-        PaletteEntry {
-            name: "Next Tab",
-            description: "Switch to the next tab",
-            keybinding: "Ctrl+Tab",
-            action: PaletteAction::NextTab,
-        },
-        PaletteEntry {
-            name: "Previous Tab",
-            description: "Switch to the previous tab",
-            keybinding: "Ctrl+Shift+Tab",
-            action: PaletteAction::PrevTab,
-        },
-        PaletteEntry {
-            name: "Increase Font Size",
-            description: "Make terminal text larger",
-            keybinding: "Cmd++",
-            action: PaletteAction::IncreaseFontSize,
-        },
-        PaletteEntry {
-            name: "Decrease Font Size",
-            description: "Make terminal text smaller",
-            keybinding: "Cmd+-",
-            action: PaletteAction::DecreaseFontSize,
-        },
-        PaletteEntry {
-            name: "Reset Font Size",
-            description: "Reset terminal text to default size",
-            keybinding: "Cmd+0",
-            action: PaletteAction::ResetFontSize,
-        },
-        PaletteEntry {
-            name: "Copy",
-            description: "Copy selected text to clipboard",
-            keybinding: "Cmd+C",
-            action: PaletteAction::Copy,
-        },
-        PaletteEntry {
-            name: "Paste",
-            description: "Paste from clipboard",
-            keybinding: "Cmd+V",
-            action: PaletteAction::Paste,
-        },
-        PaletteEntry {
-            name: "Select All",
-            description: "Select all terminal content",
-            keybinding: "Cmd+A",
-            action: PaletteAction::SelectAll,
-        },
-        PaletteEntry {
-            name: "Clear Scrollback",
-            description: "Clear terminal scrollback history",
-            keybinding: "Cmd+K",
-            action: PaletteAction::ClearScrollback,
-        },
-        PaletteEntry {
-            name: "Find",
-            description: "Open search bar",
-            keybinding: "Ctrl+Shift+F",
-            action: PaletteAction::OpenSearch,
-        },
-        PaletteEntry {
-            name: "Toggle Vi Mode",
-            description: "Enter or exit vi-mode navigation",
-            keybinding: "Ctrl+Shift+Space",
-            action: PaletteAction::ToggleViMode,
-        },
-        PaletteEntry {
-            name: "New Window",
-            description: "Open a new VeloTerm window",
-            keybinding: "Cmd+N",
-            action: PaletteAction::NewWindow,
-        },
-        PaletteEntry {
-            name: "Toggle Sidebar",
-            description: "Show or hide the session sidebar",
-            keybinding: "Cmd+B",
-            action: PaletteAction::ToggleSidebar,
-        },
-        PaletteEntry {
-            name: "Cycle Theme",
-            description: "Switch to the next color theme",
-            keybinding: "",
-            action: PaletteAction::CycleTheme,
-        },
-        PaletteEntry {
-            name: "Preview Markdown",
-            description: "Preview a markdown file in the current pane",
-            keybinding: "Ctrl+Shift+M",
-            action: PaletteAction::PreviewMarkdown,
-        },
-    ]
+    ACTIONS
+        .iter()
+        .map(|spec| PaletteEntry {
+            name: spec.title,
+            description: spec.description,
+            action: spec.action,
+        })
+        .collect()
 }
 
 /// Fuzzy match a query against a target string.
@@ -297,8 +135,15 @@ impl PaletteState {
         }
     }
 
+    /// Select the result at `index` (e.g. a clicked row), if it exists.
+    pub fn select(&mut self, index: usize) {
+        if index < self.filtered.len() {
+            self.selected = index;
+        }
+    }
+
     /// Get the currently selected palette entry's action.
-    pub fn selected_action(&self) -> Option<PaletteAction> {
+    pub fn selected_action(&self) -> Option<Action> {
         let registry = command_registry();
         self.filtered
             .get(self.selected)
@@ -331,6 +176,16 @@ impl PaletteState {
     }
 }
 
+/// The results shown when at most `max_visible` fit: scrolls so that the
+/// `selected` row stays in view, keeping it near the bottom when moving down.
+pub fn visible_range(selected: usize, len: usize, max_visible: usize) -> std::ops::Range<usize> {
+    if len <= max_visible {
+        return 0..len;
+    }
+    let start = (selected + 1).saturating_sub(max_visible).min(len - max_visible);
+    start..start + max_visible
+}
+
 /// Check if the key combination should open the command palette.
 pub fn should_open_palette(
     logical_key: &winit::keyboard::Key,
@@ -354,6 +209,7 @@ pub fn should_open_palette(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{PaneCommand, TabCommand};
 
     // ── Fuzzy match tests ───────────────────────────────────────
 
@@ -430,13 +286,13 @@ mod tests {
     #[test]
     fn registry_contains_split_vertical() {
         let reg = command_registry();
-        assert!(reg.iter().any(|e| e.action == PaletteAction::SplitVertical));
+        assert!(reg.iter().any(|e| e.action == Action::Pane(PaneCommand::SplitVertical)));
     }
 
     #[test]
     fn registry_contains_new_tab() {
         let reg = command_registry();
-        assert!(reg.iter().any(|e| e.action == PaletteAction::NewTab));
+        assert!(reg.iter().any(|e| e.action == Action::Tab(TabCommand::NewTab)));
     }
 
     // ── PaletteState tests ──────────────────────────────────────
@@ -516,6 +372,33 @@ mod tests {
         assert!(!entry.unwrap().name.is_empty());
     }
 
+    #[test]
+    fn palette_finds_copy_last_output() {
+        let mut state = PaletteState::new();
+        for ch in "copy last output".chars() {
+            state.type_char(ch);
+        }
+        let expected = Action::Shell(crate::input::ShellCommand::CopyLastOutput);
+        assert_eq!(state.selected_action(), Some(expected));
+    }
+
+    #[test]
+    fn palette_select_ignores_out_of_range() {
+        let mut state = PaletteState::new();
+        state.select(3);
+        assert_eq!(state.selected, 3);
+        state.select(state.result_count());
+        assert_eq!(state.selected, 3);
+    }
+
+    #[test]
+    fn visible_range_follows_selection() {
+        assert_eq!(visible_range(0, 4, 10), 0..4);
+        assert_eq!(visible_range(5, 40, 10), 0..10);
+        assert_eq!(visible_range(12, 40, 10), 3..13);
+        assert_eq!(visible_range(39, 40, 10), 30..40);
+    }
+
     // ── Keybinding detection tests ──────────────────────────────
 
     /// Platform-appropriate trigger modifier for command palette.
@@ -1,3 +1,4 @@
+pub mod paths;
 pub mod theme;
 pub mod types;
 pub mod watcher;
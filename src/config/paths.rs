@@ -0,0 +1,133 @@
+// Where config files live: the default ~/.config/veloterm/config.toml and
+// named profiles in ~/.config/veloterm/profiles/<name>.toml, selected with
+// `--profile <name>` so separate setups (work, demos) keep separate files.
+
+use std::path::{Path, PathBuf};
+
+use super::types::ConfigError;
+
+/// Directory under the config dir holding named profiles.
+pub const PROFILES_DIR: &str = "profiles";
+
+/// The VeloTerm config directory (~/.config/veloterm).
+pub fn config_dir() -> PathBuf {
+    let home = std::env::var("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("."));
+    home.join(".config").join("veloterm")
+}
+
+/// The default config file (~/.config/veloterm/config.toml).
+pub fn default_config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// The file for profile `name` under `config_dir`. Names are plain file
+/// stems: no path separators and not `.`/`..`.
+pub fn profile_path(config_dir: &Path, name: &str) -> Result<PathBuf, ConfigError> {
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\']);
+    if !valid {
+        return Err(ConfigError::Validation(format!("invalid profile name '{name}'")));
+    }
+    Ok(config_dir.join(PROFILES_DIR).join(format!("{name}.toml")))
+}
+
+/// Names of the profiles under `config_dir`, sorted.
+pub fn list_profiles(config_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(config_dir.join(PROFILES_DIR)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// The config file to load: `--config` as given, `--profile` resolved under
+/// `config_dir` (which must exist), or the default file.
+pub fn resolve_config_path(
+    config_dir: &Path,
+    config: Option<PathBuf>,
+    profile: Option<&str>,
+) -> Result<PathBuf, ConfigError> {
+    if let Some(path) = config {
+        return Ok(path);
+    }
+    let Some(name) = profile else {
+        return Ok(config_dir.join("config.toml"));
+    };
+    let path = profile_path(config_dir, name)?;
+    if !path.exists() {
+        let available = list_profiles(config_dir);
+        let available = if available.is_empty() {
+            "none".to_string()
+        } else {
+            available.join(", ")
+        };
+        return Err(ConfigError::Validation(format!(
+            "no profile '{name}' at {} (available: {available})",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir_with_profiles(names: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(PROFILES_DIR)).unwrap();
+        for name in names {
+            std::fs::write(dir.path().join(PROFILES_DIR).join(name), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn profile_names_must_be_file_stems() {
+        let dir = Path::new("/cfg");
+        assert_eq!(profile_path(dir, "demo").unwrap(), PathBuf::from("/cfg/profiles/demo.toml"));
+        assert!(profile_path(dir, "").is_err());
+        assert!(profile_path(dir, "..").is_err());
+        assert!(profile_path(dir, "../config").is_err());
+    }
+
+    #[test]
+    fn list_profiles_sorted_toml_stems() {
+        let dir = dir_with_profiles(&["work.toml", "demo.toml", "notes.txt"]);
+        assert_eq!(list_profiles(dir.path()), vec!["demo", "work"]);
+        assert!(list_profiles(Path::new("/nonexistent/veloterm")).is_empty());
+    }
+
+    #[test]
+    fn resolve_prefers_config_then_profile_then_default() {
+        let dir = dir_with_profiles(&["demo.toml"]);
+        let explicit = PathBuf::from("/tmp/other.toml");
+        assert_eq!(resolve_config_path(dir.path(), Some(explicit.clone()), Some("demo")).unwrap(), explicit);
+        assert_eq!(
+            resolve_config_path(dir.path(), None, Some("demo")).unwrap(),
+            dir.path().join("profiles/demo.toml")
+        );
+        assert_eq!(resolve_config_path(dir.path(), None, None).unwrap(), dir.path().join("config.toml"));
+    }
+
+    #[test]
+    fn resolve_missing_profile_lists_available() {
+        let dir = dir_with_profiles(&["demo.toml", "work.toml"]);
+        let err = resolve_config_path(dir.path(), None, Some("dmeo")).unwrap_err().to_string();
+        assert!(err.contains("no profile 'dmeo'"));
+        assert!(err.contains("available: demo, work"));
+    }
+}
@@ -3,6 +3,8 @@
 // All colors derived from Anthropic's brand tokens.
 // Replaces the old custom Color struct with iced_core::Color (same layout: r, g, b, a: f32).
 
+use std::collections::HashMap;
+
 /// Re-export iced_core::Color as the canonical Color type.
 /// All existing `use crate::config::theme::Color` imports resolve to iced_core::Color
 /// without touching import statements across the codebase.
@@ -53,11 +55,68 @@ pub fn from_hex(hex: &str) -> Color {
     }
 }
 
+/// Parse a "#RRGGBB" hex color, or None if it isn't one.
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Config names of the 16 ANSI palette entries, in index order.
+pub const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_cyan",
+    "bright_white",
+];
+
+/// Config names of the theme colors a user theme can set, besides the
+/// ANSI palette entries.
+pub const THEME_COLOR_NAMES: [&str; 22] = [
+    "bg_deep",
+    "bg_surface",
+    "bg_raised",
+    "bg_hover",
+    "bg_active",
+    "bg_input",
+    "text_primary",
+    "text_secondary",
+    "text_muted",
+    "text_ghost",
+    "accent_orange",
+    "accent_blue",
+    "accent_green",
+    "accent_purple",
+    "accent_red",
+    "accent_yellow",
+    "border_subtle",
+    "border_visible",
+    "border_strong",
+    "selection",
+    "search_match",
+    "search_match_active",
+];
+
 // ── Theme struct ────────────────────────────────────────────────────
 
 /// Complete theme definition for VeloTerm.
 /// Fields follow the Anthropic design system token hierarchy.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TerminalTheme {
     /// Theme config name (e.g. "Warm Dark").
     pub name: &'static str,
@@ -122,7 +181,8 @@ pub struct TerminalTheme {
 }
 
 impl TerminalTheme {
-    /// Look up a theme by config name.
+    /// Look up a built-in theme by config name. User themes are resolved
+    /// by `ColorsConfig::resolve_theme`.
     pub fn from_name(name: &str) -> Option<Self> {
         match name {
             "warm_dark" => Some(DARK),
@@ -144,6 +204,102 @@ impl TerminalTheme {
         }
     }
 
+    /// Returns true if the terminal background is light (relative luminance > 0.5).
+    pub fn is_light(&self) -> bool {
+        let bg = self.bg_deep;
+        0.2126 * bg.r + 0.7152 * bg.g + 0.0722 * bg.b > 0.5
+    }
+
+    /// `COLORFGBG` value ("fg;bg" as ANSI indices) so programs such as vim
+    /// pick matching light/dark color variants.
+    pub fn colorfgbg(&self) -> &'static str {
+        if self.is_light() {
+            "0;15"
+        } else {
+            "15;0"
+        }
+    }
+
+    /// This theme with ANSI palette entries replaced, from a map of entry
+    /// name (see `ANSI_NAMES`) to "#RRGGBB". Unknown names and invalid
+    /// colors are ignored (config validation reports them).
+    pub fn with_ansi_overrides(mut self, overrides: &HashMap<String, String>) -> Self {
+        for (name, hex) in overrides {
+            let index = ANSI_NAMES.iter().position(|n| n == name);
+            if let (Some(index), Some(color)) = (index, parse_hex(hex)) {
+                self.ansi[index] = color;
+            }
+        }
+        self
+    }
+
+    /// This theme with colors replaced, from a map of theme color name (see
+    /// `THEME_COLOR_NAMES`) or ANSI palette entry name to "#RRGGBB". A
+    /// replaced color keeps its alpha, so translucent colors such as the
+    /// selection stay translucent. Unknown names and invalid colors are
+    /// ignored (config validation reports them).
+    pub fn with_color_overrides(mut self, overrides: &HashMap<String, String>) -> Self {
+        for (name, hex) in overrides {
+            if let (Some(slot), Some(color)) = (self.color_mut(name), parse_hex(hex)) {
+                *slot = Color { a: slot.a, ..color };
+            }
+        }
+        self
+    }
+
+    /// This theme's colors moved `t` (0.0-1.0) of the way to `other`'s,
+    /// taking `other`'s name from halfway.
+    pub fn mix(&self, other: &TerminalTheme, t: f32) -> Self {
+        let mut mixed = if t < 0.5 { *self } else { *other };
+        let (mut from, mut to) = (*self, *other);
+        for name in THEME_COLOR_NAMES.iter().chain(ANSI_NAMES.iter()) {
+            if let (Some(a), Some(b), Some(color)) =
+                (from.color_mut(name), to.color_mut(name), mixed.color_mut(name))
+            {
+                *color = color_new(
+                    a.r + (b.r - a.r) * t,
+                    a.g + (b.g - a.g) * t,
+                    a.b + (b.b - a.b) * t,
+                    a.a + (b.a - a.a) * t,
+                );
+            }
+        }
+        mixed
+    }
+
+    /// The color a theme color or ANSI palette entry name refers to.
+    fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        let color = match name {
+            "bg_deep" => &mut self.bg_deep,
+            "bg_surface" => &mut self.bg_surface,
+            "bg_raised" => &mut self.bg_raised,
+            "bg_hover" => &mut self.bg_hover,
+            "bg_active" => &mut self.bg_active,
+            "bg_input" => &mut self.bg_input,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "text_ghost" => &mut self.text_ghost,
+            "accent_orange" => &mut self.accent_orange,
+            "accent_blue" => &mut self.accent_blue,
+            "accent_green" => &mut self.accent_green,
+            "accent_purple" => &mut self.accent_purple,
+            "accent_red" => &mut self.accent_red,
+            "accent_yellow" => &mut self.accent_yellow,
+            "border_subtle" => &mut self.border_subtle,
+            "border_visible" => &mut self.border_visible,
+            "border_strong" => &mut self.border_strong,
+            "selection" => &mut self.selection,
+            "search_match" => &mut self.search_match,
+            "search_match_active" => &mut self.search_match_active,
+            _ => {
+                let index = ANSI_NAMES.iter().position(|n| *n == name)?;
+                &mut self.ansi[index]
+            }
+        };
+        Some(color)
+    }
+
     /// List of available themes: (config_name, display_name).
     pub fn available_themes() -> &'static [(&'static str, &'static str)] {
         &[
@@ -868,4 +1024,73 @@ mod tests {
     fn neon_forge_ansi_palette_length() {
         assert_eq!(NEON_FORGE.ansi.len(), 16);
     }
+
+    // ── Light/dark detection ────────────────────────────────────────
+
+    #[test]
+    fn light_theme_is_light() {
+        assert!(LIGHT.is_light());
+        assert_eq!(LIGHT.colorfgbg(), "0;15");
+    }
+
+    #[test]
+    fn dark_themes_are_not_light() {
+        for theme in [DARK, MIDNIGHT, EMBER, DUSK, NEON_FORGE] {
+            assert!(!theme.is_light(), "{} should be dark", theme.name);
+            assert_eq!(theme.colorfgbg(), "15;0");
+        }
+    }
+
+
+    // ── Palette overrides ───────────────────────────────────────────
+
+    #[test]
+    fn parse_hex_accepts_only_rrggbb() {
+        assert_color_approx(parse_hex("#ff8000").unwrap(), rgb(255, 128, 0), "parse_hex");
+        assert!(parse_hex("ff8000").is_none());
+        assert!(parse_hex("#ff80").is_none());
+        assert!(parse_hex("#gg8000").is_none());
+    }
+
+    #[test]
+    fn ansi_overrides_replace_named_entries() {
+        let overrides = HashMap::from([
+            ("red".to_string(), "#ff0000".to_string()),
+            ("bright_white".to_string(), "#fefefe".to_string()),
+            ("not_a_color".to_string(), "#000000".to_string()),
+        ]);
+        let theme = DARK.with_ansi_overrides(&overrides);
+        assert_color_approx(theme.ansi[1], rgb(255, 0, 0), "ansi[1]");
+        assert_color_approx(theme.ansi[15], rgb(254, 254, 254), "ansi[15]");
+        assert_eq!(theme.ansi[2], DARK.ansi[2]);
+    }
+
+    #[test]
+    fn color_overrides_replace_theme_and_palette_colors() {
+        let overrides = HashMap::from([
+            ("bg_deep".to_string(), "#101010".to_string()),
+            ("selection".to_string(), "#ff0000".to_string()),
+            ("green".to_string(), "#00ff00".to_string()),
+        ]);
+        let theme = DARK.with_color_overrides(&overrides);
+        assert_color_approx(theme.bg_deep, rgb(16, 16, 16), "bg_deep");
+        assert_color_approx(theme.ansi[2], rgb(0, 255, 0), "ansi[2]");
+        assert_eq!(theme.selection.r, 1.0);
+        assert_eq!(theme.selection.a, DARK.selection.a);
+        assert_eq!(theme.text_primary, DARK.text_primary);
+        let mut theme = DARK;
+        for name in THEME_COLOR_NAMES {
+            assert!(theme.color_mut(name).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn mix_interpolates_every_color() {
+        let quarter = LIGHT.mix(&DARK, 0.25);
+        assert_eq!(quarter.name, "Light");
+        assert!((quarter.bg_deep.g - (LIGHT.bg_deep.g * 0.75 + DARK.bg_deep.g * 0.25)).abs() < 1e-5);
+        assert!((quarter.ansi[4].b - (LIGHT.ansi[4].b * 0.75 + DARK.ansi[4].b * 0.25)).abs() < 1e-5);
+        assert_color_approx(LIGHT.mix(&DARK, 1.0).bg_deep, DARK.bg_deep, "mix 1.0");
+        assert_eq!(LIGHT.mix(&DARK, 0.5).name, "Warm Dark");
+    }
 }
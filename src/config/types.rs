@@ -1,16 +1,68 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+use super::theme::{TerminalTheme, ANSI_NAMES, THEME_COLOR_NAMES};
+
 const VALID_THEMES: &[&str] = &[
     "warm_dark", "midnight", "ember", "dusk", "neon_forge", "light",
     // Legacy aliases (backward compat)
     "claude_dark", "claude_light", "claude_warm",
 ];
-const VALID_CURSOR_STYLES: &[&str] = &["block", "beam", "underline"];
+const VALID_CURSOR_STYLES: &[&str] = &["block", "beam", "bar", "underline"];
+const VALID_COPY_MODES: &[&str] = &["logical", "grid"];
+const VALID_OPTION_AS_ALT: &[&str] = &["none", "left", "right", "both"];
+const VALID_SCROLLBAR_MODES: &[&str] = &["auto", "always", "never"];
+const VALID_CLIPBOARD_POLICIES: &[&str] = &["allow", "ask", "deny"];
+const VALID_COMPAT_PRESETS: &[&str] = &["standard", "legacy"];
+const VALID_SUBPIXEL_MODES: &[&str] = &["none", "rgb", "bgr"];
+const VALID_BACKGROUND_FITS: &[&str] = &["cover", "tile", "center"];
+const VALID_COLOR_FILTERS: &[&str] =
+    &["none", "deuteranopia", "protanopia", "tritanopia", "grayscale"];
+
+/// True for "#RRGGBB" hex colors.
+fn is_hex_color(s: &str) -> bool {
+    s.strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Bounds of an explicit font scale factor.
+pub const MIN_FONT_SCALE: f64 = 0.5;
+pub const MAX_FONT_SCALE: f64 = 8.0;
+
+/// True for a font scale factor glyphs can be rasterized at.
+pub fn is_valid_font_scale(scale: f64) -> bool {
+    (MIN_FONT_SCALE..=MAX_FONT_SCALE).contains(&scale)
+}
+
+/// Whether `s` can be exported as TERM.
+fn is_term_name(s: &str) -> bool {
+    !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c.is_control())
+}
+
+/// Check palette override entry names and colors.
+fn validate_ansi_overrides(
+    section: &str,
+    overrides: &HashMap<String, String>,
+) -> Result<(), ConfigError> {
+    for (name, color) in overrides {
+        if !ANSI_NAMES.contains(&name.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown palette entry '{name}' in {section}, valid entries: {}",
+                ANSI_NAMES.join(", ")
+            )));
+        }
+        if !is_hex_color(color) {
+            return Err(ConfigError::Validation(format!(
+                "{section}.{name} must be a \"#RRGGBB\" color, got '{color}'"
+            )));
+        }
+    }
+    Ok(())
+}
 
 /// Top-level application configuration.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
 pub struct Config {
     pub font: FontConfig,
     pub padding: PaddingConfig,
@@ -25,10 +77,37 @@ pub struct Config {
     pub quick_terminal: QuickTerminalConfig,
     pub session: SessionConfig,
     pub sidebar: SidebarConfig,
+    pub suggestions: SuggestionsConfig,
+    pub accessibility: AccessibilityConfig,
+    pub guides: GuidesConfig,
+    pub command_hints: CommandHintsConfig,
+    /// Per-directory badge rules (`[[directory_rules]]`).
+    pub directory_rules: Vec<DirectoryRule>,
+    /// Launch profiles for new tabs and splits (`[[profiles]]`).
+    pub profiles: Vec<ProfileConfig>,
+    /// User themes (`[themes.<name>]`), by config name.
+    pub themes: HashMap<String, ThemeConfig>,
+    pub git_status: GitStatusConfig,
+    pub renderer: RendererConfig,
+    pub search: SearchConfig,
+    pub selection: SelectionConfig,
+    pub prompt_indicator: PromptIndicatorConfig,
+    pub window: WindowConfig,
+    pub macos: MacosConfig,
+    pub minimap: MinimapConfig,
+    pub clipboard: ClipboardConfig,
+    pub panes: PanesConfig,
+    pub redaction: RedactionConfig,
+    pub daemon: DaemonConfig,
+    pub screenshot: ScreenshotConfig,
+    pub recording: RecordingConfig,
+    pub scripting: ScriptingConfig,
+    pub quick_select: QuickSelectConfig,
+    pub security: SecurityConfig,
 }
 
 /// Font configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct FontConfig {
     pub family: String,
     pub size: f64,
@@ -38,10 +117,18 @@ pub struct FontConfig {
     pub ui_family: String,
     /// Display/header font family (welcome screen, about dialog).
     pub display_family: String,
+    /// Render programming ligatures (`=>`, `!=`, `->`) as shaped glyphs.
+    pub ligatures: bool,
+    /// Subpixel (LCD) antialiasing: "none" (grayscale), or the monitor's
+    /// subpixel order, "rgb" or "bgr".
+    pub subpixel_antialiasing: String,
+    /// Scale factor glyphs are rasterized at in place of the display's
+    /// detected one; 0.0 uses the detected scale. 1.0 makes `size` pixels.
+    pub scale_factor: f64,
 }
 
 /// Terminal content padding in pixels.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PaddingConfig {
     pub top: f64,
     pub bottom: f64,
@@ -50,52 +137,269 @@ pub struct PaddingConfig {
 }
 
 /// Colors/theme configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ColorsConfig {
     pub theme: String,
+    /// Accessibility color filter: "none", "deuteranopia", "protanopia",
+    /// "tritanopia", or "grayscale". Panes can override it at runtime.
+    pub filter: String,
+    /// ANSI palette overrides for every theme: entry name ("red",
+    /// "bright_black", ...) to "#RRGGBB".
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub ansi: HashMap<String, String>,
+    /// Per-theme palette overrides keyed by theme name, applied after `ansi`.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub theme_ansi: HashMap<String, HashMap<String, String>>,
+    /// Picture drawn beneath the cell grid for every theme.
+    pub background: BackgroundConfig,
+    /// Per-theme pictures keyed by theme name, replacing `background`.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub theme_background: HashMap<String, BackgroundConfig>,
+    /// Switching or fading between a day and a night theme by the clock.
+    pub schedule: ThemeScheduleConfig,
+    /// The config's `[themes.<name>]` tables, built. Filled in when the
+    /// config is loaded.
+    #[serde(skip)]
+    pub user_themes: HashMap<String, TerminalTheme>,
+}
+
+/// A day theme and a night theme taking turns by local time
+/// (`[colors.schedule]`), replacing `theme` while enabled.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeScheduleConfig {
+    pub enabled: bool,
+    /// Theme from `day_start` to `night_start`.
+    pub day: String,
+    /// Theme from `night_start` to `day_start`.
+    pub night: String,
+    /// "HH:MM", local time.
+    pub day_start: String,
+    /// "HH:MM", local time.
+    pub night_start: String,
+    /// Minutes over which the colors fade from one theme to the other,
+    /// starting at each switch time. 0 switches at once.
+    pub transition_minutes: u32,
+}
+
+impl Default for ThemeScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            day: "light".to_string(),
+            night: "warm_dark".to_string(),
+            day_start: "07:00".to_string(),
+            night_start: "19:00".to_string(),
+            transition_minutes: 30,
+        }
+    }
+}
+
+/// An image or gradient drawn beneath the cell grid. Cells with the
+/// default background let it show through.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackgroundConfig {
+    /// Image file (PNG, JPEG, ...); `~` is the home directory. Empty for none.
+    pub image: String,
+    /// "#RRGGBB" colors of a top-to-bottom gradient, used without an image.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gradient: Vec<String>,
+    /// How the image fills the window: "cover", "tile" or "center".
+    pub fit: String,
+    /// How far the picture is blended toward the theme background (0.0-1.0),
+    /// so text over it stays readable.
+    pub dim: f64,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        Self {
+            image: String::new(),
+            gradient: Vec::new(),
+            fit: "cover".to_string(),
+            dim: 0.5,
+        }
+    }
+}
+
+impl BackgroundConfig {
+    /// Whether there is anything to draw.
+    pub fn is_enabled(&self) -> bool {
+        !self.image.is_empty() || !self.gradient.is_empty()
+    }
+
+    fn validate(&self, section: &str) -> Result<(), ConfigError> {
+        if !VALID_BACKGROUND_FITS.contains(&self.fit.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "{section}.fit must be one of {}, got '{}'",
+                VALID_BACKGROUND_FITS.join(", "),
+                self.fit
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.dim) {
+            return Err(ConfigError::Validation(format!(
+                "{section}.dim must be between 0.0 and 1.0, got {}",
+                self.dim
+            )));
+        }
+        if self.gradient.len() == 1 {
+            return Err(ConfigError::Validation(format!(
+                "{section}.gradient needs at least two colors"
+            )));
+        }
+        if let Some(color) = self.gradient.iter().find(|color| !is_hex_color(color)) {
+            return Err(ConfigError::Validation(format!(
+                "{section}.gradient colors must be \"#RRGGBB\", got '{color}'"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ColorsConfig {
+    /// The named theme with this config's palette overrides applied.
+    pub fn resolve_theme(&self, name: &str) -> Option<TerminalTheme> {
+        let theme = TerminalTheme::from_name(name).or_else(|| self.user_themes.get(name).copied())?;
+        let mut theme = theme.with_ansi_overrides(&self.ansi);
+        if let Some(overrides) = self.theme_ansi.get(name) {
+            theme = theme.with_ansi_overrides(overrides);
+        }
+        Some(theme)
+    }
+
+    /// The background picture for theme `name`.
+    pub fn resolve_background(&self, name: &str) -> &BackgroundConfig {
+        self.theme_background.get(name).unwrap_or(&self.background)
+    }
+}
+
+/// A user theme (`[themes.<name>]`): a built-in theme with some colors
+/// replaced. Usable wherever a theme name is; `inherit` is required.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThemeConfig {
+    /// The built-in theme the colors start from.
+    pub inherit: String,
+    /// Theme color (e.g. "bg_deep") or ANSI palette entry (e.g. "red") to
+    /// "#RRGGBB".
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+impl ThemeConfig {
+    fn validate(&self, name: &str) -> Result<(), ConfigError> {
+        if VALID_THEMES.contains(&name) {
+            return Err(ConfigError::Validation(format!(
+                "themes.{name} has the name of a built-in theme"
+            )));
+        }
+        if self.inherit.is_empty() {
+            return Err(ConfigError::Validation(format!(
+                "themes.{name} needs inherit, one of: {}",
+                VALID_THEMES.join(", ")
+            )));
+        }
+        if !VALID_THEMES.contains(&self.inherit.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown theme '{}' in themes.{name}.inherit, valid themes: {}",
+                self.inherit,
+                VALID_THEMES.join(", ")
+            )));
+        }
+        for (key, color) in &self.colors {
+            if !THEME_COLOR_NAMES.contains(&key.as_str()) && !ANSI_NAMES.contains(&key.as_str()) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown key '{key}' in themes.{name}, valid keys: inherit, {}, {}",
+                    THEME_COLOR_NAMES.join(", "),
+                    ANSI_NAMES.join(", ")
+                )));
+            }
+            if !is_hex_color(color) {
+                return Err(ConfigError::Validation(format!(
+                    "themes.{name}.{key} must be a \"#RRGGBB\" color, got '{color}'"
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Keybinding configuration — string key combos mapped to action names.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
 pub struct KeysConfig {
+    #[serde(flatten)]
     pub bindings: HashMap<String, String>,
 }
 
 /// Cursor configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct CursorConfig {
+    /// Shape until an application asks for another (DECSCUSR): "block",
+    /// "beam" (or "bar") or "underline".
     pub style: String,
+    /// Whether that cursor blinks.
     pub blink: bool,
-    /// Blink rate in milliseconds. 0 = disable blinking.
-    pub blink_rate: u64,
+    /// Milliseconds between blinks. 0 = disable blinking.
+    pub blink_interval: u64,
+    /// Glide between cells and ease between shapes instead of jumping.
+    pub animation: bool,
+    /// Milliseconds each glide takes.
+    pub animation_duration: u64,
 }
 
 /// Scrollback configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ScrollbackConfig {
     pub lines: u32,
+    /// Scrollbar visibility: "auto" (while scrolling), "always" or "never".
+    pub scrollbar: String,
 }
 
 /// Performance configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PerformanceConfig {
     pub fps_limit: u32,
+    /// Scrollback lines from which a resize reflows on a worker thread
+    /// (0 = always reflow inline).
+    pub background_reflow_lines: usize,
+    /// Output rate in MiB/s that counts as a flood: the pane parses a
+    /// capped amount per frame and offers to pause or interrupt (0 = off).
+    pub flood_threshold_mb: u32,
 }
 
 /// Link detection configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct LinksConfig {
     pub enabled: bool,
+    /// Schemes opened without asking; others ask first.
+    pub allowed_schemes: Vec<String>,
+    /// Schemes never opened.
+    pub blocked_schemes: Vec<String>,
+    /// When set, links to other hosts ask first. `*.example.com` covers
+    /// the domain and its subdomains.
+    pub allowed_hosts: Vec<String>,
+    /// Hosts never opened.
+    pub blocked_hosts: Vec<String>,
 }
 
+/// Schemes opened without asking by default.
+pub const DEFAULT_ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Schemes never opened by default: they run code in the browser.
+pub const DEFAULT_BLOCKED_SCHEMES: &[&str] = &["javascript", "vbscript", "data"];
+
 impl Default for LinksConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            allowed_schemes: DEFAULT_ALLOWED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+            blocked_schemes: DEFAULT_BLOCKED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+            allowed_hosts: Vec::new(),
+            blocked_hosts: Vec::new(),
+        }
     }
 }
 
 /// Shell integration configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ShellConfig {
     /// Master toggle for shell integration features.
     pub integration_enabled: bool,
@@ -104,6 +408,7 @@ pub struct ShellConfig {
     /// Enable visual bell (brief flash on BEL character).
     pub bell_enabled: bool,
     /// Shell program path. None = $SHELL, then /bin/zsh.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub program: Option<String>,
     /// Extra arguments to pass to the shell (e.g. ["--login"]).
     pub args: Vec<String>,
@@ -111,6 +416,12 @@ pub struct ShellConfig {
     pub env: HashMap<String, String>,
     /// Suppress starship prompt initialization in VeloTerm shells.
     pub disable_starship: bool,
+    /// TERM exported to the shell. None = the compatibility preset's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub term: Option<String>,
+    /// Compatibility preset: "standard" or "legacy" (plain xterm, no
+    /// truecolor, kitty keyboard, queries or advanced OSC features).
+    pub compat: String,
 }
 
 impl Default for ShellConfig {
@@ -123,17 +434,22 @@ impl Default for ShellConfig {
             args: vec![],
             env: HashMap::new(),
             disable_starship: false,
+            term: None,
+            compat: "standard".to_string(),
         }
     }
 }
 
 /// Vi-mode configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ViModeConfig {
     /// Whether vi-mode is available.
     pub enabled: bool,
     /// Keybinding to toggle vi-mode (e.g., "ctrl+shift+space").
     pub entry_key: String,
+    /// Make j/k/0/$ move by logical (unwrapped) lines like Vim with `wrap`.
+    /// gj/gk/g0/g$ always move by screen rows.
+    pub logical_lines: bool,
 }
 
 impl Default for ViModeConfig {
@@ -141,12 +457,13 @@ impl Default for ViModeConfig {
         Self {
             enabled: true,
             entry_key: "ctrl+shift+space".to_string(),
+            logical_lines: false,
         }
     }
 }
 
 /// Quick terminal (global hotkey toggle) configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct QuickTerminalConfig {
     /// Enable the quick terminal global hotkey.
     pub enabled: bool,
@@ -164,14 +481,14 @@ impl Default for QuickTerminalConfig {
 }
 
 /// Session persistence configuration.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
 pub struct SessionConfig {
     /// Automatically restore the previous session on startup.
     pub auto_restore: bool,
 }
 
 /// Sidebar configuration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SidebarConfig {
     /// Whether the sidebar is visible by default on startup.
     pub default_visible: bool,
@@ -188,233 +505,1249 @@ impl Default for SidebarConfig {
     }
 }
 
-/// Errors that can occur during config loading and validation.
-#[derive(Debug, thiserror::Error)]
-pub enum ConfigError {
-    #[error("I/O error: {0}")]
-    Io(#[from] std::io::Error),
-    #[error("parse error: {0}")]
-    Parse(String),
-    #[error("validation error: {0}")]
-    Validation(String),
+/// History-aware completion suggestion configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct SuggestionsConfig {
+    /// Show a strip of command-history matches above the prompt while typing.
+    pub enabled: bool,
 }
 
-// ── Serde intermediate structs (allow unknown keys via flatten + deny) ───
-
-#[derive(Deserialize, Default)]
-#[serde(default)]
-struct RawConfig {
-    font: RawFontConfig,
-    padding: RawPaddingConfig,
-    colors: RawColorsConfig,
-    keys: RawKeysConfig,
-    cursor: RawCursorConfig,
-    scrollback: RawScrollbackConfig,
-    performance: RawPerformanceConfig,
-    links: RawLinksConfig,
-    shell: RawShellConfig,
-    vi_mode: RawViModeConfig,
-    quick_terminal: RawQuickTerminalConfig,
-    session: RawSessionConfig,
-    sidebar: RawSidebarConfig,
+/// Accessibility configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct AccessibilityConfig {
+    /// Program that receives the screen line view on stdin (run via `sh -c`).
+    /// None = copy to the clipboard.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_view_command: Option<String>,
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawFontConfig {
-    family: String,
-    size: f64,
-    line_height: f64,
-    ui_family: String,
-    display_family: String,
+/// Visual guide configuration (column rulers, cursor crosshair, wrap markers).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GuidesConfig {
+    /// Columns after which a vertical ruler is drawn.
+    pub rulers: Vec<usize>,
+    /// Show column rulers in new panes.
+    pub show_rulers: bool,
+    /// Highlight the cursor's row and column in new panes.
+    pub crosshair: bool,
+    /// Mark soft-wrapped rows with a dot in the right gutter.
+    pub wrap_indicator: bool,
+    /// Show new panes' long lines wrapped at `soft_wrap_column`.
+    pub soft_wrap: bool,
+    /// Column after which soft wrap breaks lines, for display only.
+    pub soft_wrap_column: usize,
 }
 
-impl Default for RawFontConfig {
+impl Default for GuidesConfig {
     fn default() -> Self {
         Self {
-            family: "JetBrains Mono".to_string(),
-            size: 13.0,
-            line_height: 1.2,
-            ui_family: "Inter".to_string(),
-            display_family: "Georgia".to_string(),
+            rulers: vec![80, 100, 120],
+            show_rulers: false,
+            crosshair: false,
+            wrap_indicator: false,
+            soft_wrap: false,
+            soft_wrap_column: 100,
         }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawPaddingConfig {
-    top: f64,
-    bottom: f64,
-    left: f64,
-    right: f64,
+/// Command-not-found hint configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct CommandHintsConfig {
+    /// Suggest the closest PATH executable when a command exits with status 127.
+    pub enabled: bool,
 }
 
-impl Default for RawPaddingConfig {
-    fn default() -> Self {
-        Self {
-            top: 16.0,
-            bottom: 16.0,
-            left: 10.0,
-            right: 10.0,
-        }
-    }
+/// A badge and colors applied to panes whose working directory is under
+/// `path`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DirectoryRule {
+    /// Directory prefix; a leading `~` expands to the home directory.
+    pub path: String,
+    /// Badge text shown on the pane and prefixed to the tab title. Empty =
+    /// no badge.
+    pub badge: String,
+    /// Badge color as "#RRGGBB".
+    pub color: String,
+    /// Theme for matching panes. None = the pane's own theme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Terminal background as "#RRGGBB", over the rule's theme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawColorsConfig {
-    theme: String,
+/// A launch profile: what a pane started from the profile picker runs and
+/// how it looks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileConfig {
+    /// Name shown in the profile picker.
+    pub name: String,
+    /// Program to run instead of the shell (e.g. "ssh"). None = the shell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Arguments, replacing `shell.args` (e.g. ["prod-db-1"]).
+    pub args: Vec<String>,
+    /// Environment variables added to `shell.env`.
+    pub env: HashMap<String, String>,
+    /// Starting directory; a leading `~` expands to the home directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Theme for the profile's panes. None = `colors.theme`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Terminal background as "#RRGGBB", over the profile's theme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// Whether the profile's panes are trusted. None = `security.trust_panes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted: Option<bool>,
+    /// TERM for the profile's panes. None = `shell.term`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub term: Option<String>,
+    /// Compatibility preset for the profile's panes. None = `shell.compat`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compat: Option<String>,
+    /// Color filter for the profile's panes. None = `colors.filter`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_filter: Option<String>,
 }
 
-impl Default for RawColorsConfig {
+/// Git branch/dirty segment in the status bar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitStatusConfig {
+    /// Show the branch and dirty state for the focused pane's cwd.
+    pub enabled: bool,
+    /// Seconds before a cached status is re-read (also refreshed on cd and after each command).
+    pub refresh_interval_secs: u64,
+}
+
+impl Default for GitStatusConfig {
     fn default() -> Self {
         Self {
-            theme: "midnight".to_string(),
+            enabled: false,
+            refresh_interval_secs: 5,
         }
     }
 }
 
-#[derive(Deserialize, Default)]
-#[serde(default)]
-struct RawKeysConfig {
-    #[serde(flatten)]
-    bindings: HashMap<String, String>,
-}
-
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawCursorConfig {
-    style: String,
-    blink: bool,
-    blink_rate: u64,
+/// GPU renderer configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RendererConfig {
+    /// "auto", "low-power", "high-performance", or an adapter name substring. Applied at startup.
+    pub gpu: String,
+    /// Surface present mode: "fifo" (vsync), "mailbox", or "immediate".
+    pub present_mode: String,
+    /// Render right after keyboard input instead of waiting for the next frame.
+    pub low_latency: bool,
 }
 
-impl Default for RawCursorConfig {
+impl Default for RendererConfig {
     fn default() -> Self {
         Self {
-            style: "block".to_string(),
-            blink: true,
-            blink_rate: 500,
+            gpu: "auto".to_string(),
+            present_mode: "fifo".to_string(),
+            low_latency: false,
         }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawScrollbackConfig {
-    lines: u32,
+/// Find-in-scrollback configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchConfig {
+    /// Center the current match vertically when navigating to it.
+    pub center_match: bool,
+    /// Lines kept between the match and the viewport edge when not centering.
+    pub context_lines: usize,
+    /// Show the match position strip on the pane's right edge.
+    pub match_strip: bool,
+    /// Regex for lines also marked on the strip (e.g. `error|failed`).
+    /// Empty = matches only.
+    pub error_pattern: String,
+    /// Pause in typing, in milliseconds, before the query is searched
+    /// (0 = on every keystroke, at most 1000).
+    pub debounce_ms: u64,
 }
 
-impl Default for RawScrollbackConfig {
+impl Default for SearchConfig {
     fn default() -> Self {
-        Self { lines: 10_000 }
+        Self {
+            center_match: true,
+            context_lines: 3,
+            match_strip: true,
+            error_pattern: String::new(),
+            debounce_ms: 60,
+        }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawPerformanceConfig {
-    fps_limit: u32,
+/// Selection and copy configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelectionConfig {
+    /// How a copy turns the selection into text: "logical" or "grid".
+    pub copy_mode: String,
 }
 
-impl Default for RawPerformanceConfig {
+impl Default for SelectionConfig {
     fn default() -> Self {
-        Self { fps_limit: 60 }
+        Self {
+            copy_mode: "logical".to_string(),
+        }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawLinksConfig {
-    enabled: bool,
+/// Cursor position indicator for long command lines at the prompt.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromptIndicatorConfig {
+    /// Show the row/column indicator when the input wraps over several rows.
+    pub enabled: bool,
+    /// Add a count of characters typed since the prompt appeared.
+    pub char_count: bool,
 }
 
-impl Default for RawLinksConfig {
+impl Default for PromptIndicatorConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            char_count: false,
+        }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawShellConfig {
-    integration_enabled: bool,
-    notification_threshold_secs: u64,
-    bell_enabled: bool,
-    program: Option<String>,
-    #[serde(default)]
-    args: Vec<String>,
-    #[serde(default)]
-    env: HashMap<String, String>,
-    disable_starship: bool,
+/// Window identity reported to the window manager, and how it is composited.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowConfig {
+    /// Wayland app-id and X11 WM_CLASS (Linux only).
+    pub class: String,
+    /// Launching `veloterm` while a window is open opens a tab there
+    /// instead of starting another process.
+    pub single_instance: bool,
+    /// Window title; `{title}` is the active tab's custom title or its
+    /// focused pane's title. Empty keeps "VeloTerm".
+    pub title: String,
+    /// Opacity of the terminal background, 0.0 (clear) to 1.0 (opaque).
+    /// Text, cursor and UI chrome stay opaque.
+    pub opacity: f64,
+    /// Ask the compositor to blur what shows through a translucent
+    /// background (KDE Plasma and macOS; ignored elsewhere).
+    pub blur: bool,
 }
 
-impl Default for RawShellConfig {
+impl Default for WindowConfig {
     fn default() -> Self {
         Self {
-            integration_enabled: true,
-            notification_threshold_secs: 10,
-            bell_enabled: true,
-            program: None,
-            args: vec![],
-            env: HashMap::new(),
-            disable_starship: false,
+            class: "veloterm".to_string(),
+            single_instance: false,
+            title: "{title} - VeloTerm".to_string(),
+            opacity: 1.0,
+            blur: false,
         }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawViModeConfig {
-    enabled: bool,
-    entry_key: String,
+/// macOS-only behaviour.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MacosConfig {
+    /// Which Option keys act as Alt: "none", "left", "right" or "both".
+    pub option_as_alt: String,
 }
 
-impl Default for RawViModeConfig {
+impl Default for MacosConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            entry_key: "ctrl+shift+space".to_string(),
+            option_as_alt: "none".to_string(),
         }
     }
 }
 
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawQuickTerminalConfig {
-    enabled: bool,
-    hotkey: String,
+/// Scrollback minimap column configuration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MinimapConfig {
+    /// Show a minimap of the scrollback on the right of each pane.
+    pub enabled: bool,
+    /// Column width in logical pixels (20-400).
+    pub width: u32,
+    /// Zoom: logical pixels per buffer line (1-8). Longer scrollback is squeezed to fit.
+    pub line_height: u32,
 }
 
-impl Default for RawQuickTerminalConfig {
+impl Default for MinimapConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            hotkey: "Control+`".to_string(),
+            width: 80,
+            line_height: 2,
         }
     }
 }
 
-#[derive(Deserialize, Default)]
-#[serde(default)]
-struct RawSessionConfig {
-    auto_restore: bool,
-}
-
-
-#[derive(Deserialize)]
-#[serde(default)]
-struct RawSidebarConfig {
-    default_visible: bool,
-    width: f32,
+/// OSC 52 clipboard access by programs in the terminal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClipboardConfig {
+    /// Programs setting the clipboard: "allow", "ask" or "deny".
+    pub osc52_write: String,
+    /// Programs reading the clipboard: "allow", "ask" or "deny".
+    pub osc52_read: String,
+    /// Largest text a program may copy, in bytes (0 = no limit).
+    pub osc52_max_bytes: usize,
+    /// Ask before pasting text that spans lines; the prompt can also type
+    /// it slowly.
+    pub confirm_multiline_paste: bool,
+    /// Characters sent at a time by slow paste (1-4096).
+    pub slow_paste_chunk_size: usize,
+    /// Pause between slow paste chunks, in milliseconds (1-10000).
+    pub slow_paste_delay_ms: u64,
 }
 
-impl Default for RawSidebarConfig {
+impl Default for ClipboardConfig {
     fn default() -> Self {
         Self {
-            default_visible: true,
-            width: 200.0,
+            osc52_write: "allow".to_string(),
+            osc52_read: "ask".to_string(),
+            osc52_max_bytes: 1_048_576,
+            confirm_multiline_paste: false,
+            slow_paste_chunk_size: 1,
+            slow_paste_delay_ms: 10,
+        }
+    }
+}
+
+/// Split pane behavior.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PanesConfig {
+    /// Share of a split one keyboard resize moves its divider (0.01 to 0.5).
+    pub resize_step: f64,
+    /// Fewest terminal columns a pane keeps when it is narrower; the rest
+    /// scroll horizontally instead of rewrapping (0 = off).
+    pub min_columns: u16,
+    /// Terminal columns of every pane whatever its width, so resizing never
+    /// reflows; narrower panes scroll horizontally (0 = follow the pane).
+    pub fixed_columns: u16,
+    /// Commands opened as a grid of panes at startup, one per pane, when no
+    /// session is restored and no `-e`/`--grid` is given (empty = a shell).
+    pub startup_grid: Vec<String>,
+}
+
+impl Default for PanesConfig {
+    fn default() -> Self {
+        Self {
+            resize_step: 0.05,
+            min_columns: 0,
+            fixed_columns: 0,
+            startup_grid: Vec::new(),
+        }
+    }
+}
+
+impl PanesConfig {
+    /// Terminal columns for a pane with room for `cols`.
+    pub fn terminal_columns(&self, cols: u16) -> u16 {
+        if self.fixed_columns > 0 {
+            self.fixed_columns
+        } else {
+            cols.max(self.min_columns)
+        }
+    }
+}
+
+/// Masking of likely secrets in text leaving the terminal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RedactionConfig {
+    /// Mask secrets in copied selections, vi yanks and Copy Last Output.
+    pub copy: bool,
+    /// Mask secrets in the line view export.
+    pub export: bool,
+    /// Mask secrets in output and input written by `--record-events`.
+    pub event_traces: bool,
+    /// Use the built-in patterns (password labels, AWS keys, bearer tokens).
+    pub builtin: bool,
+    /// Extra regexes; a `secret` group limits the mask to that part.
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            copy: false,
+            export: false,
+            event_traces: false,
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// The `veloterm --daemon` process that keeps shells running for `veloterm attach`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DaemonConfig {
+    /// Scrollback per pane sent to a window that attaches, in bytes.
+    pub replay_bytes: usize,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            replay_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// Where the screenshot actions save PNGs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScreenshotConfig {
+    /// Folder for saved screenshots; empty means ~/Pictures, or home without one.
+    pub directory: String,
+    /// File name template: `{date}`, `{time}` and `{scope}` (window, pane, selection) are filled in.
+    pub filename: String,
+    /// Briefly flash the captured area to confirm.
+    pub flash: bool,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            filename: "veloterm-{date}-{time}-{scope}.png".to_string(),
+            flash: true,
+        }
+    }
+}
+
+/// Where the pane recording action saves animated GIFs, and its limits.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordingConfig {
+    /// Folder for recordings; empty means ~/Pictures, or home without one.
+    pub directory: String,
+    /// File name template: `{date}` and `{time}` are filled in.
+    pub filename: String,
+    /// Seconds after which a recording stops by itself (1-3600).
+    pub max_duration: u32,
+    /// Most frames taken per second; frames are only taken when the pane changed (1-50).
+    pub max_fps: u32,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            filename: "veloterm-{date}-{time}.gif".to_string(),
+            max_duration: 60,
+            max_fps: 15,
+        }
+    }
+}
+
+/// The Rhai script run when the config loads: config values, key callbacks and event hooks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScriptingConfig {
+    /// Run the script when it exists.
+    pub enabled: bool,
+    /// Script file; empty means init.rhai beside the config, relative paths are beside it too.
+    pub script: String,
+}
+
+impl Default for ScriptingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            script: String::new(),
+        }
+    }
+}
+
+/// Built-in quick select patterns: UUIDs, IPv4 addresses, hex hashes and
+/// container IDs.
+pub const QUICK_SELECT_PATTERNS: &[&str] = &[
+    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+    r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b",
+    r"\b[0-9a-f]{7,64}\b",
+];
+
+/// Quick select: label the matches of these patterns on screen and copy
+/// the one whose label is typed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuickSelectConfig {
+    /// Regexes, earlier ones winning where matches overlap. A capture
+    /// group limits the copied text to its first group.
+    pub patterns: Vec<String>,
+}
+
+impl Default for QuickSelectConfig {
+    fn default() -> Self {
+        Self {
+            patterns: QUICK_SELECT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+/// Filtering of escape sequences in content that can't be trusted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SecurityConfig {
+    /// Strip escape sequences and control characters from pasted text.
+    pub sanitize_paste: bool,
+    /// Whether new panes start trusted. Untrusted panes drop title, clipboard
+    /// and query sequences from their output.
+    pub trust_panes: bool,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            sanitize_paste: true,
+            trust_panes: true,
+        }
+    }
+}
+
+/// Errors that can occur during config loading and validation.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse error: {0}")]
+    Parse(String),
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("script error: {0}")]
+    Script(String),
+}
+
+// ── Serde intermediate structs (allow unknown keys via flatten + deny) ───
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawConfig {
+    font: RawFontConfig,
+    padding: RawPaddingConfig,
+    colors: RawColorsConfig,
+    keys: RawKeysConfig,
+    cursor: RawCursorConfig,
+    scrollback: RawScrollbackConfig,
+    performance: RawPerformanceConfig,
+    links: RawLinksConfig,
+    shell: RawShellConfig,
+    vi_mode: RawViModeConfig,
+    quick_terminal: RawQuickTerminalConfig,
+    session: RawSessionConfig,
+    sidebar: RawSidebarConfig,
+    suggestions: RawSuggestionsConfig,
+    accessibility: RawAccessibilityConfig,
+    guides: RawGuidesConfig,
+    command_hints: RawCommandHintsConfig,
+    directory_rules: Vec<RawDirectoryRule>,
+    profiles: Vec<RawProfileConfig>,
+    themes: HashMap<String, RawThemeConfig>,
+    git_status: RawGitStatusConfig,
+    renderer: RawRendererConfig,
+    search: RawSearchConfig,
+    selection: RawSelectionConfig,
+    prompt_indicator: RawPromptIndicatorConfig,
+    window: RawWindowConfig,
+    macos: RawMacosConfig,
+    minimap: RawMinimapConfig,
+    clipboard: RawClipboardConfig,
+    panes: RawPanesConfig,
+    redaction: RawRedactionConfig,
+    daemon: RawDaemonConfig,
+    screenshot: RawScreenshotConfig,
+    recording: RawRecordingConfig,
+    scripting: RawScriptingConfig,
+    quick_select: RawQuickSelectConfig,
+    security: RawSecurityConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawFontConfig {
+    family: String,
+    size: f64,
+    line_height: f64,
+    ui_family: String,
+    display_family: String,
+    ligatures: bool,
+    subpixel_antialiasing: String,
+    scale_factor: f64,
+}
+
+impl Default for RawFontConfig {
+    fn default() -> Self {
+        Self {
+            family: "JetBrains Mono".to_string(),
+            size: 13.0,
+            line_height: 1.2,
+            ui_family: "Inter".to_string(),
+            display_family: "Georgia".to_string(),
+            ligatures: false,
+            subpixel_antialiasing: "none".to_string(),
+            scale_factor: 0.0,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawPaddingConfig {
+    top: f64,
+    bottom: f64,
+    left: f64,
+    right: f64,
+}
+
+impl Default for RawPaddingConfig {
+    fn default() -> Self {
+        Self {
+            top: 16.0,
+            bottom: 16.0,
+            left: 10.0,
+            right: 10.0,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawColorsConfig {
+    theme: String,
+    filter: String,
+    ansi: HashMap<String, String>,
+    theme_ansi: HashMap<String, HashMap<String, String>>,
+    background: RawBackgroundConfig,
+    theme_background: HashMap<String, RawBackgroundConfig>,
+    schedule: RawThemeScheduleConfig,
+}
+
+impl Default for RawColorsConfig {
+    fn default() -> Self {
+        Self {
+            theme: "midnight".to_string(),
+            filter: "none".to_string(),
+            ansi: HashMap::new(),
+            theme_ansi: HashMap::new(),
+            background: RawBackgroundConfig::default(),
+            theme_background: HashMap::new(),
+            schedule: RawThemeScheduleConfig::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawThemeScheduleConfig {
+    enabled: bool,
+    day: String,
+    night: String,
+    day_start: String,
+    night_start: String,
+    transition_minutes: u32,
+}
+
+impl Default for RawThemeScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            day: "light".to_string(),
+            night: "warm_dark".to_string(),
+            day_start: "07:00".to_string(),
+            night_start: "19:00".to_string(),
+            transition_minutes: 30,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawBackgroundConfig {
+    image: String,
+    gradient: Vec<String>,
+    fit: String,
+    dim: f64,
+}
+
+impl Default for RawBackgroundConfig {
+    fn default() -> Self {
+        Self {
+            image: String::new(),
+            gradient: Vec::new(),
+            fit: "cover".to_string(),
+            dim: 0.5,
+        }
+    }
+}
+
+impl RawBackgroundConfig {
+    fn into_config(self) -> BackgroundConfig {
+        BackgroundConfig {
+            image: self.image,
+            gradient: self.gradient,
+            fit: self.fit,
+            dim: self.dim,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawThemeConfig {
+    inherit: String,
+    #[serde(flatten)]
+    colors: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawKeysConfig {
+    #[serde(flatten)]
+    bindings: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawCursorConfig {
+    style: String,
+    blink: bool,
+    #[serde(alias = "blink_rate")]
+    blink_interval: u64,
+    animation: bool,
+    animation_duration: u64,
+}
+
+impl Default for RawCursorConfig {
+    fn default() -> Self {
+        Self {
+            style: "block".to_string(),
+            blink: true,
+            blink_interval: 500,
+            animation: false,
+            animation_duration: 100,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawScrollbackConfig {
+    lines: u32,
+    scrollbar: String,
+}
+
+impl Default for RawScrollbackConfig {
+    fn default() -> Self {
+        Self {
+            lines: 10_000,
+            scrollbar: "auto".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawPerformanceConfig {
+    fps_limit: u32,
+    background_reflow_lines: usize,
+    flood_threshold_mb: u32,
+}
+
+impl Default for RawPerformanceConfig {
+    fn default() -> Self {
+        Self {
+            fps_limit: 60,
+            background_reflow_lines: crate::terminal::DEFAULT_BACKGROUND_REFLOW_LINES,
+            flood_threshold_mb: 16,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawLinksConfig {
+    enabled: bool,
+    allowed_schemes: Vec<String>,
+    blocked_schemes: Vec<String>,
+    allowed_hosts: Vec<String>,
+    blocked_hosts: Vec<String>,
+}
+
+impl Default for RawLinksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_schemes: DEFAULT_ALLOWED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+            blocked_schemes: DEFAULT_BLOCKED_SCHEMES.iter().map(|s| s.to_string()).collect(),
+            allowed_hosts: Vec::new(),
+            blocked_hosts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawShellConfig {
+    integration_enabled: bool,
+    notification_threshold_secs: u64,
+    bell_enabled: bool,
+    program: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    disable_starship: bool,
+    term: Option<String>,
+    compat: String,
+}
+
+impl Default for RawShellConfig {
+    fn default() -> Self {
+        Self {
+            integration_enabled: true,
+            notification_threshold_secs: 10,
+            bell_enabled: true,
+            program: None,
+            args: vec![],
+            env: HashMap::new(),
+            disable_starship: false,
+            term: None,
+            compat: "standard".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawViModeConfig {
+    enabled: bool,
+    entry_key: String,
+    logical_lines: bool,
+}
+
+impl Default for RawViModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            entry_key: "ctrl+shift+space".to_string(),
+            logical_lines: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawQuickTerminalConfig {
+    enabled: bool,
+    hotkey: String,
+}
+
+impl Default for RawQuickTerminalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hotkey: "Control+`".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawSessionConfig {
+    auto_restore: bool,
+}
+
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawSidebarConfig {
+    default_visible: bool,
+    width: f32,
+}
+
+impl Default for RawSidebarConfig {
+    fn default() -> Self {
+        Self {
+            default_visible: true,
+            width: 200.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawSuggestionsConfig {
+    enabled: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawAccessibilityConfig {
+    line_view_command: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawGuidesConfig {
+    rulers: Vec<usize>,
+    show_rulers: bool,
+    crosshair: bool,
+    wrap_indicator: bool,
+    soft_wrap: bool,
+    soft_wrap_column: usize,
+}
+
+impl Default for RawGuidesConfig {
+    fn default() -> Self {
+        Self {
+            rulers: vec![80, 100, 120],
+            show_rulers: false,
+            crosshair: false,
+            wrap_indicator: false,
+            soft_wrap: false,
+            soft_wrap_column: 100,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawCommandHintsConfig {
+    enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawDirectoryRule {
+    path: String,
+    badge: String,
+    color: String,
+    theme: Option<String>,
+    background: Option<String>,
+}
+
+impl Default for RawDirectoryRule {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            badge: String::new(),
+            color: "#E5484D".to_string(),
+            theme: None,
+            background: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RawProfileConfig {
+    name: String,
+    command: Option<String>,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    cwd: Option<String>,
+    theme: Option<String>,
+    background: Option<String>,
+    trusted: Option<bool>,
+    term: Option<String>,
+    compat: Option<String>,
+    color_filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawGitStatusConfig {
+    enabled: bool,
+    refresh_interval_secs: u64,
+}
+
+impl Default for RawGitStatusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            refresh_interval_secs: 5,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawRendererConfig {
+    gpu: String,
+    present_mode: String,
+    low_latency: bool,
+}
+
+impl Default for RawRendererConfig {
+    fn default() -> Self {
+        Self {
+            gpu: "auto".to_string(),
+            present_mode: "fifo".to_string(),
+            low_latency: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawSearchConfig {
+    center_match: bool,
+    context_lines: usize,
+    match_strip: bool,
+    error_pattern: String,
+    debounce_ms: u64,
+}
+
+impl Default for RawSearchConfig {
+    fn default() -> Self {
+        Self {
+            center_match: true,
+            context_lines: 3,
+            match_strip: true,
+            error_pattern: String::new(),
+            debounce_ms: 60,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawSelectionConfig {
+    copy_mode: String,
+}
+
+impl Default for RawSelectionConfig {
+    fn default() -> Self {
+        Self {
+            copy_mode: "logical".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawPromptIndicatorConfig {
+    enabled: bool,
+    char_count: bool,
+}
+
+impl Default for RawPromptIndicatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            char_count: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawWindowConfig {
+    class: String,
+    single_instance: bool,
+    title: String,
+    opacity: f64,
+    blur: bool,
+}
+
+impl Default for RawWindowConfig {
+    fn default() -> Self {
+        Self {
+            class: "veloterm".to_string(),
+            single_instance: false,
+            title: "{title} - VeloTerm".to_string(),
+            opacity: 1.0,
+            blur: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawMacosConfig {
+    option_as_alt: String,
+}
+
+impl Default for RawMacosConfig {
+    fn default() -> Self {
+        Self {
+            option_as_alt: "none".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawMinimapConfig {
+    enabled: bool,
+    width: u32,
+    line_height: u32,
+}
+
+impl Default for RawMinimapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            width: 80,
+            line_height: 2,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawClipboardConfig {
+    osc52_write: String,
+    osc52_read: String,
+    osc52_max_bytes: usize,
+    confirm_multiline_paste: bool,
+    slow_paste_chunk_size: usize,
+    slow_paste_delay_ms: u64,
+}
+
+impl Default for RawClipboardConfig {
+    fn default() -> Self {
+        Self {
+            osc52_write: "allow".to_string(),
+            osc52_read: "ask".to_string(),
+            osc52_max_bytes: 1_048_576,
+            confirm_multiline_paste: false,
+            slow_paste_chunk_size: 1,
+            slow_paste_delay_ms: 10,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawPanesConfig {
+    resize_step: f64,
+    min_columns: u16,
+    fixed_columns: u16,
+    startup_grid: Vec<String>,
+}
+
+impl Default for RawPanesConfig {
+    fn default() -> Self {
+        Self {
+            resize_step: 0.05,
+            min_columns: 0,
+            fixed_columns: 0,
+            startup_grid: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawRedactionConfig {
+    copy: bool,
+    export: bool,
+    event_traces: bool,
+    builtin: bool,
+    patterns: Vec<String>,
+}
+
+impl Default for RawRedactionConfig {
+    fn default() -> Self {
+        Self {
+            copy: false,
+            export: false,
+            event_traces: false,
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawDaemonConfig {
+    replay_bytes: usize,
+}
+
+impl Default for RawDaemonConfig {
+    fn default() -> Self {
+        Self {
+            replay_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawScreenshotConfig {
+    directory: String,
+    filename: String,
+    flash: bool,
+}
+
+impl Default for RawScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            filename: "veloterm-{date}-{time}-{scope}.png".to_string(),
+            flash: true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawRecordingConfig {
+    directory: String,
+    filename: String,
+    max_duration: u32,
+    max_fps: u32,
+}
+
+impl Default for RawRecordingConfig {
+    fn default() -> Self {
+        Self {
+            directory: String::new(),
+            filename: "veloterm-{date}-{time}.gif".to_string(),
+            max_duration: 60,
+            max_fps: 15,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawQuickSelectConfig {
+    patterns: Vec<String>,
+}
+
+impl Default for RawQuickSelectConfig {
+    fn default() -> Self {
+        Self {
+            patterns: QUICK_SELECT_PATTERNS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawSecurityConfig {
+    sanitize_paste: bool,
+    trust_panes: bool,
+}
+
+impl Default for RawSecurityConfig {
+    fn default() -> Self {
+        Self {
+            sanitize_paste: true,
+            trust_panes: true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+struct RawScriptingConfig {
+    enabled: bool,
+    script: String,
+}
+
+impl Default for RawScriptingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            script: String::new(),
         }
     }
 }
@@ -429,6 +1762,9 @@ impl Default for FontConfig {
             line_height: 1.2,
             ui_family: "Inter".to_string(),
             display_family: "Georgia".to_string(),
+            ligatures: false,
+            subpixel_antialiasing: "none".to_string(),
+            scale_factor: 0.0,
         }
     }
 }
@@ -448,6 +1784,13 @@ impl Default for ColorsConfig {
     fn default() -> Self {
         Self {
             theme: "midnight".to_string(),
+            filter: "none".to_string(),
+            ansi: HashMap::new(),
+            theme_ansi: HashMap::new(),
+            background: BackgroundConfig::default(),
+            theme_background: HashMap::new(),
+            schedule: ThemeScheduleConfig::default(),
+            user_themes: HashMap::new(),
         }
     }
 }
@@ -457,20 +1800,29 @@ impl Default for CursorConfig {
         Self {
             style: "block".to_string(),
             blink: true,
-            blink_rate: 500,
+            blink_interval: 500,
+            animation: false,
+            animation_duration: 100,
         }
     }
 }
 
 impl Default for ScrollbackConfig {
     fn default() -> Self {
-        Self { lines: 10_000 }
+        Self {
+            lines: 10_000,
+            scrollbar: "auto".to_string(),
+        }
     }
 }
 
 impl Default for PerformanceConfig {
     fn default() -> Self {
-        Self { fps_limit: 60 }
+        Self {
+            fps_limit: 60,
+            background_reflow_lines: crate::terminal::DEFAULT_BACKGROUND_REFLOW_LINES,
+            flood_threshold_mb: 16,
+        }
     }
 }
 
@@ -480,27 +1832,58 @@ impl Config {
     /// Load config from a TOML file path. Returns defaults if file does not exist.
     pub fn load(path: &Path) -> Result<Self, ConfigError> {
         match std::fs::read_to_string(path) {
-            Ok(contents) => Self::from_toml(&contents),
+            Ok(contents) => Self::from_toml_with_script(&contents, path),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 log::info!("No config file at {}, using defaults", path.display());
-                Ok(Self::default())
+                Self::from_toml_with_script("", path)
             }
             Err(e) => Err(ConfigError::Io(e)),
         }
     }
 
+    /// Parse the contents of the config file at `path`, then lay over the
+    /// values its script sets with `set` (see `[scripting]`).
+    pub fn from_toml_with_script(toml_str: &str, path: &Path) -> Result<Self, ConfigError> {
+        let config = Self::from_toml(toml_str)?;
+        let Some(script) = config.script_path(path) else {
+            return Ok(config);
+        };
+        let host = crate::scripting::ScriptHost::load(&script).map_err(ConfigError::Script)?;
+        if host.overrides().is_empty() {
+            return Ok(config);
+        }
+        let mut table: toml::Table =
+            toml::from_str(toml_str).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        merge_toml(&mut table, host.overrides().clone());
+        let merged = toml::to_string(&table).map_err(|e| ConfigError::Script(e.to_string()))?;
+        Self::from_toml(&merged)
+    }
+
+    /// The script to run for the config file at `config_path`, when
+    /// scripting is enabled and the script exists.
+    pub fn script_path(&self, config_path: &Path) -> Option<std::path::PathBuf> {
+        if !self.scripting.enabled {
+            return None;
+        }
+        let script = crate::scripting::script_path(&self.scripting.script, config_path);
+        script.is_file().then_some(script)
+    }
+
     /// Parse a TOML string into a Config.
     pub fn from_toml(toml_str: &str) -> Result<Self, ConfigError> {
         let raw: RawConfig =
             toml::from_str(toml_str).map_err(|e| ConfigError::Parse(e.to_string()))?;
 
-        let config = Self {
+        let mut config = Self {
             font: FontConfig {
                 family: raw.font.family,
                 size: raw.font.size,
                 line_height: raw.font.line_height,
                 ui_family: raw.font.ui_family,
                 display_family: raw.font.display_family,
+                ligatures: raw.font.ligatures,
+                subpixel_antialiasing: raw.font.subpixel_antialiasing,
+                scale_factor: raw.font.scale_factor,
             },
             padding: PaddingConfig {
                 top: raw.padding.top,
@@ -510,6 +1893,25 @@ impl Config {
             },
             colors: ColorsConfig {
                 theme: raw.colors.theme,
+                filter: raw.colors.filter,
+                ansi: raw.colors.ansi,
+                theme_ansi: raw.colors.theme_ansi,
+                background: raw.colors.background.into_config(),
+                theme_background: raw
+                    .colors
+                    .theme_background
+                    .into_iter()
+                    .map(|(theme, background)| (theme, background.into_config()))
+                    .collect(),
+                schedule: ThemeScheduleConfig {
+                    enabled: raw.colors.schedule.enabled,
+                    day: raw.colors.schedule.day,
+                    night: raw.colors.schedule.night,
+                    day_start: raw.colors.schedule.day_start,
+                    night_start: raw.colors.schedule.night_start,
+                    transition_minutes: raw.colors.schedule.transition_minutes,
+                },
+                user_themes: HashMap::new(),
             },
             keys: KeysConfig {
                 bindings: raw.keys.bindings,
@@ -517,16 +1919,25 @@ impl Config {
             cursor: CursorConfig {
                 style: raw.cursor.style,
                 blink: raw.cursor.blink,
-                blink_rate: raw.cursor.blink_rate,
+                blink_interval: raw.cursor.blink_interval,
+                animation: raw.cursor.animation,
+                animation_duration: raw.cursor.animation_duration,
             },
             scrollback: ScrollbackConfig {
                 lines: raw.scrollback.lines,
+                scrollbar: raw.scrollback.scrollbar,
             },
             performance: PerformanceConfig {
                 fps_limit: raw.performance.fps_limit,
+                background_reflow_lines: raw.performance.background_reflow_lines,
+                flood_threshold_mb: raw.performance.flood_threshold_mb,
             },
             links: LinksConfig {
                 enabled: raw.links.enabled,
+                allowed_schemes: raw.links.allowed_schemes,
+                blocked_schemes: raw.links.blocked_schemes,
+                allowed_hosts: raw.links.allowed_hosts,
+                blocked_hosts: raw.links.blocked_hosts,
             },
             shell: ShellConfig {
                 integration_enabled: raw.shell.integration_enabled,
@@ -536,10 +1947,13 @@ impl Config {
                 args: raw.shell.args,
                 env: raw.shell.env,
                 disable_starship: raw.shell.disable_starship,
+                term: raw.shell.term,
+                compat: raw.shell.compat,
             },
             vi_mode: ViModeConfig {
                 enabled: raw.vi_mode.enabled,
                 entry_key: raw.vi_mode.entry_key,
+                logical_lines: raw.vi_mode.logical_lines,
             },
             quick_terminal: QuickTerminalConfig {
                 enabled: raw.quick_terminal.enabled,
@@ -552,79 +1966,617 @@ impl Config {
                 default_visible: raw.sidebar.default_visible,
                 width: raw.sidebar.width,
             },
+            suggestions: SuggestionsConfig {
+                enabled: raw.suggestions.enabled,
+            },
+            accessibility: AccessibilityConfig {
+                line_view_command: raw.accessibility.line_view_command,
+            },
+            guides: GuidesConfig {
+                rulers: raw.guides.rulers,
+                show_rulers: raw.guides.show_rulers,
+                crosshair: raw.guides.crosshair,
+                wrap_indicator: raw.guides.wrap_indicator,
+                soft_wrap: raw.guides.soft_wrap,
+                soft_wrap_column: raw.guides.soft_wrap_column,
+            },
+            command_hints: CommandHintsConfig {
+                enabled: raw.command_hints.enabled,
+            },
+            directory_rules: raw
+                .directory_rules
+                .into_iter()
+                .map(|r| DirectoryRule {
+                    path: r.path,
+                    badge: r.badge,
+                    color: r.color,
+                    theme: r.theme,
+                    background: r.background,
+                })
+                .collect(),
+            profiles: raw
+                .profiles
+                .into_iter()
+                .map(|p| ProfileConfig {
+                    name: p.name,
+                    command: p.command,
+                    args: p.args,
+                    env: p.env,
+                    cwd: p.cwd,
+                    theme: p.theme,
+                    background: p.background,
+                    trusted: p.trusted,
+                    term: p.term,
+                    compat: p.compat,
+                    color_filter: p.color_filter,
+                })
+                .collect(),
+            themes: raw
+                .themes
+                .into_iter()
+                .map(|(name, t)| (name, ThemeConfig { inherit: t.inherit, colors: t.colors }))
+                .collect(),
+            git_status: GitStatusConfig {
+                enabled: raw.git_status.enabled,
+                refresh_interval_secs: raw.git_status.refresh_interval_secs,
+            },
+            renderer: RendererConfig {
+                gpu: raw.renderer.gpu,
+                present_mode: raw.renderer.present_mode,
+                low_latency: raw.renderer.low_latency,
+            },
+            search: SearchConfig {
+                center_match: raw.search.center_match,
+                context_lines: raw.search.context_lines,
+                match_strip: raw.search.match_strip,
+                error_pattern: raw.search.error_pattern,
+                debounce_ms: raw.search.debounce_ms,
+            },
+            selection: SelectionConfig {
+                copy_mode: raw.selection.copy_mode,
+            },
+            prompt_indicator: PromptIndicatorConfig {
+                enabled: raw.prompt_indicator.enabled,
+                char_count: raw.prompt_indicator.char_count,
+            },
+            window: WindowConfig {
+                class: raw.window.class,
+                single_instance: raw.window.single_instance,
+                title: raw.window.title,
+                opacity: raw.window.opacity,
+                blur: raw.window.blur,
+            },
+            macos: MacosConfig {
+                option_as_alt: raw.macos.option_as_alt,
+            },
+            minimap: MinimapConfig {
+                enabled: raw.minimap.enabled,
+                width: raw.minimap.width,
+                line_height: raw.minimap.line_height,
+            },
+            clipboard: ClipboardConfig {
+                osc52_write: raw.clipboard.osc52_write,
+                osc52_read: raw.clipboard.osc52_read,
+                osc52_max_bytes: raw.clipboard.osc52_max_bytes,
+                confirm_multiline_paste: raw.clipboard.confirm_multiline_paste,
+                slow_paste_chunk_size: raw.clipboard.slow_paste_chunk_size,
+                slow_paste_delay_ms: raw.clipboard.slow_paste_delay_ms,
+            },
+            panes: PanesConfig {
+                resize_step: raw.panes.resize_step,
+                min_columns: raw.panes.min_columns,
+                fixed_columns: raw.panes.fixed_columns,
+                startup_grid: raw.panes.startup_grid,
+            },
+            redaction: RedactionConfig {
+                copy: raw.redaction.copy,
+                export: raw.redaction.export,
+                event_traces: raw.redaction.event_traces,
+                builtin: raw.redaction.builtin,
+                patterns: raw.redaction.patterns,
+            },
+            daemon: DaemonConfig {
+                replay_bytes: raw.daemon.replay_bytes,
+            },
+            screenshot: ScreenshotConfig {
+                directory: raw.screenshot.directory,
+                filename: raw.screenshot.filename,
+                flash: raw.screenshot.flash,
+            },
+            recording: RecordingConfig {
+                directory: raw.recording.directory,
+                filename: raw.recording.filename,
+                max_duration: raw.recording.max_duration,
+                max_fps: raw.recording.max_fps,
+            },
+            scripting: ScriptingConfig {
+                enabled: raw.scripting.enabled,
+                script: raw.scripting.script,
+            },
+            quick_select: QuickSelectConfig {
+                patterns: raw.quick_select.patterns,
+            },
+            security: SecurityConfig {
+                sanitize_paste: raw.security.sanitize_paste,
+                trust_panes: raw.security.trust_panes,
+            },
         };
 
-        config.validate()?;
-        Ok(config)
-    }
+        config.validate()?;
+        config.colors.user_themes = config.user_themes();
+        Ok(config)
+    }
+
+    /// Validate the config, returning an error if any values are out of range.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.font.size < 8.0 {
+            return Err(ConfigError::Validation(
+                "font size must be >= 8".to_string(),
+            ));
+        }
+
+        if self.font.line_height < 0.5 || self.font.line_height > 3.0 {
+            return Err(ConfigError::Validation(
+                "font line_height must be between 0.5 and 3.0".to_string(),
+            ));
+        }
+
+        if !VALID_SUBPIXEL_MODES.contains(&self.font.subpixel_antialiasing.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown font subpixel_antialiasing '{}', valid values: {}",
+                self.font.subpixel_antialiasing,
+                VALID_SUBPIXEL_MODES.join(", ")
+            )));
+        }
+
+        if self.font.scale_factor != 0.0 && !is_valid_font_scale(self.font.scale_factor) {
+            return Err(ConfigError::Validation(format!(
+                "font scale_factor must be 0 (detected) or between {MIN_FONT_SCALE} and {MAX_FONT_SCALE}"
+            )));
+        }
+
+        if self.padding.top < 0.0
+            || self.padding.bottom < 0.0
+            || self.padding.left < 0.0
+            || self.padding.right < 0.0
+        {
+            return Err(ConfigError::Validation(
+                "padding values must be >= 0".to_string(),
+            ));
+        }
+
+        for (name, theme) in &self.themes {
+            theme.validate(name)?;
+        }
+        if !self.is_theme(&self.colors.theme) {
+            return Err(ConfigError::Validation(format!(
+                "unknown theme '{}', valid themes: {}",
+                self.colors.theme,
+                self.theme_names()
+            )));
+        }
+
+        if !VALID_COLOR_FILTERS.contains(&self.colors.filter.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown color filter '{}', valid filters: {}",
+                self.colors.filter,
+                VALID_COLOR_FILTERS.join(", ")
+            )));
+        }
+
+        validate_ansi_overrides("colors.ansi", &self.colors.ansi)?;
+        for (theme, overrides) in &self.colors.theme_ansi {
+            if !self.is_theme(theme) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown theme '{theme}' in colors.theme_ansi, valid themes: {}",
+                    self.theme_names()
+                )));
+            }
+            validate_ansi_overrides(&format!("colors.theme_ansi.{theme}"), overrides)?;
+        }
+        self.colors.background.validate("colors.background")?;
+        for (theme, background) in &self.colors.theme_background {
+            if !self.is_theme(theme) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown theme '{theme}' in colors.theme_background, valid themes: {}",
+                    self.theme_names()
+                )));
+            }
+            background.validate(&format!("colors.theme_background.{theme}"))?;
+        }
+        let schedule = &self.colors.schedule;
+        for (key, theme) in [("day", &schedule.day), ("night", &schedule.night)] {
+            if !self.is_theme(theme) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown theme '{theme}' in colors.schedule.{key}, valid themes: {}",
+                    self.theme_names()
+                )));
+            }
+        }
+        let mut starts = [0; 2];
+        for (start, (key, time)) in starts
+            .iter_mut()
+            .zip([("day_start", &schedule.day_start), ("night_start", &schedule.night_start)])
+        {
+            *start = crate::theme_schedule::parse_clock_time(time).ok_or_else(|| {
+                ConfigError::Validation(format!(
+                    "colors.schedule.{key} must be a \"HH:MM\" time, got '{time}'"
+                ))
+            })?;
+        }
+        let (day, night) = crate::theme_schedule::period_lengths(starts[0], starts[1]);
+        if day == 0 {
+            return Err(ConfigError::Validation(
+                "colors.schedule.day_start and night_start must differ".to_string(),
+            ));
+        }
+        if schedule.transition_minutes > day.min(night) {
+            return Err(ConfigError::Validation(format!(
+                "colors.schedule.transition_minutes must fit in the shorter of day and night ({} minutes), got {}",
+                day.min(night),
+                schedule.transition_minutes
+            )));
+        }
+
+        if self.cursor.blink_interval != 0
+            && (self.cursor.blink_interval < 100 || self.cursor.blink_interval > 2000)
+        {
+            return Err(ConfigError::Validation(
+                "cursor blink_interval must be 0 (disabled) or between 100 and 2000 ms".to_string(),
+            ));
+        }
+
+        if !(10..=1000).contains(&self.cursor.animation_duration) {
+            return Err(ConfigError::Validation(
+                "cursor animation_duration must be between 10 and 1000 ms".to_string(),
+            ));
+        }
+
+        if !VALID_CURSOR_STYLES.contains(&self.cursor.style.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown cursor style '{}', valid styles: {}",
+                self.cursor.style,
+                VALID_CURSOR_STYLES.join(", ")
+            )));
+        }
+
+        if !VALID_COPY_MODES.contains(&self.selection.copy_mode.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown selection copy_mode '{}', valid modes: {}",
+                self.selection.copy_mode,
+                VALID_COPY_MODES.join(", ")
+            )));
+        }
+
+        if !VALID_OPTION_AS_ALT.contains(&self.macos.option_as_alt.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown macos option_as_alt '{}', valid values: {}",
+                self.macos.option_as_alt,
+                VALID_OPTION_AS_ALT.join(", ")
+            )));
+        }
+
+        if self.scrollback.lines == 0 {
+            return Err(ConfigError::Validation(
+                "scrollback lines must be > 0".to_string(),
+            ));
+        }
 
-    /// Validate the config, returning an error if any values are out of range.
-    pub fn validate(&self) -> Result<(), ConfigError> {
-        if self.font.size < 8.0 {
+        if !VALID_SCROLLBAR_MODES.contains(&self.scrollback.scrollbar.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown scrollback scrollbar '{}', valid values: {}",
+                self.scrollback.scrollbar,
+                VALID_SCROLLBAR_MODES.join(", ")
+            )));
+        }
+
+        if !VALID_COMPAT_PRESETS.contains(&self.shell.compat.as_str()) {
+            return Err(ConfigError::Validation(format!(
+                "unknown shell compat '{}', valid values: {}",
+                self.shell.compat,
+                VALID_COMPAT_PRESETS.join(", ")
+            )));
+        }
+
+        if self.shell.term.as_deref().is_some_and(|term| !is_term_name(term)) {
             return Err(ConfigError::Validation(
-                "font size must be >= 8".to_string(),
+                "shell term must be a non-empty name without spaces".to_string(),
             ));
         }
 
-        if self.font.line_height < 0.5 || self.font.line_height > 3.0 {
+        for (key, value) in [
+            ("osc52_write", &self.clipboard.osc52_write),
+            ("osc52_read", &self.clipboard.osc52_read),
+        ] {
+            if !VALID_CLIPBOARD_POLICIES.contains(&value.as_str()) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown clipboard {key} '{}', valid values: {}",
+                    value,
+                    VALID_CLIPBOARD_POLICIES.join(", ")
+                )));
+            }
+        }
+
+        if !(1..=4096).contains(&self.clipboard.slow_paste_chunk_size) {
             return Err(ConfigError::Validation(
-                "font line_height must be between 0.5 and 3.0".to_string(),
+                "clipboard slow_paste_chunk_size must be between 1 and 4096".to_string(),
             ));
         }
 
-        if self.padding.top < 0.0
-            || self.padding.bottom < 0.0
-            || self.padding.left < 0.0
-            || self.padding.right < 0.0
-        {
+        if !(1..=10_000).contains(&self.clipboard.slow_paste_delay_ms) {
             return Err(ConfigError::Validation(
-                "padding values must be >= 0".to_string(),
+                "clipboard slow_paste_delay_ms must be between 1 and 10000".to_string(),
             ));
         }
 
-        if !VALID_THEMES.contains(&self.colors.theme.as_str()) {
+        if !(0.01..=0.5).contains(&self.panes.resize_step) {
+            return Err(ConfigError::Validation(
+                "panes resize_step must be between 0.01 and 0.5".to_string(),
+            ));
+        }
+
+        if self.panes.min_columns > 1000 {
+            return Err(ConfigError::Validation(
+                "panes min_columns must be at most 1000".to_string(),
+            ));
+        }
+
+        if self.panes.fixed_columns != 0 && !(10..=1000).contains(&self.panes.fixed_columns) {
+            return Err(ConfigError::Validation(
+                "panes fixed_columns must be 0 or between 10 and 1000".to_string(),
+            ));
+        }
+
+        if !(20..=400).contains(&self.minimap.width) {
+            return Err(ConfigError::Validation(
+                "minimap width must be between 20 and 400".to_string(),
+            ));
+        }
+
+        if !(1..=8).contains(&self.minimap.line_height) {
+            return Err(ConfigError::Validation(
+                "minimap line_height must be between 1 and 8".to_string(),
+            ));
+        }
+
+        if self.performance.fps_limit == 0 {
+            return Err(ConfigError::Validation("fps_limit must be > 0".to_string()));
+        }
+        if self.performance.flood_threshold_mb > 4096 {
             return Err(ConfigError::Validation(format!(
-                "unknown theme '{}', valid themes: {}",
-                self.colors.theme,
-                VALID_THEMES.join(", ")
+                "performance.flood_threshold_mb must be at most 4096, got {}",
+                self.performance.flood_threshold_mb
             )));
         }
 
-        if self.cursor.blink_rate != 0
-            && (self.cursor.blink_rate < 100 || self.cursor.blink_rate > 2000)
-        {
+        if self.renderer.gpu.trim().is_empty() {
             return Err(ConfigError::Validation(
-                "cursor blink_rate must be 0 (disabled) or between 100 and 2000 ms".to_string(),
+                "renderer.gpu must be \"auto\", \"low-power\", \"high-performance\" or an adapter name".to_string(),
             ));
         }
 
-        if !VALID_CURSOR_STYLES.contains(&self.cursor.style.as_str()) {
+        if self.window.class.is_empty() || self.window.class.contains(char::is_whitespace) {
             return Err(ConfigError::Validation(format!(
-                "unknown cursor style '{}', valid styles: {}",
-                self.cursor.style,
-                VALID_CURSOR_STYLES.join(", ")
+                "window.class must be a non-empty name without spaces, got {:?}",
+                self.window.class
             )));
         }
 
-        if self.scrollback.lines == 0 {
+        if !(0.0..=1.0).contains(&self.window.opacity) {
+            return Err(ConfigError::Validation(format!(
+                "window.opacity must be between 0.0 and 1.0, got {}",
+                self.window.opacity
+            )));
+        }
+
+        if !matches!(self.renderer.present_mode.as_str(), "fifo" | "mailbox" | "immediate") {
+            return Err(ConfigError::Validation(format!(
+                "renderer.present_mode must be \"fifo\", \"mailbox\" or \"immediate\", got \"{}\"",
+                self.renderer.present_mode
+            )));
+        }
+
+        if self.git_status.refresh_interval_secs == 0 {
             return Err(ConfigError::Validation(
-                "scrollback lines must be > 0".to_string(),
+                "git_status.refresh_interval_secs must be at least 1".to_string(),
             ));
         }
 
-        if self.performance.fps_limit == 0 {
-            return Err(ConfigError::Validation("fps_limit must be > 0".to_string()));
+        for rule in &self.directory_rules {
+            if rule.path.is_empty() {
+                return Err(ConfigError::Validation(
+                    "directory_rules entries need a path".to_string(),
+                ));
+            }
+            if rule.badge.is_empty() && rule.theme.is_none() && rule.background.is_none() {
+                return Err(ConfigError::Validation(format!(
+                    "directory rule '{}' needs a badge, theme or background",
+                    rule.path
+                )));
+            }
+            if let Some(theme) = rule.theme.as_ref().filter(|t| !self.is_theme(t)) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown theme '{theme}' in directory rule '{}', valid themes: {}",
+                    rule.path,
+                    self.theme_names()
+                )));
+            }
+            if let Some(background) = rule.background.as_ref().filter(|b| !is_hex_color(b)) {
+                return Err(ConfigError::Validation(format!(
+                    "directory rule '{}' background '{background}' must be #RRGGBB",
+                    rule.path
+                )));
+            }
+            if !is_hex_color(&rule.color) {
+                return Err(ConfigError::Validation(format!(
+                    "directory rule color '{}' must be #RRGGBB",
+                    rule.color
+                )));
+            }
+        }
+
+        for (i, profile) in self.profiles.iter().enumerate() {
+            if profile.name.trim().is_empty() {
+                return Err(ConfigError::Validation(
+                    "profiles entries need a name".to_string(),
+                ));
+            }
+            if self.profiles[..i].iter().any(|p| p.name == profile.name) {
+                return Err(ConfigError::Validation(format!(
+                    "duplicate profile name '{}'",
+                    profile.name
+                )));
+            }
+            if profile.command.as_ref().is_some_and(|c| c.trim().is_empty()) {
+                return Err(ConfigError::Validation(format!(
+                    "profile '{}' has an empty command",
+                    profile.name
+                )));
+            }
+            if let Some(theme) = &profile.theme {
+                if !self.is_theme(theme) {
+                    return Err(ConfigError::Validation(format!(
+                        "unknown theme '{theme}' in profile '{}', valid themes: {}",
+                        profile.name,
+                        self.theme_names()
+                    )));
+                }
+            }
+            if let Some(background) = profile.background.as_ref().filter(|b| !is_hex_color(b)) {
+                return Err(ConfigError::Validation(format!(
+                    "profile '{}' background '{background}' must be #RRGGBB",
+                    profile.name
+                )));
+            }
+            if let Some(filter) = profile.color_filter.as_ref().filter(|f| !VALID_COLOR_FILTERS.contains(&f.as_str())) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown color filter '{filter}' in profile '{}', valid filters: {}",
+                    profile.name,
+                    VALID_COLOR_FILTERS.join(", ")
+                )));
+            }
+            if let Some(compat) = profile.compat.as_ref().filter(|c| !VALID_COMPAT_PRESETS.contains(&c.as_str())) {
+                return Err(ConfigError::Validation(format!(
+                    "unknown compat '{compat}' in profile '{}', valid values: {}",
+                    profile.name,
+                    VALID_COMPAT_PRESETS.join(", ")
+                )));
+            }
+            if profile.term.as_deref().is_some_and(|term| !is_term_name(term)) {
+                return Err(ConfigError::Validation(format!(
+                    "profile '{}' term must be a non-empty name without spaces",
+                    profile.name
+                )));
+            }
+        }
+
+        if self.guides.rulers.contains(&0) {
+            return Err(ConfigError::Validation(
+                "guides rulers must be column numbers > 0".to_string(),
+            ));
+        }
+        if self.guides.soft_wrap_column < 10 {
+            return Err(ConfigError::Validation(format!(
+                "guides soft_wrap_column must be at least 10, got {}",
+                self.guides.soft_wrap_column
+            )));
+        }
+
+        for pattern in &self.redaction.patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(ConfigError::Validation(format!(
+                    "redaction pattern '{pattern}' is not a valid regex: {e}"
+                )));
+            }
+        }
+
+        for pattern in &self.quick_select.patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(ConfigError::Validation(format!(
+                    "quick_select pattern '{pattern}' is not a valid regex: {e}"
+                )));
+            }
+        }
+
+        if self.daemon.replay_bytes > 64 * 1024 * 1024 {
+            return Err(ConfigError::Validation(
+                "daemon replay_bytes must be at most 64 MiB".to_string(),
+            ));
+        }
+
+        let filename = &self.screenshot.filename;
+        if filename.trim().is_empty() || filename.contains('/') || filename.contains('\\') {
+            return Err(ConfigError::Validation(
+                "screenshot filename must be a non-empty file name without path separators".to_string(),
+            ));
+        }
+
+        let filename = &self.recording.filename;
+        if filename.trim().is_empty() || filename.contains('/') || filename.contains('\\') {
+            return Err(ConfigError::Validation(
+                "recording filename must be a non-empty file name without path separators".to_string(),
+            ));
+        }
+        if !(1..=3600).contains(&self.recording.max_duration) {
+            return Err(ConfigError::Validation(format!(
+                "recording.max_duration must be between 1 and 3600, got {}",
+                self.recording.max_duration
+            )));
+        }
+        if !(1..=50).contains(&self.recording.max_fps) {
+            return Err(ConfigError::Validation(format!(
+                "recording.max_fps must be between 1 and 50, got {}",
+                self.recording.max_fps
+            )));
+        }
+
+        if !self.search.error_pattern.is_empty() {
+            if let Err(e) = regex::Regex::new(&self.search.error_pattern) {
+                return Err(ConfigError::Validation(format!(
+                    "search error_pattern is not a valid regex: {e}"
+                )));
+            }
+        }
+
+        if self.search.debounce_ms > 1000 {
+            return Err(ConfigError::Validation(format!(
+                "search.debounce_ms must be at most 1000, got {}",
+                self.search.debounce_ms
+            )));
         }
 
         Ok(())
     }
 
+    /// Whether `name` is a built-in or user theme.
+    fn is_theme(&self, name: &str) -> bool {
+        VALID_THEMES.contains(&name) || self.themes.contains_key(name)
+    }
+
+    /// Every valid theme name, for error messages.
+    fn theme_names(&self) -> String {
+        let mut user: Vec<&str> = self.themes.keys().map(String::as_str).collect();
+        user.sort_unstable();
+        VALID_THEMES.iter().copied().chain(user).collect::<Vec<_>>().join(", ")
+    }
+
+    /// The user themes by name, each its built-in with its colors replaced.
+    pub fn user_themes(&self) -> HashMap<String, TerminalTheme> {
+        self.themes
+            .iter()
+            .filter_map(|(name, config)| {
+                let base = TerminalTheme::from_name(&config.inherit)?;
+                Some((name.clone(), base.with_color_overrides(&config.colors)))
+            })
+            .collect()
+    }
+
     /// Compare two configs and return a delta indicating which sections changed.
     pub fn diff(&self, other: &Config) -> ConfigDelta {
         ConfigDelta {
             font_changed: self.font != other.font,
             padding_changed: self.padding != other.padding,
-            colors_changed: self.colors != other.colors,
+            colors_changed: self.colors != other.colors || self.themes != other.themes,
             keys_changed: self.keys != other.keys,
             cursor_changed: self.cursor != other.cursor,
             scrollback_changed: self.scrollback != other.scrollback,
@@ -635,13 +2587,53 @@ impl Config {
             quick_terminal_changed: self.quick_terminal != other.quick_terminal,
             session_changed: self.session != other.session,
             sidebar_changed: self.sidebar != other.sidebar,
+            suggestions_changed: self.suggestions != other.suggestions,
+            accessibility_changed: self.accessibility != other.accessibility,
+            guides_changed: self.guides != other.guides,
+            command_hints_changed: self.command_hints != other.command_hints,
+            directory_rules_changed: self.directory_rules != other.directory_rules,
+            profiles_changed: self.profiles != other.profiles,
+            git_status_changed: self.git_status != other.git_status,
+            renderer_changed: self.renderer != other.renderer,
+            search_changed: self.search != other.search,
+            scripting_changed: self.scripting != other.scripting,
+            quick_select_changed: self.quick_select != other.quick_select,
+            security_changed: self.security != other.security,
+            recording_changed: self.recording != other.recording,
+            screenshot_changed: self.screenshot != other.screenshot,
+            daemon_changed: self.daemon != other.daemon,
+            redaction_changed: self.redaction != other.redaction,
+            panes_changed: self.panes != other.panes,
+            clipboard_changed: self.clipboard != other.clipboard,
+            minimap_changed: self.minimap != other.minimap,
+            macos_changed: self.macos != other.macos,
+            window_changed: self.window != other.window,
+            prompt_indicator_changed: self.prompt_indicator != other.prompt_indicator,
+            selection_changed: self.selection != other.selection,
         }
     }
 
+    /// Every setting as TOML (defaults merged with the user's file), with
+    /// keys sorted. Parses back to an identical config.
+    pub fn to_toml_string(&self) -> Result<String, ConfigError> {
+        toml::to_string(&self.to_toml_table()?).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    /// Only the settings that differ from the defaults, as TOML.
+    pub fn overrides_toml(&self) -> Result<String, ConfigError> {
+        let overrides = table_diff(&self.to_toml_table()?, &Self::default().to_toml_table()?);
+        toml::to_string(&overrides).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    pub(crate) fn to_toml_table(&self) -> Result<toml::Table, ConfigError> {
+        toml::Table::try_from(self).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
     /// Generate a fully commented default config as a TOML string.
     pub fn print_default() -> String {
-        r#"# VeloTerm Configuration
-# Place this file at ~/.config/veloterm/veloterm.toml
+        r##"# VeloTerm Configuration
+# Place this file at ~/.config/veloterm/config.toml, or save variants as
+# ~/.config/veloterm/profiles/<name>.toml and launch with --profile <name>
 
 [font]
 # Terminal content font family. JetBrains Mono (default) includes Nerd Font glyphs for p10k.
@@ -655,6 +2647,18 @@ line_height = 1.2
 ui_family = "Inter"
 # Display/header font (welcome screen, about)
 display_family = "Georgia"
+# Draw ligatures such as => != -> as single glyphs when the font has them
+ligatures = false
+# Subpixel (LCD) antialiasing for low-DPI monitors: "none" (grayscale),
+# "rgb" or "bgr" (the monitor's subpixel order). Sharper text, with color
+# fringes if the order is wrong
+subpixel_antialiasing = "none"
+# Scale factor to rasterize the font at instead of the one detected for the
+# display, for when detection guesses wrong (e.g. external monitors on macOS).
+# 0.0 = detected; 1.0 makes size physical pixels; 2.0 suits a Retina panel.
+# Override per window with a --profile, or at runtime with
+# `veloterm ctl set-font-scale`
+scale_factor = 0.0
 
 [padding]
 # Terminal content padding in pixels
@@ -666,22 +2670,110 @@ right = 10.0
 [colors]
 # Theme: "warm_dark", "midnight", "ember", "dusk", "neon_forge", or "light"
 theme = "midnight"
+# Accessibility color filter: "none", "deuteranopia", "protanopia", "tritanopia", or "grayscale"
+filter = "none"
+
+# ANSI palette overrides for every theme. Entries: black, red, green, yellow,
+# blue, magenta, cyan, white, and bright_black ... bright_white.
+# [colors.ansi]
+# red = "#e06c75"
+
+# Palette overrides for one theme only (applied after [colors.ansi])
+# [colors.theme_ansi.light]
+# bright_yellow = "#b58900"
+
+# Picture beneath the terminal text: an image, or a gradient without one.
+# Cells with the default background let it show through.
+[colors.background]
+# Image file (PNG, JPEG, ...); "~" is your home directory. "" for none.
+image = ""
+# Top-to-bottom gradient colors, used when there is no image
+# gradient = ["#1a1b26", "#24283b"]
+# How the image fills the window: "cover" (scaled and cropped), "tile"
+# (repeated at its own size) or "center" (its own size, centered)
+fit = "cover"
+# How far the picture fades toward the theme background (0.0-1.0), so
+# text over it stays readable
+dim = 0.5
+
+# A picture for one theme only, replacing [colors.background]
+# [colors.theme_background.light]
+# gradient = ["#fdf6e3", "#eee8d5"]
+# dim = 0.2
+
+# Switch between a day and a night theme by the clock, replacing
+# colors.theme while enabled
+[colors.schedule]
+enabled = false
+# Theme from day_start to night_start, and from night_start to day_start
+day = "light"
+night = "warm_dark"
+# Local times, "HH:MM"
+day_start = "07:00"
+night_start = "19:00"
+# Minutes over which the colors fade to the next theme, from each switch
+# time (0 switches at once)
+transition_minutes = 30
+
+# Your own themes: a built-in with some colors replaced, usable as
+# colors.theme, in profiles and in the colors.theme_* tables. Keys are the
+# theme colors (bg_deep, bg_surface, bg_raised, bg_hover, bg_active,
+# bg_input, text_primary, text_secondary, text_muted, text_ghost,
+# accent_orange, accent_blue, accent_green, accent_purple, accent_red,
+# accent_yellow, border_subtle, border_visible, border_strong, selection,
+# search_match, search_match_active) and the palette entries as in
+# [colors.ansi], each "#RRGGBB".
+# [themes.my_theme]
+# Built-in theme to start from (required)
+# inherit = "warm_dark"
+# bg_deep = "#101014"
+# accent_orange = "#ff9e64"
 
 [cursor]
-# Cursor style: "block", "beam", or "underline"
+# Cursor style: "block", "beam" (or "bar"), or "underline". Programs such as
+# vim may ask for another shape (e.g. a bar in insert mode) while they run.
 style = "block"
 # Enable cursor blinking
 blink = true
-# Blink rate in milliseconds (0 = disable, 100-2000)
-blink_rate = 500
+# Milliseconds between blinks (0 = disable, 100-2000)
+blink_interval = 500
+# Glide smoothly to each new cell and ease between shapes
+animation = false
+# Milliseconds a glide takes (10-1000)
+animation_duration = 100
 
 [scrollback]
 # Number of lines to keep in scrollback history
 lines = 10000
+# Scrollbar on the right edge of each pane: "auto" shows it while scrolling,
+# "always" keeps a track and thumb visible whenever there is scrollback,
+# "never" hides it. Click the track to jump, drag the thumb to scroll.
+scrollbar = "auto"
 
 [performance]
 # Maximum frames per second
 fps_limit = 60
+# Resizing a pane with at least this many scrollback lines reflows it in the
+# background, showing "Reflowing…" meanwhile (0 = always reflow inline)
+background_reflow_lines = 50000
+# A pane receiving more than this many MiB of output per second (catting a
+# binary, a runaway loop) parses a capped amount per frame so the window
+# stays responsive, and shows a banner: Ctrl+S pauses output, Ctrl+C
+# interrupts the program (0 = off)
+flood_threshold_mb = 16
+
+[links]
+# Click URLs and file paths in the terminal to open them
+enabled = true
+# Links come from program output, so only these schemes open straight away;
+# others (file://, app handlers like vscode://) ask first
+allowed_schemes = ["http", "https", "mailto"]
+# Never opened
+blocked_schemes = ["javascript", "vbscript", "data"]
+# When set, links to other hosts ask first; "*.example.com" covers subdomains
+allowed_hosts = []
+# Never opened
+blocked_hosts = []
 
 [shell]
 # Enable shell integration features (prompt detection, CWD tracking, command timing)
@@ -696,12 +2788,22 @@ bell_enabled = true
 # args = ["--login"]
 # Suppress starship prompt if it conflicts with your prompt theme (e.g. p10k)
 disable_starship = false
+# TERM for new shells (default: xterm-256color, xterm with compat = "legacy")
+# term = "xterm-256color"
+# Compatibility preset: "standard", or "legacy" for old systems that misbehave
+# with a modern terminal. Legacy panes get TERM=xterm and no COLORTERM, and
+# their output loses queries, kitty keyboard and graphics sequences, clipboard
+# writes, hyperlinks and other OSC features beyond titles, colors and the
+# working directory. toggle_legacy_compat switches the focused pane.
+compat = "standard"
 
 [vi_mode]
 # Enable vi-mode for keyboard-driven scrollback navigation
 enabled = true
 # Keybinding to toggle vi-mode
 entry_key = "ctrl+shift+space"
+# Move j/k/0/$ by logical (unwrapped) lines; gj/gk/g0/g$ always use screen rows
+logical_lines = false
 
 [quick_terminal]
 # Enable global hotkey to toggle window visibility
@@ -710,7 +2812,9 @@ enabled = false
 hotkey = "Control+`"
 
 [session]
-# Automatically restore the previous session on startup
+# Automatically restore the previous session (tabs, splits, working
+# directories, window size) on startup. `veloterm --restore-session` does
+# the same for one launch. The session is saved when the window closes.
 auto_restore = false
 
 [sidebar]
@@ -719,14 +2823,329 @@ default_visible = true
 # Sidebar width in logical pixels
 width = 200.0
 
+[suggestions]
+# Show fuzzy matches from command history above the prompt while typing.
+# Tab or Right arrow accepts the top suggestion.
+enabled = false
+
+[accessibility]
+# Program that receives "Read Screen as Text" output on stdin, e.g. a TTS tool.
+# When unset, the text is copied to the clipboard instead.
+# line_view_command = "espeak"
+
+[guides]
+# Columns after which a vertical ruler is drawn
+rulers = [80, 100, 120]
+# Show rulers / cursor crosshair by default (toggle per pane from the command palette)
+show_rulers = false
+crosshair = false
+# Mark soft-wrapped lines with a dot in the right gutter
+wrap_indicator = false
+# Show long lines wrapped at soft_wrap_column (display only: programs still
+# see the full width). Toggle per pane with toggle_soft_wrap.
+soft_wrap = false
+soft_wrap_column = 100
+
+[command_hints]
+# After "command not found" (exit 127), suggest the closest executable on PATH.
+# Alt+Enter (or the `accept_command_hint` binding) types the corrected command.
+enabled = false
+
+# Per-directory rules: panes whose cwd (from OSC 7) is under `path` show a
+# colored badge that is also prefixed to the tab title, and take `theme` and
+# `background` while they stay there. Each rule needs at least one of badge,
+# theme or background. Longest path wins.
+# [[directory_rules]]
+# path = "~/work/prod-infra"
+# badge = "PROD"
+# color = "#E5484D"
+# theme = "midnight"
+# background = "#2A1215"
+
+# Launch profiles, offered by the profile picker (`new_tab_with_profile`,
+# `split_vertical_with_profile`, `split_horizontal_with_profile`). `command`
+# and `args` replace the shell and its arguments, `env` adds to shell.env.
+# `theme` and `background` color only the profile's panes, and `trusted`
+# overrides security.trust_panes for them. `term`, `compat` and
+# `color_filter` override shell.term, shell.compat and colors.filter. Fonts
+# are shared by the whole window, so profiles cannot change them.
+# [[profiles]]
+# name = "Prod SSH"
+# command = "ssh"
+# args = ["prod-bastion"]
+# env = { AWS_PROFILE = "prod" }
+# cwd = "~/work/prod-infra"
+# theme = "ember"
+# background = "#2A1414"
+# trusted = false
+# compat = "legacy"
+# color_filter = "deuteranopia"
+
+[git_status]
+# Show the git branch (with `*` when dirty) for the focused pane's cwd in the
+# status bar. Read in the background; refreshed on cd and after each command.
+enabled = false
+refresh_interval_secs = 5
+
+[renderer]
+# GPU to render on: "auto", "low-power" (integrated, saves battery),
+# "high-performance" (discrete), or part of an adapter name as printed by
+# `veloterm cli list-gpus`. Takes effect on restart.
+gpu = "auto"
+# Present mode: "fifo" (vsync, no tearing), "mailbox" (vsync, newest frame
+# wins), or "immediate" (may tear). Unsupported modes fall back to "fifo".
+present_mode = "fifo"
+# Draw a frame as soon as a key is typed, waiting briefly for the shell's
+# echo, instead of on the next scheduled frame. Pair with "mailbox" or
+# "immediate"; with "fifo" the frame still waits for vsync.
+low_latency = false
+
+[search]
+# Where a match lands when jumping to it: centered in the pane, or (with
+# center_match = false) at least context_lines from the top/bottom edge.
+center_match = true
+context_lines = 3
+# Thin strip on the pane's right edge marking where matches sit in
+# scrollback; click a mark to jump. Lines matching error_pattern (a regex,
+# e.g. "error|failed") are marked too while searching.
+match_strip = true
+error_pattern = ""
+# The search bar searches as you type, once typing pauses this many
+# milliseconds (0 = on every keystroke, at most 1000)
+debounce_ms = 60
+
+[selection]
+# How a copy turns the selection into text: "logical" joins soft-wrapped
+# rows and trims trailing padding; "grid" copies each screen row exactly,
+# alignment spaces included (useful for ASCII tables). The copy_logical and
+# copy_grid actions pick one mode regardless of this setting.
+copy_mode = "logical"
+
+[prompt_indicator]
+# While the command line at a shell-integrated prompt wraps over several
+# rows, show the cursor's row/column within it (e.g. "Ln 2/3, Col 14").
+enabled = true
+# Also count the characters typed since the prompt appeared.
+char_count = false
+
+[window]
+# Wayland app-id and X11 WM_CLASS, so window manager rules and the
+# desktop entry (veloterm.desktop) can match this window. Linux only;
+# `--class <name>` overrides it for one launch.
+class = "veloterm"
+# Launching `veloterm` while a window is open asks that window for a new
+# tab (in the launch directory, with any --cwd and --title) and exits,
+# saving a second process. Launches with -e always get their own window,
+# so callers like git mergetool can wait for the command, and so do
+# launches with --grid, --config, --profile or --class, which a running
+# window couldn't honour.
+single_instance = false
+# Window title. {title} is the active tab's name if you renamed it, else
+# its focused pane's title: set by the program (OSC 0/2), else the running
+# program, else the directory. Empty keeps "VeloTerm"; --title overrides it.
+title = "{title} - VeloTerm"
+# Background opacity, 0.0 (fully see-through) to 1.0 (opaque). Cell
+# backgrounds let the desktop show through; text and the cursor stay
+# opaque. Needs a compositing window manager; on X11 an opaque window
+# only turns translucent after a restart.
+opacity = 1.0
+# Blur what shows through a translucent background. Supported on KDE
+# Plasma (X11 and Wayland) and macOS; other compositors ignore it.
+blur = false
+
+[macos]
+# Which Option keys act as Alt/Meta and send ESC-prefixed sequences
+# ("left", "right", "both") instead of composing special characters
+# such as ƒ or ©. "none" keeps the macOS behaviour for both keys.
+option_as_alt = "none"
+
+[minimap]
+# Code-editor style overview of the whole scrollback on the right of each
+# pane: one colored row per line, with the visible part highlighted. Click
+# on it to jump there. Toggle with the toggle_minimap action.
+enabled = false
+# Column width in pixels (20-400)
+width = 80
+# Pixels per line (1-8); scrollback too long to fit is squeezed
+line_height = 2
+
+[clipboard]
+# OSC 52 lets programs (tmux, nvim, also over SSH) use the system clipboard.
+# "allow" lets them, "deny" ignores them, "ask" shows a prompt in the pane
+# (Enter allows, Esc denies). Reading exposes whatever you last copied.
+osc52_write = "allow"
+osc52_read = "ask"
+# Largest text a program may copy, in bytes (0 = no limit)
+osc52_max_bytes = 1048576
+# Ask before pasting text that spans lines: Enter pastes, S types it
+# slowly, Esc cancels
+confirm_multiline_paste = false
+# Slow paste (paste_slowly, or S in the paste prompt) types the clipboard
+# this many characters at a time, pausing between them, for serial consoles
+# and REPLs that drop fast pastes. Line breaks are sent as Enter.
+slow_paste_chunk_size = 1
+slow_paste_delay_ms = 10
+
+[panes]
+# How far resize_pane_left/right/up/down (ctrl+shift+alt+arrows) move the
+# divider beside the focused pane, as a share of that split (0.01 to 0.5)
+resize_step = 0.05
+# Panes narrower than this many columns keep this width and scroll sideways
+# (shift+wheel, h/l in vi mode) instead of rewrapping their output (0 = off)
+min_columns = 0
+# Keep every terminal exactly this many columns wide whatever the pane's
+# size, e.g. 200 for remote tmux sessions or wide tables: resizing never
+# reflows, narrower panes scroll sideways. Overrides min_columns (0 = off)
+fixed_columns = 0
+# Open the first window as a grid of panes, each running one command through
+# the shell, e.g. ["htop", "journalctl -f", "ping 1.1.1.1"]. Skipped when a
+# session is restored or -e/--grid is given; the CLI form is
+# --grid "htop|journalctl -f|ping 1.1.1.1"
+startup_grid = []
+
+[redaction]
+# Replace likely secrets with [REDACTED] when text leaves the terminal:
+# text after "password:", AWS access keys, bearer tokens and `patterns`.
+# "Copy Redacted" / "Copy Unredacted" in the palette override it per copy.
+# Copied selections, vi yanks and Copy Last Output
+copy = false
+# The line view export
+export = false
+# Output and typed input written by --record-events (checked per chunk);
+# keys typed while a program has echo off (password prompts) are masked whole
+event_traces = false
+# Built-in patterns; set false to use only your own
+builtin = true
+# Extra regexes. A named group `secret` masks only that part:
+# patterns = ['token=(?P<secret>\w+)', 'ghp_[A-Za-z0-9]{36}']
+patterns = []
+
+[daemon]
+# `veloterm --daemon` owns shells for windows opened with `veloterm attach`;
+# closing such a window leaves its shells running for the next attach.
+# The daemon keeps each pane's screen, modes and scrollback and redraws them
+# in a window that attaches. Scrollback sent on attach (bytes, up to 64 MiB)
+replay_bytes = 2097152
+
+[screenshot]
+# Capture the window, the focused pane or the selection as PNG from the
+# palette ("Screenshot ...") or primary+shift+s (window to file).
+# Folder for saved screenshots; empty means ~/Pictures, or home without one
+directory = ""
+# File name: {date} (2024-05-01), {time} (14-03-59) and {scope}
+# (window, pane or selection) are filled in
+filename = "veloterm-{date}-{time}-{scope}.png"
+# Briefly flash the captured area
+flash = true
+
+[recording]
+# Record the focused pane as an animated GIF with the toggle_pane_recording
+# action; run it again to stop. Frames are only taken when the pane changed.
+# Folder for recordings; empty means ~/Pictures, or home without one
+directory = ""
+# File name: {date} and {time} are filled in
+filename = "veloterm-{date}-{time}.gif"
+# Seconds after which a recording stops by itself (1-3600)
+max_duration = 60
+# Most frames per second (1-50)
+max_fps = 15
+
+[scripting]
+# A Rhai script run whenever this file loads. It can compute settings with
+# set("font.size", 15.0), bind keys with bind("ctrl+alt+g", || send_text("git status\n"))
+# and hook events with on("command_finished", |e| ...), on("bell", ...) and
+# on("tab_switched", ...). Callbacks can use panes(), active_tab(), send_text,
+# split_pane, new_tab, activate_tab, activate_pane, close_pane, clipboard(),
+# set_clipboard, env(name) and os(); scripts cannot touch files or run programs.
+enabled = true
+# Script file; empty means init.rhai beside this file
+script = ""
+
+[quick_select]
+# Quick select (ctrl+shift+x) labels every match of these regexes on screen;
+# type a label to copy it. Earlier patterns win where matches overlap, and a
+# capture group copies only its first group, e.g. 'image: (\S+)'.
+# Defaults: UUIDs, IPv4 addresses, hex hashes and container IDs.
+# patterns = ['\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b', '\b[0-9a-f]{7,64}\b']
+
+[security]
+# Strip escape sequences and control characters (other than tab and
+# newlines) from pasted text, so a paste can't end bracketed paste early or
+# carry hidden keystrokes.
+sanitize_paste = true
+# Whether new panes start trusted (toggle_pane_trust switches the focused
+# one). An untrusted pane's output loses title changes, OSC 52 clipboard
+# writes and device queries, e.g. while catting files from elsewhere.
+trust_panes = true
+
 # [keys]
-# Keybindings as "key_combo" = "action" pairs
+# Rebind shortcuts as "key_combo" = "action" pairs, or move an action with
+# "action" = "key_combo". Use "none" on either side to unbind.
 # Example:
-# "ctrl+shift+c" = "copy"
-# "ctrl+shift+v" = "paste"
-"#
+# "ctrl+shift+t" = "new_tab"
+# "toggle_file_browser" = "alt+e"
+# "ctrl+shift+k" = "none"
+"##
         .to_string()
     }
+
+    /// The config written on first launch: `print_default()` with every
+    /// built-in key binding listed (commented out) under `[keys]`.
+    pub fn first_run_default() -> String {
+        let mut out = Self::print_default();
+        out.push_str("#\n# Built-in bindings:\n");
+        for (combo, action) in crate::input::keybindings::default_bindings() {
+            out.push_str(&format!("# \"{combo}\" = \"{action}\"\n"));
+        }
+        out
+    }
+
+    /// Write `first_run_default()` to `path` unless a file is already there.
+    /// Returns true when the file was created, i.e. on first launch.
+    pub fn write_first_run(path: &Path) -> Result<bool, ConfigError> {
+        if path.exists() {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, Self::first_run_default())?;
+        Ok(true)
+    }
+}
+
+/// Entries of `current` that differ from `defaults`, recursing into tables
+/// so a section keeps only its changed keys.
+fn table_diff(current: &toml::Table, defaults: &toml::Table) -> toml::Table {
+    let mut diff = toml::Table::new();
+    for (key, value) in current {
+        match (value, defaults.get(key)) {
+            (_, Some(default)) if default == value => {}
+            (toml::Value::Table(table), Some(toml::Value::Table(default))) => {
+                let changed = table_diff(table, default);
+                if !changed.is_empty() {
+                    diff.insert(key.clone(), toml::Value::Table(changed));
+                }
+            }
+            _ => {
+                diff.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    diff
+}
+
+/// Lay `overrides` over `base`, merging tables key by key so a script's
+/// `set("font.size", ..)` keeps the file's other font settings.
+fn merge_toml(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(value)) => merge_toml(table, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 /// Indicates which config sections changed between two Config instances.
@@ -745,6 +3164,29 @@ pub struct ConfigDelta {
     pub quick_terminal_changed: bool,
     pub session_changed: bool,
     pub sidebar_changed: bool,
+    pub suggestions_changed: bool,
+    pub accessibility_changed: bool,
+    pub guides_changed: bool,
+    pub command_hints_changed: bool,
+    pub directory_rules_changed: bool,
+    pub profiles_changed: bool,
+    pub git_status_changed: bool,
+    pub renderer_changed: bool,
+    pub search_changed: bool,
+    pub selection_changed: bool,
+    pub prompt_indicator_changed: bool,
+    pub window_changed: bool,
+    pub macos_changed: bool,
+    pub minimap_changed: bool,
+    pub clipboard_changed: bool,
+    pub panes_changed: bool,
+    pub redaction_changed: bool,
+    pub daemon_changed: bool,
+    pub screenshot_changed: bool,
+    pub recording_changed: bool,
+    pub scripting_changed: bool,
+    pub quick_select_changed: bool,
+    pub security_changed: bool,
 }
 
 impl ConfigDelta {
@@ -763,6 +3205,29 @@ impl ConfigDelta {
             && !self.quick_terminal_changed
             && !self.session_changed
             && !self.sidebar_changed
+            && !self.suggestions_changed
+            && !self.accessibility_changed
+            && !self.guides_changed
+            && !self.command_hints_changed
+            && !self.directory_rules_changed
+            && !self.profiles_changed
+            && !self.git_status_changed
+            && !self.renderer_changed
+            && !self.search_changed
+            && !self.selection_changed
+            && !self.prompt_indicator_changed
+            && !self.window_changed
+            && !self.macos_changed
+            && !self.minimap_changed
+            && !self.clipboard_changed
+            && !self.panes_changed
+            && !self.redaction_changed
+            && !self.daemon_changed
+            && !self.screenshot_changed
+            && !self.recording_changed
+            && !self.scripting_changed
+            && !self.quick_select_changed
+            && !self.security_changed
     }
 }
 
@@ -930,6 +3395,17 @@ enabled = false
         assert!(!config.links.enabled);
     }
 
+    #[test]
+    fn links_scheme_and_host_lists() {
+        let config = Config::default();
+        assert_eq!(config.links.allowed_schemes, ["http", "https", "mailto"]);
+        assert!(config.links.blocked_schemes.contains(&"javascript".to_string()));
+        let config = Config::from_toml("[links]\nallowed_schemes = [\"https\"]\nblocked_hosts = [\"*.evil.test\"]\n").unwrap();
+        assert_eq!(config.links.allowed_schemes, ["https"]);
+        assert_eq!(config.links.blocked_hosts, ["*.evil.test"]);
+        assert!(config.links.enabled);
+    }
+
     #[test]
     fn parse_links_default_enabled() {
         let config = Config::from_toml("").unwrap();
@@ -1077,6 +3553,17 @@ disable_starship = false
         assert_eq!(config.vi_mode.entry_key, "ctrl+shift+space");
     }
 
+    #[test]
+    fn parse_vi_mode_logical_lines() {
+        assert!(!Config::default().vi_mode.logical_lines);
+        let toml = r#"
+[vi_mode]
+logical_lines = true
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert!(config.vi_mode.logical_lines);
+    }
+
     #[test]
     fn parse_vi_mode_config() {
         let toml = r#"
@@ -1155,6 +3642,36 @@ display_family = "Galaxie Copernicus"
         assert!(!delta.font_changed);
     }
 
+    #[test]
+    fn parse_font_ligatures() {
+        assert!(!Config::default().font.ligatures);
+        let config = Config::from_toml("[font]\nligatures = true\n").unwrap();
+        assert!(config.font.ligatures);
+        let delta = Config::default().diff(&config);
+        assert!(delta.font_changed);
+    }
+
+    #[test]
+    fn parse_font_subpixel_antialiasing() {
+        assert_eq!(Config::default().font.subpixel_antialiasing, "none");
+        let config = Config::from_toml("[font]\nsubpixel_antialiasing = \"bgr\"\n").unwrap();
+        assert_eq!(config.font.subpixel_antialiasing, "bgr");
+        assert!(Config::default().diff(&config).font_changed);
+        assert!(Config::from_toml("[font]\nsubpixel_antialiasing = \"vrgb\"\n").is_err());
+    }
+
+    #[test]
+    fn parse_font_scale_factor() {
+        assert_eq!(Config::default().font.scale_factor, 0.0);
+        let config = Config::from_toml("[font]\nscale_factor = 1.0\n").unwrap();
+        assert_eq!(config.font.scale_factor, 1.0);
+        assert!(Config::default().diff(&config).font_changed);
+        assert!(Config::from_toml("[font]\nscale_factor = 0.1\n").is_err());
+        assert!(Config::from_toml("[font]\nscale_factor = 12.0\n").is_err());
+        assert!(!is_valid_font_scale(0.0));
+        assert!(is_valid_font_scale(MAX_FONT_SCALE));
+    }
+
     #[test]
     fn diff_detects_line_height_change() {
         let a = Config::default();
@@ -1293,6 +3810,204 @@ lines = 0
         assert!(result.is_err());
     }
 
+    #[test]
+    fn clipboard_policy_defaults_and_validation() {
+        let config = Config::default();
+        assert_eq!(config.clipboard.osc52_write, "allow");
+        assert_eq!(config.clipboard.osc52_read, "ask");
+        assert_eq!(config.clipboard.osc52_max_bytes, 1_048_576);
+        let config = Config::from_toml("[clipboard]\nosc52_read = \"deny\"\n").unwrap();
+        assert_eq!(config.clipboard.osc52_read, "deny");
+        let result = Config::from_toml("[clipboard]\nosc52_write = \"sometimes\"\n");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn slow_paste_defaults_and_validation() {
+        let config = Config::default();
+        assert!(!config.clipboard.confirm_multiline_paste);
+        assert_eq!(config.clipboard.slow_paste_chunk_size, 1);
+        assert_eq!(config.clipboard.slow_paste_delay_ms, 10);
+        let config = Config::from_toml(
+            "[clipboard]\nconfirm_multiline_paste = true\nslow_paste_chunk_size = 16\nslow_paste_delay_ms = 50\n",
+        )
+        .unwrap();
+        assert!(config.clipboard.confirm_multiline_paste);
+        assert_eq!(config.clipboard.slow_paste_chunk_size, 16);
+        assert_eq!(config.clipboard.slow_paste_delay_ms, 50);
+        assert!(Config::from_toml("[clipboard]\nslow_paste_chunk_size = 0\n").is_err());
+        assert!(Config::from_toml("[clipboard]\nslow_paste_delay_ms = 0\n").is_err());
+    }
+
+    #[test]
+    fn shell_term_and_compat() {
+        let config = Config::default();
+        assert_eq!(config.shell.term, None);
+        assert_eq!(config.shell.compat, "standard");
+        let config = Config::from_toml(
+            "[shell]\nterm = \"vt220\"\ncompat = \"legacy\"\n\n[[profiles]]\nname = \"Old box\"\nterm = \"xterm\"\ncompat = \"standard\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.shell.term.as_deref(), Some("vt220"));
+        assert_eq!(config.shell.compat, "legacy");
+        assert_eq!(config.profiles[0].term.as_deref(), Some("xterm"));
+        assert_eq!(config.profiles[0].compat.as_deref(), Some("standard"));
+        assert!(Config::from_toml("[shell]\ncompat = \"modern\"\n").is_err());
+        assert!(Config::from_toml("[shell]\nterm = \"\"\n").is_err());
+    }
+
+    #[test]
+    fn panes_resize_step_default_and_validation() {
+        assert_eq!(Config::default().panes.resize_step, 0.05);
+        let config = Config::from_toml("[panes]\nresize_step = 0.1\n").unwrap();
+        assert_eq!(config.panes.resize_step, 0.1);
+        assert!(Config::from_toml("[panes]\nresize_step = 0\n").is_err());
+        assert!(Config::from_toml("[panes]\nresize_step = 0.9\n").is_err());
+    }
+
+    #[test]
+    fn panes_min_columns_default_and_validation() {
+        assert_eq!(Config::default().panes.min_columns, 0);
+        let config = Config::from_toml("[panes]\nmin_columns = 120\n").unwrap();
+        assert_eq!(config.panes.min_columns, 120);
+        assert!(Config::from_toml("[panes]\nmin_columns = 5000\n").is_err());
+    }
+
+    #[test]
+    fn panes_startup_grid_parses() {
+        assert!(Config::default().panes.startup_grid.is_empty());
+        let config = Config::from_toml("[panes]\nstartup_grid = [\"htop\", \"journalctl -f\"]\n").unwrap();
+        assert_eq!(config.panes.startup_grid, ["htop", "journalctl -f"]);
+    }
+
+    #[test]
+    fn panes_fixed_columns_override_pane_width() {
+        let panes = Config::default().panes;
+        assert_eq!(panes.terminal_columns(80), 80);
+        let config = Config::from_toml("[panes]\nmin_columns = 100\nfixed_columns = 200\n").unwrap();
+        assert_eq!(config.panes.terminal_columns(80), 200);
+        assert_eq!(config.panes.terminal_columns(300), 200);
+        let config = Config::from_toml("[panes]\nmin_columns = 100\n").unwrap();
+        assert_eq!(config.panes.terminal_columns(80), 100);
+        assert_eq!(config.panes.terminal_columns(120), 120);
+        assert!(Config::from_toml("[panes]\nfixed_columns = 5\n").is_err());
+        assert!(Config::from_toml("[panes]\nfixed_columns = 2000\n").is_err());
+    }
+
+    #[test]
+    fn quick_select_patterns_default_and_validate() {
+        let config = Config::default();
+        assert_eq!(config.quick_select.patterns.len(), QUICK_SELECT_PATTERNS.len());
+        let config = Config::from_toml("[quick_select]\npatterns = ['sha256:[0-9a-f]{64}']\n").unwrap();
+        assert_eq!(config.quick_select.patterns, vec!["sha256:[0-9a-f]{64}".to_string()]);
+        assert!(Config::from_toml("[quick_select]\npatterns = ['[a-']\n").is_err());
+    }
+
+    #[test]
+    fn security_config() {
+        let config = Config::default();
+        assert!(config.security.sanitize_paste);
+        assert!(config.security.trust_panes);
+        let config = Config::from_toml(
+            "[security]\nsanitize_paste = false\ntrust_panes = false\n\n[[profiles]]\nname = \"Local\"\ntrusted = true\n",
+        )
+        .unwrap();
+        assert!(!config.security.sanitize_paste);
+        assert!(!config.security.trust_panes);
+        assert_eq!(config.profiles[0].trusted, Some(true));
+        assert!(config.diff(&Config::default()).security_changed);
+    }
+
+    #[test]
+    fn redaction_defaults_and_validation() {
+        let config = Config::default();
+        assert!(!config.redaction.copy);
+        assert!(config.redaction.builtin);
+        assert!(config.redaction.patterns.is_empty());
+        let config = Config::from_toml("[redaction]\ncopy = true\npatterns = ['tok_\\w+']\n").unwrap();
+        assert!(config.redaction.copy);
+        assert_eq!(config.redaction.patterns, vec!["tok_\\w+".to_string()]);
+        assert!(Config::from_toml("[redaction]\npatterns = ['(']\n").is_err());
+    }
+
+    #[test]
+    fn daemon_defaults_and_validation() {
+        assert_eq!(Config::default().daemon.replay_bytes, 2 * 1024 * 1024);
+        let config = Config::from_toml("[daemon]\nreplay_bytes = 0\n").unwrap();
+        assert_eq!(config.daemon.replay_bytes, 0);
+        assert!(Config::from_toml("[daemon]\nreplay_bytes = 100000000\n").is_err());
+    }
+
+    #[test]
+    fn screenshot_defaults_and_validation() {
+        let config = Config::default();
+        assert!(config.screenshot.directory.is_empty());
+        assert!(config.screenshot.flash);
+        let config = Config::from_toml("[screenshot]\nfilename = \"shot-{time}.png\"\n").unwrap();
+        assert_eq!(config.screenshot.filename, "shot-{time}.png");
+        assert!(Config::from_toml("[screenshot]\nfilename = \"\"\n").is_err());
+        assert!(Config::from_toml("[screenshot]\nfilename = \"a/b.png\"\n").is_err());
+    }
+
+    #[test]
+    fn recording_defaults_and_validation() {
+        let config = Config::default();
+        assert_eq!(config.recording.filename, "veloterm-{date}-{time}.gif");
+        assert_eq!(config.recording.max_duration, 60);
+        assert_eq!(config.recording.max_fps, 15);
+        let config = Config::from_toml("[recording]\nmax_duration = 10\nmax_fps = 30\n").unwrap();
+        assert_eq!((config.recording.max_duration, config.recording.max_fps), (10, 30));
+        assert!(Config::from_toml("[recording]\nmax_duration = 0\n").is_err());
+        assert!(Config::from_toml("[recording]\nmax_fps = 60\n").is_err());
+        assert!(Config::from_toml("[recording]\nfilename = \"a/b.gif\"\n").is_err());
+    }
+
+    #[test]
+    fn script_overrides_merge_into_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[font]\nfamily = \"Iosevka\"\nsize = 12.0\n").unwrap();
+        std::fs::write(
+            dir.path().join("init.rhai"),
+            "set(\"font.size\", 12.0 + 3.0);\nset(\"scrollback.lines\", 2000);\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.font.family, "Iosevka");
+        assert_eq!(config.font.size, 15.0);
+        assert_eq!(config.scrollback.lines, 2000);
+
+        // Script values are validated like the file's
+        std::fs::write(dir.path().join("init.rhai"), "set(\"font.size\", 0.0);").unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Validation(_))));
+        std::fs::write(dir.path().join("init.rhai"), "set(\"font.size\",").unwrap();
+        assert!(matches!(Config::load(&path), Err(ConfigError::Script(_))));
+
+        std::fs::write(&path, "[scripting]\nenabled = false\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap().font.size, Config::default().font.size);
+    }
+
+    #[test]
+    fn minimap_defaults_and_validation() {
+        let config = Config::default();
+        assert!(!config.minimap.enabled);
+        assert_eq!(config.minimap.width, 80);
+        let config = Config::from_toml("[minimap]\nenabled = true\nline_height = 4\n").unwrap();
+        assert!(config.minimap.enabled);
+        assert_eq!(config.minimap.line_height, 4);
+        assert!(Config::from_toml("[minimap]\nline_height = 0\n").is_err());
+        assert!(Config::from_toml("[minimap]\nwidth = 1000\n").is_err());
+    }
+
+    #[test]
+    fn scrollbar_mode_default_and_validation() {
+        assert_eq!(Config::default().scrollback.scrollbar, "auto");
+        let config = Config::from_toml("[scrollback]\nscrollbar = \"always\"\n").unwrap();
+        assert_eq!(config.scrollback.scrollbar, "always");
+        let result = Config::from_toml("[scrollback]\nscrollbar = \"sometimes\"\n");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn invalid_zero_fps() {
         let toml = r#"
@@ -1385,6 +4100,17 @@ fps_limit = 0
         assert!(delta.cursor_changed);
     }
 
+    #[test]
+    fn parse_cursor_animation() {
+        assert!(!Config::default().cursor.animation);
+        let config = Config::from_toml("[cursor]\nanimation = true\nanimation_duration = 150\n").unwrap();
+        assert!(config.cursor.animation);
+        assert_eq!(config.cursor.animation_duration, 150);
+        assert!(Config::default().diff(&config).cursor_changed);
+        assert!(Config::from_toml("[cursor]\nanimation_duration = 0\n").is_err());
+        assert!(Config::from_toml("[cursor]\nanimation_duration = 5000\n").is_err());
+    }
+
     #[test]
     fn diff_detects_scrollback_change() {
         let a = Config::default();
@@ -1413,100 +4139,107 @@ fps_limit = 0
         assert_eq!(config, Config::default());
     }
 
-    // ── Cursor blink_rate config tests ─────────────────────────────
+    // ── Cursor blink_interval config tests ─────────────────────────────
 
     #[test]
-    fn default_cursor_blink_rate() {
+    fn default_cursor_blink_interval() {
         let config = Config::default();
-        assert_eq!(config.cursor.blink_rate, 500);
+        assert_eq!(config.cursor.blink_interval, 500);
     }
 
     #[test]
-    fn parse_cursor_blink_rate() {
+    fn parse_cursor_blink_interval() {
         let toml = r#"
 [cursor]
 style = "block"
 blink = true
-blink_rate = 750
+blink_interval = 750
 "#;
         let config = Config::from_toml(toml).unwrap();
-        assert_eq!(config.cursor.blink_rate, 750);
+        assert_eq!(config.cursor.blink_interval, 750);
+    }
+
+    #[test]
+    fn parse_cursor_legacy_blink_rate() {
+        let config = Config::from_toml("[cursor]\nblink_rate = 750\nstyle = \"bar\"\n").unwrap();
+        assert_eq!(config.cursor.blink_interval, 750);
+        assert_eq!(config.cursor.style, "bar");
     }
 
     #[test]
-    fn parse_cursor_blink_rate_default_when_missing() {
+    fn parse_cursor_blink_interval_default_when_missing() {
         let toml = r#"
 [cursor]
 style = "block"
 blink = true
 "#;
         let config = Config::from_toml(toml).unwrap();
-        assert_eq!(config.cursor.blink_rate, 500);
+        assert_eq!(config.cursor.blink_interval, 500);
     }
 
     #[test]
-    fn invalid_cursor_blink_rate_too_low() {
+    fn invalid_cursor_blink_interval_too_low() {
         let toml = r#"
 [cursor]
-blink_rate = 50
+blink_interval = 50
 "#;
         let result = Config::from_toml(toml);
         assert!(result.is_err());
     }
 
     #[test]
-    fn invalid_cursor_blink_rate_too_high() {
+    fn invalid_cursor_blink_interval_too_high() {
         let toml = r#"
 [cursor]
-blink_rate = 3000
+blink_interval = 3000
 "#;
         let result = Config::from_toml(toml);
         assert!(result.is_err());
     }
 
     #[test]
-    fn valid_cursor_blink_rate_zero_disables() {
+    fn valid_cursor_blink_interval_zero_disables() {
         let toml = r#"
 [cursor]
-blink_rate = 0
+blink_interval = 0
 "#;
         let config = Config::from_toml(toml).unwrap();
-        assert_eq!(config.cursor.blink_rate, 0);
+        assert_eq!(config.cursor.blink_interval, 0);
     }
 
     #[test]
-    fn valid_cursor_blink_rate_at_minimum() {
+    fn valid_cursor_blink_interval_at_minimum() {
         let toml = r#"
 [cursor]
-blink_rate = 100
+blink_interval = 100
 "#;
         let config = Config::from_toml(toml).unwrap();
-        assert_eq!(config.cursor.blink_rate, 100);
+        assert_eq!(config.cursor.blink_interval, 100);
     }
 
     #[test]
-    fn valid_cursor_blink_rate_at_maximum() {
+    fn valid_cursor_blink_interval_at_maximum() {
         let toml = r#"
 [cursor]
-blink_rate = 2000
+blink_interval = 2000
 "#;
         let config = Config::from_toml(toml).unwrap();
-        assert_eq!(config.cursor.blink_rate, 2000);
+        assert_eq!(config.cursor.blink_interval, 2000);
     }
 
     #[test]
-    fn diff_detects_cursor_blink_rate_change() {
+    fn diff_detects_cursor_blink_interval_change() {
         let a = Config::default();
         let mut b = Config::default();
-        b.cursor.blink_rate = 750;
+        b.cursor.blink_interval = 750;
         let delta = a.diff(&b);
         assert!(delta.cursor_changed);
     }
 
     #[test]
-    fn print_default_includes_blink_rate() {
+    fn print_default_includes_blink_interval() {
         let toml_str = Config::print_default();
-        assert!(toml_str.contains("blink_rate"));
+        assert!(toml_str.contains("blink_interval"));
     }
 
     #[test]
@@ -1521,6 +4254,60 @@ blink_rate = 2000
         assert_eq!(parsed.performance, default.performance);
     }
 
+    #[test]
+    fn first_run_default_lists_bindings_and_parses() {
+        let toml_str = Config::first_run_default();
+        assert!(toml_str.contains("# \"ctrl+shift+d\" = \"split_vertical\""));
+        let parsed = Config::from_toml(&toml_str).unwrap();
+        assert!(parsed.keys.bindings.is_empty());
+    }
+
+    #[test]
+    fn write_first_run_only_creates_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("veloterm").join("config.toml");
+        assert!(Config::write_first_run(&path).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), Config::first_run_default());
+        std::fs::write(&path, "[font]\nsize = 20.0\n").unwrap();
+        assert!(!Config::write_first_run(&path).unwrap());
+        assert_eq!(Config::load(&path).unwrap().font.size, 20.0);
+    }
+
+    #[test]
+    fn effective_config_round_trips() {
+        let mut config = Config::default();
+        config.font.size = 16.0;
+        config.shell.program = Some("/bin/bash".to_string());
+        config.shell.env.insert("EDITOR".to_string(), "vim".to_string());
+        config.keys.bindings.insert("ctrl+shift+d".to_string(), "none".to_string());
+        config.directory_rules.push(DirectoryRule {
+            path: "~/prod".to_string(),
+            badge: "PROD".to_string(),
+            color: "#E5484D".to_string(),
+            theme: Some("midnight".to_string()),
+            background: None,
+        });
+        let toml_str = config.to_toml_string().unwrap();
+        assert_eq!(Config::from_toml(&toml_str).unwrap(), config);
+        // Defaults are spelled out too
+        assert!(toml_str.contains("blink_interval = 500"));
+    }
+
+    #[test]
+    fn config_overrides_list_only_changed_keys() {
+        assert_eq!(Config::default().overrides_toml().unwrap(), "");
+        let config = Config::from_toml(
+            "[font]\nsize = 16.0\nfamily = \"JetBrains Mono\"\n[keys]\n\"alt+d\" = \"split_vertical\"\n",
+        )
+        .unwrap();
+        let overrides = config.overrides_toml().unwrap();
+        assert!(overrides.contains("size = 16.0"));
+        // Set to its default value, so not an override
+        assert!(!overrides.contains("family"));
+        assert!(!overrides.contains("[cursor]"));
+        assert_eq!(Config::from_toml(&overrides).unwrap(), config);
+    }
+
     // ── Quick terminal config tests ──────────────────────────────
 
     #[test]
@@ -1635,4 +4422,611 @@ width = 250.0
         let delta = a.diff(&b);
         assert!(delta.sidebar_changed);
     }
+
+    // ── Suggestions config tests ─────────────────────────────────
+
+    #[test]
+    fn parse_suggestions_config() {
+        let toml = r#"
+[suggestions]
+enabled = true
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert!(config.suggestions.enabled);
+    }
+
+    #[test]
+    fn parse_suggestions_default_disabled() {
+        let config = Config::from_toml("").unwrap();
+        assert!(!config.suggestions.enabled);
+    }
+
+    #[test]
+    fn diff_detects_suggestions_change() {
+        let a = Config::default();
+        let mut b = Config::default();
+        b.suggestions.enabled = true;
+        assert!(a.diff(&b).suggestions_changed);
+    }
+
+    // ── Color filter config tests ────────────────────────────────
+
+    #[test]
+    fn parse_colors_filter() {
+        let toml = r#"
+[colors]
+filter = "deuteranopia"
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.colors.filter, "deuteranopia");
+    }
+
+    #[test]
+    fn default_colors_filter_is_none() {
+        let config = Config::default();
+        assert_eq!(config.colors.filter, "none");
+    }
+
+    #[test]
+    fn validation_rejects_unknown_color_filter() {
+        let toml = r#"
+[colors]
+filter = "sepia"
+"#;
+        let result = Config::from_toml(toml);
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+    // ── ANSI palette override tests ──────────────────────────────
+
+    #[test]
+    fn parse_ansi_overrides() {
+        let toml = r##"
+[colors]
+theme = "light"
+
+[colors.ansi]
+red = "#e06c75"
+yellow = "#e5c07b"
+
+[colors.theme_ansi.light]
+yellow = "#b58900"
+"##;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.colors.ansi["red"], "#e06c75");
+        let light = config.colors.resolve_theme("light").unwrap();
+        assert_eq!(light.ansi[1], crate::config::theme::from_hex("#e06c75"));
+        // The per-theme entry wins over the all-themes one
+        assert_eq!(light.ansi[3], crate::config::theme::from_hex("#b58900"));
+        let dusk = config.colors.resolve_theme("dusk").unwrap();
+        assert_eq!(dusk.ansi[3], crate::config::theme::from_hex("#e5c07b"));
+    }
+
+    #[test]
+    fn default_palette_is_the_theme_palette() {
+        let config = Config::default();
+        let theme = config.colors.resolve_theme("midnight").unwrap();
+        assert_eq!(theme.ansi, TerminalTheme::midnight().ansi);
+        assert!(config.colors.resolve_theme("nonexistent").is_none());
+    }
+
+    #[test]
+    fn parse_background_per_theme() {
+        let toml = r##"
+[colors.background]
+image = "~/Pictures/waves.png"
+fit = "tile"
+
+[colors.theme_background.light]
+gradient = ["#fdf6e3", "#eee8d5"]
+dim = 0.2
+"##;
+        let config = Config::from_toml(toml).unwrap();
+        let dark = config.colors.resolve_background("midnight");
+        assert_eq!(dark.image, "~/Pictures/waves.png");
+        assert_eq!((dark.fit.as_str(), dark.dim), ("tile", 0.5));
+        let light = config.colors.resolve_background("light");
+        assert!(light.image.is_empty());
+        assert_eq!(light.gradient.len(), 2);
+        assert!(light.is_enabled());
+        assert!(!Config::default().colors.background.is_enabled());
+        assert!(Config::default().diff(&config).colors_changed);
+    }
+
+    #[test]
+    fn parse_theme_schedule() {
+        let toml = r#"
+[colors.schedule]
+enabled = true
+day = "dusk"
+night = "midnight"
+day_start = "06:30"
+transition_minutes = 0
+"#;
+        let schedule = Config::from_toml(toml).unwrap().colors.schedule;
+        assert!(schedule.enabled);
+        assert_eq!((schedule.day.as_str(), schedule.night.as_str()), ("dusk", "midnight"));
+        assert_eq!((schedule.day_start.as_str(), schedule.night_start.as_str()), ("06:30", "19:00"));
+        assert_eq!(schedule.transition_minutes, 0);
+        assert!(!Config::default().colors.schedule.enabled);
+    }
+
+    #[test]
+    fn validation_rejects_bad_theme_schedules() {
+        for toml in [
+            "[colors.schedule]\nday = \"sunny\"\n",
+            "[colors.schedule]\nnight_start = \"7pm\"\n",
+            "[colors.schedule]\nday_start = \"19:00\"\n",
+            "[colors.schedule]\nday_start = \"18:00\"\ntransition_minutes = 90\n",
+        ] {
+            let result = Config::from_toml(toml);
+            assert!(matches!(result, Err(ConfigError::Validation(_))), "{toml}");
+        }
+    }
+
+    #[test]
+    fn validation_rejects_bad_backgrounds() {
+        for toml in [
+            "[colors.background]\nfit = \"stretch\"\n",
+            "[colors.background]\ndim = 1.5\n",
+            "[colors.background]\ngradient = [\"#000000\"]\n",
+            "[colors.background]\ngradient = [\"#000000\", \"blue\"]\n",
+            "[colors.theme_background.solarized]\nimage = \"a.png\"\n",
+        ] {
+            let result = Config::from_toml(toml);
+            assert!(matches!(result, Err(ConfigError::Validation(_))), "{toml}");
+        }
+    }
+
+    #[test]
+    fn validation_rejects_bad_palette_entries() {
+        for toml in [
+            "[colors.ansi]\norange = \"#ff8800\"\n",
+            "[colors.ansi]\nred = \"red\"\n",
+            "[colors.theme_ansi.solarized]\nred = \"#ff0000\"\n",
+            "[colors.theme_ansi.light]\nred = \"#ff00\"\n",
+        ] {
+            let result = Config::from_toml(toml);
+            assert!(matches!(result, Err(ConfigError::Validation(_))), "{toml}");
+        }
+    }
+
+    // ── Accessibility config tests ───────────────────────────────
+
+    #[test]
+    fn parse_accessibility_line_view_command() {
+        let toml = r#"
+[accessibility]
+line_view_command = "espeak"
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.accessibility.line_view_command.as_deref(), Some("espeak"));
+    }
+
+    #[test]
+    fn default_accessibility_line_view_command_is_none() {
+        let config = Config::default();
+        assert!(config.accessibility.line_view_command.is_none());
+    }
+
+    // ── Guides config tests ─────────────────────────────────────
+
+    #[test]
+    fn guides_default_rulers() {
+        let config = Config::default();
+        assert_eq!(config.guides.rulers, vec![80, 100, 120]);
+        assert!(!config.guides.show_rulers);
+        assert!(!config.guides.crosshair);
+        assert!(!config.guides.wrap_indicator);
+        assert!(!config.guides.soft_wrap);
+        assert_eq!(config.guides.soft_wrap_column, 100);
+    }
+
+    #[test]
+    fn validation_rejects_tiny_soft_wrap_column() {
+        let config = Config::from_toml("[guides]\nsoft_wrap = true\nsoft_wrap_column = 72\n").unwrap();
+        assert!(config.guides.soft_wrap);
+        assert_eq!(config.guides.soft_wrap_column, 72);
+        assert!(Config::from_toml("[guides]\nsoft_wrap_column = 3\n").is_err());
+    }
+
+    #[test]
+    fn parse_guides_section() {
+        let toml = r#"
+[guides]
+rulers = [72]
+show_rulers = true
+crosshair = true
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.guides.rulers, vec![72]);
+        assert!(config.guides.show_rulers);
+        assert!(config.guides.crosshair);
+    }
+
+    #[test]
+    fn validation_rejects_zero_ruler_column() {
+        let toml = r#"
+[guides]
+rulers = [0, 80]
+"#;
+        let result = Config::from_toml(toml);
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+    // ── Command hint config tests ───────────────────────────────
+
+    #[test]
+    fn command_hints_disabled_by_default() {
+        assert!(!Config::default().command_hints.enabled);
+    }
+
+    #[test]
+    fn parse_command_hints_enabled() {
+        let toml = r#"
+[command_hints]
+enabled = true
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert!(config.command_hints.enabled);
+    }
+
+    // ── Directory rule config tests ─────────────────────────────
+
+    #[test]
+    fn parse_directory_rules() {
+        let config = Config::from_toml(
+            r##"
+[[directory_rules]]
+path = "~/work/prod-infra"
+badge = "PROD"
+
+[[directory_rules]]
+path = "/srv"
+badge = "SRV"
+color = "#30A46C"
+"##,
+        )
+        .unwrap();
+        assert_eq!(config.directory_rules.len(), 2);
+        assert_eq!(config.directory_rules[0].color, "#E5484D");
+        assert_eq!(config.directory_rules[1].badge, "SRV");
+    }
+
+    #[test]
+    fn directory_rule_rejects_bad_color() {
+        let result = Config::from_toml(
+            r#"
+[[directory_rules]]
+path = "/srv"
+badge = "SRV"
+color = "red"
+"#,
+        );
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+    #[test]
+    fn directory_rule_requires_badge() {
+        let result = Config::from_toml("[[directory_rules]]\npath = \"/srv\"\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+    #[test]
+    fn directory_rule_with_only_a_theme() {
+        let config =
+            Config::from_toml("[[directory_rules]]\npath = \"/srv\"\ntheme = \"midnight\"\n").unwrap();
+        assert_eq!(config.directory_rules[0].theme.as_deref(), Some("midnight"));
+        assert!(config.directory_rules[0].badge.is_empty());
+        let result = Config::from_toml("[[directory_rules]]\npath = \"/srv\"\ntheme = \"nope\"\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+    #[test]
+    fn diff_detects_directory_rules_change() {
+        let a = Config::default();
+        let b = Config::from_toml("[[directory_rules]]\npath = \"/srv\"\nbadge = \"SRV\"\n").unwrap();
+        assert!(a.diff(&b).directory_rules_changed);
+    }
+
+    // ── Profile config tests ────────────────────────────────────
+
+    #[test]
+    fn parse_user_theme_inheriting_builtin() {
+        let toml = r##"
+[colors]
+theme = "my_theme"
+
+[colors.theme_ansi.my_theme]
+blue = "#0000ff"
+
+[themes.my_theme]
+inherit = "midnight"
+bg_deep = "#101014"
+red = "#ff0000"
+"##;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.colors.user_themes.len(), 1);
+        let theme = config.colors.user_themes["my_theme"];
+        assert_eq!(theme.bg_deep, crate::config::theme::from_hex("#101014"));
+        assert_eq!(theme.ansi[1], crate::config::theme::from_hex("#ff0000"));
+        assert_eq!(theme.accent_orange, crate::config::theme::MIDNIGHT.accent_orange);
+        assert!(Config::default().diff(&config).colors_changed);
+        // Without inherit the theme is rejected
+        let error = Config::from_toml("[themes.plain]\nbg_deep = \"#000000\"\n").unwrap_err();
+        assert!(error.to_string().contains("themes.plain needs inherit"), "{error}");
+    }
+
+    #[test]
+    fn resolve_theme_finds_user_themes() {
+        let config = Config::from_toml(
+            "[colors.ansi]\nred = \"#ff0000\"\n\n[themes.mine]\ninherit = \"light\"\nbg_deep = \"#101014\"\n",
+        )
+        .unwrap();
+        let theme = config.colors.resolve_theme("mine").unwrap();
+        assert_eq!(theme.bg_deep, crate::config::theme::from_hex("#101014"));
+        assert_eq!(theme.ansi[1], crate::config::theme::from_hex("#ff0000"));
+        assert_eq!(theme.accent_orange, crate::config::theme::LIGHT.accent_orange);
+        assert!(ColorsConfig::default().resolve_theme("mine").is_none());
+        assert!(TerminalTheme::from_name("mine").is_none());
+    }
+
+    #[test]
+    fn validation_rejects_bad_user_themes() {
+        for toml in [
+            "[themes.mine]\ninherit = \"nope\"\n",
+            "[themes.mine]\ninherit = \"light\"\nbackground = \"#000000\"\n",
+            "[themes.mine]\ninherit = \"light\"\nbg_deep = \"black\"\n",
+            "[themes.midnight]\ninherit = \"light\"\nbg_deep = \"#000000\"\n",
+            "[colors]\ntheme = \"mine\"\n",
+        ] {
+            let result = Config::from_toml(toml);
+            assert!(matches!(result, Err(ConfigError::Validation(_))), "{toml}");
+        }
+        let error = Config::from_toml("[themes.mine]\ninherit = \"light\"\nbg_dep = \"#000000\"\n")
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown key 'bg_dep' in themes.mine"), "{error}");
+    }
+
+    #[test]
+    fn parse_profiles() {
+        let config = Config::from_toml(
+            r##"
+[[profiles]]
+name = "Prod SSH"
+command = "ssh"
+args = ["prod-bastion"]
+env = { AWS_PROFILE = "prod" }
+theme = "ember"
+background = "#2A1414"
+color_filter = "grayscale"
+
+[[profiles]]
+name = "Scratch"
+cwd = "/tmp"
+"##,
+        )
+        .unwrap();
+        assert_eq!(config.profiles.len(), 2);
+        let prod = &config.profiles[0];
+        assert_eq!(prod.command.as_deref(), Some("ssh"));
+        assert_eq!(prod.args, ["prod-bastion"]);
+        assert_eq!(prod.env["AWS_PROFILE"], "prod");
+        assert_eq!(prod.background.as_deref(), Some("#2A1414"));
+        assert_eq!(prod.trusted, None);
+        assert_eq!(prod.color_filter.as_deref(), Some("grayscale"));
+        assert_eq!(config.profiles[1].theme, None);
+        assert_eq!(config.profiles[1].cwd.as_deref(), Some("/tmp"));
+    }
+
+    #[test]
+    fn profile_validation() {
+        for toml in [
+            "[[profiles]]\ncwd = \"/tmp\"\n",
+            "[[profiles]]\nname = \"a\"\n[[profiles]]\nname = \"a\"\n",
+            "[[profiles]]\nname = \"a\"\ntheme = \"nope\"\n",
+            "[[profiles]]\nname = \"a\"\nbackground = \"red\"\n",
+            "[[profiles]]\nname = \"a\"\ncommand = \"\"\n",
+            "[[profiles]]\nname = \"a\"\ncompat = \"ancient\"\n",
+            "[[profiles]]\nname = \"a\"\ncolor_filter = \"sepia\"\n",
+            "[[profiles]]\nname = \"a\"\nterm = \"xterm 256\"\n",
+        ] {
+            assert!(matches!(Config::from_toml(toml), Err(ConfigError::Validation(_))), "{toml}");
+        }
+    }
+
+    #[test]
+    fn diff_detects_profiles_change() {
+        let a = Config::default();
+        let b = Config::from_toml("[[profiles]]\nname = \"Scratch\"\n").unwrap();
+        assert!(a.diff(&b).profiles_changed);
+    }
+
+    // ── Git status config tests ─────────────────────────────────
+
+    #[test]
+    fn git_status_disabled_by_default() {
+        let config = Config::default();
+        assert!(!config.git_status.enabled);
+        assert_eq!(config.git_status.refresh_interval_secs, 5);
+    }
+
+    #[test]
+    fn git_status_rejects_zero_interval() {
+        let result = Config::from_toml("[git_status]\nrefresh_interval_secs = 0\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+
+    // ── Renderer config tests ───────────────────────────────────
+
+    #[test]
+    fn renderer_gpu_defaults_to_auto() {
+        assert_eq!(Config::default().renderer.gpu, "auto");
+        let config = Config::from_toml("[renderer]\ngpu = \"NVIDIA\"\n").unwrap();
+        assert_eq!(config.renderer.gpu, "NVIDIA");
+    }
+
+    #[test]
+    fn renderer_rejects_empty_gpu() {
+        let result = Config::from_toml("[renderer]\ngpu = \"  \"\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+
+    #[test]
+    fn renderer_present_mode_defaults_to_fifo() {
+        let config = Config::default();
+        assert_eq!(config.renderer.present_mode, "fifo");
+        assert!(!config.renderer.low_latency);
+        let config =
+            Config::from_toml("[renderer]\npresent_mode = \"mailbox\"\nlow_latency = true\n").unwrap();
+        assert_eq!(config.renderer.present_mode, "mailbox");
+        assert!(config.renderer.low_latency);
+    }
+
+    #[test]
+    fn renderer_rejects_unknown_present_mode() {
+        let result = Config::from_toml("[renderer]\npresent_mode = \"vsync\"\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+
+    // ── Search config tests ─────────────────────────────────────
+
+    #[test]
+    fn search_centers_matches_by_default() {
+        let config = Config::default();
+        assert!(config.search.center_match);
+        assert_eq!(config.search.context_lines, 3);
+        let config = Config::from_toml("[search]\ncenter_match = false\ncontext_lines = 8\n").unwrap();
+        assert!(!config.search.center_match);
+        assert_eq!(config.search.context_lines, 8);
+    }
+
+
+    #[test]
+    fn search_match_strip_config() {
+        let config = Config::default();
+        assert!(config.search.match_strip);
+        assert!(config.search.error_pattern.is_empty());
+        let config =
+            Config::from_toml("[search]\nmatch_strip = false\nerror_pattern = \"error|failed\"\n")
+                .unwrap();
+        assert!(!config.search.match_strip);
+        assert_eq!(config.search.error_pattern, "error|failed");
+    }
+
+    #[test]
+    fn search_debounce_config() {
+        assert_eq!(Config::default().search.debounce_ms, 60);
+        let config = Config::from_toml("[search]\ndebounce_ms = 0\n").unwrap();
+        assert_eq!(config.search.debounce_ms, 0);
+        assert!(Config::from_toml("[search]\ndebounce_ms = 5000\n").is_err());
+    }
+
+    #[test]
+    fn search_invalid_error_pattern_rejected() {
+        let result = Config::from_toml("[search]\nerror_pattern = \"(unclosed\"\n");
+        assert!(matches!(result, Err(ConfigError::Validation(_))));
+    }
+
+
+    #[test]
+    fn background_reflow_lines_config() {
+        let config = Config::default();
+        assert_eq!(
+            config.performance.background_reflow_lines,
+            crate::terminal::DEFAULT_BACKGROUND_REFLOW_LINES
+        );
+        let config = Config::from_toml("[performance]\nbackground_reflow_lines = 0\n").unwrap();
+        assert_eq!(config.performance.background_reflow_lines, 0);
+    }
+
+    #[test]
+    fn flood_threshold_config() {
+        assert_eq!(Config::default().performance.flood_threshold_mb, 16);
+        let config = Config::from_toml("[performance]\nflood_threshold_mb = 0\n").unwrap();
+        assert_eq!(config.performance.flood_threshold_mb, 0);
+        assert!(Config::from_toml("[performance]\nflood_threshold_mb = 5000\n").is_err());
+    }
+
+
+    // ── Selection ───────────────────────────────────────────────────
+
+    #[test]
+    fn selection_copy_mode_defaults_to_logical() {
+        assert_eq!(Config::default().selection.copy_mode, "logical");
+        let config = Config::from_toml("[selection]\ncopy_mode = \"grid\"\n").unwrap();
+        assert_eq!(config.selection.copy_mode, "grid");
+        assert!(Config::default().diff(&config).selection_changed);
+    }
+
+    #[test]
+    fn invalid_selection_copy_mode() {
+        let result = Config::from_toml("[selection]\ncopy_mode = \"exact\"\n");
+        assert!(result.is_err());
+    }
+
+
+    // ── Window ──────────────────────────────────────────────────────
+
+    #[test]
+    fn window_class_defaults_to_veloterm() {
+        assert_eq!(Config::default().window.class, "veloterm");
+        let config = Config::from_toml("[window]\nclass = \"scratchpad\"\n").unwrap();
+        assert_eq!(config.window.class, "scratchpad");
+        assert!(Config::default().diff(&config).window_changed);
+    }
+
+    #[test]
+    fn window_title_template() {
+        assert_eq!(Config::default().window.title, "{title} - VeloTerm");
+        let config = Config::from_toml("[window]\ntitle = \"\"\n").unwrap();
+        assert!(config.window.title.is_empty());
+    }
+
+    #[test]
+    fn single_instance_defaults_off() {
+        assert!(!Config::default().window.single_instance);
+        let config = Config::from_toml("[window]\nsingle_instance = true\n").unwrap();
+        assert!(config.window.single_instance);
+        assert_eq!(config.window.class, "veloterm");
+        assert!(Config::default().diff(&config).window_changed);
+    }
+
+    #[test]
+    fn invalid_window_class() {
+        assert!(Config::from_toml("[window]\nclass = \"\"\n").is_err());
+        assert!(Config::from_toml("[window]\nclass = \"my term\"\n").is_err());
+    }
+
+    #[test]
+    fn window_opacity_and_blur() {
+        let defaults = Config::default();
+        assert_eq!(defaults.window.opacity, 1.0);
+        assert!(!defaults.window.blur);
+        let config = Config::from_toml("[window]\nopacity = 0.85\nblur = true\n").unwrap();
+        assert_eq!(config.window.opacity, 0.85);
+        assert!(config.window.blur);
+        assert!(defaults.diff(&config).window_changed);
+        assert!(Config::from_toml("[window]\nopacity = 1.5\n").is_err());
+        assert!(Config::from_toml("[window]\nopacity = -0.1\n").is_err());
+    }
+
+
+    // ── macOS ───────────────────────────────────────────────────────
+
+    #[test]
+    fn macos_option_as_alt_defaults_to_none() {
+        assert_eq!(Config::default().macos.option_as_alt, "none");
+        let config = Config::from_toml("[macos]\noption_as_alt = \"left\"\n").unwrap();
+        assert_eq!(config.macos.option_as_alt, "left");
+        assert!(Config::default().diff(&config).macos_changed);
+    }
+
+    #[test]
+    fn invalid_macos_option_as_alt() {
+        assert!(Config::from_toml("[macos]\noption_as_alt = \"only_left\"\n").is_err());
+    }
 }
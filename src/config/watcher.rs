@@ -11,6 +11,19 @@ pub enum UserEvent {
     ConfigReloaded(Config, ConfigDelta),
     /// Quick terminal global hotkey was pressed.
     QuickTerminalToggle,
+    /// A background git status read finished for a directory.
+    GitSegmentReady(String, Option<crate::git_segment::GitSegment>),
+    /// An item was chosen from the macOS menu bar.
+    MenuAction(crate::input::actions::Action),
+    /// The "New VeloTerm Tab Here" service was run on a folder in Finder.
+    OpenAtFolder(std::path::PathBuf),
+    /// The next chunk of a slow paste into a pane, and whether it is the last.
+    PasteChunk(crate::pane::PaneId, Vec<u8>, bool),
+    /// A `veloterm ctl` request, answered on the sender.
+    Control(
+        crate::control::protocol::Request,
+        crossbeam_channel::Sender<crate::control::protocol::Response>,
+    ),
 }
 
 /// Watches a config file for changes and invokes a callback on valid reloads.
@@ -62,7 +75,7 @@ impl ConfigWatcher {
                     }
                 };
 
-                let new_config = match Config::from_toml(&contents) {
+                let new_config = match Config::from_toml_with_script(&contents, &watch_path) {
                     Ok(c) => c,
                     Err(e) => {
                         log::warn!("Config reload failed (keeping previous): {e}");
@@ -71,7 +84,11 @@ impl ConfigWatcher {
                 };
 
                 let mut prev = current.lock().unwrap();
-                let delta = prev.diff(&new_config);
+                let mut delta = prev.diff(&new_config);
+                // An edited script can change key callbacks and hooks alone
+                if let Some(script) = new_config.script_path(&watch_path) {
+                    delta.scripting_changed |= event.paths.contains(&script);
+                }
                 if !delta.is_empty() {
                     on_reload(new_config.clone(), delta);
                     *prev = new_config;
@@ -210,8 +227,8 @@ mod tests {
                 assert_eq!(c, config);
                 assert!(d.is_empty());
             }
-            UserEvent::QuickTerminalToggle => {
-                panic!("Expected ConfigReloaded, got QuickTerminalToggle");
+            other => {
+                panic!("Expected ConfigReloaded, got {other:?}");
             }
         }
     }
@@ -0,0 +1,103 @@
+// Remote control: each window listens on a control socket so scripts and
+// window managers can drive it with `veloterm ctl` (list panes, split, send
+// text, switch tabs, read a screen). Shells started by the window find it through
+// $VELOTERM_SOCKET; from elsewhere `veloterm ctl` picks the newest window.
+
+pub mod protocol;
+pub mod server;
+
+use std::io::{self, BufReader};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+use protocol::{read_message, write_message, Request, Response};
+
+/// Exported to shells: the control socket of their window.
+pub const SOCKET_ENV: &str = "VELOTERM_SOCKET";
+
+/// File name prefix of window control sockets in the runtime dir.
+const SOCKET_PREFIX: &str = "ctl-";
+
+/// The control socket of the window in process `pid`.
+pub fn socket_path(pid: u32) -> PathBuf {
+    crate::daemon::runtime_dir().join(format!("{SOCKET_PREFIX}{pid}.sock"))
+}
+
+/// The socket `veloterm ctl` talks to: $VELOTERM_SOCKET, else the newest
+/// control socket in `dir` that a window is listening on. None when `dir`
+/// isn't private to this user.
+pub fn find_socket(dir: &Path) -> Option<PathBuf> {
+    if let Some(socket) = std::env::var_os(SOCKET_ENV).filter(|s| !s.is_empty()) {
+        return Some(PathBuf::from(socket));
+    }
+    if let Err(e) = crate::daemon::check_private_dir(dir) {
+        log::warn!("Not looking for windows: {e}");
+        return None;
+    }
+    let mut sockets: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(SOCKET_PREFIX) && name.ends_with(".sock")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    sockets.sort();
+    sockets
+        .into_iter()
+        .rev()
+        .map(|(_, path)| path)
+        .find(|path| UnixStream::connect(path).is_ok())
+}
+
+/// Send one request and wait for the response. Fails when the socket's
+/// directory isn't private to this user or another user listens on it.
+pub fn request(socket: &Path, request: &Request) -> io::Result<Response> {
+    if let Some(dir) = socket.parent() {
+        crate::daemon::check_private_dir(dir)?;
+    }
+    let mut stream = UnixStream::connect(socket)?;
+    crate::daemon::check_peer(&stream)?;
+    write_message(&mut stream, request)?;
+    read_message(&mut BufReader::new(stream))?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "window closed the connection"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_path_is_per_process() {
+        let path = socket_path(4242);
+        assert_eq!(path.file_name().unwrap(), "ctl-4242.sock");
+        assert_eq!(path.parent().unwrap(), crate::daemon::runtime_dir());
+    }
+
+    #[test]
+    fn find_socket_skips_shared_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sockets = dir.path().join("veloterm");
+        let socket = sockets.join("ctl-1.sock");
+        let _server = server::ControlServer::start(&socket, |_, reply| {
+            let _ = reply.send(Response::Ok);
+        })
+        .unwrap();
+        std::env::remove_var(SOCKET_ENV);
+        assert_eq!(find_socket(&sockets), Some(socket.clone()));
+
+        std::fs::set_permissions(&sockets, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert_eq!(find_socket(&sockets), None);
+        assert!(request(&socket, &Request::ListPanes).is_err());
+    }
+
+    #[test]
+    fn request_fails_without_window() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(request(&dir.path().join("ctl-1.sock"), &Request::ListPanes).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Wire format of the control socket: one JSON request per line, answered by
+// one JSON response line. Requests are tagged by `command`, e.g.
+// {"command":"send-text","pane":3,"text":"ls\n"}.
+
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// Which way `split-pane` puts the new pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SplitSide {
+    /// Beside the pane, on the right.
+    #[default]
+    Right,
+    /// Below the pane.
+    Down,
+}
+
+/// A command for a window. `pane: None` means the focused pane.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum Request {
+    /// Every pane in every tab.
+    ListPanes,
+    /// Split a pane; replies with the new pane.
+    SplitPane {
+        #[serde(default)]
+        pane: Option<u32>,
+        #[serde(default)]
+        side: SplitSide,
+    },
+    /// Write text to a pane's shell as if typed.
+    SendText {
+        #[serde(default)]
+        pane: Option<u32>,
+        text: String,
+    },
+    /// Switch to the tab at this index (0-based, as listed).
+    ActivateTab { index: usize },
+    /// Switch to a pane's tab and focus it.
+    ActivatePane { pane: u32 },
+    /// Open a tab, optionally starting its shell in `cwd` and locking its
+    /// title; replies with its pane.
+    NewTab {
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+    },
+    /// Close a pane (its tab when it is the last one).
+    ClosePane {
+        #[serde(default)]
+        pane: Option<u32>,
+    },
+    /// The rows on a pane's screen, ignoring scrollback and scrolling.
+    GetText {
+        #[serde(default)]
+        pane: Option<u32>,
+    },
+    /// Rasterize the window's font at this scale factor instead of the
+    /// display's; None goes back to `font.scale_factor`.
+    SetFontScale {
+        #[serde(default)]
+        scale: Option<f64>,
+    },
+}
+
+/// A pane as listed by `list-panes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneEntry {
+    pub id: u32,
+    /// Index of the pane's tab.
+    pub tab: usize,
+    pub tab_title: String,
+    pub cwd: Option<String>,
+    pub cols: usize,
+    pub rows: usize,
+    /// Focused pane of the active tab.
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum Response {
+    Ok,
+    /// The pane a request created.
+    Pane { id: u32 },
+    Panes { panes: Vec<PaneEntry> },
+    /// Screen rows, trailing blanks trimmed.
+    Text { lines: Vec<String> },
+    Error { message: String },
+}
+
+impl Response {
+    pub fn error(message: impl Into<String>) -> Self {
+        Response::Error { message: message.into() }
+    }
+}
+
+/// Write `value` as one JSON line.
+pub fn write_message<T: Serialize>(w: &mut impl Write, value: &T) -> io::Result<()> {
+    let mut line = serde_json::to_vec(value).map_err(io::Error::other)?;
+    line.push(b'\n');
+    w.write_all(&line)?;
+    w.flush()
+}
+
+/// Read one JSON line; None at end of stream.
+pub fn read_message<T: for<'de> Deserialize<'de>>(r: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if r.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_use_command_tags_and_defaults() {
+        let req: Request = serde_json::from_str(r#"{"command":"send-text","text":"ls\n"}"#).unwrap();
+        assert_eq!(req, Request::SendText { pane: None, text: "ls\n".into() });
+        let req: Request = serde_json::from_str(r#"{"command":"split-pane","pane":2,"side":"down"}"#).unwrap();
+        assert_eq!(req, Request::SplitPane { pane: Some(2), side: SplitSide::Down });
+        assert_eq!(
+            serde_json::to_string(&Request::ListPanes).unwrap(),
+            r#"{"command":"list-panes"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Request::NewTab { cwd: None, title: None }).unwrap(),
+            r#"{"command":"new-tab","cwd":null}"#
+        );
+        let req: Request = serde_json::from_str(r#"{"command":"new-tab","title":"logs"}"#).unwrap();
+        assert_eq!(req, Request::NewTab { cwd: None, title: Some("logs".into()) });
+        let req: Request = serde_json::from_str(r#"{"command":"get-text"}"#).unwrap();
+        assert_eq!(req, Request::GetText { pane: None });
+        let req: Request = serde_json::from_str(r#"{"command":"set-font-scale","scale":2.0}"#).unwrap();
+        assert_eq!(req, Request::SetFontScale { scale: Some(2.0) });
+        let req: Request = serde_json::from_str(r#"{"command":"set-font-scale"}"#).unwrap();
+        assert_eq!(req, Request::SetFontScale { scale: None });
+        assert!(serde_json::from_str::<Request>(r#"{"command":"reboot"}"#).is_err());
+    }
+
+    #[test]
+    fn messages_round_trip_as_lines() {
+        let responses = [
+            Response::Ok,
+            Response::Pane { id: 4 },
+            Response::Panes {
+                panes: vec![PaneEntry {
+                    id: 1,
+                    tab: 0,
+                    tab_title: "zsh".into(),
+                    cwd: Some("/tmp".into()),
+                    cols: 80,
+                    rows: 24,
+                    focused: true,
+                }],
+            },
+            Response::Text { lines: vec!["$ ls".into(), String::new()] },
+            Response::error("no pane 9"),
+        ];
+        let mut buf = Vec::new();
+        for response in &responses {
+            write_message(&mut buf, response).unwrap();
+        }
+        let mut r = buf.as_slice();
+        for response in responses {
+            assert_eq!(read_message::<Response>(&mut r).unwrap(), Some(response));
+        }
+        assert_eq!(read_message::<Response>(&mut r).unwrap(), None);
+    }
+}
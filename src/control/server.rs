@@ -0,0 +1,138 @@
+// The window's end of the control socket: a listener thread accepts
+// connections and hands each request to `dispatch` along with a channel for
+// the response. The window answers from its event loop.
+
+use std::io::{self, BufReader};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+
+use super::protocol::{read_message, write_message, Request, Response};
+
+/// How long a connection waits for the window to answer.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Listens on a control socket until dropped, which removes the socket.
+pub struct ControlServer {
+    socket: PathBuf,
+}
+
+impl ControlServer {
+    /// Listen on `socket` (only this user may connect), passing requests
+    /// to `dispatch`.
+    pub fn start<F>(socket: &Path, dispatch: F) -> io::Result<Self>
+    where
+        F: Fn(Request, Sender<Response>) + Send + Sync + 'static,
+    {
+        let listener = bind(socket)?;
+        let dispatch = Arc::new(dispatch);
+        thread::Builder::new().name("control-socket".into()).spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let dispatch = Arc::clone(&dispatch);
+                thread::spawn(move || {
+                    if let Err(e) = serve(stream, &*dispatch) {
+                        log::debug!("Control connection ended: {e}");
+                    }
+                });
+            }
+        })?;
+        Ok(Self { socket: socket.to_path_buf() })
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// Answer a connection's requests in order until it closes.
+fn serve(stream: UnixStream, dispatch: &(dyn Fn(Request, Sender<Response>) + Send + Sync)) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let response = match read_message::<Request>(&mut reader) {
+            Ok(None) => return Ok(()),
+            Ok(Some(request)) => {
+                let (tx, rx) = crossbeam_channel::bounded(1);
+                dispatch(request, tx);
+                rx.recv_timeout(REPLY_TIMEOUT)
+                    .unwrap_or_else(|_| Response::error("the window did not answer"))
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => Response::error(format!("bad request: {e}")),
+            Err(e) => return Err(e),
+        };
+        write_message(&mut writer, &response)?;
+    }
+}
+
+fn bind(socket: &Path) -> io::Result<UnixListener> {
+    if let Some(dir) = socket.parent() {
+        crate::daemon::create_private_dir(dir)?;
+    }
+    // Left behind by a window that crashed with this PID
+    let _ = std::fs::remove_file(socket);
+    let listener = UnixListener::bind(socket)?;
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::request;
+
+    #[test]
+    fn answers_requests_through_dispatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("veloterm").join("ctl.sock");
+        let server = ControlServer::start(&socket, |request, reply| {
+            let response = match request {
+                Request::ActivateTab { index } if index < 2 => Response::Ok,
+                Request::ActivateTab { index } => Response::error(format!("no tab {index}")),
+                _ => Response::Pane { id: 7 },
+            };
+            let _ = reply.send(response);
+        })
+        .unwrap();
+        assert_eq!(request(&socket, &Request::ActivateTab { index: 1 }).unwrap(), Response::Ok);
+        assert_eq!(
+            request(&socket, &Request::ActivateTab { index: 5 }).unwrap(),
+            Response::error("no tab 5")
+        );
+        assert_eq!(
+            request(&socket, &Request::NewTab { cwd: None, title: None }).unwrap(),
+            Response::Pane { id: 7 }
+        );
+        drop(server);
+        assert!(!socket.exists());
+    }
+
+    #[test]
+    fn reports_bad_requests_and_keeps_serving() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("veloterm").join("ctl.sock");
+        let _server = ControlServer::start(&socket, |_, reply| {
+            let _ = reply.send(Response::Ok);
+        })
+        .unwrap();
+        let mut stream = UnixStream::connect(&socket).unwrap();
+        stream.write_all(b"{\"command\":\"reboot\"}\n{\"command\":\"list-panes\"}\n").unwrap();
+        let mut reader = BufReader::new(stream);
+        let first: Response = read_message(&mut reader).unwrap().unwrap();
+        assert!(matches!(first, Response::Error { .. }));
+        assert_eq!(read_message::<Response>(&mut reader).unwrap(), Some(Response::Ok));
+    }
+}
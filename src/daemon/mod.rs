@@ -0,0 +1,247 @@
+// Detachable sessions: `veloterm --daemon` owns shells and their recent
+// output, and windows opened with `veloterm attach` show them over a Unix
+// socket. Closing such a window only detaches; the next `veloterm attach`
+// gets the shells back, their output replayed, one tab per pane.
+
+pub mod protocol;
+pub mod server;
+
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use protocol::{read_frame, write_frame, Frame, PaneSummary};
+
+/// How long `start` waits for a new daemon to listen.
+const START_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Per-user folder for VeloTerm's sockets: $XDG_RUNTIME_DIR/veloterm, else
+/// veloterm-<uid> in the temp dir.
+pub fn runtime_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime) if !runtime.is_empty() => PathBuf::from(runtime).join("veloterm"),
+        _ => std::env::temp_dir().join(format!("veloterm-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Create `dir` for sockets, then check it as `check_private_dir` does.
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    check_private_dir(dir)
+}
+
+/// Fail unless `dir` is a real directory (not a symlink) owned by this
+/// user that no one else can enter. The temp dir fallback of `runtime_dir`
+/// can be created by another user first, to plant or watch our sockets.
+pub fn check_private_dir(dir: &Path) -> io::Result<()> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    let uid = unsafe { libc::getuid() };
+    if !metadata.file_type().is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a directory only this user can use", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Fail unless the process at the other end of `stream` runs as this user.
+pub fn check_peer(stream: &UnixStream) -> io::Result<()> {
+    let uid = peer_uid(stream)?;
+    if uid != unsafe { libc::getuid() } {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("socket is served by another user (uid {uid})"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    use std::os::fd::AsRawFd;
+
+    let mut cred = libc::ucred { pid: 0, uid: 0, gid: 0 };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            (&mut cred as *mut libc::ucred).cast(),
+            &mut len,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(cred.uid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    use std::os::fd::AsRawFd;
+
+    let (mut uid, mut gid) = (0, 0);
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(uid)
+}
+
+/// Connect to the daemon's `socket`, if its directory is private.
+fn connect(socket: &Path) -> io::Result<UnixStream> {
+    if let Some(dir) = socket.parent() {
+        check_private_dir(dir)?;
+    }
+    UnixStream::connect(socket)
+}
+
+/// The daemon's socket: daemon.sock in the runtime dir.
+pub fn socket_path() -> PathBuf {
+    runtime_dir().join("daemon.sock")
+}
+
+/// Whether a daemon is listening on `socket`.
+pub fn is_running(socket: &Path) -> bool {
+    connect(socket).is_ok()
+}
+
+/// Start `veloterm --daemon` in the background, in its own process group
+/// so the launching terminal's hangup doesn't reach it, and wait until it
+/// listens on `socket`.
+pub fn start(socket: &Path, config: Option<&Path>) -> io::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command.arg("--daemon");
+    if let Some(config) = config {
+        command.arg("--config").arg(config);
+    }
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    let deadline = Instant::now() + START_TIMEOUT;
+    while Instant::now() < deadline {
+        if is_running(socket) {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("daemon did not start listening on {}", socket.display()),
+    ))
+}
+
+/// The daemon's panes, in creation order.
+pub fn list_panes(socket: &Path) -> io::Result<Vec<PaneSummary>> {
+    let mut stream = connect(socket)?;
+    write_frame(&mut stream, &Frame::List)?;
+    match read_frame(&mut stream)? {
+        Some(Frame::Panes(panes)) => Ok(panes),
+        Some(Frame::Error(e)) => Err(io::Error::other(e)),
+        _ => Err(io::Error::other("unexpected reply to List")),
+    }
+}
+
+/// A connection streaming one daemon pane. Dropping it detaches: the
+/// shell keeps running in the daemon.
+pub struct DaemonLink {
+    stream: UnixStream,
+    pane: PaneSummary,
+}
+
+impl DaemonLink {
+    /// Send `request` (`Spawn` or `Attach`) and wait for the daemon to
+    /// accept it.
+    pub fn connect(socket: &Path, request: &Frame) -> io::Result<Self> {
+        let mut stream = connect(socket)?;
+        write_frame(&mut stream, request)?;
+        match read_frame(&mut stream)? {
+            Some(Frame::Attached(pane)) => Ok(Self { stream, pane }),
+            Some(Frame::Error(e)) => Err(io::Error::other(e)),
+            _ => Err(io::Error::other("unexpected reply from daemon")),
+        }
+    }
+
+    /// The pane as it was when attached.
+    pub fn pane(&self) -> &PaneSummary {
+        &self.pane
+    }
+
+    /// A handle for reading the pane's `Output` frames.
+    pub fn reader(&self) -> io::Result<UnixStream> {
+        self.stream.try_clone()
+    }
+
+    /// Send `Input`, `Resize` or `Kill`.
+    pub fn send(&self, frame: &Frame) -> io::Result<()> {
+        write_frame(&mut &self.stream, frame)
+    }
+}
+
+impl Drop for DaemonLink {
+    fn drop(&mut self) {
+        // Also wakes the reader thread, which holds its own handle
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_path_is_per_user() {
+        let path = socket_path();
+        assert_eq!(path.file_name().unwrap(), "daemon.sock");
+        let dir = path.parent().unwrap().file_name().unwrap().to_string_lossy().into_owned();
+        assert!(dir == "veloterm" || dir.starts_with("veloterm-"), "{dir}");
+    }
+
+    #[test]
+    fn private_dir_must_be_ours_and_closed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let sockets = dir.path().join("veloterm");
+        create_private_dir(&sockets).unwrap();
+        assert_eq!(std::fs::metadata(&sockets).unwrap().mode() & 0o777, 0o700);
+        // Already there and still private
+        create_private_dir(&sockets).unwrap();
+
+        // Opened up, as by someone who created it first
+        std::fs::set_permissions(&sockets, std::fs::Permissions::from_mode(0o777)).unwrap();
+        let error = create_private_dir(&sockets).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(!is_running(&sockets.join("daemon.sock")));
+
+        // A symlink to a private dir isn't one
+        std::fs::set_permissions(&sockets, std::fs::Permissions::from_mode(0o700)).unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&sockets, &link).unwrap();
+        assert!(check_private_dir(&link).is_err());
+        assert!(check_private_dir(&sockets).is_ok());
+    }
+
+    #[test]
+    fn peer_check_accepts_this_user() {
+        let (a, _b) = UnixStream::pair().unwrap();
+        assert_eq!(peer_uid(&a).unwrap(), unsafe { libc::getuid() });
+        check_peer(&a).unwrap();
+    }
+
+    #[test]
+    fn list_fails_without_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        assert!(!is_running(&socket));
+        assert!(list_panes(&socket).is_err());
+    }
+}
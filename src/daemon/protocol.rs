@@ -0,0 +1,223 @@
+// Wire format between the daemon and its clients: frames of a one-byte tag,
+// a big-endian u32 payload length and the payload. PTY bytes travel raw;
+// requests and replies carrying fields are JSON.
+//
+// The first frame on a connection picks what it is for: `List` gets one
+// `Panes` reply, `Spawn` and `Attach` make it the pane's stream (`Input`,
+// `Resize` and `Kill` up; `Output` and finally `Exited` down).
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// Largest payload accepted; anything bigger means a corrupt stream.
+pub const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+/// Start a shell in a new daemon pane.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnRequest {
+    pub shell: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub cwd: Option<String>,
+}
+
+/// A pane the daemon owns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneSummary {
+    pub id: u32,
+    /// The shell's process ID.
+    pub pid: Option<u32>,
+    /// Foreground process name, else the shell's.
+    pub command: String,
+    /// Whether a window is showing it.
+    pub attached: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// Client: start a shell in a new pane and attach to it.
+    Spawn(SpawnRequest),
+    /// Client: attach to a running pane at this size.
+    Attach { pane: u32, cols: u16, rows: u16 },
+    /// Client: list the daemon's panes.
+    List,
+    /// Client: keyboard input for the pane.
+    Input(Vec<u8>),
+    /// Client: the pane's grid size changed.
+    Resize { cols: u16, rows: u16 },
+    /// Client: end the pane's shell.
+    Kill,
+    /// Daemon: the connection now streams this pane.
+    Attached(PaneSummary),
+    /// Daemon: PTY output, starting with the replayed recent output.
+    Output(Vec<u8>),
+    /// Daemon: the pane's shell exited.
+    Exited,
+    /// Daemon: the window fell behind and missed output; reset the screen,
+    /// a fresh snapshot follows.
+    Resync,
+    /// Daemon: reply to `List`.
+    Panes(Vec<PaneSummary>),
+    /// Daemon: the request failed.
+    Error(String),
+}
+
+const SPAWN: u8 = 1;
+const ATTACH: u8 = 2;
+const LIST: u8 = 3;
+const INPUT: u8 = 4;
+const RESIZE: u8 = 5;
+const KILL: u8 = 6;
+const ATTACHED: u8 = 16;
+const OUTPUT: u8 = 17;
+const EXITED: u8 = 18;
+const PANES: u8 = 19;
+const ERROR: u8 = 20;
+const RESYNC: u8 = 21;
+
+#[derive(Serialize, Deserialize)]
+struct AttachRequest {
+    pane: u32,
+    cols: u16,
+    rows: u16,
+}
+
+fn json<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("protocol types serialize")
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Write one frame.
+pub fn write_frame(w: &mut impl Write, frame: &Frame) -> io::Result<()> {
+    let (tag, payload): (u8, Vec<u8>) = match frame {
+        Frame::Spawn(req) => (SPAWN, json(req)),
+        Frame::Attach { pane, cols, rows } => (
+            ATTACH,
+            json(&AttachRequest { pane: *pane, cols: *cols, rows: *rows }),
+        ),
+        Frame::List => (LIST, Vec::new()),
+        Frame::Input(bytes) => (INPUT, bytes.clone()),
+        Frame::Resize { cols, rows } => {
+            (RESIZE, [cols.to_be_bytes(), rows.to_be_bytes()].concat())
+        }
+        Frame::Kill => (KILL, Vec::new()),
+        Frame::Attached(pane) => (ATTACHED, json(pane)),
+        Frame::Output(bytes) => (OUTPUT, bytes.clone()),
+        Frame::Exited => (EXITED, Vec::new()),
+        Frame::Panes(panes) => (PANES, json(panes)),
+        Frame::Error(message) => (ERROR, message.as_bytes().to_vec()),
+        Frame::Resync => (RESYNC, Vec::new()),
+    };
+    // One write per frame so frames from different threads never interleave
+    let mut buf = Vec::with_capacity(5 + payload.len());
+    buf.push(tag);
+    buf.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    w.write_all(&buf)?;
+    w.flush()
+}
+
+/// Read one frame; None when the other side closed between frames.
+pub fn read_frame(r: &mut impl Read) -> io::Result<Option<Frame>> {
+    let mut tag = [0u8; 1];
+    loop {
+        match r.read(&mut tag) {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME {
+        return Err(invalid(format!("frame of {len} bytes exceeds the limit")));
+    }
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)?;
+    let parse_err = |e: serde_json::Error| invalid(e.to_string());
+    Ok(Some(match tag[0] {
+        SPAWN => Frame::Spawn(serde_json::from_slice(&payload).map_err(parse_err)?),
+        ATTACH => {
+            let req: AttachRequest = serde_json::from_slice(&payload).map_err(parse_err)?;
+            Frame::Attach { pane: req.pane, cols: req.cols, rows: req.rows }
+        }
+        LIST => Frame::List,
+        INPUT => Frame::Input(payload),
+        RESIZE if payload.len() == 4 => Frame::Resize {
+            cols: u16::from_be_bytes([payload[0], payload[1]]),
+            rows: u16::from_be_bytes([payload[2], payload[3]]),
+        },
+        KILL => Frame::Kill,
+        ATTACHED => Frame::Attached(serde_json::from_slice(&payload).map_err(parse_err)?),
+        OUTPUT => Frame::Output(payload),
+        EXITED => Frame::Exited,
+        PANES => Frame::Panes(serde_json::from_slice(&payload).map_err(parse_err)?),
+        ERROR => Frame::Error(String::from_utf8_lossy(&payload).into_owned()),
+        RESYNC => Frame::Resync,
+        other => return Err(invalid(format!("unknown frame type {other}"))),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(frame: Frame) -> Frame {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &frame).unwrap();
+        read_frame(&mut buf.as_slice()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let summary = PaneSummary { id: 3, pid: Some(42), command: "vim".into(), attached: false };
+        for frame in [
+            Frame::Spawn(SpawnRequest {
+                shell: "/bin/zsh".into(),
+                cols: 80,
+                rows: 24,
+                cwd: Some("/tmp".into()),
+            }),
+            Frame::Attach { pane: 3, cols: 120, rows: 40 },
+            Frame::List,
+            Frame::Input(b"ls\r".to_vec()),
+            Frame::Resize { cols: 300, rows: 70 },
+            Frame::Kill,
+            Frame::Attached(summary.clone()),
+            Frame::Output(b"\x1b[31mred\x1b[0m".to_vec()),
+            Frame::Exited,
+            Frame::Panes(vec![summary]),
+            Frame::Error("no pane 9".into()),
+            Frame::Resync,
+        ] {
+            assert_eq!(round_trip(frame.clone()), frame);
+        }
+    }
+
+    #[test]
+    fn reads_consecutive_frames_then_eof() {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &Frame::Output(b"a".to_vec())).unwrap();
+        write_frame(&mut buf, &Frame::Exited).unwrap();
+        let mut r = buf.as_slice();
+        assert_eq!(read_frame(&mut r).unwrap(), Some(Frame::Output(b"a".to_vec())));
+        assert_eq!(read_frame(&mut r).unwrap(), Some(Frame::Exited));
+        assert_eq!(read_frame(&mut r).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_corrupt_frames() {
+        // Unknown tag
+        assert!(read_frame(&mut [99u8, 0, 0, 0, 0].as_slice()).is_err());
+        // Oversized length
+        assert!(read_frame(&mut [OUTPUT, 0xff, 0xff, 0xff, 0xff].as_slice()).is_err());
+        // Truncated payload
+        assert!(read_frame(&mut [OUTPUT, 0, 0, 0, 9, b'x'].as_slice()).is_err());
+    }
+}
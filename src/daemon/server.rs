@@ -0,0 +1,542 @@
+// The daemon: owns shells in PTYs, keeps each pane's terminal state, and
+// streams panes to the windows attached to them. A pane has at most one
+// window; attaching from another takes it over.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use alacritty_terminal::event::{Event, EventListener};
+use alacritty_terminal::term::{Config as TermConfig, Term};
+use alacritty_terminal::vte::ansi;
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+
+use super::protocol::{read_frame, write_frame, Frame, PaneSummary, SpawnRequest};
+use crate::config::types::Config;
+use crate::pty::{PtyError, PtySession};
+use crate::terminal::{snapshot, TermSize};
+
+/// Records the title a pane's programs set; the daemon's term has no
+/// window to show it in.
+#[derive(Clone, Default)]
+struct TitleListener(Arc<Mutex<Option<String>>>);
+
+impl EventListener for TitleListener {
+    fn send_event(&self, event: Event) {
+        match event {
+            Event::Title(title) => *self.0.lock().unwrap() = Some(title),
+            Event::ResetTitle => *self.0.lock().unwrap() = None,
+            _ => {}
+        }
+    }
+}
+
+/// A pane's terminal state, kept by parsing its output, so an attaching
+/// window gets the screen and modes as they are rather than a replay of
+/// recent bytes.
+pub struct PaneScreen {
+    term: Term<TitleListener>,
+    processor: ansi::Processor,
+    title: TitleListener,
+    /// Scrollback sent on attach, in bytes.
+    history_bytes: usize,
+}
+
+impl PaneScreen {
+    pub fn new(cols: u16, rows: u16, scrollback: usize, history_bytes: usize) -> Self {
+        let size = TermSize {
+            columns: cols as usize,
+            screen_lines: rows as usize,
+        };
+        let config = TermConfig {
+            scrolling_history: scrollback,
+            ..TermConfig::default()
+        };
+        let title = TitleListener::default();
+        Self {
+            term: Term::new(config, &size, title.clone()),
+            processor: ansi::Processor::new(),
+            title,
+            history_bytes,
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.processor.advance(&mut self.term, bytes);
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.term.resize(TermSize {
+            columns: cols as usize,
+            screen_lines: rows as usize,
+        });
+    }
+
+    /// Bytes that redraw the pane in a new window: its title, then the
+    /// scrollback, screen and modes (see `snapshot`).
+    pub fn snapshot(&mut self) -> Vec<u8> {
+        let mut bytes = match self.title.0.lock().unwrap().as_deref() {
+            Some(title) => format!("\x1b]2;{title}\x07").into_bytes(),
+            None => Vec::new(),
+        };
+        bytes.extend(snapshot::snapshot(&mut self.term, self.history_bytes));
+        bytes
+    }
+}
+
+/// Frames queued for an attached window before it counts as stalled.
+const CLIENT_QUEUE_FRAMES: usize = 1024;
+
+/// Output side of a pane: its terminal state and the attached window.
+struct PaneOutput {
+    screen: PaneScreen,
+    /// The attached connection's ID and its outgoing frames.
+    client: Option<(u64, Sender<Frame>)>,
+    /// A connection detached for falling behind, due a fresh snapshot once
+    /// its queue drains.
+    resync: Option<u64>,
+    exited: bool,
+}
+
+struct DaemonPane {
+    id: u32,
+    pid: Option<u32>,
+    shell: String,
+    pty: Mutex<PtySession>,
+    output: Mutex<PaneOutput>,
+}
+
+impl DaemonPane {
+    fn summary(&self, attached: bool) -> PaneSummary {
+        let command = self
+            .pid
+            .and_then(crate::pty::foreground_process_name)
+            .unwrap_or_else(|| self.shell.clone());
+        PaneSummary {
+            id: self.id,
+            pid: self.pid,
+            command,
+            attached,
+        }
+    }
+
+    /// Make connection `client` the pane's window. The snapshot is taken
+    /// and queued under the same lock as new output, so none is lost or
+    /// repeated.
+    fn attach(&self, client: u64, cols: u16, rows: u16) -> Result<Receiver<Frame>, String> {
+        let mut output = self.output.lock().unwrap();
+        if output.exited {
+            return Err(format!("pane {} has exited", self.id));
+        }
+        // Resize first so full-screen programs redraw for the new window,
+        // and the snapshot fits it
+        self.resize(&mut output, cols, rows);
+        let (tx, rx) = crossbeam_channel::bounded(CLIENT_QUEUE_FRAMES);
+        let _ = tx.send(Frame::Attached(self.summary(true)));
+        let _ = tx.send(Frame::Output(output.screen.snapshot()));
+        // A window already attached loses the pane
+        output.client = Some((client, tx));
+        output.resync = None;
+        Ok(rx)
+    }
+
+    /// Reattach connection `client`, detached for falling behind, with a
+    /// fresh snapshot. None when it wasn't, or the pane moved on.
+    fn resync(&self, client: u64) -> Option<Receiver<Frame>> {
+        let mut output = self.output.lock().unwrap();
+        if output.resync != Some(client) || output.exited {
+            return None;
+        }
+        output.resync = None;
+        let (tx, rx) = crossbeam_channel::bounded(CLIENT_QUEUE_FRAMES);
+        let _ = tx.send(Frame::Resync);
+        let _ = tx.send(Frame::Output(output.screen.snapshot()));
+        output.client = Some((client, tx));
+        log::info!("Daemon pane {} resynced", self.id);
+        Some(rx)
+    }
+
+    /// Forget connection `client` if it is still the pane's window.
+    fn detach(&self, client: u64) {
+        let mut output = self.output.lock().unwrap();
+        if output.resync == Some(client) {
+            output.resync = None;
+        }
+        if output.client.as_ref().is_some_and(|(id, _)| *id == client) {
+            output.client = None;
+            log::info!("Daemon pane {} detached", self.id);
+        }
+    }
+
+    fn resize(&self, output: &mut PaneOutput, cols: u16, rows: u16) {
+        if let Err(e) = self.pty.lock().unwrap().resize(cols, rows) {
+            log::warn!("Daemon pane {}: {e}", self.id);
+        }
+        output.screen.resize(cols, rows);
+    }
+
+    fn on_output(&self, bytes: &[u8]) {
+        let mut output = self.output.lock().unwrap();
+        output.screen.feed(bytes);
+        let Some((client, tx)) = &output.client else {
+            return;
+        };
+        if let Err(TrySendError::Full(_)) = tx.try_send(Frame::Output(bytes.to_vec())) {
+            // A stalled window: stop queueing for it. Once it drains what
+            // it has, it gets the screen afresh (`resync`).
+            log::warn!("Daemon pane {}: window fell behind, detaching it", self.id);
+            output.resync = Some(*client);
+            output.client = None;
+        }
+    }
+
+    fn on_exit(&self) {
+        let mut output = self.output.lock().unwrap();
+        output.exited = true;
+        if let Some((_, tx)) = output.client.take() {
+            let _ = tx.send(Frame::Exited);
+        }
+        drop(output);
+        // Reap the shell; its PTY usually closes just before it exits
+        let mut pty = self.pty.lock().unwrap();
+        for _ in 0..20 {
+            if pty.try_exit_status().is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// The daemon's panes and the config it spawns shells with.
+pub struct Daemon {
+    config: Config,
+    panes: Mutex<HashMap<u32, Arc<DaemonPane>>>,
+    next_pane: AtomicU32,
+    next_client: AtomicU64,
+}
+
+impl Daemon {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            panes: Mutex::new(HashMap::new()),
+            next_pane: AtomicU32::new(1),
+            next_client: AtomicU64::new(1),
+        }
+    }
+
+    /// Listen on `socket` and serve clients until the process ends.
+    pub fn run(self, socket: &Path) -> io::Result<()> {
+        let listener = bind(socket)?;
+        log::info!("Daemon listening on {}", socket.display());
+        let daemon = Arc::new(self);
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let daemon = Arc::clone(&daemon);
+                    thread::spawn(move || daemon.serve(stream));
+                }
+                Err(e) => log::warn!("Daemon accept failed: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// The daemon's panes, in creation order.
+    pub fn list(&self) -> Vec<PaneSummary> {
+        let mut panes: Vec<PaneSummary> = self
+            .panes
+            .lock()
+            .unwrap()
+            .values()
+            .map(|pane| pane.summary(pane.output.lock().unwrap().client.is_some()))
+            .collect();
+        panes.sort_by_key(|p| p.id);
+        panes
+    }
+
+    fn spawn(self: &Arc<Self>, request: &SpawnRequest) -> Result<Arc<DaemonPane>, PtyError> {
+        let pty = PtySession::new_with_cwd(
+            &request.shell,
+            request.cols,
+            request.rows,
+            request.cwd.as_deref(),
+            Some(&self.config.shell),
+            Some((&self.config.colors.theme, &self.config.colors)),
+        )?;
+        let id = self.next_pane.fetch_add(1, Ordering::Relaxed);
+        let output_rx = pty.reader_rx.clone();
+        let pane = Arc::new(DaemonPane {
+            id,
+            pid: pty.child_pid(),
+            shell: crate::pty::basename_from_path(&request.shell).to_string(),
+            pty: Mutex::new(pty),
+            output: Mutex::new(PaneOutput {
+                screen: PaneScreen::new(
+                    request.cols,
+                    request.rows,
+                    self.config.scrollback.lines as usize,
+                    self.config.daemon.replay_bytes,
+                ),
+                client: None,
+                resync: None,
+                exited: false,
+            }),
+        });
+        self.panes.lock().unwrap().insert(id, Arc::clone(&pane));
+        log::info!(
+            "Daemon pane {id}: {} ({}x{})",
+            request.shell,
+            request.cols,
+            request.rows
+        );
+
+        let daemon = Arc::clone(self);
+        let pump = Arc::clone(&pane);
+        thread::spawn(move || {
+            // Ends when the PTY reader stops: the shell exited
+            for bytes in output_rx {
+                pump.on_output(&bytes);
+            }
+            pump.on_exit();
+            daemon.panes.lock().unwrap().remove(&id);
+            log::info!("Daemon pane {id} exited");
+        });
+        Ok(pane)
+    }
+
+    /// Handle one connection: answer `List`, or stream the pane named by
+    /// `Spawn`/`Attach` until the window closes it.
+    fn serve(self: Arc<Self>, stream: UnixStream) {
+        let Ok(read_half) = stream.try_clone() else {
+            return;
+        };
+        let mut reader = BufReader::new(read_half);
+        let mut writer = stream;
+        let client = self.next_client.fetch_add(1, Ordering::Relaxed);
+
+        let attached = match read_frame(&mut reader) {
+            Ok(Some(Frame::List)) => {
+                let _ = write_frame(&mut writer, &Frame::Panes(self.list()));
+                return;
+            }
+            Ok(Some(Frame::Spawn(request))) => self
+                .spawn(&request)
+                .map_err(|e| e.to_string())
+                .and_then(|pane| {
+                    let rx = pane.attach(client, request.cols, request.rows)?;
+                    Ok((pane, rx))
+                }),
+            Ok(Some(Frame::Attach { pane, cols, rows })) => {
+                let found = self.panes.lock().unwrap().get(&pane).cloned();
+                match found {
+                    Some(pane) => pane.attach(client, cols, rows).map(|rx| (pane, rx)),
+                    None => Err(format!("no pane {pane}")),
+                }
+            }
+            Ok(Some(other)) => Err(format!("unexpected request {other:?}")),
+            Ok(None) | Err(_) => return,
+        };
+        let (pane, rx) = match attached {
+            Ok(attached) => attached,
+            Err(e) => {
+                let _ = write_frame(&mut writer, &Frame::Error(e));
+                return;
+            }
+        };
+
+        // Frames go out on their own thread; when the pane exits or another
+        // window takes it the channel closes, and so does the connection
+        let writer_pane = Arc::clone(&pane);
+        let writer_thread = thread::spawn(move || {
+            let mut rx = rx;
+            'frames: loop {
+                for frame in &rx {
+                    if write_frame(&mut writer, &frame).is_err() {
+                        break 'frames;
+                    }
+                }
+                match writer_pane.resync(client) {
+                    Some(next) => rx = next,
+                    None => break,
+                }
+            }
+            let _ = writer.shutdown(std::net::Shutdown::Both);
+        });
+
+        while let Ok(Some(frame)) = read_frame(&mut reader) {
+            match frame {
+                Frame::Input(bytes) => {
+                    if let Err(e) = pane.pty.lock().unwrap().write(&bytes) {
+                        log::warn!("Daemon pane {}: write failed: {e}", pane.id);
+                    }
+                }
+                Frame::Resize { cols, rows } => {
+                    pane.resize(&mut pane.output.lock().unwrap(), cols, rows)
+                }
+                Frame::Kill => pane.pty.lock().unwrap().kill(),
+                _ => {}
+            }
+        }
+        pane.detach(client);
+        let _ = writer_thread.join();
+    }
+}
+
+/// Listen on `socket` in a directory only this user can enter, replacing a
+/// socket left behind by a daemon that died.
+fn bind(socket: &Path) -> io::Result<UnixListener> {
+    if let Some(dir) = socket.parent() {
+        super::create_private_dir(dir)?;
+    }
+    if super::is_running(socket) {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("a daemon is already listening on {}", socket.display()),
+        ));
+    }
+    let _ = std::fs::remove_file(socket);
+    let listener = UnixListener::bind(socket)?;
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{list_panes, DaemonLink};
+
+    #[test]
+    fn screen_snapshot_carries_title_and_modes() {
+        let mut screen = PaneScreen::new(20, 4, 100, 1024);
+        screen.feed(b"\x1b]2;htop\x07$ htop\r\n\x1b[?1049h\x1b[?1h\x1b[?2004h\x1b[Hload 0.5");
+        let bytes = screen.snapshot();
+        let mut window = crate::terminal::Terminal::new(20, 4, 100);
+        window.feed(&bytes);
+        assert_eq!(window.shell_state().title.as_deref(), Some("htop"));
+        assert!(window.is_alt_screen());
+        assert!(window.is_bracketed_paste_enabled());
+        assert_eq!(window.line_text(0).trim_end(), "load 0.5");
+        // Modes set long ago survive any amount of later output
+        for _ in 0..1000 {
+            screen.feed(b"\x1b[Hload 0.7");
+        }
+        let mut window = crate::terminal::Terminal::new(20, 4, 100);
+        window.feed(&screen.snapshot());
+        assert!(window.is_alt_screen());
+        assert_eq!(window.line_text(0).trim_end(), "load 0.7");
+    }
+
+    fn start_daemon(dir: &Path) -> std::path::PathBuf {
+        // bind creates the private dir, as for the runtime dir
+        let socket = dir.join("veloterm").join("daemon.sock");
+        let listener = bind(&socket).unwrap();
+        let daemon = Arc::new(Daemon::new(Config::default()));
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let daemon = Arc::clone(&daemon);
+                thread::spawn(move || daemon.serve(stream));
+            }
+        });
+        socket
+    }
+
+    fn read_until(session: &PtySession, marker: &str) -> String {
+        let mut seen = Vec::new();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while std::time::Instant::now() < deadline {
+            if let Ok(chunk) = session.reader_rx.recv_timeout(Duration::from_millis(100)) {
+                seen.extend_from_slice(&chunk);
+                if String::from_utf8_lossy(&seen).contains(marker) {
+                    break;
+                }
+            }
+        }
+        String::from_utf8_lossy(&seen).into_owned()
+    }
+
+    #[test]
+    fn shell_survives_detach_and_replays_on_attach() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = start_daemon(dir.path());
+        assert!(bind(&socket).is_err(), "second daemon on the same socket");
+
+        let mut session = PtySession::spawn_in_daemon(&socket, "/bin/sh", 80, 24, None).unwrap();
+        session.write(b"echo DAEMON_$((6 * 7))\n").unwrap();
+        assert!(read_until(&session, "DAEMON_42").contains("DAEMON_42"));
+        let panes = list_panes(&socket).unwrap();
+        assert_eq!(panes.len(), 1);
+        assert!(panes[0].attached);
+
+        // Closing the window detaches; the shell keeps running
+        drop(session);
+        let deadline = std::time::Instant::now() + Duration::from_secs(3);
+        while list_panes(&socket).unwrap()[0].attached && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        let pane = list_panes(&socket).unwrap()[0].clone();
+        assert!(!pane.attached);
+
+        let session = PtySession::attach_daemon(&socket, pane.id, 100, 30).unwrap();
+        assert!(session.is_daemon());
+        assert!(read_until(&session, "DAEMON_42").contains("DAEMON_42"));
+        session.close();
+        let deadline = std::time::Instant::now() + Duration::from_secs(3);
+        while !list_panes(&socket).unwrap().is_empty() && std::time::Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(list_panes(&socket).unwrap().is_empty());
+    }
+
+    #[test]
+    fn stalled_window_is_detached_then_resynced() {
+        let daemon = Arc::new(Daemon::new(Config::default()));
+        // cat prints nothing of its own to race with the test output
+        let request = SpawnRequest { shell: "/bin/cat".into(), cols: 40, rows: 10, cwd: None };
+        let pane = daemon.spawn(&request).unwrap();
+        let rx = pane.attach(1, 40, 10).unwrap();
+        // The window reads nothing while output keeps coming
+        for _ in 0..CLIENT_QUEUE_FRAMES {
+            pane.on_output(b"x");
+        }
+        pane.on_output(b"\r\nLATEST");
+        assert!(pane.output.lock().unwrap().client.is_none());
+        assert_eq!(rx.len(), CLIENT_QUEUE_FRAMES);
+        assert!(pane.resync(2).is_none(), "only the stalled window resyncs");
+
+        let rx = pane.resync(1).unwrap();
+        assert_eq!(rx.recv().unwrap(), Frame::Resync);
+        let Frame::Output(snapshot) = rx.recv().unwrap() else {
+            panic!("expected a snapshot");
+        };
+        assert!(String::from_utf8_lossy(&snapshot).contains("LATEST"));
+        pane.on_output(b"more");
+        assert_eq!(rx.recv().unwrap(), Frame::Output(b"more".to_vec()));
+        assert!(pane.resync(1).is_none());
+        pane.pty.lock().unwrap().kill();
+    }
+
+    #[test]
+    fn attach_to_unknown_pane_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = start_daemon(dir.path());
+        let err = DaemonLink::connect(
+            &socket,
+            &Frame::Attach {
+                pane: 99,
+                cols: 80,
+                rows: 24,
+            },
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("no pane 99"));
+    }
+}
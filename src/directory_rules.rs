@@ -0,0 +1,178 @@
+// Per-directory rules: `[[directory_rules]]` map working-directory prefixes
+// (reported via OSC 7) to a colored badge on the pane and its tab title, and
+// to a theme for the pane.
+
+use crate::config::theme::{from_hex, Color, TerminalTheme};
+use crate::config::types::{ColorsConfig, DirectoryRule};
+
+/// Badge shown on a pane whose cwd matched a directory rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaneBadge {
+    pub text: String,
+    pub color: Color,
+}
+
+impl PaneBadge {
+    /// Badge for a validated rule.
+    pub fn from_rule(rule: &DirectoryRule) -> Self {
+        Self {
+            text: rule.badge.clone(),
+            color: from_hex(&rule.color),
+        }
+    }
+}
+
+/// Expand a leading `~` in a path to `home`.
+pub(crate) fn expand_home(path: &str, home: Option<&str>) -> String {
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{rest}", home.trim_end_matches('/'))
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Whether `cwd` is `prefix` or a directory below it.
+fn is_under(cwd: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    if prefix.is_empty() {
+        // "/" matches everything
+        return cwd.starts_with('/');
+    }
+    cwd.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// The rule whose path is the longest prefix of `cwd`, matching only at path
+/// component boundaries (`~/work/prod` does not match `~/work/production`).
+pub fn match_rule<'a>(
+    rules: &'a [DirectoryRule],
+    cwd: &str,
+    home: Option<&str>,
+) -> Option<&'a DirectoryRule> {
+    rules
+        .iter()
+        .map(|rule| (expand_home(&rule.path, home), rule))
+        .filter(|(path, _)| is_under(cwd, path))
+        .max_by_key(|(path, _)| path.trim_end_matches('/').len())
+        .map(|(_, rule)| rule)
+}
+
+/// Badge for `cwd` under the configured rules, if any.
+pub fn badge_for_cwd(rules: &[DirectoryRule], cwd: &str) -> Option<PaneBadge> {
+    let home = std::env::var("HOME").ok();
+    match_rule(rules, cwd, home.as_deref())
+        .filter(|rule| !rule.badge.is_empty())
+        .map(PaneBadge::from_rule)
+}
+
+/// Theme for a pane whose cwd matched `rule`, or None if the rule leaves
+/// colors alone.
+pub fn rule_theme(rule: &DirectoryRule, colors: &ColorsConfig) -> Option<TerminalTheme> {
+    if rule.theme.is_none() && rule.background.is_none() {
+        return None;
+    }
+    let mut theme = colors.resolve_theme(rule.theme.as_deref().unwrap_or(&colors.theme))?;
+    if let Some(background) = &rule.background {
+        theme.bg_deep = from_hex(background);
+    }
+    Some(theme)
+}
+
+/// Theme for `cwd` under the configured rules, if any.
+pub fn theme_for_cwd(rules: &[DirectoryRule], cwd: &str, colors: &ColorsConfig) -> Option<TerminalTheme> {
+    let home = std::env::var("HOME").ok();
+    match_rule(rules, cwd, home.as_deref()).and_then(|rule| rule_theme(rule, colors))
+}
+
+/// Tab title with the badge text prefixed ("PROD · vim").
+pub fn badged_title(badge: Option<&PaneBadge>, title: &str) -> String {
+    match badge {
+        Some(badge) => format!("{} · {title}", badge.text),
+        None => title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: &str, badge: &str) -> DirectoryRule {
+        DirectoryRule {
+            path: path.to_string(),
+            badge: badge.to_string(),
+            color: "#E5484D".to_string(),
+            theme: None,
+            background: None,
+        }
+    }
+
+    fn rules() -> Vec<DirectoryRule> {
+        vec![
+            rule("~/work", "WORK"),
+            rule("~/work/prod-infra", "PROD"),
+            rule("/etc", "ETC"),
+        ]
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let rules = rules();
+        let hit = match_rule(&rules, "/home/me/work/prod-infra/tf", Some("/home/me"));
+        assert_eq!(hit.unwrap().badge, "PROD");
+        let hit = match_rule(&rules, "/home/me/work/docs", Some("/home/me"));
+        assert_eq!(hit.unwrap().badge, "WORK");
+    }
+
+    #[test]
+    fn exact_directory_matches() {
+        let rules = rules();
+        assert_eq!(match_rule(&rules, "/etc", None).unwrap().badge, "ETC");
+    }
+
+    #[test]
+    fn prefix_must_end_at_component_boundary() {
+        let rules = rules();
+        assert!(match_rule(&rules, "/etcetera", None).is_none());
+        let hit = match_rule(&rules, "/home/me/work/prod-infra2", Some("/home/me"));
+        assert_eq!(hit.unwrap().badge, "WORK");
+    }
+
+    #[test]
+    fn tilde_without_home_is_literal() {
+        let rules = rules();
+        assert!(match_rule(&rules, "/home/me/work", None).is_none());
+    }
+
+    #[test]
+    fn trailing_slash_in_rule_is_ignored() {
+        let rules = vec![rule("/srv/", "SRV")];
+        assert!(match_rule(&rules, "/srv/app", None).is_some());
+    }
+
+    #[test]
+    fn badge_parses_color() {
+        let badge = PaneBadge::from_rule(&rule("/", "ROOT"));
+        assert_eq!(badge.text, "ROOT");
+        assert!((badge.color.r - 0xE5 as f32 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rule_theme_applies_theme_and_background() {
+        let colors = ColorsConfig::default();
+        assert!(rule_theme(&rule("/", "ROOT"), &colors).is_none());
+        let mut themed = rule("/", "");
+        themed.theme = Some("midnight".to_string());
+        themed.background = Some("#102030".to_string());
+        let theme = rule_theme(&themed, &colors).unwrap();
+        assert_eq!(theme.name, TerminalTheme::from_name("midnight").unwrap().name);
+        assert_eq!(theme.bg_deep, from_hex("#102030"));
+    }
+
+    #[test]
+    fn badged_title_prefixes_text() {
+        let badge = PaneBadge::from_rule(&rule("/", "PROD"));
+        assert_eq!(badged_title(Some(&badge), "vim"), "PROD · vim");
+        assert_eq!(badged_title(None, "vim"), "vim");
+    }
+}
@@ -0,0 +1,266 @@
+// Git status segment: branch name and dirty flag for a pane's cwd. Reads run
+// on a worker thread and are cached per directory, so rendering never waits
+// on git.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Minimum gap between two reads of the same directory, even when invalidated
+/// (a burst of prompts should not spawn a burst of `git status` scans).
+pub const MIN_REFRESH_GAP: Duration = Duration::from_millis(500);
+
+/// Cached directories kept before the least recently read ones are dropped.
+const MAX_ENTRIES: usize = 64;
+
+/// Branch and working-tree state for one repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSegment {
+    /// Branch name, or a short commit id when HEAD is detached.
+    pub branch: String,
+    /// True if there are staged, unstaged or untracked changes.
+    pub dirty: bool,
+}
+
+impl GitSegment {
+    /// Read the segment for the repository containing `cwd`, if any.
+    /// Blocking — call from a worker thread.
+    pub fn read(cwd: &Path) -> Option<Self> {
+        let repo = git2::Repository::discover(cwd).ok()?;
+        if repo.is_bare() {
+            return None;
+        }
+        let branch = match repo.head() {
+            Ok(head) if head.is_branch() => head.shorthand().unwrap_or("HEAD").to_string(),
+            Ok(head) => head
+                .target()
+                .map(|oid| oid.to_string()[..7].to_string())
+                .unwrap_or_else(|| "HEAD".to_string()),
+            // Unborn branch (no commits yet): HEAD still names it symbolically
+            Err(_) => repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|r| r.symbolic_target().map(|t| t.trim_start_matches("refs/heads/").to_string()))
+                .unwrap_or_else(|| "HEAD".to_string()),
+        };
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(true)
+            .include_ignored(false)
+            .exclude_submodules(true);
+        let dirty = repo
+            .statuses(Some(&mut opts))
+            .is_ok_and(|statuses| !statuses.is_empty());
+        Some(Self { branch, dirty })
+    }
+
+    /// Status bar text: branch name with a `*` when dirty.
+    pub fn label(&self) -> String {
+        if self.dirty {
+            format!("{}*", self.branch)
+        } else {
+            self.branch.clone()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    segment: Option<GitSegment>,
+    fetched_at: Option<Instant>,
+    stale: bool,
+    pending: bool,
+}
+
+/// Per-directory cache of git segments with refresh rate limiting.
+#[derive(Debug)]
+pub struct GitSegmentCache {
+    entries: HashMap<String, Entry>,
+    ttl: Duration,
+}
+
+impl GitSegmentCache {
+    /// Create a cache whose entries are re-read after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Change the refresh interval.
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    /// Last known segment for `cwd` (None outside a repository or before the
+    /// first read completes).
+    pub fn get(&self, cwd: &str) -> Option<&GitSegment> {
+        self.entries.get(cwd).and_then(|e| e.segment.as_ref())
+    }
+
+    /// Mark `cwd` for re-reading (cwd change, command finished).
+    pub fn invalidate(&mut self, cwd: &str) {
+        if let Some(entry) = self.entries.get_mut(cwd) {
+            entry.stale = true;
+        }
+    }
+
+    /// Whether `cwd` should be read now. Marks it pending when it should, so
+    /// at most one read per directory is in flight.
+    pub fn begin_refresh(&mut self, cwd: &str, now: Instant) -> bool {
+        if !self.entries.contains_key(cwd) {
+            self.evict_if_full();
+        }
+        let ttl = self.ttl;
+        let entry = self.entries.entry(cwd.to_string()).or_insert(Entry {
+            segment: None,
+            fetched_at: None,
+            stale: true,
+            pending: false,
+        });
+        if entry.pending {
+            return false;
+        }
+        let due = match entry.fetched_at {
+            None => true,
+            Some(at) => {
+                let age = now.saturating_duration_since(at);
+                age >= MIN_REFRESH_GAP && (entry.stale || age >= ttl)
+            }
+        };
+        if due {
+            entry.pending = true;
+        }
+        due
+    }
+
+    /// Store a finished read. Returns true if the visible segment changed.
+    pub fn complete(&mut self, cwd: &str, segment: Option<GitSegment>, now: Instant) -> bool {
+        let Some(entry) = self.entries.get_mut(cwd) else {
+            return false;
+        };
+        let changed = entry.segment != segment;
+        entry.segment = segment;
+        entry.fetched_at = Some(now);
+        entry.stale = false;
+        entry.pending = false;
+        changed
+    }
+
+    /// Drop all cached entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn evict_if_full(&mut self) {
+        if self.entries.len() < MAX_ENTRIES {
+            return;
+        }
+        let oldest = self
+            .entries
+            .iter()
+            .filter(|(_, e)| !e.pending)
+            .min_by_key(|(_, e)| e.fetched_at)
+            .map(|(cwd, _)| cwd.clone());
+        if let Some(cwd) = oldest {
+            self.entries.remove(&cwd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(branch: &str, dirty: bool) -> GitSegment {
+        GitSegment {
+            branch: branch.to_string(),
+            dirty,
+        }
+    }
+
+    // ── Reading ─────────────────────────────────────────────────────
+
+    #[test]
+    fn read_outside_repo_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(GitSegment::read(dir.path()).is_none());
+    }
+
+    #[test]
+    fn read_unborn_branch_and_dirty_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/trunk").unwrap();
+        let clean = GitSegment::read(dir.path()).unwrap();
+        assert_eq!(clean, segment("trunk", false));
+
+        std::fs::write(dir.path().join("new.txt"), "hello").unwrap();
+        let dirty = GitSegment::read(dir.path()).unwrap();
+        assert!(dirty.dirty);
+    }
+
+    #[test]
+    fn label_marks_dirty() {
+        assert_eq!(segment("main", false).label(), "main");
+        assert_eq!(segment("main", true).label(), "main*");
+    }
+
+    // ── Cache ───────────────────────────────────────────────────────
+
+    #[test]
+    fn first_request_is_due_once() {
+        let mut cache = GitSegmentCache::new(Duration::from_secs(5));
+        let now = Instant::now();
+        assert!(cache.begin_refresh("/repo", now));
+        // Already in flight
+        assert!(!cache.begin_refresh("/repo", now));
+    }
+
+    #[test]
+    fn complete_stores_segment_and_reports_change() {
+        let mut cache = GitSegmentCache::new(Duration::from_secs(5));
+        let now = Instant::now();
+        cache.begin_refresh("/repo", now);
+        assert!(cache.complete("/repo", Some(segment("main", false)), now));
+        assert_eq!(cache.get("/repo"), Some(&segment("main", false)));
+
+        cache.invalidate("/repo");
+        let later = now + Duration::from_secs(1);
+        assert!(cache.begin_refresh("/repo", later));
+        assert!(!cache.complete("/repo", Some(segment("main", false)), later));
+    }
+
+    #[test]
+    fn fresh_entry_waits_for_ttl() {
+        let mut cache = GitSegmentCache::new(Duration::from_secs(5));
+        let now = Instant::now();
+        cache.begin_refresh("/repo", now);
+        cache.complete("/repo", None, now);
+        assert!(!cache.begin_refresh("/repo", now + Duration::from_secs(1)));
+        assert!(cache.begin_refresh("/repo", now + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn invalidation_is_rate_limited() {
+        let mut cache = GitSegmentCache::new(Duration::from_secs(5));
+        let now = Instant::now();
+        cache.begin_refresh("/repo", now);
+        cache.complete("/repo", None, now);
+        cache.invalidate("/repo");
+        assert!(!cache.begin_refresh("/repo", now + Duration::from_millis(100)));
+        assert!(cache.begin_refresh("/repo", now + MIN_REFRESH_GAP));
+    }
+
+    #[test]
+    fn cache_evicts_when_full() {
+        let mut cache = GitSegmentCache::new(Duration::from_secs(5));
+        let now = Instant::now();
+        for i in 0..MAX_ENTRIES + 4 {
+            let cwd = format!("/repo{i}");
+            cache.begin_refresh(&cwd, now);
+            cache.complete(&cwd, None, now + Duration::from_millis(i as u64));
+        }
+        assert!(cache.entries.len() <= MAX_ENTRIES);
+    }
+}
@@ -0,0 +1,205 @@
+// Help pane: a read-only markdown page listing every action with its current
+// keys, and every config key, built at runtime from the action registry, the
+// live keymap and the commented default config.
+
+use crate::config::types::Config;
+use crate::input::actions::{Action, ACTIONS};
+use crate::input::cheatsheet::display_combo;
+use crate::input::keybindings::Keymap;
+use crate::input::TabCommand;
+
+/// Title shown in the preview header.
+pub const HELP_TITLE: &str = "VeloTerm Help";
+
+/// One documented config key, taken from `Config::print_default()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigKeyDoc {
+    /// Dotted path, e.g. "font.size".
+    pub path: String,
+    /// The comment lines above the key, joined.
+    pub description: String,
+}
+
+/// Every key in the commented default config, in file order. Keys that are
+/// only shown commented out (optional settings) are included.
+pub fn config_key_docs() -> Vec<ConfigKeyDoc> {
+    let mut docs = Vec::new();
+    let mut section = String::new();
+    let mut comments: Vec<&str> = Vec::new();
+    let default = Config::print_default();
+    for line in default.lines().map(str::trim) {
+        if line.is_empty() {
+            comments.clear();
+        } else if let Some(name) = table_header(line) {
+            section = name.to_string();
+            comments.clear();
+        } else if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim();
+            // A commented-out table header starts an optional section
+            if let Some(name) = table_header(comment) {
+                section = name.to_string();
+                continue;
+            }
+            match setting_key(comment) {
+                Some(key) if !section.is_empty() => {
+                    docs.push(key_doc(&section, key, &comments));
+                    comments.clear();
+                }
+                _ => comments.push(comment),
+            }
+        } else if let Some(key) = setting_key(line) {
+            if !section.is_empty() {
+                docs.push(key_doc(&section, key, &comments));
+            }
+            comments.clear();
+        }
+    }
+    docs
+}
+
+fn key_doc(section: &str, key: &str, comments: &[&str]) -> ConfigKeyDoc {
+    ConfigKeyDoc {
+        path: format!("{section}.{key}"),
+        description: comments.join(" "),
+    }
+}
+
+/// The table name of a `[table]` or `[[array]]` header line.
+fn table_header(line: &str) -> Option<&str> {
+    let name = line.strip_prefix('[')?.strip_suffix(']')?;
+    Some(name.trim_start_matches('[').trim_end_matches(']'))
+}
+
+/// The key of a `key = value` line, if `line` is one.
+fn setting_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once(" = ")?;
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    valid.then_some(key)
+}
+
+/// Look up a dotted path in a TOML table.
+fn lookup<'a>(table: &'a toml::Table, path: &str) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_once('.').unwrap_or((path, ""));
+    let value = table.get(first)?;
+    if rest.is_empty() {
+        return Some(value);
+    }
+    lookup(value.as_table()?, rest)
+}
+
+/// Display form of every combo bound to `action`, joined by " / ".
+fn bound_keys(keymap: &Keymap, action: Action) -> String {
+    let keys: Vec<String> = keymap.combos_for(action).map(display_combo).collect();
+    if keys.is_empty() {
+        "unbound".to_string()
+    } else {
+        keys.join(" / ")
+    }
+}
+
+/// Build the help page for the running keymap and config.
+pub fn help_markdown(keymap: &Keymap, config: &Config) -> String {
+    let mut out = format!("# {HELP_TITLE}\n\n");
+    out.push_str("Generated from the running configuration. Press Escape to close.\n\n");
+
+    out.push_str("## Actions\n\n");
+    out.push_str("Run any action from the command palette, or bind it under `[keys]`, e.g. ");
+    out.push_str("`\"ctrl+shift+d\" = \"split_vertical\"`; `\"none\"` removes a binding.\n\n");
+    for spec in ACTIONS {
+        out.push_str(&format!(
+            "- **{}** `{}` ({}): {}\n",
+            spec.title,
+            spec.name,
+            bound_keys(keymap, spec.action),
+            spec.description
+        ));
+    }
+    for n in 1..=9 {
+        let action = Action::Tab(TabCommand::SelectTab(n - 1));
+        out.push_str(&format!("- `select_tab_{n}` ({})\n", bound_keys(keymap, action)));
+    }
+
+    out.push_str("\n## Config keys\n\n");
+    let current = config.to_toml_table().unwrap_or_default();
+    let defaults = Config::default().to_toml_table().unwrap_or_default();
+    for doc in config_key_docs() {
+        let value = match (lookup(&current, &doc.path), lookup(&defaults, &doc.path)) {
+            (Some(value), Some(default)) if value != default => {
+                format!("`{value}` (default `{default}`)")
+            }
+            (Some(value), _) => format!("`{value}`"),
+            (None, _) => "unset".to_string(),
+        };
+        out.push_str(&format!("- `{}` = {value}", doc.path));
+        if !doc.description.is_empty() {
+            out.push_str(&format!(": {}", doc.description));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // ── Config keys ─────────────────────────────────────────────────
+
+    #[test]
+    fn config_keys_belong_to_real_sections() {
+        let docs = config_key_docs();
+        let defaults = Config::default().to_toml_table().unwrap();
+        for doc in &docs {
+            let (section, _) = doc.path.split_once('.').unwrap();
+            assert!(lookup(&defaults, section).is_some(), "{} has no section", doc.path);
+        }
+        let size = docs.iter().find(|d| d.path == "font.size").unwrap();
+        assert_eq!(size.description, "Font size in points");
+        // Optional keys shown commented out are listed too
+        assert!(docs.iter().any(|d| d.path == "accessibility.line_view_command"));
+        assert!(docs.iter().any(|d| d.path == "colors.theme_ansi.light.bright_yellow"));
+    }
+
+    #[test]
+    fn setting_key_ignores_prose() {
+        assert_eq!(setting_key("size = 13.0"), Some("size"));
+        assert_eq!(setting_key("Use \"x\" = y for z"), None);
+        assert_eq!(setting_key("Font size in points"), None);
+    }
+
+    // ── Page ────────────────────────────────────────────────────────
+
+    #[test]
+    fn help_lists_live_bindings() {
+        let keymap = Keymap::from_config(&HashMap::from([
+            ("ctrl+alt+x".to_string(), "split_vertical".to_string()),
+            ("ctrl+shift+z".to_string(), "none".to_string()),
+        ]));
+        let page = help_markdown(&keymap, &Config::default());
+        assert!(page.contains("- **Split Pane Right** `split_vertical` (Ctrl+Shift+D / Ctrl+Alt+X): "));
+        assert!(page.contains("`zoom_pane` (unbound)"));
+        assert!(page.contains("- `select_tab_9` (Ctrl+Shift+9"));
+    }
+
+    #[test]
+    fn help_lists_every_action() {
+        let page = help_markdown(&Keymap::default(), &Config::default());
+        for spec in ACTIONS {
+            assert!(page.contains(&format!("**{}** `{}`", spec.title, spec.name)), "{} missing", spec.name);
+        }
+    }
+
+    #[test]
+    fn help_marks_changed_config_values() {
+        let mut config = Config::default();
+        config.font.size = 16.0;
+        let page = help_markdown(&Keymap::default(), &config);
+        assert!(page.contains("- `font.size` = `16.0` (default `13.0`): Font size in points\n"));
+        assert!(page.contains("- `scrollback.lines` = `10000`"));
+        assert!(page.contains("- `accessibility.line_view_command` = unset"));
+    }
+}
@@ -0,0 +1,741 @@
+// Action registry: every command VeloTerm can run, with its config name,
+// palette title, description and default keys. Key bindings, the command
+// palette and the help pane are all built from this one table.
+
+use super::{AppCommand, EditCommand, OverlayCommand, PaneCommand, SearchCommand};
+use super::{ShellCommand, TabCommand, ViewCommand};
+use super::selection::CopyMode;
+use crate::link::hints::HintAction;
+use crate::pane::FocusDirection;
+use crate::screenshot::{ScreenshotScope, ScreenshotTarget};
+
+/// Anything a key binding or the command palette can trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Pane(PaneCommand),
+    Tab(TabCommand),
+    /// `Open` toggles the search bar; the others act on the focused pane's search.
+    Search(SearchCommand),
+    App(AppCommand),
+    Shell(ShellCommand),
+    Overlay(OverlayCommand),
+    View(ViewCommand),
+    Edit(EditCommand),
+    ToggleViMode,
+    /// The n-th key callback the config script registered with `bind`.
+    Script(usize),
+}
+
+/// Registry entry for one action.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionSpec {
+    /// Config name, used in `[keys]` ("split_vertical").
+    pub name: &'static str,
+    /// Title shown in the command palette ("Split Pane Right").
+    pub title: &'static str,
+    pub description: &'static str,
+    pub action: Action,
+    /// Built-in combos. A leading `primary+` means Cmd on macOS, Ctrl elsewhere.
+    pub default_keys: &'static [&'static str],
+}
+
+const fn spec(
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    action: Action,
+    default_keys: &'static [&'static str],
+) -> ActionSpec {
+    ActionSpec { name, title, description, action, default_keys }
+}
+
+/// All named actions (`select_tab_N` is handled separately), grouped by area.
+pub const ACTIONS: &[ActionSpec] = &[
+    spec(
+        "split_vertical",
+        "Split Pane Right",
+        "Split the current pane vertically",
+        Action::Pane(PaneCommand::SplitVertical),
+        &["ctrl+shift+d"],
+    ),
+    spec(
+        "split_horizontal",
+        "Split Pane Down",
+        "Split the current pane horizontally",
+        Action::Pane(PaneCommand::SplitHorizontal),
+        &["ctrl+shift+e"],
+    ),
+    spec(
+        "split_vertical_with_profile",
+        "Split Pane Right with Profile",
+        "Pick a profile to run in a new pane to the right",
+        Action::Pane(PaneCommand::SplitVerticalWithProfile),
+        &[],
+    ),
+    spec(
+        "split_horizontal_with_profile",
+        "Split Pane Down with Profile",
+        "Pick a profile to run in a new pane below",
+        Action::Pane(PaneCommand::SplitHorizontalWithProfile),
+        &[],
+    ),
+    spec(
+        "close_pane",
+        "Close Pane",
+        "Close the focused pane",
+        Action::Pane(PaneCommand::ClosePane),
+        &["ctrl+shift+w"],
+    ),
+    spec(
+        "zoom_pane",
+        "Zoom Pane",
+        "Toggle the focused pane filling the tab",
+        Action::Pane(PaneCommand::ZoomToggle),
+        &["ctrl+shift+z"],
+    ),
+    spec(
+        "restart_shell",
+        "Restart Shell",
+        "Start a new shell in the focused pane",
+        Action::Pane(PaneCommand::RestartShell),
+        &["ctrl+alt+r"],
+    ),
+    spec(
+        "add_note",
+        "Add Note",
+        "Attach a note to the current line of the focused pane",
+        Action::Pane(PaneCommand::AddNote),
+        &["ctrl+shift+a"],
+    ),
+    spec(
+        "next_note",
+        "Next Note",
+        "Scroll to the next note in the focused pane",
+        Action::Pane(PaneCommand::NextNote),
+        &["ctrl+shift+j"],
+    ),
+    spec(
+        "prev_note",
+        "Previous Note",
+        "Scroll to the previous note in the focused pane",
+        Action::Pane(PaneCommand::PrevNote),
+        &["ctrl+shift+k"],
+    ),
+    spec(
+        "toggle_bookmark",
+        "Toggle Bookmark",
+        "Bookmark the current line in the focused pane, or remove its bookmark",
+        Action::Pane(PaneCommand::ToggleBookmark),
+        &["ctrl+shift+o"],
+    ),
+    spec(
+        "list_bookmarks",
+        "List Bookmarks",
+        "Show the focused pane's bookmarks to jump to one",
+        Action::Pane(PaneCommand::ListBookmarks),
+        &["ctrl+shift+l"],
+    ),
+    spec(
+        "toggle_pane_trust",
+        "Toggle Pane Trust",
+        "Filter title, clipboard and query escape sequences out of the focused pane's output, or stop filtering",
+        Action::Pane(PaneCommand::ToggleTrust),
+        &[],
+    ),
+    spec(
+        "toggle_legacy_compat",
+        "Toggle Legacy Compatibility",
+        "Hide truecolor, kitty keyboard, queries and advanced OSC features from the focused pane, or show them again",
+        Action::Pane(PaneCommand::ToggleCompat),
+        &[],
+    ),
+    spec(
+        "toggle_scroll_lock",
+        "Toggle Scroll Lock",
+        "Scroll the focused pane and the pane beside it together, line for line, or stop",
+        Action::Pane(PaneCommand::ToggleScrollLock),
+        &[],
+    ),
+    spec(
+        "assign_pane_group",
+        "Assign Pane to Group",
+        "Put the focused pane in a named group, or take it out",
+        Action::Pane(PaneCommand::AssignGroup),
+        &[],
+    ),
+    spec(
+        "toggle_group_broadcast",
+        "Toggle Group Broadcast",
+        "Send what is typed in the focused pane's group to every pane in it",
+        Action::Pane(PaneCommand::ToggleGroupBroadcast),
+        &[],
+    ),
+    spec(
+        "close_pane_group",
+        "Close Group",
+        "Close every pane in the focused pane's group",
+        Action::Pane(PaneCommand::CloseGroup),
+        &[],
+    ),
+    spec(
+        "cycle_group_theme",
+        "Cycle Group Theme",
+        "Switch every pane in the focused pane's group to the next theme",
+        Action::Pane(PaneCommand::CycleGroupTheme),
+        &[],
+    ),
+    spec(
+        "rerun_in_group",
+        "Re-run Command in Group",
+        "Run the focused pane's last command again in every pane of its group",
+        Action::Pane(PaneCommand::RerunInGroup),
+        &[],
+    ),
+    spec(
+        "focus_left",
+        "Focus Pane Left",
+        "Move focus to the pane on the left",
+        Action::Pane(PaneCommand::FocusDirection(FocusDirection::Left)),
+        &["ctrl+shift+left"],
+    ),
+    spec(
+        "focus_right",
+        "Focus Pane Right",
+        "Move focus to the pane on the right",
+        Action::Pane(PaneCommand::FocusDirection(FocusDirection::Right)),
+        &["ctrl+shift+right"],
+    ),
+    spec(
+        "focus_up",
+        "Focus Pane Up",
+        "Move focus to the pane above",
+        Action::Pane(PaneCommand::FocusDirection(FocusDirection::Up)),
+        &["ctrl+shift+up"],
+    ),
+    spec(
+        "focus_down",
+        "Focus Pane Down",
+        "Move focus to the pane below",
+        Action::Pane(PaneCommand::FocusDirection(FocusDirection::Down)),
+        &["ctrl+shift+down"],
+    ),
+    spec(
+        "resize_pane_left",
+        "Resize Pane Left",
+        "Move the divider beside the focused pane left",
+        Action::Pane(PaneCommand::Resize(FocusDirection::Left)),
+        &["ctrl+shift+alt+left"],
+    ),
+    spec(
+        "resize_pane_right",
+        "Resize Pane Right",
+        "Move the divider beside the focused pane right",
+        Action::Pane(PaneCommand::Resize(FocusDirection::Right)),
+        &["ctrl+shift+alt+right"],
+    ),
+    spec(
+        "resize_pane_up",
+        "Resize Pane Up",
+        "Move the divider beside the focused pane up",
+        Action::Pane(PaneCommand::Resize(FocusDirection::Up)),
+        &["ctrl+shift+alt+up"],
+    ),
+    spec(
+        "resize_pane_down",
+        "Resize Pane Down",
+        "Move the divider beside the focused pane down",
+        Action::Pane(PaneCommand::Resize(FocusDirection::Down)),
+        &["ctrl+shift+alt+down"],
+    ),
+    spec(
+        "equalize_panes",
+        "Equalize Panes",
+        "Give all panes in the tab equal space",
+        Action::Pane(PaneCommand::Equalize),
+        &["ctrl+shift+alt+plus"],
+    ),
+    spec(
+        "swap_pane_left",
+        "Swap Pane Left",
+        "Exchange the focused pane with the pane on its left",
+        Action::Pane(PaneCommand::Swap(FocusDirection::Left)),
+        &[],
+    ),
+    spec(
+        "swap_pane_right",
+        "Swap Pane Right",
+        "Exchange the focused pane with the pane on its right",
+        Action::Pane(PaneCommand::Swap(FocusDirection::Right)),
+        &[],
+    ),
+    spec(
+        "swap_pane_up",
+        "Swap Pane Up",
+        "Exchange the focused pane with the pane above it",
+        Action::Pane(PaneCommand::Swap(FocusDirection::Up)),
+        &[],
+    ),
+    spec(
+        "swap_pane_down",
+        "Swap Pane Down",
+        "Exchange the focused pane with the pane below it",
+        Action::Pane(PaneCommand::Swap(FocusDirection::Down)),
+        &[],
+    ),
+    spec(
+        "rotate_layout",
+        "Rotate Split",
+        "Flip the split holding the focused pane between side by side and stacked",
+        Action::Pane(PaneCommand::RotateLayout),
+        &[],
+    ),
+    spec(
+        "new_tab",
+        "New Tab",
+        "Open a new terminal tab",
+        Action::Tab(TabCommand::NewTab),
+        &["ctrl+shift+t"],
+    ),
+    spec(
+        "new_tab_with_profile",
+        "New Tab with Profile",
+        "Pick a profile to open in a new tab",
+        Action::Tab(TabCommand::NewTabWithProfile),
+        &["ctrl+alt+t"],
+    ),
+    spec(
+        "close_tab",
+        "Close Tab",
+        "Close the current tab",
+        Action::Tab(TabCommand::CloseTab),
+        &[],
+    ),
+    spec(
+        "next_tab",
+        "Next Tab",
+        "Switch to the next tab",
+        Action::Tab(TabCommand::NextTab),
+        &["ctrl+shift+tab", "ctrl+shift+pagedown"],
+    ),
+    spec(
+        "prev_tab",
+        "Previous Tab",
+        "Switch to the previous tab",
+        Action::Tab(TabCommand::PrevTab),
+        &["ctrl+shift+pageup"],
+    ),
+    spec(
+        "move_tab_left",
+        "Move Tab Left",
+        "Move the current tab one place left",
+        Action::Tab(TabCommand::MoveTabLeft),
+        &["ctrl+shift+{"],
+    ),
+    spec(
+        "move_tab_right",
+        "Move Tab Right",
+        "Move the current tab one place right",
+        Action::Tab(TabCommand::MoveTabRight),
+        &["ctrl+shift+}"],
+    ),
+    spec(
+        "rename_tab",
+        "Rename Tab",
+        "Give the current tab a title that shell updates won't replace",
+        Action::Tab(TabCommand::RenameTab),
+        &["ctrl+shift+r"],
+    ),
+    spec(
+        "toggle_search",
+        "Find",
+        "Open or close the search bar",
+        Action::Search(SearchCommand::Open),
+        &["ctrl+shift+f"],
+    ),
+    spec(
+        "search_next",
+        "Next Match",
+        "Jump to the next search match",
+        Action::Search(SearchCommand::NextMatch),
+        &[],
+    ),
+    spec(
+        "search_prev",
+        "Previous Match",
+        "Jump to the previous search match",
+        Action::Search(SearchCommand::PrevMatch),
+        &[],
+    ),
+    spec(
+        "search_close",
+        "Close Search",
+        "Close the search bar",
+        Action::Search(SearchCommand::Close),
+        &[],
+    ),
+    spec(
+        "increase_font_size",
+        "Increase Font Size",
+        "Make terminal text larger",
+        Action::App(AppCommand::IncreaseFontSize),
+        &["primary+=", "primary+plus"],
+    ),
+    spec(
+        "decrease_font_size",
+        "Decrease Font Size",
+        "Make terminal text smaller",
+        Action::App(AppCommand::DecreaseFontSize),
+        &["primary+-"],
+    ),
+    spec(
+        "reset_font_size",
+        "Reset Font Size",
+        "Reset terminal text to default size",
+        Action::App(AppCommand::ResetFontSize),
+        &["primary+0"],
+    ),
+    spec(
+        "new_window",
+        "New Window",
+        "Open a new VeloTerm window",
+        Action::App(AppCommand::NewWindow),
+        &["primary+n"],
+    ),
+    spec(
+        "clear_scrollback",
+        "Clear Scrollback",
+        "Clear terminal scrollback history",
+        Action::App(AppCommand::ClearScrollback),
+        &["primary+k"],
+    ),
+    spec(
+        "toggle_cheatsheet",
+        "Keybinding Cheatsheet",
+        "Show or hide the keybinding cheatsheet",
+        Action::App(AppCommand::ToggleCheatsheet),
+        &["ctrl+shift+h"],
+    ),
+    spec(
+        "show_help",
+        "Help",
+        "List all commands, key bindings and config keys",
+        Action::App(AppCommand::ShowHelp),
+        &["f1"],
+    ),
+    spec(
+        "quit",
+        "Quit VeloTerm",
+        "Save the session and exit",
+        Action::App(AppCommand::Quit),
+        &[],
+    ),
+    spec(
+        "toggle_secure_keyboard_entry",
+        "Secure Keyboard Entry",
+        "Stop other apps from reading keystrokes while VeloTerm is focused (macOS)",
+        Action::App(AppCommand::ToggleSecureKeyboardEntry),
+        &[],
+    ),
+    spec(
+        "previous_prompt",
+        "Previous Prompt",
+        "Scroll to the previous shell prompt",
+        Action::Shell(ShellCommand::PreviousPrompt),
+        &["ctrl+shift+p"],
+    ),
+    spec(
+        "next_prompt",
+        "Next Prompt",
+        "Scroll to the next shell prompt",
+        Action::Shell(ShellCommand::NextPrompt),
+        &["ctrl+shift+n"],
+    ),
+    spec(
+        "copy_last_output",
+        "Copy Last Command Output",
+        "Copy the output of the most recent command (needs shell integration)",
+        Action::Shell(ShellCommand::CopyLastOutput),
+        &[],
+    ),
+    spec(
+        "toggle_file_browser",
+        "File Browser",
+        "Show or hide the file browser",
+        Action::Overlay(OverlayCommand::ToggleFileBrowser),
+        &["ctrl+e"],
+    ),
+    spec(
+        "toggle_git_review",
+        "Git Review",
+        "Show or hide the git review panel",
+        Action::Overlay(OverlayCommand::ToggleGitReview),
+        &["ctrl+g"],
+    ),
+    spec(
+        "toggle_markdown_preview",
+        "Preview Markdown",
+        "Preview the README in the current pane, or close the preview",
+        Action::View(ViewCommand::ToggleMarkdownPreview),
+        &["ctrl+shift+m"],
+    ),
+    spec(
+        "toggle_sidebar",
+        "Toggle Sidebar",
+        "Show or hide the session sidebar",
+        Action::View(ViewCommand::ToggleSidebar),
+        &["ctrl+shift+b"],
+    ),
+    spec(
+        "cycle_theme",
+        "Cycle Theme",
+        "Switch to the next color theme",
+        Action::View(ViewCommand::CycleTheme),
+        &[],
+    ),
+    spec(
+        "cycle_color_filter",
+        "Cycle Color Filter",
+        "Switch the current pane's color-blind filter",
+        Action::View(ViewCommand::CycleColorFilter),
+        &[],
+    ),
+    spec(
+        "toggle_column_rulers",
+        "Toggle Column Rulers",
+        "Show or hide column rulers in the current pane",
+        Action::View(ViewCommand::ToggleColumnRulers),
+        &[],
+    ),
+    spec(
+        "toggle_crosshair",
+        "Toggle Cursor Crosshair",
+        "Highlight the cursor's row and column in the current pane",
+        Action::View(ViewCommand::ToggleCrosshair),
+        &[],
+    ),
+    spec(
+        "toggle_soft_wrap",
+        "Toggle Soft Wrap",
+        "Show the current pane's long lines wrapped at the soft wrap column, without resizing the terminal",
+        Action::View(ViewCommand::ToggleSoftWrap),
+        &[],
+    ),
+    spec(
+        "toggle_control_bytes",
+        "Toggle Control Byte View",
+        "Show the current pane's control characters and invalid UTF-8 as hex codes instead of interpreting them",
+        Action::View(ViewCommand::ToggleControlBytes),
+        &[],
+    ),
+    spec(
+        "toggle_minimap",
+        "Toggle Scrollback Minimap",
+        "Show or hide an overview of the scrollback beside each pane",
+        Action::View(ViewCommand::ToggleMinimap),
+        &[],
+    ),
+    spec(
+        "export_line_view",
+        "Read Screen as Text",
+        "Export the visible screen as plain text for screen readers",
+        Action::View(ViewCommand::ExportLineView),
+        &[],
+    ),
+    spec(
+        "screenshot_window",
+        "Screenshot Window",
+        "Save the whole window as a PNG",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Window, ScreenshotTarget::File)),
+        &["primary+shift+s"],
+    ),
+    spec(
+        "screenshot_pane",
+        "Screenshot Pane",
+        "Save the focused pane as a PNG",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Pane, ScreenshotTarget::File)),
+        &[],
+    ),
+    spec(
+        "screenshot_selection",
+        "Screenshot Selection",
+        "Save the selected area as a PNG",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Selection, ScreenshotTarget::File)),
+        &[],
+    ),
+    spec(
+        "screenshot_window_to_clipboard",
+        "Copy Window Screenshot",
+        "Copy the whole window to the clipboard as an image",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Window, ScreenshotTarget::Clipboard)),
+        &[],
+    ),
+    spec(
+        "screenshot_pane_to_clipboard",
+        "Copy Pane Screenshot",
+        "Copy the focused pane to the clipboard as an image",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Pane, ScreenshotTarget::Clipboard)),
+        &[],
+    ),
+    spec(
+        "screenshot_selection_to_clipboard",
+        "Copy Selection Screenshot",
+        "Copy the selected area to the clipboard as an image",
+        Action::View(ViewCommand::Screenshot(ScreenshotScope::Selection, ScreenshotTarget::Clipboard)),
+        &[],
+    ),
+    spec(
+        "toggle_pane_recording",
+        "Record Pane as GIF",
+        "Start or stop recording the focused pane as an animated GIF",
+        Action::View(ViewCommand::TogglePaneRecording),
+        &[],
+    ),
+    spec(
+        "copy",
+        "Copy",
+        "Copy selected text to clipboard",
+        Action::Edit(EditCommand::Copy),
+        &[],
+    ),
+    spec(
+        "copy_logical",
+        "Copy Logical Lines",
+        "Copy the selection with wrapped lines joined and padding trimmed",
+        Action::Edit(EditCommand::CopyAs(CopyMode::Logical)),
+        &[],
+    ),
+    spec(
+        "copy_grid",
+        "Copy Exact Grid",
+        "Copy the selection exactly as shown on screen, alignment spaces included",
+        Action::Edit(EditCommand::CopyAs(CopyMode::Grid)),
+        &[],
+    ),
+    spec(
+        "copy_redacted",
+        "Copy Redacted",
+        "Copy the selection with passwords, keys and tokens masked",
+        Action::Edit(EditCommand::CopyRedacted(true)),
+        &[],
+    ),
+    spec(
+        "copy_unredacted",
+        "Copy Unredacted",
+        "Copy the selection as is, even when redaction.copy is on",
+        Action::Edit(EditCommand::CopyRedacted(false)),
+        &[],
+    ),
+    spec(
+        "paste",
+        "Paste",
+        "Paste from clipboard",
+        Action::Edit(EditCommand::Paste),
+        &[],
+    ),
+    spec(
+        "paste_slowly",
+        "Paste Slowly",
+        "Type the clipboard a few characters at a time, for serial consoles",
+        Action::Edit(EditCommand::PasteSlowly),
+        &[],
+    ),
+    spec(
+        "select_all",
+        "Select All",
+        "Select all terminal content",
+        Action::Edit(EditCommand::SelectAll),
+        &[],
+    ),
+    spec(
+        "hints_open",
+        "Open Hint",
+        "Label the URLs, paths, git SHAs and IP addresses on screen and open the one typed",
+        Action::Edit(EditCommand::Hints(HintAction::Open)),
+        &["ctrl+shift+i"],
+    ),
+    spec(
+        "hints_copy",
+        "Copy Hint",
+        "Label the URLs, paths, git SHAs and IP addresses on screen and copy the one typed",
+        Action::Edit(EditCommand::Hints(HintAction::Copy)),
+        &["ctrl+shift+y"],
+    ),
+    spec(
+        "quick_select",
+        "Quick Select",
+        "Label the matches of the quick_select patterns on screen and copy the one typed",
+        Action::Edit(EditCommand::QuickSelect),
+        &["ctrl+shift+x"],
+    ),
+    spec(
+        "list_vi_registers",
+        "List Vi Registers",
+        "Show the vi-mode named registers and copy one to the clipboard",
+        Action::Edit(EditCommand::ListViRegisters),
+        &[],
+    ),
+    spec(
+        "toggle_vi_mode",
+        "Toggle Vi Mode",
+        "Enter or exit vi-mode navigation",
+        Action::ToggleViMode,
+        &["ctrl+shift+space"],
+    ),
+];
+
+impl Action {
+    /// Parse a config action name such as `"split_vertical"` or `"select_tab_3"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(spec) = ACTIONS.iter().find(|spec| spec.name == name) {
+            return Some(spec.action);
+        }
+        let n: usize = name.strip_prefix("select_tab_")?.parse().ok()?;
+        if !(1..=9).contains(&n) {
+            return None;
+        }
+        Some(Self::Tab(TabCommand::SelectTab(n - 1)))
+    }
+
+    /// The config name of this action (inverse of `from_name`). None for
+    /// search-bar editing commands, which can't be bound.
+    pub fn name(&self) -> Option<String> {
+        if let Self::Tab(TabCommand::SelectTab(i)) = self {
+            return Some(format!("select_tab_{}", i + 1));
+        }
+        self.spec().map(|spec| spec.name.to_string())
+    }
+
+    /// The registry entry for this action, if it has one.
+    pub fn spec(&self) -> Option<&'static ActionSpec> {
+        ACTIONS.iter().find(|spec| spec.action == *self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry_names_and_actions_are_unique() {
+        let names: HashSet<_> = ACTIONS.iter().map(|s| s.name).collect();
+        assert_eq!(names.len(), ACTIONS.len());
+        for spec in ACTIONS {
+            assert_eq!(spec.action.spec().unwrap().name, spec.name);
+        }
+    }
+
+    #[test]
+    fn registry_entries_are_documented() {
+        for spec in ACTIONS {
+            assert!(!spec.title.is_empty(), "{} has no title", spec.name);
+            assert!(!spec.description.is_empty(), "{} has no description", spec.name);
+        }
+    }
+
+    #[test]
+    fn select_tab_names_are_not_registered() {
+        assert!(Action::Tab(TabCommand::SelectTab(0)).spec().is_none());
+        assert_eq!(Action::Tab(TabCommand::SelectTab(0)).name().as_deref(), Some("select_tab_1"));
+    }
+}
@@ -0,0 +1,165 @@
+// Keybinding cheatsheet: the live keymap grouped by area, plus the fixed
+// search-bar and vi-mode keys, so the overlay always matches what keys do.
+
+use super::actions::Action;
+use super::keybindings::Keymap;
+
+/// One row of the cheatsheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheatsheetEntry {
+    /// Key combos, display form ("Ctrl+Shift+D"); several are joined by " / ".
+    pub keys: String,
+    pub label: String,
+}
+
+/// A titled group of rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheatsheetSection {
+    pub title: &'static str,
+    pub entries: Vec<CheatsheetEntry>,
+}
+
+/// Section titles in display order.
+const SECTIONS: [&str; 9] = [
+    "Panes", "Tabs", "Search", "Vi mode", "Shell", "Window", "Overlays", "View", "Edit",
+];
+
+fn section_index(action: &Action) -> usize {
+    match action {
+        Action::Pane(_) => 0,
+        Action::Tab(_) => 1,
+        Action::Search(_) => 2,
+        Action::ToggleViMode => 3,
+        Action::Shell(_) => 4,
+        Action::App(_) | Action::Script(_) => 5,
+        Action::Overlay(_) => 6,
+        Action::View(_) => 7,
+        Action::Edit(_) => 8,
+    }
+}
+
+/// "ctrl+shift+pagedown" → "Ctrl+Shift+PageDown".
+pub fn display_combo(combo: &str) -> String {
+    combo
+        .split('+')
+        .map(|part| match part {
+            "ctrl" | "control" => "Ctrl".to_string(),
+            "cmd" | "super" | "command" => "Cmd".to_string(),
+            "alt" | "option" => "Alt".to_string(),
+            "pageup" => "PageUp".to_string(),
+            "pagedown" => "PageDown".to_string(),
+            "plus" => "+".to_string(),
+            other => {
+                let mut chars = other.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// "split_vertical" → "Split vertical".
+fn action_label(name: &str) -> String {
+    let words = name.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// Build the cheatsheet from the active keymap. Empty sections are dropped.
+pub fn cheatsheet(keymap: &Keymap) -> Vec<CheatsheetSection> {
+    let mut sections: Vec<CheatsheetSection> = SECTIONS
+        .iter()
+        .map(|&title| CheatsheetSection { title, entries: Vec::new() })
+        .collect();
+    // Rows follow binding order; an action bound to several combos is one row
+    let mut rows: Vec<(Action, CheatsheetEntry)> = Vec::new();
+    for binding in keymap.bindings() {
+        let keys = display_combo(&binding.combo);
+        match rows.iter_mut().find(|(action, _)| *action == binding.action) {
+            Some((_, entry)) => {
+                entry.keys.push_str(" / ");
+                entry.keys.push_str(&keys);
+            }
+            None => {
+                let Some(name) = binding.action.name() else {
+                    continue;
+                };
+                rows.push((binding.action, CheatsheetEntry { keys, label: action_label(&name) }));
+            }
+        }
+    }
+    for (action, entry) in rows {
+        sections[section_index(&action)].entries.push(entry);
+    }
+
+    // Keys handled outside the keymap (search bar, vi mode)
+    for (index, fixed) in [(2, super::SEARCH_KEY_HELP), (3, crate::vi_mode::KEY_HELP)] {
+        sections[index].entries.extend(fixed.iter().map(|&(keys, label)| CheatsheetEntry {
+            keys: keys.to_string(),
+            label: label.to_string(),
+        }));
+    }
+
+    sections.retain(|s| !s.entries.is_empty());
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn find<'a>(sheet: &'a [CheatsheetSection], label: &str) -> Option<&'a CheatsheetEntry> {
+        sheet.iter().flat_map(|s| &s.entries).find(|e| e.label == label)
+    }
+
+    #[test]
+    fn display_combo_capitalizes_parts() {
+        assert_eq!(display_combo("ctrl+shift+d"), "Ctrl+Shift+D");
+        assert_eq!(display_combo("ctrl+shift+pagedown"), "Ctrl+Shift+PageDown");
+        assert_eq!(display_combo("cmd+plus"), "Cmd++");
+    }
+
+    #[test]
+    fn every_default_binding_is_listed() {
+        let keymap = Keymap::default();
+        let sheet = cheatsheet(&keymap);
+        for binding in keymap.bindings() {
+            let label = action_label(&binding.action.name().unwrap());
+            let entry = find(&sheet, &label).unwrap_or_else(|| panic!("{label} missing"));
+            assert!(entry.keys.contains(&display_combo(&binding.combo)));
+        }
+    }
+
+    #[test]
+    fn sections_group_by_area() {
+        let sheet = cheatsheet(&Keymap::default());
+        let titles: Vec<_> = sheet.iter().map(|s| s.title).collect();
+        assert_eq!(titles[..4], ["Panes", "Tabs", "Search", "Vi mode"]);
+        let panes = &sheet[0];
+        assert!(panes.entries.iter().any(|e| e.label == "Split vertical"));
+        let vi = sheet.iter().find(|s| s.title == "Vi mode").unwrap();
+        assert_eq!(vi.entries[0].label, "Toggle vi mode");
+        assert!(vi.entries.len() > 1, "fixed vi keys follow the toggle");
+    }
+
+    #[test]
+    fn multiple_combos_share_a_row() {
+        let sheet = cheatsheet(&Keymap::default());
+        let next = find(&sheet, "Next tab").unwrap();
+        assert!(next.keys.contains("Ctrl+Shift+Tab / Ctrl+Shift+PageDown"));
+    }
+
+    #[test]
+    fn overrides_show_up() {
+        let overrides = HashMap::from([("split_vertical".to_string(), "alt+d".to_string())]);
+        let sheet = cheatsheet(&Keymap::from_config(&overrides));
+        assert_eq!(find(&sheet, "Split vertical").unwrap().keys, "Alt+D");
+    }
+}
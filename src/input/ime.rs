@@ -0,0 +1,70 @@
+// Input method (ibus/fcitx) support: where the candidate window should open.
+
+/// The focused pane's cursor cell in physical window pixels, reported to the
+/// input method so its candidate popup opens next to the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImeCursorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImeCursorArea {
+    /// The cell at viewport `row`/`col` of a grid whose top-left cell starts
+    /// at `origin`.
+    pub fn for_cell(origin: (f32, f32), cell_width: f32, cell_height: f32, row: usize, col: usize) -> Self {
+        Self {
+            x: (origin.0 + col as f32 * cell_width).round() as i32,
+            y: (origin.1 + row as f32 * cell_height).round() as i32,
+            width: cell_width.round().max(1.0) as u32,
+            height: cell_height.round().max(1.0) as u32,
+        }
+    }
+}
+
+/// Remembers the last area sent so the input method is only told when the
+/// cursor actually moves.
+#[derive(Debug, Default)]
+pub struct ImeState {
+    last_area: Option<ImeCursorArea>,
+}
+
+impl ImeState {
+    /// Record `area`; true when it differs from the last one reported.
+    pub fn update(&mut self, area: ImeCursorArea) -> bool {
+        if self.last_area == Some(area) {
+            return false;
+        }
+        self.last_area = Some(area);
+        true
+    }
+
+    /// Forget the last area (e.g. after focus loss), so the next one is sent.
+    pub fn reset(&mut self) {
+        self.last_area = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_area_covers_cell() {
+        let area = ImeCursorArea::for_cell((12.0, 40.0), 9.6, 20.0, 3, 5);
+        assert_eq!(area, ImeCursorArea { x: 60, y: 100, width: 10, height: 20 });
+    }
+
+    #[test]
+    fn update_reports_only_changes() {
+        let mut ime = ImeState::default();
+        let area = ImeCursorArea::for_cell((0.0, 0.0), 10.0, 20.0, 0, 0);
+        assert!(ime.update(area));
+        assert!(!ime.update(area));
+        let moved = ImeCursorArea::for_cell((0.0, 0.0), 10.0, 20.0, 0, 1);
+        assert!(ime.update(moved));
+        ime.reset();
+        assert!(ime.update(moved));
+    }
+}
@@ -0,0 +1,356 @@
+// Keybinding engine: resolves key events to a unified `Action` from the
+// built-in shortcut table plus `[keys]` overrides in the config.
+
+use std::collections::HashMap;
+use winit::keyboard::{Key, ModifiersState};
+
+use super::actions::{Action, ACTIONS};
+use super::parse_keybinding;
+
+/// Action value that removes a binding (`"ctrl+shift+d" = "none"`).
+pub const UNBIND: &str = "none";
+
+/// Built-in bindings as (key combo, action name), from the action registry.
+/// macOS uses Cmd for the primary shortcuts and adds Cmd-based tab
+/// shortcuts; elsewhere Ctrl.
+pub fn default_bindings() -> Vec<(String, &'static str)> {
+    let primary = if cfg!(target_os = "macos") { "cmd+" } else { "ctrl+" };
+    let mut bindings: Vec<(String, &'static str)> = ACTIONS
+        .iter()
+        .flat_map(|spec| {
+            spec.default_keys.iter().map(move |combo| {
+                let combo = match combo.strip_prefix("primary+") {
+                    Some(rest) => format!("{primary}{rest}"),
+                    None => combo.to_string(),
+                };
+                (combo, spec.name)
+            })
+        })
+        .collect();
+
+    const SELECT_TAB: [&str; 9] = [
+        "select_tab_1", "select_tab_2", "select_tab_3", "select_tab_4", "select_tab_5",
+        "select_tab_6", "select_tab_7", "select_tab_8", "select_tab_9",
+    ];
+    for (i, action) in SELECT_TAB.iter().enumerate() {
+        bindings.push((format!("ctrl+shift+{}", i + 1), action));
+    }
+
+    if cfg!(target_os = "macos") {
+        bindings.push(("cmd+q".to_string(), "quit"));
+        bindings.push(("cmd+t".to_string(), "new_tab"));
+        bindings.push(("cmd+w".to_string(), "close_tab"));
+        for (i, action) in SELECT_TAB.iter().enumerate() {
+            bindings.push((format!("cmd+{}", i + 1), action));
+        }
+        // Shift turns [ and ] into { and } on most layouts
+        for (combo, action) in [
+            ("cmd+shift+[", "prev_tab"),
+            ("cmd+shift+{", "prev_tab"),
+            ("cmd+shift+]", "next_tab"),
+            ("cmd+shift+}", "next_tab"),
+        ] {
+            bindings.push((combo.to_string(), action));
+        }
+    }
+
+    bindings
+}
+
+/// One resolved binding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBinding {
+    /// The combo as written ("ctrl+shift+d").
+    pub combo: String,
+    key: Key,
+    mods: ModifiersState,
+    pub action: Action,
+}
+
+impl KeyBinding {
+    fn new(combo: &str, action: Action) -> Option<Self> {
+        let (key, mods) = parse_keybinding(combo)?;
+        Some(Self {
+            combo: combo.to_string(),
+            key,
+            mods,
+            action,
+        })
+    }
+
+    /// Whether a key event triggers this binding. Modifiers must match
+    /// exactly; letters match regardless of case (Shift+D reports "D").
+    pub fn matches(&self, logical_key: &Key, modifiers: ModifiersState) -> bool {
+        if modifiers != self.mods {
+            return false;
+        }
+        match (&self.key, logical_key) {
+            (Key::Character(a), Key::Character(b)) => a.to_lowercase() == b.to_lowercase(),
+            (a, b) => a == b,
+        }
+    }
+
+    fn same_combo(&self, other: &Self) -> bool {
+        self.mods == other.mods && self.matches(&other.key, other.mods)
+    }
+}
+
+/// The active key bindings: defaults with `[keys]` overrides applied.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&HashMap::new())
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from `[keys]` entries. Two forms are accepted:
+    /// - `"ctrl+shift+d" = "split_vertical"` binds a combo (replacing whatever
+    ///   it did before); `"ctrl+shift+d" = "none"` unbinds it.
+    /// - `split_vertical = "alt+d"` moves an action to a new combo, dropping
+    ///   its defaults; `split_vertical = "none"` unbinds the action.
+    ///
+    /// Entries that are neither (e.g. `accept_command_hint`, read elsewhere)
+    /// are left alone. Unknown actions and unparsable combos are logged; an
+    /// action given an unparsable combo ends up unbound.
+    pub fn from_config(overrides: &HashMap<String, String>) -> Self {
+        let mut bindings: Vec<KeyBinding> = default_bindings()
+            .iter()
+            .filter_map(|(combo, name)| KeyBinding::new(combo, Action::from_name(name)?))
+            .collect();
+
+        let mut entries: Vec<(&String, &String)> = overrides.iter().collect();
+        entries.sort();
+
+        // Action-form entries first, so an explicit combo entry wins a conflict
+        for (name, combo) in &entries {
+            let Some(action) = Action::from_name(name) else {
+                continue;
+            };
+            // The defaults go even if the new combo is bad: a broken config
+            // entry leaves the action unbound rather than silently on its old key
+            bindings.retain(|b| b.action != action);
+            if combo.as_str() == UNBIND {
+                continue;
+            }
+            match KeyBinding::new(combo, action) {
+                Some(binding) => {
+                    bindings.retain(|b| !b.same_combo(&binding));
+                    bindings.push(binding);
+                }
+                None => log::warn!("Invalid key combo '{combo}' for action '{name}'"),
+            }
+        }
+
+        for (combo, name) in &entries {
+            if Action::from_name(combo).is_some() || parse_keybinding(combo).is_none() {
+                continue;
+            }
+            let action = match Action::from_name(name) {
+                Some(action) => Some(action),
+                None if name.as_str() == UNBIND => None,
+                None => {
+                    log::warn!("Unknown action '{name}' bound to '{combo}'");
+                    continue;
+                }
+            };
+            let Some(probe) = KeyBinding::new(combo, Action::ToggleViMode) else {
+                continue;
+            };
+            bindings.retain(|b| !b.same_combo(&probe));
+            if let Some(action) = action {
+                bindings.push(KeyBinding { action, ..probe });
+            }
+        }
+
+        Self { bindings }
+    }
+
+    /// Replace the config script's key callbacks: combo n triggers
+    /// `Action::Script(n)`. They are added last, so they win over `[keys]`
+    /// and the defaults. Returns the combos that could not be parsed.
+    pub fn set_script_bindings<'a>(&mut self, combos: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        self.bindings.retain(|b| !matches!(b.action, Action::Script(_)));
+        let mut invalid = Vec::new();
+        for (index, combo) in combos.into_iter().enumerate() {
+            match KeyBinding::new(combo, Action::Script(index)) {
+                Some(binding) => self.bindings.push(binding),
+                None => invalid.push(combo.to_string()),
+            }
+        }
+        invalid
+    }
+
+    /// The action bound to a key event, if any.
+    pub fn lookup(&self, logical_key: &Key, modifiers: ModifiersState) -> Option<Action> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|b| b.matches(logical_key, modifiers))
+            .map(|b| b.action)
+    }
+
+    /// All active bindings, defaults first.
+    pub fn bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    /// Every combo bound to `action`, in binding order.
+    pub fn combos_for(&self, action: Action) -> impl Iterator<Item = &str> {
+        self.bindings
+            .iter()
+            .filter(move |b| b.action == action)
+            .map(|b| b.combo.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{PaneCommand, SearchCommand, TabCommand};
+    use crate::pane::FocusDirection;
+    use winit::keyboard::NamedKey;
+
+    fn ctrl_shift() -> ModifiersState {
+        ModifiersState::CONTROL | ModifiersState::SHIFT
+    }
+
+    fn keymap(entries: &[(&str, &str)]) -> Keymap {
+        let overrides = entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Keymap::from_config(&overrides)
+    }
+
+    #[test]
+    fn every_default_binding_resolves() {
+        for (combo, name) in default_bindings() {
+            assert!(parse_keybinding(&combo).is_some(), "bad combo {combo}");
+            assert!(Action::from_name(name).is_some(), "bad action {name}");
+        }
+        assert_eq!(Keymap::default().bindings().len(), default_bindings().len());
+    }
+
+    #[test]
+    fn action_names_parse() {
+        assert_eq!(
+            Action::from_name("focus_left"),
+            Some(Action::Pane(PaneCommand::FocusDirection(FocusDirection::Left)))
+        );
+        assert_eq!(
+            Action::from_name("select_tab_3"),
+            Some(Action::Tab(TabCommand::SelectTab(2)))
+        );
+        assert_eq!(Action::from_name("select_tab_0"), None);
+        assert_eq!(Action::from_name("select_tab_10"), None);
+        assert_eq!(Action::from_name("frobnicate"), None);
+    }
+
+    #[test]
+    fn action_names_round_trip() {
+        for (_, name) in default_bindings() {
+            assert_eq!(Action::from_name(name).unwrap().name().as_deref(), Some(name));
+        }
+        assert_eq!(Action::Search(SearchCommand::InsertChar('x')).name(), None);
+    }
+
+    #[test]
+    fn lookup_ignores_letter_case_but_not_modifiers() {
+        let keys = Keymap::default();
+        let split = Some(Action::Pane(PaneCommand::SplitVertical));
+        assert_eq!(keys.lookup(&Key::Character("D".into()), ctrl_shift()), split);
+        assert_eq!(keys.lookup(&Key::Character("d".into()), ctrl_shift()), split);
+        let extra = ctrl_shift() | ModifiersState::ALT;
+        assert_eq!(keys.lookup(&Key::Character("d".into()), extra), None);
+    }
+
+    #[test]
+    fn combo_entry_rebinds_combo() {
+        let keys = keymap(&[("ctrl+shift+d", "new_tab")]);
+        assert_eq!(
+            keys.lookup(&Key::Character("d".into()), ctrl_shift()),
+            Some(Action::Tab(TabCommand::NewTab))
+        );
+        // The default combo for new_tab still works
+        assert_eq!(
+            keys.lookup(&Key::Character("t".into()), ctrl_shift()),
+            Some(Action::Tab(TabCommand::NewTab))
+        );
+    }
+
+    #[test]
+    fn combo_entry_can_unbind() {
+        let keys = keymap(&[("ctrl+shift+z", UNBIND)]);
+        assert_eq!(keys.lookup(&Key::Character("z".into()), ctrl_shift()), None);
+    }
+
+    #[test]
+    fn action_entry_moves_action() {
+        let keys = keymap(&[("split_vertical", "alt+d")]);
+        assert_eq!(keys.lookup(&Key::Character("d".into()), ctrl_shift()), None);
+        assert_eq!(
+            keys.lookup(&Key::Character("d".into()), ModifiersState::ALT),
+            Some(Action::Pane(PaneCommand::SplitVertical))
+        );
+    }
+
+    #[test]
+    fn action_entry_can_unbind() {
+        let keys = keymap(&[("toggle_vi_mode", UNBIND)]);
+        assert_eq!(keys.lookup(&Key::Named(NamedKey::Space), ctrl_shift()), None);
+    }
+
+    #[test]
+    fn combo_entry_wins_over_action_entry() {
+        let keys = keymap(&[("zoom_pane", "alt+z"), ("alt+z", "new_tab")]);
+        assert_eq!(
+            keys.lookup(&Key::Character("z".into()), ModifiersState::ALT),
+            Some(Action::Tab(TabCommand::NewTab))
+        );
+    }
+
+    #[test]
+    fn unknown_entries_are_ignored() {
+        let keys = keymap(&[("ctrl+shift+c", "frobnicate"), ("accept_command_hint", "ctrl+enter")]);
+        assert_eq!(keys.bindings().len(), default_bindings().len());
+    }
+
+    #[test]
+    fn invalid_combo_leaves_action_unbound() {
+        let keys = keymap(&[("split_vertical", "not+a+combo")]);
+        assert_eq!(keys.lookup(&Key::Character("d".into()), ctrl_shift()), None);
+    }
+
+
+    #[test]
+    fn palette_only_actions_are_bindable() {
+        let keys = keymap(&[("ctrl+alt+t", "cycle_theme")]);
+        assert_eq!(
+            keys.lookup(&Key::Character("t".into()), ModifiersState::CONTROL | ModifiersState::ALT),
+            Some(Action::View(crate::input::ViewCommand::CycleTheme))
+        );
+    }
+
+    #[test]
+    fn script_bindings_win_and_are_replaced() {
+        let mut keys = Keymap::default();
+        let invalid = keys.set_script_bindings(["ctrl+shift+d", "not+a+combo", "ctrl+alt+g"]);
+        assert_eq!(invalid, ["not+a+combo"]);
+        assert_eq!(keys.lookup(&Key::Character("d".into()), ctrl_shift()), Some(Action::Script(0)));
+        assert_eq!(
+            keys.lookup(&Key::Character("g".into()), ModifiersState::CONTROL | ModifiersState::ALT),
+            Some(Action::Script(2))
+        );
+        keys.set_script_bindings([]);
+        assert_eq!(keys.bindings().len(), default_bindings().len());
+        assert_eq!(
+            keys.lookup(&Key::Character("d".into()), ctrl_shift()),
+            Some(Action::Pane(PaneCommand::SplitVertical))
+        );
+    }
+}
@@ -1,8 +1,16 @@
 // Keyboard input translation: converts winit KeyEvents to terminal byte sequences.
 
+pub mod actions;
+pub mod cheatsheet;
 pub mod clipboard;
+pub mod ime;
+pub mod keybindings;
 pub mod mouse;
+pub mod mouse_report;
+pub mod option_key;
+pub mod paste;
 pub mod selection;
+pub mod touch;
 
 use std::collections::HashMap;
 use winit::event::ElementState;
@@ -31,7 +39,7 @@ pub enum InputMode {
 }
 
 /// A search-mode command resulting from a key event.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchCommand {
     /// Insert a character into the search query.
     InsertChar(char),
@@ -45,19 +53,12 @@ pub enum SearchCommand {
     Close,
     /// Open search (Ctrl+Shift+F from Normal mode).
     Open,
-}
-
-/// Check if a key event should open the search overlay (from Normal mode).
-/// Returns true if Ctrl+Shift+F is pressed.
-pub fn should_open_search(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-) -> bool {
-    let ctrl_shift = modifiers.control_key() && modifiers.shift_key();
-    if !ctrl_shift {
-        return false;
-    }
-    matches!(logical_key, Key::Character(s) if s.to_lowercase() == "f")
+    /// Toggle matching case exactly (Alt+C).
+    ToggleCaseSensitive,
+    /// Toggle matching whole words only (Alt+W).
+    ToggleWholeWord,
+    /// Toggle between regex and plain text queries (Alt+R).
+    ToggleRegex,
 }
 
 /// An overlay command triggered by a keybinding.
@@ -67,6 +68,54 @@ pub enum OverlayCommand {
     ToggleGitReview,
 }
 
+/// A display command for the window or the focused pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewCommand {
+    /// Open the README preview, or close the open preview.
+    ToggleMarkdownPreview,
+    ToggleSidebar,
+    CycleTheme,
+    CycleColorFilter,
+    ToggleColumnRulers,
+    ToggleCrosshair,
+    /// Wrap the focused pane's long lines at `guides.soft_wrap_column`.
+    ToggleSoftWrap,
+    /// Show the focused pane's control bytes as hex codes instead of
+    /// interpreting them.
+    ToggleControlBytes,
+    /// Show or hide the scrollback minimap column in every pane.
+    ToggleMinimap,
+    /// Send the screen text to the line-view command (or the clipboard).
+    ExportLineView,
+    /// Capture the window, focused pane or selection as PNG.
+    Screenshot(crate::screenshot::ScreenshotScope, crate::screenshot::ScreenshotTarget),
+    /// Start or stop recording the focused pane as an animated GIF.
+    TogglePaneRecording,
+}
+
+/// A clipboard/selection command on the focused pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditCommand {
+    /// Copy using the configured `selection.copy_mode`.
+    Copy,
+    /// Copy with an explicit mode, ignoring the config.
+    CopyAs(selection::CopyMode),
+    /// Copy with secrets masked (true) or not, ignoring `redaction.copy`.
+    CopyRedacted(bool),
+    Paste,
+    /// Type the clipboard a few characters at a time (`clipboard.slow_paste_*`).
+    PasteSlowly,
+    SelectAll,
+    /// Label the URLs, paths, SHAs and addresses on screen and open or copy
+    /// the one whose label is typed.
+    Hints(crate::link::hints::HintAction),
+    /// Label the `[quick_select]` pattern matches on screen and copy the one
+    /// whose label is typed.
+    QuickSelect,
+    /// Show the vi-mode registers; Enter copies one to the clipboard.
+    ListViRegisters,
+}
+
 /// Parse a keybinding string like "ctrl+e", "ctrl+shift+b" into (Key, ModifiersState).
 /// Returns None if the string cannot be parsed.
 pub fn parse_keybinding(s: &str) -> Option<(Key, ModifiersState)> {
@@ -109,6 +158,19 @@ pub fn parse_keybinding(s: &str) -> Option<(Key, ModifiersState)> {
         "down" | "arrowdown" => Key::Named(NamedKey::ArrowDown),
         "left" | "arrowleft" => Key::Named(NamedKey::ArrowLeft),
         "right" | "arrowright" => Key::Named(NamedKey::ArrowRight),
+        "plus" => Key::Character("+".into()),
+        "f1" => Key::Named(NamedKey::F1),
+        "f2" => Key::Named(NamedKey::F2),
+        "f3" => Key::Named(NamedKey::F3),
+        "f4" => Key::Named(NamedKey::F4),
+        "f5" => Key::Named(NamedKey::F5),
+        "f6" => Key::Named(NamedKey::F6),
+        "f7" => Key::Named(NamedKey::F7),
+        "f8" => Key::Named(NamedKey::F8),
+        "f9" => Key::Named(NamedKey::F9),
+        "f10" => Key::Named(NamedKey::F10),
+        "f11" => Key::Named(NamedKey::F11),
+        "f12" => Key::Named(NamedKey::F12),
         other if other.len() == 1 => Key::Character(other.into()),
         _ => return None, // unknown key
     };
@@ -116,52 +178,12 @@ pub fn parse_keybinding(s: &str) -> Option<(Key, ModifiersState)> {
     Some((key, mods))
 }
 
-/// Check if a key event matches an overlay toggle shortcut.
-///
-/// If custom bindings are provided, checks them first. Falls back to defaults:
-/// - Ctrl+E (Ctrl only, no Shift) -> ToggleFileBrowser
-/// - Ctrl+G (Ctrl only, no Shift) -> ToggleGitReview
-pub fn match_overlay_command(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-    bindings: &HashMap<String, String>,
-) -> Option<OverlayCommand> {
-    // Check custom bindings first
-    let commands = [
-        ("toggle_file_browser", OverlayCommand::ToggleFileBrowser),
-        ("toggle_git_review", OverlayCommand::ToggleGitReview),
-    ];
-
-    for (action_name, cmd) in &commands {
-        if let Some(binding_str) = bindings.get(*action_name) {
-            if let Some((key, mods)) = parse_keybinding(binding_str) {
-                if *logical_key == key && modifiers == mods {
-                    return Some(*cmd);
-                }
-            }
-        }
-    }
-
-    // If custom bindings are defined for an action, skip the default for that action
-    let file_browser_custom = bindings.contains_key("toggle_file_browser");
-    let git_review_custom = bindings.contains_key("toggle_git_review");
-
-    // Only Ctrl held, no Shift (to avoid conflict with Ctrl+Shift+E = SplitHorizontal)
-    if !modifiers.control_key() || modifiers.shift_key() {
-        return None;
-    }
-    match logical_key {
-        Key::Character(s) => {
-            let lower = s.to_lowercase();
-            match lower.as_str() {
-                "e" if !file_browser_custom => Some(OverlayCommand::ToggleFileBrowser),
-                "g" if !git_review_custom => Some(OverlayCommand::ToggleGitReview),
-                _ => None,
-            }
-        }
-        _ => None,
-    }
-}
+/// Fixed search-bar keys (see `match_search_command`), for the cheatsheet.
+pub const SEARCH_KEY_HELP: &[(&str, &str)] = &[
+    ("Enter / Down", "Next match"),
+    ("Shift+Enter / Up", "Previous match"),
+    ("Esc", "Close search"),
+];
 
 /// Process a key event while in Search mode.
 /// Returns a SearchCommand describing what action to take.
@@ -179,6 +201,18 @@ pub fn match_search_command(
         }
     }
 
+    // Alt+C / Alt+W / Alt+R toggle the search options
+    if modifiers.alt_key() && !modifiers.control_key() {
+        if let Key::Character(s) = logical_key {
+            match s.to_lowercase().as_str() {
+                "c" => return Some(SearchCommand::ToggleCaseSensitive),
+                "w" => return Some(SearchCommand::ToggleWholeWord),
+                "r" => return Some(SearchCommand::ToggleRegex),
+                _ => {}
+            }
+        }
+    }
+
     match logical_key {
         Key::Named(named) => match named {
             NamedKey::Escape => Some(SearchCommand::Close),
@@ -216,129 +250,64 @@ pub enum PaneCommand {
     ClosePane,
     FocusDirection(FocusDirection),
     ZoomToggle,
-}
-
-/// Check if a key event matches a pane command keybinding.
-///
-/// Hardcoded defaults (Ctrl+Shift prefix):
-/// - Ctrl+Shift+D: split vertical
-/// - Ctrl+Shift+E: split horizontal
-/// - Ctrl+Shift+W: close pane
-/// - Ctrl+Shift+Arrow: focus direction
-/// - Ctrl+Shift+Z: zoom toggle
-pub fn match_pane_command(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-) -> Option<PaneCommand> {
-    let ctrl_shift = modifiers.control_key() && modifiers.shift_key();
-    if !ctrl_shift {
-        return None;
-    }
-
-    match logical_key {
-        Key::Character(s) => {
-            let lower = s.to_lowercase();
-            match lower.as_str() {
-                "d" => Some(PaneCommand::SplitVertical),
-                "e" => Some(PaneCommand::SplitHorizontal),
-                "w" => Some(PaneCommand::ClosePane),
-                "z" => Some(PaneCommand::ZoomToggle),
-                _ => None,
-            }
-        }
-        Key::Named(named) => match named {
-            NamedKey::ArrowLeft => Some(PaneCommand::FocusDirection(FocusDirection::Left)),
-            NamedKey::ArrowRight => Some(PaneCommand::FocusDirection(FocusDirection::Right)),
-            NamedKey::ArrowUp => Some(PaneCommand::FocusDirection(FocusDirection::Up)),
-            NamedKey::ArrowDown => Some(PaneCommand::FocusDirection(FocusDirection::Down)),
-            _ => None,
-        },
-        _ => None,
-    }
+    /// Move the divider beside the focused pane by `panes.resize_step`.
+    Resize(FocusDirection),
+    /// Give all panes in the tab equal space.
+    Equalize,
+    /// Exchange the focused pane with its neighbor in a direction.
+    Swap(FocusDirection),
+    /// Flip the split holding the focused pane between side by side and stacked.
+    RotateLayout,
+    /// Respawn the focused pane's shell.
+    RestartShell,
+    /// Write or edit the note on the focused pane's current line.
+    AddNote,
+    /// Scroll to the focused pane's next or previous note.
+    NextNote,
+    PrevNote,
+    /// Bookmark the focused pane's current line, or remove its bookmark.
+    ToggleBookmark,
+    /// Open or close the focused pane's bookmark list.
+    ListBookmarks,
+    /// Mark the focused pane untrusted (its output loses title, clipboard
+    /// and query sequences), or trusted again.
+    ToggleTrust,
+    /// Switch the focused pane between the standard and legacy
+    /// compatibility presets, offering to restart its shell.
+    ToggleCompat,
+    /// Link the focused pane's scrolling to the pane across its split, or
+    /// unlink it.
+    ToggleScrollLock,
+    /// Name the focused pane's group, or take it out of its group.
+    AssignGroup,
+    /// Send input typed into the focused pane's group to all of its panes,
+    /// or stop.
+    ToggleGroupBroadcast,
+    /// Close every pane in the focused pane's group.
+    CloseGroup,
+    /// Switch the focused pane's group to the next theme.
+    CycleGroupTheme,
+    /// Run the focused pane's last command again in every pane of its group.
+    RerunInGroup,
+    /// Pick a profile to split the focused pane with.
+    SplitVerticalWithProfile,
+    SplitHorizontalWithProfile,
 }
 
 /// A tab management command triggered by a keybinding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TabCommand {
     NewTab,
+    /// Pick a profile to open in a new tab.
+    NewTabWithProfile,
     CloseTab,
     NextTab,
     PrevTab,
     SelectTab(usize),
     MoveTabLeft,
     MoveTabRight,
-}
-
-/// Check if a key event matches a tab command keybinding.
-///
-/// Platform-aware shortcuts:
-/// - Ctrl+Shift+T / Cmd+T (macOS): new tab
-/// - Ctrl+Shift+Tab / Ctrl+Shift+PageDown: next tab
-/// - Ctrl+Shift+PageUp: previous tab
-/// - Ctrl+Shift+1..9 / Cmd+1..9 (macOS): select tab by number
-/// - Ctrl+Shift+{ / }: move tab left/right
-/// - Cmd+Shift+[ / ] (macOS): previous/next tab
-pub fn match_tab_command(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-) -> Option<TabCommand> {
-    let ctrl_shift = modifiers.control_key() && modifiers.shift_key();
-
-    // macOS: Cmd+key (no Shift, no Ctrl) for T, 1-9
-    let cmd_only = cfg!(target_os = "macos")
-        && modifiers.super_key()
-        && !modifiers.control_key()
-        && !modifiers.shift_key();
-
-    // macOS: Cmd+Shift+key (no Ctrl) for [ and ]
-    let cmd_shift = cfg!(target_os = "macos")
-        && modifiers.super_key()
-        && modifiers.shift_key()
-        && !modifiers.control_key();
-
-    // Cmd+Shift+[ / ] for prev/next tab (macOS)
-    if cmd_shift {
-        if let Key::Character(s) = logical_key {
-            match s.as_ref() {
-                "[" | "{" => return Some(TabCommand::PrevTab),
-                "]" | "}" => return Some(TabCommand::NextTab),
-                _ => {}
-            }
-        }
-    }
-
-    if !ctrl_shift && !cmd_only {
-        return None;
-    }
-
-    match logical_key {
-        Key::Character(s) => {
-            let lower = s.to_lowercase();
-            match lower.as_str() {
-                "t" => Some(TabCommand::NewTab),
-                "w" if cmd_only => Some(TabCommand::CloseTab),
-                "{" if ctrl_shift => Some(TabCommand::MoveTabLeft),
-                "}" if ctrl_shift => Some(TabCommand::MoveTabRight),
-                "1" => Some(TabCommand::SelectTab(0)),
-                "2" => Some(TabCommand::SelectTab(1)),
-                "3" => Some(TabCommand::SelectTab(2)),
-                "4" => Some(TabCommand::SelectTab(3)),
-                "5" => Some(TabCommand::SelectTab(4)),
-                "6" => Some(TabCommand::SelectTab(5)),
-                "7" => Some(TabCommand::SelectTab(6)),
-                "8" => Some(TabCommand::SelectTab(7)),
-                "9" => Some(TabCommand::SelectTab(8)),
-                _ => None,
-            }
-        }
-        Key::Named(named) if ctrl_shift => match named {
-            NamedKey::Tab => Some(TabCommand::NextTab),
-            NamedKey::PageUp => Some(TabCommand::PrevTab),
-            NamedKey::PageDown => Some(TabCommand::NextTab),
-            _ => None,
-        },
-        _ => None,
-    }
+    /// Edit the active tab's title in the sidebar.
+    RenameTab,
 }
 
 /// A shell integration command triggered by a keybinding.
@@ -346,45 +315,36 @@ pub fn match_tab_command(
 pub enum ShellCommand {
     PreviousPrompt,
     NextPrompt,
+    /// Copy the most recent command's output (OSC 133 marks) to the clipboard.
+    CopyLastOutput,
 }
 
-/// Check if a key event matches a shell integration command keybinding.
-///
-/// Hardcoded defaults:
-/// - Ctrl+Shift+P: jump to previous prompt
-/// - Ctrl+Shift+N: jump to next prompt
-pub fn match_shell_command(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-) -> Option<ShellCommand> {
-    let ctrl_shift = modifiers.control_key() && modifiers.shift_key();
-    if !ctrl_shift {
-        return None;
-    }
-
-    match logical_key {
-        Key::Character(s) => {
-            let lower = s.to_lowercase();
-            match lower.as_str() {
-                "p" => Some(ShellCommand::PreviousPrompt),
-                "n" => Some(ShellCommand::NextPrompt),
-                _ => None,
-            }
-        }
-        _ => None,
+/// Check if a key event should accept the top history suggestion (Tab or Right, unmodified).
+pub fn is_suggestion_accept_key(logical_key: &Key, modifiers: ModifiersState) -> bool {
+    if !modifiers.is_empty() {
+        return false;
     }
+    matches!(
+        logical_key,
+        Key::Named(NamedKey::Tab) | Key::Named(NamedKey::ArrowRight)
+    )
 }
 
-/// Check if a key event should toggle vi-mode (default: Ctrl+Shift+Space).
-pub fn should_toggle_vi_mode(
+/// Default binding that types the corrected command from a "did you mean" hint.
+pub const DEFAULT_COMMAND_HINT_ACCEPT: &str = "alt+enter";
+
+/// Check if a key event should accept the command-not-found hint. Uses the
+/// `accept_command_hint` binding if configured, otherwise Alt+Enter.
+pub fn is_command_hint_accept_key(
     logical_key: &Key,
     modifiers: ModifiersState,
+    bindings: &HashMap<String, String>,
 ) -> bool {
-    let ctrl_shift = modifiers.control_key() && modifiers.shift_key();
-    if !ctrl_shift {
-        return false;
-    }
-    matches!(logical_key, Key::Named(NamedKey::Space))
+    let binding = bindings
+        .get("accept_command_hint")
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_COMMAND_HINT_ACCEPT);
+    parse_keybinding(binding).is_some_and(|(key, mods)| *logical_key == key && modifiers == mods)
 }
 
 /// An application-level command triggered by a keybinding.
@@ -395,64 +355,48 @@ pub enum AppCommand {
     ResetFontSize,
     NewWindow,
     ClearScrollback,
-}
-
-/// Return the platform-appropriate "primary" modifier.
-/// macOS uses Super (Cmd), others use Ctrl.
-fn is_primary_modifier(modifiers: ModifiersState) -> bool {
-    if cfg!(target_os = "macos") {
-        modifiers.super_key() && !modifiers.control_key() && !modifiers.shift_key()
-    } else {
-        modifiers.control_key() && !modifiers.super_key() && !modifiers.shift_key()
-    }
-}
-
-/// Check if a key event matches an application command keybinding.
-///
-/// Platform-aware shortcuts:
-/// - Cmd+= / Cmd+Plus (macOS) or Ctrl+= / Ctrl+Plus (Linux): increase font size
-/// - Cmd+Minus (macOS) or Ctrl+Minus (Linux): decrease font size
-/// - Cmd+0 (macOS) or Ctrl+0 (Linux): reset font size
-pub fn match_app_command(
-    logical_key: &Key,
-    modifiers: ModifiersState,
-) -> Option<AppCommand> {
-    if !is_primary_modifier(modifiers) {
-        return None;
-    }
-
-    match logical_key {
-        Key::Character(s) => {
-            match s.as_ref() {
-                "=" | "+" => Some(AppCommand::IncreaseFontSize),
-                "-" => Some(AppCommand::DecreaseFontSize),
-                "0" => Some(AppCommand::ResetFontSize),
-                "n" | "N" => Some(AppCommand::NewWindow),
-                "k" | "K" => Some(AppCommand::ClearScrollback),
-                _ => None,
-            }
-        }
-        _ => None,
-    }
+    /// Show or hide the keybinding cheatsheet overlay.
+    ToggleCheatsheet,
+    /// Open the help pane (commands, bindings and config keys).
+    ShowHelp,
+    /// Save the session and exit.
+    Quit,
+    /// Turn macOS Secure Keyboard Entry on or off.
+    ToggleSecureKeyboardEntry,
 }
 
 /// Translate a winit key event into terminal byte sequences to send to the PTY.
 ///
 /// Returns `None` if the key event should not produce any output (e.g. modifier-only
-/// keys, key releases, or unhandled keys).
+/// keys, key releases, or unhandled keys). With `alt_as_meta` (macOS Option-as-Alt)
+/// the output is prefixed with ESC, as Alt does in xterm's meta-sends-escape mode.
 pub fn translate_key(
     logical_key: &Key,
     text: Option<&str>,
     state: ElementState,
     modifiers: ModifiersState,
+    alt_as_meta: bool,
 ) -> Option<Vec<u8>> {
     // Only handle key presses, not releases
     if state == ElementState::Released {
         return None;
     }
 
+    let mut bytes = key_bytes(logical_key, text, modifiers)?;
+    if alt_as_meta && bytes.first() != Some(&0x1b) {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
+
+fn key_bytes(logical_key: &Key, text: Option<&str>, modifiers: ModifiersState) -> Option<Vec<u8>> {
     match logical_key {
         Key::Character(s) => {
+            // Cmd shortcuts belong to the app on macOS; an unbound Cmd+key
+            // must not type its letter into the shell
+            if cfg!(target_os = "macos") && modifiers.super_key() {
+                return None;
+            }
             // Ctrl+letter → control byte
             if modifiers.control_key() {
                 if let Some(ch) = s.chars().next() {
@@ -537,10 +481,66 @@ fn named_key_bytes(key: NamedKey, _modifiers: ModifiersState) -> Option<Vec<u8>>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use actions::Action;
+    use keybindings::Keymap;
+
+    // The shortcut checks below run through the default keymap (plus any
+    // `[keys]` overrides), filtered to one command family.
+
+    fn lookup(key: &Key, mods: ModifiersState, bindings: &HashMap<String, String>) -> Option<Action> {
+        Keymap::from_config(bindings).lookup(key, mods)
+    }
+
+    fn match_pane_command(key: &Key, mods: ModifiersState) -> Option<PaneCommand> {
+        match lookup(key, mods, &HashMap::new()) {
+            Some(Action::Pane(cmd)) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    fn match_tab_command(key: &Key, mods: ModifiersState) -> Option<TabCommand> {
+        match lookup(key, mods, &HashMap::new()) {
+            Some(Action::Tab(cmd)) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    fn match_shell_command(key: &Key, mods: ModifiersState) -> Option<ShellCommand> {
+        match lookup(key, mods, &HashMap::new()) {
+            Some(Action::Shell(cmd)) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    fn match_app_command(key: &Key, mods: ModifiersState) -> Option<AppCommand> {
+        match lookup(key, mods, &HashMap::new()) {
+            Some(Action::App(cmd)) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    fn match_overlay_command(
+        key: &Key,
+        mods: ModifiersState,
+        bindings: &HashMap<String, String>,
+    ) -> Option<OverlayCommand> {
+        match lookup(key, mods, bindings) {
+            Some(Action::Overlay(cmd)) => Some(cmd),
+            _ => None,
+        }
+    }
+
+    fn should_open_search(key: &Key, mods: ModifiersState) -> bool {
+        lookup(key, mods, &HashMap::new()) == Some(Action::Search(SearchCommand::Open))
+    }
+
+    fn should_toggle_vi_mode(key: &Key, mods: ModifiersState) -> bool {
+        lookup(key, mods, &HashMap::new()) == Some(Action::ToggleViMode)
+    }
 
     // Helper: create a pressed key event translation
     fn press(key: Key, text: Option<&str>, mods: ModifiersState) -> Option<Vec<u8>> {
-        translate_key(&key, text, ElementState::Pressed, mods)
+        translate_key(&key, text, ElementState::Pressed, mods, false)
     }
 
     fn no_mods() -> ModifiersState {
@@ -598,6 +598,7 @@ mod tests {
             Some("a"),
             ElementState::Released,
             no_mods(),
+            false,
         );
         assert_eq!(result, None);
     }
@@ -794,6 +795,37 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    // ── Cmd shortcuts ───────────────────────────────────────────────
+
+    #[test]
+    fn cmd_letter_types_nothing_on_macos() {
+        let result = press(Key::Character("q".into()), Some("q"), ModifiersState::SUPER);
+        if cfg!(target_os = "macos") {
+            assert_eq!(result, None);
+        } else {
+            assert_eq!(result, Some(b"q".to_vec()));
+        }
+        // Ctrl still reaches the shell as a control byte
+        let result = press(Key::Character("q".into()), None, ModifiersState::CONTROL);
+        assert_eq!(result, Some(vec![0x11]));
+    }
+
+    // ── Option as Alt ───────────────────────────────────────────────
+
+    #[test]
+    fn alt_as_meta_prefixes_escape() {
+        let meta = |key: Key, mods| translate_key(&key, None, ElementState::Pressed, mods, true);
+        assert_eq!(meta(Key::Character("f".into()), ModifiersState::ALT), Some(b"\x1bf".to_vec()));
+        assert_eq!(
+            meta(Key::Character("x".into()), ModifiersState::ALT | ModifiersState::CONTROL),
+            Some(vec![0x1b, 0x18])
+        );
+        assert_eq!(meta(Key::Named(NamedKey::Backspace), ModifiersState::ALT), Some(vec![0x1b, 0x7f]));
+        // Sequences that already start with ESC are left alone
+        assert_eq!(meta(Key::Named(NamedKey::Escape), ModifiersState::ALT), Some(vec![0x1b]));
+        assert_eq!(meta(Key::Named(NamedKey::Alt), ModifiersState::ALT), None);
+    }
+
     // ── ctrl_key_byte helper ────────────────────────────────────────
 
     #[test]
@@ -1192,6 +1224,15 @@ mod tests {
         assert_eq!(result, Some(SearchCommand::InsertChar('.')));
     }
 
+    #[test]
+    fn search_mode_alt_toggles_options() {
+        let alt = |key: &str| match_search_command(&Key::Character(key.into()), None, ModifiersState::ALT);
+        assert_eq!(alt("c"), Some(SearchCommand::ToggleCaseSensitive));
+        assert_eq!(alt("W"), Some(SearchCommand::ToggleWholeWord));
+        assert_eq!(alt("r"), Some(SearchCommand::ToggleRegex));
+        assert_eq!(alt("x"), Some(SearchCommand::InsertChar('x')));
+    }
+
     // ── 2.3.2 Backspace → DeleteChar ───────────────────────────────
 
     #[test]
@@ -1346,6 +1387,50 @@ mod tests {
         ));
     }
 
+    // ── Suggestion accept key ──────────────────────────────────────
+
+    #[test]
+    fn suggestion_accept_tab_and_right() {
+        assert!(is_suggestion_accept_key(&Key::Named(NamedKey::Tab), no_mods()));
+        assert!(is_suggestion_accept_key(
+            &Key::Named(NamedKey::ArrowRight),
+            no_mods()
+        ));
+    }
+
+    #[test]
+    fn suggestion_accept_ignores_modified_keys() {
+        assert!(!is_suggestion_accept_key(
+            &Key::Named(NamedKey::Tab),
+            ModifiersState::SHIFT
+        ));
+        assert!(!is_suggestion_accept_key(&Key::Named(NamedKey::Enter), no_mods()));
+    }
+
+    #[test]
+    fn command_hint_accept_defaults_to_alt_enter() {
+        let bindings = HashMap::new();
+        let enter = Key::Named(NamedKey::Enter);
+        assert!(is_command_hint_accept_key(&enter, ModifiersState::ALT, &bindings));
+        assert!(!is_command_hint_accept_key(&enter, no_mods(), &bindings));
+    }
+
+    #[test]
+    fn command_hint_accept_uses_custom_binding() {
+        let mut bindings = HashMap::new();
+        bindings.insert("accept_command_hint".to_string(), "ctrl+y".to_string());
+        assert!(is_command_hint_accept_key(
+            &Key::Character("y".into()),
+            ModifiersState::CONTROL,
+            &bindings
+        ));
+        assert!(!is_command_hint_accept_key(
+            &Key::Named(NamedKey::Enter),
+            ModifiersState::ALT,
+            &bindings
+        ));
+    }
+
     // ── InputMode default ──────────────────────────────────────────
 
     #[test]
@@ -3,7 +3,8 @@
 use std::time::Instant;
 
 use crate::input::selection::{
-    find_word_boundaries, pixel_to_cell, pixel_to_cell_with_side, Selection, SelectionType, Side,
+    find_word_boundaries, normalize, pixel_to_cell, pixel_to_cell_with_side, Selection,
+    SelectionType, Side,
 };
 use crate::renderer::grid_renderer::GridCell;
 
@@ -380,6 +381,30 @@ impl MouseSelectionState {
         }
     }
 
+    /// Start dragging one end of the existing selection (touch handles).
+    /// The other end stays anchored; subsequent `on_mouse_drag` calls move
+    /// the grabbed end. Returns false if there is no selection.
+    pub fn begin_handle_drag(&mut self, handle: crate::input::touch::SelectionHandle) -> bool {
+        let Some(sel) = self.active_selection.as_mut() else {
+            return false;
+        };
+        let (first, last) = normalize(sel);
+        let (anchor, anchor_side, moving, moving_side) = match handle {
+            crate::input::touch::SelectionHandle::Start => (last, Side::Right, first, Side::Left),
+            crate::input::touch::SelectionHandle::End => (first, Side::Left, last, Side::Right),
+        };
+        *sel = Selection {
+            start: anchor,
+            end: moving,
+            selection_type: SelectionType::Range,
+            start_side: anchor_side,
+            end_side: moving_side,
+        };
+        self.drag_anchor = None;
+        self.drag_phase = DragPhase::Active;
+        true
+    }
+
     /// Clear the active selection.
     pub fn clear_selection(&mut self) {
         self.active_selection = None;
@@ -856,4 +881,33 @@ mod tests {
         let sel = state.active_selection.as_ref().unwrap();
         assert_eq!(sel.end, (1, 3));
     }
+
+    // ── Touch selection handles ─────────────────────────────────
+
+    #[test]
+    fn handle_drag_moves_grabbed_start() {
+        let mut state = MouseSelectionState::new();
+        let cells = make_cells("hello world", 20);
+        state.active_selection = Some(Selection {
+            start: (0, 2),
+            end: (0, 8),
+            selection_type: SelectionType::Word,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        });
+        assert!(state.begin_handle_drag(crate::input::touch::SelectionHandle::Start));
+        assert_eq!(state.drag_phase, DragPhase::Active);
+        state.on_mouse_drag(5.0, 5.0, 10.0, 20.0, 20, 1, &cells, 0);
+        let sel = state.active_selection.as_ref().unwrap();
+        assert_eq!(sel.start, (0, 8), "end stays anchored");
+        assert_eq!(sel.end, (0, 0));
+        assert_eq!(sel.selection_type, SelectionType::Range);
+    }
+
+    #[test]
+    fn handle_drag_needs_selection() {
+        let mut state = MouseSelectionState::new();
+        assert!(!state.begin_handle_drag(crate::input::touch::SelectionHandle::End));
+        assert_eq!(state.drag_phase, DragPhase::Idle);
+    }
 }
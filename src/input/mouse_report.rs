@@ -0,0 +1,302 @@
+// Mouse reporting: encodes clicks, drags, motion and wheel events as the
+// escape sequences applications request with DECSET 1000/1002/1003 and
+// the 1005/1006/1015 coordinate encodings.
+
+use winit::keyboard::ModifiersState;
+
+/// Which mouse events the application asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MouseTracking {
+    /// No reporting; the mouse selects text.
+    Off,
+    /// Presses and releases (DECSET 1000).
+    Click,
+    /// Also motion while a button is held (DECSET 1002).
+    Drag,
+    /// Also motion with no button held (DECSET 1003).
+    Motion,
+}
+
+/// How a report is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEncoding {
+    /// `CSI M Cb Cx Cy` with one byte each; coordinates stop at 223.
+    X10,
+    /// As X10 but values above 95 are UTF-8 encoded (DECSET 1005).
+    Utf8,
+    /// `CSI Cb ; Cx ; Cy M` in decimal (DECSET 1015).
+    Urxvt,
+    /// `CSI < Cb ; Cx ; Cy M/m` in decimal, releases keep the button (DECSET 1006).
+    Sgr,
+}
+
+/// The mouse modes in effect for a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseProtocol {
+    pub tracking: MouseTracking,
+    pub encoding: MouseEncoding,
+}
+
+impl MouseProtocol {
+    /// Whether any mouse events go to the application.
+    pub fn is_active(&self) -> bool {
+        self.tracking != MouseTracking::Off
+    }
+}
+
+/// The button a report is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportButton {
+    Left,
+    Middle,
+    Right,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+    /// Motion with no button held.
+    None,
+}
+
+impl ReportButton {
+    fn code(self) -> u32 {
+        match self {
+            Self::Left => 0,
+            Self::Middle => 1,
+            Self::Right => 2,
+            Self::None => 3,
+            Self::WheelUp => 64,
+            Self::WheelDown => 65,
+            Self::WheelLeft => 66,
+            Self::WheelRight => 67,
+        }
+    }
+
+    fn is_wheel(self) -> bool {
+        matches!(self, Self::WheelUp | Self::WheelDown | Self::WheelLeft | Self::WheelRight)
+    }
+}
+
+/// What happened to the button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportKind {
+    Press,
+    Release,
+    /// The pointer moved to a new cell (with `button` held, or `None`).
+    Motion,
+}
+
+/// Whether `protocol` wants this event at all: motion needs 1002 (button
+/// held) or 1003, and wheel "releases" are never sent.
+pub fn wants(protocol: MouseProtocol, button: ReportButton, kind: ReportKind) -> bool {
+    match kind {
+        _ if protocol.tracking == MouseTracking::Off => false,
+        ReportKind::Press => true,
+        ReportKind::Release => !button.is_wheel(),
+        ReportKind::Motion if button == ReportButton::None => {
+            protocol.tracking == MouseTracking::Motion
+        }
+        ReportKind::Motion => protocol.tracking >= MouseTracking::Drag,
+    }
+}
+
+/// Encode one event at the 0-based cell (`col`, `row`). None when the
+/// protocol doesn't want the event or the cell can't be expressed in the
+/// encoding (X10 beyond column/row 223).
+pub fn encode(
+    protocol: MouseProtocol,
+    button: ReportButton,
+    kind: ReportKind,
+    col: usize,
+    row: usize,
+    mods: ModifiersState,
+) -> Option<Vec<u8>> {
+    if !wants(protocol, button, kind) {
+        return None;
+    }
+    let mut cb = button.code();
+    // Only SGR can say which button was released
+    if kind == ReportKind::Release && protocol.encoding != MouseEncoding::Sgr {
+        cb = 3;
+    }
+    if kind == ReportKind::Motion {
+        cb += 32;
+    }
+    if mods.shift_key() {
+        cb += 4;
+    }
+    if mods.alt_key() {
+        cb += 8;
+    }
+    if mods.control_key() {
+        cb += 16;
+    }
+    let (x, y) = (col as u32 + 1, row as u32 + 1);
+    match protocol.encoding {
+        MouseEncoding::Sgr => {
+            let end = if kind == ReportKind::Release { 'm' } else { 'M' };
+            Some(format!("\x1b[<{cb};{x};{y}{end}").into_bytes())
+        }
+        MouseEncoding::Urxvt => Some(format!("\x1b[{};{x};{y}M", 32 + cb).into_bytes()),
+        MouseEncoding::X10 => {
+            let byte = |v: u32| u8::try_from(32 + v).ok();
+            Some(vec![0x1b, b'[', b'M', byte(cb)?, byte(x)?, byte(y)?])
+        }
+        MouseEncoding::Utf8 => {
+            let mut out = b"\x1b[M".to_vec();
+            for v in [cb, x, y] {
+                // 2047 is the largest value two UTF-8 bytes hold
+                let c = char::from_u32(32 + v).filter(|c| c.len_utf8() <= 2)?;
+                let mut buf = [0; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+            Some(out)
+        }
+    }
+}
+
+/// Follow DECSET/DECRST 1015 (urxvt encoding), which the VT parser doesn't
+/// track, through `bytes`. Returns the state after them; a full reset
+/// (`ESC c`) turns it off.
+pub fn scan_urxvt_mode(bytes: &[u8], mut enabled: bool) -> bool {
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] != 0x1b {
+            i += 1;
+            continue;
+        }
+        if bytes[i + 1] == b'c' {
+            enabled = false;
+            i += 2;
+            continue;
+        }
+        if bytes[i + 1] != b'[' || bytes.get(i + 2) != Some(&b'?') {
+            i += 1;
+            continue;
+        }
+        let params_start = i + 3;
+        let mut j = params_start;
+        while j < bytes.len() && (bytes[j].is_ascii_digit() || bytes[j] == b';') {
+            j += 1;
+        }
+        if let Some(&fin) = bytes.get(j) {
+            if fin == b'h' || fin == b'l' {
+                let params = &bytes[params_start..j];
+                if params.split(|&b| b == b';').any(|p| p == b"1015") {
+                    enabled = fin == b'h';
+                }
+            }
+        }
+        i = j.max(i + 1);
+    }
+    enabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol(tracking: MouseTracking, encoding: MouseEncoding) -> MouseProtocol {
+        MouseProtocol { tracking, encoding }
+    }
+
+    fn none() -> ModifiersState {
+        ModifiersState::empty()
+    }
+
+    // ── Filtering ───────────────────────────────────────────────────
+
+    #[test]
+    fn tracking_level_decides_motion_reports() {
+        let click = protocol(MouseTracking::Click, MouseEncoding::Sgr);
+        let drag = protocol(MouseTracking::Drag, MouseEncoding::Sgr);
+        let motion = protocol(MouseTracking::Motion, MouseEncoding::Sgr);
+        assert!(wants(click, ReportButton::Left, ReportKind::Press));
+        assert!(!wants(click, ReportButton::Left, ReportKind::Motion));
+        assert!(wants(drag, ReportButton::Left, ReportKind::Motion));
+        assert!(!wants(drag, ReportButton::None, ReportKind::Motion));
+        assert!(wants(motion, ReportButton::None, ReportKind::Motion));
+        let off = protocol(MouseTracking::Off, MouseEncoding::Sgr);
+        assert!(!wants(off, ReportButton::Left, ReportKind::Press));
+    }
+
+    #[test]
+    fn wheel_sends_no_release() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::Sgr);
+        assert!(wants(p, ReportButton::WheelUp, ReportKind::Press));
+        assert!(!wants(p, ReportButton::WheelUp, ReportKind::Release));
+    }
+
+    // ── Encodings ───────────────────────────────────────────────────
+
+    #[test]
+    fn sgr_press_release_and_drag() {
+        let p = protocol(MouseTracking::Drag, MouseEncoding::Sgr);
+        let enc = |b, k| encode(p, b, k, 4, 2, none()).unwrap();
+        assert_eq!(enc(ReportButton::Left, ReportKind::Press), b"\x1b[<0;5;3M");
+        assert_eq!(enc(ReportButton::Right, ReportKind::Release), b"\x1b[<2;5;3m");
+        assert_eq!(enc(ReportButton::Left, ReportKind::Motion), b"\x1b[<32;5;3M");
+        assert_eq!(enc(ReportButton::WheelDown, ReportKind::Press), b"\x1b[<65;5;3M");
+    }
+
+    #[test]
+    fn sgr_has_no_coordinate_limit() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::Sgr);
+        let out = encode(p, ReportButton::Left, ReportKind::Press, 299, 0, none()).unwrap();
+        assert_eq!(out, b"\x1b[<0;300;1M");
+    }
+
+    #[test]
+    fn modifiers_add_to_button_code() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::Sgr);
+        let mods = ModifiersState::CONTROL | ModifiersState::ALT;
+        let out = encode(p, ReportButton::Left, ReportKind::Press, 0, 0, mods).unwrap();
+        assert_eq!(out, b"\x1b[<24;1;1M");
+    }
+
+    #[test]
+    fn x10_uses_offset_bytes_and_generic_release() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::X10);
+        let press = encode(p, ReportButton::Middle, ReportKind::Press, 0, 9, none()).unwrap();
+        assert_eq!(press, vec![0x1b, b'[', b'M', 33, 33, 42]);
+        let release = encode(p, ReportButton::Middle, ReportKind::Release, 0, 9, none()).unwrap();
+        assert_eq!(release, vec![0x1b, b'[', b'M', 35, 33, 42]);
+    }
+
+    #[test]
+    fn x10_drops_cells_past_223() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::X10);
+        assert!(encode(p, ReportButton::Left, ReportKind::Press, 222, 0, none()).is_some());
+        assert_eq!(encode(p, ReportButton::Left, ReportKind::Press, 223, 0, none()), None);
+    }
+
+    #[test]
+    fn utf8_encodes_large_coordinates() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::Utf8);
+        let out = encode(p, ReportButton::Left, ReportKind::Press, 299, 0, none()).unwrap();
+        let mut expected = b"\x1b[M ".to_vec();
+        expected.extend_from_slice("\u{14c}".as_bytes()); // 32 + 300
+        expected.push(b'!');
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn urxvt_is_decimal_with_offset_button() {
+        let p = protocol(MouseTracking::Click, MouseEncoding::Urxvt);
+        let press = encode(p, ReportButton::Left, ReportKind::Press, 299, 4, none()).unwrap();
+        assert_eq!(press, b"\x1b[32;300;5M");
+        let release = encode(p, ReportButton::Left, ReportKind::Release, 299, 4, none()).unwrap();
+        assert_eq!(release, b"\x1b[35;300;5M");
+    }
+
+    // ── urxvt mode tracking ─────────────────────────────────────────
+
+    #[test]
+    fn scan_follows_1015_set_and_reset() {
+        assert!(scan_urxvt_mode(b"\x1b[?1000h\x1b[?1015h", false));
+        assert!(scan_urxvt_mode(b"\x1b[?1000;1015h", false));
+        assert!(!scan_urxvt_mode(b"\x1b[?1015l", true));
+        assert!(!scan_urxvt_mode(b"\x1bc", true));
+        assert!(scan_urxvt_mode(b"\x1b[?10155h plain text", true));
+    }
+}
@@ -0,0 +1,114 @@
+// Option-as-Alt (macOS): which Option keys send ESC-prefixed input like
+// Alt/Meta instead of composing characters, and which of them are held.
+
+use winit::event::ElementState;
+use winit::keyboard::{Key, KeyLocation, NamedKey};
+
+/// Which Option keys act as Alt (`macos.option_as_alt`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OptionAsAlt {
+    /// Both Option keys compose characters (the macOS default).
+    #[default]
+    None,
+    Left,
+    Right,
+    Both,
+}
+
+impl OptionAsAlt {
+    /// Convert a config string ("none", "left", "right", "both").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(Self::None),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    /// Whether the Option key now held acts as Alt. `alt_down` is the Alt
+    /// modifier state; `held` says which side it comes from.
+    pub fn applies(self, held: OptionKeys, alt_down: bool) -> bool {
+        alt_down
+            && match self {
+                Self::None => false,
+                Self::Left => held.left,
+                Self::Right => held.right,
+                Self::Both => true,
+            }
+    }
+}
+
+/// Which Option (Alt) keys are held, from the key location of their
+/// press and release events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OptionKeys {
+    pub left: bool,
+    pub right: bool,
+}
+
+impl OptionKeys {
+    /// Follow a key event; only Alt keys with a side change anything.
+    pub fn on_key(&mut self, key: &Key, location: KeyLocation, state: ElementState) {
+        if *key != Key::Named(NamedKey::Alt) {
+            return;
+        }
+        let pressed = state == ElementState::Pressed;
+        match location {
+            KeyLocation::Left => self.left = pressed,
+            KeyLocation::Right => self.right = pressed,
+            _ => {}
+        }
+    }
+
+    /// Forget both sides once the Alt modifier is up, in case a release
+    /// happened while the window was unfocused.
+    pub fn sync(&mut self, alt_down: bool) {
+        if !alt_down {
+            *self = Self::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alt() -> Key {
+        Key::Named(NamedKey::Alt)
+    }
+
+    #[test]
+    fn config_strings() {
+        assert_eq!(OptionAsAlt::from_config_str("both"), Some(OptionAsAlt::Both));
+        assert_eq!(OptionAsAlt::from_config_str("left"), Some(OptionAsAlt::Left));
+        assert_eq!(OptionAsAlt::from_config_str("alt"), None);
+    }
+
+    #[test]
+    fn key_locations_track_sides() {
+        let mut keys = OptionKeys::default();
+        keys.on_key(&alt(), KeyLocation::Right, ElementState::Pressed);
+        assert_eq!(keys, OptionKeys { left: false, right: true });
+        keys.on_key(&Key::Character("f".into()), KeyLocation::Standard, ElementState::Pressed);
+        keys.on_key(&alt(), KeyLocation::Right, ElementState::Released);
+        assert_eq!(keys, OptionKeys::default());
+        keys.on_key(&alt(), KeyLocation::Left, ElementState::Pressed);
+        keys.sync(false);
+        assert_eq!(keys, OptionKeys::default());
+    }
+
+    #[test]
+    fn only_configured_side_acts_as_alt() {
+        let left = OptionKeys { left: true, right: false };
+        assert!(OptionAsAlt::Left.applies(left, true));
+        assert!(!OptionAsAlt::Right.applies(left, true));
+        assert!(OptionAsAlt::Both.applies(left, true));
+        assert!(!OptionAsAlt::None.applies(left, true));
+        // Both covers an Option key pressed before the window had focus
+        assert!(OptionAsAlt::Both.applies(OptionKeys::default(), true));
+        assert!(!OptionAsAlt::Both.applies(left, false));
+    }
+}
@@ -0,0 +1,136 @@
+// Paste confirmation and slow paste. With `clipboard.confirm_multiline_paste`
+// text spanning lines waits for a prompt in the pane; slow paste sends it a
+// few keystrokes at a time with a pause in between, for serial consoles and
+// REPLs that drop fast pastes. A thread paces the chunks and the window
+// writes each one as it arrives.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::pane::PaneId;
+
+/// Clipboard text waiting for the paste prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PastePrompt {
+    pub pane: PaneId,
+    pub text: String,
+}
+
+impl PastePrompt {
+    /// Prompt text shown in the pane.
+    pub fn prompt(&self) -> String {
+        let lines = self.text.lines().count();
+        let noun = if lines == 1 { "line" } else { "lines" };
+        format!("Paste {lines} {noun}? Enter pastes, S types it slowly, Esc cancels")
+    }
+}
+
+/// Text as typed keystrokes: line breaks become Enter (CR).
+pub fn keystrokes(text: &str) -> String {
+    text.replace("\r\n", "\r").replace('\n', "\r")
+}
+
+/// Split `text` into chunks of `size` characters.
+pub fn chunks(text: &str, size: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.chunks(size.max(1)).map(|chunk| chunk.iter().collect()).collect()
+}
+
+/// A slow paste in progress. Dropping it stops the remaining chunks.
+pub struct SlowPaste {
+    pane: PaneId,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SlowPaste {
+    /// Send `text` as keystrokes, `chunk_size` characters every `delay`.
+    /// `send` gets each chunk and whether it is the last, and returns false
+    /// to stop early.
+    pub fn start<F>(pane: PaneId, text: &str, chunk_size: usize, delay: Duration, send: F) -> Self
+    where
+        F: Fn(Vec<u8>, bool) -> bool + Send + 'static,
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let chunks = chunks(&keystrokes(text), chunk_size);
+        let stop = Arc::clone(&cancel);
+        let spawned = thread::Builder::new().name("slow-paste".into()).spawn(move || {
+            let count = chunks.len();
+            for (i, chunk) in chunks.into_iter().enumerate() {
+                if stop.load(Ordering::Relaxed) || !send(chunk.into_bytes(), i + 1 == count) {
+                    return;
+                }
+                if i + 1 < count {
+                    thread::sleep(delay);
+                }
+            }
+        });
+        if let Err(e) = spawned {
+            log::warn!("Slow paste thread failed to start: {e}");
+        }
+        Self { pane, cancel }
+    }
+
+    /// The pane being pasted into.
+    pub fn pane(&self) -> PaneId {
+        self.pane
+    }
+
+    /// Prompt text shown in the pane meanwhile.
+    pub fn prompt(&self) -> String {
+        "Pasting slowly, Esc stops".to_string()
+    }
+}
+
+impl Drop for SlowPaste {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keystrokes_turn_line_breaks_into_enter() {
+        assert_eq!(keystrokes("ls\nPRINT 1\r\n"), "ls\rPRINT 1\r");
+    }
+
+    #[test]
+    fn chunks_split_on_characters() {
+        assert_eq!(chunks("abcde", 2), ["ab", "cd", "e"]);
+        assert_eq!(chunks("héé", 1), ["h", "é", "é"]);
+        assert_eq!(chunks("ab", 0), ["a", "b"]);
+        assert!(chunks("", 4).is_empty());
+    }
+
+    #[test]
+    fn prompt_counts_lines() {
+        let prompt = PastePrompt { pane: PaneId(1), text: "a\nb\nc".into() };
+        assert!(prompt.prompt().starts_with("Paste 3 lines?"));
+    }
+
+    #[test]
+    fn sends_every_chunk_then_marks_the_last() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let _paste = SlowPaste::start(PaneId(1), "ab\nc", 2, Duration::ZERO, move |bytes, last| {
+            tx.send((bytes, last)).is_ok()
+        });
+        let sent: Vec<_> = rx.iter().take(2).collect();
+        assert_eq!(sent, [(b"ab".to_vec(), false), (b"\rc".to_vec(), true)]);
+    }
+
+    #[test]
+    fn dropping_stops_the_paste() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let paste = SlowPaste::start(PaneId(1), "abcdef", 1, Duration::from_millis(100), move |bytes, _| {
+            tx.send(bytes).is_ok()
+        });
+        assert_eq!(rx.recv().unwrap(), b"a");
+        drop(paste);
+        // The thread ends at its next chunk, closing the channel
+        assert_eq!(rx.iter().count(), 0);
+    }
+}
@@ -1,6 +1,8 @@
 // Text selection: state management, coordinate conversion, and text extraction.
 
-use crate::renderer::grid_renderer::{GridCell, CELL_FLAG_SELECTED};
+use crate::renderer::grid_renderer::{
+    GridCell, CELL_FLAG_SELECTED, CELL_FLAG_WIDE, CELL_FLAG_WIDE_SPACER, CELL_FLAG_WRAPLINE,
+};
 
 /// Which half of a cell the selection anchor sits in.
 /// Used for sub-cell precision when normalizing selection boundaries.
@@ -23,6 +25,27 @@ pub enum SelectionType {
     VisualBlock,
 }
 
+/// How a copy turns a selection into text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMode {
+    /// Join soft-wrapped rows into one line and trim trailing padding.
+    Logical,
+    /// Copy each screen row exactly as shown, alignment spaces included.
+    Grid,
+}
+
+impl CopyMode {
+    /// Convert a config string ("logical", "grid") to a CopyMode.
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "logical" => Some(CopyMode::Logical),
+            "grid" => Some(CopyMode::Grid),
+            _ => None,
+        }
+    }
+}
+
 /// A text selection region defined by start and end cell coordinates.
 /// Row coordinates are absolute (negative = scrollback history, 0+ = screen).
 /// `abs_row = viewport_row as i32 - display_offset as i32`
@@ -88,25 +111,85 @@ pub fn find_word_boundaries(
     cols: usize,
 ) -> (usize, usize) {
     let row_start = row * cols;
-    let ch = cells[row_start + col].ch;
+    let row_cells = &cells[row_start..row_start + cols];
+    // A click on the right half of a wide character lands on the character
+    let col = wide_char_start(row_cells, col);
 
-    if !is_word_char(ch) {
-        return (col, col);
+    if !is_word_cell(row_cells, col) {
+        return (col, wide_char_end(row_cells, col));
     }
 
     let mut start = col;
-    while start > 0 && is_word_char(cells[row_start + start - 1].ch) {
+    while start > 0 && is_word_cell(row_cells, start - 1) {
         start -= 1;
     }
 
     let mut end = col;
-    while end + 1 < cols && is_word_char(cells[row_start + end + 1].ch) {
+    while end + 1 < cols && is_word_cell(row_cells, end + 1) {
         end += 1;
     }
 
     (start, end)
 }
 
+/// Word character test for one cell; a wide-char spacer takes the class of
+/// the character it belongs to.
+fn is_word_cell(row: &[GridCell], col: usize) -> bool {
+    is_word_char(row[wide_char_start(row, col)].ch)
+}
+
+/// Column of the character covering `col`: one to the left on a wide-char spacer.
+fn wide_char_start(row: &[GridCell], col: usize) -> usize {
+    let is_spacer = row[col].flags & CELL_FLAG_WIDE_SPACER != 0;
+    if is_spacer && col > 0 && row[col - 1].flags & CELL_FLAG_WIDE != 0 {
+        col - 1
+    } else {
+        col
+    }
+}
+
+/// Last column of the character at `col`: its spacer for a wide character.
+fn wide_char_end(row: &[GridCell], col: usize) -> usize {
+    if row[col].flags & CELL_FLAG_WIDE != 0 && col + 1 < row.len() {
+        col + 1
+    } else {
+        col
+    }
+}
+
+/// Text of columns `col_start..=col_end` of the row at `row_offset`. Spacer
+/// cells are skipped and a range starting on the right half of a wide
+/// character takes in the whole character, so wide text is never split or
+/// doubled.
+fn row_text(cells: &[GridCell], row_offset: usize, cols: usize, col_start: usize, col_end: usize) -> String {
+    let Some(row) = cells.get(row_offset..row_offset + cols) else {
+        return String::new();
+    };
+    if col_start >= cols {
+        return String::new();
+    }
+    (wide_char_start(row, col_start)..=col_end.min(cols - 1))
+        .map(|col| row[col])
+        .filter(|cell| cell.flags & CELL_FLAG_WIDE_SPACER == 0)
+        .map(|cell| cell.ch)
+        .collect()
+}
+
+/// Extend the selection flag across both halves of every wide character in
+/// the row at `row_offset`, so one is never highlighted without the other.
+fn select_wide_pairs(cells: &mut [GridCell], row_offset: usize, cols: usize) {
+    for col in 0..cols.saturating_sub(1) {
+        let (wide, spacer) = (row_offset + col, row_offset + col + 1);
+        if cells[wide].flags & CELL_FLAG_WIDE == 0 {
+            continue;
+        }
+        if (cells[wide].flags | cells[spacer].flags) & CELL_FLAG_SELECTED != 0 {
+            cells[wide].flags |= CELL_FLAG_SELECTED;
+            cells[spacer].flags |= CELL_FLAG_SELECTED;
+        }
+    }
+}
+
 /// Normalize selection so start is before end in reading order.
 /// Returns ((row, col), (row, col)) — purely positional, no Side adjustments.
 /// This ensures symmetric results regardless of drag direction.
@@ -148,12 +231,14 @@ pub fn selection_contains(selection: &Selection, row: i32, col: usize) -> bool {
 }
 
 /// Extract the selected text from grid cells as a UTF-8 string.
-/// Trailing spaces per line are trimmed; lines are joined with '\n'.
+/// Trailing spaces per line are trimmed; lines are joined with '\n' except
+/// where a row soft-wraps, so wrapped logical lines copy as one line.
 /// `display_offset` converts absolute rows to viewport rows for cell lookup.
 pub fn selected_text(cells: &[GridCell], selection: &Selection, cols: usize, display_offset: usize) -> String {
     let (start, end) = normalize(selection);
     let rows = cells.len() / cols;
-    let mut lines = Vec::new();
+    let last_row = end.0.min(rows as i32 - 1 - display_offset as i32);
+    let mut text = String::new();
 
     for abs_row in start.0..=end.0 {
         let vp_row = abs_row + display_offset as i32;
@@ -165,16 +250,31 @@ pub fn selected_text(cells: &[GridCell], selection: &Selection, cols: usize, dis
         let col_end = if abs_row == end.0 { end.1 } else { cols - 1 };
         let row_offset = vp_row * cols;
 
-        let mut line = String::new();
-        for col in col_start..=col_end {
-            if row_offset + col < cells.len() {
-                line.push(cells[row_offset + col].ch);
-            }
-        }
-        lines.push(line.trim_end().to_string());
+        let line = row_text(cells, row_offset, cols, col_start, col_end);
+        push_row(&mut text, &line, row_is_wrapped(cells, row_offset, cols), abs_row == last_row);
     }
 
-    lines.join("\n")
+    text
+}
+
+/// True if the row soft-wraps into the next one (logical line continues).
+fn row_is_wrapped(cells: &[GridCell], row_offset: usize, cols: usize) -> bool {
+    cells
+        .get(row_offset + cols - 1)
+        .is_some_and(|c| c.flags & CELL_FLAG_WRAPLINE != 0)
+}
+
+/// Append a row to copied text: wrapped rows join the next row verbatim,
+/// others are trimmed and newline-terminated.
+pub(crate) fn push_row(text: &mut String, line: &str, wrapped: bool, is_last: bool) {
+    if wrapped && !is_last {
+        text.push_str(line);
+    } else {
+        text.push_str(line.trim_end());
+        if !is_last {
+            text.push('\n');
+        }
+    }
 }
 
 /// Return the column index of the rightmost non-empty cell in a grid row.
@@ -182,8 +282,8 @@ pub fn selected_text(cells: &[GridCell], selection: &Selection, cols: usize, dis
 fn last_occupied_column(cells: &[GridCell], row_offset: usize, cols: usize) -> Option<usize> {
     for col in (0..cols).rev() {
         if row_offset + col < cells.len() {
-            let ch = cells[row_offset + col].ch;
-            if ch != ' ' && ch != '\0' {
+            let cell = cells[row_offset + col];
+            if cell.is_occupied() && cell.ch != '\0' {
                 return Some(col);
             }
         }
@@ -212,6 +312,7 @@ pub fn apply_selection_flags(cells: &mut [GridCell], selection: &Selection, cols
             for col in col_min..=col_max.min(cols - 1) {
                 cells[row_offset + col].flags |= CELL_FLAG_SELECTED;
             }
+            select_wide_pairs(cells, row_offset, cols);
         }
         return;
     }
@@ -252,6 +353,7 @@ pub fn apply_selection_flags(cells: &mut [GridCell], selection: &Selection, cols
         for col in col_start..=col_end.min(cols - 1) {
             cells[row_offset + col].flags |= CELL_FLAG_SELECTED;
         }
+        select_wide_pairs(cells, row_offset, cols);
     }
 }
 
@@ -270,12 +372,7 @@ pub fn selected_text_block(cells: &[GridCell], selection: &Selection, cols: usiz
             continue;
         }
         let row_offset = vp_row as usize * cols;
-        let mut line = String::new();
-        for col in col_min..=col_max {
-            if row_offset + col < cells.len() {
-                line.push(cells[row_offset + col].ch);
-            }
-        }
+        let line = row_text(cells, row_offset, cols, col_min, col_max);
         lines.push(line.trim_end().to_string());
     }
 
@@ -294,12 +391,7 @@ pub fn selected_text_lines(cells: &[GridCell], selection: &Selection, cols: usiz
             continue;
         }
         let row_offset = vp_row as usize * cols;
-        let mut line = String::new();
-        for col in 0..cols {
-            if row_offset + col < cells.len() {
-                line.push(cells[row_offset + col].ch);
-            }
-        }
+        let line = row_text(cells, row_offset, cols, 0, cols - 1);
         lines.push(line.trim_end().to_string());
     }
 
@@ -488,6 +580,36 @@ mod tests {
         assert_eq!(text, "world\nsecond");
     }
 
+    #[test]
+    fn selected_text_joins_soft_wrapped_rows() {
+        let mut cells = make_row("abcde", 5);
+        cells[4].flags |= CELL_FLAG_WRAPLINE;
+        cells.extend(make_row("fg", 5));
+        cells.extend(make_row("next", 5));
+        let sel = Selection {
+            start: (0, 0),
+            end: (2, 4),
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        };
+        assert_eq!(selected_text(&cells, &sel, 5, 0), "abcdefg\nnext");
+    }
+
+    #[test]
+    fn selected_text_end_below_viewport_has_no_trailing_newline() {
+        let mut cells = make_row("one", 5);
+        cells.extend(make_row("two", 5));
+        let sel = Selection {
+            start: (0, 0),
+            end: (5, 4),
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        };
+        assert_eq!(selected_text(&cells, &sel, 5, 0), "one\ntwo");
+    }
+
     // ── Apply selection flags ──────────────────────────────────────
 
     #[test]
@@ -752,4 +874,64 @@ mod tests {
                 "line selection: col {} should be selected", col);
         }
     }
+
+
+    // ── Wide characters ─────────────────────────────────────────────
+
+    /// Row where every non-ASCII char is double-width: a WIDE cell plus a spacer.
+    fn make_wide_row(text: &str, cols: usize) -> Vec<GridCell> {
+        let mut cells = Vec::with_capacity(cols);
+        for cell in make_row(text, text.chars().count()) {
+            if cell.ch.is_ascii() {
+                cells.push(cell);
+            } else {
+                cells.push(GridCell { flags: CELL_FLAG_WIDE, ..cell });
+                cells.push(GridCell { ch: ' ', flags: CELL_FLAG_WIDE_SPACER, ..cell });
+            }
+        }
+        cells.resize(cols, make_row(" ", 1)[0]);
+        cells
+    }
+
+    fn range(start: (i32, usize), end: (i32, usize)) -> Selection {
+        Selection {
+            start,
+            end,
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        }
+    }
+
+    #[test]
+    fn wide_chars_copy_without_spacers() {
+        let cells = make_wide_row("a日本b", 10);
+        assert_eq!(selected_text(&cells, &range((0, 0), (0, 9)), 10, 0), "a日本b");
+    }
+
+    #[test]
+    fn selection_starting_on_spacer_keeps_whole_char() {
+        let cells = make_wide_row("a日本b", 10);
+        // Column 2 is the right half of 日
+        assert_eq!(selected_text(&cells, &range((0, 2), (0, 3)), 10, 0), "日本");
+        let block = Selection { selection_type: SelectionType::VisualBlock, ..range((0, 2), (0, 3)) };
+        assert_eq!(selected_text_block(&cells, &block, 10, 0), "日本");
+    }
+
+    #[test]
+    fn selection_flags_cover_both_halves() {
+        let mut cells = make_wide_row("a日本b", 10);
+        apply_selection_flags(&mut cells, &range((0, 2), (0, 3)), 10, 0);
+        let selected: Vec<usize> = (0..10).filter(|&c| cells[c].flags & CELL_FLAG_SELECTED != 0).collect();
+        assert_eq!(selected, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn word_boundaries_span_wide_chars() {
+        let cells = make_wide_row("x 日本語 y", 12);
+        // Click on the spacer of 本
+        assert_eq!(find_word_boundaries(&cells, 0, 5, 12), (2, 7));
+        let cells = make_wide_row("「日」", 8);
+        assert_eq!(find_word_boundaries(&cells, 0, 1, 8), (0, 1));
+    }
 }
@@ -0,0 +1,350 @@
+// Touchscreen gestures: tap, long-press, one-finger drag (selection) and
+// two-finger scroll, recognized from raw winit touch events.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use winit::event::TouchPhase;
+
+use crate::input::selection::{normalize, Selection};
+
+/// Movement in pixels before a touch stops being a tap.
+pub const TAP_SLOP: f32 = 10.0;
+
+/// How long a still finger must be held to count as a long-press.
+pub const LONG_PRESS: Duration = Duration::from_millis(500);
+
+/// Touch radius around a selection handle, in physical pixels.
+pub const HANDLE_HIT_RADIUS: f32 = 24.0;
+
+/// Diameter of the drawn selection handle, in physical pixels.
+pub const HANDLE_SIZE: f32 = 12.0;
+
+/// A recognized gesture, in window pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchAction {
+    /// Quick touch without movement: focus and position like a click.
+    Tap { x: f32, y: f32 },
+    /// Finger held still: open the context menu.
+    LongPress { x: f32, y: f32 },
+    /// One finger started dragging from this origin.
+    DragStart { x: f32, y: f32 },
+    /// The dragging finger moved.
+    DragMove { x: f32, y: f32 },
+    /// The dragging finger lifted.
+    DragEnd { x: f32, y: f32 },
+    /// Two-finger vertical scroll by `dy` pixels (positive = content down).
+    Scroll { dy: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gesture {
+    Idle,
+    /// One finger down, not yet moved beyond the tap slop.
+    Pending { id: u64, origin: (f32, f32), started: Instant },
+    Dragging { id: u64 },
+    Scrolling,
+    /// Gesture already handled (long-press fired, or scroll lost a finger);
+    /// ignore input until every finger lifts.
+    Consumed,
+}
+
+/// Gesture recognizer for one window.
+#[derive(Debug)]
+pub struct TouchState {
+    points: HashMap<u64, (f32, f32)>,
+    gesture: Gesture,
+}
+
+impl Default for TouchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TouchState {
+    pub fn new() -> Self {
+        Self {
+            points: HashMap::new(),
+            gesture: Gesture::Idle,
+        }
+    }
+
+    /// Feed a raw touch event; returns the gesture actions it completes.
+    pub fn on_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        x: f32,
+        y: f32,
+        now: Instant,
+    ) -> Vec<TouchAction> {
+        let mut actions = Vec::new();
+        match phase {
+            TouchPhase::Started => {
+                self.points.insert(id, (x, y));
+                match self.points.len() {
+                    1 => {
+                        self.gesture = Gesture::Pending { id, origin: (x, y), started: now };
+                    }
+                    2 => {
+                        if let Gesture::Dragging { id: drag_id } = self.gesture {
+                            let (dx, dy) = self.points.get(&drag_id).copied().unwrap_or((x, y));
+                            actions.push(TouchAction::DragEnd { x: dx, y: dy });
+                        }
+                        if self.gesture != Gesture::Consumed {
+                            self.gesture = Gesture::Scrolling;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            TouchPhase::Moved => {
+                let Some(old) = self.points.insert(id, (x, y)) else {
+                    return actions;
+                };
+                match self.gesture {
+                    Gesture::Pending { id: gid, origin, .. }
+                        if gid == id && distance(origin, (x, y)) > TAP_SLOP =>
+                    {
+                        self.gesture = Gesture::Dragging { id };
+                        actions.push(TouchAction::DragStart { x: origin.0, y: origin.1 });
+                        actions.push(TouchAction::DragMove { x, y });
+                    }
+                    Gesture::Dragging { id: gid } if gid == id => {
+                        actions.push(TouchAction::DragMove { x, y });
+                    }
+                    Gesture::Scrolling => {
+                        // Each finger contributes its share, so two fingers
+                        // moving together scroll by their common distance.
+                        let dy = (y - old.1) / self.points.len() as f32;
+                        if dy != 0.0 {
+                            actions.push(TouchAction::Scroll { dy });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.points.remove(&id);
+                match self.gesture {
+                    Gesture::Pending { id: gid, origin, started } if gid == id => {
+                        if phase == TouchPhase::Ended {
+                            if now.saturating_duration_since(started) >= LONG_PRESS {
+                                actions.push(TouchAction::LongPress { x: origin.0, y: origin.1 });
+                            } else {
+                                actions.push(TouchAction::Tap { x: origin.0, y: origin.1 });
+                            }
+                        }
+                        self.gesture = Gesture::Idle;
+                    }
+                    Gesture::Dragging { id: gid } if gid == id => {
+                        actions.push(TouchAction::DragEnd { x, y });
+                        self.gesture = Gesture::Idle;
+                    }
+                    Gesture::Scrolling if self.points.len() < 2 => {
+                        self.gesture = Gesture::Consumed;
+                    }
+                    _ => {}
+                }
+                if self.points.is_empty() {
+                    self.gesture = Gesture::Idle;
+                }
+            }
+        }
+        actions
+    }
+
+    /// Fire a long-press once the finger has been held still long enough.
+    pub fn poll(&mut self, now: Instant) -> Option<TouchAction> {
+        if let Gesture::Pending { origin, started, .. } = self.gesture {
+            if now.saturating_duration_since(started) >= LONG_PRESS {
+                self.gesture = Gesture::Consumed;
+                return Some(TouchAction::LongPress { x: origin.0, y: origin.1 });
+            }
+        }
+        None
+    }
+
+    /// Whether a long-press could still fire (the caller keeps polling).
+    pub fn awaiting_long_press(&self) -> bool {
+        matches!(self.gesture, Gesture::Pending { .. })
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Which end of a selection a touch handle moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionHandle {
+    Start,
+    End,
+}
+
+/// Centers of the start and end handles of `selection` in pane-local pixels
+/// (relative to the text origin). The start handle sits on the top-left
+/// corner of the first cell and the end handle on the bottom-right corner of
+/// the last, so neither covers selected text.
+pub fn handle_centers(
+    selection: &Selection,
+    cell_width: f32,
+    cell_height: f32,
+    display_offset: usize,
+) -> [(f32, f32); 2] {
+    let ((start_row, start_col), (end_row, end_col)) = normalize(selection);
+    let row_top = |abs_row: i32| (abs_row + display_offset as i32) as f32 * cell_height;
+    [
+        (start_col as f32 * cell_width, row_top(start_row)),
+        ((end_col + 1) as f32 * cell_width, row_top(end_row) + cell_height),
+    ]
+}
+
+/// The handle within `HANDLE_HIT_RADIUS` of `point`, preferring the closer.
+pub fn hit_handle(centers: &[(f32, f32); 2], point: (f32, f32)) -> Option<SelectionHandle> {
+    let start = distance(centers[0], point);
+    let end = distance(centers[1], point);
+    if start.min(end) > HANDLE_HIT_RADIUS {
+        None
+    } else if start <= end {
+        Some(SelectionHandle::Start)
+    } else {
+        Some(SelectionHandle::End)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::selection::{SelectionType, Side};
+
+    fn ms(t: Instant, n: u64) -> Instant {
+        t + Duration::from_millis(n)
+    }
+
+    // ── Tap and long-press ──────────────────────────────────────────
+
+    #[test]
+    fn quick_touch_is_tap() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        assert!(touch.on_touch(1, TouchPhase::Started, 50.0, 60.0, t).is_empty());
+        let actions = touch.on_touch(1, TouchPhase::Ended, 52.0, 61.0, ms(t, 100));
+        assert_eq!(actions, vec![TouchAction::Tap { x: 50.0, y: 60.0 }]);
+    }
+
+    #[test]
+    fn held_touch_fires_long_press_once() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        touch.on_touch(1, TouchPhase::Started, 50.0, 60.0, t);
+        assert!(touch.awaiting_long_press());
+        assert_eq!(touch.poll(ms(t, 100)), None);
+        assert_eq!(
+            touch.poll(ms(t, 600)),
+            Some(TouchAction::LongPress { x: 50.0, y: 60.0 })
+        );
+        // Lifting afterwards does not also tap
+        assert!(touch.on_touch(1, TouchPhase::Ended, 50.0, 60.0, ms(t, 700)).is_empty());
+    }
+
+    #[test]
+    fn cancelled_touch_does_nothing() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        touch.on_touch(1, TouchPhase::Started, 50.0, 60.0, t);
+        assert!(touch.on_touch(1, TouchPhase::Cancelled, 50.0, 60.0, ms(t, 50)).is_empty());
+    }
+
+    // ── Drag ────────────────────────────────────────────────────────
+
+    #[test]
+    fn moving_past_slop_starts_drag() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        touch.on_touch(1, TouchPhase::Started, 10.0, 10.0, t);
+        assert!(touch.on_touch(1, TouchPhase::Moved, 14.0, 10.0, t).is_empty());
+        let actions = touch.on_touch(1, TouchPhase::Moved, 40.0, 10.0, t);
+        assert_eq!(
+            actions,
+            vec![
+                TouchAction::DragStart { x: 10.0, y: 10.0 },
+                TouchAction::DragMove { x: 40.0, y: 10.0 },
+            ]
+        );
+        let actions = touch.on_touch(1, TouchPhase::Ended, 45.0, 12.0, t);
+        assert_eq!(actions, vec![TouchAction::DragEnd { x: 45.0, y: 12.0 }]);
+        assert!(!touch.awaiting_long_press());
+    }
+
+    // ── Two-finger scroll ───────────────────────────────────────────
+
+    #[test]
+    fn two_fingers_scroll_by_common_distance() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        touch.on_touch(1, TouchPhase::Started, 10.0, 100.0, t);
+        touch.on_touch(2, TouchPhase::Started, 60.0, 100.0, t);
+        let a = touch.on_touch(1, TouchPhase::Moved, 10.0, 120.0, t);
+        let b = touch.on_touch(2, TouchPhase::Moved, 60.0, 120.0, t);
+        let total: f32 = a
+            .iter()
+            .chain(&b)
+            .map(|action| match action {
+                TouchAction::Scroll { dy } => *dy,
+                other => panic!("unexpected {other:?}"),
+            })
+            .sum();
+        assert_eq!(total, 20.0);
+    }
+
+    #[test]
+    fn second_finger_ends_drag_and_scrolls() {
+        let mut touch = TouchState::new();
+        let t = Instant::now();
+        touch.on_touch(1, TouchPhase::Started, 10.0, 10.0, t);
+        touch.on_touch(1, TouchPhase::Moved, 40.0, 10.0, t);
+        let actions = touch.on_touch(2, TouchPhase::Started, 80.0, 10.0, t);
+        assert_eq!(actions, vec![TouchAction::DragEnd { x: 40.0, y: 10.0 }]);
+        // Lifting one finger leaves the rest of the gesture inert
+        touch.on_touch(2, TouchPhase::Ended, 80.0, 10.0, t);
+        assert!(touch.on_touch(1, TouchPhase::Moved, 10.0, 90.0, t).is_empty());
+        touch.on_touch(1, TouchPhase::Ended, 10.0, 90.0, t);
+        touch.on_touch(3, TouchPhase::Started, 5.0, 5.0, t);
+        assert!(touch.awaiting_long_press());
+    }
+
+    // ── Selection handles ───────────────────────────────────────────
+
+    fn selection() -> Selection {
+        Selection {
+            start: (4, 10),
+            end: (2, 3),
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        }
+    }
+
+    #[test]
+    fn handle_centers_follow_normalized_selection() {
+        let centers = handle_centers(&selection(), 8.0, 16.0, 0);
+        assert_eq!(centers[0], (3.0 * 8.0, 2.0 * 16.0));
+        assert_eq!(centers[1], (11.0 * 8.0, 5.0 * 16.0));
+    }
+
+    #[test]
+    fn handle_centers_account_for_scrollback() {
+        let centers = handle_centers(&selection(), 8.0, 16.0, 3);
+        assert_eq!(centers[0].1, 5.0 * 16.0);
+    }
+
+    #[test]
+    fn hit_handle_picks_nearest_within_radius() {
+        let centers = [(24.0, 40.0), (88.0, 72.0)];
+        assert_eq!(hit_handle(&centers, (30.0, 42.0)), Some(SelectionHandle::Start));
+        assert_eq!(hit_handle(&centers, (80.0, 70.0)), Some(SelectionHandle::End));
+        assert_eq!(hit_handle(&centers, (300.0, 300.0)), None);
+    }
+}
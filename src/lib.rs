@@ -1,26 +1,45 @@
+pub mod accessibility;
+pub mod bookmarks;
+pub mod cli;
+pub mod command_hint;
 pub mod command_palette;
 pub mod conductor;
 pub mod config;
 pub mod context_menu;
+pub mod control;
+pub mod daemon;
+pub mod directory_rules;
 pub mod file_browser;
 pub mod image_protocol;
 pub mod git_review;
+pub mod git_segment;
 pub mod header_bar;
+pub mod help;
 pub mod hotkey;
 pub mod input;
 pub mod link;
 pub mod markdown_preview;
+pub mod notes;
+pub mod prompt_indicator;
+pub mod redact;
 pub mod pane;
+pub mod pane_recording;
 pub mod platform;
+pub mod profiles;
 pub mod pty;
 pub mod renderer;
+pub mod replay;
+pub mod screenshot;
+pub mod scripting;
 pub mod scroll;
 pub mod search;
 pub mod session;
 pub mod shell_integration;
 pub mod split_panel;
 pub mod status_bar;
+pub mod suggestions;
 pub mod tab;
 pub mod terminal;
+pub mod theme_schedule;
 pub mod vi_mode;
 pub mod window;
@@ -1,40 +1,51 @@
+use std::ops::Range;
+
 use linkify::{LinkFinder, LinkKind as LinkifyKind};
 
 use super::{DetectedLink, LinkKind};
+use crate::terminal::text_map::TextMap;
 
 /// Detect URLs in terminal content lines using the linkify crate.
-pub fn detect_urls(lines: &[String]) -> Vec<DetectedLink> {
+/// A URL soft-wrapped over several rows is found whole.
+pub fn detect_urls(lines: &[TextMap]) -> Vec<DetectedLink> {
     let mut finder = LinkFinder::new();
     finder.kinds(&[LinkifyKind::Url]);
 
     let mut links = Vec::new();
-    for (row, line) in lines.iter().enumerate() {
-        for link in finder.links(line) {
-            let start_col = link.start();
-            let end_col = link.end().saturating_sub(1); // inclusive end
-            links.push(DetectedLink {
-                kind: LinkKind::Url,
-                start: (row, start_col),
-                end: (row, end_col),
-                text: link.as_str().to_string(),
-            });
+    for line in lines {
+        for link in finder.links(line.text()) {
+            links.extend(placed_link(line, LinkKind::Url, link.start()..link.end()));
         }
     }
     links
 }
 
 /// Detect absolute file paths in terminal content lines.
-pub fn detect_paths(lines: &[String]) -> Vec<DetectedLink> {
+pub fn detect_paths(lines: &[TextMap]) -> Vec<DetectedLink> {
     let mut links = Vec::new();
-    for (row, line) in lines.iter().enumerate() {
-        links.extend(find_paths_in_line(row, line));
+    for line in lines {
+        links.extend(find_paths_in_line(line));
     }
     links
 }
 
-fn find_paths_in_line(row: usize, line: &str) -> Vec<DetectedLink> {
+/// The link showing `line`'s text at `bytes`, in grid cells.
+fn placed_link(line: &TextMap, kind: LinkKind, bytes: Range<usize>) -> Option<DetectedLink> {
+    let cells = line.cell_range(bytes.clone())?;
+    let (end_row, end_col) = cells.last_cell(); // inclusive end
+    Some(DetectedLink {
+        kind,
+        start: (cells.start_row as usize, cells.start_col),
+        end: (end_row as usize, end_col),
+        text: line.text()[bytes].to_string(),
+    })
+}
+
+fn find_paths_in_line(line: &TextMap) -> Vec<DetectedLink> {
     let mut results = Vec::new();
-    let chars: Vec<char> = line.chars().collect();
+    let text = line.text();
+    let (bytes, chars): (Vec<usize>, Vec<char>) = text.char_indices().unzip();
+    let byte_at = |i: usize| bytes.get(i).copied().unwrap_or(text.len());
     let len = chars.len();
     let mut i = 0;
 
@@ -94,12 +105,7 @@ fn find_paths_in_line(row: usize, line: &str) -> Vec<DetectedLink> {
             continue;
         }
 
-        results.push(DetectedLink {
-            kind: LinkKind::FilePath,
-            start: (row, start_col),
-            end: (row, end.saturating_sub(1)),
-            text: path_text,
-        });
+        results.extend(placed_link(line, LinkKind::FilePath, byte_at(start_col)..byte_at(end)));
     }
 
     results
@@ -137,6 +143,14 @@ fn is_false_positive(path: &str) -> bool {
 mod tests {
     use super::*;
 
+    fn detect_urls(lines: &[String]) -> Vec<DetectedLink> {
+        super::detect_urls(&TextMap::rows(lines))
+    }
+
+    fn detect_paths(lines: &[String]) -> Vec<DetectedLink> {
+        super::detect_paths(&TextMap::rows(lines))
+    }
+
     // --- URL detection tests ---
 
     #[test]
@@ -327,4 +341,29 @@ mod tests {
         assert_eq!(links.len(), 1);
         assert_eq!(links[0].text, "/foo/bar");
     }
+
+    // --- Grid cell mapping ---
+
+    #[test]
+    fn links_after_wide_characters_use_grid_columns() {
+        // "日本" takes 4 cells in 6 bytes, "→" 1 cell in 3 bytes
+        let lines = vec!["日本 https://x.com → /tmp/a.log".to_string()];
+        let urls = detect_urls(&lines);
+        assert_eq!(urls[0].start, (0, 5));
+        assert_eq!(urls[0].end, (0, 17));
+        let paths = detect_paths(&lines);
+        assert_eq!(paths[0].text, "/tmp/a.log");
+        assert_eq!((paths[0].start, paths[0].end), ((0, 21), (0, 30)));
+    }
+
+    #[test]
+    fn wrapped_url_spans_its_rows() {
+        let mut line = TextMap::new();
+        for (i, c) in "see https://example.com/long".chars().enumerate() {
+            line.push(1 + i as i32 / 10, i % 10, c, 1);
+        }
+        let links = super::detect_urls(&[line]);
+        assert_eq!(links[0].text, "https://example.com/long");
+        assert_eq!((links[0].start, links[0].end), ((1, 4), (3, 7)));
+    }
 }
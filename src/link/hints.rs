@@ -0,0 +1,388 @@
+// Hint mode: label every URL, file path, git SHA and IP address on screen
+// with a short key sequence, then open or copy the one whose label is typed.
+// Fully keyboard driven, like kitty's `open_url` hints. Quick select labels
+// the matches of the `[quick_select]` patterns instead, for copying.
+
+use std::ops::Range;
+
+use super::detector::{detect_paths, detect_urls};
+use super::DetectedLink;
+use crate::terminal::text_map::TextMap;
+
+/// Keys labels are made of, home row first.
+pub const LABEL_ALPHABET: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+/// What choosing a hint does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintAction {
+    /// Open URLs and paths; SHAs and addresses, which can't be opened, are copied.
+    Open,
+    Copy,
+}
+
+/// What a hint points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintKind {
+    Url,
+    Path,
+    GitSha,
+    IpAddress,
+    /// A `[quick_select]` pattern match.
+    Pattern,
+}
+
+/// A labelled piece of text on screen, from column `start` of `row` to
+/// column `end` (inclusive) of `end_row`, which is a later row for text
+/// soft-wrapped over several.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub label: String,
+    pub kind: HintKind,
+    pub row: usize,
+    pub start: usize,
+    pub end_row: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Something to label, before it has a label.
+struct Target {
+    kind: HintKind,
+    /// First and last cell, as (row, col).
+    start: (usize, usize),
+    end: (usize, usize),
+    text: String,
+}
+
+impl Target {
+    fn from_link(kind: HintKind, link: DetectedLink) -> Self {
+        Self { kind, start: link.start, end: link.end, text: link.text }
+    }
+
+    /// The text of `line` at `bytes`, in grid cells.
+    fn in_line(kind: HintKind, line: &TextMap, bytes: Range<usize>) -> Option<Self> {
+        let cells = line.cell_range(bytes.clone())?;
+        let (end_row, end_col) = cells.last_cell();
+        Some(Self {
+            kind,
+            start: (cells.start_row as usize, cells.start_col),
+            end: (end_row as usize, end_col),
+            text: line.text()[bytes].to_string(),
+        })
+    }
+
+    fn overlaps(&self, others: &[Target]) -> bool {
+        others.iter().any(|other| self.start <= other.end && self.end >= other.start)
+    }
+}
+
+/// Result of typing a key in hint mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintInput {
+    /// More keys are needed.
+    Pending,
+    Chosen(Hint),
+    /// No label starts with what was typed; the key is ignored.
+    NoMatch,
+}
+
+/// Hint mode over one pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintMode {
+    pub action: HintAction,
+    hints: Vec<Hint>,
+    typed: String,
+}
+
+impl HintMode {
+    /// Label the targets in `lines` (see `grid_bridge::extract_text_maps`).
+    /// None when there is nothing to label.
+    pub fn new(lines: &[TextMap], action: HintAction) -> Option<Self> {
+        Self::labelled(find_targets(lines), action)
+    }
+
+    /// Label the matches of `patterns` in `lines` for copying. None when
+    /// nothing matches.
+    pub fn from_patterns(lines: &[TextMap], patterns: &[regex::Regex]) -> Option<Self> {
+        Self::labelled(find_pattern_targets(lines, patterns), HintAction::Copy)
+    }
+
+    fn labelled(targets: Vec<Target>, action: HintAction) -> Option<Self> {
+        if targets.is_empty() {
+            return None;
+        }
+        let hints = labels(targets.len())
+            .into_iter()
+            .zip(targets)
+            .map(|(label, target)| Hint {
+                label,
+                kind: target.kind,
+                row: target.start.0,
+                start: target.start.1,
+                end_row: target.end.0,
+                end: target.end.1,
+                text: target.text,
+            })
+            .collect();
+        Some(Self { action, hints, typed: String::new() })
+    }
+
+    /// Hints whose label starts with what was typed so far.
+    pub fn visible(&self) -> impl Iterator<Item = &Hint> {
+        self.hints.iter().filter(|hint| hint.label.starts_with(&self.typed))
+    }
+
+    /// Characters of each visible label already typed.
+    pub fn typed_len(&self) -> usize {
+        self.typed.len()
+    }
+
+    /// Type one label key.
+    pub fn push(&mut self, key: char) -> HintInput {
+        let mut typed = self.typed.clone();
+        typed.push(key.to_ascii_lowercase());
+        let mut matching = self.hints.iter().filter(|hint| hint.label.starts_with(&typed));
+        let Some(first) = matching.next() else {
+            return HintInput::NoMatch;
+        };
+        if first.label == typed {
+            return HintInput::Chosen(first.clone());
+        }
+        self.typed = typed;
+        HintInput::Pending
+    }
+
+    /// Take back the last key typed.
+    pub fn backspace(&mut self) {
+        self.typed.pop();
+    }
+}
+
+/// `count` labels of equal length, so none is a prefix of another.
+pub fn labels(count: usize) -> Vec<String> {
+    let alphabet: Vec<char> = LABEL_ALPHABET.chars().collect();
+    let mut length = 1;
+    while alphabet.len().pow(length as u32) < count {
+        length += 1;
+    }
+    (0..count)
+        .map(|mut n| {
+            let mut label = vec![alphabet[0]; length];
+            for slot in label.iter_mut().rev() {
+                *slot = alphabet[n % alphabet.len()];
+                n /= alphabet.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+/// URLs, paths, git SHAs and IPv4 addresses in reading order. Text inside
+/// an earlier kind's target (the address in a URL) isn't labelled again.
+fn find_targets(lines: &[TextMap]) -> Vec<Target> {
+    let mut targets: Vec<Target> = detect_urls(lines)
+        .into_iter()
+        .map(|link| Target::from_link(HintKind::Url, link))
+        .chain(detect_paths(lines).into_iter().map(|link| Target::from_link(HintKind::Path, link)))
+        .collect();
+    for line in lines {
+        for (start, word) in words(line.text()) {
+            let kind = if is_git_sha(word) {
+                HintKind::GitSha
+            } else if is_ipv4(word) {
+                HintKind::IpAddress
+            } else {
+                continue;
+            };
+            let Some(target) = Target::in_line(kind, line, start..start + word.len()) else {
+                continue;
+            };
+            if !target.overlaps(&targets) {
+                targets.push(target);
+            }
+        }
+    }
+    targets.sort_by_key(|target| target.start);
+    targets
+}
+
+/// Matches of `patterns` in reading order. A pattern with a capture group
+/// labels only its first group; a match overlapping an earlier pattern's is
+/// skipped, so list specific patterns (UUIDs) before broad ones (hex runs).
+fn find_pattern_targets(lines: &[TextMap], patterns: &[regex::Regex]) -> Vec<Target> {
+    let mut targets = Vec::new();
+    for re in patterns {
+        for line in lines {
+            for caps in re.captures_iter(line.text()) {
+                let Some(found) = caps.get(1).or_else(|| caps.get(0)).filter(|m| !m.is_empty()) else {
+                    continue;
+                };
+                let Some(target) = Target::in_line(HintKind::Pattern, line, found.range()) else {
+                    continue;
+                };
+                if !target.overlaps(&targets) {
+                    targets.push(target);
+                }
+            }
+        }
+    }
+    targets.sort_by_key(|target| target.start);
+    targets
+}
+
+/// Runs of letters, digits, dots and colons with their starting byte,
+/// trailing dots and colons dropped.
+fn words(line: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (byte, c) in line.char_indices() {
+        let in_word = c.is_ascii_alphanumeric() || c == '.' || c == ':';
+        match (in_word, start) {
+            (true, None) => start = Some(byte),
+            (false, Some(byte0)) => {
+                words.push((byte0, line[byte0..byte].trim_end_matches(['.', ':'])));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(byte0) = start {
+        words.push((byte0, line[byte0..].trim_end_matches(['.', ':'])));
+    }
+    words.retain(|(_, word)| !word.is_empty());
+    words
+}
+
+/// 7 to 40 hex digits with at least one letter and one digit.
+fn is_git_sha(word: &str) -> bool {
+    (7..=40).contains(&word.len())
+        && word.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        && word.chars().any(|c| c.is_ascii_digit())
+        && word.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Dotted IPv4 address, optionally with a port.
+fn is_ipv4(word: &str) -> bool {
+    let (address, port) = match word.split_once(':') {
+        Some((address, port)) => (address, Some(port)),
+        None => (word, None),
+    };
+    if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+        return false;
+    }
+    let octets: Vec<&str> = address.split('.').collect();
+    octets.len() == 4 && octets.iter().all(|octet| !octet.is_empty() && octet.len() <= 3 && octet.parse::<u8>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<TextMap> {
+        text.iter().enumerate().map(|(row, line)| TextMap::from_text(row as i32, line)).collect()
+    }
+
+    #[test]
+    fn labels_are_prefix_free() {
+        assert_eq!(labels(3), ["a", "s", "d"]);
+        let many = labels(30);
+        assert_eq!(many.len(), 30);
+        assert!(many.iter().all(|label| label.len() == 2));
+        assert_eq!(many[0], "aa");
+        assert_eq!(many[1], "as");
+        assert!(labels(0).is_empty());
+    }
+
+    #[test]
+    fn finds_each_kind_in_reading_order() {
+        let mode = HintMode::new(
+            &lines(&[
+                "commit 3f2a9c1d merged",
+                "see https://10.0.0.1/docs and /etc/hosts",
+                "ping 192.168.1.20:8080 ok",
+            ]),
+            HintAction::Open,
+        )
+        .unwrap();
+        let found: Vec<_> = mode.visible().map(|hint| (hint.kind, hint.row, hint.start, hint.text.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (HintKind::GitSha, 0, 7, "3f2a9c1d"),
+                (HintKind::Url, 1, 4, "https://10.0.0.1/docs"),
+                (HintKind::Path, 1, 30, "/etc/hosts"),
+                (HintKind::IpAddress, 2, 5, "192.168.1.20:8080"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_words_that_only_look_close() {
+        assert!(HintMode::new(&lines(&["added 1234567 deadbeef 1.2.3 999.1.1.1"]), HintAction::Copy).is_none());
+    }
+
+    #[test]
+    fn url_columns_count_characters() {
+        let mode = HintMode::new(&lines(&["→ https://example.com"]), HintAction::Open).unwrap();
+        let hint = mode.visible().next().unwrap();
+        assert_eq!((hint.start, hint.end), (2, 20));
+    }
+
+    #[test]
+    fn hints_follow_wide_characters_and_wraps() {
+        // "日本" is two double-width cells; the SHA wraps after column 9
+        let mut line = TextMap::from_text(0, "日本 ");
+        for (i, c) in "3f2a9c1d".chars().enumerate() {
+            line.push((5 + i as i32) / 10, (5 + i) % 10, c, 1);
+        }
+        let mode = HintMode::new(&[line], HintAction::Copy).unwrap();
+        let hint = mode.visible().next().unwrap();
+        assert_eq!(hint.text, "3f2a9c1d");
+        assert_eq!((hint.row, hint.start, hint.end_row, hint.end), (0, 5, 1, 2));
+    }
+
+    #[test]
+    fn patterns_label_matches_and_groups() {
+        let patterns = [
+            regex::Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}").unwrap(),
+            regex::Regex::new(r"\b[0-9a-f]{12}\b").unwrap(),
+            regex::Regex::new(r"image: (\S+)").unwrap(),
+        ];
+        let mode = HintMode::from_patterns(
+            &lines(&[
+                "→ id 123e4567-e89b-12d3-a456-426614174000",
+                "container 4f2a9c1d0b3e image: nginx:1.25",
+            ]),
+            &patterns,
+        )
+        .unwrap();
+        assert_eq!(mode.action, HintAction::Copy);
+        let found: Vec<_> = mode.visible().map(|hint| (hint.row, hint.start, hint.end, hint.text.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (0, 5, 40, "123e4567-e89b-12d3-a456-426614174000"),
+                (1, 10, 21, "4f2a9c1d0b3e"),
+                (1, 30, 39, "nginx:1.25"),
+            ]
+        );
+        assert!(HintMode::from_patterns(&lines(&["nothing here"]), &patterns).is_none());
+    }
+
+    #[test]
+    fn typing_narrows_then_chooses() {
+        let rows: Vec<String> = (0..30).map(|i| format!("host 10.0.0.{i}")).collect();
+        let mut mode = HintMode::new(&TextMap::rows(&rows), HintAction::Copy).unwrap();
+        assert_eq!(mode.push('x'), HintInput::NoMatch);
+        assert_eq!(mode.push('A'), HintInput::Pending);
+        assert_eq!(mode.visible().count(), 26);
+        assert_eq!(mode.typed_len(), 1);
+        mode.backspace();
+        assert_eq!(mode.visible().count(), 30);
+        assert_eq!(mode.push('s'), HintInput::Pending);
+        match mode.push('a') {
+            HintInput::Chosen(hint) => assert_eq!(hint.text, "10.0.0.26"),
+            other => panic!("expected a hint, got {other:?}"),
+        }
+    }
+}
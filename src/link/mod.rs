@@ -1,5 +1,9 @@
 pub mod detector;
+pub mod hints;
 pub mod opener;
+pub mod policy;
+
+use crate::terminal::text_map::TextMap;
 
 /// The kind of detected link.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,9 +57,9 @@ impl LinkDetector {
         }
     }
 
-    /// Scan the provided lines for URLs and file paths.
-    /// Each line corresponds to a terminal row.
-    pub fn scan(&mut self, lines: &[String]) {
+    /// Scan the provided lines for URLs and file paths (see
+    /// `grid_bridge::extract_text_maps`).
+    pub fn scan(&mut self, lines: &[TextMap]) {
         self.generation += 1;
         self.links.clear();
         self.links.extend(detector::detect_urls(lines));
@@ -184,7 +188,7 @@ mod tests {
             "Visit https://example.com for info".to_string(),
             "File at /usr/bin/cargo here".to_string(),
         ];
-        detector.scan(&lines);
+        detector.scan(&TextMap::rows(&lines));
         assert_eq!(detector.links().len(), 2);
         assert_eq!(detector.links()[0].kind, LinkKind::Url);
         assert_eq!(detector.links()[1].kind, LinkKind::FilePath);
@@ -194,7 +198,7 @@ mod tests {
     fn link_detector_link_at_returns_correct_link() {
         let mut detector = LinkDetector::new();
         let lines = vec!["abc https://x.com end".to_string()];
-        detector.scan(&lines);
+        detector.scan(&TextMap::rows(&lines));
         // 'h' of https starts at col 4
         let link = detector.link_at(0, 4);
         assert!(link.is_some());
@@ -206,7 +210,7 @@ mod tests {
     fn link_detector_link_at_returns_none_outside_link() {
         let mut detector = LinkDetector::new();
         let lines = vec!["abc https://x.com end".to_string()];
-        detector.scan(&lines);
+        detector.scan(&TextMap::rows(&lines));
         // col 0 is 'a', not in the URL
         assert!(detector.link_at(0, 0).is_none());
         // row 1 doesn't exist
@@ -216,11 +220,11 @@ mod tests {
     #[test]
     fn link_detector_rescan_replaces_old_links() {
         let mut detector = LinkDetector::new();
-        detector.scan(&vec!["https://first.com".to_string()]);
+        detector.scan(&[TextMap::from_text(0, "https://first.com")]);
         assert_eq!(detector.links().len(), 1);
         assert_eq!(detector.generation(), 1);
 
-        detector.scan(&vec!["https://second.com and https://third.com".to_string()]);
+        detector.scan(&[TextMap::from_text(0, "https://second.com and https://third.com")]);
         assert_eq!(detector.links().len(), 2);
         assert_eq!(detector.generation(), 2);
         assert_eq!(detector.links()[0].text, "https://second.com");
@@ -24,14 +24,42 @@ pub fn build_open_command(link: &DetectedLink) -> (String, Vec<String>) {
 
 /// Open a detected link using the appropriate system command.
 /// Spawns the process in the background (non-blocking).
-pub fn open_link(link: &DetectedLink) {
+///
+/// On Linux, URLs go through the XDG desktop portal. `activation_token`
+/// (from the window's startup-notify request) lets the opened application
+/// take focus.
+pub fn open_link(link: &DetectedLink, activation_token: Option<&str>) {
+    if link.kind == LinkKind::Url {
+        open_url(&link.text, activation_token);
+        return;
+    }
     let (program, args) = build_open_command(link);
     log::info!("Opening link: {} with {}", link.text, program);
 
-    match Command::new(&program).args(&args).spawn() {
-        Ok(_) => {}
-        Err(e) => {
-            log::error!("Failed to open link '{}' with {}: {}", link.text, program, e);
+    let mut cmd = Command::new(&program);
+    cmd.args(&args);
+    #[cfg(target_os = "linux")]
+    let spawned = crate::platform::linux::spawn_with_activation(&mut cmd, activation_token);
+    #[cfg(not(target_os = "linux"))]
+    let spawned = {
+        let _ = activation_token;
+        cmd.spawn()
+    };
+    if let Err(e) = spawned {
+        log::error!("Failed to open link '{}' with {}: {}", link.text, program, e);
+    }
+}
+
+/// Open a URL in the default browser (non-blocking).
+pub fn open_url(url: &str, activation_token: Option<&str>) {
+    log::info!("Opening URL: {url}");
+    #[cfg(target_os = "linux")]
+    crate::platform::linux::open_uri(url, activation_token);
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = activation_token;
+        if let Err(e) = Command::new(platform_open_command()).arg(url).spawn() {
+            log::error!("Failed to open URL '{url}': {e}");
         }
     }
 }
@@ -0,0 +1,160 @@
+// Which URLs open without asking. Links come from program output, which a
+// remote host or a file being catted controls, so only `links.allowed_schemes`
+// open straight away; other schemes (file://, custom app handlers) ask
+// first, and `links.blocked_schemes` (javascript:, data:) never open. Host
+// lists narrow this further for web links.
+
+use crate::config::types::LinksConfig;
+use crate::pane::PaneId;
+
+/// What to do with a URL the user asked to open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlVerdict {
+    Open,
+    /// Open only after the user confirms.
+    Ask,
+    Block,
+}
+
+/// A URL waiting for the user to confirm it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlPrompt {
+    pub pane: PaneId,
+    pub url: String,
+}
+
+impl UrlPrompt {
+    /// Prompt text shown in the pane.
+    pub fn prompt(&self) -> String {
+        format!("Open {}? Enter opens, Esc cancels", self.url)
+    }
+}
+
+/// Check `url` against the `[links]` scheme and host lists. Blocked
+/// entries win over allowed ones.
+pub fn verdict(url: &str, config: &LinksConfig) -> UrlVerdict {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return UrlVerdict::Ask;
+    };
+    let scheme = scheme.to_ascii_lowercase();
+    if config.blocked_schemes.iter().any(|s| s.eq_ignore_ascii_case(&scheme)) {
+        return UrlVerdict::Block;
+    }
+    let host = host(rest);
+    let host = host.as_deref();
+    if host.is_some_and(|host| config.blocked_hosts.iter().any(|pattern| host_matches(pattern, host))) {
+        return UrlVerdict::Block;
+    }
+    if !config.allowed_schemes.iter().any(|s| s.eq_ignore_ascii_case(&scheme)) || ambiguous_authority(rest) {
+        return UrlVerdict::Ask;
+    }
+    let host_allowed = config.allowed_hosts.is_empty()
+        || host.is_some_and(|host| config.allowed_hosts.iter().any(|pattern| host_matches(pattern, host)));
+    if host_allowed {
+        UrlVerdict::Open
+    } else {
+        UrlVerdict::Ask
+    }
+}
+
+/// The lowercase host of the part after the scheme's colon
+/// (`//user@host:port/path`), if it names one.
+fn host(rest: &str) -> Option<String> {
+    let authority = rest.strip_prefix("//")?.split(['/', '\\', '?', '#']).next()?;
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = match host_port.strip_prefix('[') {
+        // IPv6 literal
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => host_port.split(':').next()?,
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Whether a browser might find another host in the part after the
+/// scheme's colon than `host` does: backslashes (path separators to
+/// browsers), more than one `@`, or control or space characters.
+fn ambiguous_authority(rest: &str) -> bool {
+    if rest.starts_with('\\') || rest.starts_with("/\\") {
+        return true;
+    }
+    let Some(authority) = rest.strip_prefix("//").and_then(|r| r.split(['/', '?', '#']).next()) else {
+        return false;
+    };
+    authority.contains('\\')
+        || authority.matches('@').count() > 1
+        || authority.chars().any(|c| c.is_control() || c.is_whitespace())
+}
+
+/// `pattern` is a host name, or `*.domain` for the domain and every
+/// subdomain.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host == domain || host.ends_with(&format!(".{domain}")),
+        None => host == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_links_open_and_unusual_schemes_ask() {
+        let config = LinksConfig::default();
+        assert_eq!(verdict("https://example.com/a?b", &config), UrlVerdict::Open);
+        assert_eq!(verdict("HTTP://example.com", &config), UrlVerdict::Open);
+        assert_eq!(verdict("mailto:dev@example.com", &config), UrlVerdict::Open);
+        assert_eq!(verdict("file:///etc/passwd", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("vscode://file/tmp/x", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("no-scheme", &config), UrlVerdict::Ask);
+    }
+
+    #[test]
+    fn blocked_schemes_never_open() {
+        let config = LinksConfig::default();
+        assert_eq!(verdict("javascript:alert(1)", &config), UrlVerdict::Block);
+        assert_eq!(verdict("Data:text/html;base64,PHNjcmlwdD4=", &config), UrlVerdict::Block);
+    }
+
+    #[test]
+    fn host_lists_match_domains_and_subdomains() {
+        let config = LinksConfig {
+            allowed_hosts: vec!["*.example.com".into(), "localhost".into()],
+            blocked_hosts: vec!["evil.example.com".into()],
+            ..Default::default()
+        };
+        assert_eq!(verdict("https://example.com", &config), UrlVerdict::Open);
+        assert_eq!(verdict("https://docs.example.com/x", &config), UrlVerdict::Open);
+        assert_eq!(verdict("http://user@localhost:8080/", &config), UrlVerdict::Open);
+        assert_eq!(verdict("https://notexample.com", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("https://EVIL.example.com", &config), UrlVerdict::Block);
+    }
+
+    #[test]
+    fn ambiguous_authorities_ask() {
+        let config = LinksConfig {
+            allowed_hosts: vec!["*.example.com".into()],
+            ..Default::default()
+        };
+        // Browsers open evil.com: `\` ends the authority for them
+        assert_eq!(verdict("https://evil.com\\@docs.example.com/", &config), UrlVerdict::Ask);
+        assert_eq!(host("//evil.com\\@docs.example.com/").as_deref(), Some("evil.com"));
+        assert_eq!(verdict("https:\\\\docs.example.com/", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("https://a@evil.com@docs.example.com/", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("https://docs.example.com\t/", &config), UrlVerdict::Ask);
+        // Without a host list these still ask
+        let config = LinksConfig::default();
+        assert_eq!(verdict("https://evil.com\\@docs.example.com/", &config), UrlVerdict::Ask);
+        assert_eq!(verdict("https://user@docs.example.com/a\\b", &config), UrlVerdict::Open);
+    }
+
+    #[test]
+    fn host_parsing() {
+        assert_eq!(host("//a.b:80/p").as_deref(), Some("a.b"));
+        assert_eq!(host("//u:pw@Host?q").as_deref(), Some("host"));
+        assert_eq!(host("//[::1]:3000/").as_deref(), Some("::1"));
+        assert_eq!(host("dev@example.com"), None);
+        assert_eq!(host("///etc/passwd"), None);
+    }
+}
@@ -1,22 +1,316 @@
+use clap::Parser;
+use veloterm::cli::{Cli, CliCommand, Command, CtlCommand, RunArgs};
+use veloterm::config::paths;
 use veloterm::config::types::Config;
 use veloterm::window::{App, WindowConfig};
 
 fn main() {
-    // Handle --print-default-config before any other initialization
-    if std::env::args().any(|a| a == "--print-default-config") {
-        print!("{}", Config::print_default());
+    let cli = Cli::parse();
+    // --config or --profile, else ~/.config/veloterm/config.toml
+    let is_default_config = cli.config.is_none() && cli.profile.is_none();
+    let config_path =
+        match paths::resolve_config_path(&paths::config_dir(), cli.config.clone(), cli.profile.as_deref()) {
+            Ok(path) => path,
+            Err(e) => {
+                eprintln!("veloterm: {e}");
+                std::process::exit(1);
+            }
+        };
+    let log_level = cli.log_level;
+    let class = cli.class.clone();
+    match cli.command() {
+        Command::Run(args) if args.daemon => daemon(&config_path, log_level),
+        Command::Run(args) => run(args, config_path, is_default_config, log_level, class, None),
+        Command::Attach { list } => attach(list, config_path, is_default_config, log_level, class),
+        Command::Cli { command } => cli_command(command, &config_path),
+        Command::Play { trace, render, compare } => play(&trace, render.as_deref(), compare.as_deref()),
+        Command::CheckConfig => check_config(&config_path),
+        Command::InstallTerminfo => install_terminfo(),
+        Command::Ctl { socket, command } => ctl(socket, command),
+    }
+}
+
+/// `veloterm cli ...`: print config or environment details and exit.
+fn cli_command(command: CliCommand, config_path: &std::path::Path) {
+    match command {
+        CliCommand::PrintDefaultConfig => print!("{}", Config::print_default()),
+        // Defaults merged with the user's file, or only what the file changes
+        CliCommand::PrintEffectiveConfig | CliCommand::PrintConfigDiff => {
+            let effective = matches!(command, CliCommand::PrintEffectiveConfig);
+            let output = Config::load(config_path).and_then(|config| {
+                if effective {
+                    config.to_toml_string()
+                } else {
+                    config.overrides_toml()
+                }
+            });
+            match output {
+                Ok(toml) => print!("{toml}"),
+                Err(e) => {
+                    eprintln!("veloterm: {}: {e}", config_path.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+        CliCommand::ListProfiles => {
+            for name in paths::list_profiles(&paths::config_dir()) {
+                println!("{name}");
+            }
+        }
+        // Adapters usable for `renderer.gpu`
+        CliCommand::ListGpus => {
+            let adapters = veloterm::renderer::gpu::list_adapters();
+            if adapters.is_empty() {
+                println!("No GPU adapters found");
+            }
+            for info in &adapters {
+                println!("{}", veloterm::renderer::gpu::describe_adapter(info));
+            }
+        }
+    }
+}
+
+/// `veloterm play <trace>`: rebuild the panes recorded with
+/// `--record-events`, headlessly, and print their layout and screen
+/// contents as JSON. With `--render` or `--compare`, checkpoints are drawn
+/// with the default font and theme and saved or diffed as PNGs instead.
+fn play(path: &std::path::Path, render: Option<&std::path::Path>, compare: Option<&std::path::Path>) {
+    let events = std::fs::File::open(path)
+        .map_err(veloterm::replay::ReplayError::from)
+        .and_then(|file| veloterm::replay::read_trace(std::io::BufReader::new(file)));
+    let events = match events {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("veloterm: {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    };
+    let Some(dir) = render.or(compare) else {
+        match veloterm::replay::Replay::run(&events) {
+            Ok(replay) => match serde_json::to_string_pretty(&replay.snapshot()) {
+                Ok(json) => println!("{json}"),
+                Err(e) => eprintln!("veloterm: {e}"),
+            },
+            Err(e) => {
+                eprintln!("veloterm: {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+        return;
+    };
+
+    let defaults = Config::default();
+    let theme = defaults
+        .colors
+        .resolve_theme(&defaults.colors.theme)
+        .unwrap_or_else(veloterm::config::theme::TerminalTheme::warm_dark);
+    let images = veloterm::renderer::headless::HeadlessRenderer::new(
+        defaults.font.size as f32,
+        &defaults.font.family,
+        defaults.font.line_height as f32,
+    )
+    .map_err(|e| veloterm::replay::ReplayError::Render(e.to_string()))
+    .and_then(|mut renderer| veloterm::replay::render_checkpoints(&events, &mut renderer, &theme));
+    let images = match images {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("veloterm: {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    for (i, image) in images.iter().enumerate() {
+        let file = dir.join(format!("checkpoint-{i:03}.png"));
+        if render.is_some() {
+            if let Err(e) = std::fs::create_dir_all(dir).map_err(image::ImageError::from).and_then(|()| image.save(&file)) {
+                eprintln!("veloterm: {}: {e}", file.display());
+                std::process::exit(1);
+            }
+            println!("{}", file.display());
+            continue;
+        }
+        match image::open(&file) {
+            Ok(expected) => {
+                let diff = veloterm::renderer::headless::pixel_diff(&expected.to_rgba8(), image);
+                if diff > 0 {
+                    println!("{}: {diff} pixels differ", file.display());
+                    failed = true;
+                }
+            }
+            Err(e) => {
+                println!("{}: {e}", file.display());
+                failed = true;
+            }
+        }
+    }
+    let extra = dir.join(format!("checkpoint-{:03}.png", images.len()));
+    if compare.is_some() && extra.exists() {
+        println!("{}: no such checkpoint in the trace", extra.display());
+        failed = true;
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// `veloterm check-config`: load and validate the config file.
+fn check_config(config_path: &std::path::Path) {
+    if !config_path.exists() {
+        println!("{}: not found, defaults apply", config_path.display());
         return;
     }
+    match Config::load(config_path) {
+        Ok(_) => println!("{}: ok", config_path.display()),
+        Err(e) => {
+            eprintln!("veloterm: {}: {e}", config_path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `veloterm install-terminfo`: compile the `veloterm` entry into
+/// ~/.terminfo so programs on this machine recognize `TERM=veloterm`.
+fn install_terminfo() {
+    let dir = dirs_home().join(".terminfo");
+    match veloterm::cli::install_terminfo(&dir) {
+        Ok(()) => println!("Installed veloterm terminfo into {}", dir.display()),
+        Err(e) => {
+            eprintln!("veloterm: installing terminfo: {e}");
+            std::process::exit(1);
+        }
+    }
+}
 
-    // Default to info logging if RUST_LOG is not set (e.g., when launched via `open`)
-    if std::env::var("RUST_LOG").is_err() {
-        std::env::set_var("RUST_LOG", "info");
+/// `veloterm --daemon`: keep shells for `veloterm attach` windows until
+/// killed.
+fn daemon(config_path: &std::path::Path, log_level: Option<log::LevelFilter>) {
+    init_logging(log_level);
+    let config = Config::load(config_path).unwrap_or_else(|e| {
+        log::warn!("Config load error ({}), using defaults", e);
+        Config::default()
+    });
+    let socket = veloterm::daemon::socket_path();
+    if let Err(e) = veloterm::daemon::server::Daemon::new(config).run(&socket) {
+        eprintln!("veloterm: daemon: {e}");
+        std::process::exit(1);
     }
-    env_logger::init();
-    log::info!("VeloTerm v0.1.0 starting");
+}
 
-    // Load config from XDG path or use defaults
-    let config_path = dirs_config_path();
+/// `veloterm attach`: open a window on the daemon's shells, starting the
+/// daemon first if needed; with --list, print its panes instead.
+fn attach(
+    list: bool,
+    config_path: std::path::PathBuf,
+    is_default_config: bool,
+    log_level: Option<log::LevelFilter>,
+    class: Option<String>,
+) {
+    let socket = veloterm::daemon::socket_path();
+    if list {
+        match veloterm::daemon::list_panes(&socket) {
+            Ok(panes) => {
+                for pane in panes {
+                    let state = if pane.attached { "attached" } else { "detached" };
+                    println!("{}\t{}\t{state}", pane.id, pane.command);
+                }
+            }
+            Err(e) => {
+                eprintln!("veloterm: no daemon on {}: {e}", socket.display());
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if !veloterm::daemon::is_running(&socket) {
+        // The daemon spawns shells with this config too
+        let config = (!is_default_config).then_some(config_path.as_path());
+        if let Err(e) = veloterm::daemon::start(&socket, config) {
+            eprintln!("veloterm: starting daemon: {e}");
+            std::process::exit(1);
+        }
+    }
+    run(RunArgs::default(), config_path, is_default_config, log_level, class, Some(socket));
+}
+
+/// `veloterm ctl ...`: send one command to a running window and print the
+/// answer.
+fn ctl(socket: Option<std::path::PathBuf>, command: CtlCommand) {
+    use veloterm::control::protocol::Response;
+
+    let fail = |message: String| -> ! {
+        eprintln!("veloterm: {message}");
+        std::process::exit(1);
+    };
+    let Some(socket) = socket.or_else(|| veloterm::control::find_socket(&veloterm::daemon::runtime_dir())) else {
+        fail("no running VeloTerm window found".to_string());
+    };
+    let json = matches!(command, CtlCommand::ListPanes { json: true });
+    let request = command
+        .into_request(|| std::io::read_to_string(std::io::stdin()))
+        .unwrap_or_else(|e| fail(format!("reading stdin: {e}")));
+    match veloterm::control::request(&socket, &request) {
+        Ok(Response::Ok) => {}
+        Ok(Response::Pane { id }) => println!("{id}"),
+        Ok(Response::Panes { panes }) if json => {
+            println!("{}", serde_json::to_string_pretty(&panes).expect("panes serialize"));
+        }
+        Ok(Response::Panes { panes }) => {
+            for pane in panes {
+                let focused = if pane.focused { "\tfocused" } else { "" };
+                let cwd = pane.cwd.as_deref().unwrap_or("-");
+                println!("{}\t{}\t{}x{}\t{cwd}{focused}", pane.id, pane.tab, pane.cols, pane.rows);
+            }
+        }
+        Ok(Response::Text { lines }) => {
+            for line in lines {
+                println!("{line}");
+            }
+        }
+        Ok(Response::Error { message }) => fail(message),
+        Err(e) => fail(format!("{}: {e}", socket.display())),
+    }
+}
+
+/// Default to info logging if RUST_LOG is not set (e.g., when launched via
+/// `open`); --log-level wins over both.
+fn init_logging(log_level: Option<log::LevelFilter>) {
+    let mut logger = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    if let Some(level) = log_level {
+        logger.filter_level(level);
+    }
+    logger.init();
+}
+
+/// Open the terminal window; with `daemon_socket`, its shells live in the
+/// daemon.
+fn run(
+    args: RunArgs,
+    config_path: std::path::PathBuf,
+    is_default_config: bool,
+    log_level: Option<log::LevelFilter>,
+    class: Option<String>,
+    daemon_socket: Option<std::path::PathBuf>,
+) {
+    init_logging(log_level);
+    log::info!("VeloTerm v{} starting", env!("CARGO_PKG_VERSION"));
+
+    // Load the config, falling back to defaults. On first launch, write a
+    // commented default config to the XDG path and show the keybinding
+    // cheatsheet.
+    let first_run = is_default_config
+        && match Config::write_first_run(&config_path) {
+            Ok(created) => {
+                if created {
+                    log::info!("First run: wrote default config to {}", config_path.display());
+                }
+                created
+            }
+            Err(e) => {
+                log::warn!("Failed to write default config: {e}");
+                false
+            }
+        };
     let app_config = match Config::load(&config_path) {
         Ok(cfg) => {
             log::info!("Config loaded from {}", config_path.display());
@@ -27,6 +321,12 @@ fn main() {
             Config::default()
         }
     };
+    // A running window can't take on another config, profile or class, so
+    // a launch asking for one opens its own
+    let own_window = !is_default_config || class.is_some() || daemon_socket.is_some();
+    if app_config.window.single_instance && !own_window && open_in_running_window(&args) {
+        return;
+    }
     log::info!(
         "Theme: {}, Font size: {}, Scrollback: {}",
         app_config.colors.theme,
@@ -34,20 +334,84 @@ fn main() {
         app_config.scrollback.lines
     );
 
-    let window_config = WindowConfig::default();
-    let app = App::new(window_config, app_config);
+    // --class lets window manager rules tell instances apart
+    let window_config = WindowConfig {
+        class: class.unwrap_or_else(|| app_config.window.class.clone()),
+        config_path: Some(config_path),
+        daemon_socket,
+        transparent: app_config.window.opacity < 1.0,
+        blur: app_config.window.blur,
+        ..WindowConfig::default()
+    };
+    let mut app = App::new(window_config, app_config);
+    if first_run {
+        app.show_cheatsheet();
+    }
+    if args.restore_session {
+        app.request_session_restore();
+    }
+    // -e and --cwd replace the first pane's shell and directory, --grid
+    // fills the first tab with commands; a relative --cwd is taken from
+    // where veloterm was started.
+    let grid = args.grid_commands();
+    let cwd = args.cwd.map(|dir| std::path::absolute(&dir).unwrap_or(dir));
+    if !grid.is_empty() {
+        app.launch_grid(&grid, cwd);
+    } else if !args.exec.is_empty() || cwd.is_some() {
+        app.launch_command(args.exec, cwd);
+    }
+    if let Some(title) = args.title {
+        app.set_title(title);
+    }
+    if let Some(path) = args.record_events {
+        match veloterm::replay::EventRecorder::create(&path) {
+            Ok(recorder) => app.record_events(recorder),
+            Err(e) => log::warn!("Not recording events to {}: {e}", path.display()),
+        }
+    }
     if let Err(e) = app.run() {
         log::error!("Application error: {e}");
         std::process::exit(1);
     }
 }
 
-/// Get the config file path (~/.config/veloterm/config.toml).
-fn dirs_config_path() -> std::path::PathBuf {
-    let mut path = dirs_home().join(".config").join("veloterm");
-    std::fs::create_dir_all(&path).ok();
-    path.push("config.toml");
-    path
+/// `window.single_instance`: ask the newest running window for a tab in
+/// this launch's directory. False when this launch needs its own window
+/// (-e, --grid, --restore-session, --record-events) or no window answered.
+fn open_in_running_window(args: &RunArgs) -> bool {
+    use veloterm::control::protocol::{Request, Response};
+
+    if !args.exec.is_empty() || args.grid.is_some() || args.restore_session || args.record_events.is_some() {
+        return false;
+    }
+    // Another user owning the runtime dir would get every launch's cwd
+    let dir = veloterm::daemon::runtime_dir();
+    if let Err(e) = veloterm::daemon::check_private_dir(&dir) {
+        log::warn!("Not looking for a running window: {e}");
+        return false;
+    }
+    let Some(socket) = veloterm::control::find_socket(&dir) else {
+        return false;
+    };
+    let cwd = args.cwd.clone().or_else(|| std::env::current_dir().ok());
+    let request = Request::NewTab {
+        cwd: cwd.map(|dir| std::path::absolute(&dir).unwrap_or(dir).to_string_lossy().into_owned()),
+        title: args.title.clone(),
+    };
+    match veloterm::control::request(&socket, &request) {
+        Ok(Response::Pane { id }) => {
+            log::info!("Opened pane {id} in the window at {}", socket.display());
+            true
+        }
+        Ok(other) => {
+            log::warn!("Window at {} refused a new tab: {other:?}", socket.display());
+            false
+        }
+        Err(e) => {
+            log::warn!("Window at {} did not answer: {e}", socket.display());
+            false
+        }
+    }
 }
 
 /// Get the user's home directory.
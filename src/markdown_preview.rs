@@ -19,6 +19,14 @@ impl MarkdownPreviewState {
         })
     }
 
+    /// Preview generated markdown; `title` stands in for the file name.
+    pub fn from_markdown(title: &str, text: &str) -> Self {
+        Self {
+            file_path: title.to_string(),
+            items: markdown::parse(text).collect(),
+        }
+    }
+
     /// Get the parsed markdown items for rendering.
     pub fn items(&self) -> &[markdown::Item] {
         &self.items
@@ -0,0 +1,176 @@
+// Pane notes: short comments attached to buffer lines, marked in the pane's
+// left gutter with the text shown on hover, and saved with the session.
+//
+// Lines use the same numbering as prompt positions: 0 is the oldest
+// scrollback line, `history_size + row` a screen row.
+
+use serde::{Deserialize, Serialize};
+
+/// A note on one buffer line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub line: usize,
+    pub text: String,
+}
+
+/// A pane's notes, kept sorted by line with at most one per line.
+#[derive(Debug, Clone, Default)]
+pub struct PaneNotes {
+    notes: Vec<Note>,
+}
+
+impl PaneNotes {
+    /// Notes restored from a session.
+    pub fn from_notes(mut notes: Vec<Note>) -> Self {
+        notes.sort_by_key(|n| n.line);
+        notes.dedup_by_key(|n| n.line);
+        notes.retain(|n| !n.text.trim().is_empty());
+        Self { notes }
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// The note on `line`, if any.
+    pub fn get(&self, line: usize) -> Option<&str> {
+        let i = self.notes.binary_search_by_key(&line, |n| n.line).ok()?;
+        Some(&self.notes[i].text)
+    }
+
+    /// Set the note on `line`; blank text removes it.
+    pub fn set(&mut self, line: usize, text: &str) {
+        let text = text.trim();
+        match self.notes.binary_search_by_key(&line, |n| n.line) {
+            Ok(i) if text.is_empty() => {
+                self.notes.remove(i);
+            }
+            Ok(i) => self.notes[i].text = text.to_string(),
+            Err(_) if text.is_empty() => {}
+            Err(i) => self.notes.insert(i, Note { line, text: text.to_string() }),
+        }
+    }
+
+    /// Drop every note (the scrollback they point into was cleared).
+    pub fn clear(&mut self) {
+        self.notes.clear();
+    }
+
+    /// Line of the first note below `line`.
+    pub fn next_after(&self, line: usize) -> Option<usize> {
+        self.notes.iter().map(|n| n.line).find(|&l| l > line)
+    }
+
+    /// Line of the last note above `line`.
+    pub fn prev_before(&self, line: usize) -> Option<usize> {
+        self.notes.iter().rev().map(|n| n.line).find(|&l| l < line)
+    }
+
+    /// Notes in the viewport, as (viewport row, note). `first_line` is the
+    /// buffer line at the top of the viewport.
+    pub fn visible(&self, first_line: usize, rows: usize) -> impl Iterator<Item = (usize, &Note)> {
+        self.notes
+            .iter()
+            .filter(move |n| n.line >= first_line && n.line < first_line + rows)
+            .map(move |n| (n.line - first_line, n))
+    }
+}
+
+/// Gutter marker for a note, in physical pixels (same coordinate space as
+/// `PaneInfo`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteMarker {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub text: String,
+}
+
+/// Width of a gutter marker in physical pixels.
+pub const MARKER_WIDTH: f32 = 4.0;
+
+/// A note being written in a pane's inline editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteEditor {
+    pub line: usize,
+    pub value: String,
+}
+
+impl NoteEditor {
+    /// Edit the note on `line`, starting from its current text.
+    pub fn new(line: usize, notes: &PaneNotes) -> Self {
+        Self {
+            line,
+            value: notes.get(line).unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Text shown across the pane bottom while editing.
+    pub fn prompt(&self) -> String {
+        format!("Note: {}\u{258F}  Enter: save · Esc: cancel · empty removes", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(lines: &[usize]) -> PaneNotes {
+        let mut notes = PaneNotes::default();
+        for &line in lines {
+            notes.set(line, &format!("note {line}"));
+        }
+        notes
+    }
+
+    #[test]
+    fn set_keeps_notes_sorted_and_replaces() {
+        let mut n = notes(&[30, 10, 20]);
+        assert_eq!(n.notes().iter().map(|n| n.line).collect::<Vec<_>>(), vec![10, 20, 30]);
+        n.set(20, "  retry storm starts here ");
+        assert_eq!(n.get(20), Some("retry storm starts here"));
+        n.set(20, "  ");
+        assert_eq!(n.get(20), None);
+        n.set(25, "");
+        assert_eq!(n.notes().len(), 2);
+    }
+
+    #[test]
+    fn next_and_prev_skip_current_line() {
+        let n = notes(&[10, 20, 30]);
+        assert_eq!(n.next_after(10), Some(20));
+        assert_eq!(n.next_after(30), None);
+        assert_eq!(n.prev_before(20), Some(10));
+        assert_eq!(n.prev_before(10), None);
+    }
+
+    #[test]
+    fn visible_maps_lines_to_rows() {
+        let n = notes(&[5, 12, 40]);
+        let rows: Vec<_> = n.visible(10, 24).map(|(row, note)| (row, note.line)).collect();
+        assert_eq!(rows, vec![(2, 12)]);
+    }
+
+    #[test]
+    fn from_notes_sorts_and_dedups() {
+        let n = PaneNotes::from_notes(vec![
+            Note { line: 9, text: "b".into() },
+            Note { line: 3, text: "a".into() },
+            Note { line: 9, text: "dup".into() },
+            Note { line: 4, text: " ".into() },
+        ]);
+        assert_eq!(n.notes().iter().map(|n| n.line).collect::<Vec<_>>(), vec![3, 9]);
+    }
+
+    #[test]
+    fn editor_starts_from_existing_note() {
+        let n = notes(&[7]);
+        assert_eq!(NoteEditor::new(7, &n).value, "note 7");
+        assert_eq!(NoteEditor::new(8, &n).value, "");
+    }
+}
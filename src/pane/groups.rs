@@ -0,0 +1,171 @@
+// Pane groups: panes tagged with a name ("web", "db") so they can be handled
+// together. A group can broadcast typed input to all of its panes, be closed
+// at once, switch colors together or re-run a command in every pane. Groups
+// span tabs; a pane belongs to at most one.
+
+use super::PaneId;
+use crate::config::theme::{Color, TerminalTheme};
+
+/// Group membership of a window's panes.
+#[derive(Debug, Clone, Default)]
+pub struct PaneGroups {
+    /// Members in the order they joined.
+    members: Vec<(PaneId, String)>,
+    /// Groups whose typed input goes to every member.
+    broadcasting: Vec<String>,
+}
+
+impl PaneGroups {
+    /// Put `pane` in group `name`, leaving its old group. A blank name only
+    /// leaves.
+    pub fn assign(&mut self, pane: PaneId, name: &str) {
+        self.remove(pane);
+        let name = name.trim();
+        if !name.is_empty() {
+            self.members.push((pane, name.to_string()));
+        }
+    }
+
+    pub fn group_of(&self, pane: PaneId) -> Option<&str> {
+        self.members.iter().find(|(id, _)| *id == pane).map(|(_, name)| name.as_str())
+    }
+
+    /// Panes in group `name`, in the order they joined.
+    pub fn members(&self, name: &str) -> Vec<PaneId> {
+        self.members.iter().filter(|(_, group)| group == name).map(|(id, _)| *id).collect()
+    }
+
+    /// Group names in use, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.members.iter().map(|(_, name)| name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Take `pane` out of its group, e.g. when it closes. A group left
+    /// empty stops broadcasting.
+    pub fn remove(&mut self, pane: PaneId) {
+        self.members.retain(|(id, _)| *id != pane);
+        let members = &self.members;
+        self.broadcasting.retain(|name| members.iter().any(|(_, group)| group == name));
+    }
+
+    /// Start or stop broadcasting typed input to group `name`. Returns
+    /// whether it broadcasts afterwards.
+    pub fn toggle_broadcast(&mut self, name: &str) -> bool {
+        if self.is_broadcasting(name) {
+            self.broadcasting.retain(|group| group != name);
+            false
+        } else {
+            self.broadcasting.push(name.to_string());
+            true
+        }
+    }
+
+    pub fn is_broadcasting(&self, name: &str) -> bool {
+        self.broadcasting.iter().any(|group| group == name)
+    }
+
+    /// The other panes that receive input typed into `pane`.
+    pub fn broadcast_targets(&self, pane: PaneId) -> Vec<PaneId> {
+        match self.group_of(pane) {
+            Some(name) if self.is_broadcasting(name) => {
+                self.members(name).into_iter().filter(|&id| id != pane).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Badge text for `pane`'s group, marked while it broadcasts.
+    pub fn badge_text(&self, pane: PaneId) -> Option<String> {
+        let name = self.group_of(pane)?;
+        Some(if self.is_broadcasting(name) {
+            format!("{name} \u{25C9} broadcast")
+        } else {
+            name.to_string()
+        })
+    }
+
+    /// Badge color for `pane`'s group: each group in use gets its own
+    /// accent.
+    pub fn badge_color(&self, pane: PaneId, theme: &TerminalTheme) -> Option<Color> {
+        let name = self.group_of(pane)?;
+        let index = self.names().iter().position(|group| *group == name)?;
+        let accents = [
+            theme.accent_blue,
+            theme.accent_green,
+            theme.accent_purple,
+            theme.accent_yellow,
+            theme.accent_orange,
+        ];
+        Some(accents[index % accents.len()])
+    }
+}
+
+/// A group name being typed for a pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupEditor {
+    pub pane: PaneId,
+    pub value: String,
+}
+
+impl GroupEditor {
+    /// Edit the group of `pane`, starting from its current name.
+    pub fn new(pane: PaneId, groups: &PaneGroups) -> Self {
+        Self {
+            pane,
+            value: groups.group_of(pane).unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Text shown across the pane bottom while editing.
+    pub fn prompt(&self) -> String {
+        format!("Group: {}\u{258F}  Enter: assign · Esc: cancel · empty leaves the group", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assign_moves_a_pane_between_groups() {
+        let mut groups = PaneGroups::default();
+        groups.assign(PaneId(1), " web ");
+        groups.assign(PaneId(2), "db");
+        groups.assign(PaneId(3), "web");
+        assert_eq!(groups.members("web"), vec![PaneId(1), PaneId(3)]);
+        assert_eq!(groups.names(), vec!["db", "web"]);
+        groups.assign(PaneId(1), "db");
+        assert_eq!(groups.group_of(PaneId(1)), Some("db"));
+        groups.assign(PaneId(3), "");
+        assert_eq!(groups.group_of(PaneId(3)), None);
+        assert_eq!(groups.names(), vec!["db"]);
+    }
+
+    #[test]
+    fn broadcast_reaches_the_other_members() {
+        let mut groups = PaneGroups::default();
+        groups.assign(PaneId(1), "web");
+        groups.assign(PaneId(2), "web");
+        groups.assign(PaneId(3), "db");
+        assert!(groups.broadcast_targets(PaneId(1)).is_empty());
+        assert!(groups.toggle_broadcast("web"));
+        assert_eq!(groups.broadcast_targets(PaneId(1)), vec![PaneId(2)]);
+        assert!(groups.broadcast_targets(PaneId(3)).is_empty());
+        assert_eq!(groups.badge_text(PaneId(2)).unwrap(), "web \u{25C9} broadcast");
+        assert!(!groups.toggle_broadcast("web"));
+        assert_eq!(groups.badge_text(PaneId(2)).unwrap(), "web");
+    }
+
+    #[test]
+    fn emptied_group_stops_broadcasting() {
+        let mut groups = PaneGroups::default();
+        groups.assign(PaneId(1), "web");
+        groups.toggle_broadcast("web");
+        groups.remove(PaneId(1));
+        groups.assign(PaneId(2), "web");
+        assert!(!groups.is_broadcasting("web"));
+    }
+}
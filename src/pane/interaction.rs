@@ -1,7 +1,8 @@
-// PaneInteraction state machine for mouse-driven divider interaction.
+// PaneInteraction state machine for mouse-driven divider and scrollbar interaction.
 
 use super::divider::{calculate_dividers, hit_test_divider, DividerInfo, HIT_TEST_MARGIN};
 use super::{PaneId, PaneNode, Rect, SplitDirection};
+use crate::scroll::{scrollbar_hit_test, track_click_to_offset, ScrollbarHit};
 
 /// The current state of mouse interaction with pane dividers.
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +17,12 @@ pub enum InteractionState {
         /// The split ratio when drag started.
         start_ratio: f32,
     },
+    /// User is dragging a pane's scrollbar thumb.
+    ScrollbarDragging {
+        pane_id: PaneId,
+        /// Height of the scrollbar track in pixels.
+        track_height: f32,
+    },
 }
 
 /// Effects that the App should apply after processing a mouse event.
@@ -32,6 +39,18 @@ pub enum InteractionEffect {
     },
     /// Focus the pane at the given position.
     FocusPane(PaneId),
+    /// Jump the pane's viewport to `offset` lines above the bottom.
+    ScrollTo { pane_id: PaneId, offset: usize },
+    /// Start dragging the pane's scrollbar thumb from cursor y.
+    BeginScrollbarDrag { pane_id: PaneId, y: f32 },
+    /// The dragged thumb moved to cursor y.
+    ScrollbarDragTo {
+        pane_id: PaneId,
+        y: f32,
+        track_height: f32,
+    },
+    /// The scrollbar thumb was let go.
+    EndScrollbarDrag(PaneId),
 }
 
 /// Cursor types needed for pane interaction.
@@ -42,7 +61,19 @@ pub enum CursorType {
     NsResize,
 }
 
-/// State machine managing mouse interaction with pane dividers.
+/// A pane scrollbar that takes clicks, in the cursor's coordinate space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollbarInfo {
+    pub pane_id: PaneId,
+    /// The pane's rect; the scrollbar sits inside its right padding.
+    pub rect: Rect,
+    pub visible_rows: usize,
+    pub history_size: usize,
+    /// Current scroll position (0 = bottom).
+    pub display_offset: usize,
+}
+
+/// State machine managing mouse interaction with pane dividers and scrollbars.
 pub struct PaneInteraction {
     state: InteractionState,
     /// Cached divider info, updated when layout changes.
@@ -51,6 +82,10 @@ pub struct PaneInteraction {
     cursor_pos: (f32, f32),
     /// The bounds used to compute the layout (for drag ratio calculation).
     layout_bounds: Rect,
+    /// Scrollbars of the visible panes, refreshed before each press.
+    scrollbars: Vec<ScrollbarInfo>,
+    /// Terminal padding `[top, bottom, left, right]` the scrollbars sit in.
+    padding: [f32; 4],
 }
 
 impl Default for PaneInteraction {
@@ -66,6 +101,8 @@ impl PaneInteraction {
             dividers: Vec::new(),
             cursor_pos: (0.0, 0.0),
             layout_bounds: Rect::new(0.0, 0.0, 0.0, 0.0),
+            scrollbars: Vec::new(),
+            padding: [0.0; 4],
         }
     }
 
@@ -90,11 +127,29 @@ impl PaneInteraction {
         self.layout_bounds = bounds;
     }
 
+    /// Update the cached scrollbars. Panes without one are left out.
+    pub fn update_scrollbars(&mut self, scrollbars: Vec<ScrollbarInfo>, padding: [f32; 4]) {
+        self.scrollbars = scrollbars;
+        self.padding = padding;
+    }
+
+    /// Whether a scrollbar thumb is being dragged.
+    pub fn is_dragging_scrollbar(&self) -> bool {
+        matches!(self.state, InteractionState::ScrollbarDragging { .. })
+    }
+
     /// Process a cursor move event. Returns the effect to apply.
     pub fn on_cursor_moved(&mut self, x: f32, y: f32) -> InteractionEffect {
         self.cursor_pos = (x, y);
 
         match &self.state {
+            InteractionState::ScrollbarDragging { pane_id, track_height } => {
+                InteractionEffect::ScrollbarDragTo {
+                    pane_id: *pane_id,
+                    y,
+                    track_height: *track_height,
+                }
+            }
             InteractionState::Dragging { divider_index, .. } => {
                 let divider_index = *divider_index;
                 if let Some(divider) = self.dividers.get(divider_index) {
@@ -183,7 +238,44 @@ impl PaneInteraction {
                 }
                 InteractionEffect::None
             }
-            InteractionState::Dragging { .. } => InteractionEffect::None,
+            InteractionState::Dragging { .. } | InteractionState::ScrollbarDragging { .. } => {
+                InteractionEffect::None
+            }
+        }
+    }
+
+    /// Process a mouse button press on a scrollbar: a track click jumps there,
+    /// a thumb press starts a drag. Returns None when the cursor is not over a
+    /// scrollbar (or is on a divider), leaving the press to selection and
+    /// `on_mouse_press`.
+    pub fn on_scrollbar_press(&mut self) -> Option<InteractionEffect> {
+        if self.state != InteractionState::Idle {
+            return None;
+        }
+        let (x, y) = self.cursor_pos;
+        let padding = self.padding;
+        let (bar, hit) = self.scrollbars.iter().find_map(|bar| {
+            let r = bar.rect;
+            let hit = scrollbar_hit_test(
+                x, y, r.x, r.y, r.width, r.height, padding,
+                bar.visible_rows, bar.history_size, bar.display_offset,
+            );
+            (hit != ScrollbarHit::None).then_some((bar, hit))
+        })?;
+        let (pane_id, r) = (bar.pane_id, bar.rect);
+        match hit {
+            ScrollbarHit::Track(y) => Some(InteractionEffect::ScrollTo {
+                pane_id,
+                offset: track_click_to_offset(y, r.y, r.height, padding, bar.history_size),
+            }),
+            ScrollbarHit::Thumb(y) => {
+                self.state = InteractionState::ScrollbarDragging {
+                    pane_id,
+                    track_height: r.height - padding[0] - padding[1],
+                };
+                Some(InteractionEffect::BeginScrollbarDrag { pane_id, y })
+            }
+            ScrollbarHit::None => None,
         }
     }
 
@@ -194,6 +286,11 @@ impl PaneInteraction {
                 self.state = InteractionState::Idle;
                 InteractionEffect::SetCursor(CursorType::Default)
             }
+            InteractionState::ScrollbarDragging { pane_id, .. } => {
+                let pane_id = *pane_id;
+                self.state = InteractionState::Idle;
+                InteractionEffect::EndScrollbarDrag(pane_id)
+            }
             _ => InteractionEffect::None,
         }
     }
@@ -503,4 +600,57 @@ mod tests {
             _ => panic!("expected UpdateRatio, got {:?}", effect),
         }
     }
+
+
+    // ── Scrollbar ───────────────────────────────────────────────────
+
+    fn with_scrollbar() -> PaneInteraction {
+        let mut interaction = PaneInteraction::new();
+        let bar = ScrollbarInfo {
+            pane_id: PaneId(1),
+            rect: Rect::new(0.0, 0.0, 800.0, 600.0),
+            visible_rows: 24,
+            history_size: 100,
+            display_offset: 0,
+        };
+        interaction.update_scrollbars(vec![bar], [10.0; 4]);
+        interaction
+    }
+
+    #[test]
+    fn track_click_jumps_to_position() {
+        let mut interaction = with_scrollbar();
+        interaction.on_cursor_moved(786.0, 100.0);
+        let effect = interaction.on_scrollbar_press();
+        assert_eq!(effect, Some(InteractionEffect::ScrollTo { pane_id: PaneId(1), offset: 84 }));
+        assert_eq!(*interaction.state(), InteractionState::Idle);
+    }
+
+    #[test]
+    fn thumb_drag_reports_moves_until_release() {
+        let mut interaction = with_scrollbar();
+        interaction.on_cursor_moved(786.0, 550.0);
+        let effect = interaction.on_scrollbar_press();
+        assert_eq!(effect, Some(InteractionEffect::BeginScrollbarDrag { pane_id: PaneId(1), y: 550.0 }));
+        assert!(interaction.is_dragging_scrollbar());
+        // Presses and moves while dragging belong to the drag
+        assert_eq!(interaction.on_mouse_press(&[]), InteractionEffect::None);
+        let effect = interaction.on_cursor_moved(300.0, 400.0);
+        assert_eq!(
+            effect,
+            InteractionEffect::ScrollbarDragTo { pane_id: PaneId(1), y: 400.0, track_height: 580.0 }
+        );
+        assert_eq!(interaction.on_mouse_release(), InteractionEffect::EndScrollbarDrag(PaneId(1)));
+        assert!(!interaction.is_dragging_scrollbar());
+    }
+
+    #[test]
+    fn press_off_scrollbar_falls_through() {
+        let mut interaction = with_scrollbar();
+        interaction.on_cursor_moved(400.0, 300.0);
+        assert_eq!(interaction.on_scrollbar_press(), None);
+        interaction.update_scrollbars(Vec::new(), [10.0; 4]);
+        interaction.on_cursor_moved(786.0, 100.0);
+        assert_eq!(interaction.on_scrollbar_press(), None);
+    }
 }
@@ -1,8 +1,10 @@
 // Pane layout engine: binary tree data structure for terminal pane management.
 
 pub mod divider;
+pub mod groups;
 pub mod header;
 pub mod interaction;
+pub mod scroll_lock;
 
 use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -169,6 +171,115 @@ impl PaneNode {
         }
     }
 
+    /// Move the divider of the nearest split along `axis` above `target` by
+    /// `delta`. Returns None if `target` isn't in this subtree, Some(true)
+    /// once a split was adjusted.
+    fn adjust_ratio(&mut self, target: PaneId, axis: SplitDirection, delta: f32) -> Option<bool> {
+        match self {
+            PaneNode::Leaf { id } => (*id == target).then_some(false),
+            PaneNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let adjusted = match first.adjust_ratio(target, axis, delta) {
+                    Some(adjusted) => adjusted,
+                    None => second.adjust_ratio(target, axis, delta)?,
+                };
+                if adjusted || *direction != axis {
+                    return Some(adjusted);
+                }
+                *ratio = (*ratio + delta).clamp(MIN_SPLIT_RATIO, 1.0 - MIN_SPLIT_RATIO);
+                Some(true)
+            }
+        }
+    }
+
+    /// Exchange the positions of leaves `a` and `b`.
+    fn swap_leaves(&mut self, a: PaneId, b: PaneId) {
+        match self {
+            PaneNode::Leaf { id } if *id == a => *id = b,
+            PaneNode::Leaf { id } if *id == b => *id = a,
+            PaneNode::Leaf { .. } => {}
+            PaneNode::Split { first, second, .. } => {
+                first.swap_leaves(a, b);
+                second.swap_leaves(a, b);
+            }
+        }
+    }
+
+    /// The pane across the split directly above `target`: the nearest leaf
+    /// of its sibling subtree.
+    fn split_neighbor(&self, target: PaneId) -> Option<PaneId> {
+        let PaneNode::Split { first, second, .. } = self else {
+            return None;
+        };
+        if first.pane_id() == Some(target) {
+            return second.leaf_ids().first().copied();
+        }
+        if second.pane_id() == Some(target) {
+            return first.leaf_ids().last().copied();
+        }
+        first.split_neighbor(target).or_else(|| second.split_neighbor(target))
+    }
+
+    /// Flip the direction of the split directly above `target`. Returns
+    /// None if `target` isn't in this subtree, Some(true) once flipped.
+    fn rotate_parent(&mut self, target: PaneId) -> Option<bool> {
+        match self {
+            PaneNode::Leaf { id } => (*id == target).then_some(false),
+            PaneNode::Split {
+                direction,
+                first,
+                second,
+                ..
+            } => {
+                let rotated = match first.rotate_parent(target) {
+                    Some(rotated) => rotated,
+                    None => second.rotate_parent(target)?,
+                };
+                if !rotated {
+                    *direction = match direction {
+                        SplitDirection::Horizontal => SplitDirection::Vertical,
+                        SplitDirection::Vertical => SplitDirection::Horizontal,
+                    };
+                }
+                Some(true)
+            }
+        }
+    }
+
+    /// Number of panes side by side along `axis` in this subtree.
+    fn span(&self, axis: SplitDirection) -> usize {
+        match self {
+            PaneNode::Leaf { .. } => 1,
+            PaneNode::Split {
+                direction,
+                first,
+                second,
+                ..
+            } if *direction == axis => first.span(axis) + second.span(axis),
+            PaneNode::Split { first, second, .. } => first.span(axis).max(second.span(axis)),
+        }
+    }
+
+    /// Set every ratio so panes along each axis get equal space.
+    fn equalize(&mut self) {
+        if let PaneNode::Split {
+            direction,
+            ratio,
+            first,
+            second,
+        } = self
+        {
+            let (a, b) = (first.span(*direction), second.span(*direction));
+            *ratio = a as f32 / (a + b) as f32;
+            first.equalize();
+            second.equalize();
+        }
+    }
+
     /// Remove the leaf with the given id. Returns the surviving subtree if the
     /// leaf was found and removed, or None if this node IS the target leaf.
     pub fn remove_leaf(&mut self, target: PaneId) -> RemoveResult {
@@ -262,6 +373,9 @@ pub(crate) fn clamp_ratio(ratio: f32, total: f32, min_size: f32) -> f32 {
     ratio.clamp(min_ratio, max_ratio)
 }
 
+/// Smallest ratio keyboard resizing leaves either side of a split.
+pub const MIN_SPLIT_RATIO: f32 = 0.05;
+
 /// Direction for focus navigation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FocusDirection {
@@ -356,6 +470,23 @@ impl PaneTree {
         }
     }
 
+    /// Close `target` wherever focus is; focus moves to the first pane if
+    /// it was on `target`. Returns false for the last pane or one that isn't
+    /// in this tree.
+    pub fn close_pane(&mut self, target: PaneId) -> bool {
+        if self.pane_count() <= 1 {
+            return false;
+        }
+        if !matches!(self.root.remove_leaf(target), RemoveResult::Removed) {
+            return false;
+        }
+        self.zoomed = None;
+        if self.focused == target {
+            self.focused = self.pane_ids()[0];
+        }
+        true
+    }
+
     /// Calculate layout rects for all leaf panes given window dimensions.
     pub fn calculate_layout(&self, window_width: f32, window_height: f32) -> Vec<(PaneId, Rect)> {
         let min_size = 20.0; // Minimum pane size in pixels
@@ -395,6 +526,11 @@ impl PaneTree {
         self.zoomed.is_some()
     }
 
+    /// The pane sharing a split with the focused one, if any.
+    pub fn split_neighbor(&self) -> Option<PaneId> {
+        self.root.split_neighbor(self.focused)
+    }
+
     /// Navigate focus in the given direction based on pane layout rects.
     pub fn focus_direction(
         &mut self,
@@ -402,16 +538,40 @@ impl PaneTree {
         window_width: f32,
         window_height: f32,
     ) {
+        if let Some(id) = self.neighbor(direction, window_width, window_height) {
+            self.focused = id;
+        }
+    }
+
+    /// Exchange the focused pane with its neighbor in `direction`; focus
+    /// moves with the pane. Returns false if there is no neighbor.
+    pub fn swap_focused(
+        &mut self,
+        direction: FocusDirection,
+        window_width: f32,
+        window_height: f32,
+    ) -> bool {
+        let Some(other) = self.neighbor(direction, window_width, window_height) else {
+            return false;
+        };
+        self.root.swap_leaves(self.focused, other);
+        true
+    }
+
+    /// Flip the orientation of the split holding the focused pane
+    /// (side by side ↔ stacked). Returns false for a single pane.
+    pub fn rotate_layout(&mut self) -> bool {
+        self.root.rotate_parent(self.focused) == Some(true)
+    }
+
+    /// The nearest pane in `direction` from the focused one, by layout rect
+    /// centers.
+    fn neighbor(&self, direction: FocusDirection, window_width: f32, window_height: f32) -> Option<PaneId> {
         let layout = self.calculate_layout(window_width, window_height);
         let current_rect = layout
             .iter()
             .find(|(id, _)| *id == self.focused)
-            .map(|(_, r)| *r);
-
-        let current_rect = match current_rect {
-            Some(r) => r,
-            None => return,
-        };
+            .map(|(_, r)| *r)?;
 
         let (cx, cy) = current_rect.center();
 
@@ -441,9 +601,7 @@ impl PaneTree {
             }
         }
 
-        if let Some((id, _)) = best {
-            self.focused = id;
-        }
+        best.map(|(id, _)| id)
     }
 
     /// Get a reference to the root PaneNode.
@@ -458,6 +616,51 @@ impl PaneTree {
         }
     }
 
+    /// Move the divider beside the focused pane: Left/Right adjust the
+    /// nearest side-by-side split above it, Up/Down the nearest stacked one,
+    /// by `step` of that split's size. Returns false if there is none.
+    pub fn resize_focused(&mut self, direction: FocusDirection, step: f32) -> bool {
+        let (axis, delta) = match direction {
+            FocusDirection::Left => (SplitDirection::Vertical, -step),
+            FocusDirection::Right => (SplitDirection::Vertical, step),
+            FocusDirection::Up => (SplitDirection::Horizontal, -step),
+            FocusDirection::Down => (SplitDirection::Horizontal, step),
+        };
+        self.root.adjust_ratio(self.focused, axis, delta) == Some(true)
+    }
+
+    /// Split the focused pane into a grid of `count` equal panes: rows of
+    /// up to ⌈√count⌉ panes, the last row taking what is left. Returns the
+    /// panes in reading order, starting with the focused one.
+    pub fn split_into_grid(&mut self, count: usize) -> Vec<PaneId> {
+        self.zoomed = None;
+        let columns = (1..).find(|c| c * c >= count).unwrap_or(1);
+        let rows = count.div_ceil(columns).max(1);
+        let mut row_starts = vec![self.focused];
+        for _ in 1..rows {
+            let above = row_starts[row_starts.len() - 1];
+            row_starts.extend(self.root.split_leaf(above, SplitDirection::Horizontal));
+        }
+        let mut panes = Vec::with_capacity(count);
+        for (row, &start) in row_starts.iter().enumerate() {
+            let mut last = start;
+            panes.push(start);
+            for _ in 1..columns.min(count - row * columns) {
+                if let Some(id) = self.root.split_leaf(last, SplitDirection::Vertical) {
+                    panes.push(id);
+                    last = id;
+                }
+            }
+        }
+        self.equalize_splits();
+        panes
+    }
+
+    /// Give all panes along each split axis equal space.
+    pub fn equalize_splits(&mut self) {
+        self.root.equalize();
+    }
+
     /// Update the ratio of the split node at the given pre-order split_index.
     /// Returns true if the split was found and updated.
     pub fn set_split_ratio_by_index(&mut self, split_index: usize, new_ratio: f32) -> bool {
@@ -1002,4 +1205,166 @@ mod tests {
         let mut tree = PaneTree::new();
         assert!(!tree.set_split_ratio_by_index(0, 0.5));
     }
+
+    // ── resize_focused() / equalize_splits() ────────────────────────
+
+    fn ratios(node: &PaneNode, out: &mut Vec<f32>) {
+        if let PaneNode::Split { ratio, first, second, .. } = node {
+            out.push(*ratio);
+            ratios(first, out);
+            ratios(second, out);
+        }
+    }
+
+    fn tree_ratios(tree: &PaneTree) -> Vec<f32> {
+        let mut out = Vec::new();
+        ratios(tree.root(), &mut out);
+        out
+    }
+
+    #[test]
+    fn resize_moves_nearest_split_on_matching_axis() {
+        let mut tree = PaneTree::new();
+        // [A | [B / C]], focus on C
+        tree.split_focused(SplitDirection::Vertical);
+        tree.split_focused(SplitDirection::Horizontal);
+        assert!(tree.resize_focused(FocusDirection::Left, 0.1));
+        assert!(tree.resize_focused(FocusDirection::Down, 0.05));
+        let r = tree_ratios(&tree);
+        assert!((r[0] - 0.4).abs() < 1e-6);
+        assert!((r[1] - 0.55).abs() < 1e-6);
+    }
+
+    #[test]
+    fn resize_without_matching_split_returns_false() {
+        let mut tree = PaneTree::new();
+        assert!(!tree.resize_focused(FocusDirection::Right, 0.1));
+        tree.split_focused(SplitDirection::Vertical);
+        assert!(!tree.resize_focused(FocusDirection::Up, 0.1));
+        assert_eq!(tree_ratios(&tree), vec![0.5]);
+    }
+
+    #[test]
+    fn resize_clamps_ratio() {
+        let mut tree = PaneTree::new();
+        tree.split_focused(SplitDirection::Vertical);
+        for _ in 0..20 {
+            tree.resize_focused(FocusDirection::Right, 0.1);
+        }
+        assert!((tree_ratios(&tree)[0] - (1.0 - MIN_SPLIT_RATIO)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn equalize_gives_each_column_equal_width() {
+        let mut tree = PaneTree::new();
+        // [A | [B | C]] with skewed ratios
+        tree.split_focused(SplitDirection::Vertical);
+        tree.split_focused(SplitDirection::Vertical);
+        tree.set_split_ratio_by_index(0, 0.8);
+        tree.set_split_ratio_by_index(1, 0.2);
+        tree.equalize_splits();
+        let layout = tree.calculate_layout(1208.0, 600.0);
+        let widths: Vec<f32> = layout.iter().map(|(_, r)| r.width).collect();
+        for w in &widths {
+            assert!((w - widths[0]).abs() <= PANE_GAP, "widths should match: {widths:?}");
+        }
+    }
+
+    // ── swap_focused() / rotate_layout() ────────────────────────────
+
+    #[test]
+    fn swap_exchanges_focused_pane_with_neighbor() {
+        let mut tree = PaneTree::new();
+        let left = tree.focused_pane_id();
+        let right = tree.split_focused(SplitDirection::Vertical).unwrap();
+        assert!(tree.swap_focused(FocusDirection::Left, 1000.0, 800.0));
+        assert_eq!(tree.focused_pane_id(), right);
+        assert_eq!(tree.pane_ids(), vec![right, left]);
+        let layout = tree.calculate_layout(1000.0, 800.0);
+        assert_eq!(layout[0].0, right);
+        assert!(layout[0].1.x < layout[1].1.x);
+    }
+
+    #[test]
+    fn swap_without_neighbor_returns_false() {
+        let mut tree = PaneTree::new();
+        assert!(!tree.swap_focused(FocusDirection::Right, 1000.0, 800.0));
+        let first = tree.focused_pane_id();
+        tree.split_focused(SplitDirection::Vertical);
+        assert!(!tree.swap_focused(FocusDirection::Up, 1000.0, 800.0));
+        assert_eq!(tree.pane_ids()[0], first);
+    }
+
+    #[test]
+    fn split_neighbor_is_across_the_nearest_split() {
+        let mut tree = PaneTree::new();
+        assert_eq!(tree.split_neighbor(), None);
+        // [A | [B / C]]
+        let a = tree.focused_pane_id();
+        let b = tree.split_focused(SplitDirection::Vertical).unwrap();
+        let c = tree.split_focused(SplitDirection::Horizontal).unwrap();
+        assert_eq!(tree.split_neighbor(), Some(b));
+        tree.set_focus(a);
+        assert_eq!(tree.split_neighbor(), Some(b));
+        tree.set_focus(b);
+        assert_eq!(tree.split_neighbor(), Some(c));
+    }
+
+    #[test]
+    fn close_pane_keeps_focus_elsewhere() {
+        let mut tree = PaneTree::new();
+        let first = tree.focused_pane_id();
+        assert!(!tree.close_pane(first));
+        let second = tree.split_focused(SplitDirection::Vertical).unwrap();
+        let third = tree.split_focused(SplitDirection::Horizontal).unwrap();
+        assert!(tree.close_pane(second));
+        assert_eq!(tree.focused_pane_id(), third);
+        assert!(!tree.close_pane(second));
+        assert!(tree.close_pane(third));
+        assert_eq!(tree.focused_pane_id(), first);
+        assert_eq!(tree.pane_ids(), vec![first]);
+    }
+
+    #[test]
+    fn split_into_grid_fills_rows_in_reading_order() {
+        let mut tree = PaneTree::new();
+        let first = tree.focused_pane_id();
+        let panes = tree.split_into_grid(5);
+        assert_eq!(panes.len(), 5);
+        assert_eq!(panes[0], first);
+        assert_eq!(tree.focused_pane_id(), first);
+        let layout = tree.calculate_layout(1200.0, 600.0);
+        let rect = |id: PaneId| layout.iter().find(|(pane, _)| *pane == id).unwrap().1;
+        // Three on top, two below, each row split evenly
+        let (top, bottom) = panes.split_at(3);
+        for row in [top, bottom] {
+            let width = 1200.0 / row.len() as f32;
+            for pair in row.windows(2) {
+                assert_eq!(rect(pair[0]).y, rect(pair[1]).y);
+                assert!(rect(pair[0]).x < rect(pair[1]).x);
+            }
+            assert!(row.iter().all(|&id| (rect(id).width - width).abs() < PANE_GAP));
+        }
+        assert!(rect(bottom[0]).y > rect(top[0]).y);
+        assert!((rect(top[0]).height - rect(bottom[0]).height).abs() < PANE_GAP);
+        let mut single = PaneTree::new();
+        assert_eq!(single.split_into_grid(1), vec![single.focused_pane_id()]);
+    }
+
+    #[test]
+    fn rotate_flips_split_holding_focused_pane() {
+        let mut tree = PaneTree::new();
+        assert!(!tree.rotate_layout());
+        // [A | [B / C]], focus on C: only the inner split rotates
+        tree.split_focused(SplitDirection::Vertical);
+        tree.split_focused(SplitDirection::Horizontal);
+        assert!(tree.rotate_layout());
+        match tree.root() {
+            PaneNode::Split { direction, second, .. } => {
+                assert_eq!(*direction, SplitDirection::Vertical);
+                assert!(matches!(**second, PaneNode::Split { direction: SplitDirection::Vertical, .. }));
+            }
+            PaneNode::Leaf { .. } => panic!("expected a split"),
+        }
+    }
 }
@@ -0,0 +1,90 @@
+// Scroll lock: links two panes so scrolling one scrolls the other by the
+// same number of lines, for reading two logs or listings side by side. Each
+// pane belongs to at most one link; linking it again replaces the old one.
+
+use super::PaneId;
+
+/// Linked pane pairs of one window.
+#[derive(Debug, Clone, Default)]
+pub struct ScrollLocks {
+    pairs: Vec<(PaneId, PaneId)>,
+}
+
+impl ScrollLocks {
+    /// Link `a` and `b`, or unlink them if they already are. Returns
+    /// whether they are linked afterwards.
+    pub fn toggle(&mut self, a: PaneId, b: PaneId) -> bool {
+        if a == b {
+            return false;
+        }
+        if self.partner(a) == Some(b) {
+            self.remove(a);
+            return false;
+        }
+        self.remove(a);
+        self.remove(b);
+        self.pairs.push((a, b));
+        true
+    }
+
+    /// The pane linked to `pane`, if any.
+    pub fn partner(&self, pane: PaneId) -> Option<PaneId> {
+        self.pairs.iter().find_map(|&(a, b)| {
+            if a == pane {
+                Some(b)
+            } else if b == pane {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn is_locked(&self, pane: PaneId) -> bool {
+        self.partner(pane).is_some()
+    }
+
+    /// Drop the link of `pane`, e.g. when it closes.
+    pub fn remove(&mut self, pane: PaneId) {
+        self.pairs.retain(|&(a, b)| a != pane && b != pane);
+    }
+}
+
+/// Display offset of a linked pane after its partner scrolled by `delta`
+/// lines (positive = into history).
+pub fn follow_offset(offset: usize, delta: isize, history_size: usize) -> usize {
+    offset.saturating_add_signed(delta).min(history_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_links_and_unlinks_a_pair() {
+        let mut locks = ScrollLocks::default();
+        assert!(locks.toggle(PaneId(1), PaneId(2)));
+        assert_eq!(locks.partner(PaneId(2)), Some(PaneId(1)));
+        assert!(!locks.toggle(PaneId(2), PaneId(1)));
+        assert!(!locks.is_locked(PaneId(1)));
+        assert!(!locks.toggle(PaneId(3), PaneId(3)));
+    }
+
+    #[test]
+    fn relinking_replaces_the_old_pair() {
+        let mut locks = ScrollLocks::default();
+        locks.toggle(PaneId(1), PaneId(2));
+        assert!(locks.toggle(PaneId(2), PaneId(3)));
+        assert_eq!(locks.partner(PaneId(1)), None);
+        assert_eq!(locks.partner(PaneId(3)), Some(PaneId(2)));
+        locks.remove(PaneId(3));
+        assert!(!locks.is_locked(PaneId(2)));
+    }
+
+    #[test]
+    fn follower_stays_inside_its_history() {
+        assert_eq!(follow_offset(10, 3, 100), 13);
+        assert_eq!(follow_offset(2, -5, 100), 0);
+        assert_eq!(follow_offset(98, 6, 100), 100);
+    }
+}
@@ -0,0 +1,281 @@
+// Pane recording: the focused pane as an animated GIF, for sharing terminal
+// demos. A frame is taken only when the pane redrew changed cells, at most
+// `recording.max_fps` a second, and handed to a background thread that
+// encodes it as the next arrives (a frame's delay is the time until the
+// next). While the encoder is behind, frames aren't read back at all.
+// Recording stops after `recording.max_duration` seconds.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use crossbeam_channel::{Sender, TrySendError};
+use image::RgbaImage;
+
+use crate::pane::{PaneId, Rect};
+
+/// NeuQuant speed for palette quantization (1 best .. 30 fastest).
+/// Terminal frames have few colors, so a fast pass loses little.
+const QUANTIZE_SPEED: i32 = 10;
+
+/// Delay given to the last frame when a recording is dropped unfinished.
+const TAIL_DELAY: Duration = Duration::from_secs(1);
+
+/// Frames waiting for the encoder. Each is a full RGBA pane image, so the
+/// queue stays short and a slow encoder skips frames instead.
+const QUEUE_FRAMES: usize = 4;
+
+enum EncoderMsg {
+    /// A frame shown from this offset into the recording.
+    Frame(RgbaImage, Duration),
+    /// The recording ended at this offset.
+    Finish(Duration),
+}
+
+/// A recording in progress.
+pub struct PaneRecording {
+    pane: PaneId,
+    path: PathBuf,
+    started: Instant,
+    last_frame: Option<Instant>,
+    /// A changed frame was skipped by the rate limit and is still owed.
+    missed: bool,
+    min_interval: Duration,
+    max_duration: Duration,
+    frames: usize,
+    tx: Sender<EncoderMsg>,
+    encoder: JoinHandle<Result<(), String>>,
+}
+
+impl PaneRecording {
+    /// Start recording `pane` into a GIF at `path`.
+    pub fn start(pane: PaneId, path: PathBuf, max_fps: u32, max_duration: Duration) -> std::io::Result<Self> {
+        let file = File::create(&path)?;
+        let (tx, rx) = crossbeam_channel::bounded(QUEUE_FRAMES);
+        let encoder = std::thread::Builder::new()
+            .name("gif-encoder".into())
+            .spawn(move || encode(BufWriter::new(file), rx))?;
+        Ok(Self {
+            pane,
+            path,
+            started: Instant::now(),
+            last_frame: None,
+            missed: false,
+            min_interval: Duration::from_secs(1) / max_fps.max(1),
+            max_duration,
+            frames: 0,
+            tx,
+            encoder,
+        })
+    }
+
+    /// The pane being recorded.
+    pub fn pane(&self) -> PaneId {
+        self.pane
+    }
+
+    /// Time since the recording started.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+    }
+
+    /// Whether the duration cap has been reached.
+    pub fn expired(&self, now: Instant) -> bool {
+        self.elapsed(now) >= self.max_duration
+    }
+
+    /// Whether to read back the frame just drawn, given whether the pane
+    /// changed in it. Changes inside the rate limit, or while the encoder
+    /// queue is full, are taken with the next frame allowed, so the last
+    /// state is never lost.
+    pub fn wants_frame(&mut self, now: Instant, damaged: bool) -> bool {
+        let due = !self.tx.is_full()
+            && self
+                .last_frame
+                .is_none_or(|last| now.saturating_duration_since(last) >= self.min_interval);
+        let wanted = damaged || self.missed || self.last_frame.is_none();
+        if wanted && !due {
+            self.missed = true;
+        }
+        wanted && due
+    }
+
+    /// Add the `area` of a rendered window `frame`.
+    pub fn push_frame(&mut self, frame: &RgbaImage, area: &Rect, now: Instant) {
+        let Some(image) = crate::screenshot::crop(frame, area) else {
+            return;
+        };
+        match self.tx.try_send(EncoderMsg::Frame(image, self.elapsed(now))) {
+            Ok(()) => {
+                self.last_frame = Some(now);
+                self.missed = false;
+                self.frames += 1;
+            }
+            Err(TrySendError::Full(_)) => self.missed = true,
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    /// Status bar label, e.g. "REC 0:07".
+    pub fn label(&self, now: Instant) -> String {
+        let secs = self.elapsed(now).as_secs();
+        format!("REC {}:{:02}", secs / 60, secs % 60)
+    }
+
+    /// Stop recording. The GIF is finished off the calling thread, which
+    /// doesn't wait for queued frames; `done` gets its path and frame count.
+    pub fn finish(self, done: impl FnOnce(Result<(PathBuf, usize), String>) + Send + 'static) {
+        let end = self.elapsed(Instant::now());
+        let Self { path, frames, tx, encoder, .. } = self;
+        let finisher = std::thread::Builder::new().name("gif-finish".into()).spawn(move || {
+            // Waits for room behind the queued frames
+            let _ = tx.send(EncoderMsg::Finish(end));
+            drop(tx);
+            done(match encoder.join() {
+                Ok(Ok(())) => Ok((path, frames)),
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err("GIF encoder panicked".to_string()),
+            });
+        });
+        if let Err(e) = finisher {
+            log::warn!("Could not finish the recording: {e}");
+        }
+    }
+}
+
+/// Encoder thread: write each frame once the next one (or the end) gives
+/// its delay. Frames are cut to the first frame's size, which is the
+/// GIF's canvas. A dropped sender ends the GIF with `TAIL_DELAY`.
+fn encode(out: BufWriter<File>, rx: crossbeam_channel::Receiver<EncoderMsg>) -> Result<(), String> {
+    let mut encoder = GifEncoder::new_with_speed(out, QUANTIZE_SPEED);
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+    let mut canvas: Option<(u32, u32)> = None;
+    let mut pending: Option<(RgbaImage, Duration)> = None;
+    let write = |encoder: &mut GifEncoder<_>, image: RgbaImage, delay: Duration| {
+        let delay = image::Delay::from_saturating_duration(delay.max(Duration::from_millis(10)));
+        encoder
+            .encode_frame(image::Frame::from_parts(image, 0, 0, delay))
+            .map_err(|e| e.to_string())
+    };
+    loop {
+        match rx.recv() {
+            Ok(EncoderMsg::Frame(image, at)) => {
+                let (width, height) = *canvas.get_or_insert(image.dimensions());
+                let image = if image.dimensions() == (width, height) {
+                    image
+                } else {
+                    let w = width.min(image.width());
+                    let h = height.min(image.height());
+                    image::imageops::crop_imm(&image, 0, 0, w, h).to_image()
+                };
+                if let Some((prev, prev_at)) = pending.replace((image, at)) {
+                    write(&mut encoder, prev, at.saturating_sub(prev_at))?;
+                }
+            }
+            Ok(EncoderMsg::Finish(end)) => {
+                if let Some((prev, prev_at)) = pending.take() {
+                    write(&mut encoder, prev, end.saturating_sub(prev_at))?;
+                }
+                return Ok(());
+            }
+            Err(_) => {
+                if let Some((prev, _)) = pending.take() {
+                    write(&mut encoder, prev, TAIL_DELAY)?;
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, shade: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, image::Rgba([shade, shade, shade, 255]))
+    }
+
+    fn recording(dir: &std::path::Path, max_fps: u32) -> PaneRecording {
+        PaneRecording::start(PaneId(1), dir.join("rec.gif"), max_fps, Duration::from_secs(60)).unwrap()
+    }
+
+    fn finished(rec: PaneRecording) -> Result<(PathBuf, usize), String> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        rec.finish(move |result| tx.send(result).unwrap());
+        rx.recv_timeout(Duration::from_secs(10)).unwrap()
+    }
+
+    #[test]
+    fn takes_first_frame_then_only_changes_within_rate() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rec = recording(dir.path(), 10);
+        let t0 = Instant::now();
+        assert!(rec.wants_frame(t0, false));
+        rec.push_frame(&solid(4, 4, 0), &Rect::new(0.0, 0.0, 4.0, 4.0), t0);
+        // Unchanged frames are skipped
+        assert!(!rec.wants_frame(t0 + Duration::from_millis(500), false));
+        // A change inside the 100ms interval is owed to the next allowed frame
+        let t1 = t0 + Duration::from_millis(600);
+        rec.push_frame(&solid(4, 4, 1), &Rect::new(0.0, 0.0, 4.0, 4.0), t1);
+        assert!(!rec.wants_frame(t1 + Duration::from_millis(20), true));
+        assert!(rec.wants_frame(t1 + Duration::from_millis(120), false));
+    }
+
+    #[test]
+    fn writes_animated_gif_with_frame_delays() {
+        use image::AnimationDecoder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut rec = recording(dir.path(), 30);
+        let start = rec.started;
+        let area = Rect::new(2.0, 2.0, 8.0, 6.0);
+        rec.push_frame(&solid(20, 20, 10), &area, start);
+        rec.push_frame(&solid(20, 20, 200), &area, start + Duration::from_millis(300));
+        let (path, frames) = finished(rec).unwrap();
+        assert_eq!(frames, 2);
+
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(File::open(path).unwrap())).unwrap();
+        let frames = decoder.into_frames().collect_frames().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].buffer().dimensions(), (8, 6));
+        let (num, den) = frames[0].delay().numer_denom_ms();
+        assert_eq!(num / den, 300);
+    }
+
+    #[test]
+    fn full_queue_skips_readback_and_keeps_the_change_owed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rec = recording(dir.path(), 50);
+        let area = Rect::new(0.0, 0.0, 4.0, 4.0);
+        let mut now = rec.started;
+        // Fill the queue faster than the encoder could possibly drain it
+        let (held_tx, held_rx) = crossbeam_channel::bounded(QUEUE_FRAMES);
+        let encoder_tx = std::mem::replace(&mut rec.tx, held_tx);
+        for shade in 0..QUEUE_FRAMES as u8 {
+            assert!(rec.wants_frame(now, true));
+            rec.push_frame(&solid(4, 4, shade), &area, now);
+            now += Duration::from_millis(100);
+        }
+        assert!(!rec.wants_frame(now, true));
+        rec.push_frame(&solid(4, 4, 99), &area, now);
+        assert_eq!(rec.frames, QUEUE_FRAMES);
+        // Once the encoder catches up the skipped change is taken
+        held_rx.recv().unwrap();
+        assert!(rec.wants_frame(now, false));
+        rec.tx = encoder_tx;
+    }
+
+    #[test]
+    fn expires_and_labels_elapsed_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let rec = PaneRecording::start(PaneId(1), dir.path().join("rec.gif"), 10, Duration::from_secs(90)).unwrap();
+        let start = rec.started;
+        assert_eq!(rec.label(start + Duration::from_secs(75)), "REC 1:15");
+        assert!(!rec.expired(start + Duration::from_secs(89)));
+        assert!(rec.expired(start + Duration::from_secs(90)));
+    }
+}
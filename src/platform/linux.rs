@@ -81,6 +81,77 @@ pub fn parse_comm_content(content: &str) -> Option<String> {
     }
 }
 
+/// Quote a string as GVariant text (`'...'`) for `gdbus call` arguments.
+fn gvariant_string(s: &str) -> String {
+    let escaped = s.replace('\\', "\\\\").replace('\'', "\\'");
+    format!("'{escaped}'")
+}
+
+/// Arguments to `gdbus` for the desktop portal's `OpenURI` method.
+///
+/// The activation token, when present, lets the opened application take
+/// focus under Wayland compositors that enforce focus-stealing prevention.
+pub fn portal_open_uri_args(uri: &str, activation_token: Option<&str>) -> Vec<String> {
+    let options = match activation_token {
+        Some(token) => format!("{{'activation_token': <{}>}}", gvariant_string(token)),
+        None => "@a{sv} {}".to_string(),
+    };
+    vec![
+        "call".to_string(),
+        "--session".to_string(),
+        "--dest".to_string(),
+        "org.freedesktop.portal.Desktop".to_string(),
+        "--object-path".to_string(),
+        "/org/freedesktop/portal/desktop".to_string(),
+        "--method".to_string(),
+        "org.freedesktop.portal.OpenURI.OpenURI".to_string(),
+        "''".to_string(),
+        gvariant_string(uri),
+        options,
+    ]
+}
+
+/// Open a URI through the XDG desktop portal, falling back to `xdg-open`
+/// when no portal answers. Runs on a background thread.
+///
+/// The portal's `OpenURI` rejects `file://` URIs (it wants a file
+/// descriptor), so local paths should go straight to [`spawn_with_activation`].
+pub fn open_uri(uri: &str, activation_token: Option<&str>) {
+    let uri = uri.to_string();
+    let token = activation_token.map(str::to_string);
+    std::thread::spawn(move || {
+        let portal = std::process::Command::new("gdbus")
+            .args(portal_open_uri_args(&uri, token.as_deref()))
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+        match portal {
+            Ok(status) if status.success() => {}
+            _ => {
+                log::debug!("Desktop portal unavailable, opening '{uri}' with xdg-open");
+                let mut cmd = std::process::Command::new("xdg-open");
+                cmd.arg(&uri);
+                if let Err(e) = spawn_with_activation(&mut cmd, token.as_deref()) {
+                    log::error!("Failed to open '{uri}' with xdg-open: {e}");
+                }
+            }
+        }
+    });
+}
+
+/// Spawn `cmd` with the activation token in its environment
+/// (`XDG_ACTIVATION_TOKEN` for Wayland, `DESKTOP_STARTUP_ID` for X11), so
+/// the application it launches can take focus.
+pub fn spawn_with_activation(
+    cmd: &mut std::process::Command,
+    activation_token: Option<&str>,
+) -> std::io::Result<std::process::Child> {
+    if let Some(token) = activation_token {
+        cmd.env("XDG_ACTIVATION_TOKEN", token).env("DESKTOP_STARTUP_ID", token);
+    }
+    cmd.spawn()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +255,25 @@ mod tests {
         assert!(pids.is_empty());
         // foreground_process_name would return None
     }
+
+    // ── Desktop portal tests ──
+
+    #[test]
+    fn portal_args_call_open_uri() {
+        let args = portal_open_uri_args("https://example.com", None);
+        assert!(args.contains(&"org.freedesktop.portal.OpenURI.OpenURI".to_string()));
+        assert_eq!(&args[args.len() - 3..], ["''", "'https://example.com'", "@a{sv} {}"]);
+    }
+
+    #[test]
+    fn portal_args_pass_activation_token() {
+        let args = portal_open_uri_args("https://example.com", Some("tok_123"));
+        assert_eq!(args.last().unwrap(), "{'activation_token': <'tok_123'>}");
+    }
+
+    #[test]
+    fn gvariant_string_escapes_quotes() {
+        assert_eq!(gvariant_string("it's"), "'it\\'s'");
+        assert_eq!(gvariant_string("a\\b"), "'a\\\\b'");
+    }
 }
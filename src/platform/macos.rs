@@ -1,6 +1,21 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use core_graphics::display::CGDisplay;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject, Sel};
+use objc2::{define_class, msg_send, sel, DefinedClass, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSEventModifierFlags, NSMenu, NSMenuItem, NSPasteboard};
+use objc2_foundation::{NSString, NSURL};
+use winit::event_loop::EventLoopProxy;
 use winit::raw_window_handle::HasWindowHandle;
 
+use crate::config::watcher::UserEvent;
+use crate::input::actions::Action;
+use crate::input::AppCommand;
+use crate::input::keybindings::Keymap;
+use crate::platform::menu_bar::{self, KeyEquivalent, MenuItem, MENU_BAR};
+
 /// Check HiDPI status and log a warning if running without a .app bundle on Retina.
 ///
 /// macOS only reports 2x scale_factor to apps inside a .app bundle with
@@ -79,3 +94,233 @@ pub fn set_titlebar_color(window: &winit::window::Window, r: f64, g: f64, b: f64
         }
     }
 }
+
+/// State behind the menu target: where to send chosen actions, and the
+/// action for each menu item tag.
+pub struct MenuTargetIvars {
+    proxy: EventLoopProxy<UserEvent>,
+    actions: Vec<Action>,
+}
+
+define_class!(
+    // SAFETY: NSObject has no subclassing requirements and MenuTarget does
+    // not implement Drop.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "VeloTermMenuTarget"]
+    #[ivars = MenuTargetIvars]
+    struct MenuTarget;
+
+    impl MenuTarget {
+        // Menu bar items: the tag indexes the installed action list
+        #[unsafe(method(menuAction:))]
+        fn menu_action(&self, sender: &NSMenuItem) {
+            let action = usize::try_from(sender.tag())
+                .ok()
+                .and_then(|i| self.ivars().actions.get(i).copied());
+            if let Some(action) = action {
+                let _ = self.ivars().proxy.send_event(UserEvent::MenuAction(action));
+            }
+        }
+
+        // Check marks for toggles; every item stays enabled
+        #[unsafe(method(validateMenuItem:))]
+        fn validate_menu_item(&self, item: &NSMenuItem) -> bool {
+            let action = usize::try_from(item.tag())
+                .ok()
+                .and_then(|i| self.ivars().actions.get(i).copied());
+            if action == Some(Action::App(AppCommand::ToggleSecureKeyboardEntry)) {
+                let state: isize = SECURE_INPUT_HELD.load(Ordering::SeqCst).into();
+                let _: () = unsafe { msg_send![item, setState: state] };
+            }
+            true
+        }
+
+        // The "New VeloTerm Tab Here" service (NSMessage in Info.plist)
+        #[unsafe(method(newTerminalAtFolder:userData:error:))]
+        fn new_terminal_at_folder(
+            &self,
+            pboard: &NSPasteboard,
+            _user_data: Option<&NSString>,
+            _error: *mut *mut NSString,
+        ) {
+            for dir in pasteboard_folders(pboard) {
+                log::info!("Service: new tab at {}", dir.display());
+                let _ = self.ivars().proxy.send_event(UserEvent::OpenAtFolder(dir));
+            }
+        }
+    }
+);
+
+impl MenuTarget {
+    fn new(mtm: MainThreadMarker, ivars: MenuTargetIvars) -> Retained<Self> {
+        let this = Self::alloc(mtm).set_ivars(ivars);
+        unsafe { msg_send![super(this), init] }
+    }
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn EnableSecureEventInput() -> i32;
+    fn DisableSecureEventInput() -> i32;
+}
+
+/// Whether this process currently holds secure event input.
+static SECURE_INPUT_HELD: AtomicBool = AtomicBool::new(false);
+
+/// Turn Secure Keyboard Entry on or off. While on, other processes can't
+/// read keystrokes through event taps. The Carbon calls nest, so this keeps
+/// at most one enable outstanding.
+pub fn set_secure_keyboard_entry(enabled: bool) {
+    if SECURE_INPUT_HELD.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    // SAFETY: plain Carbon calls without arguments
+    let status = unsafe {
+        if enabled {
+            EnableSecureEventInput()
+        } else {
+            DisableSecureEventInput()
+        }
+    };
+    if status != 0 {
+        log::warn!("Secure keyboard entry {}: OSStatus {status}", if enabled { "enable" } else { "disable" });
+    } else {
+        log::info!("Secure keyboard entry {}", if enabled { "on" } else { "off" });
+    }
+}
+
+thread_local! {
+    /// Menu items only hold a weak reference to their target.
+    static MENU_TARGET: RefCell<Option<Retained<MenuTarget>>> = const { RefCell::new(None) };
+}
+
+/// Folders among the file URLs on a Services pasteboard. A file stands for
+/// the folder containing it.
+fn pasteboard_folders(pboard: &NSPasteboard) -> Vec<std::path::PathBuf> {
+    let mut dirs = Vec::new();
+    // SAFETY: NSPasteboardTypeFileURL is an immutable AppKit constant
+    let urls: Vec<String> = unsafe {
+        let file_url = objc2_app_kit::NSPasteboardTypeFileURL;
+        pboard
+            .pasteboardItems()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.stringForType(file_url))
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    for url in urls {
+        let Some(path) = NSURL::URLWithString(&NSString::from_str(&url)).and_then(|u| u.path()) else {
+            continue;
+        };
+        let path = std::path::PathBuf::from(path.to_string());
+        let dir = if path.is_dir() { Some(path) } else { path.parent().map(|p| p.to_path_buf()) };
+        dirs.extend(dir);
+    }
+    dirs
+}
+
+/// Install the VeloTerm menu bar (see `menu_bar::MENU_BAR`), replacing
+/// winit's default menu. Action items show their Cmd shortcut from `keymap`
+/// and, when chosen, send `UserEvent::MenuAction` through `proxy`. Also
+/// registers the Services provider. Call again after `[keys]` changes.
+pub fn install_menu_bar(keymap: &Keymap, proxy: EventLoopProxy<UserEvent>) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("Menu bar must be installed on the main thread");
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+
+    // Resolve every action item first so tags index a stable list
+    let mut actions = Vec::new();
+    for menu in MENU_BAR {
+        for item in menu.items {
+            if let MenuItem::Action { name, .. } = item {
+                actions.extend(Action::from_name(name));
+            }
+        }
+    }
+    let target = MenuTarget::new(mtm, MenuTargetIvars { proxy, actions });
+
+    let bar = NSMenu::new(mtm);
+    let mut tag: isize = 0;
+    for spec in MENU_BAR {
+        let menu = NSMenu::new(mtm);
+        menu.setTitle(&NSString::from_str(spec.title));
+        for item in spec.items {
+            match *item {
+                MenuItem::Action { name, fallback } => {
+                    let Some(action) = Action::from_name(name) else { continue };
+                    let title = action.spec().map_or(name, |s| s.title);
+                    let key = menu_bar::action_key_equivalent(keymap, action, fallback);
+                    let item = make_item(mtm, title, Some(sel!(menuAction:)), key.as_ref());
+                    let receiver: &AnyObject = &target;
+                    // SAFETY: MENU_TARGET keeps the target alive while installed
+                    let _: () = unsafe { msg_send![&*item, setTarget: receiver] };
+                    item.setTag(tag);
+                    tag += 1;
+                    menu.addItem(&item);
+                }
+                MenuItem::Standard { title, selector, key } => {
+                    let key = menu_bar::key_equivalent(key);
+                    let item = make_item(mtm, title, Some(Sel::register(selector)), key.as_ref());
+                    menu.addItem(&item);
+                }
+                MenuItem::Services => {
+                    let services = NSMenu::new(mtm);
+                    services.setTitle(&NSString::from_str("Services"));
+                    let item = make_item(mtm, "Services", None, None);
+                    item.setSubmenu(Some(&services));
+                    menu.addItem(&item);
+                    app.setServicesMenu(Some(&services));
+                }
+                MenuItem::Separator => menu.addItem(&NSMenuItem::separatorItem(mtm)),
+            }
+        }
+        let top = make_item(mtm, spec.title, None, None);
+        top.setSubmenu(Some(&menu));
+        bar.addItem(&top);
+        match spec.title {
+            "Window" => app.setWindowsMenu(Some(&menu)),
+            "Help" => app.setHelpMenu(Some(&menu)),
+            _ => {}
+        }
+    }
+    app.setMainMenu(Some(&bar));
+
+    let provider: &AnyObject = &target;
+    // SAFETY: as above, MENU_TARGET keeps the provider alive
+    let _: () = unsafe { msg_send![&*app, setServicesProvider: provider] };
+    MENU_TARGET.with(|cell| *cell.borrow_mut() = Some(target));
+}
+
+fn make_item(
+    mtm: MainThreadMarker,
+    title: &str,
+    action: Option<Sel>,
+    key: Option<&KeyEquivalent>,
+) -> Retained<NSMenuItem> {
+    let ns_title = NSString::from_str(title);
+    let ns_key = NSString::from_str(key.map_or("", |k| k.key.as_str()));
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(mtm.alloc(), &ns_title, action, &ns_key)
+    };
+    if let Some(key) = key {
+        let mut mask = NSEventModifierFlags::Command;
+        if key.shift {
+            mask |= NSEventModifierFlags::Shift;
+        }
+        if key.alt {
+            mask |= NSEventModifierFlags::Option;
+        }
+        if key.ctrl {
+            mask |= NSEventModifierFlags::Control;
+        }
+        item.setKeyEquivalentModifierMask(mask);
+    }
+    item
+}
@@ -0,0 +1,279 @@
+// Menu bar layout: the menus macOS shows in the system menu bar, which
+// registry actions they hold, and the Cmd shortcuts shown next to them.
+// `platform::macos::install_menu_bar` turns this into NSMenus.
+
+use std::ffi::CStr;
+
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::input::actions::Action;
+use crate::input::keybindings::Keymap;
+use crate::input::parse_keybinding;
+
+/// One entry in a menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuItem {
+    /// A registry action by config name. The shortcut comes from the keymap;
+    /// `fallback` is the conventional Cmd combo shown when the keymap has no
+    /// Cmd binding for the action and the combo is otherwise unbound.
+    Action { name: &'static str, fallback: &'static str },
+    /// A standard AppKit item sent to the first responder.
+    Standard { title: &'static str, selector: &'static CStr, key: &'static str },
+    /// The system Services submenu.
+    Services,
+    Separator,
+}
+
+/// One top-level menu.
+#[derive(Debug, Clone, Copy)]
+pub struct Menu {
+    pub title: &'static str,
+    pub items: &'static [MenuItem],
+}
+
+const fn action(name: &'static str) -> MenuItem {
+    MenuItem::Action { name, fallback: "" }
+}
+
+const fn standard(title: &'static str, selector: &'static CStr, key: &'static str) -> MenuItem {
+    MenuItem::Standard { title, selector, key }
+}
+
+/// The menu bar, left to right. The first menu is the application menu;
+/// the one titled "Window" becomes the system windows menu.
+pub const MENU_BAR: &[Menu] = &[
+    Menu {
+        title: "VeloTerm",
+        items: &[
+            standard("About VeloTerm", c"orderFrontStandardAboutPanel:", ""),
+            MenuItem::Separator,
+            action("toggle_secure_keyboard_entry"),
+            MenuItem::Separator,
+            MenuItem::Services,
+            MenuItem::Separator,
+            standard("Hide VeloTerm", c"hide:", "cmd+h"),
+            standard("Hide Others", c"hideOtherApplications:", "alt+cmd+h"),
+            standard("Show All", c"unhideAllApplications:", ""),
+            MenuItem::Separator,
+            action("quit"),
+        ],
+    },
+    Menu {
+        title: "Shell",
+        items: &[
+            action("new_window"),
+            action("new_tab"),
+            MenuItem::Separator,
+            action("split_vertical"),
+            action("split_horizontal"),
+            MenuItem::Separator,
+            action("restart_shell"),
+            action("export_line_view"),
+            MenuItem::Separator,
+            action("close_pane"),
+            action("close_tab"),
+        ],
+    },
+    Menu {
+        title: "Edit",
+        items: &[
+            MenuItem::Action { name: "copy", fallback: "cmd+c" },
+            action("copy_grid"),
+            MenuItem::Action { name: "paste", fallback: "cmd+v" },
+            MenuItem::Action { name: "select_all", fallback: "cmd+a" },
+            MenuItem::Separator,
+            action("toggle_search"),
+            action("search_next"),
+            action("search_prev"),
+            MenuItem::Separator,
+            action("clear_scrollback"),
+        ],
+    },
+    Menu {
+        title: "View",
+        items: &[
+            action("increase_font_size"),
+            action("decrease_font_size"),
+            action("reset_font_size"),
+            MenuItem::Separator,
+            action("toggle_sidebar"),
+            action("toggle_file_browser"),
+            action("toggle_git_review"),
+            action("toggle_markdown_preview"),
+            MenuItem::Separator,
+            action("zoom_pane"),
+            action("cycle_theme"),
+            MenuItem::Separator,
+            standard("Enter Full Screen", c"toggleFullScreen:", "ctrl+cmd+f"),
+        ],
+    },
+    Menu {
+        title: "Window",
+        items: &[
+            standard("Minimize", c"performMiniaturize:", "cmd+m"),
+            standard("Zoom", c"performZoom:", ""),
+            MenuItem::Separator,
+            action("next_tab"),
+            action("prev_tab"),
+            action("move_tab_left"),
+            action("move_tab_right"),
+            MenuItem::Separator,
+            standard("Bring All to Front", c"arrangeInFront:", ""),
+        ],
+    },
+    Menu {
+        title: "Help",
+        items: &[action("show_help"), action("toggle_cheatsheet")],
+    },
+];
+
+/// A menu item shortcut in AppKit terms: the key equivalent string plus
+/// modifiers besides Cmd, which is always part of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEquivalent {
+    pub key: String,
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+/// The AppKit key equivalent for a Cmd combo such as "cmd+shift+d". None for
+/// combos without Cmd — those stay terminal or keymap input — and for keys
+/// a menu can't show.
+pub fn key_equivalent(combo: &str) -> Option<KeyEquivalent> {
+    let (key, mods) = parse_keybinding(combo)?;
+    if !mods.contains(ModifiersState::SUPER) {
+        return None;
+    }
+    let key = match key {
+        Key::Character(s) => s.to_lowercase(),
+        Key::Named(named) => named_key_equivalent(named)?.to_string(),
+        _ => return None,
+    };
+    Some(KeyEquivalent {
+        key,
+        shift: mods.shift_key(),
+        alt: mods.alt_key(),
+        ctrl: mods.control_key(),
+    })
+}
+
+/// AppKit's function-key characters (NSUpArrowFunctionKey etc.).
+fn named_key_equivalent(key: NamedKey) -> Option<char> {
+    let c = match key {
+        NamedKey::Enter => '\r',
+        NamedKey::Tab => '\t',
+        NamedKey::Space => ' ',
+        NamedKey::Escape => '\u{1b}',
+        NamedKey::Backspace => '\u{8}',
+        NamedKey::ArrowUp => '\u{f700}',
+        NamedKey::ArrowDown => '\u{f701}',
+        NamedKey::ArrowLeft => '\u{f702}',
+        NamedKey::ArrowRight => '\u{f703}',
+        NamedKey::F1 => '\u{f704}',
+        NamedKey::F2 => '\u{f705}',
+        NamedKey::F3 => '\u{f706}',
+        NamedKey::F4 => '\u{f707}',
+        NamedKey::F5 => '\u{f708}',
+        NamedKey::F6 => '\u{f709}',
+        NamedKey::F7 => '\u{f70a}',
+        NamedKey::F8 => '\u{f70b}',
+        NamedKey::F9 => '\u{f70c}',
+        NamedKey::F10 => '\u{f70d}',
+        NamedKey::F11 => '\u{f70e}',
+        NamedKey::F12 => '\u{f70f}',
+        NamedKey::Delete => '\u{f728}',
+        NamedKey::Home => '\u{f729}',
+        NamedKey::End => '\u{f72b}',
+        NamedKey::PageUp => '\u{f72c}',
+        NamedKey::PageDown => '\u{f72d}',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// The shortcut to show for `action`: its first Cmd combo in the keymap,
+/// else `fallback` when that combo isn't bound to anything else.
+pub fn action_key_equivalent(keymap: &Keymap, action: Action, fallback: &str) -> Option<KeyEquivalent> {
+    if let Some(key) = keymap.combos_for(action).find_map(key_equivalent) {
+        return Some(key);
+    }
+    let (key, mods) = parse_keybinding(fallback)?;
+    if keymap.lookup(&key, mods).is_some() {
+        return None;
+    }
+    key_equivalent(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // ── Layout ──────────────────────────────────────────────────────
+
+    #[test]
+    fn menu_actions_are_registered() {
+        for menu in MENU_BAR {
+            for item in menu.items {
+                if let MenuItem::Action { name, .. } = item {
+                    let action = Action::from_name(name);
+                    assert!(action.is_some_and(|a| a.spec().is_some()), "{name} in {}", menu.title);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn menu_bar_has_standard_menus() {
+        let titles: Vec<&str> = MENU_BAR.iter().map(|m| m.title).collect();
+        assert_eq!(titles, ["VeloTerm", "Shell", "Edit", "View", "Window", "Help"]);
+        assert!(MENU_BAR[0].items.contains(&MenuItem::Services));
+        assert!(MENU_BAR[0].items.contains(&action("quit")));
+    }
+
+    // ── Key equivalents ─────────────────────────────────────────────
+
+    #[test]
+    fn cmd_combos_become_key_equivalents() {
+        let key = key_equivalent("cmd+shift+d").unwrap();
+        assert_eq!(key, KeyEquivalent { key: "d".into(), shift: true, alt: false, ctrl: false });
+        assert_eq!(key_equivalent("ctrl+cmd+f").unwrap().key, "f");
+        assert!(key_equivalent("ctrl+cmd+f").unwrap().ctrl);
+        assert_eq!(key_equivalent("cmd+plus").unwrap().key, "+");
+        assert_eq!(key_equivalent("cmd+left").unwrap().key, "\u{f702}");
+    }
+
+    #[test]
+    fn ctrl_combos_stay_out_of_the_menu() {
+        assert_eq!(key_equivalent("ctrl+shift+d"), None);
+        assert_eq!(key_equivalent("f1"), None);
+        assert_eq!(key_equivalent("cmd+insert"), None);
+    }
+
+    #[test]
+    fn keymap_cmd_binding_wins_over_fallback() {
+        let keymap = Keymap::from_config(&HashMap::from([(
+            "cmd+shift+c".to_string(),
+            "copy".to_string(),
+        )]));
+        let copy = Action::from_name("copy").unwrap();
+        let key = action_key_equivalent(&keymap, copy, "cmd+c").unwrap();
+        assert_eq!((key.key.as_str(), key.shift), ("c", true));
+    }
+
+    #[test]
+    fn fallback_skipped_when_combo_is_taken() {
+        let keymap = Keymap::from_config(&HashMap::from([(
+            "cmd+v".to_string(),
+            "split_vertical".to_string(),
+        )]));
+        let paste = Action::from_name("paste").unwrap();
+        assert_eq!(action_key_equivalent(&keymap, paste, "cmd+v"), None);
+        let copy = Action::from_name("copy").unwrap();
+        assert_eq!(action_key_equivalent(&keymap, copy, "cmd+c").unwrap().key, "c");
+        // Without Cmd bindings or a fallback there is no shortcut
+        let split = Action::from_name("split_horizontal").unwrap();
+        assert_eq!(action_key_equivalent(&Keymap::default(), split, ""), None);
+    }
+}
@@ -3,3 +3,5 @@ pub mod macos;
 
 #[cfg(target_os = "linux")]
 pub mod linux;
+
+pub mod menu_bar;
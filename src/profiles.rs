@@ -0,0 +1,225 @@
+// Launch profiles: `[[profiles]]` entries start a pane with their own program,
+// arguments, environment, directory and colors. The profile picker lists them
+// when opening a tab or split; a profile's pane keeps its colors while the
+// rest of the window follows `colors.theme`.
+
+use crate::config::theme::{from_hex, TerminalTheme};
+use crate::renderer::color_filter::ColorFilter;
+use crate::config::types::{ColorsConfig, ProfileConfig, ShellConfig};
+use crate::directory_rules::expand_home;
+
+/// Where the profile picked in the picker opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileTarget {
+    NewTab,
+    SplitRight,
+    SplitDown,
+}
+
+/// The profile picker overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfilePicker {
+    pub target: ProfileTarget,
+    pub selected: usize,
+}
+
+impl ProfilePicker {
+    pub fn new(target: ProfileTarget) -> Self {
+        Self { target, selected: 0 }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self, len: usize) {
+        if self.selected + 1 < len {
+            self.selected += 1;
+        }
+    }
+}
+
+/// A profile running `command` through the shell, for the panes of a
+/// startup grid (`--grid`, `panes.startup_grid`). The pane closes when the
+/// command exits.
+pub fn command_profile(command: &str, cwd: Option<&str>) -> ProfileConfig {
+    ProfileConfig {
+        name: command.to_string(),
+        command: None,
+        args: vec!["-c".to_string(), command.to_string()],
+        env: Default::default(),
+        cwd: cwd.map(str::to_string),
+        theme: None,
+        background: None,
+        trusted: None,
+        term: None,
+        compat: None,
+        color_filter: None,
+    }
+}
+
+/// The profile called `name`.
+pub fn find<'a>(profiles: &'a [ProfileConfig], name: &str) -> Option<&'a ProfileConfig> {
+    profiles.iter().find(|profile| profile.name == name)
+}
+
+/// Shell settings for a pane started from `profile`: its command,
+/// arguments, TERM and compatibility preset replace the shell's, its
+/// environment adds to `shell.env`.
+pub fn shell_config(profile: &ProfileConfig, shell: &ShellConfig) -> ShellConfig {
+    let mut config = shell.clone();
+    if let Some(command) = &profile.command {
+        config.program = Some(command.clone());
+    }
+    if profile.command.is_some() || !profile.args.is_empty() {
+        config.args = profile.args.clone();
+    }
+    config
+        .env
+        .extend(profile.env.iter().map(|(key, value)| (key.clone(), value.clone())));
+    if profile.term.is_some() {
+        config.term = profile.term.clone();
+    }
+    if let Some(compat) = &profile.compat {
+        config.compat = compat.clone();
+    }
+    config
+}
+
+/// The profile's starting directory with `~` expanded.
+pub fn start_dir(profile: &ProfileConfig, home: Option<&str>) -> Option<String> {
+    profile.cwd.as_deref().map(|cwd| expand_home(cwd, home))
+}
+
+/// Colors for the profile's panes, or None when it follows the window's
+/// theme. `background` replaces the theme's terminal background.
+pub fn theme(profile: &ProfileConfig, colors: &ColorsConfig) -> Option<TerminalTheme> {
+    if profile.theme.is_none() && profile.background.is_none() {
+        return None;
+    }
+    let mut theme = colors.resolve_theme(profile.theme.as_deref().unwrap_or(&colors.theme))?;
+    if let Some(background) = &profile.background {
+        theme.bg_deep = from_hex(background);
+    }
+    Some(theme)
+}
+
+/// Color filter for the profile's panes, or None when it follows
+/// `colors.filter`.
+pub fn color_filter(profile: &ProfileConfig) -> Option<ColorFilter> {
+    profile.color_filter.as_deref().and_then(ColorFilter::from_config_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> ProfileConfig {
+        ProfileConfig {
+            name: name.into(),
+            command: None,
+            args: Vec::new(),
+            env: Default::default(),
+            cwd: None,
+            theme: None,
+            background: None,
+            trusted: None,
+            term: None,
+            compat: None,
+            color_filter: None,
+        }
+    }
+
+    #[test]
+    fn command_profile_runs_through_the_shell() {
+        let shell = ShellConfig {
+            program: Some("/bin/bash".into()),
+            args: vec!["--login".into()],
+            ..Default::default()
+        };
+        let grid = command_profile("kubectl get pods -w", Some("/srv"));
+        let config = shell_config(&grid, &shell);
+        assert_eq!(config.program.as_deref(), Some("/bin/bash"));
+        assert_eq!(config.args, ["-c", "kubectl get pods -w"]);
+        assert_eq!(grid.cwd.as_deref(), Some("/srv"));
+    }
+
+    #[test]
+    fn command_replaces_shell_and_args() {
+        let shell = ShellConfig {
+            args: vec!["--login".into()],
+            env: [("EDITOR".to_string(), "vim".to_string())].into(),
+            ..Default::default()
+        };
+        let mut prod = profile("Prod SSH");
+        prod.command = Some("ssh".into());
+        prod.args = vec!["prod-bastion".into()];
+        prod.env.insert("AWS_PROFILE".into(), "prod".into());
+
+        let config = shell_config(&prod, &shell);
+        assert_eq!(config.program.as_deref(), Some("ssh"));
+        assert_eq!(config.args, ["prod-bastion"]);
+        assert_eq!(config.env["EDITOR"], "vim");
+        assert_eq!(config.env["AWS_PROFILE"], "prod");
+    }
+
+    #[test]
+    fn term_and_compat_override_the_shell() {
+        let shell = ShellConfig { term: Some("xterm-kitty".into()), ..Default::default() };
+        let mut old = profile("Old box");
+        old.compat = Some("legacy".into());
+        let config = shell_config(&old, &shell);
+        assert_eq!(config.compat, "legacy");
+        assert_eq!(config.term.as_deref(), Some("xterm-kitty"));
+        old.term = Some("vt100".into());
+        assert_eq!(shell_config(&old, &shell).term.as_deref(), Some("vt100"));
+    }
+
+    #[test]
+    fn profile_without_command_keeps_the_shell() {
+        let shell = ShellConfig { args: vec!["--login".into()], ..Default::default() };
+        let config = shell_config(&profile("Scratch"), &shell);
+        assert_eq!(config, shell);
+    }
+
+    #[test]
+    fn theme_follows_window_unless_set() {
+        let colors = ColorsConfig::default();
+        assert!(theme(&profile("Plain"), &colors).is_none());
+
+        let mut tinted = profile("Prod");
+        tinted.background = Some("#2A1414".into());
+        let resolved = theme(&tinted, &colors).unwrap();
+        assert_eq!(resolved.bg_deep, from_hex("#2A1414"));
+        assert_eq!(resolved.name, colors.resolve_theme(&colors.theme).unwrap().name);
+
+        tinted.theme = Some("light".into());
+        assert_eq!(theme(&tinted, &colors).unwrap().name, TerminalTheme::light().name);
+    }
+
+    #[test]
+    fn color_filter_follows_window_unless_set() {
+        let mut p = profile("Review");
+        assert_eq!(color_filter(&p), None);
+        p.color_filter = Some("protanopia".into());
+        assert_eq!(color_filter(&p), Some(ColorFilter::Protanopia));
+    }
+
+    #[test]
+    fn start_dir_expands_home() {
+        let mut p = profile("Work");
+        assert_eq!(start_dir(&p, Some("/home/u")), None);
+        p.cwd = Some("~/work".into());
+        assert_eq!(start_dir(&p, Some("/home/u")).as_deref(), Some("/home/u/work"));
+    }
+
+    #[test]
+    fn picker_selection_stays_in_range() {
+        let mut picker = ProfilePicker::new(ProfileTarget::SplitDown);
+        picker.select_prev();
+        assert_eq!(picker.selected, 0);
+        picker.select_next(2);
+        picker.select_next(2);
+        assert_eq!(picker.selected, 1);
+    }
+}
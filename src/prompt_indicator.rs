@@ -0,0 +1,130 @@
+// Prompt position indicator: while a command line typed at a shell-integrated
+// prompt wraps over several rows, report where the cursor sits within it.
+
+use crate::terminal::Terminal;
+
+/// Cursor location inside the command line being edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptPosition {
+    /// Row of the cursor within the input, 1-based.
+    pub row: usize,
+    /// Rows the input currently spans.
+    pub rows: usize,
+    /// Cursor column, 1-based.
+    pub col: usize,
+    /// Characters typed since the prompt appeared, when known.
+    pub typed: Option<usize>,
+}
+
+impl PromptPosition {
+    /// Short label such as "Ln 2/3, Col 14" or "Ln 2/3, Col 14 · 97 chars".
+    pub fn label(&self) -> String {
+        let mut label = format!("Ln {}/{}, Col {}", self.row, self.rows, self.col);
+        if let Some(typed) = self.typed {
+            label.push_str(&format!(" · {typed} chars"));
+        }
+        label
+    }
+}
+
+/// Position of the cursor in the wrapped line it is editing at the open
+/// prompt (OSC 133;A without a following 133;B). None while a command runs,
+/// without shell integration, or when the input fits on one row.
+pub fn prompt_position(terminal: &Terminal) -> Option<PromptPosition> {
+    let shell = terminal.shell_state();
+    if shell.is_command_running() {
+        return None;
+    }
+    let region = shell.last_prompt_region().filter(|r| r.end.is_none())?;
+    let (cursor_row, cursor_col) = terminal.cursor_position();
+    let cursor_line = terminal.history_size() + cursor_row;
+    if cursor_line < region.start {
+        return None;
+    }
+    // Rows of a multi-line prompt above the input line are not part of it
+    let (first, last) = crate::terminal::grid_bridge::wrapped_span(terminal, cursor_line);
+    let first = first.max(region.start);
+    let rows = last - first + 1;
+    if rows < 2 {
+        return None;
+    }
+    Some(PromptPosition {
+        row: cursor_line - first + 1,
+        rows,
+        col: cursor_col + 1,
+        typed: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(term: &mut Terminal) {
+        term.feed(b"\x1b]133;A\x07$ ");
+    }
+
+    // ── Position ────────────────────────────────────────────────────
+
+    #[test]
+    fn short_input_has_no_indicator() {
+        let mut term = Terminal::new(20, 5, 100);
+        prompt(&mut term);
+        term.feed(b"ls -la");
+        assert_eq!(prompt_position(&term), None);
+    }
+
+    #[test]
+    fn wrapped_input_reports_row_and_column() {
+        let mut term = Terminal::new(10, 5, 100);
+        prompt(&mut term);
+        term.feed(b"echo hello world");
+        let pos = prompt_position(&term).unwrap();
+        assert_eq!((pos.row, pos.rows, pos.col), (2, 2, 9));
+    }
+
+    #[test]
+    fn cursor_moved_back_counts_all_rows() {
+        let mut term = Terminal::new(10, 5, 100);
+        prompt(&mut term);
+        term.feed(b"0123456789abcdefghijklmn");
+        // Move the cursor up to the first row, as line editors do
+        term.feed(b"\x1b[2A");
+        let pos = prompt_position(&term).unwrap();
+        assert_eq!((pos.row, pos.rows), (1, 3));
+    }
+
+    #[test]
+    fn multi_line_prompt_rows_are_excluded() {
+        let mut term = Terminal::new(10, 6, 100);
+        prompt(&mut term);
+        term.feed(b"~/src\r\n> abcdefghijkl");
+        let pos = prompt_position(&term).unwrap();
+        assert_eq!((pos.row, pos.rows), (2, 2));
+    }
+
+    #[test]
+    fn running_command_has_no_indicator() {
+        let mut term = Terminal::new(10, 5, 100);
+        prompt(&mut term);
+        term.feed(b"echo hello world\x1b]133;B\x07");
+        assert_eq!(prompt_position(&term), None);
+    }
+
+    #[test]
+    fn no_shell_integration_has_no_indicator() {
+        let mut term = Terminal::new(10, 5, 100);
+        term.feed(b"$ echo hello world");
+        assert_eq!(prompt_position(&term), None);
+    }
+
+    // ── Label ───────────────────────────────────────────────────────
+
+    #[test]
+    fn label_includes_typed_count_when_known() {
+        let mut pos = PromptPosition { row: 2, rows: 3, col: 14, typed: None };
+        assert_eq!(pos.label(), "Ln 2/3, Col 14");
+        pos.typed = Some(97);
+        assert_eq!(pos.label(), "Ln 2/3, Col 14 · 97 chars");
+    }
+}
@@ -0,0 +1,210 @@
+// Output flood protection: notices a pane receiving an extreme burst of
+// output (catting a binary, a runaway loop) and caps how much of it each
+// frame parses, so the window keeps responding. The pane shows a banner
+// offering to pause output or interrupt the program. Paused output stays in
+// the PTY's bounded read queue; once that fills, the program blocks.
+
+use std::time::{Duration, Instant};
+
+/// How often the output rate is measured.
+pub const RATE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Bytes parsed per frame while a pane is flooding.
+pub const FRAME_BUDGET: usize = 512 * 1024;
+
+/// Flood state of one pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloodState {
+    #[default]
+    Calm,
+    /// Output arrives faster than the threshold; parsing is capped per frame.
+    Flooding,
+    /// The user paused output: nothing is read until resumed.
+    Paused,
+}
+
+/// Per-pane flood detector.
+#[derive(Debug, Clone)]
+pub struct FloodGuard {
+    /// Bytes per second that start a flood (0 = never).
+    threshold: u64,
+    state: FloodState,
+    window_start: Option<Instant>,
+    window_bytes: u64,
+    /// Bytes per second over the last full window.
+    rate: u64,
+    /// The banner was dismissed for this flood.
+    hidden: bool,
+}
+
+impl FloodGuard {
+    /// A guard flagging more than `threshold_mb` MiB/s (0 = off).
+    pub fn new(threshold_mb: u32) -> Self {
+        Self {
+            threshold: u64::from(threshold_mb) * 1024 * 1024,
+            state: FloodState::Calm,
+            window_start: None,
+            window_bytes: 0,
+            rate: 0,
+            hidden: false,
+        }
+    }
+
+    pub fn state(&self) -> FloodState {
+        self.state
+    }
+
+    /// Change the threshold, e.g. after a config reload.
+    pub fn set_threshold(&mut self, threshold_mb: u32) {
+        self.threshold = u64::from(threshold_mb) * 1024 * 1024;
+    }
+
+    /// Count `bytes` of output read at `now`. Returns true when this
+    /// starts a flood.
+    pub fn on_output(&mut self, now: Instant, bytes: usize) -> bool {
+        self.window_bytes += bytes as u64;
+        self.tick(now)
+    }
+
+    /// Close the measuring window once it's full: a rate above the threshold
+    /// starts a flood, one under half of it ends the flood. Returns true
+    /// when a flood starts.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let start = *self.window_start.get_or_insert(now);
+        let elapsed = now.duration_since(start);
+        if elapsed < RATE_WINDOW {
+            return false;
+        }
+        self.rate = (self.window_bytes as f64 / elapsed.as_secs_f64()) as u64;
+        self.window_start = Some(now);
+        self.window_bytes = 0;
+        if self.threshold == 0 {
+            return false;
+        }
+        match self.state {
+            FloodState::Calm if self.rate > self.threshold => {
+                self.state = FloodState::Flooding;
+                self.hidden = false;
+                true
+            }
+            FloodState::Flooding if self.rate < self.threshold / 2 => {
+                self.state = FloodState::Calm;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Bytes the pane may parse this frame: None is unlimited.
+    pub fn budget(&self) -> Option<usize> {
+        match self.state {
+            FloodState::Calm => None,
+            FloodState::Flooding => Some(FRAME_BUDGET),
+            FloodState::Paused => Some(0),
+        }
+    }
+
+    /// Pause output during a flood, or resume it.
+    pub fn toggle_pause(&mut self) {
+        self.state = match self.state {
+            FloodState::Paused => FloodState::Flooding,
+            _ => FloodState::Paused,
+        };
+        self.window_start = None;
+        self.window_bytes = 0;
+    }
+
+    /// The program was interrupted: back to normal.
+    pub fn reset(&mut self) {
+        self.state = FloodState::Calm;
+        self.window_start = None;
+        self.window_bytes = 0;
+    }
+
+    /// Hide the banner until the next flood; the cap stays.
+    pub fn hide(&mut self) {
+        self.hidden = true;
+    }
+
+    /// Whether the banner is up and takes its keys.
+    pub fn banner_visible(&self) -> bool {
+        self.state == FloodState::Paused || (self.state == FloodState::Flooding && !self.hidden)
+    }
+
+    /// Banner text shown across the pane.
+    pub fn banner(&self) -> Option<String> {
+        if !self.banner_visible() {
+            return None;
+        }
+        Some(match self.state {
+            FloodState::Paused => "Output paused — Ctrl+S resumes, Ctrl+C interrupts".to_string(),
+            _ => format!(
+                "Output flood ({} MB/s) — Ctrl+S pauses, Ctrl+C interrupts, Esc hides",
+                self.rate / (1024 * 1024)
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: usize = 1024 * 1024;
+
+    /// Feed `mb_per_sec` for one rate window starting at `now`; returns
+    /// the window's end.
+    fn burst(guard: &mut FloodGuard, now: Instant, mb_per_sec: usize) -> (Instant, bool) {
+        guard.on_output(now, 0);
+        let end = now + RATE_WINDOW;
+        (end, guard.on_output(end, mb_per_sec * MB / 4))
+    }
+
+    #[test]
+    fn fast_output_starts_a_flood() {
+        let mut guard = FloodGuard::new(8);
+        let now = Instant::now();
+        let (now, started) = burst(&mut guard, now, 4);
+        assert!(!started);
+        assert_eq!(guard.budget(), None);
+        let (_, started) = burst(&mut guard, now, 40);
+        assert!(started);
+        assert_eq!(guard.state(), FloodState::Flooding);
+        assert_eq!(guard.budget(), Some(FRAME_BUDGET));
+        assert!(guard.banner().unwrap().starts_with("Output flood (40 MB/s)"));
+    }
+
+    #[test]
+    fn flood_ends_under_half_the_threshold() {
+        let mut guard = FloodGuard::new(8);
+        let (now, _) = burst(&mut guard, Instant::now(), 40);
+        let (now, _) = burst(&mut guard, now, 6);
+        assert_eq!(guard.state(), FloodState::Flooding);
+        burst(&mut guard, now, 2);
+        assert_eq!(guard.state(), FloodState::Calm);
+        assert!(guard.banner().is_none());
+    }
+
+    #[test]
+    fn pause_stops_reading_until_resumed() {
+        let mut guard = FloodGuard::new(8);
+        burst(&mut guard, Instant::now(), 40);
+        guard.hide();
+        assert!(!guard.banner_visible());
+        guard.toggle_pause();
+        assert_eq!(guard.budget(), Some(0));
+        assert!(guard.banner().unwrap().starts_with("Output paused"));
+        guard.toggle_pause();
+        assert_eq!(guard.state(), FloodState::Flooding);
+        guard.reset();
+        assert_eq!(guard.budget(), None);
+    }
+
+    #[test]
+    fn zero_threshold_never_floods() {
+        let mut guard = FloodGuard::new(0);
+        let (_, started) = burst(&mut guard, Instant::now(), 1000);
+        assert!(!started);
+        assert_eq!(guard.state(), FloodState::Calm);
+    }
+}
@@ -1,5 +1,9 @@
 // PTY management: spawning shells, reading output, writing input.
 
+pub mod flood;
+pub mod watchdog;
+
+use crate::config::types::ColorsConfig;
 use crossbeam_channel::{Receiver, Sender};
 use portable_pty::{CommandBuilder, MasterPty, PtySize};
 use std::io::{Read, Write};
@@ -17,6 +21,8 @@ pub enum PtyError {
     ReaderCloneFailed(String),
     /// Failed to take the PTY writer.
     WriterTakeFailed(String),
+    /// The daemon could not be reached or refused the request.
+    Daemon(String),
 }
 
 impl std::fmt::Display for PtyError {
@@ -26,6 +32,7 @@ impl std::fmt::Display for PtyError {
             PtyError::SpawnFailed(e) => write!(f, "failed to spawn shell: {e}"),
             PtyError::ReaderCloneFailed(e) => write!(f, "failed to clone PTY reader: {e}"),
             PtyError::WriterTakeFailed(e) => write!(f, "failed to take PTY writer: {e}"),
+            PtyError::Daemon(e) => write!(f, "daemon: {e}"),
         }
     }
 }
@@ -109,6 +116,21 @@ pub fn foreground_process_name(_shell_pid: u32) -> Option<String> {
     None
 }
 
+/// `COLORFGBG` value for a theme config name, if the theme is known.
+pub fn colorfgbg_for_theme(colors: &ColorsConfig, theme_name: &str) -> Option<&'static str> {
+    colors.resolve_theme(theme_name).map(|t| t.colorfgbg())
+}
+
+/// Write the active theme to well-known paths read by the shell integration
+/// USR1 handlers: `/tmp/veloterm-theme` (config name) and
+/// `/tmp/veloterm-colorfgbg` (value to re-export as `COLORFGBG`).
+pub fn write_theme_files(colors: &ColorsConfig, theme_name: &str) {
+    let _ = std::fs::write("/tmp/veloterm-theme", theme_name);
+    if let Some(colorfgbg) = colorfgbg_for_theme(colors, theme_name) {
+        let _ = std::fs::write("/tmp/veloterm-colorfgbg", colorfgbg);
+    }
+}
+
 /// Extract a process name from a full path string.
 pub fn basename_from_path(path: &str) -> &str {
     path.rsplit('/').next().unwrap_or(path)
@@ -273,22 +295,37 @@ fn prepare_shell_integration(shell: &str, cmd: &mut CommandBuilder) {
     }
 }
 
-/// Managed PTY session with a reader thread and writer handle.
+/// Managed PTY session with reader and writer threads.
 pub struct PtySession {
     /// Receive raw bytes from the PTY reader thread.
     pub reader_rx: Receiver<Vec<u8>>,
-    /// Writer handle for sending input to the PTY.
-    writer: Box<dyn Write + Send>,
-    /// The child process handle.
-    _child: Box<dyn portable_pty::Child + Send + Sync>,
-    /// The master PTY handle (kept alive for resize).
-    master: Box<dyn MasterPty + Send>,
+    /// Where the shell runs.
+    backend: Backend,
     /// Reader thread join handle.
-    _reader_thread: thread::JoinHandle<()>,
+    reader_thread: thread::JoinHandle<()>,
+}
+
+enum Backend {
+    /// A shell spawned by this process.
+    Local {
+        /// Input queued for the writer thread, so a write never blocks the
+        /// caller while the reader is held back by a full queue.
+        input: Sender<Vec<u8>>,
+        /// The child process handle.
+        child: Box<dyn portable_pty::Child + Send + Sync>,
+        /// The master PTY handle (kept alive for resize).
+        master: Box<dyn MasterPty + Send>,
+    },
+    /// A shell owned by `veloterm --daemon`, streamed over its socket.
+    Daemon(crate::daemon::DaemonLink),
 }
 
 impl PtySession {
     const READ_BUFFER_SIZE: usize = 64 * 1024;
+    /// Reads queued for the window before the reader blocks (up to 16 MiB),
+    /// so a flooding or paused pane holds its program back instead of
+    /// buffering without bound.
+    const READ_QUEUE_CHUNKS: usize = 256;
 
     /// Spawn a new PTY session with the given shell and size.
     pub fn new(shell: &str, cols: u16, rows: u16) -> Result<Self, PtyError> {
@@ -296,14 +333,14 @@ impl PtySession {
     }
 
     /// Spawn a new PTY session with the given shell, size, optional CWD, optional config,
-    /// and optional theme name (exported as VELOTERM_THEME).
+    /// and optional theme name (exported as VELOTERM_THEME and COLORFGBG).
     pub fn new_with_cwd(
         shell: &str,
         cols: u16,
         rows: u16,
         cwd: Option<&str>,
         shell_config: Option<&crate::config::types::ShellConfig>,
-        theme_name: Option<&str>,
+        theme: Option<(&str, &ColorsConfig)>,
     ) -> Result<Self, PtyError> {
         let pty_system = portable_pty::native_pty_system();
 
@@ -317,10 +354,17 @@ impl PtySession {
             .map_err(|e| PtyError::OpenPtyFailed(e.to_string()))?;
 
         let mut cmd = CommandBuilder::new(shell);
-        // Set TERM for proper color and capability support
-        cmd.env("TERM", "xterm-256color");
-        // Advertise 24-bit color support to CLI tools (bat, delta, ls --color, etc.)
-        cmd.env("COLORTERM", "truecolor");
+        // Set TERM for proper color and capability support (`shell.term`,
+        // else xterm-256color, or plain xterm for the legacy preset)
+        let default_shell = crate::config::types::ShellConfig::default();
+        let term_config = shell_config.unwrap_or(&default_shell);
+        cmd.env("TERM", crate::terminal::compat::term_name(term_config));
+        // Advertise 24-bit color support to CLI tools (bat, delta, ls --color, etc.),
+        // unless the legacy preset hides it
+        match crate::terminal::compat::Compat::of(term_config).colorterm() {
+            Some(colorterm) => cmd.env("COLORTERM", colorterm),
+            None => cmd.env_remove("COLORTERM"),
+        }
         // Identify the terminal emulator to shell integration scripts (Powerlevel10k, etc.)
         cmd.env("TERM_PROGRAM", "VeloTerm");
         // Remove RUST_LOG so it doesn't leak into the user's shell and cause
@@ -328,10 +372,13 @@ impl PtySession {
         cmd.env_remove("RUST_LOG");
 
         // Export theme name so p10k and other tools can adapt per-theme
-        if let Some(theme) = theme_name {
+        if let Some((theme, colors)) = theme {
             cmd.env("VELOTERM_THEME", theme);
+            if let Some(colorfgbg) = colorfgbg_for_theme(colors, theme) {
+                cmd.env("COLORFGBG", colorfgbg);
+            }
             // Write theme to well-known path so TRAPUSR1 can read it on reload
-            let _ = std::fs::write("/tmp/veloterm-theme", theme);
+            write_theme_files(colors, theme);
         }
 
         // Apply shell config: args, env, starship suppression
@@ -371,12 +418,22 @@ impl PtySession {
             .try_clone_reader()
             .map_err(|e| PtyError::ReaderCloneFailed(e.to_string()))?;
 
-        let writer = pair
+        let mut writer = pair
             .master
             .take_writer()
             .map_err(|e| PtyError::WriterTakeFailed(e.to_string()))?;
+        let (input, input_rx) = crossbeam_channel::unbounded::<Vec<u8>>();
+        thread::spawn(move || {
+            // Ends when the session is dropped or the PTY goes away
+            for bytes in input_rx {
+                if let Err(e) = writer.write_all(&bytes).and_then(|()| writer.flush()) {
+                    log::warn!("PTY write failed: {e}");
+                    break;
+                }
+            }
+        });
 
-        let (tx, rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = crossbeam_channel::unbounded();
+        let (tx, rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = crossbeam_channel::bounded(Self::READ_QUEUE_CHUNKS);
 
         let reader_thread = thread::spawn(move || {
             let mut buf = vec![0u8; Self::READ_BUFFER_SIZE];
@@ -395,34 +452,160 @@ impl PtySession {
 
         Ok(Self {
             reader_rx: rx,
-            writer,
-            _child: child,
-            master: pair.master,
-            _reader_thread: reader_thread,
+            backend: Backend::Local {
+                input,
+                child,
+                master: pair.master,
+            },
+            reader_thread,
+        })
+    }
+
+    /// Start `shell` in a new pane of the daemon listening on `socket`.
+    pub fn spawn_in_daemon(
+        socket: &std::path::Path,
+        shell: &str,
+        cols: u16,
+        rows: u16,
+        cwd: Option<&str>,
+    ) -> Result<Self, PtyError> {
+        let request = crate::daemon::protocol::Frame::Spawn(crate::daemon::protocol::SpawnRequest {
+            shell: shell.to_string(),
+            cols,
+            rows,
+            cwd: cwd.map(str::to_string),
+        });
+        Self::from_daemon(socket, request)
+    }
+
+    /// Attach to daemon pane `pane`; its recent output arrives first.
+    pub fn attach_daemon(socket: &std::path::Path, pane: u32, cols: u16, rows: u16) -> Result<Self, PtyError> {
+        Self::from_daemon(socket, crate::daemon::protocol::Frame::Attach { pane, cols, rows })
+    }
+
+    fn from_daemon(socket: &std::path::Path, request: crate::daemon::protocol::Frame) -> Result<Self, PtyError> {
+        use crate::daemon::protocol::{read_frame, Frame};
+
+        let link = crate::daemon::DaemonLink::connect(socket, &request)
+            .map_err(|e| PtyError::Daemon(e.to_string()))?;
+        let mut reader = std::io::BufReader::new(
+            link.reader().map_err(|e| PtyError::ReaderCloneFailed(e.to_string()))?,
+        );
+        let (tx, rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = crossbeam_channel::bounded(Self::READ_QUEUE_CHUNKS);
+        let reader_thread = thread::spawn(move || {
+            // Ends when the shell exits, the daemon goes away or we detach
+            while let Ok(Some(frame)) = read_frame(&mut reader) {
+                let open = match frame {
+                    Frame::Output(bytes) => tx.send(bytes).is_ok(),
+                    // Start over from the snapshot that follows
+                    Frame::Resync => tx.send(b"\x1bc".to_vec()).is_ok(),
+                    Frame::Exited | Frame::Error(_) => false,
+                    _ => true,
+                };
+                if !open {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            reader_rx: rx,
+            backend: Backend::Daemon(link),
+            reader_thread,
         })
     }
 
-    /// Write bytes to the PTY (keyboard input).
+    /// Write bytes to the PTY (keyboard input). Local input is handed to
+    /// the writer thread and written in order.
     pub fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
-        self.writer.write_all(data)?;
-        self.writer.flush()
+        match &mut self.backend {
+            Backend::Local { input, .. } => input
+                .send(data.to_vec())
+                .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe)),
+            Backend::Daemon(link) => link.send(&crate::daemon::protocol::Frame::Input(data.to_vec())),
+        }
     }
 
     /// Returns the PID of the child shell process.
     pub fn child_pid(&self) -> Option<u32> {
-        self._child.process_id()
+        match &self.backend {
+            Backend::Local { child, .. } => child.process_id(),
+            Backend::Daemon(link) => link.pane().pid,
+        }
+    }
+
+    /// Whether the shell is owned by the daemon rather than this process.
+    pub fn is_daemon(&self) -> bool {
+        matches!(self.backend, Backend::Daemon(_))
+    }
+
+    /// Exit status of the shell if it has exited (reaps it), without
+    /// waiting. Catches a dead shell whose PTY never hung up because a
+    /// background process still holds it open. The daemon reaps its own
+    /// shells, so a daemon pane only reports exit by closing its stream.
+    pub fn try_exit_status(&mut self) -> Option<portable_pty::ExitStatus> {
+        match &mut self.backend {
+            Backend::Local { child, .. } => child.try_wait().ok().flatten(),
+            Backend::Daemon(_) => None,
+        }
+    }
+
+    /// End the shell: SIGHUP, then SIGKILL if it lingers. A daemon pane is
+    /// ended in the daemon.
+    pub fn kill(&mut self) {
+        match &mut self.backend {
+            Backend::Local { child, .. } => {
+                let _ = child.kill();
+            }
+            Backend::Daemon(link) => {
+                let _ = link.send(&crate::daemon::protocol::Frame::Kill);
+            }
+        }
+    }
+
+    /// Close the session for a pane being closed. A local shell gets SIGHUP
+    /// when its PTY is dropped; a daemon pane is ended rather than left
+    /// running detached, which only closing the window does.
+    pub fn close(mut self) {
+        if self.is_daemon() {
+            self.kill();
+        }
+    }
+
+    /// Whether the program on the PTY has turned echo off, as at a password
+    /// prompt. Always false for daemon panes and off Unix.
+    pub fn echo_disabled(&self) -> bool {
+        #[cfg(unix)]
+        if let Backend::Local { master, .. } = &self.backend {
+            if let Some(fd) = master.as_raw_fd() {
+                let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+                if unsafe { libc::tcgetattr(fd, &mut termios) } == 0 {
+                    return termios.c_lflag & libc::ECHO == 0;
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the reader thread has stopped (EOF, HUP or a read error).
+    pub fn reader_closed(&self) -> bool {
+        self.reader_thread.is_finished()
     }
 
     /// Resize the PTY.
     pub fn resize(&self, cols: u16, rows: u16) -> Result<(), PtyError> {
-        self.master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| PtyError::OpenPtyFailed(e.to_string()))
+        match &self.backend {
+            Backend::Local { master, .. } => master
+                .resize(PtySize {
+                    rows,
+                    cols,
+                    pixel_width: 0,
+                    pixel_height: 0,
+                })
+                .map_err(|e| PtyError::OpenPtyFailed(e.to_string())),
+            Backend::Daemon(link) => link
+                .send(&crate::daemon::protocol::Frame::Resize { cols, rows })
+                .map_err(|e| PtyError::Daemon(e.to_string())),
+        }
     }
 }
 
@@ -462,11 +645,18 @@ mod tests {
         );
     }
 
+    fn local_size(session: &PtySession) -> PtySize {
+        match &session.backend {
+            Backend::Local { master, .. } => master.get_size().expect("get_size failed"),
+            Backend::Daemon(_) => panic!("expected a local PTY"),
+        }
+    }
+
     #[test]
     fn pty_session_sets_initial_size() {
         let session = PtySession::new("/bin/sh", 120, 40).expect("spawn failed");
         // The master PTY should report the size we set
-        let size = session.master.get_size().expect("get_size failed");
+        let size = local_size(&session);
         assert_eq!(size.cols, 120);
         assert_eq!(size.rows, 40);
     }
@@ -518,13 +708,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pty_session_reports_echo_off() {
+        let mut session = PtySession::new("/bin/sh", 80, 24).expect("spawn failed");
+        assert!(!session.echo_disabled());
+        session.write(b"stty -echo\n").expect("write failed");
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
+        while !session.echo_disabled() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(session.echo_disabled(), "stty -echo should show on the master");
+    }
+
     // ── PTY resize ──────────────────────────────────────────────────
 
     #[test]
     fn pty_session_resize_updates_size() {
         let session = PtySession::new("/bin/sh", 80, 24).expect("spawn failed");
         session.resize(132, 50).expect("resize failed");
-        let size = session.master.get_size().expect("get_size failed");
+        let size = local_size(&session);
         assert_eq!(size.cols, 132);
         assert_eq!(size.rows, 50);
     }
@@ -548,6 +750,19 @@ mod tests {
         assert!(!is_shell_process("node"));
     }
 
+    #[test]
+    fn colorfgbg_follows_theme_brightness() {
+        let colors = ColorsConfig::default();
+        assert_eq!(colorfgbg_for_theme(&colors, "light"), Some("0;15"));
+        assert_eq!(colorfgbg_for_theme(&colors, "warm_dark"), Some("15;0"));
+        assert_eq!(colorfgbg_for_theme(&colors, "no_such_theme"), None);
+        let config = crate::config::types::Config::from_toml(
+            "[themes.paper]\ninherit = \"light\"\nbg_deep = \"#fafafa\"\n",
+        )
+        .unwrap();
+        assert_eq!(colorfgbg_for_theme(&config.colors, "paper"), Some("0;15"));
+    }
+
     #[test]
     fn basename_from_path_extracts_name() {
         assert_eq!(basename_from_path("/usr/bin/vim"), "vim");
@@ -776,4 +991,49 @@ mod tests {
             &output[..output.len().min(300)]
         );
     }
+
+    // ── Shell health ────────────────────────────────────────────────
+
+    #[test]
+    fn pty_session_reports_shell_exit_status() {
+        let mut session = PtySession::new("/bin/sh", 80, 24).expect("spawn failed");
+        assert!(session.try_exit_status().is_none());
+        session.write(b"exit 3\n").expect("write failed");
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
+        let status = loop {
+            if let Some(status) = session.try_exit_status() {
+                break status;
+            }
+            assert!(std::time::Instant::now() < deadline, "shell did not exit");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        assert_eq!(status.exit_code(), 3);
+    }
+
+    #[test]
+    fn large_write_returns_while_output_is_unread() {
+        // `cat` echoes everything back; with nobody draining the reader a
+        // synchronous write would block once the read queue filled.
+        let mut session = PtySession::new("/bin/cat", 80, 24).expect("spawn failed");
+        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+        let handle = std::thread::spawn(move || {
+            let line = [b"x".repeat(1023), b"\n".to_vec()].concat();
+            let paste = line.repeat(20 * 1024);
+            let result = session.write(&paste);
+            let _ = done_tx.send(());
+            (session, result)
+        });
+        done_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("write blocked on unread output");
+        let (mut session, result) = handle.join().unwrap();
+        result.expect("write failed");
+        session.kill();
+    }
+
+    #[test]
+    fn pty_session_with_missing_shell_fails_to_spawn() {
+        let result = PtySession::new("/nonexistent/veloterm-shell", 80, 24);
+        assert!(matches!(result, Err(PtyError::SpawnFailed(_))));
+    }
 }
@@ -0,0 +1,202 @@
+// PTY health watchdog: notices a shell that died without the PTY hanging up,
+// a PTY that stopped answering input, and shells that never started, so the
+// pane can say so and offer a restart instead of silently freezing.
+
+use portable_pty::ExitStatus;
+use std::time::{Duration, Instant};
+
+/// How often panes are probed for an exited child.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long typed input may go without any output (not even the echo)
+/// before the pane is flagged as not responding.
+pub const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(10);
+
+/// Health of a pane's shell, as far as the watchdog can tell.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PaneHealth {
+    #[default]
+    Running,
+    /// Input went unanswered for longer than `UNRESPONSIVE_AFTER`.
+    Unresponsive,
+    /// The shell exited (`signal` is set when it was killed by one).
+    Exited { code: u32, signal: Option<String> },
+    /// The PTY reader hit EOF while the shell was still running.
+    HungUp,
+    /// The shell could not be started at all.
+    SpawnFailed(String),
+}
+
+impl PaneHealth {
+    /// Whether the pane has no live shell (input goes nowhere).
+    pub fn is_dead(&self) -> bool {
+        matches!(self, Self::Exited { .. } | Self::HungUp | Self::SpawnFailed(_))
+    }
+
+    /// Banner text shown across the pane, with a restart hint naming
+    /// `restart_combo` (the key bound to `restart_shell`), if any.
+    pub fn banner(&self, restart_combo: Option<&str>) -> Option<String> {
+        let message = match self {
+            Self::Running => return None,
+            Self::Unresponsive => "Shell is not responding".to_string(),
+            Self::Exited { signal: Some(signal), .. } => format!("Shell killed by {signal}"),
+            Self::Exited { code: 0, .. } => "Shell exited".to_string(),
+            Self::Exited { code, .. } => format!("Shell exited with code {code}"),
+            Self::HungUp => "Shell closed the terminal".to_string(),
+            Self::SpawnFailed(error) => format!("Could not start shell: {error}"),
+        };
+        let hint = match (self.is_dead(), restart_combo) {
+            (true, Some(combo)) => format!(" — press Enter or {combo} to restart"),
+            (true, None) => " — press Enter to restart".to_string(),
+            (false, Some(combo)) => format!(" — press {combo} to restart"),
+            (false, None) => String::new(),
+        };
+        Some(message + hint.as_str())
+    }
+}
+
+/// Per-pane watchdog state.
+#[derive(Debug, Clone, Default)]
+pub struct Watchdog {
+    health: PaneHealth,
+    /// When input was first sent with no output seen since.
+    unanswered_since: Option<Instant>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn health(&self) -> &PaneHealth {
+        &self.health
+    }
+
+    /// Input was written to the PTY.
+    pub fn on_input(&mut self, now: Instant) {
+        self.unanswered_since.get_or_insert(now);
+    }
+
+    /// Output was read from the PTY: the shell is alive and answering.
+    pub fn on_output(&mut self) {
+        self.unanswered_since = None;
+        if self.health == PaneHealth::Unresponsive {
+            self.health = PaneHealth::Running;
+        }
+    }
+
+    /// Update health from a probe of the PTY. `exit` is the child's exit
+    /// status if it has exited; `reader_closed` is true once the reader
+    /// thread has stopped. Returns true when the health changed.
+    pub fn check(&mut self, now: Instant, exit: Option<&ExitStatus>, reader_closed: bool) -> bool {
+        if self.health.is_dead() {
+            return false;
+        }
+        let health = if let Some(status) = exit {
+            PaneHealth::Exited {
+                code: status.exit_code(),
+                signal: status.signal().map(str::to_string),
+            }
+        } else if reader_closed {
+            PaneHealth::HungUp
+        } else if self
+            .unanswered_since
+            .is_some_and(|since| now.duration_since(since) >= UNRESPONSIVE_AFTER)
+        {
+            PaneHealth::Unresponsive
+        } else {
+            self.health.clone()
+        };
+        let changed = health != self.health;
+        self.health = health;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Health transitions ──────────────────────────────────────────
+
+    #[test]
+    fn new_watchdog_is_running() {
+        let mut dog = Watchdog::new();
+        assert!(!dog.check(Instant::now(), None, false));
+        assert_eq!(*dog.health(), PaneHealth::Running);
+    }
+
+    #[test]
+    fn child_exit_without_hangup_is_detected() {
+        let mut dog = Watchdog::new();
+        let status = ExitStatus::with_exit_code(2);
+        assert!(dog.check(Instant::now(), Some(&status), false));
+        assert_eq!(*dog.health(), PaneHealth::Exited { code: 2, signal: None });
+        assert!(dog.health().is_dead());
+    }
+
+    #[test]
+    fn reader_eof_with_live_child_is_hangup() {
+        let mut dog = Watchdog::new();
+        assert!(dog.check(Instant::now(), None, true));
+        assert_eq!(*dog.health(), PaneHealth::HungUp);
+    }
+
+    #[test]
+    fn unanswered_input_marks_unresponsive_after_timeout() {
+        let mut dog = Watchdog::new();
+        let start = Instant::now();
+        dog.on_input(start);
+        assert!(!dog.check(start + UNRESPONSIVE_AFTER / 2, None, false));
+        // Later keystrokes don't push the deadline back
+        dog.on_input(start + UNRESPONSIVE_AFTER / 2);
+        assert!(dog.check(start + UNRESPONSIVE_AFTER, None, false));
+        assert_eq!(*dog.health(), PaneHealth::Unresponsive);
+        assert!(!dog.health().is_dead());
+    }
+
+    #[test]
+    fn output_clears_unresponsive() {
+        let mut dog = Watchdog::new();
+        let start = Instant::now();
+        dog.on_input(start);
+        dog.check(start + UNRESPONSIVE_AFTER, None, false);
+        dog.on_output();
+        assert_eq!(*dog.health(), PaneHealth::Running);
+        assert!(!dog.check(start + UNRESPONSIVE_AFTER * 2, None, false));
+    }
+
+    #[test]
+    fn dead_pane_stays_dead() {
+        let mut dog = Watchdog::new();
+        dog.check(Instant::now(), Some(&ExitStatus::with_exit_code(0)), false);
+        dog.on_output();
+        assert!(!dog.check(Instant::now(), None, false));
+        assert!(dog.health().is_dead());
+    }
+
+    // ── Banner ──────────────────────────────────────────────────────
+
+    #[test]
+    fn banner_describes_exit() {
+        let exited = |code| PaneHealth::Exited { code, signal: None };
+        assert_eq!(PaneHealth::Running.banner(None), None);
+        assert_eq!(exited(0).banner(None).unwrap(), "Shell exited — press Enter to restart");
+        assert!(exited(127).banner(None).unwrap().starts_with("Shell exited with code 127"));
+        let killed = PaneHealth::Exited { code: 1, signal: Some("Killed".into()) };
+        assert!(killed.banner(None).unwrap().starts_with("Shell killed by Killed"));
+    }
+
+    #[test]
+    fn banner_names_restart_key() {
+        let failed = PaneHealth::SpawnFailed("no such file".into());
+        assert_eq!(
+            failed.banner(Some("ctrl+shift+r")).unwrap(),
+            "Could not start shell: no such file — press Enter or ctrl+shift+r to restart"
+        );
+        assert_eq!(
+            PaneHealth::Unresponsive.banner(Some("ctrl+shift+r")).unwrap(),
+            "Shell is not responding — press ctrl+shift+r to restart"
+        );
+    }
+}
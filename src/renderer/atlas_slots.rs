@@ -0,0 +1,194 @@
+// Slot bookkeeping for the glyph atlas. The atlas is a grid of cell-sized
+// slots: glyphs rasterized up front keep the first ones, the rest are handed
+// out as glyphs are first drawn. The atlas adds pages of slots while it may
+// grow; once it can't, the glyphs drawn least recently give theirs up.
+
+use std::collections::HashMap;
+
+/// What a set of on-demand slots holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlyphKey {
+    /// A single-width character.
+    Narrow(char),
+    /// Both halves of a double-width character.
+    Wide(char),
+    /// The cells of a ligature, by index into `LIGATURES`.
+    Ligature(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    slots: Vec<u32>,
+    /// Frame the glyph was last drawn in.
+    last_used: u64,
+    /// Never evicted (ligatures, loaded once with the atlas).
+    pinned: bool,
+}
+
+/// Slots handed to a glyph, and the glyphs evicted to free them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Allocation {
+    pub slots: Vec<u32>,
+    pub evicted: Vec<GlyphKey>,
+}
+
+/// Owners of the on-demand slots of one atlas.
+#[derive(Debug, Clone)]
+pub struct SlotCache {
+    /// Slots from here on don't exist yet.
+    capacity: u32,
+    /// Unused slots, lowest last so they are handed out first.
+    free: Vec<u32>,
+    entries: HashMap<GlyphKey, Entry>,
+    /// Current frame.
+    frame: u64,
+}
+
+impl SlotCache {
+    /// Manage slots `first..capacity`.
+    pub fn new(first: u32, capacity: u32) -> Self {
+        Self {
+            capacity: capacity.max(first),
+            free: (first..capacity).rev().collect(),
+            entries: HashMap::new(),
+            frame: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Number of glyphs holding slots.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add the slots up to `capacity`, e.g. for a new page.
+    pub fn grow(&mut self, capacity: u32) {
+        if capacity > self.capacity {
+            let added = (self.capacity..capacity).rev();
+            self.free.splice(0..0, added);
+            self.capacity = capacity;
+        }
+    }
+
+    /// Start a frame: glyphs touched from now on can't be evicted until
+    /// the next one.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Mark `key` drawn this frame. Returns whether it holds slots.
+    pub fn touch(&mut self, key: GlyphKey) -> bool {
+        match self.entries.get_mut(&key) {
+            Some(entry) => {
+                entry.last_used = self.frame;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn slots(&self, key: GlyphKey) -> Option<&[u32]> {
+        self.entries.get(&key).map(|entry| entry.slots.as_slice())
+    }
+
+    /// Give `key` `count` slots, evicting glyphs not drawn this frame,
+    /// least recently drawn first. None, with nothing evicted, when that
+    /// still leaves too few.
+    pub fn insert(&mut self, key: GlyphKey, count: usize, pinned: bool) -> Option<Allocation> {
+        let mut evicted = Vec::new();
+        if self.free.len() < count {
+            let mut candidates: Vec<(u64, GlyphKey, usize)> = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| !entry.pinned && entry.last_used < self.frame)
+                .map(|(&key, entry)| (entry.last_used, key, entry.slots.len()))
+                .collect();
+            candidates.sort_unstable_by_key(|&(last_used, _, _)| last_used);
+            let mut freed = self.free.len();
+            for (_, key, slots) in candidates {
+                if freed >= count {
+                    break;
+                }
+                freed += slots;
+                evicted.push(key);
+            }
+            if freed < count {
+                return None;
+            }
+            for key in &evicted {
+                if let Some(entry) = self.entries.remove(key) {
+                    self.free.extend(entry.slots.into_iter().rev());
+                }
+            }
+        }
+        let slots: Vec<u32> = (0..count).filter_map(|_| self.free.pop()).collect();
+        self.entries.insert(key, Entry { slots: slots.clone(), last_used: self.frame, pinned });
+        Some(Allocation { slots, evicted })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_are_handed_out_lowest_first() {
+        let mut cache = SlotCache::new(10, 14);
+        let a = cache.insert(GlyphKey::Narrow('a'), 1, false).unwrap();
+        let b = cache.insert(GlyphKey::Wide('b'), 2, false).unwrap();
+        assert_eq!(a.slots, [10]);
+        assert_eq!(b.slots, [11, 12]);
+        assert_eq!(cache.free_count(), 1);
+        cache.grow(16);
+        assert_eq!(cache.insert(GlyphKey::Wide('c'), 2, false).unwrap().slots, [13, 14]);
+        assert_eq!(cache.capacity(), 16);
+    }
+
+    #[test]
+    fn full_cache_evicts_least_recently_drawn() {
+        let mut cache = SlotCache::new(0, 3);
+        for c in ['a', 'b', 'c'] {
+            cache.insert(GlyphKey::Narrow(c), 1, false).unwrap();
+        }
+        cache.begin_frame();
+        cache.touch(GlyphKey::Narrow('a'));
+        cache.begin_frame();
+        cache.touch(GlyphKey::Narrow('c'));
+        let d = cache.insert(GlyphKey::Narrow('d'), 1, false).unwrap();
+        assert_eq!(d.evicted, [GlyphKey::Narrow('b')]);
+        assert_eq!(d.slots, [1]);
+        assert!(!cache.touch(GlyphKey::Narrow('b')));
+        // Two slots: the oldest remaining ('a') plus whatever is next
+        cache.begin_frame();
+        let wide = cache.insert(GlyphKey::Wide('e'), 2, false).unwrap();
+        assert_eq!(wide.evicted.first(), Some(&GlyphKey::Narrow('a')));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn glyphs_drawn_this_frame_and_pinned_ones_stay() {
+        let mut cache = SlotCache::new(0, 2);
+        cache.insert(GlyphKey::Ligature(0), 1, true).unwrap();
+        cache.insert(GlyphKey::Narrow('a'), 1, false).unwrap();
+        // 'a' was inserted this frame
+        assert_eq!(cache.insert(GlyphKey::Narrow('b'), 1, false), None);
+        cache.begin_frame();
+        assert_eq!(cache.insert(GlyphKey::Wide('c'), 2, false), None);
+        assert!(cache.touch(GlyphKey::Narrow('a')));
+        cache.begin_frame();
+        let b = cache.insert(GlyphKey::Narrow('b'), 1, false).unwrap();
+        assert_eq!(b.evicted, [GlyphKey::Narrow('a')]);
+        assert_eq!(cache.slots(GlyphKey::Ligature(0)), Some(&[0][..]));
+    }
+}
@@ -0,0 +1,331 @@
+// Background picture beneath the cell grid (`colors.background`): an image
+// or a top-to-bottom gradient, drawn as one textured quad before the panes.
+// Cells with the default background are then drawn clear over it (see
+// grid.wgsl), so it shows wherever the terminal paints nothing else. The
+// dim factor fades it toward the theme background to keep text readable.
+
+use crate::config::theme::{from_hex, TerminalTheme};
+use crate::config::types::BackgroundConfig;
+use crate::renderer::gpu;
+use crate::renderer::image_renderer::{
+    create_image_bind_group, create_image_texture, ImageQuad, SourceUV,
+};
+
+/// How an image fills the window (`fit`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fit {
+    /// Scaled to cover the window, cropped to its aspect ratio.
+    #[default]
+    Cover,
+    /// Repeated at its own size from the top-left corner.
+    Tile,
+    /// At its own size in the middle of the window.
+    Center,
+}
+
+impl Fit {
+    /// Convert a config string ("cover", "tile", "center").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "cover" => Some(Self::Cover),
+            "tile" => Some(Self::Tile),
+            "center" => Some(Self::Center),
+            _ => None,
+        }
+    }
+}
+
+/// sRGB pixels of a background, ready for upload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    /// A gradient: stretched over the window whatever the fit.
+    pub gradient: bool,
+}
+
+impl Picture {
+    /// A gradient one pixel wide with one row per color. Linear filtering
+    /// between the row centers blends neighbouring colors.
+    pub fn gradient(colors: &[String]) -> Self {
+        let rgba = colors
+            .iter()
+            .flat_map(|hex| {
+                let c = from_hex(hex);
+                [c.r, c.g, c.b, c.a].map(|v| (v * 255.0).round() as u8)
+            })
+            .collect();
+        Self { width: 1, height: colors.len() as u32, rgba, gradient: true }
+    }
+
+    /// Load the image at `path`, scaled down to fit `max_dim` on its
+    /// longest side.
+    pub fn load(path: &str, max_dim: u32) -> Result<Self, String> {
+        let mut image = image::open(path).map_err(|e| format!("{path}: {e}"))?;
+        if image.width() > max_dim || image.height() > max_dim {
+            image = image.resize(max_dim, max_dim, image::imageops::FilterType::Triangle);
+        }
+        let rgba = image.to_rgba8();
+        Ok(Self { width: rgba.width(), height: rgba.height(), rgba: rgba.into_raw(), gradient: false })
+    }
+
+    /// The picture `config` asks for, None when it asks for none.
+    pub fn from_config(config: &BackgroundConfig, max_dim: u32) -> Result<Option<Self>, String> {
+        if !config.image.is_empty() {
+            let home = std::env::var("HOME").ok();
+            let path = crate::directory_rules::expand_home(&config.image, home.as_deref());
+            return Self::load(&path, max_dim).map(Some);
+        }
+        Ok((config.gradient.len() >= 2).then(|| Self::gradient(&config.gradient)))
+    }
+}
+
+/// Where the quad goes (NDC) and which part of the picture it shows, for a
+/// `picture` of the given pixel size on a `surface`.
+pub fn placement(fit: Fit, picture: &Picture, surface: (u32, u32)) -> (ImageQuad, SourceUV) {
+    let full = ImageQuad { x0: -1.0, y0: 1.0, w: 2.0, h: 2.0 };
+    let (pw, ph) = (picture.width.max(1) as f32, picture.height.max(1) as f32);
+    let (sw, sh) = (surface.0.max(1) as f32, surface.1.max(1) as f32);
+    if picture.gradient {
+        // From the first row's center to the last one's
+        let v = 0.5 / ph;
+        return (full, SourceUV { u: 0.0, v, w: 1.0, h: 1.0 - 2.0 * v });
+    }
+    match fit {
+        Fit::Cover => {
+            let scale = (sw / pw).max(sh / ph);
+            let (w, h) = (sw / (pw * scale), sh / (ph * scale));
+            (full, SourceUV { u: (1.0 - w) / 2.0, v: (1.0 - h) / 2.0, w, h })
+        }
+        Fit::Tile => (full, SourceUV { u: 0.0, v: 0.0, w: sw / pw, h: sh / ph }),
+        Fit::Center => {
+            let (w, h) = (2.0 * pw / sw, 2.0 * ph / sh);
+            (ImageQuad { x0: -w / 2.0, y0: h / 2.0, w, h }, SourceUV { u: 0.0, v: 0.0, w: 1.0, h: 1.0 })
+        }
+    }
+}
+
+/// Uniform data for the background shader. Must match BackgroundUniforms
+/// in background.wgsl.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BackgroundUniforms {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub source_uv: [f32; 4],
+    /// Theme background (linear RGB) to fade toward; [3]: dim factor.
+    pub dim_color: [f32; 4],
+    /// [0]: window opacity. [1]: 1.0 if the surface composites
+    /// premultiplied alpha. [2], [3]: unused.
+    pub alpha: [f32; 4],
+}
+
+/// A background picture on the GPU.
+pub struct BackgroundLayer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    _texture: wgpu::Texture,
+    picture: Picture,
+    fit: Fit,
+    dim: f32,
+}
+
+impl BackgroundLayer {
+    /// Upload `picture` and build the pipeline drawing it.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        mut picture: Picture,
+        fit: Fit,
+        dim: f32,
+    ) -> Self {
+        let texture = create_image_texture(device, queue, picture.width, picture.height, &picture.rgba);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let address_mode = if fit == Fit::Tile && !picture.gradient {
+            wgpu::AddressMode::Repeat
+        } else {
+            wgpu::AddressMode::ClampToEdge
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Background Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Background Uniforms"),
+            size: std::mem::size_of::<BackgroundUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let layout = create_bind_group_layout(device);
+        let bind_group = create_image_bind_group(device, &layout, &uniform_buffer, &view, &sampler);
+        let pipeline = create_pipeline(device, format, &layout);
+        // Only the size is needed from here on
+        picture.rgba = Vec::new();
+        Self { pipeline, bind_group, uniform_buffer, _texture: texture, picture, fit, dim }
+    }
+
+    /// Write this frame's placement and colors.
+    pub fn prepare(
+        &self,
+        queue: &wgpu::Queue,
+        surface: (u32, u32),
+        theme: &TerminalTheme,
+        opacity: f32,
+        alpha_mode: wgpu::CompositeAlphaMode,
+    ) {
+        let (quad, uv) = placement(self.fit, &self.picture, surface);
+        let bg = gpu::clear_color(theme, 1.0, wgpu::CompositeAlphaMode::Auto);
+        let uniforms = BackgroundUniforms {
+            position: [quad.x0, quad.y0],
+            size: [quad.w, quad.h],
+            source_uv: [uv.u, uv.v, uv.w, uv.h],
+            dim_color: [bg.r as f32, bg.g as f32, bg.b as f32, self.dim],
+            alpha: gpu::background_uniform(opacity, alpha_mode, false),
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    /// Draw the quad. Call at the start of the pass, before the panes.
+    pub fn draw(&self, pass: &mut wgpu::RenderPass<'_>) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..6, 0..1);
+    }
+}
+
+fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Background Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: std::num::NonZeroU64::new(
+                        std::mem::size_of::<BackgroundUniforms>() as u64,
+                    ),
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    })
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Background Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/background.wgsl").into()),
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Background Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Background Render Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[], // No vertex buffer — quad generated from vertex_index
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                // The picture replaces the clear color, opacity included
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32) -> Picture {
+        Picture { width, height, rgba: Vec::new(), gradient: false }
+    }
+
+    #[test]
+    fn cover_crops_the_longer_side() {
+        // A square image on a 2:1 window shows its middle half vertically
+        let (quad, uv) = placement(Fit::Cover, &image(100, 100), (200, 100));
+        assert_eq!((quad.x0, quad.y0, quad.w, quad.h), (-1.0, 1.0, 2.0, 2.0));
+        assert_eq!((uv.u, uv.v, uv.w, uv.h), (0.0, 0.25, 1.0, 0.5));
+    }
+
+    #[test]
+    fn tile_and_center_keep_the_image_size() {
+        let (_, uv) = placement(Fit::Tile, &image(100, 50), (250, 100));
+        assert_eq!((uv.w, uv.h), (2.5, 2.0));
+        let (quad, uv) = placement(Fit::Center, &image(100, 50), (200, 100));
+        assert_eq!((quad.x0, quad.y0, quad.w, quad.h), (-0.5, 0.5, 1.0, 1.0));
+        assert_eq!((uv.w, uv.h), (1.0, 1.0));
+    }
+
+    #[test]
+    fn gradient_rows_span_the_window() {
+        let gradient = Picture::gradient(&["#000000".to_string(), "#ff8000".to_string()]);
+        assert_eq!((gradient.width, gradient.height), (1, 2));
+        assert_eq!(gradient.rgba, [0, 0, 0, 255, 255, 128, 0, 255]);
+        let (quad, uv) = placement(Fit::Center, &gradient, (640, 480));
+        assert_eq!(quad.w, 2.0);
+        assert_eq!((uv.v, uv.h), (0.25, 0.5));
+        assert_eq!(Fit::from_config_str("tile"), Some(Fit::Tile));
+        assert_eq!(Fit::from_config_str("stretch"), None);
+    }
+
+    #[test]
+    fn config_without_image_or_gradient_has_no_picture() {
+        let config = BackgroundConfig::default();
+        assert_eq!(Picture::from_config(&config, 4096), Ok(None));
+        let missing = BackgroundConfig { image: "/nonexistent/veloterm.png".into(), ..config };
+        assert!(Picture::from_config(&missing, 4096).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+// Accessibility color filters applied per pane in the grid fragment shader.
+//
+// Correction filters daltonize: simulate the deficiency (Machado et al. 2009,
+// severity 1.0, linear RGB), then shift the lost information into channels the
+// viewer can still distinguish.
+
+/// A post-processing color transform for a pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorFilter {
+    #[default]
+    None,
+    /// Correction for red-green deficiency (missing M cones).
+    Deuteranopia,
+    /// Correction for red-green deficiency (missing L cones).
+    Protanopia,
+    /// Correction for blue-yellow deficiency (missing S cones).
+    Tritanopia,
+    /// Luminance-only rendering.
+    Grayscale,
+}
+
+type Mat3 = [[f32; 3]; 3];
+
+const IDENTITY: Mat3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+const PROTANOPIA_SIM: Mat3 = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+
+const DEUTERANOPIA_SIM: Mat3 = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+const TRITANOPIA_SIM: Mat3 = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+/// Redistributes the error (original − simulated) into green and blue.
+const ERROR_SHIFT: Mat3 = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+
+const GRAYSCALE: Mat3 = [
+    [0.2126, 0.7152, 0.0722],
+    [0.2126, 0.7152, 0.0722],
+    [0.2126, 0.7152, 0.0722],
+];
+
+fn mat_mul(a: &Mat3, b: &Mat3) -> Mat3 {
+    let mut out = [[0.0; 3]; 3];
+    for (r, row) in out.iter_mut().enumerate() {
+        for (c, v) in row.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[r][k] * b[k][c]).sum();
+        }
+    }
+    out
+}
+
+/// Daltonization matrix: I + E·(I − S).
+fn correction(sim: &Mat3) -> Mat3 {
+    let mut lost = IDENTITY;
+    for (r, row) in lost.iter_mut().enumerate() {
+        for (c, v) in row.iter_mut().enumerate() {
+            *v -= sim[r][c];
+        }
+    }
+    let shifted = mat_mul(&ERROR_SHIFT, &lost);
+    let mut out = IDENTITY;
+    for (r, row) in out.iter_mut().enumerate() {
+        for (c, v) in row.iter_mut().enumerate() {
+            *v += shifted[r][c];
+        }
+    }
+    out
+}
+
+impl ColorFilter {
+    /// Parse a config string (e.g. "deuteranopia").
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(Self::None),
+            "deuteranopia" => Some(Self::Deuteranopia),
+            "protanopia" => Some(Self::Protanopia),
+            "tritanopia" => Some(Self::Tritanopia),
+            "grayscale" => Some(Self::Grayscale),
+            _ => None,
+        }
+    }
+
+    /// Config string for this filter.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Deuteranopia => "deuteranopia",
+            Self::Protanopia => "protanopia",
+            Self::Tritanopia => "tritanopia",
+            Self::Grayscale => "grayscale",
+        }
+    }
+
+    /// The next filter in cycling order (wraps back to None).
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Deuteranopia,
+            Self::Deuteranopia => Self::Protanopia,
+            Self::Protanopia => Self::Tritanopia,
+            Self::Tritanopia => Self::Grayscale,
+            Self::Grayscale => Self::None,
+        }
+    }
+
+    /// Row-major 3x3 transform in linear RGB.
+    pub fn matrix(self) -> Mat3 {
+        match self {
+            Self::None => IDENTITY,
+            Self::Deuteranopia => correction(&DEUTERANOPIA_SIM),
+            Self::Protanopia => correction(&PROTANOPIA_SIM),
+            Self::Tritanopia => correction(&TRITANOPIA_SIM),
+            Self::Grayscale => GRAYSCALE,
+        }
+    }
+
+    /// Matrix rows padded to vec4 for the WGSL uniform layout.
+    pub fn uniform_rows(self) -> [[f32; 4]; 3] {
+        let m = self.matrix();
+        [
+            [m[0][0], m[0][1], m[0][2], 0.0],
+            [m[1][0], m[1][1], m[1][2], 0.0],
+            [m[2][0], m[2][1], m[2][2], 0.0],
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &[&str] = &["none", "deuteranopia", "protanopia", "tritanopia", "grayscale"];
+
+    fn apply(m: &Mat3, c: [f32; 3]) -> [f32; 3] {
+        let mut out = [0.0; 3];
+        for (r, v) in out.iter_mut().enumerate() {
+            *v = (0..3).map(|k| m[r][k] * c[k]).sum();
+        }
+        out
+    }
+
+    #[test]
+    fn none_is_identity() {
+        assert_eq!(ColorFilter::None.matrix(), IDENTITY);
+    }
+
+    #[test]
+    fn config_str_round_trips() {
+        for name in ALL {
+            let filter = ColorFilter::from_config_str(name).unwrap();
+            assert_eq!(filter.as_str(), *name);
+        }
+        assert!(ColorFilter::from_config_str("sepia").is_none());
+    }
+
+    #[test]
+    fn next_cycles_through_all_filters() {
+        let mut filter = ColorFilter::None;
+        for _ in 0..ALL.len() {
+            filter = filter.next();
+        }
+        assert_eq!(filter, ColorFilter::None);
+    }
+
+    #[test]
+    fn grayscale_equalizes_channels() {
+        let out = apply(&ColorFilter::Grayscale.matrix(), [1.0, 0.0, 0.0]);
+        assert!((out[0] - out[1]).abs() < 1e-6 && (out[1] - out[2]).abs() < 1e-6);
+        assert!((out[0] - 0.2126).abs() < 1e-6);
+    }
+
+    #[test]
+    fn corrections_preserve_neutral_gray() {
+        // Each simulation row sums to ~1, so grays pass through unchanged.
+        for filter in [
+            ColorFilter::Deuteranopia,
+            ColorFilter::Protanopia,
+            ColorFilter::Tritanopia,
+        ] {
+            let out = apply(&filter.matrix(), [0.5, 0.5, 0.5]);
+            for v in out {
+                assert!((v - 0.5).abs() < 1e-3, "{filter:?} shifted gray to {out:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn deuteranopia_correction_changes_red() {
+        let out = apply(&ColorFilter::Deuteranopia.matrix(), [1.0, 0.0, 0.0]);
+        assert_ne!(out, [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn uniform_rows_pad_with_zero() {
+        let rows = ColorFilter::Grayscale.uniform_rows();
+        assert_eq!(rows[0][3], 0.0);
+        assert_eq!(rows[2][..3], GRAYSCALE[2]);
+    }
+}
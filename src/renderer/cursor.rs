@@ -2,6 +2,7 @@
 
 use crate::config::theme::{Color, color_new};
 use crate::renderer::gpu::CellInstance;
+use alacritty_terminal::vte::ansi::CursorShape;
 use std::time::{Duration, Instant};
 
 /// Cursor shape styles.
@@ -16,15 +17,52 @@ pub enum CursorStyle {
 /// Bit 0: has_glyph (existing)
 /// Bit 1: is_cursor
 /// Bits 2-3: cursor shape (00=block, 01=beam, 10=underline, 11=hollow_block)
+/// Bit 13: shape given as a `CursorRect` in atlas_uv (animated cursor)
 pub const FLAG_CURSOR: u32 = 0x02;
 pub const FLAG_CURSOR_BLOCK: u32 = 0x00 << 2;
 pub const FLAG_CURSOR_BEAM: u32 = 0x01 << 2;
 pub const FLAG_CURSOR_UNDERLINE: u32 = 0x02 << 2;
 pub const FLAG_CURSOR_HOLLOW: u32 = 0x03 << 2;
+pub const FLAG_CURSOR_RECT: u32 = 0x2000;
 
 /// Default cursor blink interval.
 pub const BLINK_INTERVAL: Duration = Duration::from_millis(500);
 
+/// The part of its cell an animated cursor fills: left, top, right, bottom,
+/// as fractions of the cell width and of the font's height.
+pub type CursorRect = [f32; 4];
+
+/// Ease-out cubic: quick to leave, gentle to arrive.
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from * (1.0 - t) + to * t
+}
+
+/// An animated cursor on its way from where it was drawn to where the
+/// terminal's cursor is, in display cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glide {
+    from: ([f32; 2], CursorRect),
+    to: ([f32; 2], CursorRect),
+    start: Instant,
+}
+
+impl Glide {
+    /// Position and shape `now`, eased over `duration`.
+    fn sample(&self, now: Instant, duration: Duration) -> ([f32; 2], CursorRect) {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f32();
+        let t = ease_out((elapsed / duration.as_secs_f32()).min(1.0));
+        let ((from_pos, from_rect), (to_pos, to_rect)) = (self.from, self.to);
+        (
+            std::array::from_fn(|i| lerp(from_pos[i], to_pos[i], t)),
+            std::array::from_fn(|i| lerp(from_rect[i], to_rect[i], t)),
+        )
+    }
+}
+
 /// Cursor foreground color (text under block cursor — contrasts with accent).
 pub const CURSOR_FG: Color = color_new(0.0784, 0.0784, 0.0745, 1.0); // #141413
 
@@ -42,8 +80,15 @@ pub struct CursorState {
     pub(crate) last_blink: Instant,
     /// Blink rate in milliseconds. 0 = disabled.
     pub blink_rate_ms: u64,
+    /// Whether the cursor blinks at all: the application's DECSCUSR
+    /// request, else `cursor.blink`.
+    pub blinking: bool,
     /// Timestamp of last keystroke for blink-pause behavior.
     pub(crate) last_keystroke: Option<Instant>,
+    /// Milliseconds a move or shape change takes to animate
+    /// (`cursor.animation`). 0 = jump at once.
+    pub animation_ms: u64,
+    glide: Option<Glide>,
 }
 
 impl Default for CursorState {
@@ -53,16 +98,45 @@ impl Default for CursorState {
 }
 
 impl CursorStyle {
-    /// Convert a config string ("block", "beam", "underline") to a CursorStyle.
-    /// Returns None for unknown strings.
+    /// Convert a config string ("block", "beam" or "bar", "underline") to a
+    /// CursorStyle. Returns None for unknown strings.
     pub fn from_config_str(s: &str) -> Option<Self> {
         match s {
             "block" => Some(CursorStyle::Block),
-            "beam" => Some(CursorStyle::Beam),
+            "beam" | "bar" => Some(CursorStyle::Beam),
             "underline" => Some(CursorStyle::Underline),
             _ => None,
         }
     }
+
+    /// The style for a terminal cursor shape. DECSCUSR only asks for
+    /// blocks, underlines and bars; anything else draws as a block.
+    pub fn from_shape(shape: CursorShape) -> Self {
+        match shape {
+            CursorShape::Beam => CursorStyle::Beam,
+            CursorShape::Underline => CursorStyle::Underline,
+            CursorShape::Block | CursorShape::HollowBlock | CursorShape::Hidden => CursorStyle::Block,
+        }
+    }
+
+    /// The terminal cursor shape for this style.
+    pub fn to_shape(self) -> CursorShape {
+        match self {
+            CursorStyle::Block => CursorShape::Block,
+            CursorStyle::Beam => CursorShape::Beam,
+            CursorStyle::Underline => CursorShape::Underline,
+        }
+    }
+
+    /// The rect an animated cursor of this style fills, matching what the
+    /// shader draws for the style's flag.
+    pub fn rect(self) -> CursorRect {
+        match self {
+            CursorStyle::Block => [0.0, 0.0, 1.0, 1.0],
+            CursorStyle::Beam => [0.0, 0.0, 0.08, 1.0],
+            CursorStyle::Underline => [0.0, 0.94, 1.0, 1.0],
+        }
+    }
 }
 
 impl CursorState {
@@ -82,7 +156,10 @@ impl CursorState {
             blink_visible: true,
             last_blink: Instant::now(),
             blink_rate_ms,
+            blinking: true,
             last_keystroke: None,
+            animation_ms: 0,
+            glide: None,
         }
     }
 
@@ -107,6 +184,23 @@ impl CursorState {
         self.blink_rate_ms = blink_rate_ms;
     }
 
+    /// Set how long the cursor takes to glide to a new cell or shape, in
+    /// milliseconds. 0 = disabled.
+    pub fn set_animation(&mut self, animation_ms: u64) {
+        self.animation_ms = animation_ms;
+        if animation_ms == 0 {
+            self.glide = None;
+        }
+    }
+
+    /// Set whether the cursor blinks. A steady cursor stays visible.
+    pub fn set_blinking(&mut self, blinking: bool) {
+        if !blinking {
+            self.blink_visible = true;
+        }
+        self.blinking = blinking;
+    }
+
     /// Notify cursor of a keystroke: force visible and reset blink timer.
     pub fn on_keystroke(&mut self) {
         self.blink_visible = true;
@@ -117,7 +211,7 @@ impl CursorState {
     /// Advance the blink timer. Returns true if blink state changed.
     pub fn tick_blink(&mut self) -> bool {
         // Blinking disabled
-        if self.blink_rate_ms == 0 {
+        if self.blink_rate_ms == 0 || !self.blinking {
             return false;
         }
 
@@ -163,6 +257,40 @@ impl CursorState {
         })
     }
 
+    /// Move `instance`, already placed in display cells, along the cursor's
+    /// glide toward it at `now`. A new position or shape starts a glide from
+    /// wherever the cursor was drawn; a focused cursor morphs between
+    /// shapes as a `CursorRect`. Without animation the instance is as given.
+    pub fn animate(&mut self, instance: Option<CellInstance>, now: Instant) -> Option<CellInstance> {
+        let mut instance = instance?;
+        if self.animation_ms == 0 {
+            return Some(instance);
+        }
+        let duration = Duration::from_millis(self.animation_ms);
+        let target = (instance.position, self.style.rect());
+        let glide = match self.glide {
+            Some(glide) if glide.to == target => glide,
+            Some(glide) => Glide { from: glide.sample(now, duration), to: target, start: now },
+            None => Glide { from: target, to: target, start: now },
+        };
+        self.glide = Some(glide);
+        let (position, rect) = glide.sample(now, duration);
+        instance.position = position;
+        if self.focused {
+            instance.atlas_uv = rect;
+            instance.flags |= FLAG_CURSOR_RECT;
+        }
+        Some(instance)
+    }
+
+    /// Whether a glide is still under way at `now`, so frames keep coming.
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.glide.is_some_and(|glide| {
+            glide.from != glide.to
+                && now.saturating_duration_since(glide.start) < Duration::from_millis(self.animation_ms)
+        })
+    }
+
     /// Compute the flags value for this cursor's style and focus state.
     fn cursor_flags(&self) -> u32 {
         let shape_flags = if !self.focused {
@@ -423,6 +551,19 @@ mod tests {
         assert_eq!(CursorStyle::from_config_str("box"), None);
     }
 
+    #[test]
+    fn from_config_str_bar_is_beam() {
+        assert_eq!(CursorStyle::from_config_str("bar"), Some(CursorStyle::Beam));
+    }
+
+    #[test]
+    fn terminal_shapes_round_trip() {
+        for style in [CursorStyle::Block, CursorStyle::Beam, CursorStyle::Underline] {
+            assert_eq!(CursorStyle::from_shape(style.to_shape()), style);
+        }
+        assert_eq!(CursorStyle::from_shape(CursorShape::HollowBlock), CursorStyle::Block);
+    }
+
     // ── Configurable blink rate ────────────────────────────────────
 
     #[test]
@@ -457,6 +598,18 @@ mod tests {
         assert!(!cursor.blink_visible);
     }
 
+    #[test]
+    fn steady_cursor_does_not_blink() {
+        let mut cursor = CursorState::with_blink_rate(200);
+        cursor.blink_visible = false;
+        cursor.set_blinking(false);
+        assert!(cursor.blink_visible);
+        cursor.last_blink = Instant::now() - Duration::from_millis(201);
+        assert!(!cursor.tick_blink());
+        cursor.set_blinking(true);
+        assert!(cursor.tick_blink());
+    }
+
     // ── Keystroke blink pause ──────────────────────────────────────
 
     #[test]
@@ -497,4 +650,66 @@ mod tests {
         let instance = cursor.to_cell_instance().unwrap();
         assert_eq!(instance.position, [25.0, 10.0]); // [col, row]
     }
+
+    // ── Cursor animation ───────────────────────────────────────────
+
+    fn animated_cursor() -> CursorState {
+        let mut cursor = CursorState::new();
+        cursor.set_animation(100);
+        cursor
+    }
+
+    #[test]
+    fn animation_off_leaves_instance_alone() {
+        let mut cursor = CursorState::new();
+        cursor.update_position(2, 3);
+        let instance = cursor.animate(cursor.to_cell_instance(), Instant::now()).unwrap();
+        assert_eq!(instance.position, [3.0, 2.0]);
+        assert_eq!(instance.flags & FLAG_CURSOR_RECT, 0);
+    }
+
+    #[test]
+    fn animated_cursor_glides_to_new_cell() {
+        let mut cursor = animated_cursor();
+        let start = Instant::now();
+        let first = cursor.animate(cursor.to_cell_instance(), start).unwrap();
+        assert_eq!(first.position, [0.0, 0.0]);
+        assert!(!cursor.is_animating(start));
+
+        cursor.update_position(0, 10);
+        let moved = cursor.animate(cursor.to_cell_instance(), start).unwrap();
+        assert_eq!(moved.position, [0.0, 0.0]);
+        assert!(cursor.is_animating(start));
+        let halfway = cursor.animate(cursor.to_cell_instance(), start + Duration::from_millis(50)).unwrap();
+        // Eased: past the midpoint at half time
+        assert!(halfway.position[0] > 5.0 && halfway.position[0] < 10.0, "{:?}", halfway.position);
+        let done = start + Duration::from_millis(100);
+        assert_eq!(cursor.animate(cursor.to_cell_instance(), done).unwrap().position, [10.0, 0.0]);
+        assert!(!cursor.is_animating(done));
+    }
+
+    #[test]
+    fn animated_cursor_morphs_between_shapes() {
+        let mut cursor = animated_cursor();
+        let start = Instant::now();
+        let block = cursor.animate(cursor.to_cell_instance(), start).unwrap();
+        assert_ne!(block.flags & FLAG_CURSOR_RECT, 0);
+        assert_eq!(block.atlas_uv, CursorStyle::Block.rect());
+
+        cursor.set_style(CursorStyle::Beam);
+        let _ = cursor.animate(cursor.to_cell_instance(), start);
+        let morphing = cursor.animate(cursor.to_cell_instance(), start + Duration::from_millis(30)).unwrap();
+        assert!(morphing.atlas_uv[2] < 1.0 && morphing.atlas_uv[2] > 0.08);
+        let done = cursor.animate(cursor.to_cell_instance(), start + Duration::from_millis(100)).unwrap();
+        assert_eq!(done.atlas_uv, CursorStyle::Beam.rect());
+    }
+
+    #[test]
+    fn unfocused_animated_cursor_stays_hollow() {
+        let mut cursor = animated_cursor();
+        cursor.set_focused(false);
+        let instance = cursor.animate(cursor.to_cell_instance(), Instant::now()).unwrap();
+        assert_eq!(instance.flags & FLAG_CURSOR_RECT, 0);
+        assert_eq!(instance.flags & (0x03 << 2), FLAG_CURSOR_HOLLOW);
+    }
 }
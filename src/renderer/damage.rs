@@ -1,4 +1,6 @@
+use crate::renderer::gpu::CellInstance;
 use crate::renderer::grid_renderer::GridCell;
+use std::ops::Range;
 use std::time::Duration;
 
 /// Compare two grid cell buffers row-by-row and return per-row dirty flags.
@@ -7,6 +9,19 @@ use std::time::Duration;
 /// in that row differs between `prev` and `curr`. If the slices have different
 /// lengths, all rows of the larger grid are marked dirty (full damage).
 pub fn diff_grid_rows(prev: &[GridCell], curr: &[GridCell], cols: usize) -> Vec<bool> {
+    diff_grid_spans(prev, curr, cols)
+        .iter()
+        .map(Option::is_some)
+        .collect()
+}
+
+/// Compare two grid cell buffers and return, per row, the column range
+/// `start..end` covering every changed cell (`None` for unchanged rows).
+///
+/// A cursor moving or blinking inside a line dirties a span of one or two
+/// cells rather than the whole row. If the slices have different lengths,
+/// every row of the larger grid is fully dirty.
+pub fn diff_grid_spans(prev: &[GridCell], curr: &[GridCell], cols: usize) -> Vec<Option<Range<usize>>> {
     if cols == 0 {
         return Vec::new();
     }
@@ -17,18 +32,24 @@ pub fn diff_grid_rows(prev: &[GridCell], curr: &[GridCell], cols: usize) -> Vec<
     // Different grid sizes → full damage on the larger grid
     if prev.len() != curr.len() {
         let max_rows = prev_rows.max(curr_rows);
-        return vec![true; max_rows];
+        return vec![Some(0..cols); max_rows];
     }
 
     (0..curr_rows)
         .map(|row| {
             let start = row * cols;
-            let end = start + cols;
-            prev[start..end] != curr[start..end]
+            diff_row(&prev[start..start + cols], &curr[start..start + cols])
         })
         .collect()
 }
 
+/// Column span covering every cell that differs between two rows.
+fn diff_row(prev: &[GridCell], curr: &[GridCell]) -> Option<Range<usize>> {
+    let first = prev.iter().zip(curr).position(|(a, b)| a != b)?;
+    let last = prev.iter().zip(curr).rposition(|(a, b)| a != b)?;
+    Some(first..last + 1)
+}
+
 /// Tracks per-row dirty state for the terminal grid.
 ///
 /// Enables selective GPU buffer updates by identifying which rows changed
@@ -93,6 +114,21 @@ pub struct DamageState {
     prev_cells: Option<Vec<GridCell>>,
     pub(crate) cols: usize,
     force_full: bool,
+    /// Instances built for the previous frame, patched in place for dirty
+    /// spans instead of being regenerated.
+    instances: Vec<CellInstance>,
+    /// GPU copy of `instances` with one more slot for the cursor overlay,
+    /// written only where the instances changed.
+    buffer: Option<wgpu::Buffer>,
+    /// Cursor overlay in the buffer's last slot.
+    cursor: Option<CellInstance>,
+    /// `PaneDamageMap` frame in which this state was last used.
+    last_used: u64,
+    /// Whether the last processed frame had any dirty row.
+    damaged: bool,
+    /// Whether the last frame came with a terminal damage feed, so the
+    /// cached cells are the bare grid the next feed describes.
+    fed: bool,
 }
 
 impl DamageState {
@@ -102,15 +138,37 @@ impl DamageState {
             prev_cells: None,
             cols,
             force_full: false,
+            instances: Vec::new(),
+            buffer: None,
+            cursor: None,
+            last_used: 0,
+            damaged: false,
+            fed: false,
         }
     }
 
+    /// Heap bytes held by the cell and instance caches.
+    pub fn memory_bytes(&self) -> usize {
+        let cells = self.prev_cells.as_ref().map_or(0, Vec::capacity);
+        cells * std::mem::size_of::<GridCell>()
+            + self.instances.capacity() * std::mem::size_of::<CellInstance>()
+    }
+
     /// Process a new frame's cells and return per-row dirty flags.
     ///
     /// On the first frame (no cache) or after `force_full_damage()`, returns all-dirty.
     /// Otherwise, diffs against the previous frame row-by-row.
     /// Updates the cache with the current cells after diffing.
     pub fn process_frame(&mut self, cells: &[GridCell]) -> Vec<bool> {
+        self.process_frame_spans(cells)
+            .iter()
+            .map(Option::is_some)
+            .collect()
+    }
+
+    /// Like `process_frame`, but returns the dirty column span of each row.
+    /// Full damage reports `0..cols` for every row.
+    pub fn process_frame_spans(&mut self, cells: &[GridCell]) -> Vec<Option<Range<usize>>> {
         let rows = if self.cols > 0 {
             cells.len() / self.cols
         } else {
@@ -118,17 +176,98 @@ impl DamageState {
         };
 
         let dirty = match self.prev_cells.as_ref() {
-            Some(prev) if !self.force_full => diff_grid_rows(prev, cells, self.cols),
+            Some(prev) if !self.force_full => diff_grid_spans(prev, cells, self.cols),
             _ => {
                 self.force_full = false;
-                vec![true; rows]
+                vec![Some(0..self.cols); rows]
             }
         };
 
-        self.prev_cells = Some(cells.to_vec());
+        match self.prev_cells.as_mut() {
+            Some(prev) => {
+                prev.clear();
+                prev.extend_from_slice(cells);
+            }
+            None => self.prev_cells = Some(cells.to_vec()),
+        }
+        self.damaged = dirty.iter().any(Option::is_some);
+        self.fed = false;
         dirty
     }
 
+    /// Like `process_frame_spans`, with the rows the terminal reports as
+    /// modified (`grid_bridge::take_row_damage`). When the previous frame
+    /// was fed too, only those rows are compared and cached, so an idle pane
+    /// costs nothing; otherwise every row is compared.
+    pub fn process_frame_fed(&mut self, cells: &[GridCell], feed: Option<&[bool]>) -> Vec<Option<Range<usize>>> {
+        let cols = self.cols;
+        let trusted = self.fed && !self.force_full;
+        match (feed, self.prev_cells.as_mut()) {
+            (Some(feed), Some(prev)) if trusted && prev.len() == cells.len() && feed.len() * cols == cells.len() => {
+                let dirty: Vec<_> = feed
+                    .iter()
+                    .enumerate()
+                    .map(|(row, &modified)| {
+                        if !modified {
+                            return None;
+                        }
+                        let row = row * cols..(row + 1) * cols;
+                        let span = diff_row(&prev[row.clone()], &cells[row.clone()]);
+                        prev[row.clone()].copy_from_slice(&cells[row]);
+                        span
+                    })
+                    .collect();
+                self.damaged = dirty.iter().any(Option::is_some);
+                dirty
+            }
+            _ => {
+                let dirty = self.process_frame_spans(cells);
+                self.fed = feed.is_some();
+                dirty
+            }
+        }
+    }
+
+    /// Cached instances from the previous frame (empty until first stored).
+    pub fn instances_mut(&mut self) -> &mut Vec<CellInstance> {
+        &mut self.instances
+    }
+
+    pub fn instances(&self) -> &[CellInstance] {
+        &self.instances
+    }
+
+    /// The pane's GPU instance buffer, once `ensure_buffer` created it.
+    pub fn buffer(&self) -> Option<&wgpu::Buffer> {
+        self.buffer.as_ref()
+    }
+
+    /// Make sure the GPU buffer holds exactly `len` instances, replacing it
+    /// when missing or sized differently. Returns true for a new buffer,
+    /// which needs all its contents uploaded.
+    pub fn ensure_buffer(&mut self, device: &wgpu::Device, len: usize) -> bool {
+        let size = (len * std::mem::size_of::<CellInstance>()) as u64;
+        if self.buffer.as_ref().is_some_and(|buffer| buffer.size() == size) {
+            return false;
+        }
+        self.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pane Instances"),
+            size,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        self.cursor = None;
+        true
+    }
+
+    /// Record this frame's cursor overlay. Returns true when it differs
+    /// from the one in the buffer and has to be uploaded.
+    pub fn update_cursor(&mut self, cursor: Option<CellInstance>) -> bool {
+        let changed = cursor.is_some() && cursor != self.cursor;
+        self.cursor = cursor;
+        changed
+    }
+
     /// Force the next frame to be fully dirty (e.g., on resize, theme change).
     pub fn force_full_damage(&mut self) {
         self.force_full = true;
@@ -138,15 +277,40 @@ impl DamageState {
     pub fn resize(&mut self, cols: usize) {
         self.cols = cols;
         self.prev_cells = None;
+        self.instances.clear();
     }
 }
 
+/// Frames a pane's damage state survives without being drawn (e.g. while its
+/// tab is in the background) before it is evicted. A pane that comes back is
+/// simply redrawn in full.
+pub const IDLE_EVICT_FRAMES: u64 = 3600;
+
+/// Size of the per-pane damage caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DamageStats {
+    /// Panes with a damage state.
+    pub panes: usize,
+    /// Cached cells across all panes.
+    pub cached_cells: usize,
+    /// Cached instances across all panes.
+    pub cached_instances: usize,
+    /// Heap bytes held by the caches.
+    pub bytes: usize,
+    /// Bytes of the per-pane GPU instance buffers.
+    pub gpu_bytes: u64,
+}
+
 /// Manages per-pane damage states for multi-pane rendering.
 ///
 /// Each pane gets its own independent DamageState, identified by PaneId.
-/// Panes can be added/removed dynamically as splits/closes occur.
+/// Panes can be added/removed dynamically as splits/closes occur; states of
+/// panes that no longer exist are dropped by `retain_panes`, and states left
+/// unused for `IDLE_EVICT_FRAMES` frames by `begin_frame`.
 pub struct PaneDamageMap {
     states: std::collections::HashMap<crate::pane::PaneId, DamageState>,
+    /// Frame counter, advanced by `begin_frame`.
+    frame: u64,
 }
 
 impl Default for PaneDamageMap {
@@ -160,14 +324,60 @@ impl PaneDamageMap {
     pub fn new() -> Self {
         Self {
             states: std::collections::HashMap::new(),
+            frame: 0,
+        }
+    }
+
+    /// Start a frame: advance the frame counter and evict states that have
+    /// gone unused for `IDLE_EVICT_FRAMES`. Returns the number evicted.
+    pub fn begin_frame(&mut self) -> usize {
+        self.frame += 1;
+        let frame = self.frame;
+        let before = self.states.len();
+        self.states
+            .retain(|_, state| frame - state.last_used <= IDLE_EVICT_FRAMES);
+        let evicted = before - self.states.len();
+        if evicted > 0 {
+            log::debug!("Evicted {evicted} idle pane damage states");
         }
+        evicted
     }
 
     /// Get or create the DamageState for a pane. New panes start with full damage.
+    ///
+    /// A state whose column count no longer matches is reset, so an ID reused
+    /// for a different pane (e.g. after session restore) never diffs against
+    /// the old pane's cells.
     pub fn get_or_create(&mut self, pane_id: crate::pane::PaneId, cols: usize) -> &mut DamageState {
-        self.states
+        let state = self
+            .states
             .entry(pane_id)
-            .or_insert_with(|| DamageState::new(cols))
+            .or_insert_with(|| DamageState::new(cols));
+        if state.cols != cols {
+            state.resize(cols);
+        }
+        state.last_used = self.frame;
+        state
+    }
+
+    /// Drop the states of panes for which `is_live` is false. Returns the
+    /// number dropped.
+    pub fn retain_panes(&mut self, mut is_live: impl FnMut(crate::pane::PaneId) -> bool) -> usize {
+        let before = self.states.len();
+        self.states.retain(|&id, _| is_live(id));
+        before - self.states.len()
+    }
+
+    /// Whether the pane was drawn this frame with any dirty row.
+    pub fn was_damaged(&self, pane_id: crate::pane::PaneId) -> bool {
+        self.states
+            .get(&pane_id)
+            .is_some_and(|state| state.last_used == self.frame && state.damaged)
+    }
+
+    /// The pane's GPU instance buffer, if it has been drawn.
+    pub fn buffer(&self, pane_id: crate::pane::PaneId) -> Option<&wgpu::Buffer> {
+        self.states.get(&pane_id).and_then(DamageState::buffer)
     }
 
     /// Remove the DamageState for a closed pane.
@@ -186,6 +396,18 @@ impl PaneDamageMap {
     pub fn pane_count(&self) -> usize {
         self.states.len()
     }
+
+    /// Current size of all damage caches.
+    pub fn stats(&self) -> DamageStats {
+        self.states.values().fold(DamageStats::default(), |mut stats, state| {
+            stats.panes += 1;
+            stats.cached_cells += state.prev_cells.as_ref().map_or(0, Vec::len);
+            stats.cached_instances += state.instances.len();
+            stats.bytes += state.memory_bytes();
+            stats.gpu_bytes += state.buffer.as_ref().map_or(0, wgpu::Buffer::size);
+            stats
+        })
+    }
 }
 
 /// Tracks per-frame timing metrics for the render loop.
@@ -764,4 +986,172 @@ mod tests {
         let dirty = map.get_or_create(id, 4).process_frame(&cells);
         assert!(dirty.iter().all(|&d| d));
     }
+
+
+    // ── Column span tests ───────────────────────────────────────────
+
+    #[test]
+    fn diff_spans_cover_only_changed_columns() {
+        let prev = make_grid(8, 3, 'A');
+        let mut curr = prev.clone();
+        curr[8 + 2] = GridCell::new('B', white(), black()); // row 1, col 2
+        curr[8 + 4] = GridCell::new('C', white(), black()); // row 1, col 4
+        let spans = diff_grid_spans(&prev, &curr, 8);
+        assert_eq!(spans, vec![None, Some(2..5), None]);
+    }
+
+    #[test]
+    fn diff_spans_different_sizes_are_full_rows() {
+        let small = make_grid(4, 2, 'A');
+        let large = make_grid(4, 3, 'A');
+        let spans = diff_grid_spans(&small, &large, 4);
+        assert_eq!(spans, vec![Some(0..4); 3]);
+    }
+
+    #[test]
+    fn damage_state_spans_track_cursor_sized_changes() {
+        let mut state = DamageState::new(4);
+        let cells = make_grid(4, 2, 'A');
+        assert_eq!(state.process_frame_spans(&cells), vec![Some(0..4); 2]);
+        let mut moved = cells.clone();
+        moved[7] = GridCell::new('_', white(), black());
+        assert_eq!(state.process_frame_spans(&moved), vec![None, Some(3..4)]);
+        assert_eq!(state.process_frame_spans(&moved), vec![None, None]);
+    }
+
+    #[test]
+    fn damage_state_force_full_reports_full_spans() {
+        let mut state = DamageState::new(4);
+        let cells = make_grid(4, 2, 'A');
+        let _ = state.process_frame_spans(&cells);
+        state.force_full_damage();
+        assert_eq!(state.process_frame_spans(&cells), vec![Some(0..4); 2]);
+    }
+
+    #[test]
+    fn fed_frames_compare_only_reported_rows() {
+        let mut state = DamageState::new(4);
+        let cells = make_grid(4, 3, 'A');
+        // The first feed can't be trusted yet: nothing cached to patch
+        assert_eq!(state.process_frame_fed(&cells, Some(&[false; 3])), vec![Some(0..4); 3]);
+        let mut changed = cells.clone();
+        changed[5] = GridCell::new('B', white(), black());
+        changed[9] = GridCell::new('C', white(), black());
+        // Row 2 is not reported, so it is neither compared nor cached
+        assert_eq!(
+            state.process_frame_fed(&changed, Some(&[false, true, false])),
+            vec![None, Some(1..2), None]
+        );
+        assert_eq!(state.process_frame_spans(&changed), vec![None, None, Some(1..2)]);
+    }
+
+    #[test]
+    fn feed_after_unfed_frame_compares_everything() {
+        let mut state = DamageState::new(4);
+        let cells = make_grid(4, 2, 'A');
+        let _ = state.process_frame_fed(&cells, Some(&[true; 2]));
+        let mut overlaid = cells.clone();
+        overlaid[1].flags = 1;
+        let _ = state.process_frame_fed(&overlaid, None);
+        // The cache holds overlaid cells the terminal knows nothing about
+        assert_eq!(state.process_frame_fed(&cells, Some(&[false; 2])), vec![Some(1..2), None]);
+        state.force_full_damage();
+        assert_eq!(state.process_frame_fed(&cells, Some(&[false; 2])), vec![Some(0..4); 2]);
+    }
+
+    #[test]
+    fn cursor_uploads_only_when_it_changes() {
+        let mut state = DamageState::new(4);
+        let cursor: CellInstance = bytemuck::Zeroable::zeroed();
+        assert!(state.update_cursor(Some(cursor)));
+        assert!(!state.update_cursor(Some(cursor)));
+        let moved = CellInstance { position: [1.0, 0.0], ..cursor };
+        assert!(state.update_cursor(Some(moved)));
+        assert!(!state.update_cursor(None));
+        assert!(state.update_cursor(Some(moved)), "a cursor shown again is rewritten");
+    }
+
+    #[test]
+    fn damage_state_resize_drops_cached_instances() {
+        let mut state = DamageState::new(4);
+        state.instances_mut().push(bytemuck::Zeroable::zeroed());
+        state.resize(8);
+        assert!(state.instances_mut().is_empty());
+    }
+
+
+    // ── PaneDamageMap eviction and stats ────────────────────────────
+
+    #[test]
+    fn pane_damage_retain_drops_dead_panes() {
+        let mut map = PaneDamageMap::new();
+        let cells = make_grid(4, 3, 'A');
+        for id in [1, 2, 3] {
+            let _ = map.get_or_create(PaneId(id), 4).process_frame(&cells);
+        }
+        assert_eq!(map.retain_panes(|id| id != PaneId(2)), 1);
+        assert_eq!(map.pane_count(), 2);
+    }
+
+    #[test]
+    fn pane_damage_evicts_idle_states() {
+        let mut map = PaneDamageMap::new();
+        let cells = make_grid(4, 3, 'A');
+        map.begin_frame();
+        let _ = map.get_or_create(PaneId(1), 4).process_frame(&cells);
+        let _ = map.get_or_create(PaneId(2), 4).process_frame(&cells);
+        for _ in 0..IDLE_EVICT_FRAMES {
+            map.begin_frame();
+            let _ = map.get_or_create(PaneId(1), 4);
+        }
+        assert_eq!(map.pane_count(), 2);
+        assert_eq!(map.begin_frame(), 1);
+        assert_eq!(map.pane_count(), 1);
+    }
+
+    #[test]
+    fn pane_damage_reports_panes_damaged_this_frame() {
+        let mut map = PaneDamageMap::new();
+        let cells = make_grid(4, 3, 'A');
+        map.begin_frame();
+        let _ = map.get_or_create(PaneId(1), 4).process_frame(&cells);
+        assert!(map.was_damaged(PaneId(1)));
+        map.begin_frame();
+        let _ = map.get_or_create(PaneId(1), 4).process_frame(&cells);
+        assert!(!map.was_damaged(PaneId(1)));
+        // Changed cells, but in a frame that has since ended
+        let _ = map.get_or_create(PaneId(1), 4).process_frame(&make_grid(4, 3, 'B'));
+        map.begin_frame();
+        assert!(!map.was_damaged(PaneId(1)));
+        assert!(!map.was_damaged(PaneId(2)));
+    }
+
+    #[test]
+    fn pane_damage_reused_id_with_new_width_starts_fresh() {
+        let mut map = PaneDamageMap::new();
+        let id = PaneId(7);
+        let _ = map.get_or_create(id, 4).process_frame(&make_grid(4, 3, 'A'));
+        map.get_or_create(id, 4).instances_mut().push(bytemuck::Zeroable::zeroed());
+
+        // Same ID, different pane geometry (e.g. after session restore)
+        let state = map.get_or_create(id, 6);
+        assert_eq!(state.cols, 6);
+        assert!(state.instances_mut().is_empty());
+        let dirty = state.process_frame(&make_grid(6, 3, 'A'));
+        assert!(dirty.iter().all(|&d| d));
+    }
+
+    #[test]
+    fn pane_damage_stats_account_for_caches() {
+        let mut map = PaneDamageMap::new();
+        assert_eq!(map.stats(), DamageStats::default());
+        let _ = map.get_or_create(PaneId(1), 4).process_frame(&make_grid(4, 3, 'A'));
+        let _ = map.get_or_create(PaneId(2), 4).process_frame(&make_grid(4, 2, 'B'));
+        let stats = map.stats();
+        assert_eq!(stats.panes, 2);
+        assert_eq!(stats.cached_cells, 20);
+        assert!(stats.bytes >= 20 * std::mem::size_of::<GridCell>());
+        map.remove(PaneId(1));
+        assert_eq!(map.stats().cached_cells, 8);
+    }
 }
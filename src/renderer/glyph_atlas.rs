@@ -2,11 +2,19 @@
 //
 // macOS: Uses CoreText for native-quality font rendering with platform-consistent
 // antialiasing. Produces RGBA atlas with per-channel coverage.
-// Other platforms: Uses cosmic-text (swash) for cross-platform glyph rasterization.
+// Other platforms: Uses cosmic-text (swash) for cross-platform glyph rasterization,
+// into an R8 atlas, or an RGBA one with subpixel antialiasing.
+//
+// Glyphs outside the up-front set are rasterized the first time they are
+// drawn. The atlas grows a page of slots at a time up to MAX_ATLAS_HEIGHT;
+// past that, glyphs not drawn recently give their slots to new ones.
 
-#[cfg(not(target_os = "macos"))]
-use cosmic_text::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping, SwashCache};
+use cosmic_text::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping};
 use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::renderer::atlas_slots::{GlyphKey, SlotCache};
+use crate::renderer::subpixel::{Coverage, MaskRasterizer, Subpixel};
 
 /// Source Code Pro Medium — bundled as a compiled-in resource.
 const SOURCE_CODE_PRO_TTF: &[u8] =
@@ -30,6 +38,28 @@ fn select_bundled_font(family: &str) -> &'static [u8] {
 /// Descenders, ascenders, and anti-aliased fringes need room beyond the cell boundary.
 pub(crate) const GLYPH_PADDING: u32 = 2;
 
+/// Slot rows added each time the atlas grows.
+const PAGE_ROWS: u32 = 8;
+
+/// Tallest the atlas grows by default, in pixels. Every GPU wgpu supports
+/// takes textures this size.
+pub const MAX_ATLAS_HEIGHT: u32 = 2048;
+
+/// UV rect for a glyph drawn at (`x`, `y`) in the atlas.
+///
+/// The width is the exact (fractional) cell width rather than the rounded-up
+/// slot width, so one texel maps to one screen pixel. At fractional scales
+/// cells start at fractional pixel offsets; with a 1:1 mapping the shader can
+/// place the glyph at that subpixel offset instead of stretching it.
+fn slot_uv(x: u32, y: u32, cell_width: f32, cell_h: u32, atlas_width: u32, atlas_height: u32) -> [f32; 4] {
+    [
+        x as f32 / atlas_width as f32,
+        y as f32 / atlas_height as f32,
+        cell_width / atlas_width as f32,
+        cell_h as f32 / atlas_height as f32,
+    ]
+}
+
 /// Metadata for a single glyph in the atlas.
 #[derive(Debug, Clone)]
 pub struct GlyphInfo {
@@ -37,6 +67,25 @@ pub struct GlyphInfo {
     pub uv: [f32; 4],
 }
 
+impl GlyphInfo {
+    /// Renormalize after the atlas grew from `old_height` to `new_height`.
+    fn rescale(&mut self, old_height: u32, new_height: u32) {
+        self.uv[1] = (self.uv[1] * old_height as f32).round() / new_height as f32;
+        self.uv[3] = (self.uv[3] * old_height as f32).round() / new_height as f32;
+    }
+}
+
+/// How the atlas changed since the renderer last uploaded it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AtlasChanges {
+    /// Pixel rows to upload again.
+    pub rows: Option<Range<u32>>,
+    /// The atlas grew, so the texture has to be recreated.
+    pub resized: bool,
+    /// Glyphs were evicted or renormalized: UVs handed out before are stale.
+    pub remapped: bool,
+}
+
 /// Rasterized glyph atlas for GPU text rendering.
 ///
 /// Contains an R8 texture with all ASCII printable glyphs (0x20..=0x7E)
@@ -54,23 +103,55 @@ pub struct GlyphAtlas {
     pub cell_height: f32,
     /// Bytes per pixel: 1 for R8 grayscale, 4 for RGBA.
     pub bytes_per_pixel: u32,
+    /// Subpixel order glyphs are rasterized for (always None with CoreText).
+    pub subpixel: Subpixel,
     /// Ratio of font metrics height (ascent+descent) to cell_height.
     /// Used by the shader to size the cursor to font height instead of full cell height.
     pub cursor_height_ratio: f32,
     glyphs: HashMap<char, GlyphInfo>,
+    /// Shaped ligatures by character sequence: one cell-sized slice per character.
+    ligatures: HashMap<String, Vec<GlyphInfo>>,
+    /// Double-width characters drawn across two cells: left and right slice.
+    wide_glyphs: HashMap<char, [GlyphInfo; 2]>,
+    /// Length in characters of the longest loaded ligature.
+    max_ligature_len: usize,
+    /// Font size in physical pixels (for shaping ligatures).
+    font_px: f32,
     /// Number of columns in the atlas grid layout.
     atlas_cols: u32,
     /// Slot width in pixels (cell + padding).
     slot_w: u32,
     /// Slot height in pixels (cell + padding).
     slot_h: u32,
-    /// Next slot index for on-demand glyph rasterization.
-    next_overflow_index: u32,
-    /// Whether the atlas data has been modified since the last GPU upload.
-    pub atlas_dirty: bool,
+    /// Owners of the slots after the up-front glyphs.
+    slots: SlotCache,
+    /// Tallest the atlas may grow, in pixels.
+    max_height: u32,
+    /// Changes not yet uploaded to the GPU.
+    changes: AtlasChanges,
     /// CoreText rasterizer for on-demand glyph rasterization (macOS only).
     #[cfg(target_os = "macos")]
     rasterizer: crate::renderer::coretext_rasterizer::CoreTextRasterizer,
+    /// Font system kept for on-demand wide glyphs; shaping falls back to
+    /// system fonts for scripts the bundled font lacks (non-macOS only).
+    #[cfg(not(target_os = "macos"))]
+    font_system: FontSystem,
+    /// Font attributes of the configured family (non-macOS only).
+    #[cfg(not(target_os = "macos"))]
+    attrs: Attrs<'static>,
+    /// Glyph mask rasterizer for on-demand glyphs (non-macOS only).
+    #[cfg(not(target_os = "macos"))]
+    rasterizer: MaskRasterizer,
+}
+
+/// Add `coverage` to pixel `index` of an atlas with `bytes_per_pixel`
+/// bytes per pixel. R8 atlases keep the alpha.
+fn blend_coverage(atlas_data: &mut [u8], index: usize, bytes_per_pixel: u32, coverage: Coverage) {
+    let bpp = bytes_per_pixel as usize;
+    let channels = if bpp == 1 { &coverage[3..] } else { &coverage[..] };
+    for (byte, &c) in atlas_data[index * bpp..(index + 1) * bpp].iter_mut().zip(channels) {
+        *byte = (*byte).max(c);
+    }
 }
 
 /// Extra UI chrome characters beyond ASCII printable range.
@@ -126,6 +207,15 @@ const EXTRA_CHARS: &[char] = &[
     '\u{2800}', '\u{28FF}',
 ];
 
+/// Character sequences tried as ligatures when `font.ligatures` is on. Only
+/// those the font actually substitutes are loaded.
+pub const LIGATURES: &[&str] = &[
+    "=>", "->", "<-", "!=", "==", "===", "!==", ">=", "<=", "=<", "<=>", "<>",
+    "&&", "||", "::", ":::", "..", "...", "..=", "//", "///", "/*", "*/", "|>",
+    "<|", "<<", ">>", "<<<", ">>>", "++", "--", "-->", "<--", "==>", "<==",
+    "=>>", "::=", ":=", "=:", "!!", "??", "?.", "#{", "#[", "</", "/>", "</>",
+];
+
 impl GlyphAtlas {
     /// Rasterize ASCII printable glyphs + UI chrome into a texture atlas.
     ///
@@ -136,14 +226,30 @@ impl GlyphAtlas {
         scale_factor: f32,
         font_family: &str,
         line_height_multiplier: f32,
+    ) -> Self {
+        Self::with_subpixel(font_size, scale_factor, font_family, line_height_multiplier, Subpixel::None)
+    }
+
+    /// Like `new`, with subpixel antialiasing for `subpixel` order monitors:
+    /// swash then fills an RGBA atlas with per-channel coverage. CoreText
+    /// decides its own smoothing, so macOS ignores it.
+    pub fn with_subpixel(
+        font_size: f32,
+        scale_factor: f32,
+        font_family: &str,
+        line_height_multiplier: f32,
+        subpixel: Subpixel,
     ) -> Self {
         #[cfg(target_os = "macos")]
         {
+            if subpixel.is_enabled() {
+                log::debug!("Subpixel antialiasing is left to CoreText on macOS");
+            }
             Self::new_coretext(font_size, scale_factor, font_family, line_height_multiplier)
         }
         #[cfg(not(target_os = "macos"))]
         {
-            Self::new_swash(font_size, scale_factor, font_family, line_height_multiplier)
+            Self::new_swash(font_size, scale_factor, font_family, line_height_multiplier, subpixel)
         }
     }
 
@@ -175,10 +281,10 @@ impl GlyphAtlas {
         // Atlas layout: 16 glyphs per row
         let ascii_count = 95u32;
         let glyph_count = ascii_count + EXTRA_CHARS.len() as u32;
-        let cols = 16u32;
-        let rows = glyph_count.div_ceil(cols);
-        let atlas_width = (cols * slot_w).next_power_of_two().max(512);
+        let rows = glyph_count.div_ceil(16);
+        let atlas_width = (16 * slot_w).next_power_of_two().max(512);
         let atlas_height = (rows * slot_h).next_power_of_two().max(512);
+        let cols = atlas_width / slot_w;
 
         let bytes_per_pixel = 4u32;
         let mut atlas_data =
@@ -219,17 +325,12 @@ impl GlyphAtlas {
             glyphs.insert(
                 c,
                 GlyphInfo {
-                    uv: [
-                        (slot_x + pad) as f32 / atlas_width as f32,
-                        (slot_y + pad) as f32 / atlas_height as f32,
-                        cell_w as f32 / atlas_width as f32,
-                        cell_h as f32 / atlas_height as f32,
-                    ],
+                    uv: slot_uv(slot_x + pad, slot_y + pad, cell_width, cell_h, atlas_width, atlas_height),
                 },
             );
         }
 
-        let next_overflow_index = chars.len() as u32;
+        let slots = SlotCache::new(chars.len() as u32, cols * (atlas_height / slot_h));
 
         log::info!(
             "CoreText atlas: {}x{} (slot: {}x{}, cell: {:.1}x{:.1}, {} glyphs)",
@@ -249,25 +350,32 @@ impl GlyphAtlas {
             cell_width,
             cell_height,
             bytes_per_pixel,
+            subpixel: Subpixel::None,
             cursor_height_ratio,
             glyphs,
+            ligatures: HashMap::new(),
+            wide_glyphs: HashMap::new(),
+            max_ligature_len: 0,
+            font_px: scaled_size,
             atlas_cols: cols,
             slot_w,
             slot_h,
-            next_overflow_index,
-            atlas_dirty: false,
+            slots,
+            max_height: MAX_ATLAS_HEIGHT,
+            changes: AtlasChanges::default(),
             rasterizer,
         }
     }
 
     /// Cross-platform: Rasterize using cosmic-text/swash.
-    /// Produces an R8 grayscale atlas.
+    /// Produces an R8 grayscale atlas, or RGBA with subpixel coverage.
     #[cfg(not(target_os = "macos"))]
     fn new_swash(
         font_size: f32,
         scale_factor: f32,
         font_family: &str,
         line_height_multiplier: f32,
+        subpixel: Subpixel,
     ) -> Self {
         let scaled_size = font_size * scale_factor;
         let line_height = (scaled_size * line_height_multiplier).ceil();
@@ -278,7 +386,7 @@ impl GlyphAtlas {
             .db_mut()
             .load_font_data(font_data.to_vec());
 
-        let mut swash_cache = SwashCache::new();
+        let mut rasterizer = MaskRasterizer::new(subpixel);
         let metrics = Metrics::new(scaled_size, line_height);
         let attrs = Self::resolve_font_attrs(font_family);
 
@@ -305,16 +413,15 @@ impl GlyphAtlas {
 
         let ascii_count = 95u32;
         let glyph_count = ascii_count + EXTRA_CHARS.len() as u32;
-        let cols = 16u32;
-        let rows = glyph_count.div_ceil(cols);
-        let atlas_width = (cols * slot_w).next_power_of_two().max(512);
+        let rows = glyph_count.div_ceil(16);
+        let atlas_width = (16 * slot_w).next_power_of_two().max(512);
         let atlas_height = (rows * slot_h).next_power_of_two().max(512);
+        let cols = atlas_width / slot_w;
 
-        let mut atlas_data = vec![0u8; (atlas_width * atlas_height) as usize];
+        let bytes_per_pixel = if subpixel.is_enabled() { 4 } else { 1 };
+        let mut atlas_data = vec![0u8; (atlas_width * atlas_height * bytes_per_pixel) as usize];
         let mut glyphs = HashMap::with_capacity(glyph_count as usize);
 
-        let white = cosmic_text::Color::rgb(0xFF, 0xFF, 0xFF);
-
         for (i, byte) in (0x20u8..=0x7Eu8).enumerate() {
             let c = byte as char;
             Self::rasterize_glyph(
@@ -325,15 +432,15 @@ impl GlyphAtlas {
                 slot_h,
                 atlas_width,
                 atlas_height,
+                bytes_per_pixel,
                 &mut atlas_data,
                 &mut glyphs,
                 &mut font_system,
-                &mut swash_cache,
+                &mut rasterizer,
                 &mut buffer,
                 attrs,
                 cell_width,
                 cell_height,
-                white,
             );
         }
 
@@ -347,15 +454,15 @@ impl GlyphAtlas {
                 slot_h,
                 atlas_width,
                 atlas_height,
+                bytes_per_pixel,
                 &mut atlas_data,
                 &mut glyphs,
                 &mut font_system,
-                &mut swash_cache,
+                &mut rasterizer,
                 &mut buffer,
                 attrs,
                 cell_width,
                 cell_height,
-                white,
             );
         }
 
@@ -370,7 +477,7 @@ impl GlyphAtlas {
             glyphs.len(),
         );
 
-        let next_overflow_index = ascii_count + EXTRA_CHARS.len() as u32;
+        let slots = SlotCache::new(glyph_count, cols * (atlas_height / slot_h));
 
         Self {
             atlas_data,
@@ -378,14 +485,23 @@ impl GlyphAtlas {
             atlas_height,
             cell_width,
             cell_height,
-            bytes_per_pixel: 1,
+            bytes_per_pixel,
+            subpixel,
             cursor_height_ratio,
             glyphs,
+            ligatures: HashMap::new(),
+            wide_glyphs: HashMap::new(),
+            max_ligature_len: 0,
+            font_px: scaled_size,
             atlas_cols: cols,
             slot_w,
             slot_h,
-            next_overflow_index,
-            atlas_dirty: false,
+            slots,
+            max_height: MAX_ATLAS_HEIGHT,
+            changes: AtlasChanges::default(),
+            font_system,
+            attrs,
+            rasterizer,
         }
     }
 
@@ -400,15 +516,15 @@ impl GlyphAtlas {
         slot_h: u32,
         atlas_width: u32,
         atlas_height: u32,
+        bytes_per_pixel: u32,
         atlas_data: &mut [u8],
         glyphs: &mut HashMap<char, GlyphInfo>,
         font_system: &mut FontSystem,
-        swash_cache: &mut SwashCache,
+        rasterizer: &mut MaskRasterizer,
         buffer: &mut Buffer,
         attrs: Attrs<'_>,
         cell_width: f32,
         cell_height: f32,
-        color: cosmic_text::Color,
     ) {
         let col = index % cols;
         let row = index / cols;
@@ -427,7 +543,7 @@ impl GlyphAtlas {
         let sw = slot_w;
         let sh = slot_h;
         let pad = GLYPH_PADDING;
-        buffer.draw(font_system, swash_cache, color, |x, y, _w, _h, c| {
+        rasterizer.draw(font_system, buffer, |x, y, coverage| {
             if x >= 0 && y >= 0 {
                 let xu = x as u32 + pad;
                 let yu = y as u32 + pad;
@@ -435,30 +551,21 @@ impl GlyphAtlas {
                 if xu < sw && yu < sh {
                     let ax = slot_x + xu;
                     let ay = slot_y + yu;
-                    let idx = (ay * aw + ax) as usize;
-                    // Coverage/alpha is in the alpha channel
-                    atlas_data[idx] = atlas_data[idx].max(c.a());
+                    blend_coverage(atlas_data, (ay * aw + ax) as usize, bytes_per_pixel, coverage);
                 }
             }
         });
 
-        let cell_w_px = cell_width.ceil() as u32;
         let cell_h_px = cell_height.ceil() as u32;
         glyphs.insert(
             c,
             GlyphInfo {
-                uv: [
-                    (slot_x + pad) as f32 / atlas_width as f32,
-                    (slot_y + pad) as f32 / atlas_height as f32,
-                    cell_w_px as f32 / atlas_width as f32,
-                    cell_h_px as f32 / atlas_height as f32,
-                ],
+                uv: slot_uv(slot_x + pad, slot_y + pad, cell_width, cell_h_px, atlas_width, atlas_height),
             },
         );
     }
 
     /// Resolve font family name to cosmic-text `Attrs` with fallback chain.
-    #[cfg(not(target_os = "macos"))]
     fn resolve_font_attrs(font_family: &str) -> Attrs<'static> {
         match font_family.to_lowercase().as_str() {
             "source code pro" => Attrs::new().family(Family::Name("Source Code Pro")),
@@ -480,71 +587,321 @@ impl GlyphAtlas {
         self.glyphs.get(&c)
     }
 
+    /// Shape every `LIGATURES` sequence with `font_family` and rasterize the
+    /// ones the font substitutes. Each ligature is drawn across its cells and
+    /// cut into cell-sized slices, one atlas slot per character, so the grid
+    /// still draws one quad per cell. Ligature slots are never evicted.
+    /// Returns the number loaded; stops early when the atlas is full.
+    pub fn load_ligatures(&mut self, font_family: &str) -> usize {
+        use cosmic_text::{fontdb, rustybuzz, CacheKey, CacheKeyFlags};
+
+        let mut font_system = FontSystem::new();
+        font_system
+            .db_mut()
+            .load_font_data(select_bundled_font(font_family).to_vec());
+        let attrs = Self::resolve_font_attrs(font_family);
+        let query = fontdb::Query {
+            families: &[attrs.family],
+            ..Default::default()
+        };
+        let Some(font) = font_system
+            .db()
+            .query(&query)
+            .and_then(|id| font_system.get_font(id))
+        else {
+            log::warn!("No font found for ligatures in '{font_family}'");
+            return 0;
+        };
+
+        // Baseline of a laid-out line, so ligatures sit where single glyphs do.
+        // cosmic-text shapes word by word, which splits `->` and `!=`, so the
+        // sequences themselves are shaped with rustybuzz.
+        let mut buffer = Buffer::new(&mut font_system, Metrics::new(self.font_px, self.cell_height));
+        buffer.set_text(&mut font_system, "M", attrs, Shaping::Advanced);
+        buffer.shape_until_scroll(&mut font_system, true);
+        let baseline = buffer.layout_runs().next().map_or(self.font_px, |run| run.line_y);
+
+        let face = font.rustybuzz();
+        let scale = self.font_px / face.units_per_em() as f32;
+        let mut rasterizer = MaskRasterizer::new(self.subpixel);
+        let pad = GLYPH_PADDING;
+
+        for (index, &seq) in LIGATURES.iter().enumerate() {
+            let len = seq.chars().count();
+            let mut unicode = rustybuzz::UnicodeBuffer::new();
+            unicode.push_str(seq);
+            let shaped = rustybuzz::shape(face, &[], unicode);
+            let plain = seq.chars().map(|c| face.glyph_index(c).map(|g| u32::from(g.0)));
+            if shaped.glyph_infos().iter().map(|g| Some(g.glyph_id)).eq(plain) {
+                continue;
+            }
+
+            let Some(slots) = self.claim_slots(GlyphKey::Ligature(index), len, true) else {
+                log::warn!("Glyph atlas full, loaded {} ligatures", self.ligatures.len());
+                break;
+            };
+
+            let mut pen_x = 0.0;
+            for (info, pos) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+                let origin = (
+                    pen_x + pos.x_offset as f32 * scale,
+                    baseline - pos.y_offset as f32 * scale,
+                );
+                pen_x += pos.x_advance as f32 * scale;
+                let (key, gx, gy) = CacheKey::new(
+                    font.id(),
+                    info.glyph_id as u16,
+                    self.font_px,
+                    origin,
+                    CacheKeyFlags::empty(),
+                );
+                let (cell_width, slot_w, slot_h) = (self.cell_width, self.slot_w, self.slot_h);
+                let (aw, bpp) = (self.atlas_width, self.bytes_per_pixel);
+                let atlas_data = &mut self.atlas_data;
+                rasterizer.with_pixels(&mut font_system, key, |x, y, coverage| {
+                    let (x, y) = (gx + x, gy + y);
+                    if x < 0 || y < 0 {
+                        return;
+                    }
+                    // Cut the strip into cells; each slice lands in its own slot
+                    let k = ((x as f32 / cell_width) as usize).min(len - 1);
+                    let local_x = x - (k as f32 * cell_width).round() as i32;
+                    if local_x < 0 {
+                        return;
+                    }
+                    let (xu, yu) = (local_x as u32 + pad, y as u32 + pad);
+                    if xu < slot_w && yu < slot_h {
+                        let (slot_x, slot_y) = slots[k];
+                        blend_coverage(atlas_data, ((slot_y + yu) * aw + slot_x + xu) as usize, bpp, coverage);
+                    }
+                });
+            }
+
+            let slices = slots.iter().map(|&origin| self.slot_info(origin)).collect();
+            self.ligatures.insert(seq.to_string(), slices);
+            self.max_ligature_len = self.max_ligature_len.max(len);
+        }
+
+        log::info!("Loaded {} ligatures", self.ligatures.len());
+        self.ligatures.len()
+    }
+
+    /// The per-cell slices of the ligature for `seq`, if one was loaded.
+    pub fn ligature(&self, seq: &str) -> Option<&[GlyphInfo]> {
+        self.ligatures.get(seq).map(Vec::as_slice)
+    }
+
+    /// Whether any ligatures are loaded (`font.ligatures` on and supported by the font).
+    pub fn has_ligatures(&self) -> bool {
+        !self.ligatures.is_empty()
+    }
+
+    /// Length in characters of the longest loaded ligature.
+    pub fn max_ligature_len(&self) -> usize {
+        self.max_ligature_len
+    }
+
     /// Rasterize a glyph on demand if not already in the atlas.
     /// Returns the glyph info if successfully rasterized (or already present),
-    /// or None if the atlas has no room for more glyphs.
-    /// Sets `atlas_dirty` to true when new glyphs are added.
-    #[cfg(target_os = "macos")]
+    /// or None if no slot could be freed for it this frame.
     pub fn rasterize_on_demand(&mut self, c: char) -> Option<&GlyphInfo> {
         if self.glyphs.contains_key(&c) {
             return self.glyphs.get(&c);
         }
+        let slots = self.claim_slots(GlyphKey::Narrow(c), 1, false)?;
+        self.draw_glyph(c, &slots);
+        let info = self.slot_info(slots[0]);
+        self.glyphs.insert(c, info);
+        self.glyphs.get(&c)
+    }
 
-        let index = self.next_overflow_index;
-        let col = index % self.atlas_cols;
-        let row = index / self.atlas_cols;
-        let slot_x = col * self.slot_w;
-        let slot_y = row * self.slot_h;
+    /// The left and right cell slices of a double-width character, if loaded.
+    pub fn wide_glyph(&self, c: char) -> Option<&[GlyphInfo; 2]> {
+        self.wide_glyphs.get(&c)
+    }
 
-        // Check if slot fits within atlas bounds
-        if slot_x + self.slot_w > self.atlas_width || slot_y + self.slot_h > self.atlas_height {
-            return None;
+    /// Rasterize a double-width character (CJK, emoji) across two cells on
+    /// demand, cut into one slice per cell like a ligature. Returns None when
+    /// no two slots could be freed for it this frame.
+    pub fn rasterize_wide_on_demand(&mut self, c: char) -> Option<&[GlyphInfo; 2]> {
+        if self.wide_glyphs.contains_key(&c) {
+            return self.wide_glyphs.get(&c);
         }
+        let slots = self.claim_slots(GlyphKey::Wide(c), 2, false)?;
+        self.draw_glyph(c, &slots);
+        let slices = [self.slot_info(slots[0]), self.slot_info(slots[1])];
+        self.wide_glyphs.insert(c, slices);
+        self.wide_glyphs.get(&c)
+    }
+
+    /// Start a frame: glyphs prepared from now on keep their slots until
+    /// the next one.
+    pub fn begin_frame(&mut self) {
+        self.slots.begin_frame();
+    }
 
+    /// Make sure a cell's character can be drawn this frame: rasterize it
+    /// the first time, otherwise mark it used so it isn't evicted.
+    pub fn prepare(&mut self, c: char, wide: bool) {
+        if wide {
+            if !self.slots.touch(GlyphKey::Wide(c)) {
+                self.rasterize_wide_on_demand(c);
+            }
+        } else if !c.is_ascii() && !self.slots.touch(GlyphKey::Narrow(c)) && !self.glyphs.contains_key(&c) {
+            // Printable ASCII is always loaded
+            self.rasterize_on_demand(c);
+        }
+    }
+
+    /// Limit growth to `max_height` pixels, e.g. the GPU's texture limit.
+    /// The atlas never shrinks below its current height.
+    pub fn set_max_height(&mut self, max_height: u32) {
+        self.max_height = max_height;
+    }
+
+    /// Whether the atlas changed since the last `take_changes`.
+    pub fn is_dirty(&self) -> bool {
+        self.changes != AtlasChanges::default()
+    }
+
+    /// Changes to upload to the GPU, clearing them.
+    pub fn take_changes(&mut self) -> AtlasChanges {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Draw `c` into one slot per cell it spans, cut like a ligature.
+    #[cfg(target_os = "macos")]
+    fn draw_glyph(&mut self, c: char, slots: &[(u32, u32)]) {
+        let cells = slots.len();
         let cell_w = self.cell_width.ceil() as u32;
         let cell_h = self.cell_height.ceil() as u32;
         let pad = GLYPH_PADDING;
         let bpp = self.bytes_per_pixel;
+        let glyph_bmp = self.rasterizer.rasterize(c, cell_w * cells as u32, cell_h);
 
-        let glyph_bmp = self.rasterizer.rasterize(c, cell_w, cell_h);
-
-        // Copy RGBA data into padded slot in atlas
         for y in 0..glyph_bmp.height {
             for x in 0..glyph_bmp.width {
-                let src_idx = ((y * glyph_bmp.width + x) * 4) as usize;
-                let dst_x = slot_x + pad + x;
-                let dst_y = slot_y + pad + y;
-                if dst_x < self.atlas_width && dst_y < self.atlas_height {
-                    let dst_idx = ((dst_y * self.atlas_width + dst_x) * bpp) as usize;
-                    self.atlas_data[dst_idx..dst_idx + 4]
-                        .copy_from_slice(&glyph_bmp.data[src_idx..src_idx + 4]);
+                let k = ((x as f32 / self.cell_width) as usize).min(cells - 1);
+                let local_x = x.saturating_sub((k as f32 * self.cell_width).round() as u32);
+                if local_x + pad >= self.slot_w || y + pad >= self.slot_h {
+                    continue;
                 }
+                let (slot_x, slot_y) = slots[k];
+                let src_idx = ((y * glyph_bmp.width + x) * 4) as usize;
+                let dst_idx = (((slot_y + pad + y) * self.atlas_width + slot_x + pad + local_x) * bpp) as usize;
+                self.atlas_data[dst_idx..dst_idx + 4].copy_from_slice(&glyph_bmp.data[src_idx..src_idx + 4]);
             }
         }
+    }
 
-        self.glyphs.insert(
-            c,
-            GlyphInfo {
-                uv: [
-                    (slot_x + pad) as f32 / self.atlas_width as f32,
-                    (slot_y + pad) as f32 / self.atlas_height as f32,
-                    cell_w as f32 / self.atlas_width as f32,
-                    cell_h as f32 / self.atlas_height as f32,
-                ],
-            },
-        );
+    /// Draw `c` into one slot per cell it spans, cut like a ligature.
+    /// Shaping falls back to system fonts for characters the configured
+    /// font lacks.
+    #[cfg(not(target_os = "macos"))]
+    fn draw_glyph(&mut self, c: char, slots: &[(u32, u32)]) {
+        let font_system = &mut self.font_system;
+        let mut buffer = Buffer::new(font_system, Metrics::new(self.font_px, self.cell_height));
+        buffer.set_text(font_system, &c.to_string(), self.attrs, Shaping::Advanced);
+        buffer.set_size(font_system, Some(self.cell_width * 4.0), Some(self.cell_height * 2.0));
+        buffer.shape_until_scroll(font_system, true);
 
-        self.next_overflow_index += 1;
-        self.atlas_dirty = true;
+        let cells = slots.len();
+        let (cell_width, slot_w, slot_h, aw) = (self.cell_width, self.slot_w, self.slot_h, self.atlas_width);
+        let (pad, bpp) = (GLYPH_PADDING, self.bytes_per_pixel);
+        let atlas_data = &mut self.atlas_data;
+        self.rasterizer.draw(font_system, &buffer, |x, y, coverage| {
+            if x < 0 || y < 0 {
+                return;
+            }
+            let k = ((x as f32 / cell_width) as usize).min(cells - 1);
+            let local_x = x - (k as f32 * cell_width).round() as i32;
+            if local_x < 0 {
+                return;
+            }
+            let (xu, yu) = (local_x as u32 + pad, y as u32 + pad);
+            if xu < slot_w && yu < slot_h {
+                let (slot_x, slot_y) = slots[k];
+                blend_coverage(atlas_data, ((slot_y + yu) * aw + slot_x + xu) as usize, bpp, coverage);
+            }
+        });
+    }
 
-        self.glyphs.get(&c)
+    /// `count` cleared slots for `key`, adding pages while the atlas may
+    /// grow and evicting glyphs not drawn this frame after that. Returns
+    /// their pixel origins.
+    fn claim_slots(&mut self, key: GlyphKey, count: usize, pinned: bool) -> Option<Vec<(u32, u32)>> {
+        while self.slots.free_count() < count && self.add_page() {}
+        let allocation = self.slots.insert(key, count, pinned)?;
+        for evicted in allocation.evicted {
+            match evicted {
+                GlyphKey::Narrow(c) => {
+                    self.glyphs.remove(&c);
+                }
+                GlyphKey::Wide(c) => {
+                    self.wide_glyphs.remove(&c);
+                }
+                GlyphKey::Ligature(index) => {
+                    self.ligatures.remove(LIGATURES[index]);
+                }
+            }
+            self.changes.remapped = true;
+        }
+        let origins: Vec<(u32, u32)> = allocation
+            .slots
+            .iter()
+            .map(|&index| ((index % self.atlas_cols) * self.slot_w, (index / self.atlas_cols) * self.slot_h))
+            .collect();
+        for &origin in &origins {
+            self.clear_slot(origin);
+        }
+        Some(origins)
     }
 
-    /// Rasterize a glyph on demand (non-macOS stub — not yet implemented).
-    #[cfg(not(target_os = "macos"))]
-    pub fn rasterize_on_demand(&mut self, _c: char) -> Option<&GlyphInfo> {
-        // TODO: Implement cosmic-text on-demand rasterization
-        None
+    /// Blank a slot before drawing a new glyph into it.
+    fn clear_slot(&mut self, (x, y): (u32, u32)) {
+        let bpp = self.bytes_per_pixel as usize;
+        for row in y..y + self.slot_h {
+            let start = (row * self.atlas_width + x) as usize * bpp;
+            self.atlas_data[start..start + self.slot_w as usize * bpp].fill(0);
+        }
+        let rows = y..y + self.slot_h;
+        self.changes.rows = Some(match self.changes.rows.take() {
+            Some(dirty) => dirty.start.min(rows.start)..dirty.end.max(rows.end),
+            None => rows,
+        });
+    }
+
+    /// Append a page of slot rows, unless that would pass the height limit.
+    fn add_page(&mut self) -> bool {
+        let old_height = self.atlas_height;
+        let new_height = old_height + PAGE_ROWS * self.slot_h;
+        if new_height > self.max_height {
+            return false;
+        }
+        self.atlas_data
+            .resize((self.atlas_width * new_height * self.bytes_per_pixel) as usize, 0);
+        self.atlas_height = new_height;
+        // UVs are normalized to the atlas size
+        let glyphs = self.glyphs.values_mut();
+        let ligatures = self.ligatures.values_mut().flatten();
+        let wide = self.wide_glyphs.values_mut().flatten();
+        for info in glyphs.chain(ligatures).chain(wide) {
+            info.rescale(old_height, new_height);
+        }
+        self.slots.grow(self.atlas_cols * (new_height / self.slot_h));
+        self.changes.resized = true;
+        self.changes.remapped = true;
+        log::debug!("Glyph atlas grew to {}x{}", self.atlas_width, new_height);
+        true
+    }
+
+    /// UV of the cell drawn in the slot at `origin`.
+    fn slot_info(&self, (x, y): (u32, u32)) -> GlyphInfo {
+        let pad = GLYPH_PADDING;
+        let cell_h = self.cell_height.ceil() as u32;
+        GlyphInfo {
+            uv: slot_uv(x + pad, y + pad, self.cell_width, cell_h, self.atlas_width, self.atlas_height),
+        }
     }
 
     /// Dump atlas as PGM (grayscale) image for debugging.
@@ -798,6 +1155,21 @@ mod tests {
 
     // ── Glyph padding tests ────────────────────────────────────────
 
+    #[test]
+    fn atlas_uv_width_is_exact_cell_width() {
+        // 1:1 texel-to-pixel mapping for subpixel placement
+        let atlas = GlyphAtlas::new(13.0, 1.25, "JetBrains Mono", 1.5);
+        let info = atlas.glyph_info('A').unwrap();
+        let uv_w_px = info.uv[2] * atlas.atlas_width as f32;
+        assert!((uv_w_px - atlas.cell_width).abs() < 1e-3);
+    }
+
+    #[test]
+    fn slot_uv_normalizes_to_atlas() {
+        let uv = slot_uv(64, 32, 10.5, 20, 512, 256);
+        assert_eq!(uv, [0.125, 0.125, 10.5 / 512.0, 20.0 / 256.0]);
+    }
+
     #[test]
     fn atlas_uv_covers_cell_area_not_padded_slot() {
         let atlas = create_test_atlas();
@@ -924,6 +1296,21 @@ mod tests {
         assert_eq!(atlas.bytes_per_pixel, 1, "Linux should use R8 grayscale atlas");
     }
 
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn subpixel_atlas_has_per_channel_coverage() {
+        let mut atlas = GlyphAtlas::with_subpixel(13.0, 1.0, "JetBrains Mono", 1.5, Subpixel::Rgb);
+        assert_eq!(atlas.bytes_per_pixel, 4);
+        assert_eq!(atlas.atlas_data.len(), (atlas.atlas_width * atlas.atlas_height * 4) as usize);
+        // Stem edges cover the channels differently
+        let fringed = |data: &[u8]| data.chunks(4).any(|px| px[0] != px[2]);
+        assert!(fringed(&atlas.atlas_data));
+        // On-demand glyphs too
+        let before = atlas.atlas_data.clone();
+        atlas.rasterize_on_demand('Ж').unwrap();
+        assert_ne!(atlas.atlas_data, before);
+    }
+
     #[test]
     fn atlas_scale_factors_produce_different_sizes() {
         let atlas_1x = GlyphAtlas::new(13.0, 1.0, "Source Code Pro", 1.5);
@@ -1215,9 +1602,9 @@ mod tests {
     #[test]
     fn on_demand_sets_dirty_flag() {
         let mut atlas = create_test_atlas();
-        assert!(!atlas.atlas_dirty);
+        assert!(!atlas.is_dirty());
         atlas.rasterize_on_demand('α');
-        assert!(atlas.atlas_dirty, "atlas should be dirty after on-demand rasterization");
+        assert!(atlas.is_dirty(), "atlas should be dirty after on-demand rasterization");
     }
 
     #[test]
@@ -1246,4 +1633,131 @@ mod tests {
             "should not rasterize infinitely many chars"
         );
     }
+
+
+    // ── Ligatures ───────────────────────────────────────────────────
+
+    #[test]
+    fn ligatures_are_not_loaded_by_default() {
+        let atlas = create_test_atlas();
+        assert!(!atlas.has_ligatures());
+        assert!(atlas.ligature("=>").is_none());
+    }
+
+    #[test]
+    fn loaded_ligatures_have_a_slice_per_char() {
+        let mut atlas = create_test_atlas();
+        let count = atlas.load_ligatures("JetBrains Mono");
+        // JetBrains Mono ships programming ligatures
+        assert!(atlas.ligature("=>").is_some());
+        assert!(atlas.ligature("->").is_some());
+        assert!(atlas.ligature("!=").is_some());
+        assert!(atlas.max_ligature_len() >= 3);
+        for seq in LIGATURES {
+            if let Some(slices) = atlas.ligature(seq) {
+                assert_eq!(slices.len(), seq.chars().count(), "{seq}");
+                for slice in slices {
+                    let [u, v, w, h] = slice.uv;
+                    assert!(u + w <= 1.0 + 1e-6 && v + h <= 1.0 + 1e-6, "{seq} out of bounds");
+                }
+            }
+        }
+        assert_eq!(atlas.has_ligatures(), count > 0);
+        assert!(atlas.is_dirty());
+    }
+
+    #[test]
+    fn ligature_slices_have_pixels() {
+        let mut atlas = create_test_atlas();
+        atlas.load_ligatures("JetBrains Mono");
+        let slices = atlas.ligature("=>").unwrap();
+        let bpp = atlas.bytes_per_pixel as usize;
+        for slice in slices {
+            let [u, v, w, h] = slice.uv;
+            let x0 = (u * atlas.atlas_width as f32) as usize;
+            let y0 = (v * atlas.atlas_height as f32) as usize;
+            let x1 = ((u + w) * atlas.atlas_width as f32) as usize;
+            let y1 = ((v + h) * atlas.atlas_height as f32) as usize;
+            let lit = (y0..y1).any(|y| {
+                (x0..x1).any(|x| atlas.atlas_data[(y * atlas.atlas_width as usize + x) * bpp] > 0)
+            });
+            assert!(lit, "empty ligature slice");
+        }
+    }
+
+    #[test]
+    fn font_without_ligatures_loads_none() {
+        let mut atlas = GlyphAtlas::new(13.0, 1.0, "Source Code Pro", 1.2);
+        assert_eq!(atlas.load_ligatures("Source Code Pro"), 0);
+        assert!(!atlas.has_ligatures());
+    }
+
+
+    #[test]
+    fn wide_glyph_takes_two_adjacent_slots() {
+        let mut atlas = create_test_atlas();
+        assert!(atlas.wide_glyph('日').is_none());
+        let free = atlas.slots.free_count();
+        let slices = atlas.rasterize_wide_on_demand('日').unwrap().clone();
+        assert_eq!(atlas.slots.free_count(), free - 2);
+        assert_eq!(slices[1].uv[0] - slices[0].uv[0], atlas.slot_w as f32 / atlas.atlas_width as f32);
+        assert_eq!(slices[0].uv[2], slices[1].uv[2], "each slice is one cell wide");
+        assert!(atlas.is_dirty());
+        // A second request reuses the slots
+        atlas.rasterize_wide_on_demand('日');
+        assert_eq!(atlas.slots.free_count(), free - 2);
+    }
+
+    // ── Paging and eviction ─────────────────────────────────────────
+
+    /// Rasterize `count` characters from Cyrillic on.
+    fn fill(atlas: &mut GlyphAtlas, count: usize) -> Vec<char> {
+        let chars: Vec<char> = ('\u{0400}'..).take(count).collect();
+        for &c in &chars {
+            assert!(atlas.rasterize_on_demand(c).is_some(), "no room for {c}");
+        }
+        chars
+    }
+
+    #[test]
+    fn full_atlas_grows_a_page_and_renormalizes() {
+        let mut atlas = create_test_atlas();
+        let height = atlas.atlas_height;
+        let a = atlas.glyph_info('A').unwrap().uv;
+        let free = atlas.slots.free_count();
+        fill(&mut atlas, free + 1);
+        assert_eq!(atlas.atlas_height, height + PAGE_ROWS * atlas.slot_h);
+        assert_eq!(atlas.atlas_data.len(), (atlas.atlas_width * atlas.atlas_height * atlas.bytes_per_pixel) as usize);
+        // Same pixels, new normalization
+        let moved = atlas.glyph_info('A').unwrap().uv;
+        assert_eq!(moved[0], a[0]);
+        assert!((moved[1] * atlas.atlas_height as f32 - a[1] * height as f32).abs() < 1e-3);
+        let changes = atlas.take_changes();
+        assert!(changes.resized && changes.remapped);
+        assert!(!atlas.is_dirty());
+    }
+
+    #[test]
+    fn capped_atlas_evicts_glyphs_not_drawn_recently() {
+        let mut atlas = create_test_atlas();
+        atlas.set_max_height(atlas.atlas_height);
+        let free = atlas.slots.free_count();
+        let chars = fill(&mut atlas, free);
+        atlas.take_changes();
+        // Everything was drawn this frame, so nothing can go
+        assert!(atlas.rasterize_on_demand('\u{2603}').is_none());
+        assert!(!atlas.take_changes().remapped);
+
+        atlas.begin_frame();
+        for &c in &chars[1..] {
+            atlas.prepare(c, false);
+        }
+        atlas.prepare('\u{2603}', false);
+        assert!(atlas.glyph_info('\u{2603}').is_some());
+        assert!(atlas.glyph_info(chars[0]).is_none(), "least recently drawn glyph evicted");
+        assert!(atlas.glyph_info(chars[1]).is_some());
+        assert!(atlas.take_changes().remapped);
+        // Up-front glyphs are never evicted
+        assert!(atlas.glyph_info('A').is_some());
+    }
 }
@@ -60,6 +60,65 @@ impl GpuContext {
     }
 }
 
+/// Which GPU to render on (`renderer.gpu`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpuPreference {
+    /// Let VeloTerm pick (prefers the discrete GPU).
+    Auto,
+    /// Prefer the integrated GPU to save battery.
+    LowPower,
+    /// Prefer the discrete GPU.
+    HighPerformance,
+    /// The first adapter whose name contains this text (case-insensitive).
+    Named(String),
+}
+
+impl GpuPreference {
+    /// Parse a `renderer.gpu` config value; anything that is not a keyword
+    /// is treated as an adapter name substring.
+    pub fn from_config_str(s: &str) -> Self {
+        match s {
+            "auto" => Self::Auto,
+            "low-power" => Self::LowPower,
+            "high-performance" => Self::HighPerformance,
+            name => Self::Named(name.to_string()),
+        }
+    }
+
+    /// Power preference passed to wgpu's adapter request.
+    pub fn power_preference(&self) -> wgpu::PowerPreference {
+        match self {
+            Self::LowPower => wgpu::PowerPreference::LowPower,
+            Self::Auto | Self::HighPerformance | Self::Named(_) => {
+                wgpu::PowerPreference::HighPerformance
+            }
+        }
+    }
+}
+
+/// Index of the first adapter name containing `needle` (case-insensitive).
+pub fn match_adapter_name<S: AsRef<str>>(names: &[S], needle: &str) -> Option<usize> {
+    let needle = needle.to_lowercase();
+    names
+        .iter()
+        .position(|name| name.as_ref().to_lowercase().contains(&needle))
+}
+
+/// Info for every adapter wgpu can see, for `veloterm cli list-gpus`.
+pub fn list_adapters() -> Vec<wgpu::AdapterInfo> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    instance
+        .enumerate_adapters(wgpu::Backends::all())
+        .iter()
+        .map(|adapter| adapter.get_info())
+        .collect()
+}
+
+/// One-line adapter description: "name (device type, backend)".
+pub fn describe_adapter(info: &wgpu::AdapterInfo) -> String {
+    format!("{} ({:?}, {:?})", info.name, info.device_type, info.backend)
+}
+
 /// Errors that can occur during GPU initialization.
 #[derive(Debug)]
 pub enum GpuError {
@@ -85,6 +144,10 @@ pub struct SurfaceConfig {
     pub height: u32,
     pub format: wgpu::TextureFormat,
     pub present_mode: wgpu::PresentMode,
+    /// Frames the GPU may queue ahead of the display (1 in latency mode).
+    pub max_frame_latency: u32,
+    /// How the compositor reads the frame's alpha (see `choose_alpha_mode`).
+    pub alpha_mode: wgpu::CompositeAlphaMode,
 }
 
 impl SurfaceConfig {
@@ -96,9 +159,24 @@ impl SurfaceConfig {
             height,
             format,
             present_mode: wgpu::PresentMode::Fifo,
+            max_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
         }
     }
 
+    /// Composite with `alpha_mode`.
+    pub fn with_alpha_mode(mut self, alpha_mode: wgpu::CompositeAlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    /// Use `present_mode`, and queue only one frame ahead when `low_latency`.
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode, low_latency: bool) -> Self {
+        self.present_mode = present_mode;
+        self.max_frame_latency = if low_latency { 1 } else { 2 };
+        self
+    }
+
     /// Build a wgpu SurfaceConfiguration from this config.
     pub fn to_wgpu_config(&self) -> wgpu::SurfaceConfiguration {
         wgpu::SurfaceConfiguration {
@@ -107,13 +185,67 @@ impl SurfaceConfig {
             width: self.width,
             height: self.height,
             present_mode: self.present_mode,
-            desired_maximum_frame_latency: 2,
-            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            desired_maximum_frame_latency: self.max_frame_latency,
+            alpha_mode: self.alpha_mode,
             view_formats: vec![],
         }
     }
 }
 
+/// Parse a `renderer.present_mode` config value.
+pub fn parse_present_mode(s: &str) -> Option<wgpu::PresentMode> {
+    match s {
+        "fifo" => Some(wgpu::PresentMode::Fifo),
+        "mailbox" => Some(wgpu::PresentMode::Mailbox),
+        "immediate" => Some(wgpu::PresentMode::Immediate),
+        _ => None,
+    }
+}
+
+/// `requested` if the surface supports it, otherwise Fifo (always available).
+pub fn choose_present_mode(
+    requested: wgpu::PresentMode,
+    supported: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    if supported.contains(&requested) {
+        requested
+    } else {
+        wgpu::PresentMode::Fifo
+    }
+}
+
+/// Composite mode for a window whose background is `transparent`: one that
+/// honours the frame's alpha, premultiplied preferred. Opaque windows (and
+/// surfaces without such a mode) use Auto, which may ignore alpha.
+pub fn choose_alpha_mode(
+    transparent: bool,
+    supported: &[wgpu::CompositeAlphaMode],
+) -> wgpu::CompositeAlphaMode {
+    if !transparent {
+        return wgpu::CompositeAlphaMode::Auto;
+    }
+    [
+        wgpu::CompositeAlphaMode::PreMultiplied,
+        wgpu::CompositeAlphaMode::PostMultiplied,
+        wgpu::CompositeAlphaMode::Inherit,
+    ]
+    .into_iter()
+    .find(|mode| supported.contains(mode))
+    .unwrap_or(wgpu::CompositeAlphaMode::Auto)
+}
+
+/// Whether frames composited with `alpha_mode` carry premultiplied color.
+fn is_premultiplied(alpha_mode: wgpu::CompositeAlphaMode) -> bool {
+    alpha_mode != wgpu::CompositeAlphaMode::PostMultiplied
+}
+
+/// The `background` grid uniform: [opacity, 1.0 if premultiplied, 1.0 if
+/// `layered` over a background picture, 0].
+pub fn background_uniform(opacity: f32, alpha_mode: wgpu::CompositeAlphaMode, layered: bool) -> [f32; 4] {
+    let premultiplied = if is_premultiplied(alpha_mode) { 1.0 } else { 0.0 };
+    [opacity, premultiplied, if layered { 1.0 } else { 0.0 }, 0.0]
+}
+
 /// Convert a single sRGB component to linear.
 fn srgb_to_linear(c: f64) -> f64 {
     if c <= 0.04045 {
@@ -123,15 +255,22 @@ fn srgb_to_linear(c: f64) -> f64 {
     }
 }
 
-/// The clear color for the given theme's background (bg_deep).
-/// Returns linear color values for correct rendering on sRGB surfaces.
-pub fn clear_color(theme: &TerminalTheme) -> wgpu::Color {
+/// The clear color for the given theme's background (bg_deep) at `opacity`.
+/// Returns linear color values for correct rendering on sRGB surfaces,
+/// premultiplied unless `alpha_mode` wants straight alpha.
+pub fn clear_color(
+    theme: &TerminalTheme,
+    opacity: f64,
+    alpha_mode: wgpu::CompositeAlphaMode,
+) -> wgpu::Color {
     let c = theme.bg_deep;
+    let a = c.a as f64 * opacity;
+    let k = if is_premultiplied(alpha_mode) { a } else { 1.0 };
     wgpu::Color {
-        r: srgb_to_linear(c.r as f64),
-        g: srgb_to_linear(c.g as f64),
-        b: srgb_to_linear(c.b as f64),
-        a: c.a as f64,
+        r: srgb_to_linear(c.r as f64) * k,
+        g: srgb_to_linear(c.g as f64) * k,
+        b: srgb_to_linear(c.b as f64) * k,
+        a,
     }
 }
 
@@ -200,6 +339,11 @@ pub struct GridUniforms {
     /// [0]: 1.0 if atlas is RGBA (per-channel subpixel blending), 0.0 for R8 (grayscale alpha).
     /// [1]: cursor height ratio — (font ascent+descent) / cell_height, for font-proportional cursor sizing.
     pub flags: [f32; 2],
+    /// Per-pane color filter rows (linear RGB 3x3, each row padded to vec4).
+    pub color_filter: [[f32; 4]; 3],
+    /// [0]: background opacity (`window.opacity`).
+    /// [1]: 1.0 if the surface composites premultiplied alpha. [2], [3]: unused.
+    pub background: [f32; 4],
 }
 
 /// Create the bind group layout for the grid shader.
@@ -244,10 +388,14 @@ pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout
 }
 
 /// Create the render pipeline for the grid shader.
+///
+/// `blend` is `BlendState::REPLACE` for cells drawn straight onto the clear
+/// color and `PREMULTIPLIED_ALPHA_BLENDING` for cells over a background picture.
 pub fn create_render_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
     bind_group_layout: &wgpu::BindGroupLayout,
+    blend: wgpu::BlendState,
 ) -> wgpu::RenderPipeline {
     let shader_source = include_str!("../../shaders/grid.wgsl");
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -275,7 +423,9 @@ pub fn create_render_pipeline(
             entry_point: Some("fs_main"),
             targets: &[Some(wgpu::ColorTargetState {
                 format,
-                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                // Cells are opaque quads; a translucent background's alpha
+                // must reach the surface as is, not blend with the clear color
+                blend: Some(blend),
                 write_mask: wgpu::ColorWrites::ALL,
             })],
             compilation_options: wgpu::PipelineCompilationOptions::default(),
@@ -481,17 +631,54 @@ mod tests {
         assert_eq!(wgpu_cfg.height, 720);
         assert_eq!(wgpu_cfg.format, wgpu::TextureFormat::Bgra8UnormSrgb);
         assert_eq!(wgpu_cfg.present_mode, wgpu::PresentMode::Fifo);
+        assert_eq!(wgpu_cfg.alpha_mode, wgpu::CompositeAlphaMode::Auto);
+        let cfg = cfg.with_alpha_mode(wgpu::CompositeAlphaMode::PreMultiplied);
+        assert_eq!(cfg.to_wgpu_config().alpha_mode, wgpu::CompositeAlphaMode::PreMultiplied);
         assert!(wgpu_cfg
             .usage
             .contains(wgpu::TextureUsages::RENDER_ATTACHMENT));
     }
 
+    #[test]
+    fn surface_config_low_latency_queues_one_frame() {
+        let cfg = SurfaceConfig::new(1280, 720, wgpu::TextureFormat::Bgra8UnormSrgb)
+            .with_present_mode(wgpu::PresentMode::Mailbox, true);
+        let wgpu_cfg = cfg.to_wgpu_config();
+        assert_eq!(wgpu_cfg.present_mode, wgpu::PresentMode::Mailbox);
+        assert_eq!(wgpu_cfg.desired_maximum_frame_latency, 1);
+        let cfg = cfg.with_present_mode(wgpu::PresentMode::Fifo, false);
+        assert_eq!(cfg.to_wgpu_config().desired_maximum_frame_latency, 2);
+    }
+
+    // ── Present mode tests ─────────────────────────────────────────
+
+    #[test]
+    fn parse_present_mode_accepts_known_names() {
+        assert_eq!(parse_present_mode("fifo"), Some(wgpu::PresentMode::Fifo));
+        assert_eq!(parse_present_mode("mailbox"), Some(wgpu::PresentMode::Mailbox));
+        assert_eq!(parse_present_mode("immediate"), Some(wgpu::PresentMode::Immediate));
+        assert_eq!(parse_present_mode("vsync"), None);
+    }
+
+    #[test]
+    fn choose_present_mode_falls_back_to_fifo() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Immediate];
+        assert_eq!(
+            choose_present_mode(wgpu::PresentMode::Immediate, &supported),
+            wgpu::PresentMode::Immediate
+        );
+        assert_eq!(
+            choose_present_mode(wgpu::PresentMode::Mailbox, &supported),
+            wgpu::PresentMode::Fifo
+        );
+    }
+
     // ── Clear color tests ──────────────────────────────────────────
 
     #[test]
     fn clear_color_is_linear_version_of_warm_dark_background() {
         use crate::config::theme::DARK;
-        let c = clear_color(&DARK);
+        let c = clear_color(&DARK, 1.0, wgpu::CompositeAlphaMode::Auto);
         // #141413 = sRGB(20/255, 20/255, 19/255) → linear values are much smaller
         // srgb_to_linear(20/255) ≈ 0.00607
         assert!(c.r < 0.01, "red should be linearized: {}", c.r);
@@ -501,6 +688,31 @@ mod tests {
         assert_eq!(c.a, 1.0);
     }
 
+    #[test]
+    fn translucent_clear_color_is_premultiplied_unless_post_multiplied() {
+        use crate::config::theme::DARK;
+        let opaque = clear_color(&DARK, 1.0, wgpu::CompositeAlphaMode::PreMultiplied);
+        let c = clear_color(&DARK, 0.5, wgpu::CompositeAlphaMode::PreMultiplied);
+        assert_eq!(c.a, 0.5);
+        assert!((c.r - opaque.r * 0.5).abs() < 1e-9);
+        let straight = clear_color(&DARK, 0.5, wgpu::CompositeAlphaMode::PostMultiplied);
+        assert_eq!((straight.r, straight.a), (opaque.r, 0.5));
+    }
+
+    // ── Alpha mode tests ───────────────────────────────────────────
+
+    #[test]
+    fn choose_alpha_mode_prefers_premultiplied_when_transparent() {
+        use wgpu::CompositeAlphaMode::*;
+        assert_eq!(choose_alpha_mode(false, &[Opaque, PreMultiplied]), Auto);
+        assert_eq!(choose_alpha_mode(true, &[Opaque, PostMultiplied, PreMultiplied]), PreMultiplied);
+        assert_eq!(choose_alpha_mode(true, &[Opaque, Inherit, PostMultiplied]), PostMultiplied);
+        assert_eq!(choose_alpha_mode(true, &[Opaque]), Auto);
+        assert_eq!(background_uniform(0.8, PostMultiplied, false), [0.8, 0.0, 0.0, 0.0]);
+        assert_eq!(background_uniform(0.8, Inherit, false)[1], 1.0);
+        assert_eq!(background_uniform(1.0, Auto, true)[2], 1.0);
+    }
+
     // ── CellInstance layout tests ──────────────────────────────────
 
     #[test]
@@ -544,11 +756,11 @@ mod tests {
     }
 
     #[test]
-    fn grid_uniforms_size_is_32_bytes() {
+    fn grid_uniforms_size_is_96_bytes() {
         assert_eq!(
             std::mem::size_of::<GridUniforms>(),
-            32,
-            "GridUniforms must be 32 bytes (cell_size 8 + grid_size 8 + atlas_size 8 + flags 8)"
+            96,
+            "GridUniforms must be 96 bytes (cell_size 8 + grid_size 8 + atlas_size 8 + flags 8 + color_filter 48 + background 16)"
         );
     }
 
@@ -563,6 +775,7 @@ mod tests {
             &ctx.device,
             wgpu::TextureFormat::Bgra8UnormSrgb,
             &bind_group_layout,
+            wgpu::BlendState::REPLACE,
         );
         // If we get here without panicking, the shader compiled and pipeline created
         // The pipeline object existing is the assertion
@@ -632,4 +845,40 @@ mod tests {
             create_grid_bind_group(&ctx.device, &layout, &uniform_buffer, &view, &sampler);
     }
 
+
+    // ── GPU preference tests ───────────────────────────────────────
+
+    #[test]
+    fn gpu_preference_parses_keywords() {
+        assert_eq!(GpuPreference::from_config_str("auto"), GpuPreference::Auto);
+        assert_eq!(GpuPreference::from_config_str("low-power"), GpuPreference::LowPower);
+        assert_eq!(
+            GpuPreference::from_config_str("high-performance"),
+            GpuPreference::HighPerformance
+        );
+        assert_eq!(
+            GpuPreference::from_config_str("Radeon"),
+            GpuPreference::Named("Radeon".to_string())
+        );
+    }
+
+    #[test]
+    fn gpu_preference_power_preference() {
+        assert_eq!(
+            GpuPreference::LowPower.power_preference(),
+            wgpu::PowerPreference::LowPower
+        );
+        assert_eq!(
+            GpuPreference::Auto.power_preference(),
+            wgpu::PowerPreference::HighPerformance
+        );
+    }
+
+    #[test]
+    fn match_adapter_name_is_case_insensitive() {
+        let names = ["Intel(R) Iris(R) Xe Graphics", "NVIDIA GeForce RTX 3060"];
+        assert_eq!(match_adapter_name(&names, "nvidia"), Some(1));
+        assert_eq!(match_adapter_name(&names, "IRIS"), Some(0));
+        assert_eq!(match_adapter_name(&names, "radeon"), None);
+    }
 }
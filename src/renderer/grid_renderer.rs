@@ -10,6 +10,16 @@ pub const CELL_FLAG_STRIKETHROUGH: u32 = 0x20; // bit 5
 pub const CELL_FLAG_SELECTED: u32 = 0x40; // bit 6
 pub const CELL_FLAG_VI_CURSOR: u32 = 0x80; // bit 7
 pub const CELL_FLAG_BOLD: u32 = 0x100; // bit 8
+/// Set on the last cell of a soft-wrapped row (the logical line continues below).
+pub const CELL_FLAG_WRAPLINE: u32 = 0x200; // bit 9
+/// Set on a double-width character (CJK, emoji); the next cell is its spacer.
+pub const CELL_FLAG_WIDE: u32 = 0x400; // bit 10
+/// Set on the right half of a double-width character, and on the blank cell
+/// left at a row end when a wide character wraps to the next row. Holds no text.
+pub const CELL_FLAG_WIDE_SPACER: u32 = 0x800; // bit 11
+/// Set when the cell shows the theme's default background, so a background
+/// picture beneath the grid can show through it.
+pub const CELL_FLAG_DEFAULT_BG: u32 = 0x1000; // bit 12
 
 /// A single cell in the terminal grid.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -35,6 +45,12 @@ impl GridCell {
         }
     }
 
+    /// Whether the cell shows text: a non-space character or the right half
+    /// of a double-width one.
+    pub fn is_occupied(&self) -> bool {
+        self.ch != ' ' || self.flags & CELL_FLAG_WIDE_SPACER != 0
+    }
+
     /// Create an empty cell with the given background color.
     pub fn empty(bg: Color) -> Self {
         Self {
@@ -130,6 +146,11 @@ pub fn row_byte_offset(row: usize, cols: usize) -> u64 {
     (row * cols * std::mem::size_of::<CellInstance>()) as u64
 }
 
+/// Byte offset into the instance buffer for a cell.
+pub fn cell_byte_offset(row: usize, col: usize, cols: usize) -> u64 {
+    ((row * cols + col) * std::mem::size_of::<CellInstance>()) as u64
+}
+
 /// Generate CellInstance data for a single row of the grid.
 ///
 /// `cells` should have `grid.columns * grid.rows` entries, in row-major order.
@@ -139,24 +160,143 @@ pub fn generate_row_instances(
     cells: &[GridCell],
     atlas: &GlyphAtlas,
     row: u32,
+) -> Vec<CellInstance> {
+    generate_span_instances(grid, cells, atlas, row, 0..grid.columns as usize)
+}
+
+/// Whether any cell in `row` carries the selection flag.
+pub fn row_has_selection(cells: &[GridCell], cols: usize, row: usize) -> bool {
+    cells
+        .iter()
+        .skip(row * cols)
+        .take(cols)
+        .any(|cell| cell.flags & CELL_FLAG_SELECTED != 0)
+}
+
+/// Extend a damaged `span` of `row` to cover the spacer of a double-width
+/// character ending it: the spacer draws the glyph's right half, so it must be
+/// regenerated whenever the character changes, even if the spacer did not.
+pub fn widen_span_for_wide_chars(
+    cells: &[GridCell],
+    cols: usize,
+    row: usize,
+    span: std::ops::Range<usize>,
+) -> std::ops::Range<usize> {
+    let ends_wide = span.end > 0
+        && cells
+            .get(row * cols + span.end - 1)
+            .is_some_and(|cell| cell.flags & CELL_FLAG_WIDE != 0);
+    if ends_wide && span.end < cols {
+        span.start..span.end + 1
+    } else {
+        span
+    }
+}
+
+/// Atlas UV and foreground color for the glyph drawn in cell `col` of `row`,
+/// or None for an empty cell. Ligature slices win; a double-width character
+/// draws its left half in its own cell and its right half in the spacer.
+fn cell_glyph(
+    row: &[GridCell],
+    col: usize,
+    atlas: &GlyphAtlas,
+    ligatures: &[Option<[f32; 4]>],
+) -> Option<([f32; 4], Color)> {
+    let cell = row.get(col)?;
+    if let Some(uv) = ligatures.get(col).copied().flatten() {
+        return Some((uv, cell.fg));
+    }
+    if cell.flags & CELL_FLAG_WIDE_SPACER != 0 {
+        let wide = row.get(col.checked_sub(1)?)?;
+        if wide.flags & CELL_FLAG_WIDE == 0 {
+            return None;
+        }
+        return atlas.wide_glyph(wide.ch).map(|slices| (slices[1].uv, wide.fg));
+    }
+    if cell.ch == ' ' {
+        return None;
+    }
+    if cell.flags & CELL_FLAG_WIDE != 0 {
+        if let Some(slices) = atlas.wide_glyph(cell.ch) {
+            return Some((slices[0].uv, cell.fg));
+        }
+    }
+    atlas.glyph_info(cell.ch).map(|info| (info.uv, cell.fg))
+}
+
+/// Atlas UVs of ligature slices for each cell of `row` (empty when the atlas
+/// has no ligatures). Sequences match greedily left to right, longest first,
+/// and only across cells with the same colors and attributes.
+pub fn row_ligatures(row: &[GridCell], atlas: &GlyphAtlas) -> Vec<Option<[f32; 4]>> {
+    if !atlas.has_ligatures() {
+        return Vec::new();
+    }
+    let mut uvs = vec![None; row.len()];
+    let mut seq = String::new();
+    let mut col = 0;
+    while col < row.len() {
+        let first = row[col];
+        let max_len = atlas.max_ligature_len().min(row.len() - col);
+        let run = row[col..col + max_len]
+            .iter()
+            .take_while(|cell| {
+                cell.ch.is_ascii_graphic()
+                    && cell.fg == first.fg
+                    && cell.bg == first.bg
+                    && cell.flags == first.flags
+            })
+            .count();
+        let mut matched = 1;
+        for len in (2..=run).rev() {
+            seq.clear();
+            seq.extend(row[col..col + len].iter().map(|cell| cell.ch));
+            if let Some(slices) = atlas.ligature(&seq) {
+                for (uv, slice) in uvs[col..col + len].iter_mut().zip(slices) {
+                    *uv = Some(slice.uv);
+                }
+                matched = len;
+                break;
+            }
+        }
+        col += matched;
+    }
+    uvs
+}
+
+/// Generate CellInstance data for columns `span` of a single row.
+///
+/// Selection trimming still looks at the whole row, so the result matches the
+/// same slice of `generate_row_instances`. Callers should widen the span to
+/// the full row when `row_has_selection`, since a change anywhere in the row
+/// can move its last occupied column, and when the atlas has ligatures, since
+/// a change can join or split a ligature outside the span.
+pub fn generate_span_instances(
+    grid: &GridDimensions,
+    cells: &[GridCell],
+    atlas: &GlyphAtlas,
+    row: u32,
+    span: std::ops::Range<usize>,
 ) -> Vec<CellInstance> {
     let cols = grid.columns as usize;
     let start = row as usize * cols;
-    let mut instances = Vec::with_capacity(cols);
+    let span = span.start.min(cols)..span.end.min(cols);
+    let mut instances = Vec::with_capacity(span.len());
 
     // Bug A fix: find last occupied column in this row (rightmost non-space)
     let last_occupied: Option<usize> = {
         let mut last = None;
         for c in (0..cols).rev() {
-            if cells.get(start + c).map(|cell| cell.ch != ' ').unwrap_or(false) {
+            if cells.get(start + c).map(|cell| cell.is_occupied()).unwrap_or(false) {
                 last = Some(c);
                 break;
             }
         }
         last
     };
+    let row_cells = &cells[start.min(cells.len())..(start + cols).min(cells.len())];
+    let ligatures = row_ligatures(row_cells, atlas);
 
-    for col in 0..cols {
+    for col in span {
         let i = start + col;
         let cell = cells
             .get(i)
@@ -164,14 +304,9 @@ pub fn generate_row_instances(
             .unwrap_or(GridCell::empty(color_new(0.0, 0.0, 0.0, 1.0)));
 
         let _is_bold = cell.flags & CELL_FLAG_BOLD != 0;
-        let (atlas_uv, has_glyph) = if cell.ch != ' ' {
-            if let Some(info) = atlas.glyph_info(cell.ch) {
-                (info.uv, true)
-            } else {
-                ([0.0, 0.0, 0.0, 0.0], false)
-            }
-        } else {
-            ([0.0, 0.0, 0.0, 0.0], false)
+        let (atlas_uv, has_glyph, fg) = match cell_glyph(row_cells, col, atlas, &ligatures) {
+            Some((uv, fg)) => (uv, true, fg),
+            None => ([0.0, 0.0, 0.0, 0.0], false, cell.fg),
         };
 
         // Bug A fix: strip selection on cells past last occupied column
@@ -187,7 +322,7 @@ pub fn generate_row_instances(
         instances.push(CellInstance {
             position: [col as f32, row as f32],
             atlas_uv,
-            fg_color: [cell.fg.r, cell.fg.g, cell.fg.b, cell.fg.a],
+            fg_color: [fg.r, fg.g, fg.b, fg.a],
             bg_color: [cell.bg.r, cell.bg.g, cell.bg.b, cell.bg.a],
             flags: (if has_glyph { 1 } else { 0 }) | cell_flags,
             _padding: [0; 3],
@@ -216,16 +351,23 @@ pub fn generate_instances(
     for (r, occupied) in last_occupied.iter_mut().enumerate().take(rows) {
         let row_start = r * cols;
         for c in (0..cols).rev() {
-            if cells.get(row_start + c).is_some_and(|cell| cell.ch != ' ') {
+            if cells.get(row_start + c).is_some_and(|cell| cell.is_occupied()) {
                 *occupied = Some(c);
                 break;
             }
         }
     }
 
+    let mut ligatures = Vec::new();
+    let (mut row_start, mut row_end) = (0, 0);
     for i in 0..total {
         let col = (i as u32) % grid.columns;
         let row = (i as u32) / grid.columns;
+        if col == 0 {
+            row_start = (row as usize * cols).min(cells.len());
+            row_end = (row_start + cols).min(cells.len());
+            ligatures = row_ligatures(&cells[row_start..row_end], atlas);
+        }
 
         let cell = cells
             .get(i)
@@ -233,14 +375,10 @@ pub fn generate_instances(
             .unwrap_or(GridCell::empty(color_new(0.0, 0.0, 0.0, 1.0)));
 
         let _is_bold = cell.flags & CELL_FLAG_BOLD != 0;
-        let (atlas_uv, has_glyph) = if cell.ch != ' ' {
-            if let Some(info) = atlas.glyph_info(cell.ch) {
-                (info.uv, true)
-            } else {
-                ([0.0, 0.0, 0.0, 0.0], false)
-            }
-        } else {
-            ([0.0, 0.0, 0.0, 0.0], false)
+        let row_cells = &cells[row_start..row_end];
+        let (atlas_uv, has_glyph, fg) = match cell_glyph(row_cells, col as usize, atlas, &ligatures) {
+            Some((uv, fg)) => (uv, true, fg),
+            None => ([0.0, 0.0, 0.0, 0.0], false, cell.fg),
         };
 
         // Bug A fix: strip selection on cells past last occupied column
@@ -257,7 +395,7 @@ pub fn generate_instances(
         instances.push(CellInstance {
             position: [col as f32, row as f32],
             atlas_uv,
-            fg_color: [cell.fg.r, cell.fg.g, cell.fg.b, cell.fg.a],
+            fg_color: [fg.r, fg.g, fg.b, fg.a],
             bg_color: [cell.bg.r, cell.bg.g, cell.bg.b, cell.bg.a],
             flags: (if has_glyph { 1 } else { 0 }) | cell_flags,
             _padding: [0; 3],
@@ -909,4 +1047,152 @@ mod tests {
                                      // They should be independent
         assert_ne!(inst_a.len(), inst_b.len());
     }
+
+
+    #[test]
+    fn cell_byte_offset_counts_columns() {
+        assert_eq!(cell_byte_offset(0, 0, 80), 0);
+        // row 1, col 3, 4 columns: (4 + 3) * 72 = 504
+        assert_eq!(cell_byte_offset(1, 3, 4), 504);
+        assert_eq!(cell_byte_offset(2, 0, 4), row_byte_offset(2, 4));
+    }
+
+    #[test]
+    fn generate_span_instances_matches_row_slice() {
+        let atlas = test_atlas();
+        let grid = test_grid(4, 2);
+        let mut cells: Vec<GridCell> = (0..grid.total_cells())
+            .map(|_| GridCell::new('A', test_fg(), test_bg()))
+            .collect();
+        cells[5].flags |= CELL_FLAG_SELECTED;
+        let row = generate_row_instances(&grid, &cells, &atlas, 1);
+        let span = generate_span_instances(&grid, &cells, &atlas, 1, 1..3);
+        assert_eq!(span.as_slice(), &row[1..3]);
+        // Spans past the row end are clamped
+        assert_eq!(generate_span_instances(&grid, &cells, &atlas, 1, 2..9).len(), 2);
+    }
+
+    #[test]
+    fn row_has_selection_checks_only_that_row() {
+        let mut cells = vec![GridCell::empty(test_bg()); 8];
+        cells[5].flags |= CELL_FLAG_SELECTED;
+        assert!(!row_has_selection(&cells, 4, 0));
+        assert!(row_has_selection(&cells, 4, 1));
+    }
+
+
+    // ── Ligatures ───────────────────────────────────────────────────
+
+    fn ligature_atlas() -> GlyphAtlas {
+        let mut atlas = test_atlas();
+        atlas.load_ligatures("JetBrains Mono");
+        atlas
+    }
+
+    fn text_row(text: &str) -> Vec<GridCell> {
+        text.chars().map(|c| GridCell::new(c, test_fg(), test_bg())).collect()
+    }
+
+    #[test]
+    fn row_ligatures_empty_without_ligatures() {
+        assert!(row_ligatures(&text_row("a => b"), &test_atlas()).is_empty());
+    }
+
+    #[test]
+    fn row_ligatures_maps_each_cell_to_its_slice() {
+        let atlas = ligature_atlas();
+        let uvs = row_ligatures(&text_row("a => b"), &atlas);
+        let slices = atlas.ligature("=>").unwrap();
+        assert_eq!(uvs[2], Some(slices[0].uv));
+        assert_eq!(uvs[3], Some(slices[1].uv));
+        assert_eq!((uvs[0], uvs[1], uvs[4], uvs[5]), (None, None, None, None));
+    }
+
+    #[test]
+    fn row_ligatures_prefers_longest_match() {
+        let atlas = ligature_atlas();
+        let uvs = row_ligatures(&text_row("!=="), &atlas);
+        let slices = atlas.ligature("!==").unwrap();
+        assert_eq!(uvs[2], Some(slices[2].uv));
+    }
+
+    #[test]
+    fn row_ligatures_break_on_style_change() {
+        let atlas = ligature_atlas();
+        let mut row = text_row("->");
+        row[1].fg = test_bg();
+        assert_eq!(row_ligatures(&row, &atlas), vec![None, None]);
+    }
+
+    #[test]
+    fn generate_instances_uses_ligature_slices() {
+        let atlas = ligature_atlas();
+        let grid = GridDimensions::new(4 * 16, 42, 16.0, 42.0);
+        let cells = text_row("x!=y");
+        let instances = generate_instances(&grid, &cells, &atlas);
+        let slices = atlas.ligature("!=").unwrap();
+        assert_eq!(instances[1].atlas_uv, slices[0].uv);
+        assert_eq!(instances[2].atlas_uv, slices[1].uv);
+        assert_eq!(instances[0].atlas_uv, atlas.glyph_info('x').unwrap().uv);
+        let span = generate_span_instances(&grid, &cells, &atlas, 0, 2..3);
+        assert_eq!(span[0].atlas_uv, slices[1].uv);
+    }
+
+
+    // ── Wide characters ─────────────────────────────────────────────
+
+    fn wide_pair(ch: char) -> [GridCell; 2] {
+        let mut wide = GridCell::new(ch, test_fg(), test_bg());
+        wide.flags = CELL_FLAG_WIDE;
+        let mut spacer = GridCell::new(' ', test_fg(), test_bg());
+        spacer.flags = CELL_FLAG_WIDE_SPACER;
+        [wide, spacer]
+    }
+
+    #[test]
+    fn wide_char_spans_both_cells() {
+        let mut atlas = test_atlas();
+        let slices = atlas.rasterize_wide_on_demand('日').unwrap().clone();
+        let grid = test_grid(3, 1);
+        let [wide, spacer] = wide_pair('日');
+        let cells = vec![wide, spacer, GridCell::new('a', test_fg(), test_bg())];
+        let instances = generate_instances(&grid, &cells, &atlas);
+        assert_eq!(instances[0].atlas_uv, slices[0].uv);
+        assert_eq!(instances[1].atlas_uv, slices[1].uv);
+        assert_eq!(instances[1].flags & 1, 1, "spacer should draw the right half");
+        let row = generate_row_instances(&grid, &cells, &atlas, 0);
+        assert_eq!(row, instances);
+    }
+
+    #[test]
+    fn spacer_without_wide_glyph_draws_nothing() {
+        let atlas = test_atlas();
+        let grid = test_grid(2, 1);
+        let instances = generate_instances(&grid, &wide_pair('日'), &atlas);
+        assert_eq!(instances[1].flags & 1, 0);
+    }
+
+    #[test]
+    fn selected_spacer_at_row_end_keeps_highlight() {
+        let atlas = test_atlas();
+        let grid = test_grid(4, 1);
+        let mut cells = vec![GridCell::new('a', test_fg(), test_bg())];
+        cells.extend(wide_pair('日'));
+        cells.push(GridCell::new(' ', test_fg(), test_bg()));
+        for cell in &mut cells[..3] {
+            cell.flags |= CELL_FLAG_SELECTED;
+        }
+        let instances = generate_instances(&grid, &cells, &atlas);
+        assert_ne!(instances[2].flags & CELL_FLAG_SELECTED, 0);
+    }
+
+    #[test]
+    fn damage_span_widens_to_spacer() {
+        let mut cells = vec![GridCell::new('a', test_fg(), test_bg())];
+        cells.extend(wide_pair('日'));
+        cells.push(GridCell::new('b', test_fg(), test_bg()));
+        assert_eq!(widen_span_for_wide_chars(&cells, 4, 0, 0..2), 0..3);
+        assert_eq!(widen_span_for_wide_chars(&cells, 4, 0, 0..1), 0..1);
+        assert_eq!(widen_span_for_wide_chars(&cells, 4, 0, 2..4), 2..4);
+    }
 }
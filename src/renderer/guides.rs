@@ -0,0 +1,191 @@
+// Alignment guides: vertical column rulers, a cursor row/column crosshair and
+// soft-wrap markers, laid out as translucent rects for the iced overlay.
+
+/// Width of a column ruler line in physical pixels.
+pub const RULER_WIDTH: f32 = 1.0;
+
+/// Diameter of the soft-wrap gutter dot in physical pixels.
+pub const WRAP_MARKER_SIZE: f32 = 4.0;
+
+/// Gap between the last text column and the wrap marker.
+const WRAP_MARKER_GAP: f32 = 2.0;
+
+/// What a guide rect represents (selects its color in the overlay).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuideKind {
+    Ruler,
+    CrosshairRow,
+    CrosshairColumn,
+    /// Gutter dot after a soft-wrapped row.
+    WrapMarker,
+    /// Touch drag handle at one end of the selection.
+    SelectionHandle,
+}
+
+/// A guide rect in physical pixels (same coordinate space as `PaneInfo`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuideRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub kind: GuideKind,
+}
+
+/// Per-pane guide toggles. `None` follows the `[guides]` config defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaneGuides {
+    pub rulers: Option<bool>,
+    pub crosshair: Option<bool>,
+    pub soft_wrap: Option<bool>,
+}
+
+/// Terminal text area of a pane, used to map columns/rows to pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct GuideGrid {
+    /// Top-left of the text area.
+    pub x: f32,
+    pub y: f32,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+/// Build guide rects for a pane.
+///
+/// A ruler at column `n` sits on the right edge of the `n`th column, so text
+/// that stays left of it fits in `n` columns. Rulers outside the grid are
+/// skipped. `cursor` is the visible (row, col) to cross-hair, if any.
+pub fn guide_rects(
+    grid: &GuideGrid,
+    rulers: &[usize],
+    cursor: Option<(usize, usize)>,
+) -> Vec<GuideRect> {
+    let text_width = grid.cols as f32 * grid.cell_width;
+    let text_height = grid.rows as f32 * grid.cell_height;
+    let mut rects = Vec::new();
+
+    if let Some((row, col)) = cursor {
+        if row < grid.rows {
+            rects.push(GuideRect {
+                x: grid.x,
+                y: grid.y + row as f32 * grid.cell_height,
+                width: text_width,
+                height: grid.cell_height,
+                kind: GuideKind::CrosshairRow,
+            });
+        }
+        if col < grid.cols {
+            rects.push(GuideRect {
+                x: grid.x + col as f32 * grid.cell_width,
+                y: grid.y,
+                width: grid.cell_width,
+                height: text_height,
+                kind: GuideKind::CrosshairColumn,
+            });
+        }
+    }
+
+    for &column in rulers {
+        if column == 0 || column >= grid.cols {
+            continue;
+        }
+        rects.push(GuideRect {
+            x: grid.x + column as f32 * grid.cell_width - RULER_WIDTH / 2.0,
+            y: grid.y,
+            width: RULER_WIDTH,
+            height: text_height,
+            kind: GuideKind::Ruler,
+        });
+    }
+
+    rects
+}
+
+/// Build gutter dots just right of the text area for each soft-wrapped row.
+pub fn wrap_marker_rects(grid: &GuideGrid, wrapped_rows: &[usize]) -> Vec<GuideRect> {
+    let x = grid.x + grid.cols as f32 * grid.cell_width + WRAP_MARKER_GAP;
+    wrapped_rows
+        .iter()
+        .filter(|&&row| row < grid.rows)
+        .map(|&row| GuideRect {
+            x,
+            y: grid.y + (row as f32 + 0.5) * grid.cell_height - WRAP_MARKER_SIZE / 2.0,
+            width: WRAP_MARKER_SIZE,
+            height: WRAP_MARKER_SIZE,
+            kind: GuideKind::WrapMarker,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> GuideGrid {
+        GuideGrid {
+            x: 10.0,
+            y: 20.0,
+            cell_width: 8.0,
+            cell_height: 16.0,
+            cols: 100,
+            rows: 30,
+        }
+    }
+
+    #[test]
+    fn no_guides_when_nothing_enabled() {
+        assert!(guide_rects(&grid(), &[], None).is_empty());
+    }
+
+    #[test]
+    fn ruler_placed_after_column() {
+        let rects = guide_rects(&grid(), &[80], None);
+        assert_eq!(rects.len(), 1);
+        let r = rects[0];
+        assert_eq!(r.kind, GuideKind::Ruler);
+        assert_eq!(r.x + RULER_WIDTH / 2.0, 10.0 + 80.0 * 8.0);
+        assert_eq!(r.y, 20.0);
+        assert_eq!(r.height, 30.0 * 16.0);
+    }
+
+    #[test]
+    fn rulers_beyond_grid_skipped() {
+        let rects = guide_rects(&grid(), &[80, 100, 120], None);
+        assert_eq!(rects.len(), 1);
+    }
+
+    #[test]
+    fn crosshair_covers_cursor_row_and_column() {
+        let rects = guide_rects(&grid(), &[], Some((3, 5)));
+        let row = rects.iter().find(|r| r.kind == GuideKind::CrosshairRow).unwrap();
+        assert_eq!(row.y, 20.0 + 3.0 * 16.0);
+        assert_eq!(row.width, 100.0 * 8.0);
+        let col = rects.iter().find(|r| r.kind == GuideKind::CrosshairColumn).unwrap();
+        assert_eq!(col.x, 10.0 + 5.0 * 8.0);
+        assert_eq!(col.height, 30.0 * 16.0);
+    }
+
+    #[test]
+    fn crosshair_row_skipped_when_cursor_offscreen() {
+        let rects = guide_rects(&grid(), &[], Some((40, 5)));
+        assert_eq!(rects.len(), 1);
+        assert_eq!(rects[0].kind, GuideKind::CrosshairColumn);
+    }
+
+    #[test]
+    fn wrap_marker_centered_on_row_right_of_text() {
+        let rects = wrap_marker_rects(&grid(), &[2]);
+        assert_eq!(rects.len(), 1);
+        let r = rects[0];
+        assert_eq!(r.kind, GuideKind::WrapMarker);
+        assert_eq!(r.x, 10.0 + 100.0 * 8.0 + WRAP_MARKER_GAP);
+        assert_eq!(r.y + r.height / 2.0, 20.0 + 2.5 * 16.0);
+    }
+
+    #[test]
+    fn wrap_markers_outside_grid_skipped() {
+        assert!(wrap_marker_rects(&grid(), &[30, 99]).is_empty());
+    }
+}
@@ -0,0 +1,314 @@
+// Offscreen rendering: draws pane grids with the grid shader into a texture
+// and reads it back as an image, with no window or surface. Used to turn
+// replayed event traces into screenshots that can be saved and compared.
+
+use super::glyph_atlas::GlyphAtlas;
+use super::gpu::{
+    self, clear_color, create_atlas_sampler, create_atlas_texture, create_bind_group_layout,
+    create_grid_bind_group, create_render_pipeline, GpuContext, GridUniforms,
+};
+use super::grid_renderer::{generate_instances, GridCell, GridDimensions, CELL_FLAG_WIDE};
+use crate::config::theme::TerminalTheme;
+use crate::pane::Rect;
+use wgpu::util::DeviceExt;
+
+/// Texture format frames are drawn in; read back as RGBA as is.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// One pane to draw: its grid cells and where they go in the frame.
+#[derive(Debug, Clone)]
+pub struct HeadlessPane {
+    /// Pixel rect within the frame; cells outside it are clipped.
+    pub rect: Rect,
+    pub cols: usize,
+    pub rows: usize,
+    /// Row-major cells, `cols * rows` of them.
+    pub cells: Vec<GridCell>,
+}
+
+/// Renders pane grids to images on a GPU device without a surface.
+pub struct HeadlessRenderer {
+    ctx: GpuContext,
+    atlas: GlyphAtlas,
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    uniform_align: u64,
+}
+
+impl HeadlessRenderer {
+    /// Open a headless GPU device and rasterize glyphs for `font_family` at
+    /// `font_size` pixels.
+    pub fn new(font_size: f32, font_family: &str, line_height_multiplier: f32) -> anyhow::Result<Self> {
+        let ctx = pollster::block_on(GpuContext::new_headless())?;
+        let atlas = GlyphAtlas::new(font_size, 1.0, font_family, line_height_multiplier);
+        let layout = create_bind_group_layout(&ctx.device);
+        let pipeline = create_render_pipeline(&ctx.device, FORMAT, &layout, wgpu::BlendState::REPLACE);
+        let sampler = create_atlas_sampler(&ctx.device);
+        let uniform_align = (ctx.device.limits().min_uniform_buffer_offset_alignment as u64)
+            .max(std::mem::size_of::<GridUniforms>() as u64);
+        Ok(Self {
+            ctx,
+            atlas,
+            layout,
+            pipeline,
+            sampler,
+            uniform_align,
+        })
+    }
+
+    /// Cell size in pixels, for laying out panes.
+    pub fn cell_size(&self) -> (f32, f32) {
+        (self.atlas.cell_width, self.atlas.cell_height)
+    }
+
+    /// Draw `panes` on a `width` x `height` frame cleared to the theme's
+    /// background and read it back.
+    pub fn render(
+        &mut self,
+        width: u32,
+        height: u32,
+        theme: &TerminalTheme,
+        panes: &[HeadlessPane],
+    ) -> anyhow::Result<image::RgbaImage> {
+        let max_dim = self.ctx.device.limits().max_texture_dimension_2d;
+        let (width, height) = (width.clamp(1, max_dim), height.clamp(1, max_dim));
+        let device = &self.ctx.device;
+        let queue = &self.ctx.queue;
+
+        self.atlas.begin_frame();
+        for pane in panes {
+            for cell in &pane.cells {
+                self.atlas.prepare(cell.ch, cell.flags & CELL_FLAG_WIDE != 0);
+            }
+        }
+        let atlas_texture = create_atlas_texture(
+            device,
+            queue,
+            self.atlas.atlas_width,
+            self.atlas.atlas_height,
+            &self.atlas.atlas_data,
+            self.atlas.bytes_per_pixel,
+        );
+        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Uniforms"),
+            size: self.uniform_align * panes.len().max(1) as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = create_grid_bind_group(device, &self.layout, &uniform_buffer, &atlas_view, &self.sampler);
+
+        let (cw, ch) = self.cell_size();
+        let atlas_rgba = if self.atlas.bytes_per_pixel == 4 { 1.0 } else { 0.0 };
+        let mut draws = Vec::with_capacity(panes.len());
+        for (i, pane) in panes.iter().enumerate() {
+            let grid = GridDimensions {
+                columns: pane.cols.max(1) as u32,
+                rows: pane.rows.max(1) as u32,
+                cell_width: cw,
+                cell_height: ch,
+                window_width: width,
+                window_height: height,
+            };
+            let uniforms = GridUniforms {
+                cell_size: grid.cell_size_ndc(),
+                grid_size: grid.grid_size(),
+                atlas_size: [self.atlas.atlas_width as f32, self.atlas.atlas_height as f32],
+                flags: [atlas_rgba, self.atlas.cursor_height_ratio],
+                color_filter: super::color_filter::ColorFilter::None.uniform_rows(),
+                background: gpu::background_uniform(1.0, wgpu::CompositeAlphaMode::Opaque, false),
+            };
+            queue.write_buffer(&uniform_buffer, i as u64 * self.uniform_align, bytemuck::bytes_of(&uniforms));
+            let instances = generate_instances(&grid, &pane.cells, &self.atlas);
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Headless Instances"),
+                contents: bytemuck::cast_slice(&instances),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            draws.push((buffer, instances.len() as u32, grid));
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Frame"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Headless Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(clear_color(theme, 1.0, wgpu::CompositeAlphaMode::Opaque)),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            for (i, (pane, (buffer, count, grid))) in panes.iter().zip(&draws).enumerate() {
+                // The viewport covers the whole grid and must stay inside
+                // the frame; the scissor clips it to the pane's rect.
+                let x = pane.rect.x.max(0.0).min(width as f32);
+                let y = pane.rect.y.max(0.0).min(height as f32);
+                let vp_w = (grid.columns as f32 * cw).min(width as f32 - x);
+                let vp_h = (grid.rows as f32 * ch).min(height as f32 - y);
+                let sw = pane.rect.width.min(vp_w).round() as u32;
+                let sh = pane.rect.height.min(vp_h).round() as u32;
+                if *count == 0 || sw == 0 || sh == 0 {
+                    continue;
+                }
+                pass.set_bind_group(0, &bind_group, &[(i as u64 * self.uniform_align) as u32]);
+                pass.set_vertex_buffer(0, buffer.slice(..));
+                pass.set_viewport(x, y, vp_w, vp_h, 0.0, 1.0);
+                pass.set_scissor_rect(x as u32, y as u32, sw, sh);
+                pass.draw(0..6, 0..*count);
+            }
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+        self.read_back(&texture, width, height)
+    }
+
+    /// Copy `texture` into a buffer and return its pixels.
+    fn read_back(&self, texture: &wgpu::Texture, width: u32, height: u32) -> anyhow::Result<image::RgbaImage> {
+        let device = &self.ctx.device;
+        // Rows of a texture copy are padded to 256 bytes
+        let unpadded = width * 4;
+        let padded = unpadded.div_ceil(256) * 256;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Readback"),
+            size: (padded * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.ctx.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device.poll(wgpu::PollType::Wait { submission_index: None, timeout: None }).ok();
+        receiver.recv()??;
+
+        let data = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((unpadded * height) as usize);
+        for row in data.chunks_exact(padded as usize) {
+            pixels.extend_from_slice(&row[..unpadded as usize]);
+        }
+        drop(data);
+        buffer.unmap();
+        image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create image buffer"))
+    }
+}
+
+/// Number of pixels that differ between two frames; every pixel of the
+/// larger one when their sizes differ.
+pub fn pixel_diff(a: &image::RgbaImage, b: &image::RgbaImage) -> usize {
+    if a.dimensions() != b.dimensions() {
+        let (aw, ah) = a.dimensions();
+        let (bw, bh) = b.dimensions();
+        return (aw * ah).max(bw * bh) as usize;
+    }
+    a.pixels().zip(b.pixels()).filter(|(p, q)| p != q).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::theme::TerminalTheme;
+
+    fn try_renderer() -> Option<HeadlessRenderer> {
+        HeadlessRenderer::new(13.0, "JetBrains Mono", 1.5).ok()
+    }
+
+    fn pane(text: &str, theme: &TerminalTheme) -> HeadlessPane {
+        let mut cells = vec![GridCell::empty(theme.bg_deep); 10 * 2];
+        for (cell, ch) in cells.iter_mut().zip(text.chars()) {
+            *cell = GridCell::new(ch, theme.text_primary, theme.bg_deep);
+        }
+        HeadlessPane {
+            rect: Rect::new(0.0, 0.0, 200.0, 80.0),
+            cols: 10,
+            rows: 2,
+            cells,
+        }
+    }
+
+    #[test]
+    fn blank_frame_is_the_theme_background() {
+        let Some(mut renderer) = try_renderer() else {
+            return; // no GPU available
+        };
+        let theme = TerminalTheme::warm_dark();
+        let image = renderer.render(32, 16, &theme, &[]).unwrap();
+        assert_eq!(image.dimensions(), (32, 16));
+        let first = *image.get_pixel(0, 0);
+        assert!(image.pixels().all(|p| *p == first));
+    }
+
+    #[test]
+    fn text_changes_the_frame() {
+        let Some(mut renderer) = try_renderer() else {
+            return; // no GPU available
+        };
+        let theme = TerminalTheme::warm_dark();
+        let blank = renderer.render(200, 80, &theme, &[pane("", &theme)]).unwrap();
+        let text = renderer.render(200, 80, &theme, &[pane("hello", &theme)]).unwrap();
+        let again = renderer.render(200, 80, &theme, &[pane("hello", &theme)]).unwrap();
+        assert!(pixel_diff(&blank, &text) > 0);
+        assert_eq!(pixel_diff(&text, &again), 0);
+    }
+
+    #[test]
+    fn frames_of_different_sizes_differ_everywhere() {
+        let a = image::RgbaImage::new(4, 4);
+        let b = image::RgbaImage::new(2, 2);
+        assert_eq!(pixel_diff(&a, &b), 16);
+        assert_eq!(pixel_diff(&a, &a.clone()), 0);
+    }
+}
@@ -5,11 +5,19 @@
 // status bar as an iced overlay on top of the custom wgpu terminal renderer.
 
 use crate::config::theme::TerminalTheme;
+use crate::directory_rules::PaneBadge;
+use crate::input::cheatsheet::CheatsheetSection;
+use crate::notes::NoteMarker;
+use crate::pane::divider::OverlayQuad;
+use crate::pane::Rect;
+use crate::renderer::guides::{GuideKind, GuideRect};
+use crate::renderer::minimap::MinimapView;
+use crate::search::minimap::{MarkerKind, MatchStrip};
 use iced_graphics::Viewport;
 use iced_runtime::user_interface::{Cache, UserInterface};
 use iced_wgpu::Engine;
 use iced_widget::{
-    column, container, pin, row, stack, text, text_input, MouseArea, Row, Stack,
+    column, container, pin, row, stack, text, text_input, tooltip, MouseArea, Row, Stack,
 };
 use std::borrow::Cow;
 
@@ -48,8 +56,6 @@ pub enum UiMessage {
     ToggleThemeSelector,
     SetTheme(String),
     RenameTab(usize),
-    RenameTabInput(String),
-    RenameTabCommit(usize, String),
     NewTabHovered(bool),
     CloseButtonHovered(Option<usize>),
     // Sidebar toggle button hover
@@ -68,6 +74,26 @@ pub enum UiMessage {
     ContextMenuAction(crate::context_menu::ContextMenuAction),
     /// Dismiss the context menu overlay (click outside or Escape).
     ContextMenuDismiss,
+    /// Close the keybinding cheatsheet (click anywhere on it).
+    CheatsheetDismiss,
+    /// Run the command palette result at this index.
+    PaletteItemClicked(usize),
+    /// Close the command palette (click outside it).
+    PaletteDismiss,
+    /// Jump to the bookmark at this index in the bookmark list.
+    BookmarkClicked(usize),
+    /// A search bar button (the option toggles).
+    Search(crate::input::SearchCommand),
+    /// Close the bookmark list (click outside it).
+    BookmarkListDismiss,
+    /// Copy the register at this index in the vi register viewer.
+    RegisterClicked(usize),
+    /// Close the vi register viewer (click outside it).
+    RegisterListDismiss,
+    /// Open the profile at this index in the profile picker.
+    ProfileClicked(usize),
+    /// Close the profile picker (click outside it).
+    ProfilePickerDismiss,
     // Overlay toggle (from toolbar icons)
     ToggleFileBrowser,
     ToggleGitReview,
@@ -124,11 +150,75 @@ pub struct PaneInfo {
     pub index: usize,
     pub title: String,
     pub shell_name: String,
-    /// Scrollbar thumb rect in physical pixels relative to content area origin.
-    /// (x, y, width, height). None if no scrollbar visible.
-    pub scrollbar_thumb: Option<(f32, f32, f32, f32)>,
-    /// Scrollbar opacity (0.0 = hidden, 1.0 = fully visible).
-    pub scrollbar_alpha: f32,
+    /// Scrollbar track and thumb in physical pixels relative to content area
+    /// origin. Empty when no scrollbar is visible.
+    pub scrollbar: Vec<OverlayQuad>,
+    /// Scrollback minimap column, when shown.
+    pub minimap: Option<MinimapView>,
+    /// Search match positions along the right edge (physical pixels).
+    pub match_strip: Option<MatchStrip>,
+    /// History suggestion strip anchored to the prompt line, if any.
+    pub suggestion_strip: Option<SuggestionStrip>,
+    /// Row/column label for a command line wrapping over several rows.
+    pub prompt_indicator: Option<PromptIndicator>,
+    /// Column rulers, cursor crosshair and wrap marker rects (physical pixels).
+    pub guides: Vec<GuideRect>,
+    /// Badge from a matching `[[directory_rules]]` entry.
+    pub badge: Option<PaneBadge>,
+    /// Scrolls together with another pane.
+    pub scroll_locked: bool,
+    /// Name and color of the pane's group.
+    pub group: Option<PaneBadge>,
+    /// Shell exited, hung or failed to start; shown across the pane bottom.
+    pub error_banner: Option<String>,
+    /// Question or inline editor across the pane bottom (OSC 52 request,
+    /// note being written).
+    pub prompt: Option<String>,
+    /// Gutter markers for notes on visible lines.
+    pub notes: Vec<NoteMarker>,
+    /// Gutter markers for bookmarks on visible lines (physical pixels).
+    pub bookmarks: Vec<Rect>,
+    /// Hint mode labels over their targets.
+    pub hints: Vec<HintLabel>,
+}
+
+/// A hint mode label at the first cell of its target (physical pixels
+/// relative to the content area origin).
+#[derive(Debug, Clone)]
+pub struct HintLabel {
+    pub x: f32,
+    pub y: f32,
+    pub height: f32,
+    /// Keys still to type.
+    pub text: String,
+}
+
+/// Command-history suggestions shown next to the prompt.
+/// Positions are in physical pixels relative to the content area origin.
+#[derive(Debug, Clone)]
+pub struct SuggestionStrip {
+    /// Left edge of the terminal text area.
+    pub x: f32,
+    /// Top of the cursor row.
+    pub row_y: f32,
+    /// Height of one terminal row.
+    pub row_height: f32,
+    /// Matches, best first. The first entry is accepted by the accept key.
+    pub items: Vec<String>,
+    /// Accept key hint shown after the items (e.g. "⇥").
+    pub accept_label: String,
+}
+
+/// Cursor position label shown while editing a long command line.
+/// Positions are in physical pixels relative to the content area origin.
+#[derive(Debug, Clone)]
+pub struct PromptIndicator {
+    /// Top of the cursor row.
+    pub row_y: f32,
+    /// Height of one terminal row.
+    pub row_height: f32,
+    /// Text such as "Ln 2/3, Col 14".
+    pub label: String,
 }
 
 /// Sidebar tab descriptor for the minimap sidebar.
@@ -189,15 +279,29 @@ pub struct UiState<'a> {
     pub search_current: usize,
     pub search_total: usize,
     pub search_error: bool,
+    /// Case, whole-word and regex toggles of the focused pane's search.
+    pub search_options: crate::search::SearchOptions,
     /// Dividers between panes.
     pub dividers: Vec<DividerDisplay>,
     /// Whether a visual bell flash is active.
     pub bell_flash: bool,
+    /// Area flashing to confirm a screenshot (physical pixels, window
+    /// coordinates).
+    pub screenshot_flash: Option<Rect>,
     /// Command palette state.
     pub palette_active: bool,
     pub palette_query: String,
     pub palette_items: Vec<(String, String, String)>, // (name, description, keybinding)
     pub palette_selected: usize,
+    /// Bookmark list for the focused pane, as (line label, line text) rows.
+    pub bookmark_list: Option<Vec<(String, String)>>,
+    pub bookmark_selected: usize,
+    /// Vi register viewer, as (register, contents preview) rows.
+    pub register_list: Option<Vec<(String, String)>>,
+    pub register_selected: usize,
+    /// Profile picker entries, as (name, command) rows; the first is the shell.
+    pub profile_picker: Option<Vec<(String, String)>>,
+    pub profile_selected: usize,
     /// Sidebar state.
     pub sidebar_visible: bool,
     pub sidebar_tabs: Vec<SidebarTabInfo>,
@@ -205,6 +309,8 @@ pub struct UiState<'a> {
     pub hovered_sidebar_tab: Option<usize>,
     /// Theme selector popup state.
     pub theme_selector_open: bool,
+    /// Keybinding cheatsheet contents while it is open.
+    pub cheatsheet: Option<Vec<CheatsheetSection>>,
     /// Tab being renamed inline (index into sidebar_tabs).
     pub editing_tab: Option<usize>,
     /// Current text in the rename field.
@@ -283,6 +389,12 @@ pub struct UiState<'a> {
     pub git_review_branch: String,
     /// Current hunk index (for visual highlighting).
     pub git_review_current_hunk: usize,
+    /// Git branch/dirty segment for the focused pane's cwd.
+    pub git_segment: Option<crate::git_segment::GitSegment>,
+    /// macOS Secure Keyboard Entry is on (status bar indicator).
+    pub secure_keyboard_entry: bool,
+    /// Pane recording indicator label (e.g. "REC 0:07") while recording.
+    pub recording: Option<String>,
 }
 
 /// A file browser row for rendering.
@@ -601,6 +713,24 @@ impl IcedLayer {
             main_ui
         };
 
+        let with_flash: IcedElement<'a> = if let Some(area) = state.screenshot_flash {
+            let flash = container(column![])
+                .width(area.width / scale)
+                .height(area.height / scale)
+                .style(|_: &iced_core::Theme| container::Style {
+                    background: Some(iced_core::Background::Color(
+                        iced_core::Color::from_rgba(1.0, 1.0, 1.0, 0.25),
+                    )),
+                    ..Default::default()
+                });
+            stack![with_flash, pin(flash).x(area.x / scale).y(area.y / scale)]
+                .width(iced_core::Length::Fill)
+                .height(iced_core::Length::Fill)
+                .into()
+        } else {
+            with_flash
+        };
+
         // Theme selector popup (positioned below title bar icons)
         let with_theme_popup: IcedElement<'a> = if state.theme_selector_open {
             let popup = Self::theme_selector_popup(state, scale);
@@ -615,15 +745,18 @@ impl IcedLayer {
         // Command palette overlay (modal, centered at top)
         let with_palette: IcedElement<'a> = if state.palette_active {
             let palette_overlay = Self::command_palette(state, scale);
-            let scrim = container(column![])
-                .width(iced_core::Length::Fill)
-                .height(iced_core::Length::Fill)
-                .style(|_: &iced_core::Theme| container::Style {
-                    background: Some(iced_core::Background::Color(
-                        iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
-                    )),
-                    ..Default::default()
-                });
+            let scrim = MouseArea::new(
+                container(column![])
+                    .width(iced_core::Length::Fill)
+                    .height(iced_core::Length::Fill)
+                    .style(|_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(
+                            iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                        )),
+                        ..Default::default()
+                    }),
+            )
+            .on_press(UiMessage::PaletteDismiss);
 
             stack![with_theme_popup, scrim, palette_overlay]
                 .width(iced_core::Length::Fill)
@@ -633,19 +766,91 @@ impl IcedLayer {
             with_theme_popup
         };
 
+        // Bookmark list overlay (modal, same place as the palette)
+        let with_bookmarks: IcedElement<'a> = if let Some(items) = &state.bookmark_list {
+            let list = Self::bookmark_list(items, state.bookmark_selected, state, scale);
+            let scrim = MouseArea::new(
+                container(column![])
+                    .width(iced_core::Length::Fill)
+                    .height(iced_core::Length::Fill)
+                    .style(|_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(
+                            iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                        )),
+                        ..Default::default()
+                    }),
+            )
+            .on_press(UiMessage::BookmarkListDismiss);
+
+            stack![with_palette, scrim, list]
+                .width(iced_core::Length::Fill)
+                .height(iced_core::Length::Fill)
+                .into()
+        } else {
+            with_palette
+        };
+
+        // Vi register viewer overlay (modal, same place as the palette)
+        let with_registers: IcedElement<'a> = if let Some(items) = &state.register_list {
+            let list = Self::register_list(items, state.register_selected, state, scale);
+            let scrim = MouseArea::new(
+                container(column![])
+                    .width(iced_core::Length::Fill)
+                    .height(iced_core::Length::Fill)
+                    .style(|_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(
+                            iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                        )),
+                        ..Default::default()
+                    }),
+            )
+            .on_press(UiMessage::RegisterListDismiss);
+
+            stack![with_bookmarks, scrim, list]
+                .width(iced_core::Length::Fill)
+                .height(iced_core::Length::Fill)
+                .into()
+        } else {
+            with_bookmarks
+        };
+
+        // Profile picker overlay (modal, same place as the palette)
+        let with_profiles: IcedElement<'a> = if let Some(items) = &state.profile_picker {
+            let list = Self::profile_picker(items, state.profile_selected, state, scale);
+            let scrim = MouseArea::new(
+                container(column![])
+                    .width(iced_core::Length::Fill)
+                    .height(iced_core::Length::Fill)
+                    .style(|_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(
+                            iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                        )),
+                        ..Default::default()
+                    }),
+            )
+            .on_press(UiMessage::ProfilePickerDismiss);
+
+            stack![with_registers, scrim, list]
+                .width(iced_core::Length::Fill)
+                .height(iced_core::Length::Fill)
+                .into()
+        } else {
+            with_registers
+        };
+
         // Markdown preview overlay
         let with_markdown: IcedElement<'a> = if state.markdown_items.is_some() {
             let md_overlay = Self::markdown_overlay(state, scale);
-            stack![with_palette, md_overlay]
+            stack![with_profiles, md_overlay]
                 .width(iced_core::Length::Fill)
                 .height(iced_core::Length::Fill)
                 .into()
         } else {
-            with_palette
+            with_profiles
         };
 
         // Context menu overlay (non-macOS only — macOS uses native NSMenu)
-        if state.context_menu_visible {
+        let with_context_menu: IcedElement<'a> = if state.context_menu_visible {
             let ctx_menu = Self::context_menu_overlay(state, scale);
             // Scrim: clicking outside the menu dismisses it
             let scrim = MouseArea::new(
@@ -667,9 +872,106 @@ impl IcedLayer {
                 .into()
         } else {
             with_markdown
+        };
+
+        // Keybinding cheatsheet (modal; click anywhere or press a key to dismiss)
+        if let Some(sections) = &state.cheatsheet {
+            let sheet = MouseArea::new(Self::cheatsheet_overlay(state, sections, scale))
+                .on_press(UiMessage::CheatsheetDismiss);
+            stack![with_context_menu, sheet]
+                .width(iced_core::Length::Fill)
+                .height(iced_core::Length::Fill)
+                .into()
+        } else {
+            with_context_menu
         }
     }
 
+    /// Keybinding cheatsheet: sections laid out in columns on a scrim.
+    fn cheatsheet_overlay<'a>(
+        state: &'a UiState,
+        sections: &'a [CheatsheetSection],
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let text_color = to_iced_color(&theme.text_primary);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let accent = to_iced_color(&theme.accent_orange);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let font_size = 12.0;
+        let column_width = 300.0 / scale;
+        let keys_width = 130.0 / scale;
+        let pad = 20.0 / scale;
+
+        // Fill columns in order, starting a new one when a column runs long
+        let max_rows = ((state.window_height / scale - 4.0 * pad) / 20.0).max(8.0) as usize;
+        let mut body = Row::new().spacing(pad);
+        let mut current = iced_widget::Column::new().spacing(4.0 / scale);
+        let mut rows_in_column = 0;
+        for section in sections {
+            let needed = section.entries.len() + 2;
+            if rows_in_column > 0 && rows_in_column + needed > max_rows {
+                let full = std::mem::replace(&mut current, iced_widget::Column::new().spacing(4.0 / scale));
+                body = body.push(container(full).width(column_width));
+                rows_in_column = 0;
+            }
+            if rows_in_column > 0 {
+                current = current.push(container(column![]).height(8.0 / scale));
+            }
+            current = current.push(text(section.title).size(13.0).color(accent).font(DM_SANS));
+            for entry in &section.entries {
+                current = current.push(
+                    row![
+                        text(entry.keys.as_str())
+                            .size(font_size)
+                            .color(text_color)
+                            .width(keys_width),
+                        text(entry.label.as_str()).size(font_size).color(text_dim),
+                    ]
+                    .spacing(8.0 / scale),
+                );
+            }
+            rows_in_column += needed;
+        }
+        body = body.push(container(current).width(column_width));
+
+        let card = container(
+            column![
+                text("Keyboard shortcuts").size(15.0).color(text_color).font(DM_SANS),
+                body,
+                text("Press any key to close")
+                    .size(11.0)
+                    .color(text_dim),
+            ]
+            .spacing(12.0 / scale),
+        )
+        .padding(pad)
+        .style(move |_: &iced_core::Theme| container::Style {
+            background: Some(iced_core::Background::Color(surface)),
+            border: iced_core::Border {
+                color: border_color,
+                width: 1.0,
+                radius: (8.0 / scale).into(),
+            },
+            ..Default::default()
+        });
+
+        container(card)
+            .width(iced_core::Length::Fill)
+            .height(iced_core::Length::Fill)
+            .align_x(iced_core::alignment::Horizontal::Center)
+            .align_y(iced_core::alignment::Vertical::Center)
+            .style(|_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(
+                    iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                )),
+                ..Default::default()
+            })
+            .into()
+    }
+
     /// Chrome bar: centered [✦ VeloTerm]
     /// Per VeloTerm design spec. Height: 38px.
     fn chrome_bar<'a>(state: &'a UiState, scale: f32) -> IcedElement<'a> {
@@ -831,6 +1133,7 @@ impl IcedLayer {
         let theme = state.theme;
         let accent = to_iced_color(&theme.accent_orange);
         let _border_color = to_iced_color(&theme.border_visible);
+        let ruler_color = to_iced_color(&theme.border_strong);
 
         let mut chrome_stack = Stack::new()
             .width(iced_core::Length::Fill)
@@ -858,26 +1161,99 @@ impl IcedLayer {
 
             chrome_stack = chrome_stack.push(pin(pane_container).x(px).y(py));
 
-            // Scrollbar thumb (overlay on right edge of pane)
-            if let Some((sx, sy, sw, sh)) = pane.scrollbar_thumb {
-                if pane.scrollbar_alpha > 0.0 {
-                    let alpha = pane.scrollbar_alpha;
-                    let scrollbar = container(column![])
-                        .width(sw / scale)
-                        .height(sh / scale)
+            // Alignment guides (column rulers, cursor crosshair, wrap markers)
+            for guide in &pane.guides {
+                let (color, radius) = match guide.kind {
+                    GuideKind::Ruler => (iced_core::Color { a: 0.6, ..ruler_color }, 0.0),
+                    GuideKind::CrosshairRow | GuideKind::CrosshairColumn => {
+                        (iced_core::Color { a: 0.08, ..accent }, 0.0)
+                    }
+                    GuideKind::WrapMarker => {
+                        (iced_core::Color { a: 0.7, ..accent }, guide.width / 2.0 / scale)
+                    }
+                    GuideKind::SelectionHandle => (accent, guide.width / 2.0 / scale),
+                };
+                let widget = container(column![])
+                    .width(guide.width / scale)
+                    .height(guide.height / scale)
+                    .style(move |_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(color)),
+                        border: iced_core::Border {
+                            radius: radius.into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    });
+                chrome_stack =
+                    chrome_stack.push(pin(widget).x(guide.x / scale).y(guide.y / scale));
+            }
+
+            // Scrollback minimap: tinted column, the cached image, and the
+            // visible rows highlighted on top
+            if let Some(minimap) = &pane.minimap {
+                chrome_stack = chrome_stack.push(Self::minimap(minimap, accent, theme, scale));
+            }
+
+            // Bookmark markers at the outer edge of the left gutter
+            let bookmark_color = to_iced_color(&theme.accent_blue);
+            for mark in &pane.bookmarks {
+                let bar = container(column![])
+                    .width(mark.width / scale)
+                    .height(mark.height / scale)
+                    .style(move |_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(bookmark_color)),
+                        border: iced_core::Border::default().rounded(mark.width / 2.0 / scale),
+                        ..Default::default()
+                    });
+                chrome_stack = chrome_stack.push(pin(bar).x(mark.x / scale).y(mark.y / scale));
+            }
+
+            // Note markers in the left gutter, the note shown on hover
+            for marker in &pane.notes {
+                chrome_stack = chrome_stack.push(Self::note_marker(marker, theme, scale));
+            }
+
+            // Hint mode labels over the start of each target
+            let hint_bg = to_iced_color(&theme.accent_orange);
+            let hint_fg = to_iced_color(&theme.bg_deep);
+            for hint in &pane.hints {
+                let label = container(text(hint.text.clone()).font(JETBRAINS_MONO).size(hint.height * 0.75 / scale).color(hint_fg))
+                    .height(hint.height / scale)
+                    .padding(iced_core::Padding::from([0.0, 2.0 / scale]))
+                    .center_y(hint.height / scale)
+                    .style(move |_: &iced_core::Theme| container::Style {
+                        background: Some(iced_core::Background::Color(hint_bg)),
+                        border: iced_core::Border::default().rounded(2.0 / scale),
+                        ..Default::default()
+                    });
+                chrome_stack = chrome_stack.push(pin(label).x(hint.x / scale).y(hint.y / scale));
+            }
+
+            // Scrollbar track and thumb (overlay on right edge of pane)
+            for quad in &pane.scrollbar {
+                chrome_stack = chrome_stack.push(Self::overlay_quad(quad, scale));
+            }
+
+            // Search match strip (drawn over the scrollbar track)
+            if let Some(strip) = &pane.match_strip {
+                let match_color = to_iced_color(&theme.search_match);
+                let current_color = to_iced_color(&theme.search_match_active);
+                let error_color = to_iced_color(&theme.accent_red);
+                for marker in &strip.markers {
+                    let color = match marker.kind {
+                        MarkerKind::Match => iced_core::Color { a: 1.0, ..match_color },
+                        MarkerKind::CurrentMatch => iced_core::Color { a: 1.0, ..current_color },
+                        MarkerKind::ErrorLine => error_color,
+                    };
+                    let tick = container(column![])
+                        .width(strip.width / scale)
+                        .height(marker.height / scale)
                         .style(move |_: &iced_core::Theme| container::Style {
-                            background: Some(iced_core::Background::Color(
-                                iced_core::Color::from_rgba(1.0, 1.0, 1.0, alpha * 0.5),
-                            )),
-                            border: iced_core::Border {
-                                color: iced_core::Color::TRANSPARENT,
-                                width: 0.0,
-                                radius: (3.0 / scale).into(),
-                            },
+                            background: Some(iced_core::Background::Color(color)),
                             ..Default::default()
                         });
                     chrome_stack =
-                        chrome_stack.push(pin(scrollbar).x(sx / scale).y(sy / scale));
+                        chrome_stack.push(pin(tick).x(strip.x / scale).y(marker.y / scale));
                 }
             }
 
@@ -894,6 +1270,42 @@ impl IcedLayer {
                     });
                 chrome_stack = chrome_stack.push(pin(stripe).x(px).y(py));
             }
+
+            // Directory rule badge pinned to the top-right corner (the search
+            // bar takes that spot on the focused pane while it is open).
+            if let Some(badge) = &pane.badge {
+                if !(pane.is_focused && state.search_active) {
+                    chrome_stack = chrome_stack.push(Self::pane_badge(badge, px, py, pw, scale));
+                }
+            }
+
+            // Group badge and scroll lock marker in the top-left corner
+            let lock_badge = pane.scroll_locked.then(|| PaneBadge {
+                text: "\u{21C5} locked".to_string(),
+                color: theme.accent_blue,
+            });
+            let corner_badges: Vec<&PaneBadge> = pane.group.iter().chain(lock_badge.as_ref()).collect();
+            if !corner_badges.is_empty() {
+                let inset = 8.0 / scale;
+                let mut pills = row![].spacing(4.0 / scale);
+                for badge in corner_badges {
+                    pills = pills.push(Self::badge_pill(badge, scale));
+                }
+                chrome_stack = chrome_stack.push(pin(pills).x(px + inset).y(py + inset));
+            }
+
+            let mut banner_bottom = py + ph;
+            if let Some(message) = &pane.error_banner {
+                let bg = to_iced_color(&theme.accent_red);
+                let (banner, height) = Self::pane_banner(message, bg, theme, px, banner_bottom, pw, ph, scale);
+                chrome_stack = chrome_stack.push(banner);
+                banner_bottom -= height;
+            }
+            if let Some(message) = &pane.prompt {
+                let bg = to_iced_color(&theme.accent_orange);
+                let (banner, _) = Self::pane_banner(message, bg, theme, px, banner_bottom, pw, ph, scale);
+                chrome_stack = chrome_stack.push(banner);
+            }
         }
 
         // Dividers between panes — visible 1px line, brighter on hover, orange on drag
@@ -946,9 +1358,283 @@ impl IcedLayer {
             }
         }
 
+        // History suggestion strip (anchored to the focused pane's prompt line)
+        if let Some(focused_pane) = state.panes.iter().find(|p| p.is_focused) {
+            if let Some(strip) = &focused_pane.suggestion_strip {
+                if !strip.items.is_empty() {
+                    let widget = Self::suggestion_strip(state, focused_pane, strip, scale);
+                    chrome_stack = chrome_stack.push(widget);
+                }
+            }
+        }
+
+        // Cursor position within a wrapped command line
+        if let Some(focused_pane) = state.panes.iter().find(|p| p.is_focused) {
+            if let Some(indicator) = &focused_pane.prompt_indicator {
+                chrome_stack = chrome_stack.push(Self::prompt_indicator(state, focused_pane, indicator, scale));
+            }
+        }
+
         chrome_stack.into()
     }
 
+    /// A flat colored rectangle from the overlay quad pipeline.
+    fn overlay_quad<'a>(quad: &OverlayQuad, scale: f32) -> IcedElement<'a> {
+        let [r, g, b, a] = quad.color;
+        let radius = quad.border_radius / scale;
+        let widget = container(column![])
+            .width(quad.rect.width / scale)
+            .height(quad.rect.height / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(iced_core::Color::from_rgba(r, g, b, a))),
+                border: iced_core::Border {
+                    radius: radius.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        pin(widget).x(quad.rect.x / scale).y(quad.rect.y / scale).into()
+    }
+
+    /// A pane's scrollback minimap with its viewport highlight and search
+    /// match marks.
+    fn minimap<'a>(
+        minimap: &MinimapView,
+        accent: iced_core::Color,
+        theme: &TerminalTheme,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let l = minimap.layout;
+        let backdrop = container(column![])
+            .width(l.width / scale)
+            .height(l.height / scale)
+            .style(|_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.25))),
+                ..Default::default()
+            });
+        let lines = iced_widget::image::Image::new(minimap.image.clone())
+            .width(l.width / scale)
+            .height(l.height / scale)
+            .content_fit(iced_core::ContentFit::Fill)
+            .filter_method(iced_core::image::FilterMethod::Nearest);
+        let viewport = container(column![])
+            .width(l.width / scale)
+            .height(l.viewport_height / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(iced_core::Color { a: 0.15, ..accent })),
+                border: iced_core::Border {
+                    color: iced_core::Color { a: 0.5, ..accent },
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+                ..Default::default()
+            });
+        let mut layers = stack![
+            pin(backdrop).x(l.x / scale).y(l.y / scale),
+            pin(lines).x(l.x / scale).y(l.y / scale),
+            pin(viewport).x(l.x / scale).y(l.viewport_y / scale),
+        ];
+        let match_color = iced_core::Color { a: 1.0, ..to_iced_color(&theme.search_match) };
+        let current_color = iced_core::Color { a: 1.0, ..to_iced_color(&theme.search_match_active) };
+        for mark in &minimap.marks {
+            let color = if mark.current { current_color } else { match_color };
+            let bar = container(column![])
+                .width(l.width / scale)
+                .height(mark.height / scale)
+                .style(move |_: &iced_core::Theme| container::Style {
+                    background: Some(iced_core::Background::Color(color)),
+                    ..Default::default()
+                });
+            layers = layers.push(pin(bar).x(l.x / scale).y(mark.y / scale));
+        }
+        layers.into()
+    }
+
+    /// Accent bar beside a noted line, with a hover area three bars wide
+    /// that shows the note.
+    fn note_marker<'a>(marker: &'a NoteMarker, theme: &TerminalTheme, scale: f32) -> IcedElement<'a> {
+        let accent = to_iced_color(&theme.accent_orange);
+        let surface = to_iced_color(&theme.bg_surface);
+        let border = to_iced_color(&theme.border_visible);
+        let fg = to_iced_color(&theme.text_primary);
+        let bar = container(column![])
+            .width(marker.width / scale)
+            .height(marker.height / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(accent)),
+                border: iced_core::Border::default().rounded(marker.width / 2.0 / scale),
+                ..Default::default()
+            });
+        let hover_area = container(bar)
+            .width(marker.width * 3.0 / scale)
+            .center_x(marker.width * 3.0 / scale);
+        let bubble = container(text(marker.text.clone()).size(12.0).color(fg))
+            .padding(iced_core::Padding::from([4.0 / scale, 8.0 / scale]))
+            .max_width(320.0 / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(surface)),
+                border: iced_core::Border {
+                    color: border,
+                    width: 1.0,
+                    radius: (4.0 / scale).into(),
+                },
+                ..Default::default()
+            });
+        let widget = tooltip(hover_area, bubble, tooltip::Position::Right);
+        pin(widget).x((marker.x - marker.width) / scale).y(marker.y / scale).into()
+    }
+
+    /// Colored pill with the badge text, right-aligned inside the pane.
+    fn pane_badge<'a>(badge: &PaneBadge, px: f32, py: f32, pw: f32, scale: f32) -> IcedElement<'a> {
+        let inset = 8.0 / scale;
+        let anchor = container(Self::badge_pill(badge, scale))
+            .width((pw - inset).max(0.0))
+            .align_x(iced_core::alignment::Horizontal::Right);
+        pin(anchor).x(px).y(py + inset).into()
+    }
+
+    /// A badge's text on a rounded pill of its color.
+    fn badge_pill<'a>(badge: &PaneBadge, scale: f32) -> IcedElement<'a> {
+        let bg = badge.color;
+        let luminance = 0.2126 * bg.r + 0.7152 * bg.g + 0.0722 * bg.b;
+        let fg = if luminance > 0.6 {
+            iced_core::Color::BLACK
+        } else {
+            iced_core::Color::WHITE
+        };
+        container(text(badge.text.clone()).size(11.0).color(fg))
+            .padding(iced_core::Padding::from([2.0 / scale, 8.0 / scale]))
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(bg)),
+                border: iced_core::Border {
+                    radius: (4.0 / scale).into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .into()
+    }
+
+    /// Full-width strip ending at `bottom` of a pane: a dead or unresponsive
+    /// shell, or a clipboard request waiting for an answer. Also returns the
+    /// strip's height so banners can stack.
+    #[allow(clippy::too_many_arguments)]
+    fn pane_banner<'a>(
+        message: &str,
+        bg: iced_core::Color,
+        theme: &TerminalTheme,
+        px: f32,
+        bottom: f32,
+        pw: f32,
+        ph: f32,
+        scale: f32,
+    ) -> (IcedElement<'a>, f32) {
+        let fg = to_iced_color(&theme.text_primary);
+        let height = (28.0 / scale).min(ph);
+        let banner = container(text(message.to_string()).size(12.0).color(fg))
+            .width(pw)
+            .height(height)
+            .padding(iced_core::Padding::from([0.0, 10.0 / scale]))
+            .align_y(iced_core::alignment::Vertical::Center)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(iced_core::Color { a: 0.9, ..bg })),
+                ..Default::default()
+            });
+        (pin(banner).x(px).y(bottom - height).into(), height)
+    }
+
+    /// Suggestion strip: compact row of history matches just above the prompt
+    /// line (or below it when the prompt is at the top of the pane).
+    fn suggestion_strip<'a>(
+        state: &'a UiState,
+        pane: &PaneInfo,
+        strip: &SuggestionStrip,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let text_color = to_iced_color(&theme.text_primary);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let accent = to_iced_color(&theme.accent_orange);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let font_size = 12.0;
+        let strip_height = 24.0;
+        let pad_h = 8.0 / scale;
+        let spacing = 12.0 / scale;
+        let radius = 4.0 / scale;
+
+        let mut items = row![].spacing(spacing).align_y(iced_core::Alignment::Center);
+        for (i, item) in strip.items.iter().enumerate() {
+            let color = if i == 0 { text_color } else { text_dim };
+            items = items.push(text(item.clone()).size(font_size).color(color));
+        }
+        items = items.push(text(strip.accept_label.clone()).size(font_size).color(accent));
+
+        let bar = container(items.padding(iced_core::Padding::from([0.0, pad_h])))
+            .height(strip_height / scale)
+            .align_y(iced_core::alignment::Vertical::Center)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(surface)),
+                border: iced_core::Border {
+                    color: border_color,
+                    width: 1.0,
+                    radius: radius.into(),
+                },
+                ..Default::default()
+            });
+
+        let above = strip.row_y - strip_height;
+        let y = if above >= pane.y {
+            above
+        } else {
+            strip.row_y + strip.row_height
+        };
+
+        pin(bar).x(strip.x / scale).y(y / scale).into()
+    }
+
+    /// Prompt indicator: small dim label at the right edge of the pane, just
+    /// above the cursor row (or below it when the cursor is at the top).
+    fn prompt_indicator<'a>(
+        state: &'a UiState,
+        pane: &PaneInfo,
+        indicator: &PromptIndicator,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let height = 20.0;
+        let inset = 8.0 / scale;
+        let label = container(text(indicator.label.clone()).size(11.0).color(text_dim))
+            .height(height / scale)
+            .padding(iced_core::Padding::from([0.0, 6.0 / scale]))
+            .align_y(iced_core::alignment::Vertical::Center)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(iced_core::Color { a: 0.85, ..surface })),
+                border: iced_core::Border {
+                    color: border_color,
+                    width: 1.0,
+                    radius: (4.0 / scale).into(),
+                },
+                ..Default::default()
+            });
+
+        let above = indicator.row_y - height;
+        let y = if above >= pane.y {
+            above
+        } else {
+            indicator.row_y + indicator.row_height
+        };
+        let anchor = container(label)
+            .width((pane.width / scale - inset).max(0.0))
+            .align_x(iced_core::alignment::Horizontal::Right);
+        pin(anchor).x(pane.x / scale).y(y / scale).into()
+    }
+
     /// Search bar: floating overlay at top-right of the focused pane.
     fn search_bar<'a>(
         state: &'a UiState,
@@ -964,6 +1650,7 @@ impl IcedLayer {
         let border_color = to_iced_color(&theme.border_visible);
 
         let font_size = 12.0;
+        let small_size = 10.0;
         let pad_v = 6.0 / scale;
         let pad_h = 12.0 / scale;
         let bar_width = 260.0 / scale;
@@ -1000,10 +1687,39 @@ impl IcedLayer {
             String::new()
         };
 
+        // Option toggles, lit when on
+        let toggle = |label: &'static str, on: bool, command: crate::input::SearchCommand| -> IcedElement<'a> {
+            let label_color = if on { text_color } else { text_dim };
+            let toggle_bg = if on { Some(iced_core::Background::Color(accent.scale_alpha(0.3))) } else { None };
+            MouseArea::new(
+                container(text(label).size(small_size).color(label_color))
+                    .padding(iced_core::Padding::from([1.0 / scale, 4.0 / scale]))
+                    .style(move |_: &iced_core::Theme| container::Style {
+                        background: toggle_bg,
+                        border: iced_core::Border {
+                            radius: (3.0 / scale).into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }),
+            )
+            .on_press(UiMessage::Search(command))
+            .into()
+        };
+        let options = state.search_options;
+        let toggles = row![
+            toggle("Aa", options.case_sensitive, crate::input::SearchCommand::ToggleCaseSensitive),
+            toggle("W", options.whole_word, crate::input::SearchCommand::ToggleWholeWord),
+            toggle(".*", !options.literal, crate::input::SearchCommand::ToggleRegex),
+        ]
+        .spacing(2.0 / scale)
+        .align_y(iced_core::Alignment::Center);
+
         let bar_content = row![
             text(query_display).size(font_size).color(query_color),
             hspace(),
             text(match_text).size(font_size).color(text_dim),
+            toggles,
         ]
         .spacing(spacing)
         .align_y(iced_core::Alignment::Center)
@@ -1038,6 +1754,295 @@ impl IcedLayer {
         pin(bar).x(bar_x).y(bar_y).into()
     }
 
+    /// Bookmark list: the focused pane's bookmarks, styled like the command
+    /// palette. Click or Enter jumps to one.
+    fn bookmark_list<'a>(
+        items: &'a [(String, String)],
+        selected: usize,
+        state: &'a UiState,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let surface_raised = to_iced_color(&theme.bg_hover);
+        let text_color = to_iced_color(&theme.text_primary);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let text_secondary = to_iced_color(&theme.text_secondary);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let font_size = 13.0;
+        let small_size = 11.0;
+        let pad_h = 14.0 / scale;
+        let pad_v = 8.0 / scale;
+        let list_width = 520.0 / scale;
+        let item_height = 32.0 / scale;
+        let max_visible = 10;
+
+        let header = container(text("Bookmarks").size(font_size).color(text_color))
+            .width(list_width)
+            .padding(iced_core::Padding::from([pad_v, pad_h]));
+
+        let mut rows = iced_widget::Column::new().spacing(2.0 / scale);
+        if items.is_empty() {
+            rows = rows.push(
+                container(
+                    text("No bookmarks in this pane")
+                        .size(font_size)
+                        .color(text_dim),
+                )
+                .width(list_width)
+                .padding(iced_core::Padding::from([pad_v, pad_h])),
+            );
+        }
+        let visible = crate::command_palette::visible_range(selected, items.len(), max_visible);
+        for (i, (label, line)) in items.iter().enumerate().skip(visible.start).take(visible.len()) {
+            let is_selected = i == selected;
+            let item_bg = if is_selected { surface_raised } else { surface };
+            let item_fg = if is_selected { text_color } else { text_secondary };
+            let item = container(
+                row![
+                    text(label.as_str()).size(small_size).color(text_dim).width(64.0 / scale),
+                    text(line.as_str()).size(font_size).color(item_fg),
+                ]
+                .align_y(iced_core::Alignment::Center)
+                .padding(iced_core::Padding::from([pad_v, pad_h])),
+            )
+            .width(list_width)
+            .height(item_height)
+            .clip(true)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(item_bg)),
+                ..Default::default()
+            });
+            rows = rows.push(MouseArea::new(item).on_press(UiMessage::BookmarkClicked(i)));
+        }
+
+        let footer = container(
+            text("Enter: jump \u{00B7} Esc: close")
+                .size(small_size)
+                .color(text_dim),
+        )
+        .width(list_width)
+        .padding(iced_core::Padding::from([4.0 / scale, pad_h]));
+
+        let sep = container(column![])
+            .width(list_width)
+            .height(1.0 / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(border_color)),
+                ..Default::default()
+            });
+
+        let list_box = container(column![header, sep, rows, footer])
+            .width(list_width)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(surface)),
+                border: iced_core::Border {
+                    color: border_color,
+                    width: 1.0,
+                    radius: (8.0 / scale).into(),
+                },
+                shadow: iced_core::Shadow {
+                    color: iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced_core::Vector::new(0.0, 4.0 / scale),
+                    blur_radius: 16.0 / scale,
+                },
+                ..Default::default()
+            });
+
+        let x = ((state.window_width / scale) - list_width) / 2.0;
+        pin(list_box).x(x).y(80.0 / scale).into()
+    }
+
+    /// Vi register viewer: the named registers, styled like the command
+    /// palette. Click or Enter copies one to the clipboard.
+    fn register_list<'a>(
+        items: &'a [(String, String)],
+        selected: usize,
+        state: &'a UiState,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let surface_raised = to_iced_color(&theme.bg_hover);
+        let text_color = to_iced_color(&theme.text_primary);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let text_secondary = to_iced_color(&theme.text_secondary);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let font_size = 13.0;
+        let small_size = 11.0;
+        let pad_h = 14.0 / scale;
+        let pad_v = 8.0 / scale;
+        let list_width = 520.0 / scale;
+        let item_height = 32.0 / scale;
+        let max_visible = 10;
+
+        let header = container(text("Vi Registers").size(font_size).color(text_color))
+            .width(list_width)
+            .padding(iced_core::Padding::from([pad_v, pad_h]));
+
+        let mut rows = iced_widget::Column::new().spacing(2.0 / scale);
+        if items.is_empty() {
+            rows = rows.push(
+                container(
+                    text("No registers yet \u{2014} yank with \"a y in vi-mode")
+                        .size(font_size)
+                        .color(text_dim),
+                )
+                .width(list_width)
+                .padding(iced_core::Padding::from([pad_v, pad_h])),
+            );
+        }
+        let visible = crate::command_palette::visible_range(selected, items.len(), max_visible);
+        for (i, (name, contents)) in items.iter().enumerate().skip(visible.start).take(visible.len()) {
+            let is_selected = i == selected;
+            let item_bg = if is_selected { surface_raised } else { surface };
+            let item_fg = if is_selected { text_color } else { text_secondary };
+            let item = container(
+                row![
+                    text(name.as_str()).size(small_size).color(text_dim).width(64.0 / scale),
+                    text(contents.as_str()).size(font_size).color(item_fg),
+                ]
+                .align_y(iced_core::Alignment::Center)
+                .padding(iced_core::Padding::from([pad_v, pad_h])),
+            )
+            .width(list_width)
+            .height(item_height)
+            .clip(true)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(item_bg)),
+                ..Default::default()
+            });
+            rows = rows.push(MouseArea::new(item).on_press(UiMessage::RegisterClicked(i)));
+        }
+
+        let footer = container(
+            text("Enter: copy to clipboard \u{00B7} Esc: close")
+                .size(small_size)
+                .color(text_dim),
+        )
+        .width(list_width)
+        .padding(iced_core::Padding::from([4.0 / scale, pad_h]));
+
+        let sep = container(column![])
+            .width(list_width)
+            .height(1.0 / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(border_color)),
+                ..Default::default()
+            });
+
+        let list_box = container(column![header, sep, rows, footer])
+            .width(list_width)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(surface)),
+                border: iced_core::Border {
+                    color: border_color,
+                    width: 1.0,
+                    radius: (8.0 / scale).into(),
+                },
+                shadow: iced_core::Shadow {
+                    color: iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced_core::Vector::new(0.0, 4.0 / scale),
+                    blur_radius: 16.0 / scale,
+                },
+                ..Default::default()
+            });
+
+        let x = ((state.window_width / scale) - list_width) / 2.0;
+        pin(list_box).x(x).y(80.0 / scale).into()
+    }
+
+    /// Profile picker: the shell and the `[[profiles]]` entries, styled like
+    /// the command palette. Click or Enter opens one.
+    fn profile_picker<'a>(
+        items: &'a [(String, String)],
+        selected: usize,
+        state: &'a UiState,
+        scale: f32,
+    ) -> IcedElement<'a> {
+        let theme = state.theme;
+        let surface = to_iced_color(&theme.bg_surface);
+        let surface_raised = to_iced_color(&theme.bg_hover);
+        let text_color = to_iced_color(&theme.text_primary);
+        let text_dim = to_iced_color(&theme.text_ghost);
+        let text_secondary = to_iced_color(&theme.text_secondary);
+        let border_color = to_iced_color(&theme.border_visible);
+
+        let font_size = 13.0;
+        let small_size = 11.0;
+        let pad_h = 14.0 / scale;
+        let pad_v = 8.0 / scale;
+        let list_width = 520.0 / scale;
+        let item_height = 32.0 / scale;
+        let max_visible = 10;
+
+        let header = container(text("Open Profile").size(font_size).color(text_color))
+            .width(list_width)
+            .padding(iced_core::Padding::from([pad_v, pad_h]));
+
+        let mut rows = iced_widget::Column::new().spacing(2.0 / scale);
+        let visible = crate::command_palette::visible_range(selected, items.len(), max_visible);
+        for (i, (name, command)) in items.iter().enumerate().skip(visible.start).take(visible.len()) {
+            let is_selected = i == selected;
+            let item_bg = if is_selected { surface_raised } else { surface };
+            let item_fg = if is_selected { text_color } else { text_secondary };
+            let item = container(
+                row![
+                    text(name.as_str()).size(font_size).color(item_fg).width(180.0 / scale),
+                    text(command.as_str()).size(small_size).color(text_dim),
+                ]
+                .align_y(iced_core::Alignment::Center)
+                .padding(iced_core::Padding::from([pad_v, pad_h])),
+            )
+            .width(list_width)
+            .height(item_height)
+            .clip(true)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(item_bg)),
+                ..Default::default()
+            });
+            rows = rows.push(MouseArea::new(item).on_press(UiMessage::ProfileClicked(i)));
+        }
+
+        let footer = container(
+            text("Enter: open \u{00B7} Esc: close")
+                .size(small_size)
+                .color(text_dim),
+        )
+        .width(list_width)
+        .padding(iced_core::Padding::from([4.0 / scale, pad_h]));
+
+        let sep = container(column![])
+            .width(list_width)
+            .height(1.0 / scale)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(border_color)),
+                ..Default::default()
+            });
+
+        let list_box = container(column![header, sep, rows, footer])
+            .width(list_width)
+            .style(move |_: &iced_core::Theme| container::Style {
+                background: Some(iced_core::Background::Color(surface)),
+                border: iced_core::Border {
+                    color: border_color,
+                    width: 1.0,
+                    radius: (8.0 / scale).into(),
+                },
+                shadow: iced_core::Shadow {
+                    color: iced_core::Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    offset: iced_core::Vector::new(0.0, 4.0 / scale),
+                    blur_radius: 16.0 / scale,
+                },
+                ..Default::default()
+            });
+
+        let x = ((state.window_width / scale) - list_width) / 2.0;
+        pin(list_box).x(x).y(80.0 / scale).into()
+    }
+
     /// Command palette: floating modal centered near top of window.
     fn command_palette<'a>(state: &'a UiState, scale: f32) -> IcedElement<'a> {
         let theme = state.theme;
@@ -1093,11 +2098,15 @@ impl IcedLayer {
             ..Default::default()
         });
 
-        // Result list
-        let visible_count = state.palette_items.len().min(max_visible);
+        // Result list, scrolled to keep the selection in view
+        let visible = crate::command_palette::visible_range(
+            state.palette_selected,
+            state.palette_items.len(),
+            max_visible,
+        );
         let mut result_col = iced_widget::Column::new().spacing(spacing);
 
-        for (i, (name, _desc, keybinding)) in state.palette_items.iter().enumerate().take(visible_count) {
+        for (i, (name, _desc, keybinding)) in state.palette_items.iter().enumerate().skip(visible.start).take(visible.len()) {
             let is_selected = i == state.palette_selected;
             let item_bg = if is_selected { surface_raised } else { surface };
             let item_fg = if is_selected { text_color } else { text_secondary };
@@ -1129,13 +2138,23 @@ impl IcedLayer {
                     ..Default::default()
                 });
 
-            result_col = result_col.push(item);
+            result_col = result_col.push(MouseArea::new(item).on_press(UiMessage::PaletteItemClicked(i)));
         }
 
-        // Item count footer
+        // Footer: what the selected command does, and the result count
+        let description = state
+            .palette_items
+            .get(state.palette_selected)
+            .map(|(_, desc, _)| desc.as_str())
+            .unwrap_or("");
         let count_text = format!("{} commands", state.palette_items.len());
         let footer = container(
-            text(count_text).size(small_size).color(text_dim),
+            row![
+                text(description).size(small_size).color(text_secondary),
+                hspace(),
+                text(count_text).size(small_size).color(text_dim),
+            ]
+            .spacing(8.0 / scale),
         )
         .width(palette_width)
         .padding(iced_core::Padding::from([4.0 / scale, pad_h]))
@@ -1527,10 +2546,8 @@ impl IcedLayer {
             title_row = title_row.push(dot);
 
             if is_editing {
-                let idx = i;
+                // Typing is handled by the window (Enter keeps, Escape cancels)
                 let rename_input = text_input("Tab name", &state.editing_tab_value)
-                    .on_input(UiMessage::RenameTabInput)
-                    .on_submit(UiMessage::RenameTabCommit(idx, state.editing_tab_value.clone()))
                     .size(font_size)
                     .width(iced_core::Length::Fill)
                     .style(move |_: &iced_core::Theme, _status| {
@@ -1754,8 +2771,8 @@ impl IcedLayer {
 
         let session_name = format!("Session {}", state.active_tab_index + 1);
 
-        // Left group: dot + session + | + shell + | + cwd
-        let left = row![
+        // Left group: dot + session + | + shell [+ | + git branch]
+        let mut left = row![
             green_dot,
             text(session_name).size(status_size).color(text_muted).font(DM_SANS),
             make_divider(),
@@ -1763,6 +2780,28 @@ impl IcedLayer {
         ]
         .spacing(8.0 / scale)
         .align_y(iced_core::Alignment::Center);
+        if let Some(git) = &state.git_segment {
+            let color = if git.dirty {
+                to_iced_color(&theme.accent_orange)
+            } else {
+                text_muted
+            };
+            left = left
+                .push(make_divider())
+                .push(text(git.label()).size(status_size).color(color).font(DM_SANS));
+        }
+        if state.secure_keyboard_entry {
+            let color = to_iced_color(&theme.accent_orange);
+            left = left
+                .push(make_divider())
+                .push(text("Secure Input").size(status_size).color(color).font(DM_SANS));
+        }
+        if let Some(label) = &state.recording {
+            let color = to_iced_color(&theme.accent_red);
+            left = left
+                .push(make_divider())
+                .push(text(format!("\u{25CF} {label}")).size(status_size).color(color).font(DM_SANS));
+        }
 
         // Right: encoding | theme button
         let text_secondary = to_iced_color(&theme.text_secondary);
@@ -3224,8 +4263,20 @@ mod tests {
                 index: 0,
                 title: "Shell".to_string(),
                 shell_name: "zsh".to_string(),
-                scrollbar_thumb: None,
-                scrollbar_alpha: 0.0,
+                scrollbar: Vec::new(),
+                minimap: None,
+                match_strip: None,
+                suggestion_strip: None,
+                prompt_indicator: None,
+                guides: Vec::new(),
+                badge: None,
+                error_banner: None,
+                prompt: None,
+                notes: Vec::new(),
+                bookmarks: Vec::new(),
+                hints: Vec::new(),
+                scroll_locked: false,
+                group: None,
             }],
             pane_count: 1,
             is_zoomed: false,
@@ -3238,17 +4289,26 @@ mod tests {
             search_current: 0,
             search_total: 0,
             search_error: false,
+            search_options: Default::default(),
             dividers: Vec::new(),
             bell_flash: false,
+            screenshot_flash: None,
             palette_active: false,
             palette_query: String::new(),
             palette_items: Vec::new(),
             palette_selected: 0,
+            bookmark_list: None,
+            bookmark_selected: 0,
+            register_list: None,
+            register_selected: 0,
+            profile_picker: None,
+            profile_selected: 0,
             sidebar_visible: false,
             sidebar_tabs: Vec::new(),
             sidebar_width: 200.0,
             hovered_sidebar_tab: None,
             theme_selector_open: false,
+            cheatsheet: None,
             editing_tab: None,
             editing_tab_value: String::new(),
             hovering_new_tab: false,
@@ -3287,6 +4347,9 @@ mod tests {
             git_review_diff_scroll: 0.0,
             git_review_branch: String::new(),
             git_review_current_hunk: 0,
+            git_segment: None,
+            secure_keyboard_entry: false,
+            recording: None,
         }
     }
 
@@ -3514,8 +4577,20 @@ mod tests {
                 index: 0,
                 title: "Shell".to_string(),
                 shell_name: "zsh".to_string(),
-                scrollbar_thumb: None,
-                scrollbar_alpha: 0.0,
+                scrollbar: Vec::new(),
+                minimap: None,
+                match_strip: None,
+                suggestion_strip: None,
+                prompt_indicator: None,
+                guides: Vec::new(),
+                badge: None,
+                error_banner: None,
+                prompt: None,
+                notes: Vec::new(),
+                bookmarks: Vec::new(),
+                hints: Vec::new(),
+                scroll_locked: false,
+                group: None,
             }],
             pane_count: 1,
             is_zoomed: false,
@@ -3528,17 +4603,26 @@ mod tests {
             search_current: 0,
             search_total: 0,
             search_error: false,
+            search_options: Default::default(),
             dividers: Vec::new(),
             bell_flash: false,
+            screenshot_flash: None,
             palette_active: false,
             palette_query: String::new(),
             palette_items: Vec::new(),
             palette_selected: 0,
+            bookmark_list: None,
+            bookmark_selected: 0,
+            register_list: None,
+            register_selected: 0,
+            profile_picker: None,
+            profile_selected: 0,
             sidebar_visible: false,
             sidebar_tabs: Vec::new(),
             sidebar_width: 200.0,
             hovered_sidebar_tab: None,
             theme_selector_open: false,
+            cheatsheet: None,
             editing_tab: None,
             editing_tab_value: String::new(),
         hovering_new_tab: false,
@@ -3576,6 +4660,9 @@ mod tests {
             git_review_commit_message: String::new(),
             git_review_diff_scroll: 0.0,
             git_review_current_hunk: 0,
+            git_segment: None,
+            secure_keyboard_entry: false,
+            recording: None,
             git_review_branch: String::new(),
             };
         let messages = layer.render(&view, &state);
@@ -3634,8 +4721,20 @@ mod tests {
                     index: 0,
                     title: "src".to_string(),
                     shell_name: "zsh".to_string(),
-                    scrollbar_thumb: None,
-                    scrollbar_alpha: 0.0,
+                    scrollbar: Vec::new(),
+                    minimap: None,
+                    match_strip: None,
+                    suggestion_strip: None,
+                    prompt_indicator: None,
+                    guides: Vec::new(),
+                    badge: None,
+                    error_banner: None,
+                    prompt: None,
+                    notes: Vec::new(),
+                    bookmarks: Vec::new(),
+                    hints: Vec::new(),
+                    scroll_locked: false,
+                    group: None,
                 },
                 PaneInfo {
                     x: 636.0,
@@ -3646,8 +4745,20 @@ mod tests {
                     index: 1,
                     title: "tests".to_string(),
                     shell_name: "zsh".to_string(),
-                    scrollbar_thumb: None,
-                    scrollbar_alpha: 0.0,
+                    scrollbar: Vec::new(),
+                    minimap: None,
+                    match_strip: None,
+                    suggestion_strip: None,
+                    prompt_indicator: None,
+                    guides: Vec::new(),
+                    badge: None,
+                    error_banner: None,
+                    prompt: None,
+                    notes: Vec::new(),
+                    bookmarks: Vec::new(),
+                    hints: Vec::new(),
+                    scroll_locked: false,
+                    group: None,
                 },
             ],
             pane_count: 2,
@@ -3661,17 +4772,26 @@ mod tests {
             search_current: 0,
             search_total: 0,
             search_error: false,
+            search_options: Default::default(),
             dividers: Vec::new(),
             bell_flash: false,
+            screenshot_flash: None,
             palette_active: false,
             palette_query: String::new(),
             palette_items: Vec::new(),
             palette_selected: 0,
+            bookmark_list: None,
+            bookmark_selected: 0,
+            register_list: None,
+            register_selected: 0,
+            profile_picker: None,
+            profile_selected: 0,
             sidebar_visible: false,
             sidebar_tabs: Vec::new(),
             sidebar_width: 200.0,
             hovered_sidebar_tab: None,
             theme_selector_open: false,
+            cheatsheet: None,
             editing_tab: None,
             editing_tab_value: String::new(),
         hovering_new_tab: false,
@@ -3709,6 +4829,9 @@ mod tests {
             git_review_commit_message: String::new(),
             git_review_diff_scroll: 0.0,
             git_review_current_hunk: 0,
+            git_segment: None,
+            secure_keyboard_entry: false,
+            recording: None,
             git_review_branch: String::new(),
             };
         let messages = layer.render(&view, &state);
@@ -3726,8 +4849,20 @@ mod tests {
             index: 0,
             title: "home".to_string(),
             shell_name: "zsh".to_string(),
-            scrollbar_thumb: None,
-            scrollbar_alpha: 0.0,
+            scrollbar: Vec::new(),
+            minimap: None,
+            match_strip: None,
+            suggestion_strip: None,
+            prompt_indicator: None,
+            guides: Vec::new(),
+            badge: None,
+            error_banner: None,
+            prompt: None,
+            notes: Vec::new(),
+            bookmarks: Vec::new(),
+            hints: Vec::new(),
+            scroll_locked: false,
+            group: None,
         };
         assert!(pane.is_focused);
         assert_eq!(pane.title, "home");
@@ -4082,17 +5217,26 @@ mod tests {
             search_current: 0,
             search_total: 0,
             search_error: false,
+            search_options: Default::default(),
             dividers: vec![],
             bell_flash: false,
+            screenshot_flash: None,
             palette_active: false,
             palette_query: String::new(),
             palette_items: vec![],
             palette_selected: 0,
+            bookmark_list: None,
+            bookmark_selected: 0,
+            register_list: None,
+            register_selected: 0,
+            profile_picker: None,
+            profile_selected: 0,
             sidebar_visible: false,
             sidebar_tabs: vec![],
             sidebar_width: 200.0,
             hovered_sidebar_tab: None,
             theme_selector_open: false,
+            cheatsheet: None,
             editing_tab: None,
             editing_tab_value: String::new(),
             hovering_new_tab: false,
@@ -4131,6 +5275,9 @@ mod tests {
             git_review_diff_scroll: 0.0,
             git_review_branch: String::new(),
             git_review_current_hunk: 0,
+            git_segment: None,
+            secure_keyboard_entry: false,
+            recording: None,
         };
         assert!(!state.context_menu_visible);
         assert_eq!(state.context_menu_position, (0.0, 0.0));
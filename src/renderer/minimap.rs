@@ -0,0 +1,374 @@
+// Scrollback minimap: a code-editor style column on the pane's right edge
+// showing the whole buffer as one row of colored blocks per line, with the
+// visible part highlighted. The blocks are rasterized off-screen into a small
+// RGBA image that is reused as a texture until the pane's content changes.
+
+use std::time::{Duration, Instant};
+
+use crate::config::theme::Color;
+use crate::renderer::grid_renderer::GridCell;
+
+/// Gap left for the scrollbar and match strip between the minimap and the
+/// pane's right padding, in physical pixels.
+pub const SCROLLBAR_GAP: f32 = 8.0;
+
+/// Most buffer lines rasterized; longer scrollback is sampled evenly.
+pub const MAX_IMAGE_LINES: usize = 4096;
+
+/// Most columns rasterized per line.
+pub const MAX_IMAGE_COLUMNS: usize = 256;
+
+/// Minimum height of the viewport highlight in physical pixels.
+const MIN_VIEWPORT_HEIGHT: f32 = 4.0;
+
+/// Minimum height of a search match mark in physical pixels.
+const MIN_MARK_HEIGHT: f32 = 2.0;
+
+/// How often a pane with new output re-rasterizes its minimap.
+const REBUILD_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Ink cells blend this much foreground over their background.
+const INK_WEIGHT: f32 = 0.8;
+
+/// Where a pane's minimap is drawn, in physical pixels (same coordinate
+/// space as `PaneInfo`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinimapLayout {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    /// Height of the drawn lines: the buffer at the zoom's line height,
+    /// squeezed to the pane when it doesn't fit.
+    pub height: f32,
+    /// Height of one buffer line.
+    pub line_height: f32,
+    /// The visible rows, highlighted on top of the lines.
+    pub viewport_y: f32,
+    pub viewport_height: f32,
+}
+
+/// Lay out the minimap for a pane.
+///
+/// `pane_x`, `pane_y`, `pane_w`, `pane_h` are the pane content area and
+/// `padding` is `[top, bottom, left, right]`, as for the match strip.
+/// `width` and `line_height` are already scaled to physical pixels.
+#[allow(clippy::too_many_arguments)]
+pub fn minimap_layout(
+    pane_x: f32,
+    pane_y: f32,
+    pane_w: f32,
+    pane_h: f32,
+    padding: [f32; 4],
+    width: f32,
+    line_height: f32,
+    history_size: usize,
+    screen_rows: usize,
+    display_offset: usize,
+) -> Option<MinimapLayout> {
+    let total = history_size + screen_rows;
+    let avail_height = pane_h - padding[0] - padding[1];
+    if total == 0 || avail_height <= 0.0 {
+        return None;
+    }
+    let height = (total as f32 * line_height).min(avail_height);
+    let line_height = height / total as f32;
+    let y = pane_y + padding[0];
+    let first_visible = history_size.saturating_sub(display_offset);
+    let viewport_height = (screen_rows as f32 * line_height).max(MIN_VIEWPORT_HEIGHT).min(height);
+    let viewport_y = (y + first_visible as f32 * line_height).min(y + height - viewport_height);
+    Some(MinimapLayout {
+        x: pane_x + pane_w - padding[3] - SCROLLBAR_GAP - width,
+        y,
+        width,
+        height,
+        line_height,
+        viewport_y,
+        viewport_height,
+    })
+}
+
+/// The display offset that centers the viewport on the line under a click,
+/// or `None` when the click misses the minimap.
+pub fn offset_at(
+    layout: &MinimapLayout,
+    click_x: f32,
+    click_y: f32,
+    history_size: usize,
+    screen_rows: usize,
+) -> Option<usize> {
+    let inside_x = click_x >= layout.x && click_x <= layout.x + layout.width;
+    let inside_y = click_y >= layout.y && click_y <= layout.y + layout.height;
+    if !inside_x || !inside_y {
+        return None;
+    }
+    let total = history_size + screen_rows;
+    let line = (((click_y - layout.y) / layout.line_height) as usize).min(total.saturating_sub(1));
+    let first = line.saturating_sub(screen_rows / 2).min(history_size);
+    Some(history_size - first)
+}
+
+/// A search match drawn across the minimap at its line, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchMark {
+    pub y: f32,
+    pub height: f32,
+    /// The current match, drawn in the active match color.
+    pub current: bool,
+}
+
+/// Marks for search matches at their lines' places in the minimap. Matches
+/// that land on the same pixel row share one mark, the current match's if
+/// it is among them.
+pub fn match_marks(
+    layout: &MinimapLayout,
+    history_size: usize,
+    matches: &[crate::search::SearchMatch],
+    current_index: usize,
+) -> Vec<MatchMark> {
+    let height = layout.line_height.max(MIN_MARK_HEIGHT);
+    let mut marks: Vec<MatchMark> = Vec::new();
+    for (i, m) in matches.iter().enumerate() {
+        let line = (m.row + history_size as i32).max(0) as f32;
+        let y = (layout.y + line * layout.line_height).min(layout.y + layout.height - height).floor();
+        let current = i == current_index;
+        match marks.iter_mut().find(|mark| mark.y == y) {
+            Some(mark) => mark.current |= current,
+            None => marks.push(MatchMark { y, height, current }),
+        }
+    }
+    marks
+}
+
+/// An off-screen rendering of the buffer: one pixel per cell, one row per
+/// (sampled) line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinimapImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8, row-major.
+    pub pixels: Vec<u8>,
+}
+
+/// Buffer lines to rasterize: all of them, or an even sample of
+/// [`MAX_IMAGE_LINES`] when the buffer is longer.
+pub fn sampled_lines(total_lines: usize) -> Vec<usize> {
+    if total_lines <= MAX_IMAGE_LINES {
+        return (0..total_lines).collect();
+    }
+    (0..MAX_IMAGE_LINES)
+        .map(|i| i * total_lines / MAX_IMAGE_LINES)
+        .collect()
+}
+
+/// Rasterize `total_lines` buffer lines of `columns` cells. `line_cells`
+/// returns the cells of one buffer line (0 = oldest scrollback line).
+pub fn rasterize(
+    total_lines: usize,
+    columns: usize,
+    default_bg: Color,
+    line_cells: impl Fn(usize) -> Vec<GridCell>,
+) -> MinimapImage {
+    let lines = sampled_lines(total_lines);
+    let width = columns.clamp(1, MAX_IMAGE_COLUMNS);
+    let mut pixels = vec![0u8; width * lines.len() * 4];
+    for (row, &line) in lines.iter().enumerate() {
+        let out = &mut pixels[row * width * 4..(row + 1) * width * 4];
+        for (col, cell) in line_cells(line).iter().take(width).enumerate() {
+            out[col * 4..col * 4 + 4].copy_from_slice(&cell_pixel(cell, default_bg));
+        }
+    }
+    MinimapImage {
+        width: width as u32,
+        height: lines.len() as u32,
+        pixels,
+    }
+}
+
+/// One cell's block: text shows as its foreground color, blank cells only
+/// when they have a non-default background.
+fn cell_pixel(cell: &GridCell, default_bg: Color) -> [u8; 4] {
+    if !cell.ch.is_whitespace() && cell.ch != '\0' {
+        let mix = |fg: f32, bg: f32| fg * INK_WEIGHT + bg * (1.0 - INK_WEIGHT);
+        Color::from_rgb(
+            mix(cell.fg.r, cell.bg.r),
+            mix(cell.fg.g, cell.bg.g),
+            mix(cell.fg.b, cell.bg.b),
+        )
+        .into_rgba8()
+    } else if cell.bg != default_bg {
+        Color { a: 1.0, ..cell.bg }.into_rgba8()
+    } else {
+        [0; 4]
+    }
+}
+
+/// A pane's rasterized minimap, kept as an image handle so the GPU texture
+/// is reused across frames until new output marks it dirty.
+#[derive(Debug, Default)]
+pub struct MinimapCache {
+    handle: Option<iced_core::image::Handle>,
+    dirty: bool,
+    built_at: Option<Instant>,
+}
+
+impl MinimapCache {
+    /// Content changed (output, reflow, theme); rebuild at the next chance.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether to re-rasterize now: never built, or dirty and the last
+    /// build is older than the rebuild interval.
+    pub fn needs_rebuild(&self, now: Instant) -> bool {
+        match (self.handle.is_some(), self.built_at) {
+            (true, Some(built_at)) => self.dirty && now.duration_since(built_at) >= REBUILD_INTERVAL,
+            _ => true,
+        }
+    }
+
+    /// Keep a freshly rasterized image.
+    pub fn store(&mut self, image: MinimapImage, now: Instant) {
+        self.handle = Some(iced_core::image::Handle::from_rgba(
+            image.width,
+            image.height,
+            image.pixels,
+        ));
+        self.dirty = false;
+        self.built_at = Some(now);
+    }
+
+    /// The cached image, if one has been built.
+    pub fn handle(&self) -> Option<&iced_core::image::Handle> {
+        self.handle.as_ref()
+    }
+}
+
+/// What the overlay draws for one pane's minimap.
+#[derive(Debug, Clone)]
+pub struct MinimapView {
+    pub layout: MinimapLayout,
+    pub image: iced_core::image::Handle,
+    /// Search matches, while a search is open.
+    pub marks: Vec<MatchMark>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PADDING: [f32; 4] = [10.0, 10.0, 10.0, 10.0];
+
+    fn layout(history: usize, rows: usize, offset: usize) -> MinimapLayout {
+        minimap_layout(0.0, 0.0, 800.0, 420.0, PADDING, 80.0, 2.0, history, rows, offset).unwrap()
+    }
+
+    // ── Layout ──────────────────────────────────────────────────
+
+    #[test]
+    fn short_buffer_uses_zoom_line_height() {
+        let l = layout(50, 50, 0);
+        assert_eq!(l.x, 800.0 - 10.0 - SCROLLBAR_GAP - 80.0);
+        assert_eq!(l.height, 200.0);
+        assert_eq!(l.line_height, 2.0);
+        assert_eq!(l.viewport_y, 10.0 + 100.0);
+        assert_eq!(l.viewport_height, 100.0);
+    }
+
+    #[test]
+    fn long_buffer_is_squeezed_to_fit() {
+        let l = layout(3950, 50, 3950);
+        assert_eq!(l.height, 400.0);
+        assert!((l.line_height - 0.1).abs() < 1e-6);
+        assert_eq!(l.viewport_y, 10.0);
+        assert!((l.viewport_height - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn no_layout_for_collapsed_pane() {
+        assert!(minimap_layout(0.0, 0.0, 800.0, 15.0, PADDING, 80.0, 2.0, 10, 5, 0).is_none());
+    }
+
+    // ── Click to jump ───────────────────────────────────────────
+
+    #[test]
+    fn click_centers_viewport_on_line() {
+        let l = layout(150, 50, 0);
+        // Line 40 of 200 at 2px per line
+        assert_eq!(offset_at(&l, l.x + 5.0, 10.0 + 81.0, 150, 50), Some(150 - 15));
+        // Near the top and bottom the offset is clamped
+        assert_eq!(offset_at(&l, l.x + 5.0, 11.0, 150, 50), Some(150));
+        assert_eq!(offset_at(&l, l.x + 5.0, 10.0 + 399.0, 150, 50), Some(0));
+    }
+
+    #[test]
+    fn click_outside_minimap_misses() {
+        let l = layout(150, 50, 0);
+        assert_eq!(offset_at(&l, l.x - 1.0, 100.0, 150, 50), None);
+        assert_eq!(offset_at(&l, l.x + 5.0, 10.0 + l.height + 1.0, 150, 50), None);
+    }
+
+    // ── Match marks ─────────────────────────────────────────────
+
+    #[test]
+    fn marks_sit_at_their_lines() {
+        let l = layout(150, 50, 0);
+        let at = |row| crate::search::SearchMatch { row, start_col: 0, end_row: row, end_col: 1 };
+        let marks = match_marks(&l, 150, &[at(-150), at(-100), at(49)], 1);
+        assert_eq!(marks.len(), 3);
+        assert_eq!(marks[0], MatchMark { y: 10.0, height: 2.0, current: false });
+        assert_eq!(marks[1], MatchMark { y: 110.0, height: 2.0, current: true });
+        assert_eq!(marks[2].y, 10.0 + 398.0);
+    }
+
+    #[test]
+    fn matches_on_one_pixel_row_share_a_mark() {
+        // 4000 lines squeezed into 400px: ten lines per pixel row
+        let l = layout(3950, 50, 0);
+        let at = |row| crate::search::SearchMatch { row, start_col: 0, end_row: row, end_col: 1 };
+        let marks = match_marks(&l, 3950, &[at(-3950), at(-3948), at(-3900)], 1);
+        assert_eq!(marks.len(), 2);
+        assert!(marks[0].current);
+        assert_eq!(marks[0].height, 2.0);
+    }
+
+    // ── Rasterization ───────────────────────────────────────────
+
+    #[test]
+    fn sampled_lines_caps_image_height() {
+        assert_eq!(sampled_lines(3), vec![0, 1, 2]);
+        let sample = sampled_lines(MAX_IMAGE_LINES * 2);
+        assert_eq!(sample.len(), MAX_IMAGE_LINES);
+        assert_eq!(sample[1], 2);
+    }
+
+    #[test]
+    fn rasterize_colors_text_and_backgrounds() {
+        let bg = Color::BLACK;
+        let red = Color::from_rgb(1.0, 0.0, 0.0);
+        let image = rasterize(2, 3, bg, |line| match line {
+            0 => vec![GridCell::new('a', Color::WHITE, bg), GridCell::new(' ', Color::WHITE, bg)],
+            _ => vec![GridCell::new(' ', Color::WHITE, red)],
+        });
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(&image.pixels[0..4], &[204, 204, 204, 255]);
+        assert_eq!(&image.pixels[4..8], &[0, 0, 0, 0]);
+        assert_eq!(&image.pixels[12..16], &[255, 0, 0, 255]);
+        // Cells past the end of a short line stay transparent
+        assert_eq!(&image.pixels[20..24], &[0, 0, 0, 0]);
+    }
+
+    // ── Cache ───────────────────────────────────────────────────
+
+    #[test]
+    fn cache_rebuilds_when_dirty_after_interval() {
+        let now = Instant::now();
+        let mut cache = MinimapCache::default();
+        assert!(cache.needs_rebuild(now));
+        cache.store(rasterize(1, 1, Color::BLACK, |_| Vec::new()), now);
+        assert!(cache.handle().is_some());
+        assert!(!cache.needs_rebuild(now + REBUILD_INTERVAL));
+        cache.mark_dirty();
+        assert!(!cache.needs_rebuild(now));
+        assert!(cache.needs_rebuild(now + REBUILD_INTERVAL));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod atlas_slots;
+pub mod background;
+pub mod color_filter;
 pub mod cursor;
 #[cfg(target_os = "macos")]
 pub mod coretext_rasterizer;
@@ -5,20 +8,29 @@ pub mod damage;
 pub mod glyph_atlas;
 pub mod gpu;
 pub mod grid_renderer;
+pub mod guides;
+pub mod headless;
 pub mod iced_layer;
 pub mod image_renderer;
+pub mod minimap;
+pub mod pacing;
+pub mod soft_wrap;
+pub mod subpixel;
 
 use crate::config::theme::TerminalTheme;
+use crate::config::types::BackgroundConfig;
 use crate::pane::{PaneId, Rect as PaneRect};
-use damage::{DamageState, PaneDamageMap};
+use damage::{DamageState, DamageStats, PaneDamageMap};
 use glyph_atlas::GlyphAtlas;
+use subpixel::Subpixel;
 use gpu::{
     clear_color, create_atlas_sampler, create_atlas_texture, create_bind_group_layout,
-    create_grid_bind_group, create_render_pipeline, GpuError, GridUniforms, SurfaceConfig,
+    create_grid_bind_group, create_render_pipeline, GpuError, GpuPreference, GridUniforms,
+    SurfaceConfig,
 };
 use grid_renderer::{
-    generate_instances, generate_row_instances, generate_test_pattern, row_byte_offset, GridCell,
-    GridDimensions,
+    cell_byte_offset, generate_instances, generate_span_instances, generate_test_pattern,
+    row_has_selection, widen_span_for_wide_chars, GridCell, GridDimensions,
 };
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
@@ -32,8 +44,17 @@ pub struct PaneRenderDescriptor {
     pub rect: PaneRect,
     /// The pane's terminal cells (row-major).
     pub cells: Vec<GridCell>,
+    /// Rows the terminal modified since the last frame, when `cells` are its
+    /// bare grid (no highlights, selection or soft wrap). `None` compares
+    /// every row against the previous frame.
+    pub row_damage: Option<Vec<bool>>,
     /// Optional cursor overlay instance for this pane.
     pub cursor_instance: Option<gpu::CellInstance>,
+    /// Accessibility color filter applied in the fragment shader.
+    pub color_filter: color_filter::ColorFilter,
+    /// Visual magnification of the grid around the content origin (1.0 = none).
+    /// Used for live pinch-zoom previews; cells are scaled, not reflowed.
+    pub zoom: f32,
 }
 
 /// Top-level render coordinator.
@@ -45,7 +66,17 @@ pub struct Renderer {
     queue: wgpu::Queue,
     surface: wgpu::Surface<'static>,
     surface_config: SurfaceConfig,
+    /// Present modes the surface accepts (Fifo is always among them).
+    present_modes: Vec<wgpu::PresentMode>,
+    /// Composite alpha modes the surface accepts.
+    alpha_modes: Vec<wgpu::CompositeAlphaMode>,
+    /// Background opacity (`window.opacity`).
+    opacity: f32,
     render_pipeline: wgpu::RenderPipeline,
+    /// Grid pipeline blending cells over the background picture.
+    layered_pipeline: wgpu::RenderPipeline,
+    /// Picture drawn beneath the panes (`colors.background`).
+    background: Option<background::BackgroundLayer>,
     bind_group: wgpu::BindGroup,
     instance_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
@@ -61,8 +92,10 @@ pub struct Renderer {
     _sampler: wgpu::Sampler,
     /// Terminal content padding in physical pixels (top, bottom, left, right).
     padding: [f32; 4],
-    /// DPI scale factor used for atlas creation.
+    /// Detected DPI scale factor.
     scale_factor: f32,
+    /// Scale the glyph atlas is rasterized at instead of `scale_factor`.
+    font_scale: Option<f32>,
     /// Minimum uniform buffer offset alignment (from device limits).
     uniform_align: u64,
     /// iced UI layer for widget rendering (composited on top of custom pipeline).
@@ -72,12 +105,17 @@ pub struct Renderer {
 impl Renderer {
     /// Initialize the renderer with a window, theme, and font configuration.
     /// Creates GPU context, glyph atlas, grid, and all render resources.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         window: Arc<Window>,
         theme: TerminalTheme,
         font_size: f32,
         font_family: &str,
         line_height_multiplier: f32,
+        ligatures: bool,
+        subpixel: Subpixel,
+        font_scale: Option<f32>,
+        gpu: &GpuPreference,
     ) -> Result<Self, GpuError> {
         let size = window.inner_size();
         let winit_scale = window.scale_factor();
@@ -96,14 +134,37 @@ impl Renderer {
             .create_surface(window)
             .map_err(|_| GpuError::AdapterNotFound)?;
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .map_err(|_| GpuError::AdapterNotFound)?;
+        // A named adapter is matched among those that can drive the surface;
+        // otherwise (or if none matches) wgpu picks by power preference.
+        let named = match gpu {
+            GpuPreference::Named(needle) => {
+                let mut adapters: Vec<wgpu::Adapter> = instance
+                    .enumerate_adapters(wgpu::Backends::all())
+                    .into_iter()
+                    .filter(|a| a.is_surface_supported(&surface))
+                    .collect();
+                let names: Vec<String> = adapters.iter().map(|a| a.get_info().name).collect();
+                match gpu::match_adapter_name(&names, needle) {
+                    Some(index) => Some(adapters.swap_remove(index)),
+                    None => {
+                        log::warn!("No GPU adapter matches '{needle}', using the default");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let adapter = match named {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: gpu.power_preference(),
+                    force_fallback_adapter: false,
+                    compatible_surface: Some(&surface),
+                })
+                .await
+                .map_err(|_| GpuError::AdapterNotFound)?,
+        };
 
         let info = adapter.get_info();
         log::info!(
@@ -148,11 +209,22 @@ impl Renderer {
         let clamped_width = size.width.min(max_dim).max(1);
         let clamped_height = size.height.min(max_dim).max(1);
 
+        let present_modes = surface_caps.present_modes.clone();
+        let alpha_modes = surface_caps.alpha_modes.clone();
         let surface_config = SurfaceConfig::new(clamped_width, clamped_height, format);
         surface.configure(&device, &surface_config.to_wgpu_config());
 
         // Glyph atlas
-        let atlas = GlyphAtlas::new(font_size, scale_factor, font_family, line_height_multiplier);
+        let mut atlas = GlyphAtlas::with_subpixel(
+            font_size,
+            font_scale.unwrap_or(scale_factor),
+            font_family,
+            line_height_multiplier,
+            subpixel,
+        );
+        if ligatures {
+            atlas.load_ligatures(font_family);
+        }
         log::info!(
             "Glyph atlas: {}x{} (cell: {:.1}x{:.1})",
             atlas.atlas_width,
@@ -170,6 +242,8 @@ impl Renderer {
             &atlas.atlas_data,
             atlas.bytes_per_pixel,
         );
+        // Uploaded whole, ligatures included
+        atlas.take_changes();
         let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = create_atlas_sampler(&device);
 
@@ -193,6 +267,8 @@ impl Renderer {
             grid_size: grid.grid_size(),
             atlas_size: [atlas.atlas_width as f32, atlas.atlas_height as f32],
             flags: [atlas_rgba, atlas.cursor_height_ratio],
+            color_filter: color_filter::ColorFilter::None.uniform_rows(),
+            background: gpu::background_uniform(1.0, surface_config.alpha_mode, false),
         };
         let mut uniform_data = vec![0u8; uniform_buffer_size as usize];
         uniform_data[..std::mem::size_of::<GridUniforms>()].copy_from_slice(bytemuck::bytes_of(&uniforms));
@@ -211,7 +287,14 @@ impl Renderer {
 
         // Render pipeline
         let bind_group_layout = create_bind_group_layout(&device);
-        let render_pipeline = create_render_pipeline(&device, format, &bind_group_layout);
+        let render_pipeline =
+            create_render_pipeline(&device, format, &bind_group_layout, wgpu::BlendState::REPLACE);
+        let layered_pipeline = create_render_pipeline(
+            &device,
+            format,
+            &bind_group_layout,
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
         let bind_group = create_grid_bind_group(
             &device,
             &bind_group_layout,
@@ -241,7 +324,12 @@ impl Renderer {
             queue,
             surface,
             surface_config,
+            present_modes,
+            alpha_modes,
+            opacity: 1.0,
             render_pipeline,
+            layered_pipeline,
+            background: None,
             bind_group,
             instance_buffer,
             uniform_buffer,
@@ -257,11 +345,105 @@ impl Renderer {
             _sampler: sampler,
             padding: [0.0; 4],
             scale_factor,
+            font_scale,
             uniform_align,
             iced,
         })
     }
 
+    /// Reconfigure the surface for `renderer.present_mode` / `renderer.low_latency`.
+    /// Unsupported modes fall back to Fifo. Returns the mode actually used.
+    pub fn set_present_mode(
+        &mut self,
+        requested: wgpu::PresentMode,
+        low_latency: bool,
+    ) -> wgpu::PresentMode {
+        let mode = gpu::choose_present_mode(requested, &self.present_modes);
+        if mode != requested {
+            log::warn!("Present mode {requested:?} not supported, using {mode:?}");
+        }
+        self.surface_config = self
+            .surface_config
+            .clone()
+            .with_present_mode(mode, low_latency);
+        self.surface
+            .configure(&self.device, &self.surface_config.to_wgpu_config());
+        mode
+    }
+
+    /// Draw the background at `opacity` (`window.opacity`), reconfiguring
+    /// the surface to composite with alpha below 1.0. Returns the alpha
+    /// mode used; Auto means the compositor may ignore the alpha.
+    pub fn set_opacity(&mut self, opacity: f32) -> wgpu::CompositeAlphaMode {
+        let opacity = opacity.clamp(0.0, 1.0);
+        let transparent = opacity < 1.0;
+        let mode = gpu::choose_alpha_mode(transparent, &self.alpha_modes);
+        if transparent && mode == wgpu::CompositeAlphaMode::Auto {
+            log::warn!(
+                "Surface has no alpha compositing (supports {:?}); window.opacity may have no effect",
+                self.alpha_modes
+            );
+        }
+        self.opacity = opacity;
+        if mode != self.surface_config.alpha_mode {
+            self.surface_config = self.surface_config.clone().with_alpha_mode(mode);
+            self.surface
+                .configure(&self.device, &self.surface_config.to_wgpu_config());
+        }
+        mode
+    }
+
+    /// The `background` grid uniform for the current opacity and surface.
+    fn background_uniform(&self) -> [f32; 4] {
+        gpu::background_uniform(self.opacity, self.surface_config.alpha_mode, self.background.is_some())
+    }
+
+    /// Draw `config`'s image or gradient beneath the panes, or none. An
+    /// image that fails to load is logged and leaves no picture.
+    pub fn set_background(&mut self, config: &BackgroundConfig) {
+        let max_dim = self.device.limits().max_texture_dimension_2d;
+        self.background = match background::Picture::from_config(config, max_dim) {
+            Ok(picture) => picture.map(|picture| {
+                background::BackgroundLayer::new(
+                    &self.device,
+                    &self.queue,
+                    self.surface_config.format,
+                    picture,
+                    background::Fit::from_config_str(&config.fit).unwrap_or_default(),
+                    config.dim as f32,
+                )
+            }),
+            Err(e) => {
+                log::warn!("Failed to load background image {e}");
+                None
+            }
+        };
+    }
+
+    /// Draw the background picture, if any, at the start of `pass`.
+    fn draw_background(&self, pass: &mut wgpu::RenderPass<'_>) {
+        if let Some(layer) = &self.background {
+            layer.prepare(
+                &self.queue,
+                (self.surface_config.width, self.surface_config.height),
+                &self.theme,
+                self.opacity,
+                self.surface_config.alpha_mode,
+            );
+            layer.draw(pass);
+        }
+    }
+
+    /// The grid pipeline for this frame: blending over a background
+    /// picture, or replacing the clear color.
+    fn pane_pipeline(&self) -> &wgpu::RenderPipeline {
+        if self.background.is_some() {
+            &self.layered_pipeline
+        } else {
+            &self.render_pipeline
+        }
+    }
+
     /// Set terminal content padding in physical pixels [top, bottom, left, right].
     pub fn set_padding(&mut self, top: f32, bottom: f32, left: f32, right: f32) {
         self.padding = [top, bottom, left, right];
@@ -277,10 +459,38 @@ impl Renderer {
         self.scale_factor
     }
 
+    /// Scale the glyph atlas is rasterized at: the font scale override, else
+    /// the detected one.
+    pub fn font_scale_factor(&self) -> f32 {
+        self.font_scale.unwrap_or(self.scale_factor)
+    }
+
+    /// Rasterize glyphs at `scale` instead of the detected scale factor (None
+    /// goes back to it). Takes effect at the next `rebuild_atlas`.
+    pub fn set_font_scale(&mut self, scale: Option<f32>) {
+        self.font_scale = scale;
+    }
+
     /// Rebuild the glyph atlas with new font parameters and update all dependent GPU resources.
-    /// Call after font size, family, or line_height changes.
-    pub fn rebuild_atlas(&mut self, font_size: f32, font_family: &str, line_height_multiplier: f32) {
-        let atlas = GlyphAtlas::new(font_size, self.scale_factor, font_family, line_height_multiplier);
+    /// Call after font size, family, line_height, ligature or antialiasing changes.
+    pub fn rebuild_atlas(
+        &mut self,
+        font_size: f32,
+        font_family: &str,
+        line_height_multiplier: f32,
+        ligatures: bool,
+        subpixel: Subpixel,
+    ) {
+        let mut atlas = GlyphAtlas::with_subpixel(
+            font_size,
+            self.font_scale_factor(),
+            font_family,
+            line_height_multiplier,
+            subpixel,
+        );
+        if ligatures {
+            atlas.load_ligatures(font_family);
+        }
         log::info!(
             "Atlas rebuilt: {}x{} (cell: {:.1}x{:.1})",
             atlas.atlas_width,
@@ -298,6 +508,8 @@ impl Renderer {
             &atlas.atlas_data,
             atlas.bytes_per_pixel,
         );
+        // Uploaded whole, ligatures included
+        atlas.take_changes();
         let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = create_atlas_sampler(&self.device);
 
@@ -325,6 +537,8 @@ impl Renderer {
             grid_size: grid.grid_size(),
             atlas_size: [atlas.atlas_width as f32, atlas.atlas_height as f32],
             flags: [atlas_rgba, self.atlas.cursor_height_ratio],
+            color_filter: color_filter::ColorFilter::None.uniform_rows(),
+            background: self.background_uniform(),
         };
         self.queue
             .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
@@ -338,6 +552,59 @@ impl Renderer {
         self.pane_damage.force_full_damage_all();
     }
 
+    /// Upload what on-demand rasterization changed in the atlas: the changed
+    /// rows, or a new texture when the atlas grew. Cached pane instances
+    /// are rebuilt when glyphs moved.
+    fn sync_atlas_texture(&mut self) {
+        let changes = self.atlas.take_changes();
+        let bpp = self.atlas.bytes_per_pixel;
+        let width = self.atlas.atlas_width;
+        if changes.resized {
+            let atlas_texture = create_atlas_texture(
+                &self.device,
+                &self.queue,
+                width,
+                self.atlas.atlas_height,
+                &self.atlas.atlas_data,
+                bpp,
+            );
+            let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.bind_group = create_grid_bind_group(
+                &self.device,
+                &self._bind_group_layout,
+                &self.uniform_buffer,
+                &atlas_view,
+                &self._sampler,
+            );
+            self.atlas_texture = atlas_texture;
+            self._atlas_view = atlas_view;
+        } else if let Some(rows) = changes.rows {
+            let row_bytes = (width * bpp) as usize;
+            self.queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.atlas_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: 0, y: rows.start, z: 0 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &self.atlas.atlas_data[rows.start as usize * row_bytes..rows.end as usize * row_bytes],
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * bpp),
+                    rows_per_image: Some(rows.len() as u32),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height: rows.len() as u32,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        if changes.remapped {
+            self.pane_damage.force_full_damage_all();
+        }
+    }
+
     /// Render a frame: acquire surface texture, draw instances, present.
     pub fn render_frame(&self) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
@@ -358,7 +625,7 @@ impl Renderer {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(clear_color(&self.theme)),
+                        load: wgpu::LoadOp::Clear(clear_color(&self.theme, self.opacity as f64, self.surface_config.alpha_mode)),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
@@ -391,7 +658,8 @@ impl Renderer {
         let height = height.min(max_dim);
 
         // Reconfigure surface
-        self.surface_config = SurfaceConfig::new(width, height, self.surface_config.format);
+        self.surface_config.width = width;
+        self.surface_config.height = height;
         self.surface
             .configure(&self.device, &self.surface_config.to_wgpu_config());
 
@@ -422,6 +690,8 @@ impl Renderer {
                 self.atlas.atlas_height as f32,
             ],
             flags: [atlas_rgba, self.atlas.cursor_height_ratio],
+            color_filter: color_filter::ColorFilter::None.uniform_rows(),
+            background: self.background_uniform(),
         };
         self.queue
             .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
@@ -446,19 +716,19 @@ impl Renderer {
 
     /// Update the rendered cells from external terminal state.
     ///
-    /// Uses damage tracking to only update GPU buffer rows that changed.
+    /// Uses damage tracking to only update the GPU buffer cells that changed.
     /// Falls back to full buffer write on first frame, after resize, or
     /// when force_full_damage() was called.
     pub fn update_cells(&mut self, cells: &[GridCell]) {
-        let dirty = self.damage_state.process_frame(cells);
+        let dirty = self.damage_state.process_frame_spans(cells);
         let cols = self.grid.columns as usize;
 
-        let any_dirty = dirty.iter().any(|&d| d);
+        let any_dirty = dirty.iter().any(Option::is_some);
         if !any_dirty {
             return;
         }
 
-        let all_dirty = dirty.iter().all(|&d| d);
+        let all_dirty = dirty.iter().all(|span| span.as_ref() == Some(&(0..cols)));
         if all_dirty {
             // Full update: regenerate all instances and write entire buffer
             let instances = generate_instances(&self.grid, cells, &self.atlas);
@@ -466,18 +736,21 @@ impl Renderer {
             self.queue
                 .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
         } else {
-            // Partial update: only write dirty rows
-            for (row, &is_dirty) in dirty.iter().enumerate() {
-                if is_dirty {
-                    let row_instances =
-                        generate_row_instances(&self.grid, cells, &self.atlas, row as u32);
-                    let offset = row_byte_offset(row, cols);
-                    self.queue.write_buffer(
-                        &self.instance_buffer,
-                        offset,
-                        bytemuck::cast_slice(&row_instances),
-                    );
+            // Partial update: only write the dirty span of each dirty row
+            for (row, span) in dirty.into_iter().enumerate() {
+                let Some(mut span) = span else { continue };
+                if row_has_selection(cells, cols, row) || self.atlas.has_ligatures() {
+                    span = 0..cols;
                 }
+                let span = widen_span_for_wide_chars(cells, cols, row, span);
+                let offset = cell_byte_offset(row, span.start, cols);
+                let span_instances =
+                    generate_span_instances(&self.grid, cells, &self.atlas, row as u32, span);
+                self.queue.write_buffer(
+                    &self.instance_buffer,
+                    offset,
+                    bytemuck::cast_slice(&span_instances),
+                );
             }
         }
     }
@@ -518,6 +791,16 @@ impl Renderer {
         self.pane_damage.remove(pane_id);
     }
 
+    /// Whether the last frame redrew changed cells of a pane.
+    pub fn pane_damaged(&self, pane_id: PaneId) -> bool {
+        self.pane_damage.was_damaged(pane_id)
+    }
+
+    /// Size of the per-pane damage caches.
+    pub fn damage_stats(&self) -> DamageStats {
+        self.pane_damage.stats()
+    }
+
     /// Update the DPI scale factor (e.g. when window moves between displays).
     pub fn update_scale_factor(&mut self, scale: f32) {
         if (self.scale_factor - scale).abs() < 0.01 {
@@ -544,51 +827,34 @@ impl Renderer {
     ) -> Result<(wgpu::SurfaceTexture, Vec<iced_layer::UiMessage>), wgpu::SurfaceError> {
         // Process damage and prepare per-pane instance data
         struct PaneDrawData {
+            pane_id: PaneId,
             rect: PaneRect,
             grid: GridDimensions,
-            instances: Vec<gpu::CellInstance>,
+            /// Instances to draw from the pane's buffer (cells, then cursor).
+            count: u32,
+            color_filter: color_filter::ColorFilter,
+            zoom: f32,
         }
 
         let mut draw_data: Vec<PaneDrawData> = Vec::with_capacity(panes.len());
+        self.pane_damage.begin_frame();
 
         let [pad_top, pad_bottom, pad_left, pad_right] = self.padding;
         let _multi_pane = panes.len() > 1;
         let cw = self.cell_width();
         let ch = self.cell_height();
 
-        // Pre-scan: rasterize any unknown characters on demand before generating instances.
-        // This mutably borrows self.atlas once, then the generated glyphs are available
-        // for the immutable glyph_info() calls in generate_instances().
+        // Pre-scan: rasterize any unknown characters on demand before generating instances,
+        // and mark the visible ones used so the atlas evicts others first. This mutably
+        // borrows self.atlas once, then the generated glyphs are available for the
+        // immutable glyph_info() calls in generate_instances().
+        self.atlas.begin_frame();
         for pane in panes.iter() {
             for cell in &pane.cells {
-                if cell.ch != ' ' && self.atlas.glyph_info(cell.ch).is_none() {
-                    self.atlas.rasterize_on_demand(cell.ch);
-                }
+                self.atlas.prepare(cell.ch, cell.flags & grid_renderer::CELL_FLAG_WIDE != 0);
             }
         }
-        // If atlas was modified by on-demand rasterization, re-upload to GPU
-        if self.atlas.atlas_dirty {
-            self.queue.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: &self.atlas_texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                &self.atlas.atlas_data,
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(self.atlas.atlas_width * self.atlas.bytes_per_pixel),
-                    rows_per_image: Some(self.atlas.atlas_height),
-                },
-                wgpu::Extent3d {
-                    width: self.atlas.atlas_width,
-                    height: self.atlas.atlas_height,
-                    depth_or_array_layers: 1,
-                },
-            );
-            self.atlas.atlas_dirty = false;
-        }
+        self.sync_atlas_texture();
 
         for pane in panes.iter_mut() {
             // Compute content area: pane rect inset by padding
@@ -619,42 +885,80 @@ impl Renderer {
                 );
             }
 
-            // Get or create damage state for this pane
+            // Get or create damage state for this pane (reset if the grid changed size)
             let damage_state = self.pane_damage.get_or_create(pane.pane_id, cols);
+            let dirty = damage_state.process_frame_fed(&pane.cells, pane.row_damage.as_deref());
 
-            // If pane grid changed size, resize damage
-            if damage_state.cols != cols {
-                damage_state.resize(cols);
-            }
-
-            let dirty = damage_state.process_frame(&pane.cells);
-            let any_dirty = dirty.iter().any(|&d| d);
-
-            // Always regenerate instances: the render pass clears the surface every
+            // Always draw every instance: the render pass clears the surface every
             // frame, so we can never rely on the previous frame's content surviving.
-            // Damage tracking would cause flashing (clear wipes grid, iced body is
-            // transparent → terminal content disappears on "clean" frames).
-            let force_redraw = true;
-            let mut instances = if any_dirty || force_redraw {
-                generate_instances(&pane_grid, &pane.cells, &self.atlas)
+            // Damage tracking only decides which cached instances to rebuild.
+            let total = pane_grid.total_cells() as usize;
+            let cached = damage_state.instances_mut();
+            let full = cached.len() != total
+                || dirty.iter().all(|span| span.as_ref() == Some(&(0..cols)));
+            // Instance ranges whose GPU copy is out of date
+            let mut uploads = Vec::new();
+            if full {
+                *cached = generate_instances(&pane_grid, &pane.cells, &self.atlas);
+                uploads.push(0..cached.len());
             } else {
-                Vec::new()
-            };
+                for (row, span) in dirty.into_iter().enumerate() {
+                    let Some(mut span) = span else { continue };
+                    if row >= pane_grid.rows as usize {
+                        break;
+                    }
+                    if row_has_selection(&pane.cells, cols, row) || self.atlas.has_ligatures() {
+                        span = 0..cols;
+                    }
+                    let span = widen_span_for_wide_chars(&pane.cells, cols, row, span);
+                    let base = row * cols;
+                    let patch = generate_span_instances(
+                        &pane_grid, &pane.cells, &self.atlas, row as u32, span.clone(),
+                    );
+                    let range = base + span.start..base + span.start + patch.len();
+                    cached[range.clone()].copy_from_slice(&patch);
+                    uploads.push(range);
+                }
+            }
 
-            // Append cursor overlay instance
-            if let Some(cursor_inst) = &pane.cursor_instance {
-                instances.push(*cursor_inst);
+            // Keep the pane's GPU buffer in step: only changed spans and a
+            // moved cursor are written, into the slot after the cells
+            let cell_count = cached.len();
+            if damage_state.ensure_buffer(&self.device, cell_count + 1) {
+                uploads.clear();
+                uploads.push(0..cell_count);
+            }
+            let instance_size = std::mem::size_of::<gpu::CellInstance>();
+            let buffer = damage_state.buffer().expect("pane buffer ensured above");
+            for range in uploads.into_iter().filter(|range| !range.is_empty()) {
+                self.queue.write_buffer(
+                    buffer,
+                    (range.start * instance_size) as u64,
+                    bytemuck::cast_slice(&damage_state.instances()[range]),
+                );
+            }
+            if damage_state.update_cursor(pane.cursor_instance) {
+                if let (Some(cursor), Some(buffer)) = (&pane.cursor_instance, damage_state.buffer()) {
+                    self.queue.write_buffer(
+                        buffer,
+                        (cell_count * instance_size) as u64,
+                        bytemuck::bytes_of(cursor),
+                    );
+                }
             }
 
             draw_data.push(PaneDrawData {
+                pane_id: pane.pane_id,
                 rect: pane.rect,
                 grid: pane_grid,
-                instances,
+                count: (cell_count + usize::from(pane.cursor_instance.is_some())) as u32,
+                color_filter: pane.color_filter,
+                zoom: pane.zoom,
             });
         }
 
         // Compute total pane instances
-        let total_pane_instances: usize = draw_data.iter().map(|d| d.instances.len()).sum();
+        let total_pane_instances: usize = draw_data.iter().map(|d| d.count as usize).sum();
 
         // Get surface texture (even if nothing to render, we need it for present)
         let output = self.surface.get_current_texture()?;
@@ -668,13 +972,13 @@ impl Renderer {
                 &wgpu::CommandEncoderDescriptor { label: Some("Clear Encoder") },
             );
             {
-                let _pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     label: Some("Clear Pass"),
                     color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                         view: &view,
                         resolve_target: None,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(clear_color(&self.theme)),
+                            load: wgpu::LoadOp::Clear(clear_color(&self.theme, self.opacity as f64, self.surface_config.alpha_mode)),
                             store: wgpu::StoreOp::Store,
                         },
                         depth_slice: None,
@@ -683,61 +987,21 @@ impl Renderer {
                     timestamp_writes: None,
                     occlusion_query_set: None,
                 });
+                self.draw_background(&mut pass);
             }
             self.queue.submit(Some(encoder.finish()));
             let iced_messages = self.iced.render(&view, ui_state);
             return Ok((output, iced_messages));
         }
 
-        // Build a single combined instance buffer with all pane data
-        let mut all_instances: Vec<gpu::CellInstance> =
-            Vec::with_capacity(total_pane_instances);
-        let mut pane_ranges: Vec<(PaneRect, GridDimensions, u32, u32)> = Vec::new();
-
-        for data in &draw_data {
-            let start = all_instances.len() as u32;
-            if !data.instances.is_empty() {
-                all_instances.extend_from_slice(&data.instances);
-            }
-            let count = if !data.instances.is_empty() {
-                data.instances.len() as u32
-            } else {
-                0
-            };
-            pane_ranges.push((data.rect, data.grid.clone(), start, count));
-        }
-
-        // Upload instance buffer
-        if !all_instances.is_empty() {
-            let buffer_size =
-                (all_instances.len() * std::mem::size_of::<gpu::CellInstance>()) as u64;
-            let current_size = self.instance_buffer.size();
-
-            if buffer_size > current_size {
-                self.instance_buffer =
-                    self.device
-                        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                            label: Some("Cell Instances"),
-                            contents: bytemuck::cast_slice(&all_instances),
-                            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                        });
-            } else {
-                self.queue.write_buffer(
-                    &self.instance_buffer,
-                    0,
-                    bytemuck::cast_slice(&all_instances),
-                );
-            }
-            self.instance_count = all_instances.len() as u32;
-        }
-
         // Upload all pane uniforms to the dynamic uniform buffer BEFORE encoding
         // the render pass. Each pane's uniforms go at a different aligned offset.
         // This prevents the old bug where queue.write_buffer inside the render pass
         // loop would overwrite earlier panes' data (GPU only saw the last write).
         let align = self.uniform_align;
         let atlas_rgba = if self.atlas.bytes_per_pixel == 4 { 1.0 } else { 0.0 };
-        for (i, (_rect, grid, _start, _count)) in pane_ranges.iter().enumerate() {
+        for (i, data) in draw_data.iter().enumerate() {
+            let grid = &data.grid;
             let uniforms = GridUniforms {
                 cell_size: grid.cell_size_ndc(),
                 grid_size: grid.grid_size(),
@@ -746,6 +1010,8 @@ impl Renderer {
                     self.atlas.atlas_height as f32,
                 ],
                 flags: [atlas_rgba, self.atlas.cursor_height_ratio],
+                color_filter: data.color_filter.uniform_rows(),
+                background: self.background_uniform(),
             };
             let offset = i as u64 * align;
             self.queue
@@ -770,7 +1036,7 @@ impl Renderer {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(clear_color(&self.theme)),
+                        load: wgpu::LoadOp::Clear(clear_color(&self.theme, self.opacity as f64, self.surface_config.alpha_mode)),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
@@ -780,13 +1046,16 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+            self.draw_background(&mut render_pass);
+            render_pass.set_pipeline(self.pane_pipeline());
+            let max_viewport = self.device.limits().max_texture_dimension_2d as f32;
 
-            for (i, (rect, grid, start, count)) in pane_ranges.iter().enumerate() {
-                if *count == 0 {
+            for (i, data) in draw_data.iter().enumerate() {
+                let Some(buffer) = self.pane_damage.buffer(data.pane_id).filter(|_| data.count > 0) else {
                     continue;
-                }
+                };
+                let (rect, grid) = (&data.rect, &data.grid);
+                render_pass.set_vertex_buffer(0, buffer.slice(..));
 
                 // Use dynamic offset to select this pane's uniforms
                 let dynamic_offset = (i as u64 * align) as u32;
@@ -814,10 +1083,13 @@ impl Renderer {
                 let sy = cy.max(0.0) as u32;
                 let sw = (vp_w as u32).min(self.surface_config.width.saturating_sub(sx));
                 let sh = (vp_h as u32).min(self.surface_config.height.saturating_sub(sy));
+                // Pinch preview: stretch the viewport, keep the scissor on the
+                // content area so magnified text is clipped to the pane.
+                let (vp_w, vp_h) = zoomed_viewport(vp_w, vp_h, data.zoom, max_viewport);
                 if sw > 0 && sh > 0 {
                     render_pass.set_viewport(cx, cy, vp_w, vp_h, 0.0, 1.0);
                     render_pass.set_scissor_rect(sx, sy, sw, sh);
-                    render_pass.draw(0..6, *start..*start + *count);
+                    render_pass.draw(0..6, 0..data.count);
                 }
             }
 
@@ -833,14 +1105,9 @@ impl Renderer {
         Ok((output, iced_messages))
     }
 
-    /// Capture a screenshot of the surface texture to PNG.
+    /// Read the surface texture back as an RGBA image (for screenshots).
     /// Call this AFTER render_panes() but BEFORE calling present() on the texture.
-    pub fn capture_screenshot(
-        &self,
-        surface_texture: &wgpu::Texture,
-        path: &std::path::Path,
-    ) -> anyhow::Result<()> {
-        use image::{ImageBuffer, Rgba};
+    pub fn capture_image(&self, surface_texture: &wgpu::Texture) -> anyhow::Result<image::RgbaImage> {
 
         let width = self.surface_config.width;
         let height = self.surface_config.height;
@@ -924,14 +1191,8 @@ impl Renderer {
         drop(data);
         output_buffer.unmap();
 
-        // Save as PNG
-        let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
-            ImageBuffer::from_raw(width, height, pixels)
-                .ok_or_else(|| anyhow::anyhow!("Failed to create image buffer"))?;
-        img.save(path)?;
-
-        log::info!("✓ Screenshot saved: {}", path.display());
-        Ok(())
+        image::RgbaImage::from_raw(width, height, pixels)
+            .ok_or_else(|| anyhow::anyhow!("Failed to create image buffer"))
     }
 
     /// Compute scissor rect parameters for a pane rect's content area within the surface.
@@ -948,6 +1209,13 @@ impl Renderer {
     }
 }
 
+/// Viewport size for a pane magnified by `zoom`, capped at the device's
+/// maximum viewport dimension.
+fn zoomed_viewport(width: f32, height: f32, zoom: f32, max: f32) -> (f32, f32) {
+    let zoom = if zoom.is_finite() && zoom > 0.0 { zoom } else { 1.0 };
+    ((width * zoom).min(max), (height * zoom).min(max))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -978,6 +1246,8 @@ mod tests {
             grid_size: grid.grid_size(),
             atlas_size: [atlas.atlas_width as f32, atlas.atlas_height as f32],
             flags: [0.0, 0.0],
+            color_filter: color_filter::ColorFilter::None.uniform_rows(),
+            background: gpu::background_uniform(1.0, wgpu::CompositeAlphaMode::Auto, false),
         };
 
         assert_eq!(uniforms.grid_size[0], grid.columns as f32);
@@ -1040,6 +1310,8 @@ mod tests {
             grid_size: grid.grid_size(),
             atlas_size: [atlas.atlas_width as f32, atlas.atlas_height as f32],
             flags: [0.0, 0.0],
+            color_filter: color_filter::ColorFilter::None.uniform_rows(),
+            background: gpu::background_uniform(1.0, wgpu::CompositeAlphaMode::Auto, false),
         };
         let uniform_buffer = ctx
             .device
@@ -1062,6 +1334,7 @@ mod tests {
             &ctx.device,
             wgpu::TextureFormat::Bgra8UnormSrgb,
             &bind_group_layout,
+            wgpu::BlendState::REPLACE,
         );
         let _bind_group = create_grid_bind_group(
             &ctx.device,
@@ -1291,4 +1564,19 @@ mod tests {
         assert_eq!(grid.columns, 1);
         assert_eq!(grid.rows, 1);
     }
+
+    // ── Pinch zoom viewport ────────────────────────────────────────
+
+    #[test]
+    fn zoomed_viewport_scales_both_axes() {
+        assert_eq!(zoomed_viewport(800.0, 600.0, 1.5, 8192.0), (1200.0, 900.0));
+        assert_eq!(zoomed_viewport(800.0, 600.0, 1.0, 8192.0), (800.0, 600.0));
+    }
+
+    #[test]
+    fn zoomed_viewport_caps_and_ignores_bad_zoom() {
+        assert_eq!(zoomed_viewport(800.0, 600.0, 20.0, 8192.0), (8192.0, 8192.0));
+        assert_eq!(zoomed_viewport(800.0, 600.0, 0.0, 8192.0), (800.0, 600.0));
+        assert_eq!(zoomed_viewport(800.0, 600.0, f32::NAN, 8192.0), (800.0, 600.0));
+    }
 }
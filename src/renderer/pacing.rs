@@ -0,0 +1,197 @@
+// Frame pacing: the window draws at most once per monitor refresh, skips
+// frames in which nothing changed and nothing is animating, and stops
+// drawing while it is occluded or minimized. The rest of the frame loop
+// (reading shell output, timers) keeps ticking, slowly while hidden, so a
+// busy shell never blocks on a full pipe for long.
+
+use std::time::{Duration, Instant};
+
+/// Refresh rate assumed when the monitor doesn't report one.
+pub const DEFAULT_REFRESH_MILLIHERTZ: u32 = 60_000;
+
+/// How often a hidden window reads its shells' output.
+pub const HIDDEN_POLL: Duration = Duration::from_millis(250);
+
+/// Longest a visible window goes without drawing, so elapsed times and
+/// other clock-driven chrome keep moving.
+pub const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Decides which ticks of the frame loop draw.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    /// One monitor refresh.
+    interval: Duration,
+    last_frame: Option<Instant>,
+    /// Something changed since the last drawn frame.
+    dirty: bool,
+    /// The last drawn frame was animating, so the next one draws the end
+    /// state.
+    animating: bool,
+    /// Draw the next due frame without waiting out the refresh interval.
+    immediate: bool,
+    occluded: bool,
+    minimized: bool,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self {
+            interval: interval(DEFAULT_REFRESH_MILLIHERTZ),
+            last_frame: None,
+            dirty: true,
+            animating: false,
+            immediate: false,
+            occluded: false,
+            minimized: false,
+        }
+    }
+
+    /// Pace frames to a monitor's refresh rate, in millihertz as winit
+    /// reports it (None or 0 = 60 Hz).
+    pub fn set_refresh_rate(&mut self, millihertz: Option<u32>) {
+        self.interval = interval(millihertz.filter(|&mhz| mhz > 0).unwrap_or(DEFAULT_REFRESH_MILLIHERTZ));
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Note a change that needs drawing: input, shell output, a resize.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Let the next frame skip the refresh cap (low-latency key echo).
+    pub fn request_immediate(&mut self) {
+        self.immediate = true;
+    }
+
+    pub fn set_occluded(&mut self, occluded: bool) {
+        self.occluded = occluded;
+        self.dirty = true;
+    }
+
+    /// Window minimized or hidden (quick terminal).
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.minimized = minimized;
+        self.dirty = true;
+    }
+
+    /// Whether nothing of the window can be seen.
+    pub fn is_hidden(&self) -> bool {
+        self.occluded || self.minimized
+    }
+
+    /// Whether this tick draws. `animating` is true while something moves
+    /// on its own (smooth scrolling, a flash, a scan in progress). Marks
+    /// the frame drawn when it returns true.
+    pub fn frame_due(&mut self, now: Instant, animating: bool) -> bool {
+        if self.is_hidden() {
+            return false;
+        }
+        let since = self.last_frame.map(|last| now.saturating_duration_since(last));
+        let wanted = self.dirty || animating || self.animating || since.is_none_or(|since| since >= HEARTBEAT);
+        // A timer firing a little early still counts as the next refresh
+        let capped = !self.immediate && since.is_some_and(|since| since + self.interval / 4 < self.interval);
+        if !wanted {
+            return false;
+        }
+        if capped {
+            self.dirty = true;
+            return false;
+        }
+        self.last_frame = Some(now);
+        self.dirty = false;
+        self.animating = animating;
+        self.immediate = false;
+        true
+    }
+
+    /// When the frame loop should tick next.
+    pub fn next_tick(&self, now: Instant) -> Instant {
+        if self.is_hidden() {
+            return now + HIDDEN_POLL;
+        }
+        self.last_frame
+            .map(|last| last + self.interval)
+            .filter(|&next| next > now)
+            .unwrap_or(now + self.interval)
+    }
+}
+
+fn interval(millihertz: u32) -> Duration {
+    Duration::from_secs_f64(1000.0 / millihertz as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn(pacer: &mut FramePacer) -> Instant {
+        let now = Instant::now();
+        assert!(pacer.frame_due(now, false));
+        now
+    }
+
+    #[test]
+    fn idle_frames_are_skipped_until_the_heartbeat() {
+        let mut pacer = FramePacer::new();
+        let start = drawn(&mut pacer);
+        let tick = start + pacer.interval() * 2;
+        assert!(!pacer.frame_due(tick, false));
+        assert!(pacer.frame_due(start + HEARTBEAT, false));
+    }
+
+    #[test]
+    fn changes_draw_at_most_once_per_refresh() {
+        let mut pacer = FramePacer::new();
+        pacer.set_refresh_rate(Some(120_000));
+        let start = drawn(&mut pacer);
+        pacer.mark_dirty();
+        assert!(!pacer.frame_due(start + Duration::from_millis(1), false));
+        // Still pending at the next refresh
+        assert!(pacer.frame_due(start + pacer.interval(), false));
+        assert_eq!(pacer.next_tick(start + pacer.interval()), start + pacer.interval() * 2);
+    }
+
+    #[test]
+    fn animation_draws_its_end_state() {
+        let mut pacer = FramePacer::new();
+        let start = drawn(&mut pacer);
+        let step = pacer.interval();
+        assert!(pacer.frame_due(start + step, true));
+        // Stopped animating: one more frame, then idle
+        assert!(pacer.frame_due(start + step * 2, false));
+        assert!(!pacer.frame_due(start + step * 3, false));
+    }
+
+    #[test]
+    fn immediate_frames_skip_the_cap() {
+        let mut pacer = FramePacer::new();
+        let start = drawn(&mut pacer);
+        pacer.mark_dirty();
+        pacer.request_immediate();
+        assert!(pacer.frame_due(start + Duration::from_millis(1), false));
+    }
+
+    #[test]
+    fn hidden_windows_never_draw_and_poll_slowly() {
+        let mut pacer = FramePacer::new();
+        let now = Instant::now();
+        pacer.set_occluded(true);
+        assert!(!pacer.frame_due(now, true));
+        assert_eq!(pacer.next_tick(now), now + HIDDEN_POLL);
+        pacer.set_occluded(false);
+        pacer.set_minimized(true);
+        assert!(pacer.is_hidden());
+        pacer.set_minimized(false);
+        // Shown again: the pending change draws right away
+        assert!(pacer.frame_due(now, false));
+    }
+}
@@ -0,0 +1,240 @@
+// Soft wrap display mode: long terminal rows are shown wrapped at a chosen
+// column, like an editor's soft wrap, without resizing the terminal or
+// changing what applications see. The layout maps each display row to the
+// slice of a terminal row it shows, so cells, the cursor, guides and mouse
+// positions can be moved between the two.
+
+use super::grid_renderer::{GridCell, CELL_FLAG_WIDE, CELL_FLAG_WIDE_SPACER, CELL_FLAG_WRAPLINE};
+
+/// One display row: terminal `row`, columns `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    row: usize,
+    start: usize,
+    end: usize,
+}
+
+/// How a pane's visible terminal rows are laid out when wrapped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftWrapLayout {
+    cols: usize,
+    rows: usize,
+    /// The display rows on screen, top first.
+    segments: Vec<Segment>,
+    /// Display rows that continue on the next one.
+    wrapped: Vec<usize>,
+}
+
+impl SoftWrapLayout {
+    /// Lay out `cells` (a `cols` x `rows` viewport) wrapped after `wrap_at`
+    /// columns. Rows only take the display rows their text needs. When
+    /// wrapping pushes rows off the screen, rows below `cursor` (row, col)
+    /// go first, so the prompt stays in view; without a cursor the bottom
+    /// is kept. None when `wrap_at` leaves rows as they are.
+    pub fn new(
+        cells: &[GridCell],
+        cols: usize,
+        rows: usize,
+        wrap_at: usize,
+        cursor: Option<(usize, usize)>,
+    ) -> Option<Self> {
+        if wrap_at == 0 || wrap_at >= cols || cells.len() < cols * rows {
+            return None;
+        }
+        let mut segments = Vec::new();
+        let mut cursor_segment = None;
+        for row in 0..rows {
+            let line = &cells[row * cols..(row + 1) * cols];
+            let mut len = content_len(line);
+            if let Some((_, cursor_col)) = cursor.filter(|&(r, _)| r == row) {
+                len = len.max(cursor_col + 1);
+            }
+            let mut start = 0;
+            loop {
+                let mut end = (start + wrap_at).min(cols);
+                // A double-width character that would straddle the wrap moves down
+                if end < cols && end > start + 1 && line[end - 1].flags & CELL_FLAG_WIDE != 0 {
+                    end -= 1;
+                }
+                if cursor.is_some_and(|(r, c)| r == row && c >= start && c < end) {
+                    cursor_segment = Some(segments.len());
+                }
+                segments.push(Segment { row, start, end });
+                if end >= len || end >= cols {
+                    break;
+                }
+                start = end;
+            }
+        }
+
+        let top = match cursor_segment {
+            Some(index) => (index + 1).saturating_sub(rows),
+            None => segments.len().saturating_sub(rows),
+        };
+        let segments: Vec<Segment> = segments.into_iter().skip(top).take(rows).collect();
+        let wrapped = segments
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0].row == pair[1].row)
+            .map(|(index, _)| index)
+            .collect();
+        Some(Self { cols, rows, segments, wrapped })
+    }
+
+    /// The viewport's cells rearranged into display rows, padded with the
+    /// background of each terminal row's last cell.
+    pub fn apply(&self, cells: &[GridCell]) -> Vec<GridCell> {
+        let mut out = Vec::with_capacity(self.cols * self.rows);
+        for segment in &self.segments {
+            let line = &cells[segment.row * self.cols..(segment.row + 1) * self.cols];
+            let mut blank = line[self.cols - 1];
+            blank.ch = ' ';
+            blank.flags = 0;
+            out.extend(line[segment.start..segment.end].iter().map(|cell| GridCell {
+                flags: cell.flags & !CELL_FLAG_WRAPLINE,
+                ..*cell
+            }));
+            out.resize(out.len() + self.cols - (segment.end - segment.start), blank);
+        }
+        out
+    }
+
+    /// Where terminal cell (row, col) is shown, if on screen.
+    pub fn to_display(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let mut last = None;
+        for (index, segment) in self.segments.iter().enumerate().filter(|(_, s)| s.row == row) {
+            if col < segment.end {
+                return (col >= segment.start).then_some((index, col - segment.start));
+            }
+            last = Some((index, segment));
+        }
+        // Past the text: beside the row's last display row
+        last.map(|(index, segment)| (index, (col - segment.start).min(self.cols - 1)))
+    }
+
+    /// The terminal cell shown at display (row, col). Columns past a display
+    /// row's text map to just after it.
+    pub fn to_terminal(&self, row: usize, col: usize) -> (usize, usize) {
+        let Some(segment) = self.segments.get(row).or(self.segments.last()) else {
+            return (row, col);
+        };
+        let continues = self.wrapped.contains(&row);
+        let width = segment.end - segment.start;
+        let col = if continues { col.min(width.saturating_sub(1)) } else { col };
+        (segment.row, (segment.start + col).min(self.cols - 1))
+    }
+
+    /// A pane-local pixel position over the display grid, moved to the same
+    /// spot over the terminal cell it shows (for mouse selection).
+    pub fn to_terminal_pixel(&self, x: f32, y: f32, cell_width: f32, cell_height: f32) -> (f32, f32) {
+        if cell_width <= 0.0 || cell_height <= 0.0 {
+            return (x, y);
+        }
+        let row = (y.max(0.0) / cell_height) as usize;
+        let col = (x.max(0.0) / cell_width) as usize;
+        let (term_row, term_col) = self.to_terminal(row.min(self.rows - 1), col.min(self.cols - 1));
+        (
+            term_col as f32 * cell_width + x.max(0.0) % cell_width,
+            term_row as f32 * cell_height + y.max(0.0) % cell_height,
+        )
+    }
+
+    /// Display rows that continue on the next one, for wrap markers.
+    pub fn wrapped_rows(&self) -> &[usize] {
+        &self.wrapped
+    }
+}
+
+/// Columns up to the last one showing text or a background of its own.
+fn content_len(line: &[GridCell]) -> usize {
+    let Some(last) = line.last() else {
+        return 0;
+    };
+    line.iter()
+        .rposition(|cell| {
+            cell.is_occupied() || cell.bg != last.bg || cell.flags & !(CELL_FLAG_WRAPLINE | CELL_FLAG_WIDE_SPACER) != 0
+        })
+        .map_or(0, |index| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::theme::color_new;
+
+    fn grid(lines: &[&str], cols: usize) -> Vec<GridCell> {
+        let bg = color_new(0.0, 0.0, 0.0, 1.0);
+        let fg = color_new(1.0, 1.0, 1.0, 1.0);
+        lines
+            .iter()
+            .flat_map(|line| {
+                let mut row: Vec<GridCell> = line.chars().map(|c| GridCell::new(c, fg, bg)).collect();
+                row.resize(cols, GridCell::new(' ', fg, bg));
+                row
+            })
+            .collect()
+    }
+
+    fn text(cells: &[GridCell], cols: usize) -> Vec<String> {
+        cells
+            .chunks(cols)
+            .map(|row| row.iter().map(|c| c.ch).collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_long_rows_and_keeps_the_bottom() {
+        let cells = grid(&["$ echo", "abcdefghij", "$"], 10);
+        let layout = SoftWrapLayout::new(&cells, 10, 3, 4, None).unwrap();
+        // "abcdefghij" takes three display rows; the first row scrolls off
+        assert_eq!(text(&layout.apply(&cells), 10), ["efgh", "ij", "$"]);
+        assert_eq!(layout.wrapped_rows(), [0]);
+        assert_eq!(layout.to_display(1, 9), Some((1, 1)));
+        assert_eq!(layout.to_display(0, 0), None);
+        assert_eq!(layout.to_terminal(0, 2), (1, 6));
+    }
+
+    #[test]
+    fn keeps_cursor_visible() {
+        let cells = grid(&["$ ls", "abcdefghij", "abcdefghij"], 10);
+        let layout = SoftWrapLayout::new(&cells, 10, 3, 5, Some((0, 4))).unwrap();
+        assert_eq!(text(&layout.apply(&cells), 10), ["$ ls", "abcde", "fghij"]);
+        assert_eq!(layout.to_display(0, 4), Some((0, 4)));
+    }
+
+    #[test]
+    fn cursor_past_the_text_gets_its_row() {
+        let cells = grid(&["abcd", ""], 8);
+        let layout = SoftWrapLayout::new(&cells, 8, 2, 4, Some((0, 4))).unwrap();
+        assert_eq!(text(&layout.apply(&cells), 8), ["abcd", ""]);
+        assert_eq!(layout.to_display(0, 4), Some((1, 0)));
+        assert_eq!(layout.wrapped_rows(), [0]);
+    }
+
+    #[test]
+    fn wide_characters_do_not_straddle_the_wrap() {
+        let mut cells = grid(&["abc漢 x", ""], 8);
+        cells[3].flags |= CELL_FLAG_WIDE;
+        cells[4].flags |= CELL_FLAG_WIDE_SPACER;
+        let layout = SoftWrapLayout::new(&cells, 8, 2, 4, Some((0, 0))).unwrap();
+        let shown = layout.apply(&cells);
+        assert_eq!(shown[0].ch, 'a');
+        assert_eq!(shown[8].ch, '漢');
+        assert_eq!(layout.to_display(0, 3), Some((1, 0)));
+    }
+
+    #[test]
+    fn no_layout_when_the_pane_is_narrower() {
+        let cells = grid(&["abc"], 10);
+        assert!(SoftWrapLayout::new(&cells, 10, 1, 10, None).is_none());
+        assert!(SoftWrapLayout::new(&cells, 10, 1, 0, None).is_none());
+    }
+
+    #[test]
+    fn pixels_map_to_the_terminal_cell_shown() {
+        let cells = grid(&["abcdefgh", ""], 8);
+        let layout = SoftWrapLayout::new(&cells, 8, 2, 4, Some((0, 0))).unwrap();
+        // Display row 1, col 1 shows terminal col 5 of row 0
+        assert_eq!(layout.to_terminal_pixel(15.0, 25.0, 10.0, 20.0), (55.0, 5.0));
+    }
+}
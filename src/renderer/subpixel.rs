@@ -0,0 +1,141 @@
+// Glyph masks for the swash atlas, grayscale or subpixel (LCD). On a low-DPI
+// LCD every pixel is three colored stripes; rasterizing each color channel a
+// third of a pixel apart gives it its own coverage, for sharper stems than
+// grayscale. Subpixel atlases hold RGBA masks and the grid shader blends each
+// channel separately. CoreText smooths glyphs its own way and doesn't use this.
+
+use cosmic_text::{Buffer, CacheKey, FontSystem};
+use swash::scale::image::Content;
+use swash::scale::{Render, ScaleContext, Source, StrikeWith};
+use swash::zeno::{Format, Vector};
+
+/// Subpixel order of the monitor (`font.subpixel_antialiasing`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Subpixel {
+    /// Grayscale antialiasing.
+    #[default]
+    None,
+    Rgb,
+    Bgr,
+}
+
+impl Subpixel {
+    /// Convert a config string ("none", "rgb", "bgr").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "none" => Some(Self::None),
+            "rgb" => Some(Self::Rgb),
+            "bgr" => Some(Self::Bgr),
+            _ => None,
+        }
+    }
+
+    /// Whether glyphs get per-channel coverage (an RGBA atlas).
+    pub fn is_enabled(self) -> bool {
+        self != Self::None
+    }
+
+    fn format(self) -> Format {
+        match self {
+            Self::None => Format::Alpha,
+            Self::Rgb => Format::Subpixel,
+            Self::Bgr => Format::subpixel_bgra(),
+        }
+    }
+}
+
+/// Coverage of one pixel as [r, g, b, a], the alpha being the strongest
+/// channel. Grayscale coverage is the same in every channel.
+pub type Coverage = [u8; 4];
+
+/// Coverage of pixel `i` of a rendered glyph image.
+fn coverage(content: Content, data: &[u8], i: usize) -> Coverage {
+    match content {
+        Content::Mask => [data[i]; 4],
+        Content::SubpixelMask => {
+            let [r, g, b] = [data[i * 4], data[i * 4 + 1], data[i * 4 + 2]];
+            [r, g, b, r.max(g).max(b)]
+        }
+        // Color glyphs (emoji) are drawn in the text color like the rest
+        Content::Color => [data[i * 4 + 3]; 4],
+    }
+}
+
+/// Renders glyphs as coverage masks, like `SwashCache` but with subpixel
+/// coverage when enabled.
+pub struct MaskRasterizer {
+    context: ScaleContext,
+    subpixel: Subpixel,
+}
+
+impl MaskRasterizer {
+    pub fn new(subpixel: Subpixel) -> Self {
+        Self { context: ScaleContext::new(), subpixel }
+    }
+
+    /// Call `f(x, y, coverage)` for each pixel of glyph `key`, relative to
+    /// its origin like `SwashCache::with_pixels`.
+    pub fn with_pixels(&mut self, font_system: &mut FontSystem, key: CacheKey, mut f: impl FnMut(i32, i32, Coverage)) {
+        let Some(font) = font_system.get_font(key.font_id) else {
+            log::warn!("Glyph font {:?} not found", key.font_id);
+            return;
+        };
+        let mut scaler = self
+            .context
+            .builder(font.as_swash())
+            .size(f32::from_bits(key.font_size_bits))
+            .hint(true)
+            .build();
+        let image = Render::new(&[
+            Source::ColorOutline(0),
+            Source::ColorBitmap(StrikeWith::BestFit),
+            Source::Outline,
+        ])
+        .format(self.subpixel.format())
+        .offset(Vector::new(key.x_bin.as_float(), key.y_bin.as_float()))
+        .render(&mut scaler, key.glyph_id);
+        let Some(image) = image else {
+            return;
+        };
+        let width = image.placement.width as usize;
+        for i in 0..width * image.placement.height as usize {
+            let (x, y) = ((i % width) as i32, (i / width) as i32);
+            f(image.placement.left + x, y - image.placement.top, coverage(image.content, &image.data, i));
+        }
+    }
+
+    /// Draw every glyph of a shaped `buffer`, like `Buffer::draw`.
+    pub fn draw(&mut self, font_system: &mut FontSystem, buffer: &Buffer, mut f: impl FnMut(i32, i32, Coverage)) {
+        for run in buffer.layout_runs() {
+            for glyph in run.glyphs {
+                let physical = glyph.physical((0., 0.), 1.0);
+                self.with_pixels(font_system, physical.cache_key, |x, y, coverage| {
+                    f(physical.x + x, run.line_y as i32 + physical.y + y, coverage);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_strings() {
+        assert_eq!(Subpixel::from_config_str("none"), Some(Subpixel::None));
+        assert_eq!(Subpixel::from_config_str("bgr"), Some(Subpixel::Bgr));
+        assert_eq!(Subpixel::from_config_str("vrgb"), None);
+        assert!(!Subpixel::default().is_enabled());
+        assert!(Subpixel::Rgb.is_enabled());
+    }
+
+    #[test]
+    fn coverage_keeps_channels_apart_only_for_subpixel_masks() {
+        assert_eq!(coverage(Content::Mask, &[0, 90], 1), [90; 4]);
+        let rgba = [0, 0, 0, 0, 40, 200, 10, 255];
+        assert_eq!(coverage(Content::SubpixelMask, &rgba, 1), [40, 200, 10, 200]);
+        assert_eq!(coverage(Content::Color, &rgba, 1), [255; 4]);
+    }
+}
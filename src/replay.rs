@@ -0,0 +1,638 @@
+// Event traces: an append-only log of what drove the terminal (PTY output,
+// input, resizes, layout changes, actions) and a headless replay harness that
+// rebuilds the pane layout and terminal grids from it, so a trace attached to
+// a bug report reproduces the same screen in a test. Checkpoints (before each
+// action and at the end) can be rendered to images and compared.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::config::theme::TerminalTheme;
+use crate::pane::{PaneId, PaneNode, PaneTree, Rect, SplitDirection};
+use crate::renderer::headless::{HeadlessPane, HeadlessRenderer};
+use crate::terminal::Terminal;
+
+/// Scrollback kept by replayed terminals.
+const REPLAY_SCROLLBACK: usize = 10_000;
+
+/// A recorded pane layout node; leaves carry the pane ids of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceNode {
+    Leaf {
+        pane: u32,
+    },
+    Split {
+        /// "vertical" (side by side) or "horizontal" (stacked).
+        direction: String,
+        ratio: f32,
+        first: Box<TraceNode>,
+        second: Box<TraceNode>,
+    },
+}
+
+impl TraceNode {
+    /// Capture a pane tree node.
+    pub fn capture(node: &PaneNode) -> Self {
+        match node {
+            PaneNode::Leaf { id } => Self::Leaf { pane: id.0 },
+            PaneNode::Split { direction, ratio, first, second } => Self::Split {
+                direction: match direction {
+                    SplitDirection::Vertical => "vertical".to_string(),
+                    SplitDirection::Horizontal => "horizontal".to_string(),
+                },
+                ratio: *ratio,
+                first: Box::new(Self::capture(first)),
+                second: Box::new(Self::capture(second)),
+            },
+        }
+    }
+
+    fn to_pane_node(&self) -> PaneNode {
+        match self {
+            Self::Leaf { pane } => PaneNode::leaf(PaneId(*pane)),
+            Self::Split { direction, ratio, first, second } => {
+                let direction = match direction.as_str() {
+                    "horizontal" => SplitDirection::Horizontal,
+                    _ => SplitDirection::Vertical,
+                };
+                PaneNode::split(direction, *ratio, first.to_pane_node(), second.to_pane_node())
+            }
+        }
+    }
+}
+
+/// One thing that happened to the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// Pane area of the window, in physical pixels.
+    Resize { width: f32, height: f32 },
+    /// The active tab's pane layout.
+    Layout { root: TraceNode, focused: u32, zoomed: bool },
+    /// A pane's terminal grid was resized.
+    PaneResize { pane: u32, cols: usize, rows: usize },
+    /// Bytes read from a pane's PTY, base64-encoded.
+    Output { pane: u32, data: String },
+    /// Bytes written to a pane's PTY, base64-encoded.
+    Input { pane: u32, data: String },
+    /// A key-bound or palette action, by config name.
+    Action { name: String },
+}
+
+impl TraceEvent {
+    pub fn output(pane: PaneId, bytes: &[u8]) -> Self {
+        Self::Output { pane: pane.0, data: encode(bytes) }
+    }
+
+    pub fn input(pane: PaneId, bytes: &[u8]) -> Self {
+        Self::Input { pane: pane.0, data: encode(bytes) }
+    }
+
+    pub fn layout(tree: &PaneTree) -> Self {
+        Self::Layout {
+            root: TraceNode::capture(tree.root()),
+            focused: tree.focused_pane_id().0,
+            zoomed: tree.is_zoomed(),
+        }
+    }
+}
+
+/// An event with its time since recording started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, ReplayError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| ReplayError::Parse(format!("bad base64 payload: {e}")))
+}
+
+/// Errors reading or replaying a trace.
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("trace I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("trace parse error: {0}")]
+    Parse(String),
+    #[error("rendering failed: {0}")]
+    Render(String),
+}
+
+/// Writes events to a trace file, one JSON object per line.
+pub struct EventRecorder {
+    writer: Box<dyn Write + Send>,
+    started: Instant,
+}
+
+impl EventRecorder {
+    /// Start a trace at `path`, replacing any existing file.
+    pub fn create(path: &Path) -> Result<Self, ReplayError> {
+        let file = std::fs::File::create(path)?;
+        Ok(Self::new(Box::new(BufWriter::new(file))))
+    }
+
+    pub fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self { writer, started: Instant::now() }
+    }
+
+    /// Append an event. Write errors are logged and otherwise ignored so a
+    /// full disk never takes the terminal down.
+    pub fn record(&mut self, event: TraceEvent) {
+        let timed = TimedEvent {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            event,
+        };
+        let result = serde_json::to_writer(&mut self.writer, &timed)
+            .map_err(std::io::Error::other)
+            .and_then(|()| self.writer.write_all(b"\n"));
+        if let Err(e) = result {
+            log::warn!("Failed to record trace event: {e}");
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            log::warn!("Failed to flush event trace: {e}");
+        }
+    }
+}
+
+impl Drop for EventRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Read a trace written by `EventRecorder`. Blank lines are skipped.
+pub fn read_trace(reader: impl BufRead) -> Result<Vec<TimedEvent>, ReplayError> {
+    let mut events = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line)
+            .map_err(|e| ReplayError::Parse(format!("line {}: {e}", n + 1)))?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// What one pane looks like after a replay.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PaneSnapshot {
+    pub pane: u32,
+    pub rect: [f32; 4],
+    pub cols: usize,
+    pub rows: usize,
+    pub cursor: (usize, usize),
+    /// Visible screen rows, trailing blanks trimmed.
+    pub screen: Vec<String>,
+}
+
+/// Headless app state rebuilt from a trace: the active tab's layout and one
+/// terminal per recorded pane. Actions and input are kept for reference;
+/// their effects reach the state through the layout and output they caused.
+pub struct Replay {
+    width: f32,
+    height: f32,
+    tree: Option<PaneTree>,
+    terminals: HashMap<u32, Terminal>,
+    actions: Vec<String>,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            width: 0.0,
+            height: 0.0,
+            tree: None,
+            terminals: HashMap::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Replay a whole trace.
+    pub fn run(events: &[TimedEvent]) -> Result<Self, ReplayError> {
+        let mut replay = Self::new();
+        for timed in events {
+            replay.apply(&timed.event)?;
+        }
+        Ok(replay)
+    }
+
+    /// Replay a whole trace, handing `at_checkpoint` the state as it was
+    /// before each recorded action (what the user was looking at) and once
+    /// more at the end.
+    pub fn run_checkpoints(
+        events: &[TimedEvent],
+        mut at_checkpoint: impl FnMut(&Self) -> Result<(), ReplayError>,
+    ) -> Result<Self, ReplayError> {
+        let mut replay = Self::new();
+        for timed in events {
+            if matches!(timed.event, TraceEvent::Action { .. }) {
+                at_checkpoint(&replay)?;
+            }
+            replay.apply(&timed.event)?;
+        }
+        at_checkpoint(&replay)?;
+        Ok(replay)
+    }
+
+    pub fn apply(&mut self, event: &TraceEvent) -> Result<(), ReplayError> {
+        match event {
+            TraceEvent::Resize { width, height } => {
+                self.width = *width;
+                self.height = *height;
+            }
+            TraceEvent::Layout { root, focused, zoomed } => {
+                let mut tree = PaneTree::from_node(root.to_pane_node(), Some(PaneId(*focused)));
+                if *zoomed {
+                    tree.zoom_toggle();
+                }
+                self.tree = Some(tree);
+            }
+            TraceEvent::PaneResize { pane, cols, rows } => {
+                self.terminal(*pane, *cols, *rows).resize(*cols, *rows);
+            }
+            TraceEvent::Output { pane, data } => {
+                let bytes = decode(data)?;
+                self.terminal(*pane, 80, 24).feed(&bytes);
+            }
+            TraceEvent::Input { data, .. } => {
+                decode(data)?;
+            }
+            TraceEvent::Action { name } => self.actions.push(name.clone()),
+        }
+        Ok(())
+    }
+
+    fn terminal(&mut self, pane: u32, cols: usize, rows: usize) -> &mut Terminal {
+        self.terminals
+            .entry(pane)
+            .or_insert_with(|| Terminal::new(cols, rows, REPLAY_SCROLLBACK))
+    }
+
+    /// Actions seen so far, in order.
+    pub fn actions(&self) -> &[String] {
+        &self.actions
+    }
+
+    /// The focused pane of the last recorded layout.
+    pub fn focused_pane(&self) -> Option<u32> {
+        self.tree.as_ref().map(|tree| tree.focused_pane_id().0)
+    }
+
+    /// Visible panes in layout order with their rects, empty for panes that
+    /// only produced output (no layout recorded).
+    fn layout(&self) -> Vec<(u32, Rect)> {
+        match &self.tree {
+            Some(tree) => tree
+                .calculate_layout(self.width, self.height)
+                .into_iter()
+                .map(|(id, rect)| (id.0, rect))
+                .collect(),
+            None => {
+                let mut panes: Vec<u32> = self.terminals.keys().copied().collect();
+                panes.sort_unstable();
+                panes.into_iter().map(|id| (id, Rect::new(0.0, 0.0, 0.0, 0.0))).collect()
+            }
+        }
+    }
+
+    /// Layout and screen contents of every visible pane, in layout order.
+    /// Panes that only produced output (no layout recorded) get an empty rect.
+    pub fn snapshot(&self) -> Vec<PaneSnapshot> {
+        self.layout()
+            .into_iter()
+            .map(|(pane, rect)| {
+                let terminal = self.terminals.get(&pane);
+                PaneSnapshot {
+                    pane,
+                    rect: [rect.x, rect.y, rect.width, rect.height],
+                    cols: terminal.map_or(0, Terminal::columns),
+                    rows: terminal.map_or(0, Terminal::rows),
+                    cursor: terminal.map_or((0, 0), Terminal::cursor_position),
+                    screen: terminal.map_or_else(Vec::new, |t| {
+                        (0..t.rows() as i32)
+                            .map(|row| t.line_text(row).trim_end().to_string())
+                            .collect()
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Frame size and pane grids for the headless renderer, for cells of
+    /// `cell` pixels. The recorded layout is scaled up until every pane's
+    /// grid fits its rect, since the renderer's font may be larger than the
+    /// recording's; without a layout, panes are stacked top to bottom.
+    pub fn frame(&self, theme: &TerminalTheme, cell: (f32, f32)) -> (u32, u32, Vec<HeadlessPane>) {
+        let (cw, ch) = cell;
+        let grid_size = |t: &Terminal| (t.columns() as f32 * cw, t.rows() as f32 * ch);
+        let mut placed: Vec<(&Terminal, Rect)> = Vec::new();
+        let (width, height) = if self.tree.is_some() {
+            let layout: Vec<(&Terminal, Rect)> = self
+                .layout()
+                .into_iter()
+                .filter_map(|(pane, rect)| Some((self.terminals.get(&pane)?, rect)))
+                .collect();
+            let scale = layout
+                .iter()
+                .map(|(t, rect)| {
+                    let (w, h) = grid_size(t);
+                    (w / rect.width.max(1.0)).max(h / rect.height.max(1.0))
+                })
+                .fold(1.0f32, f32::max);
+            placed.extend(layout.into_iter().map(|(t, r)| {
+                (t, Rect::new(r.x * scale, r.y * scale, r.width * scale, r.height * scale))
+            }));
+            ((self.width * scale).ceil(), (self.height * scale).ceil())
+        } else {
+            let (mut width, mut y) = (0.0f32, 0.0);
+            let mut panes: Vec<u32> = self.terminals.keys().copied().collect();
+            panes.sort_unstable();
+            for t in panes.iter().filter_map(|pane| self.terminals.get(pane)) {
+                let (w, h) = grid_size(t);
+                placed.push((t, Rect::new(0.0, y, w, h)));
+                width = width.max(w);
+                y += h;
+            }
+            (width.ceil(), y.ceil())
+        };
+        let panes = placed
+            .into_iter()
+            .map(|(t, rect)| HeadlessPane {
+                rect,
+                cols: t.columns(),
+                rows: t.rows(),
+                cells: crate::terminal::grid_bridge::extract_grid_cells(t, theme),
+            })
+            .collect();
+        (width as u32, height as u32, panes)
+    }
+
+    /// Render the current state with `renderer`.
+    pub fn render(
+        &self,
+        renderer: &mut HeadlessRenderer,
+        theme: &TerminalTheme,
+    ) -> Result<image::RgbaImage, ReplayError> {
+        let (width, height, panes) = self.frame(theme, renderer.cell_size());
+        renderer
+            .render(width, height, theme, &panes)
+            .map_err(|e| ReplayError::Render(e.to_string()))
+    }
+}
+
+/// Replay `events` and render every checkpoint (see `Replay::run_checkpoints`).
+pub fn render_checkpoints(
+    events: &[TimedEvent],
+    renderer: &mut HeadlessRenderer,
+    theme: &TerminalTheme,
+) -> Result<Vec<image::RgbaImage>, ReplayError> {
+    let mut images = Vec::new();
+    Replay::run_checkpoints(events, |replay| {
+        images.push(replay.render(renderer, theme)?);
+        Ok(())
+    })?;
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+
+    /// A `Write` that appends to a shared buffer.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn split_layout(focused: u32) -> TraceEvent {
+        TraceEvent::Layout {
+            root: TraceNode::Split {
+                direction: "vertical".to_string(),
+                ratio: 0.5,
+                first: Box::new(TraceNode::Leaf { pane: 1 }),
+                second: Box::new(TraceNode::Leaf { pane: 2 }),
+            },
+            focused,
+            zoomed: false,
+        }
+    }
+
+    fn trace() -> Vec<TraceEvent> {
+        vec![
+            TraceEvent::Resize { width: 800.0, height: 600.0 },
+            TraceEvent::Layout { root: TraceNode::Leaf { pane: 1 }, focused: 1, zoomed: false },
+            TraceEvent::PaneResize { pane: 1, cols: 40, rows: 5 },
+            TraceEvent::output(PaneId(1), b"$ echo hi\r\nhi\r\n$ "),
+            TraceEvent::Action { name: "split_vertical".to_string() },
+            split_layout(2),
+            TraceEvent::PaneResize { pane: 1, cols: 20, rows: 5 },
+            TraceEvent::PaneResize { pane: 2, cols: 20, rows: 5 },
+            TraceEvent::output(PaneId(2), b"\x1b[31mred\x1b[0m"),
+        ]
+    }
+
+    // ── Recording ───────────────────────────────────────────────────
+
+    #[test]
+    fn recorded_trace_reads_back() {
+        let buf = SharedBuf::default();
+        let mut recorder = EventRecorder::new(Box::new(buf.clone()));
+        for event in trace() {
+            recorder.record(event);
+        }
+        recorder.flush();
+        let bytes = buf.0.lock().unwrap().clone();
+        let events = read_trace(Cursor::new(bytes)).unwrap();
+        let kinds: Vec<TraceEvent> = events.into_iter().map(|e| e.event).collect();
+        assert_eq!(kinds, trace());
+    }
+
+    #[test]
+    fn password_typed_with_echo_off_stays_out_of_trace() {
+        let redactor = crate::redact::Redactor::new(true, &[]).unwrap();
+        let buf = SharedBuf::default();
+        let mut recorder = EventRecorder::new(Box::new(buf.clone()));
+        recorder.record(TraceEvent::input(PaneId(1), &redactor.redact_input(b"sudo ls\r", true)));
+        recorder.record(TraceEvent::output(PaneId(1), &redactor.redact_bytes(b"[sudo] password for me: ")));
+        // One keystroke per event, as typed at the no-echo prompt
+        for key in b"hunter2\r" {
+            recorder.record(TraceEvent::input(PaneId(1), &redactor.redact_input(&[*key], false)));
+        }
+        recorder.flush();
+        let events = read_trace(Cursor::new(buf.0.lock().unwrap().clone())).unwrap();
+        let typed: Vec<u8> = events
+            .iter()
+            .filter_map(|e| match &e.event {
+                TraceEvent::Input { data, .. } => Some(decode(data).unwrap()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let typed = String::from_utf8(typed).unwrap();
+        assert!(typed.starts_with("sudo ls\r"), "{typed}");
+        assert!(!typed.contains('h') && !typed.contains('2'), "{typed}");
+        assert!(typed.ends_with("[REDACTED]\r"), "{typed}");
+    }
+
+    #[test]
+    fn trace_lines_are_tagged_json() {
+        let line = serde_json::to_string(&TimedEvent {
+            at_ms: 5,
+            event: TraceEvent::input(PaneId(3), b"ls\r"),
+        })
+        .unwrap();
+        assert_eq!(line, r#"{"at_ms":5,"type":"input","pane":3,"data":"bHMN"}"#);
+    }
+
+    #[test]
+    fn malformed_line_reports_its_number() {
+        let text = "{\"at_ms\":0,\"type\":\"action\",\"name\":\"new_tab\"}\n\nnot json\n";
+        let err = read_trace(Cursor::new(text)).unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{err}");
+    }
+
+    #[test]
+    fn layout_event_captures_tree() {
+        let mut tree = PaneTree::from_node(PaneNode::leaf(PaneId(7)), None);
+        tree.split_focused(SplitDirection::Horizontal).unwrap();
+        let TraceEvent::Layout { root, focused, .. } = TraceEvent::layout(&tree) else {
+            panic!("not a layout event");
+        };
+        assert_eq!(focused, tree.focused_pane_id().0);
+        let TraceNode::Split { direction, first, .. } = root else {
+            panic!("expected a split");
+        };
+        assert_eq!(direction, "horizontal");
+        assert_eq!(*first, TraceNode::Leaf { pane: 7 });
+    }
+
+    // ── Replay ──────────────────────────────────────────────────────
+
+    #[test]
+    fn replay_rebuilds_layout_and_screens() {
+        let events: Vec<TimedEvent> =
+            trace().into_iter().map(|event| TimedEvent { at_ms: 0, event }).collect();
+        let replay = Replay::run(&events).unwrap();
+        let snapshot = replay.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].pane, 1);
+        // Side by side, halves of the 800px width less the divider
+        assert_eq!(snapshot[0].rect[2], snapshot[1].rect[2]);
+        assert!(snapshot[1].rect[0] >= snapshot[0].rect[2]);
+        assert_eq!((snapshot[0].cols, snapshot[0].rows), (20, 5));
+        assert!(snapshot[0].screen.iter().any(|line| line == "hi"));
+        assert_eq!(snapshot[1].screen[0], "red");
+        assert_eq!(replay.focused_pane(), Some(2));
+        assert_eq!(replay.actions(), ["split_vertical"]);
+    }
+
+    #[test]
+    fn replay_is_deterministic() {
+        let events: Vec<TimedEvent> =
+            trace().into_iter().map(|event| TimedEvent { at_ms: 0, event }).collect();
+        let first = Replay::run(&events).unwrap().snapshot();
+        let second = Replay::run(&events).unwrap().snapshot();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn zoomed_layout_shows_only_focused_pane() {
+        let mut replay = Replay::new();
+        replay.apply(&TraceEvent::Resize { width: 800.0, height: 600.0 }).unwrap();
+        replay.apply(&split_layout(1)).unwrap();
+        let TraceEvent::Layout { root, .. } = split_layout(1) else { unreachable!() };
+        replay.apply(&TraceEvent::Layout { root, focused: 1, zoomed: true }).unwrap();
+        let snapshot = replay.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].rect, [0.0, 0.0, 800.0, 600.0]);
+    }
+
+    #[test]
+    fn checkpoints_come_before_each_action_and_at_the_end() {
+        let events: Vec<TimedEvent> =
+            trace().into_iter().map(|event| TimedEvent { at_ms: 0, event }).collect();
+        let mut seen = Vec::new();
+        Replay::run_checkpoints(&events, |replay| {
+            seen.push(replay.snapshot().len());
+            Ok(())
+        })
+        .unwrap();
+        // One pane when split_vertical was pressed, two at the end
+        assert_eq!(seen, [1, 2]);
+    }
+
+    #[test]
+    fn frame_scales_layout_until_grids_fit() {
+        let events: Vec<TimedEvent> =
+            trace().into_iter().map(|event| TimedEvent { at_ms: 0, event }).collect();
+        let replay = Replay::run(&events).unwrap();
+        let theme = TerminalTheme::warm_dark();
+        // 20 columns of 80px need 1600px per pane, about four times the
+        // recorded half of 800px; the frame keeps its aspect ratio
+        let (width, height, panes) = replay.frame(&theme, (80.0, 10.0));
+        assert!(width > 3200, "{width}");
+        assert!((height as f32 / width as f32 - 0.75).abs() < 0.01, "{width}x{height}");
+        assert_eq!(panes.len(), 2);
+        assert!(panes.iter().all(|p| p.rect.width.round() >= 20.0 * 80.0));
+        assert_eq!(panes[1].cells.len(), 20 * 5);
+        assert_eq!(panes[1].cells[0].ch, 'r');
+    }
+
+    #[test]
+    fn rendered_checkpoints_are_deterministic() {
+        let Ok(mut renderer) = HeadlessRenderer::new(13.0, "JetBrains Mono", 1.5) else {
+            return; // no GPU available
+        };
+        let events: Vec<TimedEvent> =
+            trace().into_iter().map(|event| TimedEvent { at_ms: 0, event }).collect();
+        let theme = TerminalTheme::warm_dark();
+        let first = render_checkpoints(&events, &mut renderer, &theme).unwrap();
+        let second = render_checkpoints(&events, &mut renderer, &theme).unwrap();
+        assert_eq!(first.len(), 2);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(crate::renderer::headless::pixel_diff(a, b), 0);
+        }
+        assert!(crate::renderer::headless::pixel_diff(&first[0], &first[1]) > 0);
+    }
+
+    #[test]
+    fn bad_payload_is_an_error() {
+        let mut replay = Replay::new();
+        let event = TraceEvent::Output { pane: 1, data: "***".to_string() };
+        assert!(matches!(replay.apply(&event), Err(ReplayError::Parse(_))));
+    }
+}
@@ -0,0 +1,204 @@
+// Screenshots: the whole window, the focused pane or the selection area as
+// PNG, saved under the [screenshot] folder and file name template or put on
+// the clipboard. The area is cut from the next rendered frame.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use image::RgbaImage;
+
+use crate::input::selection::{normalize, Selection, SelectionType};
+use crate::pane::Rect;
+
+/// How long the captured area flashes.
+pub const FLASH_DURATION: Duration = Duration::from_millis(150);
+
+/// What a screenshot covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotScope {
+    Window,
+    Pane,
+    Selection,
+}
+
+impl ScreenshotScope {
+    /// Name used for `{scope}` in the file name template.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScreenshotScope::Window => "window",
+            ScreenshotScope::Pane => "pane",
+            ScreenshotScope::Selection => "selection",
+        }
+    }
+}
+
+/// Where a screenshot goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotTarget {
+    File,
+    Clipboard,
+}
+
+/// A screenshot taken from the next frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingScreenshot {
+    pub scope: ScreenshotScope,
+    pub target: ScreenshotTarget,
+    /// Area to keep, in surface pixels; None keeps the whole window.
+    pub area: Option<Rect>,
+}
+
+/// `template` with `{date}`, `{time}` and `{scope}` filled in.
+pub fn file_name(template: &str, scope: ScreenshotScope, now: chrono::NaiveDateTime) -> String {
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H-%M-%S").to_string())
+        .replace("{scope}", scope.name())
+}
+
+/// The folder screenshots are saved in: `directory` (a leading `~/` is the
+/// home folder), else ~/Pictures when it exists, else home.
+pub fn output_dir(directory: &str, home: &Path) -> PathBuf {
+    let directory = directory.trim();
+    if directory.is_empty() {
+        let pictures = home.join("Pictures");
+        return if pictures.is_dir() { pictures } else { home.to_path_buf() };
+    }
+    match directory.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None if directory == "~" => home.to_path_buf(),
+        None => PathBuf::from(directory),
+    }
+}
+
+/// `path`, or `name-2.png`, `name-3.png`... if it is taken.
+pub fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{stem}-{n}{ext}")))
+        .find(|candidate| !candidate.exists())
+        .expect("some numbered name is free")
+}
+
+/// Viewport cells spanned by `selection` as (first row, first col, last
+/// row, last col), inclusive; None if it is scrolled out of view. A
+/// selection over several lines covers whole rows.
+pub fn selection_cells(
+    selection: &Selection,
+    display_offset: usize,
+    rows: usize,
+    cols: usize,
+) -> Option<(usize, usize, usize, usize)> {
+    if rows == 0 || cols == 0 {
+        return None;
+    }
+    let (start, end) = normalize(selection);
+    let first = start.0 + display_offset as i32;
+    let last = end.0 + display_offset as i32;
+    if last < 0 || first >= rows as i32 {
+        return None;
+    }
+    let first_row = first.max(0) as usize;
+    let last_row = (last as usize).min(rows - 1);
+    let (first_col, last_col) = if selection.selection_type == SelectionType::VisualBlock {
+        (start.1.min(end.1), start.1.max(end.1))
+    } else if start.0 == end.0 {
+        (start.1, end.1)
+    } else {
+        (0, cols - 1)
+    };
+    Some((first_row, first_col.min(cols - 1), last_row, last_col.min(cols - 1)))
+}
+
+/// The part of `image` inside `area`, clipped to the image; None if
+/// nothing is left.
+pub fn crop(image: &RgbaImage, area: &Rect) -> Option<RgbaImage> {
+    let x0 = area.x.max(0.0).floor() as u32;
+    let y0 = area.y.max(0.0).floor() as u32;
+    let x1 = ((area.x + area.width).ceil().max(0.0) as u32).min(image.width());
+    let y1 = ((area.y + area.height).ceil().max(0.0) as u32).min(image.height());
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    Some(image::imageops::crop_imm(image, x0, y0, x1 - x0, y1 - y0).to_image())
+}
+
+/// Put `image` on the system clipboard.
+pub fn copy_to_clipboard(image: &RgbaImage) -> Result<(), arboard::Error> {
+    let mut clipboard = arboard::Clipboard::new()?;
+    clipboard.set_image(arboard::ImageData {
+        width: image.width() as usize,
+        height: image.height() as usize,
+        bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::selection::Side;
+
+    fn selection(start: (i32, usize), end: (i32, usize), selection_type: SelectionType) -> Selection {
+        Selection { start, end, selection_type, start_side: Side::Left, end_side: Side::Right }
+    }
+
+    #[test]
+    fn file_name_fills_placeholders() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(14, 3, 59).unwrap();
+        assert_eq!(
+            file_name("veloterm-{date}-{time}-{scope}.png", ScreenshotScope::Pane, now),
+            "veloterm-2024-05-01-14-03-59-pane.png"
+        );
+    }
+
+    #[test]
+    fn output_dir_expands_home_and_defaults() {
+        let home = tempfile::tempdir().unwrap();
+        assert_eq!(output_dir("", home.path()), home.path());
+        std::fs::create_dir(home.path().join("Pictures")).unwrap();
+        assert_eq!(output_dir(" ", home.path()), home.path().join("Pictures"));
+        assert_eq!(output_dir("~/shots", home.path()), home.path().join("shots"));
+        assert_eq!(output_dir("/srv/shots", home.path()), PathBuf::from("/srv/shots"));
+    }
+
+    #[test]
+    fn unique_path_numbers_taken_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.png");
+        assert_eq!(unique_path(path.clone()), path);
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(unique_path(path.clone()), dir.path().join("shot-2.png"));
+    }
+
+    #[test]
+    fn selection_cells_single_line_multi_line_and_block() {
+        let one_line = selection((2, 5), (2, 9), SelectionType::Range);
+        assert_eq!(selection_cells(&one_line, 0, 24, 80), Some((2, 5, 2, 9)));
+        // Reversed drag across lines covers whole rows
+        let lines = selection((4, 10), (1, 3), SelectionType::Range);
+        assert_eq!(selection_cells(&lines, 0, 24, 80), Some((1, 0, 4, 79)));
+        let block = selection((1, 20), (3, 4), SelectionType::VisualBlock);
+        assert_eq!(selection_cells(&block, 0, 24, 80), Some((1, 4, 3, 20)));
+    }
+
+    #[test]
+    fn selection_cells_clips_to_viewport() {
+        // Starts in scrollback, scrolled up by 2
+        let sel = selection((-5, 0), (-1, 7), SelectionType::Range);
+        assert_eq!(selection_cells(&sel, 2, 24, 80), Some((0, 0, 1, 79)));
+        assert_eq!(selection_cells(&sel, 0, 24, 80), None);
+    }
+
+    #[test]
+    fn crop_clips_to_image() {
+        let image = RgbaImage::from_fn(10, 8, |x, y| image::Rgba([x as u8, y as u8, 0, 255]));
+        let cropped = crop(&image, &Rect::new(6.0, 5.0, 20.0, 20.0)).unwrap();
+        assert_eq!(cropped.dimensions(), (4, 3));
+        assert_eq!(cropped.get_pixel(0, 0).0, [6, 5, 0, 255]);
+        assert!(crop(&image, &Rect::new(12.0, 0.0, 5.0, 5.0)).is_none());
+    }
+}
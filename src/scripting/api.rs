@@ -0,0 +1,218 @@
+// The functions scripts can call. Registration (`set`, `bind`, `on`) is
+// meant for the top level of the script; window functions (`send_text`,
+// `split_pane`, ...) queue commands and only act inside callbacks.
+
+use std::sync::{Arc, Mutex};
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FnPtr, Map, INT};
+
+use super::{ScriptCommand, ScriptEvent, Shared};
+use crate::control::protocol::{Request, SplitSide};
+
+type Result<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+/// Operations a single script run or callback may take before it is
+/// stopped, so a runaway loop cannot hang the window.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 1 << 20;
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// A sandboxed engine whose functions record into `shared`.
+pub(super) fn engine(shared: Arc<Mutex<Shared>>) -> Engine {
+    let mut engine = Engine::new();
+    // The default resolver would let `import` read any file
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .disable_symbol("eval")
+        .on_print(|text| log::info!("Script: {text}"))
+        .on_debug(|text, _, pos| log::debug!("Script {pos}: {text}"));
+
+    // ── Registration ─────────────────────────────────────────────────
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("set", move |key: &str, value: Dynamic| -> Result<()> {
+        let segments: Vec<&str> = key.split('.').collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(format!("set: bad config key '{key}'").into());
+        }
+        if segments[0] == "scripting" {
+            return Err("set: scripting settings cannot be set from a script".into());
+        }
+        s.lock().unwrap().overrides.push((key.to_string(), value));
+        Ok(())
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("bind", move |combo: &str, callback: FnPtr| {
+        s.lock().unwrap().bindings.push((combo.to_string(), callback));
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("on", move |event: &str, callback: FnPtr| -> Result<()> {
+        let Some(event) = ScriptEvent::from_name(event) else {
+            let names: Vec<&str> = ScriptEvent::ALL.iter().map(|e| e.name()).collect();
+            return Err(format!("on: unknown event '{event}' (expected {})", names.join(", ")).into());
+        };
+        s.lock().unwrap().hooks.push((event, callback));
+        Ok(())
+    });
+
+    // ── Panes and tabs ───────────────────────────────────────────────
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("panes", move || -> Array {
+        s.lock().unwrap().context.panes.iter().map(pane_map).map(Dynamic::from_map).collect()
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("active_tab", move || s.lock().unwrap().context.active_tab as INT);
+
+    let queue = move |shared: &Arc<Mutex<Shared>>, request: Request| {
+        shared.lock().unwrap().commands.push(ScriptCommand::Control(request));
+    };
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("send_text", move |text: &str| {
+        queue(&s, Request::SendText { pane: None, text: text.to_string() });
+    });
+    let s = Arc::clone(&shared);
+    engine.register_fn("send_text", move |pane: INT, text: &str| -> Result<()> {
+        queue(&s, Request::SendText { pane: Some(pane_id(pane)?), text: text.to_string() });
+        Ok(())
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("split_pane", move |side: &str| -> Result<()> {
+        queue(&s, Request::SplitPane { pane: None, side: split_side(side)? });
+        Ok(())
+    });
+    let s = Arc::clone(&shared);
+    engine.register_fn("split_pane", move |pane: INT, side: &str| -> Result<()> {
+        queue(&s, Request::SplitPane { pane: Some(pane_id(pane)?), side: split_side(side)? });
+        Ok(())
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("new_tab", move || queue(&s, Request::NewTab { cwd: None, title: None }));
+    let s = Arc::clone(&shared);
+    engine.register_fn("new_tab", move |cwd: &str| {
+        queue(&s, Request::NewTab { cwd: Some(cwd.to_string()), title: None });
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("activate_tab", move |index: INT| -> Result<()> {
+        let index = usize::try_from(index).map_err(|_| format!("activate_tab: bad index {index}"))?;
+        queue(&s, Request::ActivateTab { index });
+        Ok(())
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("activate_pane", move |pane: INT| -> Result<()> {
+        queue(&s, Request::ActivatePane { pane: pane_id(pane)? });
+        Ok(())
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("close_pane", move || queue(&s, Request::ClosePane { pane: None }));
+    let s = Arc::clone(&shared);
+    engine.register_fn("close_pane", move |pane: INT| -> Result<()> {
+        queue(&s, Request::ClosePane { pane: Some(pane_id(pane)?) });
+        Ok(())
+    });
+
+    // ── Clipboard and environment ────────────────────────────────────
+
+    engine.register_fn("clipboard", || -> String {
+        arboard::Clipboard::new().and_then(|mut c| c.get_text()).unwrap_or_default()
+    });
+
+    let s = Arc::clone(&shared);
+    engine.register_fn("set_clipboard", move |text: &str| {
+        s.lock().unwrap().commands.push(ScriptCommand::SetClipboard(text.to_string()));
+    });
+
+    engine.register_fn("env", |name: &str| -> Dynamic {
+        std::env::var(name).map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+    });
+    engine.register_fn("os", || std::env::consts::OS);
+
+    engine
+}
+
+/// Lay the `set` calls, in order, into a TOML table.
+pub(super) fn overrides_table(overrides: &[(String, Dynamic)]) -> std::result::Result<toml::Table, String> {
+    let mut root = toml::Table::new();
+    for (key, value) in overrides {
+        let value = to_toml(value).map_err(|e| format!("set(\"{key}\"): {e}"))?;
+        let (path, leaf) = key.rsplit_once('.').map_or(("", key.as_str()), |(p, l)| (p, l));
+        let mut table = &mut root;
+        for segment in path.split('.').filter(|s| !s.is_empty()) {
+            let entry = table
+                .entry(segment.to_string())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            table = entry
+                .as_table_mut()
+                .ok_or_else(|| format!("set(\"{key}\"): '{segment}' is already a value"))?;
+        }
+        table.insert(leaf.to_string(), value);
+    }
+    Ok(root)
+}
+
+fn to_toml(value: &Dynamic) -> std::result::Result<toml::Value, String> {
+    if let Some(v) = value.clone().try_cast::<INT>() {
+        return Ok(toml::Value::Integer(v));
+    }
+    if let Some(v) = value.clone().try_cast::<rhai::FLOAT>() {
+        return Ok(toml::Value::Float(v));
+    }
+    if let Some(v) = value.clone().try_cast::<bool>() {
+        return Ok(toml::Value::Boolean(v));
+    }
+    if value.is_string() || value.is_char() {
+        return Ok(toml::Value::String(value.to_string()));
+    }
+    if let Some(items) = value.clone().try_cast::<Array>() {
+        return items.iter().map(to_toml).collect::<std::result::Result<_, _>>().map(toml::Value::Array);
+    }
+    if let Some(map) = value.clone().try_cast::<Map>() {
+        let mut table = toml::Table::new();
+        for (k, v) in &map {
+            table.insert(k.to_string(), to_toml(v)?);
+        }
+        return Ok(toml::Value::Table(table));
+    }
+    Err(format!("{} is not a config value", value.type_name()))
+}
+
+fn pane_map(pane: &crate::control::protocol::PaneEntry) -> Map {
+    let mut map = Map::new();
+    map.insert("id".into(), (pane.id as INT).into());
+    map.insert("tab".into(), (pane.tab as INT).into());
+    map.insert("tab_title".into(), pane.tab_title.clone().into());
+    map.insert("cwd".into(), pane.cwd.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT));
+    map.insert("cols".into(), (pane.cols as INT).into());
+    map.insert("rows".into(), (pane.rows as INT).into());
+    map.insert("focused".into(), pane.focused.into());
+    map
+}
+
+fn pane_id(pane: INT) -> Result<u32> {
+    u32::try_from(pane).map_err(|_| format!("bad pane id {pane}").into())
+}
+
+fn split_side(side: &str) -> Result<SplitSide> {
+    match side {
+        "right" => Ok(SplitSide::Right),
+        "down" => Ok(SplitSide::Down),
+        _ => Err(format!("split_pane: side must be \"right\" or \"down\", not '{side}'").into()),
+    }
+}
@@ -0,0 +1,296 @@
+// Scripting: an optional Rhai script (init.rhai beside the config file) that
+// runs whenever the config loads. It can compute config values with
+// `set("font.size", 15.0)`, bind keys to callbacks with
+// `bind("ctrl+alt+g", || send_text("git status\n"))` and subscribe to events
+// with `on("bell", |event| ...)`.
+//
+// Scripts are sandboxed: no file, process or network access, bounded
+// operations and sizes. Callbacks act on the window only through the
+// functions in `api`, which queue `ScriptCommand`s for the window to carry
+// out once the callback returns.
+
+mod api;
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rhai::{Dynamic, Engine, FnPtr, Map, AST};
+
+use crate::control::protocol::{PaneEntry, Request};
+
+/// Script file used when `scripting.script` is empty, beside the config.
+pub const DEFAULT_SCRIPT: &str = "init.rhai";
+
+/// Something a script can subscribe to with `on(name, callback)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptEvent {
+    /// A shell-integrated command finished: `pane`, `exit_code` (or ()),
+    /// `duration_ms`, `command` (the typed line, or "").
+    CommandFinished,
+    /// A pane rang the bell: `pane`.
+    Bell,
+    /// Another tab became active: `index`, `previous`.
+    TabSwitched,
+}
+
+impl ScriptEvent {
+    pub const ALL: [ScriptEvent; 3] = [ScriptEvent::CommandFinished, ScriptEvent::Bell, ScriptEvent::TabSwitched];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptEvent::CommandFinished => "command_finished",
+            ScriptEvent::Bell => "bell",
+            ScriptEvent::TabSwitched => "tab_switched",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.name() == name)
+    }
+}
+
+/// What a callback asked the window to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptCommand {
+    /// A pane or tab command, as `veloterm ctl` sends them.
+    Control(Request),
+    /// Put text on the clipboard.
+    SetClipboard(String),
+}
+
+/// The window as callbacks see it.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptContext {
+    pub panes: Vec<PaneEntry>,
+    pub active_tab: usize,
+}
+
+/// State shared between the host and the functions scripts call.
+#[derive(Default)]
+struct Shared {
+    overrides: Vec<(String, Dynamic)>,
+    bindings: Vec<(String, FnPtr)>,
+    hooks: Vec<(ScriptEvent, FnPtr)>,
+    commands: Vec<ScriptCommand>,
+    context: ScriptContext,
+}
+
+/// A loaded script: its compiled code and what it registered.
+pub struct ScriptHost {
+    engine: Engine,
+    ast: AST,
+    shared: Arc<Mutex<Shared>>,
+    bindings: Vec<(String, FnPtr)>,
+    hooks: Vec<(ScriptEvent, FnPtr)>,
+    overrides: toml::Table,
+}
+
+/// The script for `script` (the `scripting.script` setting) and a config
+/// file at `config_path`: relative paths are beside the config, `~/` is
+/// home, empty is [`DEFAULT_SCRIPT`].
+pub fn script_path(script: &str, config_path: &Path) -> PathBuf {
+    let dir = config_path.parent().unwrap_or(Path::new("."));
+    let script = script.trim();
+    if script.is_empty() {
+        return dir.join(DEFAULT_SCRIPT);
+    }
+    match script.strip_prefix("~/") {
+        Some(rest) => std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(rest),
+        None => dir.join(script),
+    }
+}
+
+impl ScriptHost {
+    /// Compile and run the script at `path`, collecting its settings,
+    /// bindings and hooks.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::from_source(&source).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Compile and run a script given as text.
+    pub fn from_source(source: &str) -> Result<Self, String> {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let engine = api::engine(Arc::clone(&shared));
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        engine.run_ast(&ast).map_err(|e| e.to_string())?;
+
+        let mut registered = shared.lock().unwrap();
+        let overrides = api::overrides_table(&registered.overrides)?;
+        let bindings = std::mem::take(&mut registered.bindings);
+        let hooks = std::mem::take(&mut registered.hooks);
+        // Top-level calls to window functions have no window to act on
+        registered.commands.clear();
+        drop(registered);
+        Ok(Self { engine, ast, shared, bindings, hooks, overrides })
+    }
+
+    /// Config values set with `set`, as a TOML table to lay over the file.
+    pub fn overrides(&self) -> &toml::Table {
+        &self.overrides
+    }
+
+    /// Key combos bound with `bind`, in order; the n-th is binding n.
+    pub fn binding_combos(&self) -> impl Iterator<Item = &str> {
+        self.bindings.iter().map(|(combo, _)| combo.as_str())
+    }
+
+    /// Whether any callback is subscribed to `event`.
+    pub fn has_hooks(&self, event: ScriptEvent) -> bool {
+        self.hooks.iter().any(|(e, _)| *e == event)
+    }
+
+    /// Run binding `index` and return what it asked for.
+    pub fn run_binding(&self, index: usize, context: ScriptContext) -> Vec<ScriptCommand> {
+        let Some((combo, callback)) = self.bindings.get(index) else {
+            return Vec::new();
+        };
+        self.run(context, |engine, ast| {
+            if let Err(e) = callback.call::<Dynamic>(engine, ast, ()) {
+                log::warn!("Script binding {combo}: {e}");
+            }
+        })
+    }
+
+    /// Run the callbacks subscribed to `event` with its `data` and return
+    /// what they asked for.
+    pub fn emit(&self, event: ScriptEvent, data: Map, context: ScriptContext) -> Vec<ScriptCommand> {
+        self.run(context, |engine, ast| {
+            for (_, callback) in self.hooks.iter().filter(|(e, _)| *e == event) {
+                if let Err(e) = callback.call::<Dynamic>(engine, ast, (data.clone(),)) {
+                    log::warn!("Script hook {}: {e}", event.name());
+                }
+            }
+        })
+    }
+
+    fn run(&self, context: ScriptContext, call: impl FnOnce(&Engine, &AST)) -> Vec<ScriptCommand> {
+        self.shared.lock().unwrap().context = context;
+        call(&self.engine, &self.ast);
+        std::mem::take(&mut self.shared.lock().unwrap().commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> ScriptContext {
+        ScriptContext {
+            panes: vec![PaneEntry {
+                id: 3,
+                tab: 0,
+                tab_title: "zsh".into(),
+                cwd: Some("/tmp".into()),
+                cols: 80,
+                rows: 24,
+                focused: true,
+            }],
+            active_tab: 0,
+        }
+    }
+
+    #[test]
+    fn set_builds_override_table() {
+        let host = ScriptHost::from_source(
+            r#"
+            let size = if os() == "macos" { 14.0 } else { 13.0 };
+            set("font.size", size + 1.0);
+            set("colors.theme", "claude_light");
+            set("scrollback.lines", 5000);
+            "#,
+        )
+        .unwrap();
+        let overrides = host.overrides();
+        let font = overrides["font"].as_table().unwrap();
+        assert!(matches!(font["size"].as_float(), Some(s) if s == 15.0 || s == 14.0));
+        assert_eq!(overrides["colors"]["theme"].as_str(), Some("claude_light"));
+        assert_eq!(overrides["scrollback"]["lines"].as_integer(), Some(5000));
+    }
+
+    #[test]
+    fn bindings_queue_window_commands() {
+        let host = ScriptHost::from_source(
+            r#"
+            send_text("ignored at top level");
+            bind("ctrl+alt+g", || {
+                let pane = panes()[0];
+                send_text(pane.id, "git status\n");
+                set_clipboard(pane.cwd);
+            });
+            bind("ctrl+alt+s", || split_pane("down"));
+            "#,
+        )
+        .unwrap();
+        assert_eq!(host.binding_combos().collect::<Vec<_>>(), ["ctrl+alt+g", "ctrl+alt+s"]);
+        assert_eq!(
+            host.run_binding(0, context()),
+            vec![
+                ScriptCommand::Control(Request::SendText { pane: Some(3), text: "git status\n".into() }),
+                ScriptCommand::SetClipboard("/tmp".into()),
+            ]
+        );
+        assert_eq!(
+            host.run_binding(1, context()),
+            vec![ScriptCommand::Control(Request::SplitPane {
+                pane: None,
+                side: crate::control::protocol::SplitSide::Down
+            })]
+        );
+        assert!(host.run_binding(9, context()).is_empty());
+    }
+
+    #[test]
+    fn hooks_receive_event_data() {
+        let host = ScriptHost::from_source(
+            r#"
+            let threshold = 1000;
+            on("command_finished", |event| {
+                if event.exit_code != 0 && event.duration_ms > threshold {
+                    send_text(event.pane, "echo failed\n");
+                }
+            });
+            "#,
+        )
+        .unwrap();
+        assert!(host.has_hooks(ScriptEvent::CommandFinished));
+        assert!(!host.has_hooks(ScriptEvent::Bell));
+        let mut data = Map::new();
+        data.insert("pane".into(), Dynamic::from(3_i64));
+        data.insert("exit_code".into(), Dynamic::from(1_i64));
+        data.insert("duration_ms".into(), Dynamic::from(5000_i64));
+        assert_eq!(
+            host.emit(ScriptEvent::CommandFinished, data, context()),
+            vec![ScriptCommand::Control(Request::SendText { pane: Some(3), text: "echo failed\n".into() })]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_scripts_and_unknown_events() {
+        assert!(ScriptHost::from_source("let x = ;").is_err());
+        assert!(ScriptHost::from_source(r#"on("reboot", || 1);"#).is_err());
+        assert!(ScriptHost::from_source(r#"set("scripting.enabled", false);"#).is_err());
+        // Runaway loops hit the operation limit
+        assert!(ScriptHost::from_source("loop { }").is_err());
+    }
+
+    #[test]
+    fn import_cannot_read_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("secret.rhai");
+        std::fs::write(&module, "export const VALUE = 42;").unwrap();
+        let source = format!(r#"import "{}" as m; set("font.size", m::VALUE);"#, module.with_extension("").display());
+        assert!(ScriptHost::from_source(&source).is_err());
+    }
+
+    #[test]
+    fn script_path_resolves_beside_config() {
+        let config = Path::new("/home/u/.config/veloterm/config.toml");
+        assert_eq!(script_path("", config), PathBuf::from("/home/u/.config/veloterm/init.rhai"));
+        assert_eq!(script_path("hooks.rhai", config), PathBuf::from("/home/u/.config/veloterm/hooks.rhai"));
+        assert_eq!(script_path("/etc/v.rhai", config), PathBuf::from("/etc/v.rhai"));
+    }
+}
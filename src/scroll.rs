@@ -3,6 +3,9 @@
 
 use std::time::Instant;
 
+use crate::pane::divider::OverlayQuad;
+use crate::pane::Rect;
+
 /// Per-pane scroll state tracking smooth animation and auto-hide.
 pub struct ScrollState {
     /// Target display offset in lines (integer, what we're animating toward).
@@ -230,6 +233,31 @@ impl ScrollState {
     }
 }
 
+/// When the scrollbar is shown (`scrollback.scrollbar`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollbarMode {
+    /// Fade in while scrolling, out after a pause.
+    #[default]
+    Auto,
+    /// Track and thumb stay visible whenever there is scrollback.
+    Always,
+    /// Never drawn and not clickable.
+    Never,
+}
+
+impl ScrollbarMode {
+    /// Convert a config string ("auto", "always", "never").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
 /// Scrollbar width in physical pixels.
 const SCROLLBAR_WIDTH: f32 = 6.0;
 
@@ -316,6 +344,44 @@ pub fn scrollbar_thumb_rect(
     })
 }
 
+/// The scrollbar track for a pane: the full-height column the thumb moves
+/// in. Same arguments as [`scrollbar_thumb_rect`]; `None` when the pane is too
+/// short for a track.
+pub fn scrollbar_track_rect(
+    pane_x: f32,
+    pane_y: f32,
+    pane_w: f32,
+    pane_h: f32,
+    padding: [f32; 4],
+) -> Option<Rect> {
+    let track_height = pane_h - padding[0] - padding[1];
+    if track_height <= 0.0 {
+        return None;
+    }
+    let x = pane_x + pane_w - padding[3] - SCROLLBAR_WIDTH;
+    Some(Rect::new(x, pane_y + padding[0], SCROLLBAR_WIDTH, track_height))
+}
+
+/// Overlay quads for a scrollbar at opacity `alpha`: the track first (when
+/// given), then the thumb.
+pub fn scrollbar_quads(thumb: &ScrollbarThumb, track: Option<Rect>, alpha: f32) -> Vec<OverlayQuad> {
+    let radius = SCROLLBAR_WIDTH / 2.0;
+    let mut quads = Vec::with_capacity(2);
+    if let Some(track) = track {
+        quads.push(OverlayQuad {
+            rect: track,
+            color: [1.0, 1.0, 1.0, alpha * 0.15],
+            border_radius: radius,
+        });
+    }
+    quads.push(OverlayQuad {
+        rect: Rect::new(thumb.x, thumb.y, thumb.width, thumb.height),
+        color: [1.0, 1.0, 1.0, alpha * 0.5],
+        border_radius: radius,
+    });
+    quads
+}
+
 /// Hit-test result for scrollbar interaction.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ScrollbarHit {
@@ -397,6 +463,157 @@ pub fn track_click_to_offset(
     offset.min(history_size)
 }
 
+// ── Horizontal scrolling ─────────────────────────────────────────────
+
+/// Columns per discrete mouse wheel notch when scrolling sideways.
+const COLUMNS_PER_NOTCH: f32 = 6.0;
+
+/// Which columns a pane shows when its terminal is wider than the pane
+/// (`panes.min_columns`, `panes.fixed_columns`). Everything else in the
+/// pane is laid out on the shown columns; these helpers move between them
+/// and terminal columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HorizontalScroll {
+    /// First terminal column shown.
+    offset: usize,
+    /// Columns the pane has room for (0 = all of them).
+    visible: usize,
+    /// Terminal cursor column last kept in view.
+    followed: Option<usize>,
+    /// Fractional columns from pixel deltas.
+    pixel_accumulator: f32,
+}
+
+impl HorizontalScroll {
+    /// First terminal column shown.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Columns shown of a terminal `total` columns wide.
+    pub fn visible(&self, total: usize) -> usize {
+        if self.visible == 0 {
+            total
+        } else {
+            self.visible.min(total)
+        }
+    }
+
+    /// Whether some of the `total` columns are off screen.
+    pub fn is_scrollable(&self, total: usize) -> bool {
+        self.visible(total) < total
+    }
+
+    /// The pane now has room for `visible` of `total` columns.
+    pub fn set_visible(&mut self, visible: usize, total: usize) {
+        self.visible = visible;
+        self.clamp(total);
+    }
+
+    /// Scroll by whole columns (positive = right).
+    pub fn scroll_columns(&mut self, columns: isize, total: usize) {
+        self.offset = self.offset.saturating_add_signed(columns);
+        self.clamp(total);
+    }
+
+    /// Apply a discrete wheel delta. Positive = scroll left, as winit
+    /// reports wheel-up and swipe-right.
+    pub fn apply_line_delta(&mut self, delta: f32, total: usize) {
+        self.scroll_columns(-(delta * COLUMNS_PER_NOTCH).round() as isize, total);
+    }
+
+    /// Apply a touchpad pixel delta, keeping the sub-column remainder.
+    pub fn apply_pixel_delta(&mut self, delta: f32, cell_width: f32, total: usize) {
+        if cell_width <= 0.0 {
+            return;
+        }
+        self.pixel_accumulator -= delta / cell_width;
+        let columns = self.pixel_accumulator.trunc();
+        self.pixel_accumulator -= columns;
+        self.scroll_columns(columns as isize, total);
+    }
+
+    /// Scroll just enough to show terminal column `col`.
+    pub fn reveal(&mut self, col: usize, total: usize) {
+        let visible = self.visible(total);
+        if col < self.offset {
+            self.offset = col;
+        } else if col >= self.offset + visible {
+            self.offset = col + 1 - visible;
+        }
+        self.clamp(total);
+    }
+
+    /// Keep the terminal cursor in view when it moves, so typing and output
+    /// are followed; a cursor that stays put leaves the user's position alone.
+    pub fn follow(&mut self, cursor_col: usize, total: usize) {
+        if self.followed != Some(cursor_col) {
+            self.followed = Some(cursor_col);
+            self.reveal(cursor_col, total);
+        }
+    }
+
+    /// Pane column showing terminal column `col`, if on screen.
+    pub fn to_display(&self, col: usize, total: usize) -> Option<usize> {
+        col.checked_sub(self.offset).filter(|&c| c < self.visible(total))
+    }
+
+    /// The shown columns of each `total`-wide row of `cells`.
+    pub fn crop<T: Copy>(&self, cells: &[T], total: usize) -> Vec<T> {
+        let visible = self.visible(total);
+        if total == 0 {
+            return Vec::new();
+        }
+        cells
+            .chunks(total)
+            .flat_map(|row| &row[self.offset..self.offset + visible])
+            .copied()
+            .collect()
+    }
+
+    fn clamp(&mut self, total: usize) {
+        self.offset = self.offset.min(total - self.visible(total));
+    }
+}
+
+/// The horizontal position bar along the bottom of a pane whose terminal
+/// is `total` columns wide with `visible` of them shown from `offset`.
+/// Same pane arguments as [`scrollbar_thumb_rect`]; returns (track, thumb),
+/// or None when nothing is off screen.
+#[allow(clippy::too_many_arguments)]
+pub fn hscrollbar_rects(
+    pane_x: f32,
+    pane_y: f32,
+    pane_w: f32,
+    pane_h: f32,
+    padding: [f32; 4],
+    visible: usize,
+    total: usize,
+    offset: usize,
+) -> Option<(Rect, ScrollbarThumb)> {
+    let track_width = pane_w - padding[2] - padding[3];
+    if visible >= total || track_width <= 0.0 {
+        return None;
+    }
+    let track = Rect::new(
+        pane_x + padding[2],
+        pane_y + pane_h - padding[1] - SCROLLBAR_WIDTH,
+        track_width,
+        SCROLLBAR_WIDTH,
+    );
+    let thumb_width = (visible as f32 / total as f32 * track_width)
+        .max(MIN_THUMB_HEIGHT)
+        .min(track_width);
+    let fraction = offset as f32 / (total - visible) as f32;
+    let thumb = ScrollbarThumb {
+        x: track.x + (track_width - thumb_width) * fraction.clamp(0.0, 1.0),
+        y: track.y,
+        width: thumb_width,
+        height: SCROLLBAR_WIDTH,
+    };
+    Some((track, thumb))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -700,6 +917,32 @@ mod tests {
         assert!(thumb.y + thumb.height <= 345.5);
     }
 
+    #[test]
+    fn track_spans_padded_height_at_thumb_column() {
+        let track = scrollbar_track_rect(100.0, 50.0, 400.0, 300.0, [5.0, 5.0, 5.0, 5.0]).unwrap();
+        assert_eq!(track, Rect::new(489.0, 55.0, 6.0, 290.0));
+        assert_eq!(scrollbar_track_rect(0.0, 0.0, 400.0, 8.0, [5.0, 5.0, 5.0, 5.0]), None);
+    }
+
+    #[test]
+    fn quads_draw_track_under_thumb() {
+        let thumb = scrollbar_thumb_rect(0.0, 0.0, 800.0, 600.0, [10.0; 4], 24, 100, 0).unwrap();
+        let track = scrollbar_track_rect(0.0, 0.0, 800.0, 600.0, [10.0; 4]);
+        let quads = scrollbar_quads(&thumb, track, 0.3);
+        assert_eq!(quads.len(), 2);
+        assert_eq!(quads[0].rect, track.unwrap());
+        assert_eq!(quads[1].rect.y, thumb.y);
+        assert!(quads[1].color[3] > quads[0].color[3]);
+        assert_eq!(scrollbar_quads(&thumb, None, 0.3).len(), 1);
+    }
+
+    #[test]
+    fn scrollbar_mode_from_config() {
+        assert_eq!(ScrollbarMode::from_config_str("always"), Some(ScrollbarMode::Always));
+        assert_eq!(ScrollbarMode::from_config_str("never"), Some(ScrollbarMode::Never));
+        assert_eq!(ScrollbarMode::from_config_str("hidden"), None);
+    }
+
     // ── Hit testing ─────────────────────────────────────────────────
 
     #[test]
@@ -847,4 +1090,68 @@ mod tests {
         s.end_drag();
         assert!(!s.is_dragging_scrollbar);
     }
+
+    // ── Horizontal scrolling ────────────────────────────────────────
+
+    #[test]
+    fn horizontal_scroll_clamps_to_hidden_columns() {
+        let mut h = HorizontalScroll::default();
+        assert!(!h.is_scrollable(120));
+        h.set_visible(80, 120);
+        assert!(h.is_scrollable(120));
+        h.scroll_columns(100, 120);
+        assert_eq!(h.offset(), 40);
+        h.apply_line_delta(1.0, 120);
+        assert_eq!(h.offset(), 34);
+        h.scroll_columns(-100, 120);
+        assert_eq!(h.offset(), 0);
+        // Widening the pane pulls the view back
+        h.scroll_columns(40, 120);
+        h.set_visible(100, 120);
+        assert_eq!(h.offset(), 20);
+    }
+
+    #[test]
+    fn horizontal_pixel_deltas_accumulate() {
+        let mut h = HorizontalScroll::default();
+        h.set_visible(80, 120);
+        h.apply_pixel_delta(-6.0, 10.0, 120);
+        assert_eq!(h.offset(), 0);
+        h.apply_pixel_delta(-6.0, 10.0, 120);
+        assert_eq!(h.offset(), 1);
+    }
+
+    #[test]
+    fn follow_reveals_only_a_moving_cursor() {
+        let mut h = HorizontalScroll::default();
+        h.set_visible(10, 30);
+        h.follow(15, 30);
+        assert_eq!(h.offset(), 6);
+        assert_eq!(h.to_display(15, 30), Some(9));
+        // The user scrolls away; the cursor did not move
+        h.scroll_columns(-6, 30);
+        h.follow(15, 30);
+        assert_eq!(h.offset(), 0);
+        assert_eq!(h.to_display(15, 30), None);
+        h.follow(16, 30);
+        assert_eq!(h.offset(), 7);
+    }
+
+    #[test]
+    fn crop_takes_visible_columns_of_each_row() {
+        let mut h = HorizontalScroll::default();
+        h.set_visible(2, 4);
+        h.scroll_columns(1, 4);
+        assert_eq!(h.crop(&[1, 2, 3, 4, 5, 6, 7, 8], 4), vec![2, 3, 6, 7]);
+    }
+
+    #[test]
+    fn hscrollbar_thumb_tracks_offset() {
+        let padding = [0.0, 0.0, 0.0, 0.0];
+        assert!(hscrollbar_rects(0.0, 0.0, 400.0, 300.0, padding, 80, 80, 0).is_none());
+        let (track, thumb) = hscrollbar_rects(0.0, 0.0, 400.0, 300.0, padding, 50, 100, 50).unwrap();
+        assert_eq!(track.y, 300.0 - SCROLLBAR_WIDTH);
+        assert_eq!(thumb.width, 200.0);
+        assert_eq!(thumb.x, 200.0);
+    }
 }
@@ -0,0 +1,137 @@
+// Query prefixes that narrow a search by what the text is, not what it says.
+// `!out: error` skips shell prompts and the commands typed at them (OSC 133
+// regions), `!red: failed` only matches red text, `!bold:` bold text.
+// Prefixes combine (`!out:!red: failed`); text after an unknown prefix is
+// searched as typed.
+
+use std::ops::Range;
+
+use crate::terminal::text_map::TextMap;
+
+use super::SearchSource;
+
+/// Names of the ANSI colors 0-7, as color prefixes.
+const COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// Attributes of a cell that filters look at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellStyle {
+    pub bold: bool,
+    /// ANSI palette index of the foreground (0-15). None for the default
+    /// foreground, 256-color and truecolor.
+    pub ansi_fg: Option<u8>,
+}
+
+/// A restriction from a query prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFilter {
+    /// `!out:` — only command output: lines outside shell prompts.
+    Output,
+    /// `!bold:` — only bold text.
+    Bold,
+    /// `!red:`, `!green:`... — only text in ANSI color 0-7 or its bright
+    /// variant.
+    Color(u8),
+}
+
+impl SearchFilter {
+    /// The filter a prefix names ("out", "bold", a color name).
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "out" => Some(Self::Output),
+            "bold" => Some(Self::Bold),
+            _ => COLOR_NAMES.iter().position(|&color| color == name).map(|i| Self::Color(i as u8)),
+        }
+    }
+
+    fn accepts_style(self, style: CellStyle) -> bool {
+        match self {
+            Self::Output => true,
+            Self::Bold => style.bold,
+            Self::Color(color) => style.ansi_fg.is_some_and(|fg| fg % 8 == color),
+        }
+    }
+
+    /// Whether the text at `bytes` of `line` passes. Attribute filters
+    /// need every visible character of the match to have the attribute.
+    pub fn accepts(self, source: &impl SearchSource, line: &TextMap, bytes: Range<usize>) -> bool {
+        match self {
+            Self::Output => line.cell_at(bytes.start).is_none_or(|(row, _)| !source.is_prompt(row)),
+            Self::Bold | Self::Color(_) => line.text()[bytes.clone()]
+                .char_indices()
+                .filter(|(_, c)| !c.is_whitespace())
+                .filter_map(|(i, _)| line.cell_at(bytes.start + i))
+                .all(|(row, col)| self.accepts_style(source.cell_style(row, col))),
+        }
+    }
+}
+
+/// Split the leading `!name:` prefixes off `query`, with the space after
+/// the last one. Returns the filters and the pattern left to search for.
+pub fn parse_query(query: &str) -> (Vec<SearchFilter>, &str) {
+    let mut filters = Vec::new();
+    let mut rest = query;
+    while let Some((name, after)) = rest.strip_prefix('!').and_then(|s| s.split_once(':')) {
+        let Some(filter) = SearchFilter::from_name(name) else {
+            break;
+        };
+        if !filters.contains(&filter) {
+            filters.push(filter);
+        }
+        rest = after;
+    }
+    if filters.is_empty() {
+        return (filters, query);
+    }
+    (filters, rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_are_split_off_the_pattern() {
+        assert_eq!(parse_query("!out: error"), (vec![SearchFilter::Output], "error"));
+        assert_eq!(
+            parse_query("!out:!red: failed"),
+            (vec![SearchFilter::Output, SearchFilter::Color(1)], "failed")
+        );
+        assert_eq!(parse_query("!bold:x: y"), (vec![SearchFilter::Bold], "x: y"));
+        assert_eq!(parse_query("!out:"), (vec![SearchFilter::Output], ""));
+        // Not a filter: searched as typed
+        assert_eq!(parse_query("!important: note"), (vec![], "!important: note"));
+        assert_eq!(parse_query("a !out: b"), (vec![], "a !out: b"));
+    }
+
+    /// Row 0 is a prompt; "fail" is red at columns 5-8 of every row, bold on row 1.
+    struct Styled;
+
+    impl SearchSource for Styled {
+        fn line_at(&self, row: i32) -> TextMap {
+            TextMap::from_text(row, "$ ok fail fail")
+        }
+
+        fn is_prompt(&self, row: i32) -> bool {
+            row == 0
+        }
+
+        fn cell_style(&self, row: i32, col: usize) -> CellStyle {
+            let red = (5..9).contains(&col);
+            CellStyle { bold: row == 1 && red, ansi_fg: red.then_some(9) }
+        }
+    }
+
+    #[test]
+    fn filters_check_prompts_and_cell_styles() {
+        let line = Styled.line_at(1);
+        assert!(SearchFilter::Output.accepts(&Styled, &line, 5..9));
+        assert!(!SearchFilter::Output.accepts(&Styled, &Styled.line_at(0), 5..9));
+        // Bright red counts as red
+        assert!(SearchFilter::Color(1).accepts(&Styled, &line, 5..9));
+        assert!(!SearchFilter::Color(1).accepts(&Styled, &line, 10..14));
+        assert!(!SearchFilter::Color(1).accepts(&Styled, &line, 2..9));
+        assert!(SearchFilter::Bold.accepts(&Styled, &line, 5..9));
+        assert!(!SearchFilter::Bold.accepts(&Styled, &Styled.line_at(2), 5..9));
+    }
+}
@@ -19,18 +19,13 @@ pub fn apply_search_highlights(
 ) {
     let total_rows = cells.len() / columns.max(1);
     for (i, m) in matches.iter().enumerate() {
-        if m.row < 0 || m.row as usize >= total_rows {
-            continue;
-        }
         let color = if i == current_index {
             active_color
         } else {
             match_color
         };
-        let row_start = m.row as usize * columns;
-        let col_end = m.end_col.min(columns);
-        for col in m.start_col..col_end {
-            cells[row_start + col].bg = color;
+        for index in match_cells(m, columns, total_rows) {
+            cells[index].bg = color;
         }
     }
 }
@@ -47,17 +42,24 @@ pub fn clear_search_highlights(
 ) {
     let total_rows = cells.len() / columns.max(1);
     for m in matches {
-        if m.row < 0 || m.row as usize >= total_rows {
-            continue;
-        }
-        let row_start = m.row as usize * columns;
-        let col_end = m.end_col.min(columns);
-        for col in m.start_col..col_end {
-            cells[row_start + col].bg = original_bg;
+        for index in match_cells(m, columns, total_rows) {
+            cells[index].bg = original_bg;
         }
     }
 }
 
+/// Indices of the cells `m` covers among `total_rows` rows of `columns`.
+/// A match over wrapped rows runs to the end of each row but its last.
+fn match_cells(m: &SearchMatch, columns: usize, total_rows: usize) -> impl Iterator<Item = usize> + use<> {
+    let SearchMatch { row: start_row, start_col, end_row, end_col } = *m;
+    (start_row.max(0)..=end_row.min(total_rows as i32 - 1)).flat_map(move |row| {
+        let start = if row == start_row { start_col } else { 0 };
+        let end = if row == end_row { end_col } else { columns };
+        let row_start = row as usize * columns;
+        (start.min(columns)..end.min(columns)).map(move |col| row_start + col)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +91,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: 0,
             start_col: 0,
+            end_row: 0,
             end_col: 5,
         }];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -110,6 +113,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: 0,
             start_col: 0,
+            end_row: 0,
             end_col: 5,
         }];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -129,8 +133,8 @@ mod tests {
         let cols = 20;
         let mut cells = make_cells(cols, 2, &["aaa bbb aaa", "ccc"]);
         let matches = vec![
-            SearchMatch { row: 0, start_col: 0, end_col: 3 },
-            SearchMatch { row: 0, start_col: 8, end_col: 11 },
+            SearchMatch { row: 0, start_col: 0, end_row: 0, end_col: 3 },
+            SearchMatch { row: 0, start_col: 8, end_row: 0, end_col: 11 },
         ];
         // current_index=1 means second match is active
         apply_search_highlights(&mut cells, &matches, 1, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -149,9 +153,9 @@ mod tests {
         let cols = 20;
         let mut cells = make_cells(cols, 1, &["abc abc abc"]);
         let matches = vec![
-            SearchMatch { row: 0, start_col: 0, end_col: 3 },
-            SearchMatch { row: 0, start_col: 4, end_col: 7 },
-            SearchMatch { row: 0, start_col: 8, end_col: 11 },
+            SearchMatch { row: 0, start_col: 0, end_row: 0, end_col: 3 },
+            SearchMatch { row: 0, start_col: 4, end_row: 0, end_col: 7 },
+            SearchMatch { row: 0, start_col: 8, end_row: 0, end_col: 11 },
         ];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
         // Match 0 (current) → active
@@ -173,6 +177,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: 5,
             start_col: 0,
+            end_row: 5,
             end_col: 3,
         }];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -190,6 +195,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: -1,
             start_col: 0,
+            end_row: -1,
             end_col: 3,
         }];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -207,6 +213,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: 0,
             start_col: 0,
+            end_row: 0,
             end_col: 5,
         }];
         // Apply then clear
@@ -236,8 +243,8 @@ mod tests {
         let cols = 10;
         let mut cells = make_cells(cols, 3, &["abc", "def", "abc"]);
         let matches = vec![
-            SearchMatch { row: 0, start_col: 0, end_col: 3 },
-            SearchMatch { row: 2, start_col: 0, end_col: 3 },
+            SearchMatch { row: 0, start_col: 0, end_row: 0, end_col: 3 },
+            SearchMatch { row: 2, start_col: 0, end_row: 2, end_col: 3 },
         ];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
         // Row 0 = active match
@@ -256,6 +263,7 @@ mod tests {
         let matches = vec![SearchMatch {
             row: 0,
             start_col: 3,
+            end_row: 0,
             end_col: 10,
         }];
         apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
@@ -264,4 +272,19 @@ mod tests {
         assert_eq!(cells[4].bg, ACTIVE_COLOR);
         // Should not panic accessing out of bounds
     }
+
+    #[test]
+    fn wrapped_match_covers_the_end_of_its_first_row() {
+        let cols = 5;
+        let mut cells = make_cells(cols, 3, &["xxhel", "lo", ""]);
+        // Starts above the grid, ends on row 1
+        let matches = vec![
+            SearchMatch { row: 0, start_col: 2, end_row: 1, end_col: 2 },
+            SearchMatch { row: -1, start_col: 4, end_row: 0, end_col: 1 },
+        ];
+        apply_search_highlights(&mut cells, &matches, 0, cols, MATCH_COLOR, ACTIVE_COLOR);
+        let lit: Vec<usize> = (0..cells.len()).filter(|&i| cells[i].bg != DEFAULT_BG).collect();
+        assert_eq!(lit, vec![0, 2, 3, 4, 5, 6]);
+        assert_eq!(cells[0].bg, MATCH_COLOR);
+    }
 }
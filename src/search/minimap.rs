@@ -0,0 +1,239 @@
+// Match position strip: a thin column on the pane's right edge with a tick for
+// every search match (and every error-pattern line), placed by the row's
+// position within scrollback. Clicking a tick jumps to its row.
+
+use super::SearchMatch;
+
+/// Width of the strip in physical pixels.
+pub const STRIP_WIDTH: f32 = 4.0;
+
+/// Height of one tick in physical pixels. Rows that land on the same tick
+/// are merged.
+pub const MARKER_HEIGHT: f32 = 2.0;
+
+/// Extra pixels around the strip and each tick that still count as a hit.
+const HIT_SLOP: f32 = 4.0;
+
+/// What a tick marks (selects its color in the overlay).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MarkerKind {
+    Match,
+    ErrorLine,
+    CurrentMatch,
+}
+
+/// One tick on the strip, in physical pixels (same coordinate space as the
+/// scrollbar thumb).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StripMarker {
+    pub y: f32,
+    pub height: f32,
+    /// Terminal row the tick jumps to (0 = top of screen, negative = scrollback).
+    pub row: i32,
+    /// Index into `SearchState::matches` for match ticks.
+    pub match_index: Option<usize>,
+    pub kind: MarkerKind,
+}
+
+/// The strip for one pane: its column and the ticks on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchStrip {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub markers: Vec<StripMarker>,
+}
+
+/// Position of `row` within the whole buffer, 0.0 = oldest scrollback line,
+/// 1.0 = bottom of the screen.
+pub fn row_fraction(row: i32, history_size: usize, screen_rows: usize) -> f32 {
+    let total = history_size + screen_rows;
+    if total <= 1 {
+        return 0.0;
+    }
+    let line = (row + history_size as i32).clamp(0, total as i32 - 1);
+    line as f32 / (total - 1) as f32
+}
+
+/// Lay out the strip for a pane.
+///
+/// `pane_x`, `pane_y`, `pane_w`, `pane_h` are the pane content area and
+/// `padding` is `[top, bottom, left, right]`, as for the scrollbar. The strip
+/// shares the scrollbar's right edge. Returns `None` when there is nothing
+/// to mark.
+#[allow(clippy::too_many_arguments)]
+pub fn match_strip(
+    pane_x: f32,
+    pane_y: f32,
+    pane_w: f32,
+    pane_h: f32,
+    padding: [f32; 4],
+    history_size: usize,
+    screen_rows: usize,
+    matches: &[SearchMatch],
+    current_index: usize,
+    error_rows: &[i32],
+) -> Option<MatchStrip> {
+    if matches.is_empty() && error_rows.is_empty() {
+        return None;
+    }
+    let track_top = pane_y + padding[0];
+    let track_height = pane_h - padding[0] - padding[1];
+    if track_height <= MARKER_HEIGHT {
+        return None;
+    }
+    let x = pane_x + pane_w - padding[3] - STRIP_WIDTH;
+
+    let rows = matches
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let kind = if i == current_index {
+                MarkerKind::CurrentMatch
+            } else {
+                MarkerKind::Match
+            };
+            (m.row, Some(i), kind)
+        })
+        .chain(error_rows.iter().map(|&row| (row, None, MarkerKind::ErrorLine)));
+
+    // One tick per MARKER_HEIGHT bucket; the most important kind wins.
+    let mut markers: Vec<StripMarker> = Vec::new();
+    for (row, match_index, kind) in rows {
+        let travel = track_height - MARKER_HEIGHT;
+        let offset = row_fraction(row, history_size, screen_rows) * travel;
+        let y = track_top + (offset / MARKER_HEIGHT).floor() * MARKER_HEIGHT;
+        let marker = StripMarker {
+            y,
+            height: MARKER_HEIGHT,
+            row,
+            match_index,
+            kind,
+        };
+        match markers.iter_mut().find(|m| m.y == y) {
+            Some(existing) if kind > existing.kind => *existing = marker,
+            Some(_) => {}
+            None => markers.push(marker),
+        }
+    }
+    markers.sort_by(|a, b| a.y.total_cmp(&b.y));
+
+    Some(MatchStrip {
+        x,
+        y: track_top,
+        width: STRIP_WIDTH,
+        height: track_height,
+        markers,
+    })
+}
+
+/// The tick nearest a click, if the click is on the strip and close enough
+/// to one.
+pub fn strip_hit_test(strip: &MatchStrip, click_x: f32, click_y: f32) -> Option<&StripMarker> {
+    if click_x < strip.x - HIT_SLOP || click_x > strip.x + strip.width + HIT_SLOP {
+        return None;
+    }
+    strip
+        .markers
+        .iter()
+        .map(|m| (m, (click_y - (m.y + m.height / 2.0)).abs()))
+        .filter(|(m, dist)| *dist <= m.height / 2.0 + HIT_SLOP)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(row: i32) -> SearchMatch {
+        SearchMatch {
+            row,
+            start_col: 0,
+            end_row: row,
+            end_col: 1,
+        }
+    }
+
+    fn strip(matches: &[SearchMatch], current: usize, errors: &[i32]) -> Option<MatchStrip> {
+        // 400px track, 100 history lines + 100 screen rows
+        match_strip(0.0, 0.0, 800.0, 400.0, [0.0; 4], 100, 100, matches, current, errors)
+    }
+
+    // ── Layout ──────────────────────────────────────────────────────
+
+    #[test]
+    fn row_fraction_spans_scrollback_and_screen() {
+        assert_eq!(row_fraction(-100, 100, 100), 0.0);
+        assert_eq!(row_fraction(99, 100, 100), 1.0);
+        assert!((row_fraction(0, 100, 100) - 100.0 / 199.0).abs() < 1e-6);
+        // Out-of-range rows are clamped to the ends
+        assert_eq!(row_fraction(-500, 100, 100), 0.0);
+        assert_eq!(row_fraction(0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn no_marks_means_no_strip() {
+        assert!(strip(&[], 0, &[]).is_none());
+    }
+
+    #[test]
+    fn strip_sits_on_right_edge_inside_padding() {
+        let s = match_strip(10.0, 20.0, 800.0, 400.0, [5.0, 5.0, 0.0, 8.0], 0, 10, &[at(0)], 0, &[])
+            .unwrap();
+        assert_eq!(s.x, 10.0 + 800.0 - 8.0 - STRIP_WIDTH);
+        assert_eq!(s.y, 25.0);
+        assert_eq!(s.height, 390.0);
+    }
+
+    #[test]
+    fn markers_follow_row_order() {
+        let s = strip(&[at(-100), at(0), at(99)], 0, &[]).unwrap();
+        assert_eq!(s.markers.len(), 3);
+        assert_eq!(s.markers[0].y, 0.0);
+        assert!(s.markers[1].y > s.markers[0].y && s.markers[1].y < s.markers[2].y);
+        assert!(s.markers[2].y + MARKER_HEIGHT <= s.y + s.height);
+    }
+
+    #[test]
+    fn current_match_is_marked() {
+        let s = strip(&[at(-50), at(50)], 1, &[]).unwrap();
+        assert_eq!(s.markers[0].kind, MarkerKind::Match);
+        assert_eq!(s.markers[1].kind, MarkerKind::CurrentMatch);
+        assert_eq!(s.markers[1].match_index, Some(1));
+    }
+
+    #[test]
+    fn rows_on_one_tick_merge_keeping_current() {
+        let s = strip(&[at(10), at(10), at(10)], 2, &[10]).unwrap();
+        assert_eq!(s.markers.len(), 1);
+        assert_eq!(s.markers[0].kind, MarkerKind::CurrentMatch);
+        assert_eq!(s.markers[0].match_index, Some(2));
+    }
+
+    #[test]
+    fn error_lines_without_matches_still_draw() {
+        let s = strip(&[], 0, &[-20]).unwrap();
+        assert_eq!(s.markers.len(), 1);
+        assert_eq!(s.markers[0].kind, MarkerKind::ErrorLine);
+        assert_eq!(s.markers[0].match_index, None);
+    }
+
+    // ── Hit testing ─────────────────────────────────────────────────
+
+    #[test]
+    fn click_picks_nearest_marker() {
+        let s = strip(&[at(-100), at(99)], 0, &[]).unwrap();
+        let x = s.x + 1.0;
+        assert_eq!(strip_hit_test(&s, x, 1.0).unwrap().row, -100);
+        assert_eq!(strip_hit_test(&s, x, 399.0).unwrap().row, 99);
+    }
+
+    #[test]
+    fn click_away_from_markers_misses() {
+        let s = strip(&[at(-100)], 0, &[]).unwrap();
+        assert!(strip_hit_test(&s, s.x + 1.0, 200.0).is_none());
+        assert!(strip_hit_test(&s, s.x - 50.0, 1.0).is_none());
+    }
+}
@@ -1,14 +1,35 @@
+pub mod filter;
 pub mod highlight;
+pub mod minimap;
 pub mod overlay;
 
-/// A single match in the scrollback buffer.
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::Flags as CellFlags;
+use alacritty_terminal::vte::ansi::Color as AnsiColor;
+
+use crate::terminal::text_map::TextMap;
+use crate::terminal::Terminal;
+use filter::{CellStyle, SearchFilter};
+
+/// Lines scanned per `SearchState::advance` call, so a long scrollback is
+/// searched over several frames instead of blocking one.
+pub const SCAN_CHUNK_LINES: usize = 5_000;
+
+/// A single match in the scrollback buffer, in grid cells. A match in a
+/// soft-wrapped line can end on a later row than it starts.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SearchMatch {
-    /// Row index (0 = first row of scrollback, positive = toward screen bottom).
+    /// Terminal row (0 = top of screen, negative = scrollback). Matches from
+    /// `SearchEngine::search` use the index into the lines it was given.
     pub row: i32,
     /// Starting column (inclusive).
     pub start_col: usize,
-    /// Ending column (exclusive).
+    /// Row of the last matched cell.
+    pub end_row: i32,
+    /// Ending column on `end_row` (exclusive).
     pub end_col: usize,
 }
 
@@ -23,23 +44,40 @@ pub struct SearchResult {
     pub error: Option<String>,
 }
 
-/// Regex search engine over terminal content lines.
-pub struct SearchEngine;
+/// How a query matches text. The default is a case-insensitive regex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Matches must start and end at word boundaries.
+    pub whole_word: bool,
+    /// The query is plain text, not a regex.
+    pub literal: bool,
+}
 
-impl Default for SearchEngine {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Regex search engine over terminal content lines.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchEngine {
+    pub options: SearchOptions,
 }
 
 impl SearchEngine {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_options(options: SearchOptions) -> Self {
+        Self { options }
     }
 
     /// Search for `query` across `lines`. Each element of `lines` is one row of text.
-    /// Returns `SearchResult` with all matches found. Uses case-insensitive regex by default.
+    /// Returns `SearchResult` with all matches found.
     pub fn search(&self, query: &str, lines: &[String]) -> SearchResult {
+        self.search_maps(query, &TextMap::rows(lines))
+    }
+
+    /// Search for `query` in grid text (see `grid_bridge::extract_text_maps`),
+    /// one logical line per map. Matches are in grid cells.
+    pub fn search_maps(&self, query: &str, lines: &[TextMap]) -> SearchResult {
         if query.is_empty() {
             return SearchResult {
                 matches: Vec::new(),
@@ -48,27 +86,20 @@ impl SearchEngine {
             };
         }
 
-        let pattern = format!("(?i){}", query);
-        let re = match regex::Regex::new(&pattern) {
+        let re = match self.compile(query) {
             Ok(re) => re,
-            Err(e) => {
+            Err(error) => {
                 return SearchResult {
                     matches: Vec::new(),
                     total_count: 0,
-                    error: Some(e.to_string()),
+                    error: Some(error),
                 };
             }
         };
 
         let mut matches = Vec::new();
-        for (row_idx, line) in lines.iter().enumerate() {
-            for m in re.find_iter(line) {
-                matches.push(SearchMatch {
-                    row: row_idx as i32,
-                    start_col: m.start(),
-                    end_col: m.end(),
-                });
-            }
+        for line in lines {
+            find_in_line(&re, line, |_| true, &mut matches);
         }
 
         let total_count = matches.len();
@@ -78,6 +109,107 @@ impl SearchEngine {
             error: None,
         }
     }
+
+    /// Compile `query` as a regex following the engine's options.
+    pub fn compile(&self, query: &str) -> Result<regex::Regex, String> {
+        let options = self.options;
+        let mut pattern = if options.literal {
+            regex::escape(query)
+        } else {
+            query.to_string()
+        };
+        if options.whole_word {
+            pattern = format!(r"\b(?:{pattern})\b");
+        }
+        regex::RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Append the matches of `re` in `line` that `keep` accepts (given their
+/// byte range) to `out`, mapped to grid cells.
+fn find_in_line(
+    re: &regex::Regex,
+    line: &TextMap,
+    mut keep: impl FnMut(Range<usize>) -> bool,
+    out: &mut Vec<SearchMatch>,
+) {
+    out.extend(re.find_iter(line.text()).filter_map(|m| {
+        if !keep(m.range()) {
+            return None;
+        }
+        let cells = line.cell_range(m.range())?;
+        Some(SearchMatch {
+            row: cells.start_row,
+            start_col: cells.start_col,
+            end_row: cells.end_row,
+            end_col: cells.end_col,
+        })
+    }));
+}
+
+/// Grid content for a scrollback search.
+pub trait SearchSource {
+    /// The logical line ending at `row`, from its first soft-wrapped row on
+    /// (`Terminal::line_map`).
+    fn line_at(&self, row: i32) -> TextMap;
+
+    /// Whether `row` is part of a shell prompt (OSC 133;A through B).
+    fn is_prompt(&self, _row: i32) -> bool {
+        false
+    }
+
+    /// Attributes of the cell at `row`, `col`.
+    fn cell_style(&self, _row: i32, _col: usize) -> CellStyle {
+        CellStyle::default()
+    }
+}
+
+/// Plain text without prompts or attributes.
+impl<F: Fn(i32) -> TextMap> SearchSource for F {
+    fn line_at(&self, row: i32) -> TextMap {
+        self(row)
+    }
+}
+
+impl SearchSource for &Terminal {
+    fn line_at(&self, row: i32) -> TextMap {
+        self.line_map(row)
+    }
+
+    fn is_prompt(&self, row: i32) -> bool {
+        let line = row + self.history_size() as i32;
+        line >= 0 && self.shell_state().prompt_region_at(line as usize).is_some()
+    }
+
+    fn cell_style(&self, row: i32, col: usize) -> CellStyle {
+        let history = self.history_size() as i32;
+        if row < -history || row >= self.rows() as i32 || col >= self.columns() {
+            return CellStyle::default();
+        }
+        let cell = &self.inner().grid()[Point::new(Line(row), Column(col))];
+        let ansi_fg = match cell.fg {
+            AnsiColor::Named(name) => Some(name as usize),
+            AnsiColor::Indexed(index) => Some(index as usize),
+            AnsiColor::Spec(_) => None,
+        };
+        CellStyle {
+            bold: cell.flags.contains(CellFlags::BOLD),
+            ansi_fg: ansi_fg.filter(|&index| index < 16).map(|index| index as u8),
+        }
+    }
+}
+
+/// A search still working through scrollback, newest rows first.
+struct PendingScan {
+    regex: regex::Regex,
+    error_regex: Option<regex::Regex>,
+    /// From the query's prefixes (see `filter::parse_query`).
+    filters: Vec<SearchFilter>,
+    /// Rows not yet scanned: `rows.start..rows.end`, consumed from the end.
+    rows: Range<i32>,
 }
 
 /// Manages search state: query, matches, navigation, and active status.
@@ -87,7 +219,12 @@ pub struct SearchState {
     pub current_index: usize,
     pub is_active: bool,
     pub error: Option<String>,
+    /// Rows matching the configured error pattern, marked on the match strip.
+    pub error_rows: Vec<i32>,
     engine: SearchEngine,
+    pending: Option<PendingScan>,
+    /// When the typed query last changed, while it waits to be searched.
+    queued_at: Option<Instant>,
 }
 
 impl Default for SearchState {
@@ -104,20 +241,140 @@ impl SearchState {
             current_index: 0,
             is_active: false,
             error: None,
+            error_rows: Vec::new(),
             engine: SearchEngine::new(),
+            pending: None,
+            queued_at: None,
         }
     }
 
     /// Update the search query and re-run search against provided lines.
-    /// Resets current_index to 0.
+    /// Resets current_index to 0. Plain lines have no prompts or
+    /// attributes, so query prefixes are dropped rather than applied.
     pub fn set_query(&mut self, query: &str, lines: &[String]) {
         self.query = query.to_string();
         self.current_index = 0;
-        let result = self.engine.search(query, lines);
+        self.pending = None;
+        self.queued_at = None;
+        let (_, pattern) = filter::parse_query(query);
+        let result = self.engine.search(pattern, lines);
         self.matches = result.matches;
         self.error = result.error;
     }
 
+    /// Start searching `rows` (terminal rows, 0 = top of screen, negative =
+    /// scrollback) for `query`, which may start with filter prefixes such
+    /// as `!out:` (see `filter`). Nothing is scanned yet: call `advance`
+    /// until it reports completion. Rows matching `error_pattern`
+    /// (case-sensitive, empty = none) are collected into `error_rows` along
+    /// the way.
+    pub fn start_query(&mut self, query: &str, error_pattern: &str, rows: Range<i32>) {
+        self.query = query.to_string();
+        self.current_index = 0;
+        self.matches.clear();
+        self.error_rows.clear();
+        self.error = None;
+        self.pending = None;
+        self.queued_at = None;
+        let (filters, pattern) = filter::parse_query(query);
+        if pattern.is_empty() {
+            return;
+        }
+        let regex = match self.engine.compile(pattern) {
+            Ok(re) => re,
+            Err(error) => {
+                self.error = Some(error);
+                return;
+            }
+        };
+        let error_regex = Some(error_pattern)
+            .filter(|p| !p.is_empty())
+            .and_then(|p| regex::Regex::new(p).ok());
+        self.pending = Some(PendingScan {
+            regex,
+            error_regex,
+            filters,
+            rows,
+        });
+    }
+
+    /// Scan up to `budget` more rows of `source`, newest first. Its lines
+    /// start at their first soft-wrapped row, so matches across a wrap are
+    /// found whole. The current match starts at the newest match and stays
+    /// on the same match as older ones are found. Returns true once the
+    /// search is complete (or there is none).
+    pub fn advance(&mut self, budget: usize, source: impl SearchSource) -> bool {
+        let Some(scan) = &mut self.pending else {
+            return true;
+        };
+        let stop = scan.rows.end.saturating_sub(budget as i32).max(scan.rows.start);
+        let mut lines = Vec::new();
+        while scan.rows.end > stop {
+            let line = source.line_at(scan.rows.end - 1);
+            scan.rows.end = line.first_row().map_or(scan.rows.end - 1, |first| first.min(scan.rows.end - 1));
+            lines.push(line);
+        }
+        let mut found = Vec::new();
+        let mut error_rows = Vec::new();
+        for line in lines.iter().rev() {
+            let keep = |bytes: Range<usize>| {
+                scan.filters.iter().all(|filter| filter.accepts(&source, line, bytes.clone()))
+            };
+            find_in_line(&scan.regex, line, keep, &mut found);
+            let error = scan.error_regex.as_ref().and_then(|re| re.find(line.text()));
+            if let Some((row, _)) = error.and_then(|m| line.cell_at(m.start())) {
+                error_rows.push(row);
+            }
+        }
+        let done = scan.rows.is_empty();
+
+        if self.matches.is_empty() {
+            self.current_index = found.len().saturating_sub(1);
+        } else {
+            self.current_index += found.len();
+        }
+        found.append(&mut self.matches);
+        self.matches = found;
+        error_rows.append(&mut self.error_rows);
+        self.error_rows = error_rows;
+
+        if done {
+            self.pending = None;
+        }
+        done
+    }
+
+    /// Drop the query, matches and any scan in progress.
+    pub fn clear(&mut self) {
+        self.start_query("", "", 0..0);
+    }
+
+    /// Note that `query` was edited at `now`. The old matches stay until
+    /// `query_due` says typing has paused and the new search starts.
+    pub fn queue_query(&mut self, now: Instant) {
+        self.queued_at = Some(now);
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        self.engine.options
+    }
+
+    /// Change how the query matches. The caller re-runs the search.
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.engine.options = options;
+    }
+
+    /// Whether a queued query has gone `delay` without an edit.
+    pub fn query_due(&self, now: Instant, delay: Duration) -> bool {
+        self.queued_at.is_some_and(|at| now.duration_since(at) >= delay)
+    }
+
+    /// Whether the search is still in progress: waiting for typing to
+    /// pause or scanning scrollback.
+    pub fn is_scanning(&self) -> bool {
+        self.pending.is_some() || self.queued_at.is_some()
+    }
+
     /// Advance to the next match. Wraps from last → 0.
     pub fn next_match(&mut self) {
         if self.matches.is_empty() {
@@ -143,13 +400,14 @@ impl SearchState {
         self.matches.get(self.current_index)
     }
 
-    /// Returns matches visible in the given viewport range (± buffer rows).
+    /// Returns matches visible in the given viewport range (± buffer rows),
+    /// including wrapped ones only partly inside it.
     pub fn visible_matches(&self, viewport_start: i32, viewport_end: i32, buffer: i32) -> Vec<&SearchMatch> {
         let start = viewport_start - buffer;
         let end = viewport_end + buffer;
         self.matches
             .iter()
-            .filter(|m| m.row >= start && m.row <= end)
+            .filter(|m| m.end_row >= start && m.row <= end)
             .collect()
     }
 
@@ -164,6 +422,27 @@ impl SearchState {
     }
 }
 
+/// Where a match should land in the viewport when navigation scrolls to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollMargin {
+    /// Center the match vertically.
+    Center,
+    /// Keep at least this many lines between the match and the nearest edge
+    /// (capped at half the viewport).
+    Lines(usize),
+}
+
+impl ScrollMargin {
+    /// Margin from the `[search]` config.
+    pub fn from_config(config: &crate::config::types::SearchConfig) -> Self {
+        if config.center_match {
+            Self::Center
+        } else {
+            Self::Lines(config.context_lines)
+        }
+    }
+}
+
 /// Compute the display_offset needed to bring `match_row` into the visible viewport.
 ///
 /// Returns `None` if the match is already visible (no scroll needed).
@@ -173,24 +452,38 @@ impl SearchState {
 /// - `viewport_rows`: number of visible rows in the terminal
 /// - `current_offset`: current display_offset (0 = bottom, positive = scrolled up)
 /// - `max_offset`: maximum display_offset (total scrollback lines)
+/// - `margin`: centering, or context lines to keep around the match. A match
+///   inside the margin counts as not visible; a match reached by scrolling up
+///   lands `n` lines below the top, one reached by scrolling down `n` lines
+///   above the bottom.
 pub fn compute_scroll_offset(
     match_row: i32,
     viewport_rows: usize,
     current_offset: usize,
     max_offset: usize,
+    margin: ScrollMargin,
 ) -> Option<usize> {
     let offset = current_offset as i32;
     let vp = viewport_rows as i32;
-    // Visible row range: [-offset, -offset + viewport - 1]
-    let visible_top = -offset;
-    let visible_bottom = -offset + vp - 1;
+    let context = match margin {
+        ScrollMargin::Center => 0,
+        ScrollMargin::Lines(lines) => (lines as i32).min((vp - 1) / 2),
+    };
+    // Comfortable row range: [-offset + context, -offset + viewport - 1 - context]
+    let visible_top = -offset + context;
+    let visible_bottom = -offset + vp - 1 - context;
 
     if match_row >= visible_top && match_row <= visible_bottom {
         return None; // already visible
     }
 
-    // Scroll so match_row is at the top of the viewport
-    let new_offset = (-match_row).max(0) as usize;
+    // Screen row the match should end up on
+    let target = match margin {
+        ScrollMargin::Center => vp / 2,
+        ScrollMargin::Lines(_) if match_row < visible_top => context,
+        ScrollMargin::Lines(_) => vp - 1 - context,
+    };
+    let new_offset = (target - match_row).max(0) as usize;
     Some(new_offset.min(max_offset))
 }
 
@@ -212,7 +505,7 @@ mod tests {
         assert_eq!(result.total_count, 1);
         assert_eq!(
             result.matches[0],
-            SearchMatch { row: 0, start_col: 6, end_col: 11 }
+            SearchMatch { row: 0, start_col: 6, end_row: 0, end_col: 11 }
         );
         assert!(result.error.is_none());
     }
@@ -223,9 +516,9 @@ mod tests {
         let content = lines(&["foo bar foo baz foo"]);
         let result = engine.search("foo", &content);
         assert_eq!(result.total_count, 3);
-        assert_eq!(result.matches[0], SearchMatch { row: 0, start_col: 0, end_col: 3 });
-        assert_eq!(result.matches[1], SearchMatch { row: 0, start_col: 8, end_col: 11 });
-        assert_eq!(result.matches[2], SearchMatch { row: 0, start_col: 16, end_col: 19 });
+        assert_eq!(result.matches[0], SearchMatch { row: 0, start_col: 0, end_row: 0, end_col: 3 });
+        assert_eq!(result.matches[1], SearchMatch { row: 0, start_col: 8, end_row: 0, end_col: 11 });
+        assert_eq!(result.matches[2], SearchMatch { row: 0, start_col: 16, end_row: 0, end_col: 19 });
     }
 
     #[test]
@@ -246,8 +539,8 @@ mod tests {
         let content = lines(&["abc 123 def 456"]);
         let result = engine.search(r"\d+", &content);
         assert_eq!(result.total_count, 2);
-        assert_eq!(result.matches[0], SearchMatch { row: 0, start_col: 4, end_col: 7 });
-        assert_eq!(result.matches[1], SearchMatch { row: 0, start_col: 12, end_col: 15 });
+        assert_eq!(result.matches[0], SearchMatch { row: 0, start_col: 4, end_row: 0, end_col: 7 });
+        assert_eq!(result.matches[1], SearchMatch { row: 0, start_col: 12, end_row: 0, end_col: 15 });
     }
 
     #[test]
@@ -278,6 +571,46 @@ mod tests {
         assert_eq!(result.total_count, 3);
     }
 
+    // ── Search options ─────────────────────────────────────────────
+
+    #[test]
+    fn case_sensitive_option() {
+        let engine = SearchEngine::with_options(SearchOptions { case_sensitive: true, ..Default::default() });
+        let result = engine.search("hello", &lines(&["Hello WORLD hello World"]));
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.matches[0].start_col, 12);
+    }
+
+    #[test]
+    fn whole_word_option() {
+        let engine = SearchEngine::with_options(SearchOptions { whole_word: true, ..Default::default() });
+        let result = engine.search("err|fail", &lines(&["err error failed fail"]));
+        let starts: Vec<usize> = result.matches.iter().map(|m| m.start_col).collect();
+        assert_eq!(starts, vec![0, 17]);
+    }
+
+    #[test]
+    fn literal_option_escapes_regex_syntax() {
+        let engine = SearchEngine::with_options(SearchOptions { literal: true, ..Default::default() });
+        let result = engine.search("a.b(", &lines(&["axb( a.b("]));
+        assert!(result.error.is_none());
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.matches[0].start_col, 5);
+    }
+
+    #[test]
+    fn state_options_apply_to_the_next_scan() {
+        let mut state = SearchState::new();
+        state.set_options(SearchOptions { case_sensitive: true, ..Default::default() });
+        state.start_query("OK", "", -4..4);
+        while !state.advance(3, fake_line) {}
+        assert_eq!(state.total_count(), 0);
+        state.set_options(SearchOptions::default());
+        state.start_query("OK", "", -4..4);
+        while !state.advance(3, fake_line) {}
+        assert_eq!(state.total_count(), 4);
+    }
+
     // ── 1.1.5 Invalid regex returns error ──────────────────────────
 
     #[test]
@@ -604,7 +937,7 @@ mod tests {
     fn scroll_offset_for_match_in_scrollback() {
         // Match at row -5 (in scrollback), viewport=24 rows, currently at offset 0
         // Need to scroll up to show row -5: offset = 5
-        let result = compute_scroll_offset(-5, 24, 0, 100);
+        let result = compute_scroll_offset(-5, 24, 0, 100, ScrollMargin::Lines(0));
         assert_eq!(result, Some(5));
     }
 
@@ -641,7 +974,7 @@ mod tests {
         // For match above viewport (match_row < -offset): need offset = -match_row
         // For match below viewport (match_row > -offset + viewport - 1): offset = -(match_row - viewport + 1)
         // But offset can't be negative (can't scroll past bottom).
-        let result = compute_scroll_offset(10, 24, 20, 100);
+        let result = compute_scroll_offset(10, 24, 20, 100, ScrollMargin::Lines(0));
         // Match at row 10, viewport=24. Visible range with offset=20: [-20, 3].
         // Row 10 > 3 → need to scroll down. New offset = -(10 - 24 + 1) = -(10-23) = 13? No...
         // offset so row 10 is visible: -offset + viewport - 1 >= 10 → offset <= viewport - 1 - 10 = 13
@@ -666,14 +999,14 @@ mod tests {
     #[test]
     fn no_scroll_when_match_in_viewport() {
         // Match at row 5, viewport=24, offset=0 → visible range [0, 23]
-        let result = compute_scroll_offset(5, 24, 0, 100);
+        let result = compute_scroll_offset(5, 24, 0, 100, ScrollMargin::Lines(0));
         assert_eq!(result, None);
     }
 
     #[test]
     fn no_scroll_when_match_at_viewport_edge() {
         // Match at row -3, viewport=24, offset=3 → visible range [-3, 20]
-        let result = compute_scroll_offset(-3, 24, 3, 100);
+        let result = compute_scroll_offset(-3, 24, 3, 100, ScrollMargin::Lines(0));
         assert_eq!(result, None);
     }
 
@@ -682,7 +1015,7 @@ mod tests {
     #[test]
     fn scroll_offset_clamped_to_max() {
         // Match at row -200, viewport=24, max_offset=100
-        let result = compute_scroll_offset(-200, 24, 0, 100);
+        let result = compute_scroll_offset(-200, 24, 0, 100, ScrollMargin::Lines(0));
         assert!(result.is_some());
         assert!(result.unwrap() <= 100);
     }
@@ -691,9 +1024,202 @@ mod tests {
     fn scroll_offset_clamped_to_zero_minimum() {
         // Match at row 20, viewport=24, offset=50 → match below visible area?
         // Visible range: [-50, -27]. Row 20 is way below.
-        // Need offset = max(0, -20) = 0
-        let result = compute_scroll_offset(20, 24, 50, 100);
+        // Centering needs offset = max(0, 12 - 20) = 0
+        let result = compute_scroll_offset(20, 24, 50, 100, ScrollMargin::Center);
         assert!(result.is_some());
         assert!(result.unwrap() == 0);
     }
+
+    // ── Scroll margin ───────────────────────────────────────────────
+
+    #[test]
+    fn scroll_margin_centers_match_from_scrollback() {
+        // Row -50 centered in 24 rows → screen row 12 → offset 62
+        let result = compute_scroll_offset(-50, 24, 0, 100, ScrollMargin::Center);
+        assert_eq!(result, Some(62));
+    }
+
+    #[test]
+    fn scroll_margin_lines_keeps_context_above_match() {
+        // Scrolling up: match lands 3 lines below the top
+        let result = compute_scroll_offset(-50, 24, 0, 100, ScrollMargin::Lines(3));
+        assert_eq!(result, Some(53));
+    }
+
+    #[test]
+    fn scroll_margin_lines_keeps_context_below_match() {
+        // Visible [-40, -17]; row -10 is below → lands 3 lines above the bottom
+        let result = compute_scroll_offset(-10, 24, 40, 100, ScrollMargin::Lines(3));
+        assert_eq!(result, Some(30));
+        let offset = result.unwrap() as i32;
+        assert_eq!(-10 + offset, 20);
+    }
+
+    #[test]
+    fn scroll_margin_lines_scrolls_when_match_is_inside_margin() {
+        // Row -1 is visible on the top row but inside a 3-line margin
+        let result = compute_scroll_offset(-1, 24, 1, 100, ScrollMargin::Lines(3));
+        assert_eq!(result, Some(4));
+        // Row 5 clears the margin → no scroll
+        assert_eq!(compute_scroll_offset(5, 24, 1, 100, ScrollMargin::Lines(3)), None);
+    }
+
+    #[test]
+    fn scroll_margin_is_capped_at_half_viewport() {
+        // A 100-line margin on a 5-row viewport behaves like centering
+        let result = compute_scroll_offset(-50, 5, 0, 100, ScrollMargin::Lines(100));
+        assert_eq!(result, Some(52));
+    }
+
+    #[test]
+    fn scroll_margin_from_config() {
+        let mut config = crate::config::types::SearchConfig::default();
+        assert_eq!(ScrollMargin::from_config(&config), ScrollMargin::Center);
+        config.center_match = false;
+        config.context_lines = 5;
+        assert_eq!(ScrollMargin::from_config(&config), ScrollMargin::Lines(5));
+    }
+
+
+    // ── Incremental scrollback scan ─────────────────────────────────
+
+    /// Rows -4..4 of a fake terminal: "err N" on scrollback rows, "ok N" on screen.
+    fn fake_line(row: i32) -> TextMap {
+        if row < 0 {
+            TextMap::from_text(row, &format!("err {row}"))
+        } else {
+            TextMap::from_text(row, &format!("ok {row}"))
+        }
+    }
+
+    #[test]
+    fn scan_runs_over_several_advances_newest_first() {
+        let mut state = SearchState::new();
+        state.start_query("ok|err", "", -4..4);
+        assert!(state.is_scanning());
+        assert!(state.matches.is_empty());
+
+        assert!(!state.advance(3, fake_line));
+        assert_eq!(state.matches.iter().map(|m| m.row).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(!state.advance(3, fake_line));
+        assert!(state.advance(3, fake_line));
+        assert!(!state.is_scanning());
+        let rows: Vec<i32> = state.matches.iter().map(|m| m.row).collect();
+        assert_eq!(rows, (-4..4).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn scan_keeps_current_on_newest_match() {
+        let mut state = SearchState::new();
+        state.start_query("ok", "", -4..4);
+        state.advance(2, fake_line);
+        assert_eq!(state.current_match().unwrap().row, 3);
+        state.prev_match();
+        assert_eq!(state.current_match().unwrap().row, 2);
+        // Older rows found later shift indices but not the current match
+        while !state.advance(2, fake_line) {}
+        assert_eq!(state.current_match().unwrap().row, 2);
+    }
+
+    /// `fake_line` with prompts on the odd rows.
+    struct Prompted;
+
+    impl SearchSource for Prompted {
+        fn line_at(&self, row: i32) -> TextMap {
+            fake_line(row)
+        }
+
+        fn is_prompt(&self, row: i32) -> bool {
+            row % 2 != 0
+        }
+    }
+
+    #[test]
+    fn scan_applies_query_prefixes() {
+        let mut state = SearchState::new();
+        state.start_query("!out: ok|err", "", -4..4);
+        assert_eq!(state.query, "!out: ok|err");
+        while !state.advance(3, Prompted) {}
+        let rows: Vec<i32> = state.matches.iter().map(|m| m.row).collect();
+        assert_eq!(rows, vec![-4, -2, 0, 2]);
+        // Nothing in fake_line is bold
+        state.start_query("!bold: ok", "", -4..4);
+        while !state.advance(3, Prompted) {}
+        assert_eq!(state.total_count(), 0);
+        state.start_query("!out:", "", -4..4);
+        assert!(!state.is_scanning());
+    }
+
+    #[test]
+    fn terminal_reports_prompts_and_colors() {
+        let mut term = Terminal::new(20, 4, 100);
+        // Shell events land on the cursor line at the end of each feed
+        term.feed(b"\x1b]133;A\x07$ make");
+        term.feed(b"\x1b]133;B\x07");
+        term.feed(b"\r\n\x1b[1;31mfailed\x1b[0m ok\r\n");
+        let source = &term;
+        assert!(source.is_prompt(0));
+        assert!(!source.is_prompt(1));
+        assert_eq!(source.cell_style(1, 0), CellStyle { bold: true, ansi_fg: Some(1) });
+        assert_eq!(source.cell_style(1, 7), CellStyle::default());
+        let mut state = SearchState::new();
+        state.start_query("!red: failed|ok", "", 0..4);
+        while !state.advance(10, source) {}
+        assert_eq!(state.matches, vec![SearchMatch { row: 1, start_col: 0, end_row: 1, end_col: 6 }]);
+    }
+
+    #[test]
+    fn scan_collects_error_rows() {
+        let mut state = SearchState::new();
+        state.start_query("ok", "err", -4..4);
+        while !state.advance(3, fake_line) {}
+        assert_eq!(state.error_rows, vec![-4, -3, -2, -1]);
+    }
+
+    #[test]
+    fn scan_invalid_query_sets_error_and_finishes() {
+        let mut state = SearchState::new();
+        state.start_query("(", "", -4..4);
+        assert!(state.error.is_some());
+        assert!(!state.is_scanning());
+        assert!(state.advance(10, fake_line));
+    }
+
+    #[test]
+    fn scan_empty_query_has_nothing_to_do() {
+        let mut state = SearchState::new();
+        state.start_query("", "err", -4..4);
+        assert!(!state.is_scanning());
+        assert!(state.matches.is_empty());
+        assert!(state.error_rows.is_empty());
+    }
+
+    #[test]
+    fn queued_query_waits_for_typing_to_pause() {
+        let mut state = SearchState::new();
+        let delay = Duration::from_millis(50);
+        let start = Instant::now();
+        state.query = "o".into();
+        state.queue_query(start);
+        assert!(state.is_scanning());
+        state.query = "ok".into();
+        state.queue_query(start + Duration::from_millis(30));
+        assert!(!state.query_due(start + Duration::from_millis(60), delay));
+        assert!(state.query_due(start + Duration::from_millis(80), delay));
+        let query = state.query.clone();
+        state.start_query(&query, "", -4..4);
+        assert!(!state.query_due(start + Duration::from_millis(200), delay));
+        while !state.advance(3, fake_line) {}
+        assert!(!state.is_scanning());
+        assert_eq!(state.total_count(), 4);
+    }
+
+    #[test]
+    fn set_query_cancels_scan() {
+        let mut state = SearchState::new();
+        state.start_query("ok", "", -4..4);
+        state.set_query("x", &lines(&["x"]));
+        assert!(!state.is_scanning());
+        assert_eq!(state.total_count(), 1);
+    }
 }
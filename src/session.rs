@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::notes::Note;
 use crate::pane::{PaneId, PaneNode, SplitDirection};
 use crate::tab::TabManager;
 
@@ -12,12 +13,28 @@ use crate::tab::TabManager;
 pub struct SessionState {
     pub tabs: Vec<SessionTab>,
     pub active_tab: usize,
+    /// Window size when the session was saved (absent in older files).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<SessionWindow>,
+}
+
+/// Window inner size in logical pixels, and its font scale override.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SessionWindow {
+    pub width: f64,
+    pub height: f64,
+    /// Set with `veloterm ctl set-font-scale`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_scale: Option<f64>,
 }
 
 /// Serializable snapshot of a single tab.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SessionTab {
     pub title: String,
+    /// The title was set with Rename Tab.
+    #[serde(default)]
+    pub title_is_manual: bool,
     pub pane_tree: SessionPaneNode,
 }
 
@@ -27,6 +44,8 @@ pub struct SessionTab {
 pub enum SessionPaneNode {
     Leaf {
         cwd: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        notes: Vec<Note>,
     },
     Split {
         direction: String,
@@ -36,9 +55,10 @@ pub enum SessionPaneNode {
     },
 }
 
-/// Per-pane data needed for session capture (CWD).
+/// Per-pane data needed for session capture (CWD and notes).
 pub struct PaneCwdInfo {
     pub cwd: Option<String>,
+    pub notes: Vec<Note>,
 }
 
 impl SessionState {
@@ -52,6 +72,7 @@ impl SessionState {
             .iter()
             .map(|tab| SessionTab {
                 title: tab.title.clone(),
+                title_is_manual: tab.title_is_manual,
                 pane_tree: capture_pane_node(tab.pane_tree.root(), pane_cwds),
             })
             .collect();
@@ -59,6 +80,7 @@ impl SessionState {
         Self {
             tabs,
             active_tab: tab_manager.active_index(),
+            window: None,
         }
     }
 
@@ -132,10 +154,11 @@ fn capture_pane_node(
 ) -> SessionPaneNode {
     match node {
         PaneNode::Leaf { id } => {
-            let cwd = pane_cwds
-                .get(id)
-                .and_then(|info| info.cwd.clone());
-            SessionPaneNode::Leaf { cwd }
+            let info = pane_cwds.get(id);
+            SessionPaneNode::Leaf {
+                cwd: info.and_then(|info| info.cwd.clone()),
+                notes: info.map(|info| info.notes.clone()).unwrap_or_default(),
+            }
         }
         PaneNode::Split {
             direction,
@@ -164,11 +187,12 @@ fn count_panes(node: &SessionPaneNode) -> usize {
     }
 }
 
+/// A restored pane to spawn: its new id, cwd and notes.
+pub type PaneSpawn = (PaneId, Option<String>, Vec<Note>);
+
 /// Restore a PaneTree from a session pane node, returning the new PaneIds for spawning.
-/// Returns (root PaneNode, list of (PaneId, cwd) pairs for spawning PTYs).
-pub fn restore_pane_tree(
-    session_node: &SessionPaneNode,
-) -> (PaneNode, Vec<(PaneId, Option<String>)>) {
+/// Returns (root PaneNode, list of (PaneId, cwd, notes) for spawning PTYs).
+pub fn restore_pane_tree(session_node: &SessionPaneNode) -> (PaneNode, Vec<PaneSpawn>) {
     let mut pane_spawns = Vec::new();
     let root = restore_pane_node(session_node, &mut pane_spawns);
     (root, pane_spawns)
@@ -176,10 +200,10 @@ pub fn restore_pane_tree(
 
 fn restore_pane_node(
     session_node: &SessionPaneNode,
-    pane_spawns: &mut Vec<(PaneId, Option<String>)>,
+    pane_spawns: &mut Vec<PaneSpawn>,
 ) -> PaneNode {
     match session_node {
-        SessionPaneNode::Leaf { cwd } => {
+        SessionPaneNode::Leaf { cwd, notes } => {
             let id = PaneId::next();
             // Validate CWD exists, fallback to None (which uses $HOME)
             let valid_cwd = cwd.as_ref().and_then(|path| {
@@ -190,7 +214,7 @@ fn restore_pane_node(
                     None
                 }
             });
-            pane_spawns.push((id, valid_cwd));
+            pane_spawns.push((id, valid_cwd, notes.clone()));
             PaneNode::leaf(id)
         }
         SessionPaneNode::Split {
@@ -218,11 +242,14 @@ mod tests {
         SessionState {
             tabs: vec![SessionTab {
                 title: "Shell".to_string(),
+                title_is_manual: false,
                 pane_tree: SessionPaneNode::Leaf {
                     cwd: Some("/home/user".to_string()),
+                    notes: Vec::new(),
                 },
             }],
             active_tab: 0,
+            window: None,
         }
     }
 
@@ -230,18 +257,22 @@ mod tests {
         SessionState {
             tabs: vec![SessionTab {
                 title: "Work".to_string(),
+                title_is_manual: false,
                 pane_tree: SessionPaneNode::Split {
                     direction: "vertical".to_string(),
                     ratio: 0.5,
                     first: Box::new(SessionPaneNode::Leaf {
                         cwd: Some("/home/user/project".to_string()),
+                        notes: Vec::new(),
                     }),
                     second: Box::new(SessionPaneNode::Leaf {
                         cwd: Some("/home/user/logs".to_string()),
+                        notes: Vec::new(),
                     }),
                 },
             }],
             active_tab: 0,
+            window: None,
         }
     }
 
@@ -250,16 +281,20 @@ mod tests {
             tabs: vec![
                 SessionTab {
                     title: "Tab 1".to_string(),
-                    pane_tree: SessionPaneNode::Leaf { cwd: None },
+                    title_is_manual: false,
+                    pane_tree: SessionPaneNode::Leaf { cwd: None, notes: Vec::new() },
                 },
                 SessionTab {
                     title: "Tab 2".to_string(),
+                    title_is_manual: false,
                     pane_tree: SessionPaneNode::Leaf {
                         cwd: Some("/tmp".to_string()),
+                        notes: Vec::new(),
                     },
                 },
             ],
             active_tab: 1,
+            window: None,
         }
     }
 
@@ -294,9 +329,11 @@ mod tests {
         let session = SessionState {
             tabs: vec![SessionTab {
                 title: "Shell".to_string(),
-                pane_tree: SessionPaneNode::Leaf { cwd: None },
+                title_is_manual: false,
+                pane_tree: SessionPaneNode::Leaf { cwd: None, notes: Vec::new() },
             }],
             active_tab: 0,
+            window: None,
         };
         let json = serde_json::to_string(&session).unwrap();
         assert!(json.contains("\"cwd\":null"));
@@ -304,6 +341,27 @@ mod tests {
         assert_eq!(session, deserialized);
     }
 
+    #[test]
+    fn serialize_window_size() {
+        let mut session = simple_session();
+        session.window = Some(SessionWindow { width: 1400.0, height: 900.0, font_scale: None });
+        let json = serde_json::to_string(&session).unwrap();
+        assert!(!json.contains("font_scale"));
+        let deserialized: SessionState = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.window, session.window);
+        session.window = Some(SessionWindow { width: 1400.0, height: 900.0, font_scale: Some(2.0) });
+        let json = serde_json::to_string(&session).unwrap();
+        let deserialized: SessionState = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.window.unwrap().font_scale, Some(2.0));
+    }
+
+    #[test]
+    fn session_without_window_size_still_loads() {
+        let json = r#"{"tabs":[{"title":"Shell","pane_tree":{"type":"Leaf","cwd":null}}],"active_tab":0}"#;
+        let session: SessionState = serde_json::from_str(json).unwrap();
+        assert_eq!(session.window, None);
+    }
+
     // ── File save/load tests ────────────────────────────────────
 
     #[test]
@@ -360,6 +418,7 @@ mod tests {
             pane_id,
             PaneCwdInfo {
                 cwd: Some("/home/user".to_string()),
+                notes: Vec::new(),
             },
         );
 
@@ -367,7 +426,7 @@ mod tests {
         assert_eq!(session.tabs.len(), 1);
         assert_eq!(session.active_tab, 0);
         match &session.tabs[0].pane_tree {
-            SessionPaneNode::Leaf { cwd } => {
+            SessionPaneNode::Leaf { cwd, .. } => {
                 assert_eq!(cwd.as_deref(), Some("/home/user"));
             }
             _ => panic!("Expected Leaf"),
@@ -381,7 +440,7 @@ mod tests {
 
         let session = SessionState::capture(&tab_manager, &cwds);
         match &session.tabs[0].pane_tree {
-            SessionPaneNode::Leaf { cwd } => {
+            SessionPaneNode::Leaf { cwd, .. } => {
                 assert!(cwd.is_none());
             }
             _ => panic!("Expected Leaf"),
@@ -411,6 +470,7 @@ mod tests {
     fn restore_single_leaf() {
         let node = SessionPaneNode::Leaf {
             cwd: Some("/tmp".to_string()),
+            notes: Vec::new(),
         };
         let (pane_node, spawns) = restore_pane_tree(&node);
         assert_eq!(spawns.len(), 1);
@@ -418,14 +478,31 @@ mod tests {
         assert!(matches!(pane_node, PaneNode::Leaf { .. }));
     }
 
+    #[test]
+    fn notes_round_trip_and_restore() {
+        let node = SessionPaneNode::Leaf {
+            cwd: None,
+            notes: vec![Note { line: 42, text: "first timeout".to_string() }],
+        };
+        let json = serde_json::to_string(&node).unwrap();
+        let parsed: SessionPaneNode = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, node);
+        let (_pane_node, spawns) = restore_pane_tree(&parsed);
+        assert_eq!(spawns[0].2[0].line, 42);
+        // Panes without notes don't write the key
+        let bare = SessionPaneNode::Leaf { cwd: None, notes: Vec::new() };
+        assert!(!serde_json::to_string(&bare).unwrap().contains("notes"));
+    }
+
     #[test]
     fn restore_split_creates_two_panes() {
         let node = SessionPaneNode::Split {
             direction: "vertical".to_string(),
             ratio: 0.5,
-            first: Box::new(SessionPaneNode::Leaf { cwd: None }),
+            first: Box::new(SessionPaneNode::Leaf { cwd: None, notes: Vec::new() }),
             second: Box::new(SessionPaneNode::Leaf {
                 cwd: Some("/tmp".to_string()),
+                notes: Vec::new(),
             }),
         };
         let (pane_node, spawns) = restore_pane_tree(&node);
@@ -439,6 +516,7 @@ mod tests {
     fn restore_stale_cwd_falls_back_to_none() {
         let node = SessionPaneNode::Leaf {
             cwd: Some("/nonexistent/path/that/does/not/exist".to_string()),
+            notes: Vec::new(),
         };
         let (_pane_node, spawns) = restore_pane_tree(&node);
         assert_eq!(spawns.len(), 1);
@@ -0,0 +1,172 @@
+// OSC 52 clipboard access: remote programs (tmux, nvim over SSH) setting or
+// reading the system clipboard, gated by the `[clipboard]` policy.
+
+use std::sync::Arc;
+
+/// Whether an OSC 52 write or read goes through (`clipboard.osc52_write`,
+/// `clipboard.osc52_read`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardPolicy {
+    #[default]
+    Allow,
+    /// Hold the request until the user allows or denies it in the pane.
+    Ask,
+    Deny,
+}
+
+impl ClipboardPolicy {
+    /// Convert a config string ("allow", "ask", "deny").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "allow" => Some(Self::Allow),
+            "ask" => Some(Self::Ask),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Which clipboard an OSC 52 sequence names: `c` is the clipboard, `p`/`s`
+/// the primary selection (the clipboard where there is none).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardTarget {
+    Clipboard,
+    Selection,
+}
+
+impl ClipboardTarget {
+    /// Put `text` on the system clipboard or primary selection.
+    pub fn write(self, text: &str) -> Result<(), arboard::Error> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        #[cfg(target_os = "linux")]
+        if self == Self::Selection {
+            use arboard::{LinuxClipboardKind, SetExtLinux};
+            return clipboard.set().clipboard(LinuxClipboardKind::Primary).text(text);
+        }
+        clipboard.set_text(text)
+    }
+
+    /// Text on the system clipboard or primary selection.
+    pub fn read(self) -> Result<String, arboard::Error> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        #[cfg(target_os = "linux")]
+        if self == Self::Selection {
+            use arboard::{GetExtLinux, LinuxClipboardKind};
+            return clipboard.get().clipboard(LinuxClipboardKind::Primary).text();
+        }
+        clipboard.get_text()
+    }
+}
+
+/// An OSC 52 request from the program running in a pane.
+#[derive(Clone)]
+pub enum ClipboardRequest {
+    /// `OSC 52 ; c ; <base64>`: set the clipboard to the decoded text.
+    Store { target: ClipboardTarget, text: String },
+    /// `OSC 52 ; c ; ?`: answer with the clipboard contents, formatted as
+    /// the reply sequence by `reply`.
+    Load {
+        target: ClipboardTarget,
+        reply: Arc<dyn Fn(&str) -> String + Sync + Send>,
+    },
+}
+
+impl std::fmt::Debug for ClipboardRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardRequest::Store { target, text } => {
+                write!(f, "Store({target:?}, {} bytes)", text.len())
+            }
+            ClipboardRequest::Load { target, .. } => write!(f, "Load({target:?})"),
+        }
+    }
+}
+
+impl ClipboardRequest {
+    /// The policy that applies to this request.
+    pub fn policy(&self, write: ClipboardPolicy, read: ClipboardPolicy) -> ClipboardPolicy {
+        match self {
+            ClipboardRequest::Store { .. } => write,
+            ClipboardRequest::Load { .. } => read,
+        }
+    }
+
+    /// Whether a write fits in `max_bytes` (0 = no limit). Reads always do.
+    pub fn within_limit(&self, max_bytes: usize) -> bool {
+        match self {
+            ClipboardRequest::Store { text, .. } => max_bytes == 0 || text.len() <= max_bytes,
+            ClipboardRequest::Load { .. } => true,
+        }
+    }
+
+    /// Question shown in the pane while the request waits for an answer.
+    pub fn prompt(&self) -> String {
+        let question = match self {
+            ClipboardRequest::Store { text, .. } => {
+                format!("Allow this program to copy {} to the clipboard?", format_size(text.len()))
+            }
+            ClipboardRequest::Load { .. } => "Allow this program to read the clipboard?".to_string(),
+        };
+        format!("{question}  Enter: allow · Esc: deny")
+    }
+}
+
+/// Human-readable byte count ("512 B", "12.5 KB", "1.2 MB").
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(text: &str) -> ClipboardRequest {
+        ClipboardRequest::Store {
+            target: ClipboardTarget::Clipboard,
+            text: text.to_string(),
+        }
+    }
+
+    fn load() -> ClipboardRequest {
+        ClipboardRequest::Load {
+            target: ClipboardTarget::Clipboard,
+            reply: Arc::new(|text| format!("\x1b]52;c;{text}\x07")),
+        }
+    }
+
+    #[test]
+    fn policy_from_config_str() {
+        assert_eq!(ClipboardPolicy::from_config_str("allow"), Some(ClipboardPolicy::Allow));
+        assert_eq!(ClipboardPolicy::from_config_str("ask"), Some(ClipboardPolicy::Ask));
+        assert_eq!(ClipboardPolicy::from_config_str("deny"), Some(ClipboardPolicy::Deny));
+        assert_eq!(ClipboardPolicy::from_config_str("maybe"), None);
+    }
+
+    #[test]
+    fn writes_and_reads_use_their_own_policy() {
+        let (write, read) = (ClipboardPolicy::Allow, ClipboardPolicy::Ask);
+        assert_eq!(store("x").policy(write, read), ClipboardPolicy::Allow);
+        assert_eq!(load().policy(write, read), ClipboardPolicy::Ask);
+    }
+
+    #[test]
+    fn size_limit_applies_to_writes() {
+        assert!(store("abcd").within_limit(4));
+        assert!(!store("abcde").within_limit(4));
+        assert!(store("abcde").within_limit(0));
+        assert!(load().within_limit(1));
+    }
+
+    #[test]
+    fn prompt_describes_request() {
+        assert!(store(&"x".repeat(2048)).prompt().starts_with("Allow this program to copy 2.0 KB"));
+        assert!(load().prompt().contains("read the clipboard"));
+    }
+}
@@ -1,12 +1,13 @@
 // Custom EventListener for capturing alacritty_terminal events.
 
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use alacritty_terminal::event::{Event, EventListener, WindowSize};
+use alacritty_terminal::term::ClipboardType;
 use alacritty_terminal::vte::ansi::Rgb;
 
+use super::clipboard::{ClipboardRequest, ClipboardTarget};
+
 /// Terminal event captured by VeloTermListener.
 #[derive(Debug, Clone)]
 pub enum TerminalEvent {
@@ -16,6 +17,8 @@ pub enum TerminalEvent {
     TitleReset,
     /// Terminal bell.
     Bell,
+    /// OSC 52 clipboard write or read.
+    Clipboard(ClipboardRequest),
 }
 
 /// A query response that needs to be written back to the PTY.
@@ -39,17 +42,18 @@ impl std::fmt::Debug for QueryResponse {
 }
 
 /// Shared event queue between VeloTermListener and the Terminal wrapper.
-pub type EventQueue = Rc<RefCell<Vec<TerminalEvent>>>;
+/// Thread-safe so a Term can be handed to a worker thread for reflow.
+pub type EventQueue = Arc<Mutex<Vec<TerminalEvent>>>;
 
 /// Shared queue for PTY write-back responses (query answers).
-pub type ResponseQueue = Rc<RefCell<Vec<QueryResponse>>>;
+pub type ResponseQueue = Arc<Mutex<Vec<QueryResponse>>>;
 
 /// Create a new listener and its associated event queue.
 /// The listener is moved into alacritty_terminal's Term, and the queue
 /// is retained by our Terminal wrapper to drain events after each feed().
 pub fn create_listener() -> (VeloTermListener, EventQueue, ResponseQueue) {
-    let queue = Rc::new(RefCell::new(Vec::new()));
-    let responses = Rc::new(RefCell::new(Vec::new()));
+    let queue = Arc::new(Mutex::new(Vec::new()));
+    let responses = Arc::new(Mutex::new(Vec::new()));
     let listener = VeloTermListener {
         events: queue.clone(),
         responses: responses.clone(),
@@ -59,7 +63,7 @@ pub fn create_listener() -> (VeloTermListener, EventQueue, ResponseQueue) {
 
 /// Custom event listener that captures terminal events for shell integration
 /// and query responses for PTY write-back.
-/// Uses shared Rc<RefCell> since the listener is moved into Term<T> and
+/// Uses shared Arc<Mutex> since the listener is moved into Term<T> and
 /// we need to read events from outside.
 #[derive(Debug, Clone)]
 pub struct VeloTermListener {
@@ -72,28 +76,50 @@ impl EventListener for VeloTermListener {
         match event {
             Event::Title(title) => {
                 self.events
-                    .borrow_mut()
+                    .lock()
+                    .unwrap()
                     .push(TerminalEvent::TitleChanged(title));
             }
             Event::ResetTitle => {
-                self.events.borrow_mut().push(TerminalEvent::TitleReset);
+                self.events.lock().unwrap().push(TerminalEvent::TitleReset);
             }
             Event::Bell => {
-                self.events.borrow_mut().push(TerminalEvent::Bell);
+                self.events.lock().unwrap().push(TerminalEvent::Bell);
+            }
+            Event::ClipboardStore(ty, text) => {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(TerminalEvent::Clipboard(ClipboardRequest::Store {
+                        target: clipboard_target(ty),
+                        text,
+                    }));
+            }
+            Event::ClipboardLoad(ty, reply) => {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(TerminalEvent::Clipboard(ClipboardRequest::Load {
+                        target: clipboard_target(ty),
+                        reply,
+                    }));
             }
             Event::PtyWrite(response) => {
                 self.responses
-                    .borrow_mut()
+                    .lock()
+                    .unwrap()
                     .push(QueryResponse::Direct(response));
             }
             Event::ColorRequest(index, formatter) => {
                 self.responses
-                    .borrow_mut()
+                    .lock()
+                    .unwrap()
                     .push(QueryResponse::Color(index, formatter));
             }
             Event::TextAreaSizeRequest(formatter) => {
                 self.responses
-                    .borrow_mut()
+                    .lock()
+                    .unwrap()
                     .push(QueryResponse::TextAreaSize(formatter));
             }
             _ => {}
@@ -101,19 +127,27 @@ impl EventListener for VeloTermListener {
     }
 }
 
+/// The clipboard an OSC 52 sequence names.
+fn clipboard_target(ty: ClipboardType) -> ClipboardTarget {
+    match ty {
+        ClipboardType::Clipboard => ClipboardTarget::Clipboard,
+        ClipboardType::Selection => ClipboardTarget::Selection,
+    }
+}
+
 /// Drain all pending events from a shared event queue.
 pub fn drain_events(queue: &EventQueue) -> Vec<TerminalEvent> {
-    queue.borrow_mut().drain(..).collect()
+    queue.lock().unwrap().drain(..).collect()
 }
 
 /// Drain all pending query responses from the response queue.
 pub fn drain_responses(queue: &ResponseQueue) -> Vec<QueryResponse> {
-    queue.borrow_mut().drain(..).collect()
+    queue.lock().unwrap().drain(..).collect()
 }
 
 /// Check if there are any pending events in a shared event queue.
 pub fn has_events(queue: &EventQueue) -> bool {
-    !queue.borrow().is_empty()
+    !queue.lock().unwrap().is_empty()
 }
 
 #[cfg(test)]
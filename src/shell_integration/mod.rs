@@ -1,5 +1,6 @@
 // Shell integration: OSC sequence parsing, shell state tracking, and prompt navigation.
 
+pub mod clipboard;
 pub mod listener;
 
 use std::collections::VecDeque;
@@ -16,6 +17,8 @@ const MAX_COMMAND_HISTORY: usize = 100;
 pub enum PromptMarker {
     /// OSC 133;A — Prompt region start.
     PromptStart,
+    /// OSC 133;A;k=s or k=r — Continuation (PS2) or right prompt inside an open region.
+    SecondaryPrompt,
     /// OSC 133;B — Command start (user pressed Enter).
     CommandStart,
     /// OSC 133;C — Command output start.
@@ -37,6 +40,24 @@ pub enum ShellEvent {
     KittyGraphics(Vec<u8>),
 }
 
+/// Line range covered by a prompt, from OSC 133;A through OSC 133;B.
+///
+/// Spans multi-line prompts and continuation lines typed before Enter.
+/// `end` is `None` while the prompt is still being edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptRegion {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl PromptRegion {
+    /// Returns true if `line` falls within this region.
+    /// An open region contains every line from its start onward.
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start && self.end.is_none_or(|end| line <= end)
+    }
+}
+
 /// Record of a completed command with timing information.
 #[derive(Debug, Clone)]
 pub struct CommandRecord {
@@ -57,6 +78,8 @@ pub struct ShellState {
     pub title_is_explicit: bool,
     /// Line positions where prompts were detected (OSC 133;A).
     prompt_positions: VecDeque<usize>,
+    /// Full prompt regions (OSC 133;A..B), parallel to `prompt_positions`.
+    prompt_regions: VecDeque<PromptRegion>,
     /// Start time of the currently running command, if any.
     command_start: Option<Instant>,
     /// History of completed commands with timing.
@@ -83,6 +106,7 @@ impl ShellState {
             title: None,
             title_is_explicit: false,
             prompt_positions: VecDeque::new(),
+            prompt_regions: VecDeque::new(),
             command_start: None,
             command_history: VecDeque::new(),
             last_exit_status: None,
@@ -100,9 +124,24 @@ impl ShellState {
                     if self.prompt_positions.len() > MAX_PROMPT_POSITIONS {
                         self.prompt_positions.pop_front();
                     }
+                    self.prompt_regions.push_back(PromptRegion {
+                        start: current_line,
+                        end: None,
+                    });
+                    if self.prompt_regions.len() > MAX_PROMPT_POSITIONS {
+                        self.prompt_regions.pop_front();
+                    }
+                }
+                PromptMarker::SecondaryPrompt => {
+                    // Continuation lines stay inside the open region; no new prompt.
                 }
                 PromptMarker::CommandStart => {
                     self.command_start = Some(Instant::now());
+                    if let Some(region) = self.prompt_regions.back_mut() {
+                        if region.end.is_none() {
+                            region.end = Some(current_line.max(region.start));
+                        }
+                    }
                 }
                 PromptMarker::CommandOutputStart => {
                     // Informational — no state change needed
@@ -147,6 +186,25 @@ impl ShellState {
         &self.prompt_positions
     }
 
+    /// Get the prompt regions (oldest first).
+    pub fn prompt_regions(&self) -> &VecDeque<PromptRegion> {
+        &self.prompt_regions
+    }
+
+    /// Get the most recent prompt region, if any.
+    pub fn last_prompt_region(&self) -> Option<PromptRegion> {
+        self.prompt_regions.back().copied()
+    }
+
+    /// Find the prompt region containing `line`, if any.
+    pub fn prompt_region_at(&self, line: usize) -> Option<PromptRegion> {
+        self.prompt_regions
+            .iter()
+            .rev()
+            .find(|region| region.contains(line))
+            .copied()
+    }
+
     /// Get the command history (oldest first).
     pub fn command_history(&self) -> &VecDeque<CommandRecord> {
         &self.command_history
@@ -162,6 +220,23 @@ impl ShellState {
         self.prompt_positions.back().copied()
     }
 
+    /// First and last line of the most recent command's output: from the
+    /// line after its command line up to the line before the next prompt, or
+    /// to `current_line` while no new prompt has appeared. None without
+    /// shell integration or when the command printed nothing.
+    pub fn last_output_range(&self, current_line: usize) -> Option<(usize, usize)> {
+        let last = self.prompt_regions.back()?;
+        let (region, output_end) = match last.end {
+            Some(_) => (*last, current_line),
+            None => {
+                let previous = self.prompt_regions.get(self.prompt_regions.len().checked_sub(2)?)?;
+                (*previous, last.start.checked_sub(1)?)
+            }
+        };
+        let first = region.end? + 1;
+        (first <= output_end).then_some((first, output_end))
+    }
+
     /// Returns true if a command is currently running (between OSC 133;B and 133;D).
     pub fn is_command_running(&self) -> bool {
         self.command_start.is_some()
@@ -322,7 +397,17 @@ pub fn parse_osc133_payload(payload: &str) -> Option<(PromptMarker, Option<i32>)
     }
     let first = payload.as_bytes()[0];
     match first {
-        b'A' => Some((PromptMarker::PromptStart, None)),
+        b'A' => {
+            let secondary = payload
+                .split(';')
+                .skip(1)
+                .any(|opt| opt == "k=s" || opt == "k=r");
+            if secondary {
+                Some((PromptMarker::SecondaryPrompt, None))
+            } else {
+                Some((PromptMarker::PromptStart, None))
+            }
+        }
         b'B' => Some((PromptMarker::CommandStart, None)),
         b'C' => Some((PromptMarker::CommandOutputStart, None)),
         b'D' => {
@@ -553,6 +638,114 @@ mod tests {
         assert_eq!(state.prompt_positions()[0], 100);
     }
 
+    // ── Prompt regions ─────────────────────────────────────────────
+
+    #[test]
+    fn osc133a_opens_prompt_region() {
+        let mut state = ShellState::new();
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+            5,
+        );
+        assert_eq!(
+            state.last_prompt_region(),
+            Some(PromptRegion { start: 5, end: None })
+        );
+    }
+
+    #[test]
+    fn osc133b_closes_prompt_region_across_lines() {
+        let mut state = ShellState::new();
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+            5,
+        );
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::SecondaryPrompt, None),
+            6,
+        );
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::CommandStart, None),
+            7,
+        );
+        let region = state.last_prompt_region().unwrap();
+        assert_eq!(region, PromptRegion { start: 5, end: Some(7) });
+        assert!(region.contains(6));
+        assert!(!region.contains(8));
+    }
+
+    #[test]
+    fn secondary_prompt_does_not_add_prompt_position() {
+        let mut state = ShellState::new();
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+            5,
+        );
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::SecondaryPrompt, None),
+            6,
+        );
+        assert_eq!(state.prompt_positions().len(), 1);
+        assert_eq!(state.prompt_regions().len(), 1);
+    }
+
+    #[test]
+    fn osc133b_does_not_reclose_finished_region() {
+        let mut state = ShellState::new();
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+            5,
+        );
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::CommandStart, None),
+            6,
+        );
+        state.handle_event(
+            &ShellEvent::SemanticPrompt(PromptMarker::CommandStart, None),
+            20,
+        );
+        assert_eq!(state.last_prompt_region().unwrap().end, Some(6));
+    }
+
+    #[test]
+    fn prompt_region_at_finds_containing_region() {
+        let mut state = ShellState::new();
+        for (a, b) in [(0, 1), (10, 12)] {
+            state.handle_event(
+                &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+                a,
+            );
+            state.handle_event(
+                &ShellEvent::SemanticPrompt(PromptMarker::CommandStart, None),
+                b,
+            );
+        }
+        assert_eq!(state.prompt_region_at(11).unwrap().start, 10);
+        assert_eq!(state.prompt_region_at(1).unwrap().start, 0);
+        assert!(state.prompt_region_at(5).is_none());
+    }
+
+    #[test]
+    fn open_prompt_region_contains_following_lines() {
+        let region = PromptRegion { start: 3, end: None };
+        assert!(region.contains(3));
+        assert!(region.contains(100));
+        assert!(!region.contains(2));
+    }
+
+    #[test]
+    fn prompt_regions_bounded_at_max() {
+        let mut state = ShellState::new();
+        for i in 0..MAX_PROMPT_POSITIONS + 10 {
+            state.handle_event(
+                &ShellEvent::SemanticPrompt(PromptMarker::PromptStart, None),
+                i,
+            );
+        }
+        assert_eq!(state.prompt_regions().len(), MAX_PROMPT_POSITIONS);
+        assert_eq!(state.prompt_regions()[0].start, 10);
+    }
+
     // ── Malformed OSC sequence handling ─────────────────────────────
 
     #[test]
@@ -561,6 +754,22 @@ mod tests {
         assert_eq!(result, Some((PromptMarker::PromptStart, None)));
     }
 
+    #[test]
+    fn parse_osc133_secondary_prompt() {
+        assert_eq!(
+            parse_osc133_payload("A;k=s"),
+            Some((PromptMarker::SecondaryPrompt, None))
+        );
+        assert_eq!(
+            parse_osc133_payload("A;k=r"),
+            Some((PromptMarker::SecondaryPrompt, None))
+        );
+        assert_eq!(
+            parse_osc133_payload("A;k=i"),
+            Some((PromptMarker::PromptStart, None))
+        );
+    }
+
     #[test]
     fn parse_osc133_valid_b() {
         let result = parse_osc133_payload("B");
@@ -765,6 +974,26 @@ mod tests {
         assert_eq!(state.next_prompt(0), Some(10));
     }
 
+    #[test]
+    fn last_output_range_spans_command_output() {
+        let mut state = ShellState::new();
+        assert_eq!(state.last_output_range(5), None);
+        let mark = |state: &mut ShellState, marker, line| {
+            state.handle_event(&ShellEvent::SemanticPrompt(marker, None), line);
+        };
+        mark(&mut state, PromptMarker::PromptStart, 0);
+        mark(&mut state, PromptMarker::CommandStart, 1);
+        // Still running: output so far ends at the cursor line
+        assert_eq!(state.last_output_range(1), None);
+        assert_eq!(state.last_output_range(4), Some((2, 4)));
+        mark(&mut state, PromptMarker::PromptStart, 7);
+        assert_eq!(state.last_output_range(7), Some((2, 6)));
+        // A command with no output has no range
+        mark(&mut state, PromptMarker::CommandStart, 7);
+        mark(&mut state, PromptMarker::PromptStart, 8);
+        assert_eq!(state.last_output_range(8), None);
+    }
+
     // ── Command timing ────────────────────────────────────────────
 
     #[test]
@@ -1085,6 +1314,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn all_scripts_reexport_colorfgbg_on_usr1() {
+        let bash = include_str!("../../shell/bash-integration.sh");
+        let zsh = include_str!("../../shell/zsh-integration.sh");
+        let fish = include_str!("../../shell/fish-integration.fish");
+        assert!(bash.contains("trap '__veloterm_theme_reload' USR1"));
+        assert!(zsh.contains("TRAPUSR1"));
+        assert!(fish.contains("--on-signal SIGUSR1"));
+        for script in [bash, zsh, fish] {
+            assert!(script.contains("COLORFGBG"), "script should re-export COLORFGBG");
+            assert!(script.contains("/tmp/veloterm-colorfgbg"));
+        }
+    }
+
     #[test]
     fn all_scripts_guard_against_double_sourcing() {
         let bash = include_str!("../../shell/bash-integration.sh");
@@ -0,0 +1,360 @@
+// History-aware completion suggestions: mirrors keystrokes typed at the prompt
+// and fuzzy-matches them against previously submitted command lines.
+
+use std::collections::VecDeque;
+
+use crate::command_palette::fuzzy_match;
+
+/// Maximum number of command lines retained per pane.
+const MAX_HISTORY: usize = 500;
+
+/// Maximum number of suggestions shown in the strip.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Bonus added to candidates that extend the typed input verbatim.
+const PREFIX_BONUS: i32 = 1000;
+
+/// Per-pane suggestion state.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionState {
+    /// Input mirrored from keystrokes since the current prompt appeared.
+    input: String,
+    /// False once the mirror can no longer be trusted (cursor keys, history
+    /// recall, control sequences). Reset at the next prompt.
+    tracking: bool,
+    /// Prompt line the mirror belongs to; a new prompt resets the input.
+    prompt_line: Option<usize>,
+    /// Submitted command lines, oldest first, deduplicated.
+    history: VecDeque<String>,
+    /// The most recently submitted line (kept even when blank).
+    last_submitted: Option<String>,
+}
+
+impl SuggestionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start mirroring for the prompt at `prompt_line` if it differs from the
+    /// one currently tracked. Pass `None` while a command is running.
+    pub fn sync_prompt(&mut self, prompt_line: Option<usize>) {
+        if prompt_line != self.prompt_line {
+            self.prompt_line = prompt_line;
+            self.input.clear();
+            self.tracking = prompt_line.is_some();
+        }
+    }
+
+    /// Mirror bytes about to be written to the PTY.
+    pub fn on_input(&mut self, bytes: &[u8]) {
+        if !self.tracking {
+            // A line submitted without a trustworthy mirror is unknown
+            if bytes.iter().any(|b| matches!(b, b'\r' | b'\n')) {
+                self.last_submitted = None;
+            }
+            return;
+        }
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            self.tracking = false;
+            return;
+        };
+        for ch in text.chars() {
+            match ch {
+                '\r' | '\n' => {
+                    self.commit();
+                    return;
+                }
+                '\x7f' | '\x08' => {
+                    self.input.pop();
+                }
+                // Ctrl+C / Ctrl+U discard the line
+                '\x03' | '\x15' => self.input.clear(),
+                c if c.is_control() => {
+                    self.input.clear();
+                    self.tracking = false;
+                    return;
+                }
+                c => self.input.push(c),
+            }
+        }
+    }
+
+    /// Record the mirrored line as a submitted command and stop tracking
+    /// until the next prompt.
+    fn commit(&mut self) {
+        let line = self.input.trim().to_string();
+        self.input.clear();
+        self.tracking = false;
+        self.last_submitted = Some(line.clone());
+        if !line.is_empty() {
+            self.push_history(line);
+        }
+    }
+
+    /// Add a command line to history, moving duplicates to the newest slot.
+    pub fn push_history(&mut self, line: String) {
+        self.history.retain(|h| *h != line);
+        self.history.push_back(line);
+        if self.history.len() > MAX_HISTORY {
+            self.history.pop_front();
+        }
+    }
+
+    /// The input mirrored so far at the current prompt.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Characters typed at the current prompt, or None once the mirror can
+    /// no longer be trusted.
+    pub fn typed_len(&self) -> Option<usize> {
+        self.tracking.then(|| self.input.chars().count())
+    }
+
+    /// The last line submitted with Enter, if it was fully mirrored.
+    pub fn last_submitted(&self) -> Option<&str> {
+        self.last_submitted.as_deref()
+    }
+
+    /// Submitted command lines (oldest first).
+    pub fn history(&self) -> &VecDeque<String> {
+        &self.history
+    }
+
+    /// Best matching history entries for the current input, best first.
+    /// Empty when not tracking, nothing typed, or the only match is the input itself.
+    pub fn suggestions(&self) -> Vec<&str> {
+        if !self.tracking || self.input.trim().is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(i32, usize, &str)> = self
+            .history
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| **h != self.input)
+            .filter_map(|(i, h)| {
+                let score = fuzzy_match(&self.input, h)?;
+                let bonus = if h.starts_with(&self.input) { PREFIX_BONUS } else { 0 };
+                Some((score + bonus, i, h.as_str()))
+            })
+            .collect();
+        // Higher score first; newer entries win ties
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+        scored
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, _, h)| h)
+            .collect()
+    }
+
+    /// Accept the top suggestion. Returns the bytes to write to the PTY:
+    /// the remaining suffix when the suggestion extends the input, otherwise
+    /// backspaces over the typed text followed by the whole command.
+    pub fn accept(&mut self) -> Option<Vec<u8>> {
+        let chosen = self.suggestions().first()?.to_string();
+        let bytes = match chosen.strip_prefix(self.input.as_str()) {
+            Some(rest) => rest.as_bytes().to_vec(),
+            None => {
+                let mut bytes = vec![0x7f; self.input.chars().count()];
+                bytes.extend_from_slice(chosen.as_bytes());
+                bytes
+            }
+        };
+        self.input = chosen;
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracking_state(history: &[&str]) -> SuggestionState {
+        let mut state = SuggestionState::new();
+        for h in history {
+            state.push_history(h.to_string());
+        }
+        state.sync_prompt(Some(0));
+        state
+    }
+
+    // ── Keystroke mirroring ─────────────────────────────────────────
+
+    #[test]
+    fn mirrors_printable_input() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"git st");
+        assert_eq!(state.input(), "git st");
+    }
+
+    #[test]
+    fn backspace_removes_last_char() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"lsx\x7f");
+        assert_eq!(state.input(), "ls");
+    }
+
+    #[test]
+    fn ctrl_u_clears_input() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"make\x15");
+        assert_eq!(state.input(), "");
+    }
+
+    #[test]
+    fn enter_commits_to_history() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"cargo test\r");
+        assert_eq!(state.history().back().map(String::as_str), Some("cargo test"));
+        assert_eq!(state.input(), "");
+    }
+
+    #[test]
+    fn enter_records_last_submitted() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"gti status\r");
+        assert_eq!(state.last_submitted(), Some("gti status"));
+    }
+
+    #[test]
+    fn untracked_submit_forgets_last_submitted() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"gti\r");
+        state.sync_prompt(Some(3));
+        state.on_input(b"\x1b[A");
+        state.on_input(b"\r");
+        assert_eq!(state.last_submitted(), None);
+    }
+
+    #[test]
+    fn escape_sequence_stops_tracking() {
+        let mut state = tracking_state(&["cargo build"]);
+        state.on_input(b"ca\x1b[A");
+        assert!(state.suggestions().is_empty());
+        state.on_input(b"\r");
+        assert_eq!(state.history().len(), 1);
+    }
+
+    #[test]
+    fn typed_len_counts_chars_while_tracking() {
+        let mut state = tracking_state(&[]);
+        state.on_input("écho".as_bytes());
+        assert_eq!(state.typed_len(), Some(4));
+        state.on_input(b"\x1b[D");
+        assert_eq!(state.typed_len(), None);
+    }
+
+    #[test]
+    fn no_tracking_without_prompt() {
+        let mut state = SuggestionState::new();
+        state.on_input(b"ls\r");
+        assert!(state.history().is_empty());
+    }
+
+    #[test]
+    fn new_prompt_resets_input() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"abc");
+        state.sync_prompt(Some(5));
+        assert_eq!(state.input(), "");
+        state.on_input(b"x");
+        assert_eq!(state.input(), "x");
+    }
+
+    #[test]
+    fn same_prompt_keeps_input() {
+        let mut state = tracking_state(&[]);
+        state.on_input(b"abc");
+        state.sync_prompt(Some(0));
+        assert_eq!(state.input(), "abc");
+    }
+
+    // ── History ─────────────────────────────────────────────────────
+
+    #[test]
+    fn duplicate_history_moves_to_newest() {
+        let mut state = tracking_state(&["ls", "pwd"]);
+        state.push_history("ls".to_string());
+        let history: Vec<_> = state.history().iter().map(String::as_str).collect();
+        assert_eq!(history, vec!["pwd", "ls"]);
+    }
+
+    #[test]
+    fn history_bounded_at_max() {
+        let mut state = SuggestionState::new();
+        for i in 0..MAX_HISTORY + 10 {
+            state.push_history(format!("cmd{i}"));
+        }
+        assert_eq!(state.history().len(), MAX_HISTORY);
+        assert_eq!(state.history()[0], "cmd10");
+    }
+
+    // ── Matching ────────────────────────────────────────────────────
+
+    #[test]
+    fn no_suggestions_for_empty_input() {
+        let state = tracking_state(&["ls"]);
+        assert!(state.suggestions().is_empty());
+    }
+
+    #[test]
+    fn prefix_matches_rank_first() {
+        let mut state = tracking_state(&["git status", "gst", "echo gi"]);
+        state.on_input(b"gi");
+        assert_eq!(state.suggestions()[0], "git status");
+    }
+
+    #[test]
+    fn fuzzy_matches_included() {
+        let mut state = tracking_state(&["cargo build --release"]);
+        state.on_input(b"cbr");
+        assert_eq!(state.suggestions(), vec!["cargo build --release"]);
+    }
+
+    #[test]
+    fn suggestions_capped() {
+        let mut state = tracking_state(&["a1", "a2", "a3", "a4", "a5"]);
+        state.on_input(b"a");
+        assert_eq!(state.suggestions().len(), MAX_SUGGESTIONS);
+    }
+
+    #[test]
+    fn newer_entries_win_ties() {
+        let mut state = tracking_state(&["make a", "make b"]);
+        state.on_input(b"make ");
+        assert_eq!(state.suggestions()[0], "make b");
+    }
+
+    #[test]
+    fn exact_input_not_suggested() {
+        let mut state = tracking_state(&["ls"]);
+        state.on_input(b"ls");
+        assert!(state.suggestions().is_empty());
+    }
+
+    // ── Acceptance ──────────────────────────────────────────────────
+
+    #[test]
+    fn accept_prefix_sends_suffix() {
+        let mut state = tracking_state(&["git status"]);
+        state.on_input(b"git s");
+        assert_eq!(state.accept(), Some(b"tatus".to_vec()));
+        assert_eq!(state.input(), "git status");
+    }
+
+    #[test]
+    fn accept_fuzzy_replaces_input() {
+        let mut state = tracking_state(&["cargo build"]);
+        state.on_input(b"cb");
+        let bytes = state.accept().unwrap();
+        assert_eq!(&bytes[..2], &[0x7f, 0x7f]);
+        assert_eq!(&bytes[2..], b"cargo build");
+    }
+
+    #[test]
+    fn accept_without_match_is_none() {
+        let mut state = tracking_state(&["ls"]);
+        state.on_input(b"zzz");
+        assert!(state.accept().is_none());
+    }
+}
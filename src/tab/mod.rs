@@ -33,6 +33,8 @@ impl TabId {
 pub struct Tab {
     pub id: TabId,
     pub title: String,
+    /// Set with Rename Tab; shell-derived titles no longer replace it.
+    pub title_is_manual: bool,
     pub pane_tree: PaneTree,
     /// Whether this tab has a notification badge (long-running command completed).
     pub has_notification: bool,
@@ -50,6 +52,7 @@ impl Tab {
         Self {
             id: TabId::new(),
             title: "Shell".to_string(),
+            title_is_manual: false,
             pane_tree,
             has_notification: false,
         }
@@ -60,6 +63,7 @@ impl Tab {
         Self {
             id: TabId::new(),
             title,
+            title_is_manual: false,
             pane_tree,
             has_notification: false,
         }
@@ -126,6 +130,11 @@ impl TabManager {
         &mut self.tabs[self.active_index]
     }
 
+    /// Returns a mutable reference to the tab at `index`, if it exists.
+    pub fn tab_mut(&mut self, index: usize) -> Option<&mut Tab> {
+        self.tabs.get_mut(index)
+    }
+
     /// Creates a new tab, appends it after the active tab, makes it active, and returns its TabId.
     pub fn new_tab(&mut self) -> TabId {
         let tab = Tab::new();
@@ -198,10 +207,25 @@ impl TabManager {
         }
     }
 
-    /// Sets the title of the tab at the given index.
+    /// Sets the shell-derived title of the tab at the given index.
+    /// Tabs the user renamed keep their title.
     pub fn set_title(&mut self, tab_index: usize, title: &str) {
         if let Some(tab) = self.tabs.get_mut(tab_index) {
-            tab.title = title.to_string();
+            if !tab.title_is_manual {
+                tab.title = title.to_string();
+            }
+        }
+    }
+
+    /// Give the tab at the given index a sticky title. An empty title
+    /// hands the tab back to shell-derived titles.
+    pub fn rename_tab(&mut self, tab_index: usize, title: &str) {
+        if let Some(tab) = self.tabs.get_mut(tab_index) {
+            let title = title.trim();
+            tab.title_is_manual = !title.is_empty();
+            if tab.title_is_manual {
+                tab.title = title.to_string();
+            }
         }
     }
 
@@ -225,6 +249,40 @@ impl TabManager {
     }
 }
 
+/// Title for a tab from its focused pane, in priority order: a title set
+/// with OSC 0/2, a non-shell foreground process, the program of the command
+/// still running, then the basename of the working directory.
+pub fn auto_title(
+    explicit: Option<&str>,
+    process: Option<&str>,
+    running_command: Option<&str>,
+    cwd: Option<&str>,
+) -> Option<String> {
+    if let Some(title) = explicit {
+        return Some(title.to_string());
+    }
+    if let Some(name) = process.filter(|name| !crate::pty::is_shell_process(name)) {
+        return Some(name.to_string());
+    }
+    if let Some(program) = running_command.and_then(command_program) {
+        return Some(program.to_string());
+    }
+    cwd.map(|cwd| crate::shell_integration::dir_name_from_path(cwd).to_string())
+}
+
+/// The window title from a `window.title` template: `{title}` becomes
+/// `title`.
+pub fn window_title(template: &str, title: &str) -> String {
+    template.replace("{title}", title)
+}
+
+/// Basename of the program a command line runs, skipping leading
+/// `NAME=value` assignments.
+fn command_program(line: &str) -> Option<&str> {
+    let word = line.split_whitespace().find(|word| !word.contains('='))?;
+    word.rsplit('/').next().filter(|name| !name.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,6 +545,49 @@ mod tests {
         assert_eq!(mgr.active_tab().title, "Shell");
     }
 
+    // ── Manual titles ────────────────────────────────────────────
+
+    #[test]
+    fn renamed_tab_keeps_title_until_cleared() {
+        setup();
+        let mut mgr = TabManager::new();
+        mgr.rename_tab(0, "  deploy  ");
+        assert_eq!(mgr.active_tab().title, "deploy");
+        mgr.set_title(0, "vim");
+        assert_eq!(mgr.active_tab().title, "deploy");
+        mgr.rename_tab(0, " ");
+        assert!(!mgr.active_tab().title_is_manual);
+        mgr.set_title(0, "vim");
+        assert_eq!(mgr.active_tab().title, "vim");
+    }
+
+    // ── auto_title ──────────────────────────────────────────────
+
+    #[test]
+    fn auto_title_priority() {
+        let cwd = Some("/home/me/projects/veloterm");
+        assert_eq!(auto_title(Some("notes"), Some("vim"), None, cwd).as_deref(), Some("notes"));
+        assert_eq!(auto_title(None, Some("vim"), Some("make"), cwd).as_deref(), Some("vim"));
+        assert_eq!(auto_title(None, Some("zsh"), Some("make test"), cwd).as_deref(), Some("make"));
+        assert_eq!(auto_title(None, Some("zsh"), None, cwd).as_deref(), Some("veloterm"));
+        assert_eq!(auto_title(None, None, None, None), None);
+    }
+
+    #[test]
+    fn auto_title_uses_program_of_running_command() {
+        assert_eq!(
+            auto_title(None, None, Some("RUST_LOG=debug ./target/debug/veloterm --help"), None).as_deref(),
+            Some("veloterm")
+        );
+        assert_eq!(auto_title(None, None, Some("   "), Some("/tmp")).as_deref(), Some("tmp"));
+    }
+
+    #[test]
+    fn window_title_fills_template() {
+        assert_eq!(window_title("{title} - VeloTerm", "vim"), "vim - VeloTerm");
+        assert_eq!(window_title("VeloTerm", "vim"), "VeloTerm");
+    }
+
     // ── Notification badge ──────────────────────────────────────
 
     #[test]
@@ -0,0 +1,107 @@
+// Compatibility presets for programs that misbehave with a modern terminal,
+// mostly on old remote systems. `legacy` names the pane plain `xterm`, stops
+// advertising truecolor, and drops from the pane's output what such systems
+// trip over or answer wrongly: queries whose replies would be typed into the
+// shell, kitty keyboard and graphics sequences, and OSC features beyond
+// titles, colors and the working directory.
+
+use crate::config::types::ShellConfig;
+
+/// How much of VeloTerm a pane's programs see (`shell.compat`, a profile's
+/// `compat`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compat {
+    #[default]
+    Standard,
+    Legacy,
+}
+
+impl Compat {
+    /// Convert a config string ("standard", "legacy").
+    /// Returns None for unknown strings.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s {
+            "standard" => Some(Self::Standard),
+            "legacy" => Some(Self::Legacy),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Legacy => "legacy",
+        }
+    }
+
+    /// The preset `shell` asks for.
+    pub fn of(shell: &ShellConfig) -> Self {
+        Self::from_config_str(&shell.compat).unwrap_or_default()
+    }
+
+    /// The other preset, for the runtime switch.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Standard => Self::Legacy,
+            Self::Legacy => Self::Standard,
+        }
+    }
+
+    /// TERM when no `term` is configured.
+    pub fn default_term(self) -> &'static str {
+        match self {
+            Self::Standard => "xterm-256color",
+            Self::Legacy => "xterm",
+        }
+    }
+
+    /// COLORTERM to export, None to unset it.
+    pub fn colorterm(self) -> Option<&'static str> {
+        match self {
+            Self::Standard => Some("truecolor"),
+            Self::Legacy => None,
+        }
+    }
+}
+
+/// TERM for a shell started with `shell`: its `term`, else the preset's.
+pub fn term_name(shell: &ShellConfig) -> &str {
+    shell.term.as_deref().unwrap_or(Compat::of(shell).default_term())
+}
+
+/// Text shown across a pane switched to `compat` while its shell runs.
+/// Output filtering changes at once; TERM only for a new shell.
+pub fn restart_prompt(compat: Compat, term: &str) -> String {
+    let name = match compat {
+        Compat::Standard => "Standard",
+        Compat::Legacy => "Legacy",
+    };
+    format!("{name} compatibility on · restart the shell for TERM={term}?  Enter: restart · Esc: keep it running")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_names_a_plain_xterm_without_truecolor() {
+        let mut shell = ShellConfig::default();
+        assert_eq!(Compat::of(&shell), Compat::Standard);
+        assert_eq!(term_name(&shell), "xterm-256color");
+        assert_eq!(Compat::Standard.colorterm(), Some("truecolor"));
+        shell.compat = "legacy".into();
+        assert_eq!(term_name(&shell), "xterm");
+        assert_eq!(Compat::of(&shell).colorterm(), None);
+        shell.term = Some("vt220".into());
+        assert_eq!(term_name(&shell), "vt220");
+    }
+
+    #[test]
+    fn config_strings_round_trip() {
+        for compat in [Compat::Standard, Compat::Legacy] {
+            assert_eq!(Compat::from_config_str(compat.as_str()), Some(compat));
+            assert_eq!(compat.toggled().toggled(), compat);
+        }
+        assert_eq!(Compat::from_config_str("modern"), None);
+    }
+}
@@ -0,0 +1,136 @@
+// Control byte view: a per-pane switch that rewrites output before it is
+// parsed, so C0/C1 control characters and invalid UTF-8 show up as
+// reverse-video hex codes like `<0x9b>` instead of moving the cursor or
+// changing modes. For debugging programs that speak terminal protocols.
+
+/// Starts and ends the reverse video around each code.
+const REVERSE_ON: &[u8] = b"\x1b[7m";
+const REVERSE_OFF: &[u8] = b"\x1b[27m";
+
+/// Output transform for a pane with the view switched on. Keeps the tail
+/// of a UTF-8 sequence split across reads until the rest arrives.
+#[derive(Debug, Clone, Default)]
+pub struct ControlByteView {
+    partial: Vec<u8>,
+}
+
+impl ControlByteView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `bytes` with every control character and invalid byte replaced by
+    /// its code. Line feeds also still end the line.
+    pub fn transform(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut input = std::mem::take(&mut self.partial);
+        input.extend_from_slice(bytes);
+        let mut out = Vec::with_capacity(input.len() * 2);
+        let mut rest = &input[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    push_text(&mut out, text);
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    push_text(&mut out, std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(len) => {
+                            for &byte in &after[..len] {
+                                push_code(&mut out, byte);
+                            }
+                            rest = &after[len..];
+                        }
+                        // Cut off mid-character: wait for the next read
+                        None => {
+                            self.partial = after.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Codes for a character still waiting for its last bytes, when the
+    /// view is switched off.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for byte in std::mem::take(&mut self.partial) {
+            push_code(&mut out, byte);
+        }
+        out
+    }
+}
+
+/// Whether `c` is a C0 or C1 control character (or DEL).
+fn is_control(c: char) -> bool {
+    c < ' ' || c == '\x7f' || ('\u{80}'..='\u{9f}').contains(&c)
+}
+
+fn push_text(out: &mut Vec<u8>, text: &str) {
+    let mut buf = [0; 4];
+    for c in text.chars() {
+        if is_control(c) {
+            push_code(out, c as u32 as u8);
+            if c == '\n' {
+                out.extend_from_slice(b"\r\n");
+            }
+        } else {
+            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+}
+
+fn push_code(out: &mut Vec<u8>, byte: u8) {
+    out.extend_from_slice(REVERSE_ON);
+    out.extend_from_slice(format!("<0x{byte:02x}>").as_bytes());
+    out.extend_from_slice(REVERSE_OFF);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The transform's output with the reverse-video escapes dropped.
+    fn shown(view: &mut ControlByteView, bytes: &[u8]) -> String {
+        String::from_utf8(view.transform(bytes))
+            .unwrap()
+            .replace("\x1b[7m", "")
+            .replace("\x1b[27m", "")
+    }
+
+    #[test]
+    fn control_characters_become_codes() {
+        let mut view = ControlByteView::new();
+        assert_eq!(shown(&mut view, b"\x1b[31mred\x07"), "<0x1b>[31mred<0x07>");
+        assert_eq!(shown(&mut view, "a\u{9b}b\x7f".as_bytes()), "a<0x9b>b<0x7f>");
+    }
+
+    #[test]
+    fn line_feeds_still_break_lines() {
+        let mut view = ControlByteView::new();
+        assert_eq!(shown(&mut view, b"one\r\ntwo"), "one<0x0d><0x0a>\r\ntwo");
+    }
+
+    #[test]
+    fn invalid_utf8_bytes_become_codes() {
+        let mut view = ControlByteView::new();
+        assert_eq!(shown(&mut view, b"ok\x9b\xff\xc3("), "ok<0x9b><0xff><0xc3>(");
+        let raw = view.transform(b"\x01");
+        assert!(raw.starts_with(REVERSE_ON) && raw.ends_with(REVERSE_OFF));
+    }
+
+    #[test]
+    fn characters_split_across_reads_are_kept_whole() {
+        let mut view = ControlByteView::new();
+        let e_acute = "é".as_bytes();
+        assert_eq!(shown(&mut view, &[b'x', e_acute[0]]), "x");
+        assert_eq!(shown(&mut view, &e_acute[1..]), "é");
+        view.transform(&e_acute[..1]);
+        let rest = String::from_utf8(view.finish()).unwrap();
+        assert!(rest.contains("<0xc3>"));
+    }
+}
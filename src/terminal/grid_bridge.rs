@@ -1,13 +1,19 @@
 // Grid bridge: converts alacritty_terminal grid state to renderer GridCell data.
 
 use crate::config::theme::{Color, TerminalTheme, color_new};
-use crate::renderer::grid_renderer::{GridCell, CELL_FLAG_STRIKETHROUGH, CELL_FLAG_UNDERLINE};
+use crate::renderer::grid_renderer::{
+    GridCell, CELL_FLAG_DEFAULT_BG, CELL_FLAG_STRIKETHROUGH, CELL_FLAG_UNDERLINE, CELL_FLAG_WIDE,
+    CELL_FLAG_WIDE_SPACER, CELL_FLAG_WRAPLINE,
+};
 use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::TermDamage;
 use alacritty_terminal::term::cell::Flags as CellFlags;
 use alacritty_terminal::vte::ansi::Color as AnsiColor;
 use alacritty_terminal::vte::ansi::NamedColor;
 
+use super::text_map::TextMap;
+
 /// Default foreground color from the given theme.
 /// Uses text_primary for readable terminal output that matches standard
 /// terminal emulators (Alacritty, GNOME Terminal use ~85% brightness).
@@ -84,6 +90,17 @@ pub fn ansi_indexed_color(index: u8, theme: &TerminalTheme) -> Color {
     }
 }
 
+/// Color reported for an OSC 4/10/11 query. alacritty_terminal uses internal
+/// indices 256 = foreground (OSC 10) and 257 = background (OSC 11); 0-255 are
+/// palette entries (OSC 4).
+pub fn query_color(index: usize, theme: &TerminalTheme) -> Color {
+    match index {
+        0..=255 => ansi_indexed_color(index as u8, theme),
+        257 => default_bg(theme),
+        _ => default_fg(theme),
+    }
+}
+
 /// Convert an alacritty_terminal Color to our renderer Color.
 pub fn convert_color(ansi: AnsiColor, _default: Color, theme: &TerminalTheme) -> Color {
     match ansi {
@@ -149,6 +166,155 @@ pub fn extract_text_lines(terminal: &super::Terminal) -> Vec<String> {
     lines
 }
 
+/// Append grid row `abs_row` to `map`, placed on `row`: each character at
+/// its column, double-width ones over two cells, combining marks on the
+/// cell they belong to. Wide-char spacer cells add nothing.
+pub fn push_row_text(
+    map: &mut TextMap,
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+    abs_row: i32,
+    row: i32,
+) {
+    for col in 0..grid.columns() {
+        let cell = &grid[Point::new(Line(abs_row), Column(col))];
+        if cell.flags.intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER) {
+            continue;
+        }
+        let width = if cell.flags.contains(CellFlags::WIDE_CHAR) { 2 } else { 1 };
+        map.push(row, col, cell.c, width);
+        for &mark in cell.zerowidth().unwrap_or_default() {
+            map.push(row, col, mark, 0);
+        }
+    }
+}
+
+/// The visible rows as logical lines for matching (links, hints, vi-mode
+/// search): rows that soft-wrap are joined, and rows are numbered from the
+/// top of the viewport.
+pub fn extract_text_maps(terminal: &super::Terminal) -> Vec<TextMap> {
+    let grid = terminal.inner().grid();
+    let rows = grid.screen_lines() as i32;
+    let offset = grid.display_offset() as i32;
+    let last_col = Column(grid.columns().saturating_sub(1));
+    let mut maps = Vec::new();
+    let mut map = TextMap::new();
+    for row in 0..rows {
+        push_row_text(&mut map, grid, row - offset, row);
+        if !grid[Point::new(Line(row - offset), last_col)].flags.contains(CellFlags::WRAPLINE) || row == rows - 1 {
+            maps.push(std::mem::take(&mut map));
+        }
+    }
+    maps
+}
+
+/// Extract the visible screen as clean reading-order text for screen readers
+/// and TTS tools. Wide-char spacers are dropped, soft-wrapped rows are joined
+/// into one logical line, trailing blanks are trimmed, and runs of blank lines
+/// collapse to a single empty line.
+pub fn extract_line_view(terminal: &super::Terminal) -> String {
+    let term = terminal.inner();
+    let grid = term.grid();
+    let cols = grid.columns();
+    let rows = grid.screen_lines();
+    let offset = grid.display_offset() as i32;
+    let spacers = CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER;
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for row in 0..rows {
+        let mut wrapped = false;
+        for col in 0..cols {
+            let point = Point::new(Line(row as i32 - offset), Column(col));
+            let cell = &grid[point];
+            if col == cols - 1 && cell.flags.contains(CellFlags::WRAPLINE) {
+                wrapped = true;
+            }
+            if cell.flags.intersects(spacers) {
+                continue;
+            }
+            current.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                current.extend(zerowidth.iter());
+            }
+        }
+        if !wrapped || row == rows - 1 {
+            let line = current.trim_end().to_string();
+            current.clear();
+            let prev_blank = lines.last().is_some_and(|l| l.is_empty());
+            if !(line.is_empty() && (lines.is_empty() || prev_blank)) {
+                lines.push(line);
+            }
+        }
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Visible rows that soft-wrap into the next row (WRAPLINE on the last cell).
+pub fn wrapped_rows(terminal: &super::Terminal) -> Vec<usize> {
+    let term = terminal.inner();
+    let grid = term.grid();
+    let last_col = Column(grid.columns() - 1);
+    let offset = grid.display_offset() as i32;
+    (0..grid.screen_lines())
+        .filter(|&row| {
+            let point = Point::new(Line(row as i32 - offset), last_col);
+            grid[point].flags.contains(CellFlags::WRAPLINE)
+        })
+        .collect()
+}
+
+/// Whether buffer row `row` (row 0 = top of scrollback, as in
+/// `Terminal::char_at`) soft-wraps into the next row. Rows past the end
+/// don't wrap.
+pub fn buffer_row_wraps(terminal: &super::Terminal, row: usize) -> bool {
+    if row >= terminal.total_rows() {
+        return false;
+    }
+    let grid = terminal.inner().grid();
+    let point = Point::new(Line(row as i32 - grid.history_size() as i32), Column(grid.columns() - 1));
+    grid[point].flags.contains(CellFlags::WRAPLINE)
+}
+
+/// First and last buffer row (row 0 = top of scrollback) of the soft-wrapped
+/// line that `row` belongs to.
+pub fn wrapped_span(terminal: &super::Terminal, row: usize) -> (usize, usize) {
+    let wraps = |row: usize| buffer_row_wraps(terminal, row);
+    let last_row = terminal.total_rows() - 1;
+    let mut first = row.min(last_row);
+    while first > 0 && wraps(first - 1) {
+        first -= 1;
+    }
+    let mut last = row.min(last_row);
+    while last < last_row && wraps(last) {
+        last += 1;
+    }
+    (first, last)
+}
+
+/// Text of buffer rows `first..=last` (row 0 = top of scrollback), joining
+/// soft-wrapped rows and trimming trailing blanks, as a logical copy does.
+/// Trailing empty lines are dropped.
+pub fn buffer_text(terminal: &super::Terminal, first: usize, last: usize) -> String {
+    let term = terminal.inner();
+    let grid = term.grid();
+    let cols = grid.columns();
+    let history = grid.history_size() as i32;
+    let last = last.min(terminal.total_rows().saturating_sub(1));
+    let mut text = String::new();
+    for row in first..=last {
+        let abs_row = row as i32 - history;
+        let line = row_text(grid, abs_row, 0..=cols - 1);
+        let wrapped = grid[Point::new(Line(abs_row), Column(cols - 1))]
+            .flags
+            .contains(CellFlags::WRAPLINE);
+        crate::input::selection::push_row(&mut text, &line, wrapped, row == last);
+    }
+    text.trim_end_matches('\n').to_string()
+}
+
 /// Extract GridCell data from a Terminal for the current viewport.
 /// When scrolled up, reads from scrollback history; at bottom, reads the active screen.
 pub fn extract_grid_cells(terminal: &super::Terminal, theme: &TerminalTheme) -> Vec<GridCell> {
@@ -164,47 +330,189 @@ pub fn extract_grid_cells(terminal: &super::Terminal, theme: &TerminalTheme) ->
     for row in 0..rows {
         for col in 0..cols {
             let point = Point::new(Line(row as i32 - offset), Column(col));
-            let cell = &grid[point];
-            let ch = cell.c;
-            let cell_flags = cell.flags;
-
-            // Convert base colors, applying bold→bright for named colors
-            let mut fg = if cell_flags.contains(CellFlags::BOLD) {
-                match cell.fg {
-                    AnsiColor::Named(name) => ansi_named_color(bold_brighten_named(name), theme),
-                    other => convert_color(other, fg_default, theme),
+            cells.push(convert_cell(&grid[point], col == cols - 1, theme, fg_default, bg_default));
+        }
+    }
+
+    cells
+}
+
+/// Viewport rows alacritty modified since the last call, for skipping the
+/// cell compare of rows that can't have changed. `None` means the whole
+/// viewport may differ (scrolling, resize, clear). Resets the terminal's
+/// damage, so take it once per frame, right before `extract_grid_cells`.
+pub fn take_row_damage(terminal: &mut super::Terminal) -> Option<Vec<bool>> {
+    let rows = terminal.term.grid().screen_lines();
+    let full = std::mem::take(&mut terminal.full_damage);
+    let dirty = match terminal.term.damage() {
+        TermDamage::Full => None,
+        TermDamage::Partial(lines) => {
+            let mut dirty = vec![false; rows];
+            for bounds in lines {
+                if let Some(row) = dirty.get_mut(bounds.line) {
+                    *row = true;
                 }
-            } else {
-                convert_color(cell.fg, fg_default, theme)
-            };
-            let mut bg = convert_color(cell.bg, bg_default, theme);
-
-            // Apply dim: reduce fg intensity
-            if cell_flags.contains(CellFlags::DIM) {
-                fg = apply_dim(fg);
             }
+            Some(dirty)
+        }
+    };
+    terminal.term.reset_damage();
+    dirty.filter(|_| !full)
+}
 
-            // Apply inverse: swap fg and bg
-            if cell_flags.contains(CellFlags::INVERSE) {
-                std::mem::swap(&mut fg, &mut bg);
-            }
+/// GridCell data for one buffer line (0 = oldest scrollback line), whatever
+/// the display offset. `line` past the bottom of the screen yields no cells.
+pub fn buffer_line_cells(terminal: &super::Terminal, theme: &TerminalTheme, line: usize) -> Vec<GridCell> {
+    let term = terminal.inner();
+    let grid = term.grid();
+    if line >= grid.history_size() + grid.screen_lines() {
+        return Vec::new();
+    }
+    let cols = grid.columns();
+    let abs_row = line as i32 - grid.history_size() as i32;
+    let fg_default = default_fg(theme);
+    let bg_default = default_bg(theme);
+    (0..cols)
+        .map(|col| {
+            let cell = &grid[Point::new(Line(abs_row), Column(col))];
+            convert_cell(cell, col == cols - 1, theme, fg_default, bg_default)
+        })
+        .collect()
+}
 
-            // Propagate underline and strikethrough flags
-            let mut flags = 0u32;
-            if cell_flags.intersects(CellFlags::UNDERLINE) {
-                flags |= CELL_FLAG_UNDERLINE;
-            }
-            if cell_flags.contains(CellFlags::STRIKEOUT) {
-                flags |= CELL_FLAG_STRIKETHROUGH;
-            }
+/// Resolve one terminal cell's colors and attributes for rendering.
+fn convert_cell(
+    cell: &alacritty_terminal::term::cell::Cell,
+    last_col: bool,
+    theme: &TerminalTheme,
+    fg_default: Color,
+    bg_default: Color,
+) -> GridCell {
+    let cell_flags = cell.flags;
+
+    // Convert base colors, applying bold→bright for named colors
+    let mut fg = if cell_flags.contains(CellFlags::BOLD) {
+        match cell.fg {
+            AnsiColor::Named(name) => ansi_named_color(bold_brighten_named(name), theme),
+            other => convert_color(other, fg_default, theme),
+        }
+    } else {
+        convert_color(cell.fg, fg_default, theme)
+    };
+    let mut bg = convert_color(cell.bg, bg_default, theme);
+
+    // Apply dim: reduce fg intensity
+    if cell_flags.contains(CellFlags::DIM) {
+        fg = apply_dim(fg);
+    }
 
-            let mut grid_cell = GridCell::new(ch, fg, bg);
-            grid_cell.flags = flags;
-            cells.push(grid_cell);
+    // Apply inverse: swap fg and bg
+    if cell_flags.contains(CellFlags::INVERSE) {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+
+    // Propagate underline and strikethrough flags
+    let mut flags = 0u32;
+    if cell_flags.intersects(CellFlags::UNDERLINE) {
+        flags |= CELL_FLAG_UNDERLINE;
+    }
+    if cell_flags.contains(CellFlags::STRIKEOUT) {
+        flags |= CELL_FLAG_STRIKETHROUGH;
+    }
+    if last_col && cell_flags.contains(CellFlags::WRAPLINE) {
+        flags |= CELL_FLAG_WRAPLINE;
+    }
+    if cell_flags.contains(CellFlags::WIDE_CHAR) {
+        flags |= CELL_FLAG_WIDE;
+    }
+    if cell_flags.intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER) {
+        flags |= CELL_FLAG_WIDE_SPACER;
+    }
+    if cell.bg == AnsiColor::Named(NamedColor::Background) && !cell_flags.contains(CellFlags::INVERSE) {
+        flags |= CELL_FLAG_DEFAULT_BG;
+    }
+
+    let mut grid_cell = GridCell::new(cell.c, fg, bg);
+    grid_cell.flags = flags;
+    grid_cell
+}
+
+/// Text of `cols` on one grid row. Wide-char spacer cells are skipped so a
+/// double-width character copies as itself, not followed by a space, and a
+/// range starting on a character's right half takes in the whole character.
+fn row_text(
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+    abs_row: i32,
+    cols: std::ops::RangeInclusive<usize>,
+) -> String {
+    let (mut start, end) = cols.into_inner();
+    if start > 0
+        && start <= end
+        && grid[Point::new(Line(abs_row), Column(start))].flags.contains(CellFlags::WIDE_CHAR_SPACER)
+    {
+        start -= 1;
+    }
+    let mut line = String::new();
+    for col in start..=end {
+        let cell = &grid[Point::new(Line(abs_row), Column(col))];
+        if cell
+            .flags
+            .intersects(CellFlags::WIDE_CHAR_SPACER | CellFlags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
         }
+        line.push(cell.c);
     }
+    line
+}
 
-    cells
+/// Copy the selection as text in `mode`. Logical copies join soft-wrapped
+/// rows and trim padding; grid copies keep every screen row as its own line,
+/// exactly as wide as it is selected.
+pub fn grid_copy_text(
+    terminal: &super::Terminal,
+    selection: &crate::input::selection::Selection,
+    mode: crate::input::selection::CopyMode,
+) -> String {
+    use crate::input::selection::{CopyMode, SelectionType};
+
+    match (mode, selection.selection_type) {
+        (CopyMode::Logical, SelectionType::VisualBlock) => grid_selected_text_block(terminal, selection),
+        (CopyMode::Logical, SelectionType::Line) => grid_selected_text_lines(terminal, selection),
+        (CopyMode::Logical, _) => grid_selected_text(terminal, selection),
+        (CopyMode::Grid, _) => grid_selected_text_exact(terminal, selection),
+    }
+}
+
+/// Extract the selection row by row exactly as shown: no wrap joining and no
+/// trimming, so column alignment (e.g. ASCII tables) survives the copy.
+pub fn grid_selected_text_exact(terminal: &super::Terminal, selection: &crate::input::selection::Selection) -> String {
+    use crate::input::selection::{normalize, SelectionType};
+
+    let term = terminal.inner();
+    let grid = term.grid();
+    let cols = grid.columns();
+    let history = grid.history_size() as i32;
+    let screen_lines = grid.screen_lines() as i32;
+    let (start, end) = normalize(selection);
+    let mut lines = Vec::new();
+
+    for abs_row in start.0..=end.0 {
+        if abs_row < -history || abs_row >= screen_lines {
+            continue;
+        }
+        let (col_start, col_end) = match selection.selection_type {
+            SelectionType::VisualBlock => (start.1.min(end.1), start.1.max(end.1)),
+            SelectionType::Line => (0, cols - 1),
+            SelectionType::Range | SelectionType::Word => (
+                if abs_row == start.0 { start.1 } else { 0 },
+                if abs_row == end.0 { end.1 } else { cols - 1 },
+            ),
+        };
+        lines.push(row_text(grid, abs_row, col_start..=col_end.min(cols - 1)));
+    }
+
+    lines.join("\n")
 }
 
 /// Extract selected text directly from the terminal grid (includes scrollback).
@@ -219,7 +527,8 @@ pub fn grid_selected_text(terminal: &super::Terminal, selection: &crate::input::
     let history = grid.history_size() as i32;
     let screen_lines = grid.screen_lines() as i32;
     let (start, end) = normalize(selection);
-    let mut lines = Vec::new();
+    let last_row = end.0.min(screen_lines - 1);
+    let mut text = String::new();
 
     for abs_row in start.0..=end.0 {
         if abs_row < -history || abs_row >= screen_lines {
@@ -228,15 +537,14 @@ pub fn grid_selected_text(terminal: &super::Terminal, selection: &crate::input::
         let col_start = if abs_row == start.0 { start.1 } else { 0 };
         let col_end = if abs_row == end.0 { end.1 } else { cols - 1 };
 
-        let mut line = String::new();
-        for col in col_start..=col_end.min(cols - 1) {
-            let point = Point::new(Line(abs_row), Column(col));
-            line.push(grid[point].c);
-        }
-        lines.push(line.trim_end().to_string());
+        let line = row_text(grid, abs_row, col_start..=col_end.min(cols - 1));
+        let wrapped = grid[Point::new(Line(abs_row), Column(cols - 1))]
+            .flags
+            .contains(CellFlags::WRAPLINE);
+        crate::input::selection::push_row(&mut text, &line, wrapped, abs_row == last_row);
     }
 
-    lines.join("\n")
+    text
 }
 
 /// Extract selected text from a visual-block (rectangular) selection directly from the grid.
@@ -257,11 +565,7 @@ pub fn grid_selected_text_block(terminal: &super::Terminal, selection: &crate::i
         if abs_row < -history || abs_row >= screen_lines {
             continue;
         }
-        let mut line = String::new();
-        for col in col_min..=col_max.min(cols - 1) {
-            let point = Point::new(Line(abs_row), Column(col));
-            line.push(grid[point].c);
-        }
+        let line = row_text(grid, abs_row, col_min..=col_max.min(cols - 1));
         lines.push(line.trim_end().to_string());
     }
 
@@ -278,27 +582,28 @@ pub fn grid_selected_text_lines(terminal: &super::Terminal, selection: &crate::i
     let history = grid.history_size() as i32;
     let screen_lines = grid.screen_lines() as i32;
     let (start, end) = normalize(selection);
-    let mut lines = Vec::new();
+    let last_row = end.0.min(screen_lines - 1);
+    let mut text = String::new();
 
     for abs_row in start.0..=end.0 {
         if abs_row < -history || abs_row >= screen_lines {
             continue;
         }
-        let mut line = String::new();
-        for col in 0..cols {
-            let point = Point::new(Line(abs_row), Column(col));
-            line.push(grid[point].c);
-        }
-        lines.push(line.trim_end().to_string());
+        let line = row_text(grid, abs_row, 0..=cols - 1);
+        let wrapped = grid[Point::new(Line(abs_row), Column(cols - 1))]
+            .flags
+            .contains(CellFlags::WRAPLINE);
+        crate::input::selection::push_row(&mut text, &line, wrapped, abs_row == last_row);
     }
 
-    lines.join("\n")
+    text
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::theme::DARK;
+    use crate::input::selection::CopyMode;
     use crate::terminal::Terminal;
     use alacritty_terminal::vte::ansi::Rgb;
 
@@ -373,6 +678,47 @@ mod tests {
         assert!((dark.r - dark.b).abs() < 0.01);
     }
 
+    fn to_rgb8(color: Color) -> (u8, u8, u8) {
+        let c = |v: f32| (v * 255.0).round() as u8;
+        (c(color.r), c(color.g), c(color.b))
+    }
+
+    #[test]
+    fn indexed_cube_and_grayscale_match_xterm() {
+        // xterm's 256-color table: cube levels 0, 95, 135, 175, 215, 255
+        assert_eq!(to_rgb8(ansi_indexed_color(17, &DARK)), (0, 0, 95));
+        assert_eq!(to_rgb8(ansi_indexed_color(67, &DARK)), (95, 135, 175));
+        assert_eq!(to_rgb8(ansi_indexed_color(196, &DARK)), (255, 0, 0));
+        assert_eq!(to_rgb8(ansi_indexed_color(214, &DARK)), (255, 175, 0));
+        // Grayscale ramp 8, 18, ..., 238
+        assert_eq!(to_rgb8(ansi_indexed_color(232, &DARK)), (8, 8, 8));
+        assert_eq!(to_rgb8(ansi_indexed_color(244, &DARK)), (128, 128, 128));
+        assert_eq!(to_rgb8(ansi_indexed_color(255, &DARK)), (238, 238, 238));
+    }
+
+    #[test]
+    fn indexed_low_colors_follow_palette_overrides() {
+        let overrides = std::collections::HashMap::from([
+            ("blue".to_string(), "#0000ff".to_string()),
+            ("bright_blue".to_string(), "#8080ff".to_string()),
+        ]);
+        let theme = DARK.with_ansi_overrides(&overrides);
+        assert_eq!(to_rgb8(ansi_indexed_color(4, &theme)), (0, 0, 255));
+        assert_eq!(to_rgb8(ansi_indexed_color(12, &theme)), (128, 128, 255));
+        assert_eq!(to_rgb8(convert_color(AnsiColor::Named(NamedColor::Blue), DARK.ansi[7], &theme)), (0, 0, 255));
+        assert_eq!(to_rgb8(query_color(4, &theme)), (0, 0, 255));
+        // The cube is independent of the theme
+        assert_eq!(ansi_indexed_color(100, &theme), ansi_indexed_color(100, &DARK));
+    }
+
+    #[test]
+    fn truecolor_round_trips_exactly() {
+        for (r, g, b) in [(0, 0, 0), (1, 2, 3), (128, 64, 200), (255, 255, 255)] {
+            let color = convert_color(AnsiColor::Spec(Rgb { r, g, b }), DARK.ansi[7], &DARK);
+            assert_eq!(to_rgb8(color), (r, g, b));
+        }
+    }
+
     // ── Color conversion (AnsiColor → Color) ─────────────────────────
 
     #[test]
@@ -644,6 +990,20 @@ mod tests {
         assert_eq!(cells[0].flags & CELL_FLAG_STRIKETHROUGH, 0);
     }
 
+    // ── Default background flag ────────────────────────────────────
+
+    #[test]
+    fn extract_flags_default_background_cells() {
+        let mut term = Terminal::new(80, 24, 10_000);
+        term.feed(b"A\x1b[41mB\x1b[0m\x1b[7mC");
+        let cells = extract_grid_cells(&term, &DARK);
+        assert_ne!(cells[0].flags & CELL_FLAG_DEFAULT_BG, 0);
+        assert_eq!(cells[1].flags & CELL_FLAG_DEFAULT_BG, 0);
+        // Inverse video paints the foreground color behind the text
+        assert_eq!(cells[2].flags & CELL_FLAG_DEFAULT_BG, 0);
+        assert_ne!(cells[3].flags & CELL_FLAG_DEFAULT_BG, 0);
+    }
+
     // ── Viewport-aware extraction ────────────────────────────────────
 
     #[test]
@@ -714,6 +1074,33 @@ mod tests {
         assert!(lines[2].starts_with("CCC"));
     }
 
+    #[test]
+    fn text_maps_join_wrapped_rows_and_skip_spacers() {
+        let mut term = Terminal::new(6, 3, 100);
+        term.feed("日本 abcdef\r\nx".as_bytes());
+        let maps = extract_text_maps(&term);
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0].text().trim_end(), "日本 abcdef");
+        // "日本 a" fills the first row, "bcdef" wraps onto the second
+        let abc = maps[0].text().find("abc").unwrap();
+        let range = maps[0].cell_range(abc..abc + 3).unwrap();
+        assert_eq!((range.start_row, range.start_col, range.end_row, range.end_col), (0, 5, 1, 2));
+        let wide = maps[0].cell_range(3..6).unwrap();
+        assert_eq!((wide.start_col, wide.end_col), (2, 4));
+        assert_eq!(maps[1].first_row(), Some(2));
+    }
+
+    #[test]
+    fn line_map_reaches_back_to_the_first_wrapped_row() {
+        let mut term = Terminal::new(4, 3, 100);
+        term.feed(b"abcdefgh\r\nz");
+        let map = term.line_map(1);
+        assert_eq!(map.text(), "abcdefgh");
+        assert_eq!((map.first_row(), map.last_row()), (Some(0), Some(1)));
+        assert_eq!(term.line_map(2).text().trim_end(), "z");
+        assert!(term.line_map(9).is_empty());
+    }
+
     // ── Grid-direct text extraction (scrollback-aware) ─────────────
 
     use crate::input::selection::{Selection, SelectionType, Side};
@@ -840,4 +1227,228 @@ mod tests {
         // Should not panic, and should contain some text from valid rows
         assert!(!text.is_empty(), "should have extracted some text");
     }
+
+    #[test]
+    fn grid_selected_text_joins_soft_wrapped_rows() {
+        let mut term = Terminal::new(10, 4, 100);
+        term.feed(b"abcdefghijklmno\r\nnext");
+        let sel = Selection {
+            start: (0, 0),
+            end: (2, 9),
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        };
+        assert_eq!(grid_selected_text(&term, &sel), "abcdefghijklmno\nnext");
+    }
+
+    #[test]
+    fn grid_selected_text_lines_joins_soft_wrapped_rows() {
+        let mut term = Terminal::new(10, 4, 100);
+        term.feed(b"abcdefghijklmno");
+        let sel = Selection {
+            start: (0, 0),
+            end: (1, 9),
+            selection_type: SelectionType::Line,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        };
+        assert_eq!(grid_selected_text_lines(&term, &sel), "abcdefghijklmno");
+    }
+
+    // ── Soft-wrap markers ────────────────────────────────────────────
+
+    #[test]
+    fn wrapped_rows_reports_soft_wrapped_rows_only() {
+        let mut term = Terminal::new(10, 5, 100);
+        term.feed(b"short\r\nabcdefghijklmnopqrstuvw");
+        assert_eq!(wrapped_rows(&term), vec![1, 2]);
+    }
+
+    #[test]
+    fn extract_sets_wrapline_flag_on_last_cell() {
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed(b"abcdefghijkl");
+        let cells = extract_grid_cells(&term, &DARK);
+        assert_ne!(cells[9].flags & CELL_FLAG_WRAPLINE, 0);
+        assert_eq!(cells[8].flags & CELL_FLAG_WRAPLINE, 0);
+        assert_eq!(cells[19].flags & CELL_FLAG_WRAPLINE, 0);
+    }
+
+    #[test]
+    fn buffer_text_joins_wrapped_rows() {
+        let mut term = Terminal::new(10, 5, 100);
+        term.feed(b"$ ls\r\nabcdefghijklmn\r\nshort   \r\n\r\n$ ");
+        assert_eq!(buffer_text(&term, 1, 4), "abcdefghijklmn\nshort");
+        assert_eq!(buffer_text(&term, 3, 4), "short");
+    }
+
+    #[test]
+    fn buffer_line_cells_reads_scrollback() {
+        let theme = DARK;
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed(b"first\r\nsecond\r\nthird\r\nfourth\r\nfifth");
+        assert_eq!(term.history_size(), 2);
+        let line = buffer_line_cells(&term, &theme, 0);
+        assert_eq!(line.len(), 10);
+        assert_eq!(line[0].ch, 'f');
+        assert_eq!(buffer_line_cells(&term, &theme, 4)[1].ch, 'i');
+        assert!(buffer_line_cells(&term, &theme, 5).is_empty());
+    }
+
+    // ── Line view export ─────────────────────────────────────────────
+
+    #[test]
+    fn line_view_trims_and_collapses_blank_runs() {
+        let mut term = Terminal::new(20, 8, 100);
+        term.feed(b"\r\n\r\nfirst   \r\n\r\n\r\n\r\nsecond\r\n");
+        assert_eq!(extract_line_view(&term), "first\n\nsecond");
+    }
+
+    #[test]
+    fn line_view_joins_soft_wrapped_rows() {
+        let mut term = Terminal::new(10, 4, 100);
+        term.feed(b"abcdefghijklmno");
+        assert_eq!(extract_line_view(&term), "abcdefghijklmno");
+    }
+
+    #[test]
+    fn line_view_skips_wide_char_spacers() {
+        let mut term = Terminal::new(20, 3, 100);
+        term.feed("日本語 ok".as_bytes());
+        assert_eq!(extract_line_view(&term), "日本語 ok");
+    }
+
+    #[test]
+    fn line_view_empty_screen() {
+        let term = Terminal::new(20, 3, 100);
+        assert_eq!(extract_line_view(&term), "");
+    }
+
+    // ── OSC color query mapping ──────────────────────────────────────
+
+    #[test]
+    fn query_color_background_matches_rendered_bg() {
+        assert_eq!(query_color(257, &DARK), default_bg(&DARK));
+    }
+
+    #[test]
+    fn query_color_foreground_matches_rendered_fg() {
+        assert_eq!(query_color(256, &DARK), default_fg(&DARK));
+    }
+
+    #[test]
+    fn query_color_palette_index() {
+        assert_eq!(query_color(1, &DARK), ansi_indexed_color(1, &DARK));
+        assert_eq!(query_color(200, &DARK), ansi_indexed_color(200, &DARK));
+    }
+
+    #[test]
+    fn buffer_row_wraps_indexes_from_top_of_scrollback() {
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed(b"1\r\n2\r\n3\r\nabcdefghijkl");
+        let wrapped: Vec<usize> = (0..term.total_rows()).filter(|&r| buffer_row_wraps(&term, r)).collect();
+        assert_eq!(wrapped, vec![term.total_rows() - 2]);
+        assert!(!buffer_row_wraps(&term, term.total_rows()));
+    }
+
+
+    // ── Copy modes ──────────────────────────────────────────────────
+
+    fn range(start: (i32, usize), end: (i32, usize)) -> Selection {
+        Selection {
+            start,
+            end,
+            selection_type: SelectionType::Range,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        }
+    }
+
+    #[test]
+    fn copy_logical_joins_wrapped_rows() {
+        let mut term = Terminal::new(10, 4, 100);
+        term.feed(b"abcdefghijklmno\r\nnext");
+        let text = grid_copy_text(&term, &range((0, 0), (2, 9)), CopyMode::Logical);
+        assert_eq!(text, "abcdefghijklmno\nnext");
+    }
+
+    #[test]
+    fn copy_grid_keeps_wrapped_rows_apart() {
+        let mut term = Terminal::new(10, 4, 100);
+        term.feed(b"abcdefghijklmno\r\nnext");
+        let text = grid_copy_text(&term, &range((0, 0), (2, 9)), CopyMode::Grid);
+        assert_eq!(text, "abcdefghij\nklmno     \nnext      ");
+    }
+
+    #[test]
+    fn copy_grid_preserves_table_alignment() {
+        let mut term = Terminal::new(12, 4, 100);
+        term.feed(b"| a  | bb |\r\n| ccc| d  |\r\n");
+        let block = Selection {
+            start: (0, 0),
+            end: (1, 5),
+            selection_type: SelectionType::VisualBlock,
+            start_side: Side::Left,
+            end_side: Side::Right,
+        };
+        assert_eq!(grid_copy_text(&term, &block, CopyMode::Grid), "| a  |\n| ccc|");
+        let line = Selection { selection_type: SelectionType::Line, ..range((0, 0), (0, 0)) };
+        assert_eq!(grid_copy_text(&term, &line, CopyMode::Grid), "| a  | bb | ");
+        assert_eq!(grid_copy_text(&term, &line, CopyMode::Logical), "| a  | bb |");
+    }
+
+    #[test]
+    fn copy_skips_wide_char_spacers() {
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed("日本 ok".as_bytes());
+        let sel = range((0, 0), (0, 6));
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Logical), "日本 ok");
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Grid), "日本 ok");
+    }
+
+    #[test]
+    fn copy_wrapped_wide_chars_drop_leading_spacer() {
+        // A wide char that doesn't fit in the last column wraps, leaving a
+        // leading spacer cell at the end of the first row.
+        let mut term = Terminal::new(5, 3, 100);
+        term.feed("abcd日x".as_bytes());
+        let sel = range((0, 0), (1, 4));
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Logical), "abcd日x");
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Grid), "abcd\n日x  ");
+    }
+
+
+    #[test]
+    fn extract_grid_cells_flags_wide_chars() {
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed("a日b".as_bytes());
+        let cells = extract_grid_cells(&term, &DARK);
+        assert_eq!(cells[1].ch, '日');
+        assert_ne!(cells[1].flags & CELL_FLAG_WIDE, 0);
+        assert_ne!(cells[2].flags & CELL_FLAG_WIDE_SPACER, 0);
+        assert_eq!(cells[3].ch, 'b');
+        assert_eq!(cells[3].flags & (CELL_FLAG_WIDE | CELL_FLAG_WIDE_SPACER), 0);
+    }
+
+    #[test]
+    fn row_damage_names_written_rows() {
+        let mut term = Terminal::new(10, 4, 100);
+        assert_eq!(take_row_damage(&mut term), None, "a new grid is fully damaged");
+        term.feed(b"\x1b[3;1Hx");
+        // Row 2 was written; row 0 held the cursor before
+        assert_eq!(take_row_damage(&mut term), Some(vec![true, false, true, false]));
+        term.clear_lines_from(0);
+        assert_eq!(take_row_damage(&mut term), None);
+    }
+
+    #[test]
+    fn copy_starting_on_spacer_keeps_whole_char() {
+        let mut term = Terminal::new(10, 3, 100);
+        term.feed("a日本b".as_bytes());
+        // Column 2 is the right half of 日
+        let sel = range((0, 2), (0, 4));
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Logical), "日本");
+        assert_eq!(grid_copy_text(&term, &sel, CopyMode::Grid), "日本");
+    }
 }
@@ -1,6 +1,11 @@
 // Terminal state machine: wraps alacritty_terminal for VT parsing and grid state.
 
+pub mod compat;
+pub mod control_bytes;
 pub mod grid_bridge;
+pub mod sanitize;
+pub mod snapshot;
+pub mod text_map;
 
 use alacritty_terminal::grid::{Dimensions, GridCell, Scroll};
 use alacritty_terminal::index::{Column, Line, Point};
@@ -8,9 +13,11 @@ use alacritty_terminal::term::Config;
 use alacritty_terminal::vte::ansi;
 
 use crate::image_protocol::ImageStore;
+use crate::renderer::cursor::CursorStyle;
 use crate::shell_integration::listener::{
     self, EventQueue, QueryResponse, ResponseQueue, TerminalEvent, VeloTermListener,
 };
+use crate::shell_integration::clipboard::ClipboardRequest;
 use crate::shell_integration::{self, ShellEvent, ShellState};
 
 /// Terminal dimensions for alacritty_terminal.
@@ -31,9 +38,31 @@ impl Dimensions for TermSize {
     }
 }
 
+/// Scrollback size (lines) from which a resize reflows on a worker thread.
+pub const DEFAULT_BACKGROUND_REFLOW_LINES: usize = 50_000;
+
+/// PTY output held back during a background reflow beyond which the reflow
+/// is waited for instead, so a chatty program can't grow the buffer forever.
+const MAX_REFLOW_PENDING_BYTES: usize = 4 * 1024 * 1024;
+
+/// Shown in a pane while its scrollback reflows in the background.
+const REFLOW_MESSAGE: &str = "Reflowing\u{2026}";
+
+type Term = alacritty_terminal::term::Term<VeloTermListener>;
+
+/// A resize running on a worker thread. The pane shows a placeholder grid
+/// until the reflowed term comes back.
+struct Reflow {
+    rx: crossbeam_channel::Receiver<Term>,
+    /// Resizes requested while the worker runs; the latest wins.
+    debouncer: ResizeDebouncer,
+    /// PTY output received meanwhile, fed once the term is back.
+    pending_bytes: Vec<u8>,
+}
+
 /// Wrapper around alacritty_terminal providing VT parsing and grid state.
 pub struct Terminal {
-    term: alacritty_terminal::term::Term<VeloTermListener>,
+    term: Term,
     processor: ansi::Processor,
     event_queue: EventQueue,
     response_queue: ResponseQueue,
@@ -44,6 +73,21 @@ pub struct Terminal {
     image_store: ImageStore,
     /// Pending image protocol responses to write back to PTY.
     image_responses: Vec<String>,
+    /// In-flight background reflow, if any.
+    reflow: Option<Reflow>,
+    /// Scrollback size from which resizes reflow in the background (0 = never).
+    background_reflow_lines: usize,
+    /// urxvt mouse encoding (DECSET 1015), which alacritty_terminal ignores.
+    urxvt_mouse: bool,
+    /// OSC 52 clipboard requests awaiting the app's policy check.
+    clipboard_requests: Vec<ClipboardRequest>,
+    /// The grid was changed behind alacritty's damage tracking (direct
+    /// cell writes, a swapped-in term); see `grid_bridge::take_row_damage`.
+    full_damage: bool,
+    /// Scrollback size the term was created with.
+    scrollback: usize,
+    /// Cursor shown until the application asks for another with DECSCUSR.
+    default_cursor_style: ansi::CursorStyle,
 }
 
 impl Terminal {
@@ -53,10 +97,8 @@ impl Terminal {
             columns: cols,
             screen_lines: rows,
         };
-        let config = Config {
-            scrolling_history: scrollback,
-            ..Config::default()
-        };
+        let default_cursor_style = ansi::CursorStyle::default();
+        let config = Self::term_config(scrollback, default_cursor_style);
         let (veloterm_listener, event_queue, response_queue) = listener::create_listener();
         let term = alacritty_terminal::term::Term::new(config, &size, veloterm_listener);
         let processor = ansi::Processor::new();
@@ -69,15 +111,71 @@ impl Terminal {
             bell_pending: false,
             image_store: ImageStore::new(320 * 1024 * 1024), // 320MB default limit
             image_responses: Vec::new(),
+            reflow: None,
+            background_reflow_lines: DEFAULT_BACKGROUND_REFLOW_LINES,
+            urxvt_mouse: false,
+            clipboard_requests: Vec::new(),
+            full_damage: false,
+            scrollback,
+            default_cursor_style,
         }
     }
 
+    /// Options for the alacritty term.
+    fn term_config(scrollback: usize, default_cursor_style: ansi::CursorStyle) -> Config {
+        Config {
+            scrolling_history: scrollback,
+            // Both directions reach the listener; `[clipboard]` policy decides
+            osc52: alacritty_terminal::term::Osc52::CopyPaste,
+            default_cursor_style,
+            ..Config::default()
+        }
+    }
+
+    /// Set the cursor shown until the application asks for another shape
+    /// with DECSCUSR (`CSI Ps SP q`), and again after it resets with
+    /// `CSI 0 SP q` (`cursor.style`, `cursor.blink`).
+    pub fn set_default_cursor_style(&mut self, style: CursorStyle, blinking: bool) {
+        self.default_cursor_style = ansi::CursorStyle { shape: style.to_shape(), blinking };
+        // A term away being reflowed gets it when it returns
+        if self.reflow.is_none() {
+            self.apply_term_config();
+        }
+    }
+
+    /// Hand the term its options again, after a change.
+    fn apply_term_config(&mut self) {
+        self.term.set_options(Self::term_config(self.scrollback, self.default_cursor_style));
+        // It re-announces the unchanged title; nothing to pass on
+        listener::drain_events(&self.event_queue);
+    }
+
+    /// The cursor shape and whether it blinks: the application's last
+    /// DECSCUSR request, else the default (`set_default_cursor_style`).
+    pub fn cursor_style(&self) -> (CursorStyle, bool) {
+        let style = self.term.cursor_style();
+        (CursorStyle::from_shape(style.shape), style.blinking)
+    }
+
     /// Feed raw bytes from the PTY into the terminal parser.
     /// Also extracts shell integration events (OSC 7, OSC 133) from the byte stream
     /// and processes any title events from the event listener.
     pub fn feed(&mut self, bytes: &[u8]) {
+        // The real term is away being reflowed; replay this once it returns
+        if let Some(reflow) = &mut self.reflow {
+            if reflow.pending_bytes.len() + bytes.len() <= MAX_REFLOW_PENDING_BYTES {
+                reflow.pending_bytes.extend_from_slice(bytes);
+                return;
+            }
+            // Too much to hold: finish the reflow here and parse inline
+            while self.reflow.is_some() {
+                self.wait_reflow();
+            }
+        }
+
         // Pre-scan for OSC 7 and OSC 133 sequences before alacritty_terminal processes them
         let shell_events = shell_integration::extract_shell_events(bytes);
+        self.urxvt_mouse = crate::input::mouse_report::scan_urxvt_mode(bytes, self.urxvt_mouse);
 
         // Feed to alacritty_terminal for normal VT processing
         self.processor.advance(&mut self.term, bytes);
@@ -114,6 +212,9 @@ impl Terminal {
                 TerminalEvent::Bell => {
                     self.bell_pending = true;
                 }
+                TerminalEvent::Clipboard(request) => {
+                    self.clipboard_requests.push(request);
+                }
             }
         }
     }
@@ -147,6 +248,33 @@ impl Terminal {
         self.term.mode().contains(TermMode::BRACKETED_PASTE)
     }
 
+    /// Mouse reporting requested by the application (DECSET 1000/1002/1003)
+    /// and the coordinate encoding to use (1005/1006/1015, else X10).
+    pub fn mouse_protocol(&self) -> crate::input::mouse_report::MouseProtocol {
+        use crate::input::mouse_report::{MouseEncoding, MouseProtocol, MouseTracking};
+        use alacritty_terminal::term::TermMode;
+        let mode = self.term.mode();
+        let tracking = if mode.contains(TermMode::MOUSE_MOTION) {
+            MouseTracking::Motion
+        } else if mode.contains(TermMode::MOUSE_DRAG) {
+            MouseTracking::Drag
+        } else if mode.contains(TermMode::MOUSE_REPORT_CLICK) {
+            MouseTracking::Click
+        } else {
+            MouseTracking::Off
+        };
+        let encoding = if mode.contains(TermMode::SGR_MOUSE) {
+            MouseEncoding::Sgr
+        } else if self.urxvt_mouse {
+            MouseEncoding::Urxvt
+        } else if mode.contains(TermMode::UTF8_MOUSE) {
+            MouseEncoding::Utf8
+        } else {
+            MouseEncoding::X10
+        };
+        MouseProtocol { tracking, encoding }
+    }
+
     /// Get the cursor position as (row, col).
     pub fn cursor_position(&self) -> (usize, usize) {
         let content = self.term.renderable_content();
@@ -187,6 +315,105 @@ impl Terminal {
         Some(self.term.grid()[point].c)
     }
 
+    /// Whether the cell at a position in the full buffer (indexed like
+    /// `char_at`) is the right half of a double-width character.
+    pub fn is_wide_spacer(&self, row: usize, col: usize) -> bool {
+        if row >= self.total_rows() || col >= self.columns() {
+            return false;
+        }
+        let line_idx = row as i32 - self.history_size() as i32;
+        let point = Point::new(Line(line_idx), Column(col));
+        self.term.grid()[point].flags.contains(alacritty_terminal::term::cell::Flags::WIDE_CHAR_SPACER)
+    }
+
+    /// Text of one row (0 = top of screen, negative = scrollback), ignoring
+    /// the display offset. Out-of-range rows are empty.
+    pub fn line_text(&self, row: i32) -> String {
+        let top = -(self.history_size() as i32);
+        if row < top || row >= self.rows() as i32 {
+            return String::new();
+        }
+        let grid = self.term.grid();
+        (0..self.columns())
+            .map(|col| grid[Point::new(Line(row), Column(col))].c)
+            .collect()
+    }
+
+    /// The logical line ending at `row` (see `line_text`): its soft-wrapped
+    /// rows from the first one, mapped to their cells for matching. Empty
+    /// for out-of-range rows.
+    pub fn line_map(&self, row: i32) -> text_map::TextMap {
+        let history = self.history_size() as i32;
+        let mut map = text_map::TextMap::new();
+        if row < -history || row >= self.rows() as i32 {
+            return map;
+        }
+        let mut first = row;
+        while first > -history && self.is_wrapped((first - 1 + history) as usize) {
+            first -= 1;
+        }
+        for line in first..=row {
+            grid_bridge::push_row_text(&mut map, self.term.grid(), line, line);
+        }
+        map
+    }
+
+    /// Whether buffer line `line` (indexed like `char_at`) soft-wraps onto
+    /// the next line.
+    pub fn is_wrapped(&self, line: usize) -> bool {
+        if line >= self.total_rows() {
+            return false;
+        }
+        let row = line as i32 - self.history_size() as i32;
+        let point = Point::new(Line(row), Column(self.columns() - 1));
+        self.term.grid()[point].flags.contains(alacritty_terminal::term::cell::Flags::WRAPLINE)
+    }
+
+    /// First buffer line of the logical (unwrapped) line containing `line`.
+    pub fn logical_line_start(&self, line: usize) -> usize {
+        let mut start = line.min(self.total_rows().saturating_sub(1));
+        while start > 0 && self.is_wrapped(start - 1) {
+            start -= 1;
+        }
+        start
+    }
+
+    /// Up to `max_chars` of the logical line starting at buffer `line`,
+    /// trailing blanks trimmed. The text doesn't change when a resize
+    /// rewraps the line. None if `line` is out of range or continues the
+    /// line above.
+    pub fn logical_line_text(&self, line: usize, max_chars: usize) -> Option<String> {
+        if line >= self.total_rows() || (line > 0 && self.is_wrapped(line - 1)) {
+            return None;
+        }
+        let history = self.history_size() as i32;
+        let mut text = String::new();
+        let mut current = line;
+        loop {
+            text.push_str(&self.line_text(current as i32 - history));
+            if text.chars().count() >= max_chars || !self.is_wrapped(current) {
+                break;
+            }
+            current += 1;
+        }
+        let mut text: String = text.chars().take(max_chars).collect();
+        text.truncate(text.trim_end().len());
+        Some(text)
+    }
+
+    /// Whether the alternate screen (full-screen programs) is showing.
+    pub fn is_alt_screen(&self) -> bool {
+        self.term.mode().contains(alacritty_terminal::term::TermMode::ALT_SCREEN)
+    }
+
+    /// Every row from the oldest scrollback line to the bottom of the screen,
+    /// with its row number (see `line_text`). Lines are built lazily, so
+    /// callers can stop or walk backwards without touching the whole buffer.
+    pub fn history_lines(&self) -> impl DoubleEndedIterator<Item = (i32, String)> + '_ {
+        let top = -(self.history_size() as i32);
+        (top..self.rows() as i32).map(move |row| (row, self.line_text(row)))
+    }
+
     /// Get the number of lines in scrollback history.
     pub fn history_size(&self) -> usize {
         self.term.grid().history_size()
@@ -241,15 +468,128 @@ impl Terminal {
                 self.term.grid_mut()[point].reset(&Default::default());
             }
         }
+        self.full_damage = true;
     }
 
     /// Resize the terminal grid to new dimensions. Triggers content reflow.
+    ///
+    /// With at least `background_reflow_lines` of scrollback the reflow runs on
+    /// a worker thread (see `poll_reflow`); resizes requested meanwhile are
+    /// coalesced and applied when it finishes.
     pub fn resize(&mut self, cols: usize, rows: usize) {
         let size = TermSize {
             columns: cols,
             screen_lines: rows,
         };
-        self.term.resize(size);
+        if let Some(reflow) = &mut self.reflow {
+            reflow.debouncer.request(cols, rows);
+            self.term.resize(size);
+            return;
+        }
+        if self.background_reflow_lines > 0 && self.history_size() >= self.background_reflow_lines {
+            self.start_reflow(size);
+        } else {
+            self.term.resize(size);
+        }
+    }
+
+    /// Set the scrollback size from which resizes reflow in the background
+    /// (0 = always reflow inline).
+    pub fn set_background_reflow_lines(&mut self, lines: usize) {
+        self.background_reflow_lines = lines;
+    }
+
+    /// Whether a background reflow is in progress.
+    pub fn is_reflowing(&self) -> bool {
+        self.reflow.is_some()
+    }
+
+    /// Swap the reflowed term back in if the worker is done, then apply any
+    /// resize requested meanwhile and replay buffered output. Returns true
+    /// when the grid changed.
+    pub fn poll_reflow(&mut self) -> bool {
+        let Some(reflow) = &self.reflow else {
+            return false;
+        };
+        let term = match reflow.rx.try_recv() {
+            Ok(term) => Some(term),
+            Err(crossbeam_channel::TryRecvError::Empty) => return false,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                log::error!("Background reflow worker exited without returning the terminal");
+                None
+            }
+        };
+        self.finish_reflow(term);
+        true
+    }
+
+    /// Block until the background reflow is done, then finish it as
+    /// `poll_reflow` would.
+    fn wait_reflow(&mut self) {
+        let Some(reflow) = &self.reflow else {
+            return;
+        };
+        let term = match reflow.rx.recv() {
+            Ok(term) => Some(term),
+            Err(_) => {
+                log::error!("Background reflow worker exited without returning the terminal");
+                None
+            }
+        };
+        self.finish_reflow(term);
+    }
+
+    /// Swap in the reflowed `term` (a blank one if the worker died), apply
+    /// the latest requested size and replay buffered output.
+    fn finish_reflow(&mut self, term: Option<Term>) {
+        let reflow = self.reflow.take().expect("finish_reflow without a reflow");
+        let (cols, rows) = reflow
+            .debouncer
+            .pending()
+            .unwrap_or((self.columns(), self.rows()));
+        if let Some(mut term) = term {
+            // Keep the viewport pinned to the bottom, like an inline resize
+            term.scroll_display(Scroll::Bottom);
+            self.term = term;
+            self.apply_term_config();
+        } else {
+            self.term = Self::placeholder_term(&TermSize { columns: cols, screen_lines: rows }, "");
+        }
+        self.full_damage = true;
+        if self.columns() != cols || self.rows() != rows {
+            self.resize(cols, rows);
+        }
+        if !reflow.pending_bytes.is_empty() {
+            self.feed(&reflow.pending_bytes);
+        }
+    }
+
+    /// Move the term to a worker thread for resizing, showing a placeholder.
+    fn start_reflow(&mut self, size: TermSize) {
+        let placeholder = Self::placeholder_term(&size, REFLOW_MESSAGE);
+        let mut term = std::mem::replace(&mut self.term, placeholder);
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            term.resize(size);
+            let _ = tx.send(term);
+        });
+        self.reflow = Some(Reflow {
+            rx,
+            debouncer: ResizeDebouncer::new(),
+            pending_bytes: Vec::new(),
+        });
+    }
+
+    /// A scrollback-less term of `size` showing `message` on its first row.
+    fn placeholder_term(size: &TermSize, message: &str) -> Term {
+        let config = Config {
+            scrolling_history: 0,
+            ..Config::default()
+        };
+        let (listener, _, _) = listener::create_listener();
+        let mut term = Term::new(config, size, listener);
+        ansi::Processor::<ansi::StdSyncHandler>::new().advance(&mut term, message.as_bytes());
+        term
     }
 
     /// Returns true if a bell event is pending, and clears it.
@@ -263,6 +603,7 @@ impl Terminal {
     pub fn clear_scrollback(&mut self) {
         self.term.grid_mut().clear_history();
         self.term.scroll_display(Scroll::Bottom);
+        self.full_damage = true;
     }
 
     /// Access the shell state for this terminal.
@@ -282,6 +623,11 @@ impl Terminal {
         listener::drain_responses(&self.response_queue)
     }
 
+    /// Drain pending OSC 52 clipboard writes and reads.
+    pub fn drain_clipboard_requests(&mut self) -> Vec<ClipboardRequest> {
+        std::mem::take(&mut self.clipboard_requests)
+    }
+
     /// Drain pending Kitty Graphics Protocol responses to write back to PTY.
     pub fn drain_image_responses(&mut self) -> Vec<String> {
         std::mem::take(&mut self.image_responses)
@@ -577,6 +923,32 @@ mod tests {
         assert!(term.shell_state().title.is_none());
     }
 
+    #[test]
+    fn decscusr_sets_cursor_style() {
+        let mut term = Terminal::new(80, 24, 100);
+        assert_eq!(term.cursor_style(), (CursorStyle::Block, false));
+        // vim's insert mode: steady bar
+        term.feed(b"\x1b[6 q");
+        assert_eq!(term.cursor_style(), (CursorStyle::Beam, false));
+        term.feed(b"\x1b[3 q");
+        assert_eq!(term.cursor_style(), (CursorStyle::Underline, true));
+        term.feed(b"\x1b[2 q");
+        assert_eq!(term.cursor_style(), (CursorStyle::Block, false));
+    }
+
+    #[test]
+    fn decscusr_zero_restores_default_cursor_style() {
+        let mut term = Terminal::new(80, 24, 100);
+        term.feed(b"\x1b]2;kept\x07");
+        term.set_default_cursor_style(CursorStyle::Underline, true);
+        assert_eq!(term.cursor_style(), (CursorStyle::Underline, true));
+        term.feed(b"\x1b[5 q");
+        assert_eq!(term.cursor_style(), (CursorStyle::Beam, true));
+        term.feed(b"\x1b[0 q");
+        assert_eq!(term.cursor_style(), (CursorStyle::Underline, true));
+        assert_eq!(term.shell_state().title.as_deref(), Some("kept"));
+    }
+
     #[test]
     fn feed_osc2_title_updates_shell_state() {
         let mut term = Terminal::new(80, 24, 10_000);
@@ -847,4 +1219,169 @@ mod tests {
         assert_eq!(term.image_store().placement_count(), 0);
         assert_eq!(term.image_store().memory_used(), 0);
     }
+
+
+    // ── Background reflow ───────────────────────────────────────────────
+
+    fn terminal_with_history(lines: usize) -> Terminal {
+        let mut term = Terminal::new(40, 10, 10_000);
+        for i in 0..lines {
+            term.feed(format!("line {i}\r\n").as_bytes());
+        }
+        term
+    }
+
+    fn wait_for_reflow(term: &mut Terminal) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !term.poll_reflow() {
+            assert!(std::time::Instant::now() < deadline, "reflow did not finish");
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn small_scrollback_resizes_inline() {
+        let mut term = terminal_with_history(50);
+        term.resize(60, 12);
+        assert!(!term.is_reflowing());
+        assert_eq!((term.columns(), term.rows()), (60, 12));
+        assert!(!term.poll_reflow());
+    }
+
+    #[test]
+    fn large_scrollback_reflows_in_background() {
+        let mut term = terminal_with_history(200);
+        term.set_background_reflow_lines(100);
+        let history = term.history_size();
+        term.resize(60, 12);
+        assert!(term.is_reflowing());
+        // Placeholder already has the new size and shows the notice
+        assert_eq!((term.columns(), term.rows()), (60, 12));
+        assert_eq!(term.cell_char(0, 0), 'R');
+        assert_eq!(term.history_size(), 0);
+
+        term.feed(b"after");
+        wait_for_reflow(&mut term);
+        assert!(!term.is_reflowing());
+        assert_eq!((term.columns(), term.rows()), (60, 12));
+        assert!(term.history_size() >= history - 2);
+        let (row, _) = term.cursor_position();
+        let text: String = (0..5).map(|col| term.cell_char(row, col)).collect();
+        assert_eq!(text, "after");
+    }
+
+    #[test]
+    fn resizes_during_reflow_coalesce_to_latest() {
+        let mut term = terminal_with_history(200);
+        term.set_background_reflow_lines(100);
+        term.resize(60, 12);
+        term.resize(70, 14);
+        term.resize(30, 8);
+        // Drain every follow-up reflow until the term settles
+        while term.is_reflowing() {
+            wait_for_reflow(&mut term);
+        }
+        assert_eq!((term.columns(), term.rows()), (30, 8));
+    }
+
+    #[test]
+    fn output_past_the_reflow_buffer_waits_for_the_reflow() {
+        let mut term = terminal_with_history(200);
+        term.set_background_reflow_lines(100);
+        term.resize(60, 12);
+        assert!(term.is_reflowing());
+        term.feed(&vec![b'x'; MAX_REFLOW_PENDING_BYTES - 5]);
+        assert!(term.is_reflowing());
+        term.feed(b"\r\nafter");
+        assert!(!term.is_reflowing());
+        assert_eq!((term.columns(), term.rows()), (60, 12));
+        let (row, _) = term.cursor_position();
+        let text: String = (0..5).map(|col| term.cell_char(row, col)).collect();
+        assert_eq!(text, "after");
+    }
+
+    #[test]
+    fn zero_threshold_never_reflows_in_background() {
+        let mut term = terminal_with_history(200);
+        term.set_background_reflow_lines(0);
+        term.resize(60, 12);
+        assert!(!term.is_reflowing());
+    }
+
+
+    // ── History lines ───────────────────────────────────────────────────
+
+    #[test]
+    fn history_lines_cover_scrollback_and_screen() {
+        let mut term = Terminal::new(20, 5, 10_000);
+        for i in 0..12 {
+            term.feed(format!("line {i}\r\n").as_bytes());
+        }
+        let history = term.history_size() as i32;
+        let lines: Vec<(i32, String)> = term.history_lines().collect();
+        assert_eq!(lines.len(), term.total_rows());
+        assert_eq!(lines[0].0, -history);
+        assert!(lines[0].1.starts_with("line 0"));
+        assert_eq!(lines.last().unwrap().0, 4);
+        // Newest-first walk without building every line
+        let (row, text) = term.history_lines().rev().nth(1).unwrap();
+        assert_eq!(row, 3);
+        assert!(text.starts_with("line 11"));
+    }
+
+    #[test]
+    fn line_text_ignores_display_offset() {
+        let mut term = Terminal::new(20, 5, 10_000);
+        for i in 0..12 {
+            term.feed(format!("line {i}\r\n").as_bytes());
+        }
+        let before = term.line_text(0);
+        term.scroll_up(3);
+        assert_eq!(term.line_text(0), before);
+        assert_eq!(term.line_text(100), "");
+        assert_eq!(term.line_text(-100), "");
+    }
+
+
+    // ── Mouse reporting modes ───────────────────────────────────────────
+
+    #[test]
+    fn mouse_protocol_follows_decset() {
+        use crate::input::mouse_report::{MouseEncoding, MouseTracking};
+        let mut term = Terminal::new(80, 24, 100);
+        assert!(!term.mouse_protocol().is_active());
+        term.feed(b"\x1b[?1002h\x1b[?1006h");
+        let p = term.mouse_protocol();
+        assert_eq!((p.tracking, p.encoding), (MouseTracking::Drag, MouseEncoding::Sgr));
+        term.feed(b"\x1b[?1006l\x1b[?1015h\x1b[?1003h");
+        let p = term.mouse_protocol();
+        assert_eq!((p.tracking, p.encoding), (MouseTracking::Motion, MouseEncoding::Urxvt));
+        term.feed(b"\x1b[?1003l\x1b[?1002l");
+        assert!(!term.mouse_protocol().is_active());
+    }
+
+    // ── Logical lines ────────────────────────────────────────────────
+
+    #[test]
+    fn logical_line_text_joins_wrapped_rows() {
+        let mut term = Terminal::new(10, 5, 100);
+        term.feed(b"abcdefghijklmno\r\nxyz");
+        assert!(term.is_wrapped(0));
+        assert!(!term.is_wrapped(1));
+        assert_eq!(term.logical_line_start(1), 0);
+        assert_eq!(term.logical_line_text(0, 80).as_deref(), Some("abcdefghijklmno"));
+        assert_eq!(term.logical_line_text(1, 80), None);
+        assert_eq!(term.logical_line_text(2, 80).as_deref(), Some("xyz"));
+        assert_eq!(term.logical_line_text(0, 4).as_deref(), Some("abcd"));
+    }
+
+    #[test]
+    fn logical_line_text_survives_reflow() {
+        let mut term = Terminal::new(10, 5, 100);
+        term.set_background_reflow_lines(0);
+        term.feed(b"abcdefghijklmno\r\nxyz");
+        term.resize(20, 5);
+        assert_eq!(term.logical_line_text(0, 80).as_deref(), Some("abcdefghijklmno"));
+        assert_eq!(term.logical_line_text(1, 80).as_deref(), Some("xyz"));
+    }
 }
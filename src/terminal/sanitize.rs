@@ -0,0 +1,520 @@
+// Escape sequence filtering for content VeloTerm doesn't trust. Pasted text
+// loses every escape sequence and control character (bar tab and newlines),
+// so it can't end a bracketed paste early or type hidden commands. Output of
+// a pane marked untrusted keeps its colors and cursor movement but loses the
+// sequences that reach outside the pane: title changes, OSC 52 clipboard
+// writes and queries whose replies would be typed into the shell. The same
+// filter hides modern features from a pane on the legacy compatibility
+// preset (see `compat`).
+
+use std::borrow::Cow;
+
+use super::compat::Compat;
+
+/// Longest sequence held back while deciding whether to drop it. Longer
+/// CSI sequences and color OSC strings, which may still be queries, are
+/// dropped; other strings pass through.
+const MAX_HELD: usize = 4096;
+
+/// `text` with escape sequences and control characters other than tab,
+/// line feed and carriage return removed.
+pub fn sanitize_paste(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| is_control(c) && !matches!(c, '\t' | '\n' | '\r')) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, DCS, SOS, PM, APC: up to BEL or ST
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Two- or three-character escapes (ESC 7, ESC ( B)
+                Some(' '..='/') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\t' | '\n' | '\r' => out.push(c),
+            c if is_control(c) => {}
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Whether `c` is a C0 or C1 control character (or DEL).
+fn is_control(c: char) -> bool {
+    c < ' ' || c == '\x7f' || ('\u{80}'..='\u{9f}').contains(&c)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum FilterState {
+    #[default]
+    Ground,
+    /// After ESC.
+    Escape,
+    /// Inside `ESC [`, held until the final byte.
+    Csi,
+    /// Inside a CSI too long to hold, dropped through its final byte.
+    CsiDrop,
+    /// Inside an OSC or DCS string, held until it can be judged.
+    Held,
+    /// Inside a string already judged: passed on or dropped to its end.
+    Stream { keep: bool },
+}
+
+/// Output transform for an untrusted or legacy pane. Keeps a sequence split
+/// across reads until the rest arrives.
+#[derive(Debug, Clone)]
+pub struct OutputFilter {
+    /// Drop title changes too. Queries and clipboard writes always go.
+    untrusted: bool,
+    /// Drop kitty keyboard and graphics sequences and OSC features beyond
+    /// titles, colors and the working directory.
+    legacy: bool,
+    state: FilterState,
+    /// The sequence being read, not yet passed on.
+    held: Vec<u8>,
+    /// The last byte of a string was ESC, which may start its terminator.
+    string_escape: bool,
+}
+
+impl Default for OutputFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputFilter {
+    /// Filter for an untrusted pane.
+    pub fn new() -> Self {
+        Self {
+            untrusted: true,
+            legacy: false,
+            state: FilterState::Ground,
+            held: Vec::new(),
+            string_escape: false,
+        }
+    }
+
+    /// Filter for a pane, None when it is trusted and on the standard
+    /// preset.
+    pub fn for_pane(trusted: bool, compat: Compat) -> Option<Self> {
+        let legacy = compat == Compat::Legacy;
+        (!trusted || legacy).then(|| Self { untrusted: !trusted, legacy, ..Self::new() })
+    }
+
+    pub fn is_untrusted(&self) -> bool {
+        self.untrusted
+    }
+
+    /// `bytes` without title, clipboard and query sequences.
+    pub fn filter(&mut self, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            self.push(&mut out, byte);
+        }
+        out
+    }
+
+    /// Bytes still held back, when the filter is switched off.
+    pub fn finish(&mut self) -> Vec<u8> {
+        self.state = FilterState::Ground;
+        self.string_escape = false;
+        std::mem::take(&mut self.held)
+    }
+
+    /// Follows vte's state machine closely enough that a sequence can't hide
+    /// from the filter: ESC restarts a sequence from anywhere (and ends an
+    /// OSC or DCS string), CAN and SUB abort one, and other C0 controls run
+    /// without interrupting it.
+    fn push(&mut self, out: &mut Vec<u8>, byte: u8) {
+        match self.state {
+            FilterState::Ground => {
+                if byte == 0x1b {
+                    self.held.push(byte);
+                    self.state = FilterState::Escape;
+                } else {
+                    out.push(byte);
+                }
+            }
+            FilterState::Escape => match byte {
+                // A repeated ESC, DEL and high bytes are ignored by vte
+                0x1b | 0x7f..=0xff => {}
+                0x18 | 0x1a => self.abort(out, byte),
+                0x00..=0x1f => out.push(byte),
+                _ => {
+                    self.held.push(byte);
+                    match byte {
+                        b'[' => self.state = FilterState::Csi,
+                        b']' | b'P' => self.state = FilterState::Held,
+                        // DECID asks for the terminal's identity
+                        b'Z' => self.drop_held(),
+                        // APC, PM, SOS: kitty graphics and the like
+                        b'_' | b'^' | b'X' if self.legacy => {
+                            self.held.clear();
+                            self.state = FilterState::Stream { keep: false };
+                        }
+                        _ => self.pass_held(out),
+                    }
+                }
+            },
+            FilterState::Csi => match byte {
+                0x1b => self.restart(),
+                0x18 | 0x1a => self.abort(out, byte),
+                0x00..=0x1f => out.push(byte),
+                0x7f..=0xff => {}
+                _ => {
+                    self.held.push(byte);
+                    if (0x40..=0x7e).contains(&byte) {
+                        if csi_allowed(&self.held[2..], self.legacy) {
+                            self.pass_held(out);
+                        } else {
+                            self.drop_held();
+                        }
+                    } else if self.held.len() > MAX_HELD {
+                        // vte saturates the parameters, so the padding
+                        // can't disarm a query
+                        self.held.clear();
+                        self.state = FilterState::CsiDrop;
+                    }
+                }
+            },
+            FilterState::CsiDrop => match byte {
+                0x1b => self.restart(),
+                0x18 | 0x1a => self.abort(out, byte),
+                0x00..=0x1f => out.push(byte),
+                0x40..=0x7e => self.state = FilterState::Ground,
+                _ => {}
+            },
+            FilterState::Held => {
+                if std::mem::take(&mut self.string_escape) {
+                    if byte == b'\\' {
+                        self.held.push(byte);
+                        self.close_string(out);
+                    } else {
+                        // The ESC ended the string and starts a new sequence
+                        self.held.pop();
+                        self.close_string(out);
+                        self.restart();
+                        self.push(out, byte);
+                    }
+                    return;
+                }
+                match byte {
+                    0x07 => {
+                        self.held.push(byte);
+                        self.close_string(out);
+                    }
+                    0x18 | 0x1a => {
+                        self.close_string(out);
+                        out.push(byte);
+                    }
+                    0x1b => {
+                        self.held.push(byte);
+                        self.string_escape = true;
+                    }
+                    _ => {
+                        self.held.push(byte);
+                        if let Some(keep) = self.string_verdict() {
+                            if keep {
+                                out.append(&mut self.held);
+                            }
+                            self.held.clear();
+                            self.state = FilterState::Stream { keep };
+                        }
+                    }
+                }
+            }
+            FilterState::Stream { keep } => {
+                if std::mem::take(&mut self.string_escape) {
+                    self.state = FilterState::Ground;
+                    if byte == b'\\' {
+                        if keep {
+                            out.extend_from_slice(b"\x1b\\");
+                        }
+                    } else {
+                        self.restart();
+                        self.push(out, byte);
+                    }
+                    return;
+                }
+                match byte {
+                    0x07 => {
+                        if keep {
+                            out.push(byte);
+                        }
+                        self.state = FilterState::Ground;
+                    }
+                    0x18 | 0x1a => {
+                        out.push(byte);
+                        self.state = FilterState::Ground;
+                    }
+                    0x1b => self.string_escape = true,
+                    _ if keep => out.push(byte),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Pass or drop the complete OSC or DCS string held.
+    fn close_string(&mut self, out: &mut Vec<u8>) {
+        if self.string_allowed() {
+            self.pass_held(out);
+        } else {
+            self.drop_held();
+        }
+    }
+
+    /// ESC in the middle of a sequence: vte abandons it and starts over.
+    fn restart(&mut self) {
+        self.held.clear();
+        self.held.push(0x1b);
+        self.state = FilterState::Escape;
+    }
+
+    /// CAN or SUB: vte abandons the sequence and runs the control.
+    fn abort(&mut self, out: &mut Vec<u8>, byte: u8) {
+        self.drop_held();
+        out.push(byte);
+    }
+
+    fn pass_held(&mut self, out: &mut Vec<u8>) {
+        out.append(&mut self.held);
+        self.state = FilterState::Ground;
+    }
+
+    fn drop_held(&mut self) {
+        self.held.clear();
+        self.state = FilterState::Ground;
+    }
+
+    /// Judge the held OSC or DCS string from its start, once enough of it
+    /// has arrived: Some(keep), or None to keep holding it. A color string
+    /// longer than `MAX_HELD` is dropped, as it may end in a query.
+    fn string_verdict(&self) -> Option<bool> {
+        let held = &self.held;
+        let body = &held[2..];
+        if held[1] == b'P' {
+            // XTGETTCAP (+q) and DECRQSS ($q) ask for terminal settings
+            return (body.len() >= 2).then(|| body[..2] != *b"+q" && body[..2] != *b"$q");
+        }
+        let overflow = held.len() > MAX_HELD;
+        let end = match body.iter().position(|&b| b == b';') {
+            Some(end) => end,
+            None if overflow => body.len(),
+            None => return None,
+        };
+        match self.osc_verdict(&body[..end]) {
+            None if overflow => Some(false),
+            verdict => verdict,
+        }
+    }
+
+    /// Whether the complete held OSC or DCS string may pass.
+    fn string_allowed(&self) -> bool {
+        if let Some(keep) = self.string_verdict() {
+            return keep;
+        }
+        let held = &self.held;
+        if held[1] == b'P' {
+            return true;
+        }
+        let body = held[2..].strip_suffix(b"\x07").or_else(|| held[2..].strip_suffix(b"\x1b\\")).unwrap_or(&held[2..]);
+        let mut params = body.split(|&b| b == b';');
+        let number = params.next().unwrap_or_default();
+        // An OSC with no parameters at all (`ESC ] 2 BEL`) still counts
+        if let Some(keep) = self.osc_verdict(number) {
+            return keep;
+        }
+        !params.any(|param| param == b"?")
+    }
+
+    /// Judge an OSC by its number: Some(keep), or None for color strings,
+    /// which are queries when a parameter is `?`, known only at the end.
+    fn osc_verdict(&self, number: &[u8]) -> Option<bool> {
+        match number {
+            b"0" | b"1" | b"2" => Some(!self.untrusted),
+            b"52" => Some(false),
+            number if is_color_osc(number) => None,
+            number => Some(!self.legacy || is_basic_osc(number)),
+        }
+    }
+}
+
+/// Whether a CSI sequence (after `ESC [`, final byte included) may pass.
+/// Device attribute and status reports, mode and version queries and window
+/// operations (which include title reports) are dropped, and with `legacy`
+/// kitty keyboard mode changes.
+fn csi_allowed(body: &[u8], legacy: bool) -> bool {
+    let Some((&last, params)) = body.split_last() else {
+        return true;
+    };
+    match last {
+        b'c' | b'n' | b't' => false,
+        // DECRQM: CSI ? Ps $ p
+        b'p' => !params.ends_with(b"$"),
+        // XTVERSION: CSI > q
+        b'q' => !params.starts_with(b">"),
+        // Kitty keyboard flags query: CSI ? u; push, pop and set: CSI > u,
+        // CSI < u, CSI = u
+        b'u' if legacy => !matches!(params.first(), Some(b'>' | b'<' | b'=' | b'?')),
+        b'u' => params != b"?",
+        _ => true,
+    }
+}
+
+/// OSC 7 (working directory) and the palette and dynamic color resets,
+/// which even a legacy pane keeps.
+fn is_basic_osc(number: &[u8]) -> bool {
+    matches!(number, b"7" | b"104" | b"110" | b"111" | b"112" | b"113" | b"114" | b"115" | b"116" | b"117" | b"118" | b"119")
+}
+
+/// OSC 4 and 10-19 set or, given `?`, report palette and dynamic colors.
+fn is_color_osc(number: &[u8]) -> bool {
+    matches!(number, b"4" | b"10" | b"11" | b"12" | b"13" | b"14" | b"15" | b"16" | b"17" | b"18" | b"19")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filtered(bytes: &[u8]) -> Vec<u8> {
+        OutputFilter::new().filter(bytes)
+    }
+
+    // ── Paste ────────────────────────────────────────────────────────
+
+    #[test]
+    fn paste_keeps_plain_text_borrowed() {
+        assert!(matches!(sanitize_paste("ls -la\n\tcd ..\r\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn paste_loses_escapes_and_controls() {
+        assert_eq!(sanitize_paste("echo hi\x1b[201~; rm -rf ~\n"), "echo hi; rm -rf ~\n");
+        assert_eq!(sanitize_paste("a\x1b]52;c;ZXZpbA==\x07b"), "ab");
+        assert_eq!(sanitize_paste("a\x1b]2;title\x1b\\b\x1b(Bc"), "abc");
+        assert_eq!(sanitize_paste("x\x03\x08\u{9b}y\x7f"), "xy");
+    }
+
+    // ── Output ───────────────────────────────────────────────────────
+
+    #[test]
+    fn output_keeps_ordinary_sequences() {
+        let text = b"\x1b[1;31mred\x1b[0m \x1b[2J\x1b[H\x1b]8;;https://x\x07link\x1b]8;;\x07\x1b(B\r\n";
+        assert_eq!(filtered(text), text.to_vec());
+    }
+
+    #[test]
+    fn output_drops_titles_and_clipboard_writes() {
+        assert_eq!(filtered(b"a\x1b]0;spoof\x07b\x1b]2;x\x1b\\c"), b"abc");
+        assert_eq!(filtered(b"a\x1b]52;c;ZXZpbA==\x07b"), b"ab");
+        assert_eq!(filtered(b"a\x1b]2\x07b"), b"ab");
+    }
+
+    #[test]
+    fn output_drops_device_queries() {
+        assert_eq!(filtered(b"a\x1b[c\x1b[>0c\x1b[6n\x1b[21tb"), b"ab");
+        assert_eq!(filtered(b"a\x1b[?2004$p\x1b[>q\x1b[?u\x1bZb"), b"ab");
+        assert_eq!(filtered(b"a\x1bP+q544e\x1b\\\x1bP$qm\x1b\\b"), b"ab");
+        assert_eq!(filtered(b"a\x1b]11;?\x07\x1b]4;1;?\x1b\\b"), b"ab");
+        // Setting a color is fine
+        assert_eq!(filtered(b"\x1b]11;#102030\x07"), b"\x1b]11;#102030\x07");
+    }
+
+    #[test]
+    fn output_drops_queries_padded_past_the_hold_limit() {
+        let mut padded = b"a\x1b[".to_vec();
+        padded.extend(std::iter::repeat_n(b'0', 5000));
+        padded.extend_from_slice(b"cb");
+        assert_eq!(filtered(&padded), b"ab");
+
+        let mut color = b"a\x1b]4;1;".to_vec();
+        color.extend(std::iter::repeat_n(b'x', 5000));
+        color.extend_from_slice(b";1;?\x07b");
+        assert_eq!(filtered(&color), b"ab");
+
+        // Other long strings still pass
+        let mut link = b"\x1b]8;;https://x/".to_vec();
+        link.extend(std::iter::repeat_n(b'y', 5000));
+        link.push(0x07);
+        assert_eq!(filtered(&link), link);
+    }
+
+    #[test]
+    fn output_follows_vte_restarts_and_aborts() {
+        // A second ESC is ignored by vte, so this is still a DA query
+        assert_eq!(filtered(b"a\x1b\x1b[cb"), b"ab");
+        // ESC restarts a CSI: `]` starts an OSC rather than ending the CSI
+        assert_eq!(filtered(b"a\x1b[\x1b]2;spoof\x07b"), b"ab");
+        // CAN and SUB abort a sequence and still run
+        assert_eq!(filtered(b"a\x1b[\x18\x1b]0;spoof\x1ab"), b"a\x18\x1ab");
+        // C0 controls run without leaving the sequence
+        assert_eq!(filtered(b"a\x1b\n[c\x1b[?2004$\rpb"), b"a\n\rb");
+        // A lone ESC ends a string and starts the next sequence
+        assert_eq!(filtered(b"\x1b]8;;u\x1b[cb"), b"\x1b]8;;ub");
+        assert_eq!(filtered(b"a\x1b]11;?\x1b[6nb"), b"ab");
+    }
+
+    // ── Legacy preset ────────────────────────────────────────────────
+
+    fn legacy(bytes: &[u8]) -> Vec<u8> {
+        OutputFilter::for_pane(true, Compat::Legacy).unwrap().filter(bytes)
+    }
+
+    #[test]
+    fn trusted_standard_panes_are_not_filtered() {
+        assert!(OutputFilter::for_pane(true, Compat::Standard).is_none());
+        assert!(OutputFilter::for_pane(false, Compat::Standard).unwrap().is_untrusted());
+        assert!(!OutputFilter::for_pane(true, Compat::Legacy).unwrap().is_untrusted());
+    }
+
+    #[test]
+    fn legacy_keeps_titles_colors_and_cwd() {
+        let text = b"\x1b]2;vim\x07\x1b]7;file://host/tmp\x07\x1b]11;#102030\x07\x1b]104\x07\x1b[38;5;1mx\x1b[m";
+        assert_eq!(legacy(text), text.to_vec());
+    }
+
+    #[test]
+    fn legacy_drops_modern_features() {
+        // Kitty keyboard push, pop, set and query
+        assert_eq!(legacy(b"a\x1b[>1u\x1b[<u\x1b[=5;1u\x1b[?ub"), b"ab");
+        // Hyperlinks, notifications, semantic marks, iTerm2 and clipboard
+        assert_eq!(legacy(b"a\x1b]8;;https://x\x07b\x1b]8;;\x07"), b"ab");
+        assert_eq!(legacy(b"a\x1b]9;done\x07\x1b]133;A\x07\x1b]1337;SetMark\x07\x1b]52;c;Zm9v\x07b"), b"ab");
+        // Kitty graphics
+        assert_eq!(legacy(b"a\x1b_Ga=q,i=1;AAAA\x1b\\b"), b"ab");
+        // Queries, as for untrusted panes
+        assert_eq!(legacy(b"a\x1b[c\x1b]11;?\x07\x1bP+q5463\x1b\\b"), b"ab");
+        // Cursor save/restore keys (CSI s / CSI u) still work
+        assert_eq!(legacy(b"\x1b[s\x1b[u"), b"\x1b[s\x1b[u");
+    }
+
+    #[test]
+    fn sequences_split_across_reads() {
+        let mut filter = OutputFilter::new();
+        assert_eq!(filter.filter(b"a\x1b]5"), b"a");
+        assert_eq!(filter.filter(b"2;c;Zm9v"), b"");
+        assert_eq!(filter.filter(b"\x07b\x1b[3"), b"b");
+        assert_eq!(filter.filter(b"1mc"), b"\x1b[31mc");
+        filter.filter(b"\x1b[");
+        assert_eq!(filter.finish(), b"\x1b[");
+    }
+}
@@ -0,0 +1,340 @@
+// Terminal snapshots: bytes that rebuild a term's scrollback, screen, cursor
+// and modes in a fresh terminal of the same size. The daemon sends one to a
+// window attaching to a pane, since replaying recent raw output would miss
+// the modes set before it (alternate screen, cursor keys, mouse reporting).
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::event::EventListener;
+use alacritty_terminal::grid::{Dimensions, Grid, GridCell};
+use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vte::ansi::{Color, CursorShape};
+
+/// Modes restored by a snapshot with their DEC private (`?`) or ANSI
+/// numbers, set or reset when they differ from a fresh term's.
+const MODES: &[(TermMode, &str)] = &[
+    (TermMode::APP_CURSOR, "?1"),
+    (TermMode::SHOW_CURSOR, "?25"),
+    (TermMode::LINE_WRAP, "?7"),
+    (TermMode::INSERT, "4"),
+    (TermMode::LINE_FEED_NEW_LINE, "20"),
+    (TermMode::MOUSE_REPORT_CLICK, "?1000"),
+    (TermMode::MOUSE_DRAG, "?1002"),
+    (TermMode::MOUSE_MOTION, "?1003"),
+    (TermMode::FOCUS_IN_OUT, "?1004"),
+    (TermMode::UTF8_MOUSE, "?1005"),
+    (TermMode::SGR_MOUSE, "?1006"),
+    (TermMode::ALTERNATE_SCROLL, "?1007"),
+    (TermMode::URGENCY_HINTS, "?1042"),
+    (TermMode::BRACKETED_PASTE, "?2004"),
+];
+
+/// Bytes that redraw `term` in a fresh terminal of the same size: up to
+/// about `history_bytes` of its newest scrollback, the primary screen, the
+/// alternate screen when it is showing, the cursor and the modes programs
+/// set. The scroll region and origin mode aren't restored; full-screen
+/// programs set them again on their next redraw.
+pub fn snapshot<T: EventListener>(term: &mut Term<T>, history_bytes: usize) -> Vec<u8> {
+    let mut out = String::new();
+    if term.mode().contains(TermMode::ALT_SCREEN) {
+        // The primary screen is under the alternate one. Swapping back in
+        // clears the alternate grid, so it is put back from a copy.
+        let alt = term.grid().clone();
+        term.swap_alt();
+        write_primary(&mut out, term.grid(), history_bytes);
+        // Where 1049 saves the cursor, and restores it on the way out
+        write_cursor(&mut out, term.grid());
+        term.swap_alt();
+        *term.grid_mut() = alt;
+        out.push_str("\x1b[?1049h");
+        write_alt(&mut out, term.grid());
+    } else {
+        write_primary(&mut out, term.grid(), history_bytes);
+    }
+    write_cursor(&mut out, term.grid());
+    write_modes(&mut out, term);
+    out.into_bytes()
+}
+
+/// Scrollback and screen as lines printed from the top, so the oldest
+/// scroll into the new terminal's history.
+fn write_primary(out: &mut String, grid: &Grid<Cell>, history_bytes: usize) {
+    let history = grid.history_size() as i32;
+    let mut lines: Vec<String> = Vec::new();
+    let mut size = 0;
+    for line in (-history..0).rev() {
+        let text = row_text(grid, Line(line));
+        size += text.len() + 2;
+        if size > history_bytes {
+            break;
+        }
+        lines.push(text);
+    }
+    let mut first = -(lines.len() as i32);
+    // Don't start halfway through a wrapped line
+    while first < 0 && wraps(grid, Line(first - 1)) {
+        first += 1;
+    }
+    for line in first..grid.screen_lines() as i32 {
+        let text = if line < 0 {
+            std::mem::take(&mut lines[(-line - 1) as usize])
+        } else {
+            row_text(grid, Line(line))
+        };
+        out.push_str(&text);
+        // A wrapped row fills the width, and the next one wraps onto it
+        if !wraps(grid, Line(line)) && line + 1 < grid.screen_lines() as i32 {
+            out.push_str("\r\n");
+        }
+    }
+}
+
+/// The alternate screen, row by row.
+fn write_alt(out: &mut String, grid: &Grid<Cell>) {
+    for line in 0..grid.screen_lines() {
+        let _ = write!(out, "\x1b[{};1H", line + 1);
+        out.push_str(&row_text(grid, Line(line as i32)));
+    }
+}
+
+fn write_cursor(out: &mut String, grid: &Grid<Cell>) {
+    let cursor = grid.cursor.point;
+    let _ = write!(out, "\x1b[{};{}H", cursor.line.0 + 1, cursor.column.0 + 1);
+}
+
+/// Whether `line` soft-wraps onto the next.
+fn wraps(grid: &Grid<Cell>, line: Line) -> bool {
+    line.0 >= -(grid.history_size() as i32)
+        && grid[line][Column(grid.columns() - 1)]
+            .flags
+            .contains(Flags::WRAPLINE)
+}
+
+/// One row with its colors, attributes and links, trailing blanks dropped
+/// unless it wraps. Ends with attributes and links reset.
+fn row_text(grid: &Grid<Cell>, line: Line) -> String {
+    let row = &grid[line];
+    let cols = grid.columns();
+    let end = if wraps(grid, line) {
+        cols
+    } else {
+        (0..cols)
+            .rev()
+            .find(|&col| !row[Column(col)].is_empty())
+            .map_or(0, |col| col + 1)
+    };
+    let mut text = String::new();
+    let mut sgr = String::from("0");
+    let mut link = None;
+    for col in 0..end {
+        let cell = &row[Column(col)];
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        let cell_sgr = cell_sgr(cell);
+        if cell_sgr != sgr {
+            let _ = write!(text, "\x1b[{cell_sgr}m");
+            sgr = cell_sgr;
+        }
+        let cell_link = cell.hyperlink().map(|link| link.uri().to_string());
+        if cell_link != link {
+            let _ = write!(text, "\x1b]8;;{}\x1b\\", cell_link.as_deref().unwrap_or(""));
+            link = cell_link;
+        }
+        text.push(cell.c);
+        text.extend(cell.zerowidth().unwrap_or_default());
+    }
+    if link.is_some() {
+        text.push_str("\x1b]8;;\x1b\\");
+    }
+    if sgr != "0" {
+        text.push_str("\x1b[0m");
+    }
+    text
+}
+
+/// SGR parameters drawing `cell`, from a reset.
+fn cell_sgr(cell: &Cell) -> String {
+    let mut sgr = String::from("0");
+    for (flag, code) in [
+        (Flags::BOLD, "1"),
+        (Flags::DIM, "2"),
+        (Flags::ITALIC, "3"),
+        (Flags::UNDERLINE, "4"),
+        (Flags::DOUBLE_UNDERLINE, "4:2"),
+        (Flags::UNDERCURL, "4:3"),
+        (Flags::DOTTED_UNDERLINE, "4:4"),
+        (Flags::DASHED_UNDERLINE, "4:5"),
+        (Flags::INVERSE, "7"),
+        (Flags::HIDDEN, "8"),
+        (Flags::STRIKEOUT, "9"),
+    ] {
+        if cell.flags.contains(flag) {
+            sgr.push(';');
+            sgr.push_str(code);
+        }
+    }
+    push_color(&mut sgr, cell.fg, 30);
+    push_color(&mut sgr, cell.bg, 40);
+    if let Some(color) = cell.underline_color() {
+        push_color(&mut sgr, color, 50);
+    }
+    sgr
+}
+
+/// Append the SGR for `color` in the group starting at `base` (30
+/// foreground, 40 background, 50 underline). Default colors add nothing.
+fn push_color(sgr: &mut String, color: Color, base: u16) {
+    let _ = match color {
+        Color::Spec(rgb) => write!(sgr, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b),
+        Color::Indexed(index) => write!(sgr, ";{};5;{index}", base + 8),
+        Color::Named(named) if base != 50 && (named as usize) < 8 => {
+            write!(sgr, ";{}", base + named as u16)
+        }
+        Color::Named(named) if base != 50 && (named as usize) < 16 => {
+            write!(sgr, ";{}", base + 60 + named as u16 - 8)
+        }
+        Color::Named(named) if (named as usize) < 16 => write!(sgr, ";58;5;{}", named as usize),
+        Color::Named(_) => Ok(()),
+    };
+}
+
+/// Modes, keyboard protocol flags and cursor shape that differ from a
+/// fresh term's.
+fn write_modes<T: EventListener>(out: &mut String, term: &Term<T>) {
+    let mode = *term.mode();
+    let fresh = TermMode::default();
+    for &(flag, number) in MODES {
+        match (mode.contains(flag), fresh.contains(flag)) {
+            (true, false) => {
+                let _ = write!(out, "\x1b[{number}h");
+            }
+            (false, true) => {
+                let _ = write!(out, "\x1b[{number}l");
+            }
+            _ => {}
+        }
+    }
+    if mode.contains(TermMode::APP_KEYPAD) {
+        out.push_str("\x1b=");
+    }
+    let keyboard =
+        (mode & TermMode::KITTY_KEYBOARD_PROTOCOL).bits() / TermMode::DISAMBIGUATE_ESC_CODES.bits();
+    if keyboard != 0 {
+        let _ = write!(out, "\x1b[>{keyboard}u");
+    }
+    let style = term.cursor_style();
+    let shape = match style.shape {
+        CursorShape::Block => Some(1),
+        CursorShape::Underline => Some(3),
+        CursorShape::Beam => Some(5),
+        CursorShape::HollowBlock | CursorShape::Hidden => None,
+    };
+    if let Some(shape) = shape.filter(|_| style != Default::default()) {
+        let _ = write!(out, "\x1b[{} q", shape + u8::from(!style.blinking));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::Config;
+    use alacritty_terminal::vte::ansi::{self, NamedColor};
+
+    use crate::terminal::TermSize;
+
+    fn term(cols: usize, rows: usize) -> Term<VoidListener> {
+        let size = TermSize {
+            columns: cols,
+            screen_lines: rows,
+        };
+        Term::new(
+            Config {
+                scrolling_history: 100,
+                ..Config::default()
+            },
+            &size,
+            VoidListener,
+        )
+    }
+
+    fn feed(term: &mut Term<VoidListener>, bytes: &[u8]) {
+        ansi::Processor::<ansi::StdSyncHandler>::new().advance(term, bytes);
+    }
+
+    fn lines(term: &Term<VoidListener>) -> Vec<String> {
+        let grid = term.grid();
+        let history = grid.history_size() as i32;
+        (-history..grid.screen_lines() as i32)
+            .map(|line| {
+                let row = &grid[Line(line)];
+                let text: String = (0..grid.columns()).map(|col| row[Column(col)].c).collect();
+                text.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// `source` redrawn into a fresh term of the same size.
+    fn rebuilt(source: &mut Term<VoidListener>, history_bytes: usize) -> Term<VoidListener> {
+        let bytes = snapshot(source, history_bytes);
+        let mut copy = term(source.columns(), source.screen_lines());
+        feed(&mut copy, &bytes);
+        copy
+    }
+
+    #[test]
+    fn rebuilds_history_screen_and_cursor() {
+        let mut source = term(10, 3);
+        feed(
+            &mut source,
+            b"one\r\n\x1b[1;31mtwo\x1b[0m\r\nabcdefghijklm\r\nfour\r\n$ ls",
+        );
+        let copy = rebuilt(&mut source, 1024);
+        assert_eq!(lines(&copy), lines(&source));
+        assert_eq!(copy.grid().cursor.point, source.grid().cursor.point);
+        let two = &copy.grid()[Line(-2)][Column(0)];
+        assert_eq!(two.fg, Color::Named(NamedColor::Red));
+        assert!(two.flags.contains(Flags::BOLD));
+        // The wrapped line is still one logical line
+        assert!(wraps(copy.grid(), Line(-1)));
+    }
+
+    #[test]
+    fn history_is_cut_to_the_budget_at_a_line() {
+        let mut source = term(10, 2);
+        feed(&mut source, b"old\r\nnewer\r\nnewest\r\n$ ");
+        let copy = rebuilt(&mut source, 9);
+        assert_eq!(lines(&copy), ["newer", "newest", "$"]);
+        let none = rebuilt(&mut source, 0);
+        assert_eq!(none.grid().history_size(), 0);
+    }
+
+    #[test]
+    fn restores_alternate_screen_and_modes() {
+        let mut source = term(20, 4);
+        feed(
+            &mut source,
+            b"$ vim\r\n\x1b[?1049h\x1b[?1h\x1b=\x1b[?2004h\x1b[?1002h\x1b[?1006h\x1b[>1u\x1b[5 q",
+        );
+        feed(&mut source, b"\x1b[H~\x1b[2;1H~ file\x1b[4;5H");
+        let before = lines(&source);
+        let mut copy = rebuilt(&mut source, 1024);
+        // Taking the snapshot leaves the source as it was
+        assert_eq!(lines(&source), before);
+        assert_eq!(lines(&copy), before);
+        assert_eq!(*copy.mode(), *source.mode());
+        assert_eq!(copy.grid().cursor.point, source.grid().cursor.point);
+        assert_eq!(copy.cursor_style(), source.cursor_style());
+        // Leaving the alternate screen shows the shell's screen again
+        feed(&mut source, b"\x1b[?1049l");
+        feed(&mut copy, b"\x1b[?1049l");
+        assert_eq!(lines(&copy), lines(&source));
+        assert_eq!(lines(&copy)[0], "$ vim");
+    }
+}
@@ -0,0 +1,190 @@
+// Grid text for matchers. Regexes and linkify report byte offsets into the
+// text they are given, while highlights, hints and link hit-testing need
+// grid cells. The two drift apart on multi-byte characters, double-width
+// characters (one character, two cells) and soft-wrapped rows (one logical
+// line over several rows). A `TextMap` keeps a line's text together with the
+// cell of every character, so a byte range maps back to exactly the cells
+// that show it.
+
+use std::ops::Range;
+
+use unicode_width::UnicodeWidthChar;
+
+/// Where one character of the text sits on the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Placed {
+    /// Byte offset of the character in the text.
+    byte: usize,
+    row: i32,
+    col: usize,
+    /// Cells it covers: 2 for wide characters, 0 for combining marks.
+    width: usize,
+}
+
+/// Cells showing a piece of text, from `start` to `end` (exclusive column
+/// on `end_row`). Rows between the two are covered entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    pub start_row: i32,
+    pub start_col: usize,
+    pub end_row: i32,
+    pub end_col: usize,
+}
+
+impl CellRange {
+    /// Last covered cell, as (row, col). The start cell for an empty range.
+    pub fn last_cell(&self) -> (i32, usize) {
+        if self.end_row == self.start_row && self.end_col <= self.start_col {
+            (self.start_row, self.start_col)
+        } else {
+            (self.end_row, self.end_col.saturating_sub(1))
+        }
+    }
+}
+
+/// Text of one logical line with the grid cell of each character.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextMap {
+    text: String,
+    chars: Vec<Placed>,
+}
+
+impl TextMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map of plain text on `row`, placing characters by their display
+    /// width. For text that doesn't come from the grid (tests, prompts).
+    pub fn from_text(row: i32, text: &str) -> Self {
+        let mut map = Self::new();
+        let mut col: usize = 0;
+        for c in text.chars() {
+            let width = c.width().unwrap_or(1);
+            if width == 0 {
+                map.push(row, col.saturating_sub(1), c, 0);
+            } else {
+                map.push(row, col, c, width);
+            }
+            col += width;
+        }
+        map
+    }
+
+    /// One map per line of `lines`, line `i` on row `i`.
+    pub fn rows(lines: &[String]) -> Vec<Self> {
+        lines.iter().enumerate().map(|(row, line)| Self::from_text(row as i32, line)).collect()
+    }
+
+    /// Append character `c`, shown at `row`, `col` over `width` cells.
+    pub fn push(&mut self, row: i32, col: usize, c: char, width: usize) {
+        self.chars.push(Placed { byte: self.text.len(), row, col, width });
+        self.text.push(c);
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Row of the first character.
+    pub fn first_row(&self) -> Option<i32> {
+        self.chars.first().map(|placed| placed.row)
+    }
+
+    /// Row of the last character.
+    pub fn last_row(&self) -> Option<i32> {
+        self.chars.last().map(|placed| placed.row)
+    }
+
+    /// Cell of the character at or containing byte `byte`.
+    pub fn cell_at(&self, byte: usize) -> Option<(i32, usize)> {
+        let index = self.chars.partition_point(|placed| placed.byte <= byte).checked_sub(1)?;
+        let placed = self.chars[index];
+        Some((placed.row, placed.col))
+    }
+
+    /// Cells showing the text at `bytes`, as a matcher reported it. An
+    /// empty range covers no cells and starts where the next character
+    /// would.
+    pub fn cell_range(&self, bytes: Range<usize>) -> Option<CellRange> {
+        let first = self.chars.partition_point(|placed| placed.byte < bytes.start);
+        let end = self.chars.partition_point(|placed| placed.byte < bytes.end);
+        if first >= end {
+            let (row, col) = match self.chars.get(first) {
+                Some(placed) => (placed.row, placed.col),
+                None => self.chars.last().map(|placed| (placed.row, placed.col + placed.width))?,
+            };
+            return Some(CellRange { start_row: row, start_col: col, end_row: row, end_col: col });
+        }
+        let start = self.chars[first];
+        // Combining marks end where the character they sit on does
+        let last = self.chars[..end]
+            .iter()
+            .rev()
+            .find(|placed| placed.width > 0)
+            .filter(|placed| placed.byte >= start.byte)
+            .copied()
+            .unwrap_or(start);
+        Some(CellRange {
+            start_row: start.row,
+            start_col: start.col,
+            end_row: last.row,
+            end_col: last.col + last.width.max(1),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_bytes_are_columns() {
+        let map = TextMap::from_text(3, "hello world");
+        let range = map.cell_range(6..11).unwrap();
+        assert_eq!(range, CellRange { start_row: 3, start_col: 6, end_row: 3, end_col: 11 });
+        assert_eq!(range.last_cell(), (3, 10));
+    }
+
+    #[test]
+    fn multibyte_and_wide_characters_map_to_their_cells() {
+        // "→" is 3 bytes in 1 cell, "日本" 6 bytes in 4 cells
+        let map = TextMap::from_text(0, "→ 日本 ok");
+        let ok = map.text().find("ok").unwrap();
+        assert_eq!(map.cell_range(ok..ok + 2).unwrap().start_col, 7);
+        let wide = map.text().find('本').unwrap();
+        let range = map.cell_range(wide..wide + '本'.len_utf8()).unwrap();
+        assert_eq!((range.start_col, range.end_col), (4, 6));
+        // A byte inside a character belongs to it
+        assert_eq!(map.cell_at(wide + 1), Some((0, 4)));
+    }
+
+    #[test]
+    fn wrapped_rows_join_into_one_range() {
+        let mut map = TextMap::new();
+        for (col, c) in "abcd".chars().enumerate() {
+            map.push(-2, col, c, 1);
+        }
+        for (col, c) in "ef".chars().enumerate() {
+            map.push(-1, col, c, 1);
+        }
+        assert_eq!(map.text(), "abcdef");
+        let range = map.cell_range(2..5).unwrap();
+        assert_eq!(range, CellRange { start_row: -2, start_col: 2, end_row: -1, end_col: 1 });
+        assert_eq!((map.first_row(), map.last_row()), (Some(-2), Some(-1)));
+    }
+
+    #[test]
+    fn empty_ranges_and_combining_marks() {
+        let map = TextMap::from_text(0, "e\u{301}x");
+        assert_eq!(map.cell_range(0..3).unwrap().end_col, 1);
+        let empty = map.cell_range(4..4).unwrap();
+        assert_eq!((empty.start_col, empty.end_col), (2, 2));
+        assert_eq!(map.cell_range(0..0).unwrap().last_cell(), (0, 0));
+        assert_eq!(TextMap::new().cell_range(0..0), None);
+    }
+}
@@ -0,0 +1,219 @@
+// Theme schedule: `[colors.schedule]` takes turns between a day and a night
+// theme by local time, fading the colors over `transition_minutes` from each
+// switch time. The window polls it on a timer and applies the theme it
+// reports through the usual runtime theme switch.
+
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveTime, Timelike};
+
+use crate::config::theme::TerminalTheme;
+use crate::config::types::{ColorsConfig, ThemeScheduleConfig};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// How often the schedule is checked against the clock.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Minutes since midnight for an "HH:MM" time, or None if it isn't one.
+pub fn parse_clock_time(s: &str) -> Option<u32> {
+    let time = NaiveTime::parse_from_str(s, "%H:%M").ok()?;
+    Some(time.hour() * 60 + time.minute())
+}
+
+/// Lengths in minutes of the day (`day_start` to `night_start`) and the
+/// night, for start times in minutes since midnight.
+pub fn period_lengths(day_start: u32, night_start: u32) -> (u32, u32) {
+    let day = (night_start + MINUTES_PER_DAY - day_start) % MINUTES_PER_DAY;
+    (day, MINUTES_PER_DAY - day)
+}
+
+/// Where the schedule stands: `progress` (0.0-1.0) of the way from the
+/// colors of theme `from` to those of theme `to`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTheme {
+    pub from: String,
+    pub to: String,
+    pub progress: f32,
+}
+
+impl ScheduledTheme {
+    /// The theme the colors are nearer to, which shells and the rest of
+    /// the window go by.
+    pub fn name(&self) -> &str {
+        if self.progress < 0.5 {
+            &self.from
+        } else {
+            &self.to
+        }
+    }
+
+    /// The colors to draw with, or None if a theme is unknown.
+    pub fn theme(&self, colors: &ColorsConfig) -> Option<TerminalTheme> {
+        let from = colors.resolve_theme(&self.from)?;
+        let to = colors.resolve_theme(&self.to)?;
+        Some(from.mix(&to, self.progress))
+    }
+}
+
+/// A validated `[colors.schedule]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeSchedule {
+    day: String,
+    night: String,
+    day_start: u32,
+    night_start: u32,
+    transition: u32,
+}
+
+impl ThemeSchedule {
+    /// The schedule `config` describes, None when it is disabled or its
+    /// times are invalid (config validation reports them).
+    pub fn from_config(config: &ThemeScheduleConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        Some(Self {
+            day: config.day.clone(),
+            night: config.night.clone(),
+            day_start: parse_clock_time(&config.day_start)?,
+            night_start: parse_clock_time(&config.night_start)?,
+            transition: config.transition_minutes,
+        })
+    }
+
+    /// The theme at local `time`. Progress moves in steps of 1%, so a fade
+    /// changes the colors a hundred times at most.
+    pub fn at(&self, time: NaiveTime) -> ScheduledTheme {
+        let minute = time.hour() * 60 + time.minute();
+        let since_day_start = (minute + MINUTES_PER_DAY - self.day_start) % MINUTES_PER_DAY;
+        let (day_length, _) = period_lengths(self.day_start, self.night_start);
+        let (from, to, since_switch) = if since_day_start < day_length {
+            (&self.night, &self.day, since_day_start)
+        } else {
+            (&self.day, &self.night, since_day_start - day_length)
+        };
+        let elapsed = since_switch as f32 + time.second() as f32 / 60.0;
+        let progress = if self.transition == 0 {
+            1.0
+        } else {
+            (elapsed / self.transition as f32).min(1.0)
+        };
+        ScheduledTheme {
+            from: from.clone(),
+            to: to.clone(),
+            progress: (progress * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// Checks a schedule every `CHECK_INTERVAL`, reporting its theme when it
+/// changed. A theme picked by hand stays until the schedule moves on.
+#[derive(Debug)]
+pub struct ThemeScheduler {
+    schedule: ThemeSchedule,
+    next_check: Instant,
+    current: Option<ScheduledTheme>,
+}
+
+impl ThemeScheduler {
+    /// A scheduler for `config`, None when the schedule is disabled. Its
+    /// first poll reports the theme for the time.
+    pub fn new(config: &ThemeScheduleConfig) -> Option<Self> {
+        let schedule = ThemeSchedule::from_config(config)?;
+        Some(Self { schedule, next_check: Instant::now(), current: None })
+    }
+
+    /// The theme to apply, when the check is due at `now` and the theme at
+    /// local `time` differs from the last one reported.
+    pub fn poll(&mut self, now: Instant, time: NaiveTime) -> Option<ScheduledTheme> {
+        if now < self.next_check {
+            return None;
+        }
+        self.next_check = now + CHECK_INTERVAL;
+        let scheduled = self.schedule.at(time);
+        if self.current.as_ref() == Some(&scheduled) {
+            return None;
+        }
+        self.current = Some(scheduled.clone());
+        Some(scheduled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(transition_minutes: u32) -> ThemeSchedule {
+        ThemeSchedule::from_config(&ThemeScheduleConfig {
+            enabled: true,
+            transition_minutes,
+            ..ThemeScheduleConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_clock_times() {
+        assert_eq!(parse_clock_time("07:00"), Some(420));
+        assert_eq!(parse_clock_time("23:59"), Some(1439));
+        assert_eq!(parse_clock_time("24:00"), None);
+        assert_eq!(parse_clock_time("7pm"), None);
+        assert_eq!(period_lengths(420, 1140), (720, 720));
+        // A night shift: day from 22:00 to 06:00
+        assert_eq!(period_lengths(1320, 360), (480, 960));
+    }
+
+    #[test]
+    fn switches_at_once_without_transition() {
+        let schedule = schedule(0);
+        let noon = schedule.at(at(12, 0));
+        assert_eq!((noon.name(), noon.progress), ("light", 1.0));
+        assert_eq!(schedule.at(at(19, 0)).name(), "warm_dark");
+        assert_eq!(schedule.at(at(3, 0)).name(), "warm_dark");
+        assert_eq!(schedule.at(at(7, 0)).name(), "light");
+    }
+
+    #[test]
+    fn fades_over_the_transition_from_each_switch() {
+        let schedule = schedule(30);
+        let dusk = schedule.at(at(19, 6));
+        assert_eq!((dusk.from.as_str(), dusk.to.as_str(), dusk.progress), ("light", "warm_dark", 0.2));
+        assert_eq!(dusk.name(), "light");
+        assert_eq!(schedule.at(at(19, 15)).name(), "warm_dark");
+        assert_eq!(schedule.at(at(19, 30)).progress, 1.0);
+        let dawn = schedule.at(at(7, 24));
+        assert_eq!((dawn.to.as_str(), dawn.progress), ("light", 0.8));
+    }
+
+    #[test]
+    fn scheduler_reports_changes_once_per_check() {
+        let mut config = ThemeScheduleConfig { transition_minutes: 0, ..ThemeScheduleConfig::default() };
+        assert!(ThemeScheduler::new(&config).is_none());
+        config.enabled = true;
+        let mut scheduler = ThemeScheduler::new(&config).unwrap();
+        let start = Instant::now();
+        assert_eq!(scheduler.poll(start, at(12, 0)).unwrap().name(), "light");
+        // Not due yet, then due but unchanged
+        assert!(scheduler.poll(start + Duration::from_secs(1), at(20, 0)).is_none());
+        assert!(scheduler.poll(start + CHECK_INTERVAL, at(12, 1)).is_none());
+        let evening = scheduler.poll(start + CHECK_INTERVAL * 2, at(20, 0)).unwrap();
+        assert_eq!(evening.name(), "warm_dark");
+    }
+
+    #[test]
+    fn scheduled_theme_mixes_colors() {
+        let colors = ColorsConfig::default();
+        let halfway = ScheduledTheme { from: "light".into(), to: "warm_dark".into(), progress: 0.5 };
+        let theme = halfway.theme(&colors).unwrap();
+        let (light, dark) = (crate::config::theme::LIGHT, crate::config::theme::DARK);
+        assert!((theme.bg_deep.r - (light.bg_deep.r + dark.bg_deep.r) / 2.0).abs() < 1e-4);
+        assert_eq!(theme.name, "Warm Dark");
+        let unknown = ScheduledTheme { to: "nope".into(), ..halfway };
+        assert!(unknown.theme(&colors).is_none());
+    }
+}
@@ -1,5 +1,28 @@
 // Vi-mode: modal keyboard-driven navigation and selection for terminal scrollback.
 
+pub mod registers;
+
+use registers::Register;
+
+/// Vi-mode keys handled by `ViState::process_key`, for the cheatsheet.
+pub const KEY_HELP: &[(&str, &str)] = &[
+    ("h j k l", "Move cursor"),
+    ("w b e", "Word forward / back / end"),
+    ("f F t T", "To / back to / till / back till a character"),
+    ("; ,", "Repeat the last f F t T / in reverse"),
+    ("0 ^ $", "Line start / first non-blank / end"),
+    ("gg G", "Top / bottom of scrollback"),
+    ("H M L", "Top / middle / bottom of view"),
+    ("Ctrl+U Ctrl+D", "Half page up / down"),
+    ("v V Ctrl+V", "Visual / line / block selection"),
+    ("iw aw i\" i(", "Select word / quoted / bracketed text (visual)"),
+    ("y", "Yank selection"),
+    ("\"a y  \"A y", "Yank into / append to register a (a–z)"),
+    ("/ ? n N", "Search forward / back, next / previous"),
+    ("Ctrl+L", "Clear search highlighting"),
+    ("Esc", "Exit vi mode"),
+];
+
 /// Direction of the last search.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchDirection {
@@ -48,6 +71,18 @@ pub struct ViState {
     pub search_query: String,
     /// Direction of the current/last search.
     pub search_direction: SearchDirection,
+    /// Whether matches of `search_query` are highlighted: from `/` or `?`
+    /// until Ctrl+L or a cancelled search.
+    pub search_highlight: bool,
+    /// When true, j/k/0/$ operate on logical (unwrapped) lines; the g-prefixed
+    /// variants always operate on screen rows.
+    pub logical_lines: bool,
+    /// The last f/F/t/T, repeated by `;` and `,`.
+    pub last_find: Option<CharSearch>,
+    /// Register picked with `"` for the next yank (None = unnamed).
+    pub register: Option<Register>,
+    /// Mode and anchor of the selection `y` just ended, for `take_yank`.
+    pub yanked: Option<(ViMode, CursorPos)>,
 }
 
 /// Actions that the vi-mode handler can produce.
@@ -55,6 +90,8 @@ pub struct ViState {
 pub enum ViAction {
     /// Move cursor by a motion.
     Motion(Motion),
+    /// Select a text object (visual modes only).
+    SelectTextObject(TextObject),
     /// Enter a visual mode (sets anchor at current cursor).
     EnterVisual(ViMode),
     /// Return to Normal mode (clear selection).
@@ -99,6 +136,46 @@ pub enum Motion {
     ViewportBottom,
     HalfPageUp,
     HalfPageDown,
+    /// gk: up by screen rows, ignoring soft wraps.
+    DisplayLineUp(usize),
+    /// gj: down by screen rows, ignoring soft wraps.
+    DisplayLineDown(usize),
+    /// g0: first column of the screen row.
+    DisplayLineStart,
+    /// g$: last column of the screen row.
+    DisplayLineEnd,
+    /// f/F/t/T: the count-th occurrence of a character in the row.
+    FindChar(CharSearch, usize),
+    /// `;` and `,`: like FindChar, but t/T step past the character they
+    /// already stop before.
+    RepeatFind(CharSearch, usize),
+}
+
+/// A character search within the row: `f` is forward, `t` forward till.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharSearch {
+    pub target: char,
+    pub forward: bool,
+    /// Stop one column short of the character (t/T).
+    pub till: bool,
+}
+
+impl CharSearch {
+    /// The same search in the other direction, for `,`.
+    pub fn reversed(self) -> Self {
+        Self { forward: !self.forward, ..self }
+    }
+}
+
+/// A visual-mode text object: `i` selects inside, `a` around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextObject {
+    /// iw/aw: `count` words (whitespace runs count as words for iw).
+    Word { around: bool, count: usize },
+    /// i"/a" and the ' and ` variants, within the row.
+    Quote { quote: char, around: bool },
+    /// i(/a( and the [ { < variants: the count-th enclosing pair.
+    Bracket { open: char, close: char, around: bool, count: usize },
 }
 
 impl ViState {
@@ -113,6 +190,11 @@ impl ViState {
             search_input_active: false,
             search_query: String::new(),
             search_direction: SearchDirection::Forward,
+            search_highlight: false,
+            logical_lines: false,
+            last_find: None,
+            register: None,
+            yanked: None,
         }
     }
 
@@ -189,8 +271,15 @@ impl ViState {
             'H' => ViAction::Motion(Motion::ViewportTop),
             'M' => ViAction::Motion(Motion::ViewportMiddle),
             'L' => ViAction::Motion(Motion::ViewportBottom),
-            'g' => {
-                self.pending_key = Some('g');
+            'g' | 'f' | 'F' | 't' | 'T' => {
+                // Keep the count for the second key (e.g. 3gj, 2fx)
+                self.count = Some(count);
+                self.pending_key = Some(ch);
+                ViAction::None
+            }
+            ';' | ',' => self.repeat_find(ch == ',', count),
+            '"' => {
+                self.pending_key = Some(ch);
                 ViAction::None
             }
             'v' => {
@@ -207,16 +296,24 @@ impl ViState {
                 self.search_input_active = true;
                 self.search_query.clear();
                 self.search_direction = SearchDirection::Forward;
+                self.search_highlight = true;
                 ViAction::SearchForward
             }
             '?' => {
                 self.search_input_active = true;
                 self.search_query.clear();
                 self.search_direction = SearchDirection::Backward;
+                self.search_highlight = true;
                 ViAction::SearchBackward
             }
-            'n' => ViAction::NextMatch,
-            'N' => ViAction::PrevMatch,
+            'n' => {
+                self.search_highlight = true;
+                ViAction::NextMatch
+            }
+            'N' => {
+                self.search_highlight = true;
+                ViAction::PrevMatch
+            }
             '\x1b' => ViAction::ExitViMode, // Escape
             _ => ViAction::None,
         }
@@ -240,13 +337,21 @@ impl ViState {
             'H' => ViAction::Motion(Motion::ViewportTop),
             'M' => ViAction::Motion(Motion::ViewportMiddle),
             'L' => ViAction::Motion(Motion::ViewportBottom),
-            'g' => {
-                self.pending_key = Some('g');
+            'g' | 'f' | 'F' | 't' | 'T' | 'i' | 'a' => {
+                // Keep the count for the second key (e.g. 3gj, 2fx, 2i()
+                self.count = Some(count);
+                self.pending_key = Some(ch);
                 ViAction::None
             }
-            // Yank
+            ';' | ',' => self.repeat_find(ch == ',', count),
+            '"' => {
+                self.pending_key = Some(ch);
+                ViAction::None
+            }
+            // Yank (to `self.register`, which the caller takes)
             'y' => {
                 let action = ViAction::Yank;
+                self.yanked = self.anchor.map(|anchor| (self.mode, anchor));
                 self.mode = ViMode::Normal;
                 self.anchor = None;
                 action
@@ -279,20 +384,29 @@ impl ViState {
                 self.search_input_active = true;
                 self.search_query.clear();
                 self.search_direction = SearchDirection::Forward;
+                self.search_highlight = true;
                 ViAction::SearchForward
             }
             '?' => {
                 self.search_input_active = true;
                 self.search_query.clear();
                 self.search_direction = SearchDirection::Backward;
+                self.search_highlight = true;
                 ViAction::SearchBackward
             }
-            'n' => ViAction::NextMatch,
-            'N' => ViAction::PrevMatch,
+            'n' => {
+                self.search_highlight = true;
+                ViAction::NextMatch
+            }
+            'N' => {
+                self.search_highlight = true;
+                ViAction::PrevMatch
+            }
             // Escape → back to Normal
             '\x1b' => {
                 self.mode = ViMode::Normal;
                 self.anchor = None;
+                self.register = None;
                 ViAction::ExitVisual
             }
             _ => ViAction::None,
@@ -306,6 +420,11 @@ impl ViState {
         match ch {
             'u' | 'U' => ViAction::Motion(Motion::HalfPageUp),
             'd' | 'D' => ViAction::Motion(Motion::HalfPageDown),
+            // Like :nohlsearch
+            'l' | 'L' => {
+                self.search_highlight = false;
+                ViAction::None
+            }
             'v' | 'V' => {
                 // Ctrl+V → Visual-Block toggle
                 if self.mode == ViMode::VisualBlock {
@@ -327,12 +446,58 @@ impl ViState {
 
     /// Process the second key of a multi-key sequence.
     fn process_pending(&mut self, first: char, second: char) -> ViAction {
+        let count = self.effective_count();
         match (first, second) {
             ('g', 'g') => {
                 let action = ViAction::Motion(Motion::BufferTop);
                 self.count = None;
                 action
             }
+            ('g', 'j') => {
+                self.count = None;
+                ViAction::Motion(Motion::DisplayLineDown(count))
+            }
+            ('g', 'k') => {
+                self.count = None;
+                ViAction::Motion(Motion::DisplayLineUp(count))
+            }
+            ('g', '0') => {
+                self.count = None;
+                ViAction::Motion(Motion::DisplayLineStart)
+            }
+            ('g', '$') => {
+                self.count = None;
+                ViAction::Motion(Motion::DisplayLineEnd)
+            }
+            ('f' | 'F' | 't' | 'T', target) if !target.is_control() => {
+                self.count = None;
+                let search = CharSearch {
+                    target,
+                    forward: first.is_ascii_lowercase(),
+                    till: first.eq_ignore_ascii_case(&'t'),
+                };
+                self.last_find = Some(search);
+                ViAction::Motion(Motion::FindChar(search, count))
+            }
+            ('"', key) => {
+                self.count = None;
+                self.register = Register::from_key(key);
+                ViAction::None
+            }
+            ('i' | 'a', object) => {
+                self.count = None;
+                let around = first == 'a';
+                let object = match object {
+                    'w' => TextObject::Word { around, count },
+                    '"' | '\'' | '`' => TextObject::Quote { quote: object, around },
+                    '(' | ')' | 'b' => TextObject::Bracket { open: '(', close: ')', around, count },
+                    '[' | ']' => TextObject::Bracket { open: '[', close: ']', around, count },
+                    '{' | '}' | 'B' => TextObject::Bracket { open: '{', close: '}', around, count },
+                    '<' | '>' => TextObject::Bracket { open: '<', close: '>', around, count },
+                    _ => return ViAction::None,
+                };
+                ViAction::SelectTextObject(object)
+            }
             _ => {
                 self.count = None;
                 ViAction::None
@@ -340,6 +505,15 @@ impl ViState {
         }
     }
 
+    /// `;` (or `,` when `reverse`): repeat the last f/F/t/T.
+    fn repeat_find(&self, reverse: bool, count: usize) -> ViAction {
+        match self.last_find {
+            Some(search) if reverse => ViAction::Motion(Motion::RepeatFind(search.reversed(), count)),
+            Some(search) => ViAction::Motion(Motion::RepeatFind(search, count)),
+            None => ViAction::None,
+        }
+    }
+
     /// Get the mode indicator text for the status bar.
     pub fn mode_text(&self) -> &'static str {
         match self.mode {
@@ -380,6 +554,28 @@ impl ViState {
         self.cursor.col = col;
     }
 
+    /// Index of the match drawn as current among `matches` (in row order):
+    /// while the query is typed, the one the search would jump to from the
+    /// cursor; afterwards, the one under the cursor.
+    pub fn highlighted_match(&self, matches: &[SearchMatch]) -> Option<usize> {
+        let (row, col) = (self.cursor.row as i32, self.cursor.col);
+        if !self.search_input_active {
+            return matches
+                .iter()
+                .position(|m| m.row == row && m.start_col <= col && col < m.end_col.max(m.start_col + 1));
+        }
+        match self.search_direction {
+            SearchDirection::Forward => matches
+                .iter()
+                .position(|m| m.row > row || (m.row == row && m.start_col >= col))
+                .or((!matches.is_empty()).then_some(0)),
+            SearchDirection::Backward => matches
+                .iter()
+                .rposition(|m| m.row < row || (m.row == row && m.start_col <= col))
+                .or(matches.len().checked_sub(1)),
+        }
+    }
+
     /// Process a key during search input mode.
     fn process_search_input(&mut self, ch: char, ctrl: bool) -> ViAction {
         if ctrl {
@@ -390,6 +586,7 @@ impl ViState {
                 // Escape: cancel search
                 self.search_input_active = false;
                 self.search_query.clear();
+                self.search_highlight = false;
                 ViAction::SearchCancel
             }
             '\r' | '\n' => {
@@ -413,27 +610,67 @@ impl ViState {
     /// Apply a motion to the cursor position, clamping to buffer bounds.
     /// `line_len` is a callback that returns the length (number of columns) for a given row.
     pub fn apply_motion(&mut self, motion: &Motion, ctx: &BufferContext) {
+        self.apply_motion_cells(motion, ctx);
+        // The cursor never rests on the right half of a wide character
+        if self.cursor.col > 0 && ctx.is_wide_spacer(self.cursor.row, self.cursor.col) {
+            self.cursor.col -= 1;
+        }
+    }
+
+    fn apply_motion_cells(&mut self, motion: &Motion, ctx: &BufferContext) {
         match *motion {
             Motion::CharLeft(n) => {
-                self.cursor.col = self.cursor.col.saturating_sub(n);
+                // Count characters, not cells: a wide character is one step
+                for _ in 0..n {
+                    if self.cursor.col == 0 {
+                        break;
+                    }
+                    self.cursor.col -= 1;
+                    if self.cursor.col > 0 && ctx.is_wide_spacer(self.cursor.row, self.cursor.col) {
+                        self.cursor.col -= 1;
+                    }
+                }
             }
             Motion::CharRight(n) => {
                 let max_col = ctx.line_len(self.cursor.row).saturating_sub(1);
-                self.cursor.col = (self.cursor.col + n).min(max_col);
+                for _ in 0..n {
+                    let mut next = self.cursor.col + 1;
+                    if ctx.is_wide_spacer(self.cursor.row, next) {
+                        next += 1;
+                    }
+                    if next > max_col {
+                        break;
+                    }
+                    self.cursor.col = next;
+                }
+            }
+            Motion::LineUp(n) if self.logical_lines => {
+                self.move_logical_lines(ctx, -(n as isize));
+            }
+            Motion::LineDown(n) if self.logical_lines => {
+                self.move_logical_lines(ctx, n as isize);
+            }
+            Motion::LineStart if self.logical_lines => {
+                self.cursor.row = logical_line_start(ctx, self.cursor.row);
+                self.cursor.col = 0;
             }
-            Motion::LineUp(n) => {
+            Motion::LineEnd if self.logical_lines => {
+                self.cursor.row = logical_line_end(ctx, self.cursor.row);
+                self.cursor.col = ctx.line_len(self.cursor.row).saturating_sub(1);
+            }
+            Motion::LineUp(n) | Motion::DisplayLineUp(n) => {
                 self.cursor.row = self.cursor.row.saturating_sub(n);
                 self.clamp_col(ctx);
             }
-            Motion::LineDown(n) => {
+            Motion::LineDown(n) | Motion::DisplayLineDown(n) => {
                 let max_row = ctx.total_rows.saturating_sub(1);
                 self.cursor.row = (self.cursor.row + n).min(max_row);
                 self.clamp_col(ctx);
             }
-            Motion::LineStart => {
+            Motion::LineStart | Motion::DisplayLineStart => {
                 self.cursor.col = 0;
             }
-            Motion::LineEnd => {
+            Motion::LineEnd | Motion::DisplayLineEnd => {
                 let max_col = ctx.line_len(self.cursor.row).saturating_sub(1);
                 self.cursor.col = max_col;
             }
@@ -498,7 +735,93 @@ impl ViState {
                     self.move_word_end(ctx);
                 }
             }
+            Motion::FindChar(search, n) => self.find_char(ctx, search, n, false),
+            Motion::RepeatFind(search, n) => self.find_char(ctx, search, n, true),
+        }
+    }
+
+    /// Move to the `count`-th `search.target` in the row, or one column
+    /// short of it for t/T. Stays put when there are fewer.
+    fn find_char(&mut self, ctx: &BufferContext, search: CharSearch, count: usize, repeat: bool) {
+        let row = self.cursor.row;
+        let col = self.cursor.col;
+        // A repeated t/T would find the character it already stops before
+        let skip = usize::from(repeat && search.till);
+        let is_target = |c: usize| !ctx.is_wide_spacer(row, c) && ctx.char_at(row, c) == Some(search.target);
+        let found = if search.forward {
+            (col + 1 + skip..ctx.line_len(row)).filter(|&c| is_target(c)).nth(count.max(1) - 1)
+        } else {
+            (0..col.saturating_sub(skip)).rev().filter(|&c| is_target(c)).nth(count.max(1) - 1)
+        };
+        let Some(found) = found else {
+            return;
+        };
+        self.cursor.col = match (search.till, search.forward) {
+            (false, _) => found,
+            (true, true) => found - 1,
+            // Past the character, and past its right half when it's wide
+            (true, false) if ctx.is_wide_spacer(row, found + 1) => found + 2,
+            (true, false) => found + 1,
+        };
+    }
+
+    /// Select `object` around the cursor: the anchor goes to its start and
+    /// the cursor to its end, in character-wise visual mode. Leaves the
+    /// selection alone when the cursor isn't in such an object.
+    pub fn select_text_object(&mut self, object: &TextObject, ctx: &BufferContext) {
+        let range = match *object {
+            TextObject::Word { around, count } => word_object(ctx, self.cursor, around, count),
+            TextObject::Quote { quote, around } => quote_object(ctx, self.cursor, quote, around),
+            TextObject::Bracket { open, close, around, count } => {
+                bracket_object(ctx, self.cursor, open, close, around, count)
+            }
+        };
+        let Some((start, mut end)) = range else {
+            return;
+        };
+        if end.col > 0 && ctx.is_wide_spacer(end.row, end.col) {
+            end.col -= 1;
+        }
+        self.mode = ViMode::Visual;
+        self.anchor = Some(start);
+        self.cursor = end;
+    }
+
+    /// Move `delta` logical lines (negative = up), keeping the cursor's offset
+    /// within the logical line like Vim's j/k over wrapped text.
+    fn move_logical_lines(&mut self, ctx: &BufferContext, delta: isize) {
+        let cols = ctx.cols.max(1);
+        let start = logical_line_start(ctx, self.cursor.row);
+        let offset = (self.cursor.row - start) * cols + self.cursor.col;
+
+        let mut target = start;
+        if delta >= 0 {
+            for _ in 0..delta {
+                let end = logical_line_end(ctx, target);
+                if end + 1 >= ctx.total_rows {
+                    break;
+                }
+                target = end + 1;
+            }
+        } else {
+            for _ in 0..delta.unsigned_abs() {
+                if target == 0 {
+                    break;
+                }
+                target = logical_line_start(ctx, target - 1);
+            }
+        }
+
+        let end = logical_line_end(ctx, target);
+        let row = target + offset / cols;
+        if row > end {
+            self.cursor.row = end;
+            self.cursor.col = cols - 1;
+        } else {
+            self.cursor.row = row;
+            self.cursor.col = offset % cols;
         }
+        self.clamp_col(ctx);
     }
 
     /// Clamp column to the current line's length.
@@ -648,6 +971,7 @@ use crate::input::selection::{
     Selection, SelectionType, Side, selected_text, selected_text_block, selected_text_lines,
 };
 use crate::renderer::grid_renderer::{GridCell, CELL_FLAG_VI_CURSOR};
+use crate::search::SearchMatch;
 
 impl ViState {
     /// Convert the current vi-mode visual selection to a Selection for rendering.
@@ -695,6 +1019,14 @@ impl ViState {
         Some(text)
     }
 
+    /// Text of the selection `y` just ended, and the register it goes to.
+    pub fn take_yank(&mut self, cells: &[GridCell], cols: usize, display_offset: usize) -> Option<(Register, String)> {
+        let (mode, anchor) = self.yanked.take()?;
+        let register = self.register.take().unwrap_or(Register::Unnamed);
+        let selection = ViState { mode, anchor: Some(anchor), ..self.clone() };
+        Some((register, selection.yank_text(cells, cols, display_offset)?))
+    }
+
     /// Set the CELL_FLAG_VI_CURSOR on the cell at the current cursor position.
     /// The cursor position is relative to the viewport: `viewport_top` is subtracted from cursor.row.
     pub fn apply_vi_cursor_flag(&self, cells: &mut [GridCell], cols: usize, viewport_top: usize) {
@@ -734,6 +1066,11 @@ pub struct BufferContext<'a> {
     /// Callback to get a character at a given (row, col).
     /// Returns None if out of bounds.
     pub char_at_fn: &'a dyn Fn(usize, usize) -> Option<char>,
+    /// Callback returning true if a row soft-wraps into the next one.
+    pub wrapped_fn: &'a dyn Fn(usize) -> bool,
+    /// Callback returning true if (row, col) is the right half of a
+    /// double-width character.
+    pub wide_spacer_fn: &'a dyn Fn(usize, usize) -> bool,
 }
 
 impl<'a> BufferContext<'a> {
@@ -742,10 +1079,179 @@ impl<'a> BufferContext<'a> {
         self.cols
     }
 
-    /// Get the character at (row, col).
+    /// Get the character at (row, col). The right half of a double-width
+    /// character reads as the character itself, so word motions see it whole.
     pub fn char_at(&self, row: usize, col: usize) -> Option<char> {
+        if col > 0 && self.is_wide_spacer(row, col) {
+            return (self.char_at_fn)(row, col - 1);
+        }
         (self.char_at_fn)(row, col)
     }
+
+    /// Whether (row, col) is the right half of a double-width character.
+    pub fn is_wide_spacer(&self, row: usize, col: usize) -> bool {
+        (self.wide_spacer_fn)(row, col)
+    }
+
+    /// Whether `row` soft-wraps into the next row.
+    pub fn is_wrapped(&self, row: usize) -> bool {
+        (self.wrapped_fn)(row)
+    }
+}
+
+/// Word class for text objects: blank, punctuation or word character.
+fn char_class(ch: Option<char>) -> u8 {
+    match ch {
+        None | Some(' ' | '\t' | '\0') => 0,
+        Some(ch) if is_word_char(ch) => 2,
+        Some(_) => 1,
+    }
+}
+
+/// Columns of the run of same-class characters in `row` around `col`.
+fn class_run(ctx: &BufferContext, row: usize, col: usize) -> (usize, usize) {
+    let class = char_class(ctx.char_at(row, col));
+    let (mut start, mut end) = (col, col);
+    while start > 0 && char_class(ctx.char_at(row, start - 1)) == class {
+        start -= 1;
+    }
+    while end + 1 < ctx.line_len(row) && char_class(ctx.char_at(row, end + 1)) == class {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// iw/aw within the cursor's row. iw counts blank runs as words; aw takes
+/// each word with the blanks after it, or before it at the end of the row.
+fn word_object(ctx: &BufferContext, cursor: CursorPos, around: bool, count: usize) -> Option<(CursorPos, CursorPos)> {
+    let row = cursor.row;
+    let len = ctx.line_len(row);
+    if cursor.col >= len {
+        return None;
+    }
+    let (mut start, mut end) = class_run(ctx, row, cursor.col);
+    let started_blank = char_class(ctx.char_at(row, start)) == 0;
+    for i in 0..count.max(1) {
+        if i > 0 {
+            if end + 1 >= len {
+                break;
+            }
+            end = class_run(ctx, row, end + 1).1;
+        }
+        // A word takes the blanks after it, blanks the word after them
+        let blank = char_class(ctx.char_at(row, end)) == 0;
+        if around && end + 1 < len && blank != (char_class(ctx.char_at(row, end + 1)) == 0) {
+            end = class_run(ctx, row, end + 1).1;
+        }
+    }
+    // No blanks after the last word: take the ones before the first
+    let ends_blank = char_class(ctx.char_at(row, end)) == 0;
+    if around && !started_blank && !ends_blank && start > 0 && char_class(ctx.char_at(row, start - 1)) == 0 {
+        start = class_run(ctx, row, start - 1).0;
+    }
+    Some((CursorPos { row, col: start }, CursorPos { row, col: end }))
+}
+
+/// i"/a" within the cursor's row. Quotes pair up from the start of the
+/// row; with the cursor before every pair, the next pair is used. a" adds
+/// the blanks after the closing quote.
+fn quote_object(ctx: &BufferContext, cursor: CursorPos, quote: char, around: bool) -> Option<(CursorPos, CursorPos)> {
+    let row = cursor.row;
+    let len = ctx.line_len(row);
+    let quotes: Vec<usize> = (0..len).filter(|&c| ctx.char_at(row, c) == Some(quote)).collect();
+    let (open, close) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, close)| cursor.col <= close)?;
+    let (start, end) = if around {
+        let mut end = close;
+        while end + 1 < len && char_class(ctx.char_at(row, end + 1)) == 0 {
+            end += 1;
+        }
+        (open, end)
+    } else if close > open + 1 {
+        (open + 1, close - 1)
+    } else {
+        return None;
+    };
+    Some((CursorPos { row, col: start }, CursorPos { row, col: end }))
+}
+
+/// i(/a( and friends: the `count`-th pair enclosing the cursor, searched
+/// across rows. A bracket under the cursor counts as enclosing it.
+fn bracket_object(
+    ctx: &BufferContext,
+    cursor: CursorPos,
+    open: char,
+    close: char,
+    around: bool,
+    count: usize,
+) -> Option<(CursorPos, CursorPos)> {
+    let cols = ctx.cols.max(1);
+    let total = ctx.total_rows * cols;
+    let at = |i: usize| ctx.char_at(i / cols, i % cols);
+    let here = cursor.row * cols + cursor.col;
+
+    // Back to the count-th unmatched opening bracket
+    let mut depth = 0usize;
+    let mut remaining = count.max(1);
+    let mut i = if at(here) == Some(close) { here.checked_sub(1)? } else { here };
+    let start = loop {
+        match at(i) {
+            Some(c) if c == close => depth += 1,
+            Some(c) if c == open && depth > 0 => depth -= 1,
+            Some(c) if c == open => {
+                remaining -= 1;
+                if remaining == 0 {
+                    break i;
+                }
+            }
+            _ => {}
+        }
+        i = i.checked_sub(1)?;
+    };
+
+    // Forward to its match
+    let mut depth = 0usize;
+    let end = (start + 1..total).find(|&i| match at(i) {
+        Some(c) if c == open => {
+            depth += 1;
+            false
+        }
+        Some(c) if c == close && depth > 0 => {
+            depth -= 1;
+            false
+        }
+        Some(c) => c == close,
+        None => false,
+    })?;
+
+    let (start, end) = if around {
+        (start, end)
+    } else if end > start + 1 {
+        (start + 1, end - 1)
+    } else {
+        return None;
+    };
+    let pos = |i: usize| CursorPos { row: i / cols, col: i % cols };
+    Some((pos(start), pos(end)))
+}
+
+/// First screen row of the logical line containing `row`.
+fn logical_line_start(ctx: &BufferContext, mut row: usize) -> usize {
+    while row > 0 && ctx.is_wrapped(row - 1) {
+        row -= 1;
+    }
+    row
+}
+
+/// Last screen row of the logical line containing `row`.
+fn logical_line_end(ctx: &BufferContext, mut row: usize) -> usize {
+    let max_row = ctx.total_rows.saturating_sub(1);
+    while row < max_row && ctx.is_wrapped(row) {
+        row += 1;
+    }
+    row
 }
 
 #[cfg(test)]
@@ -1259,6 +1765,8 @@ mod tests {
                 char_at_fn: &|row, col| {
                     grid_ref.get(row).and_then(|r| r.get(col).copied())
                 },
+                wrapped_fn: &|_| false,
+                wide_spacer_fn: &|_, _| false,
             }
         }};
     }
@@ -1551,6 +2059,125 @@ mod tests {
         assert_eq!(state.cursor.col, 0);
     }
 
+    // ── Display-line vs logical-line motions ────────────────────────
+
+    /// Context over `lines` where `wrapped[i]` marks row i as soft-wrapped.
+    macro_rules! wrapped_ctx {
+        ($grid:expr, $cols:expr, $wrapped:expr) => {{
+            let grid_ref = &$grid;
+            let wrapped_ref: &[bool] = $wrapped;
+            BufferContext {
+                total_rows: grid_ref.len(),
+                cols: $cols,
+                viewport_top: 0,
+                viewport_rows: grid_ref.len(),
+                char_at_fn: &|row, col| {
+                    grid_ref.get(row).and_then(|r| r.get(col).copied())
+                },
+                wrapped_fn: &|row| wrapped_ref.get(row).copied().unwrap_or(false),
+                wide_spacer_fn: &|_, _| false,
+            }
+        }};
+    }
+
+    #[test]
+    fn g_prefixed_display_motions_parse() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('g', false);
+        assert_eq!(state.process_key('j', false), ViAction::Motion(Motion::DisplayLineDown(1)));
+        state.process_key('g', false);
+        assert_eq!(state.process_key('k', false), ViAction::Motion(Motion::DisplayLineUp(1)));
+        state.process_key('g', false);
+        assert_eq!(state.process_key('0', false), ViAction::Motion(Motion::DisplayLineStart));
+        state.process_key('g', false);
+        assert_eq!(state.process_key('$', false), ViAction::Motion(Motion::DisplayLineEnd));
+    }
+
+    #[test]
+    fn count_applies_to_gj() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('3', false);
+        state.process_key('g', false);
+        assert_eq!(state.process_key('j', false), ViAction::Motion(Motion::DisplayLineDown(3)));
+        assert_eq!(state.count, None);
+    }
+
+    #[test]
+    fn gj_in_visual_mode() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('v', false);
+        state.process_key('g', false);
+        assert_eq!(state.process_key('j', false), ViAction::Motion(Motion::DisplayLineDown(1)));
+    }
+
+    #[test]
+    fn j_moves_by_screen_row_by_default() {
+        let (grid, cols) = make_ctx(&["abcde", "fgh", "next"], 0, 3);
+        let ctx = wrapped_ctx!(grid, cols, &[true, false, false]);
+        let mut state = ViState::new(0, 1);
+        state.apply_motion(&Motion::LineDown(1), &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 1, col: 1 });
+    }
+
+    #[test]
+    fn logical_j_skips_wrapped_continuation() {
+        let (grid, cols) = make_ctx(&["abcde", "fgh", "next"], 0, 3);
+        let ctx = wrapped_ctx!(grid, cols, &[true, false, false]);
+        let mut state = ViState::new(0, 1);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::LineDown(1), &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 2, col: 1 });
+    }
+
+    #[test]
+    fn logical_k_lands_on_matching_offset() {
+        let (grid, cols) = make_ctx(&["x", "abcde", "fgh", "next"], 0, 4);
+        let ctx = wrapped_ctx!(grid, cols, &[false, true, false, false]);
+        let mut state = ViState::new(3, 2);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::LineUp(1), &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 1, col: 2 });
+        // From the continuation row, k returns to the line above the wrapped line
+        let mut state = ViState::new(2, 0);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::LineUp(1), &ctx);
+        assert_eq!(state.cursor.row, 0);
+    }
+
+    #[test]
+    fn logical_offset_clamps_to_shorter_line() {
+        let (grid, cols) = make_ctx(&["x", "abcde", "fgh"], 0, 3);
+        let ctx = wrapped_ctx!(grid, cols, &[false, true, false]);
+        let mut state = ViState::new(2, 2);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::LineUp(1), &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 0, col: cols - 1 });
+    }
+
+    #[test]
+    fn gj_ignores_logical_mode() {
+        let (grid, cols) = make_ctx(&["abcde", "fgh", "next"], 0, 3);
+        let ctx = wrapped_ctx!(grid, cols, &[true, false, false]);
+        let mut state = ViState::new(0, 1);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::DisplayLineDown(1), &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 1, col: 1 });
+    }
+
+    #[test]
+    fn logical_line_start_and_end_span_wrapped_rows() {
+        let (grid, cols) = make_ctx(&["abcde", "fghij", "kl"], 0, 3);
+        let ctx = wrapped_ctx!(grid, cols, &[true, true, false]);
+        let mut state = ViState::new(1, 2);
+        state.logical_lines = true;
+        state.apply_motion(&Motion::LineStart, &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 0, col: 0 });
+        state.apply_motion(&Motion::LineEnd, &ctx);
+        assert_eq!(state.cursor.row, 2);
+        state.apply_motion(&Motion::DisplayLineStart, &ctx);
+        assert_eq!(state.cursor, CursorPos { row: 2, col: 0 });
+    }
+
     // ── to_selection conversion ─────────────────────────────────────
 
     #[test]
@@ -1651,6 +2278,48 @@ mod tests {
         assert_eq!(text, "hello\nabcde");
     }
 
+    #[test]
+    fn take_yank_copies_the_selection_y_ended() {
+        let cells = make_cells(&["hello world"], 20);
+        let mut state = ViState::new(0, 6);
+        state.process_key('v', false);
+        state.cursor = CursorPos { row: 0, col: 10 };
+        assert_eq!(state.process_key('y', false), ViAction::Yank);
+        assert_eq!(state.take_yank(&cells, 20, 0), Some((Register::Unnamed, "world".to_string())));
+        assert_eq!(state.take_yank(&cells, 20, 0), None);
+    }
+
+    #[test]
+    fn register_prefix_names_the_yank_register() {
+        let cells = make_cells(&["hello world"], 20);
+        let mut state = ViState::new(0, 0);
+        assert_eq!(state.process_key('"', false), ViAction::None);
+        assert_eq!(state.process_key('a', false), ViAction::None);
+        state.process_key('v', false);
+        state.process_key('e', false);
+        state.cursor = CursorPos { row: 0, col: 4 };
+        state.process_key('"', false);
+        state.process_key('B', false);
+        state.process_key('y', false);
+        assert_eq!(state.take_yank(&cells, 20, 0), Some((Register::Append('b'), "hello".to_string())));
+        assert_eq!(state.register, None);
+    }
+
+    #[test]
+    fn escape_forgets_the_register() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('v', false);
+        state.process_key('"', false);
+        state.process_key('c', false);
+        assert_eq!(state.register, Some(Register::Named('c')));
+        state.process_key('\x1b', false);
+        assert_eq!(state.register, None);
+        state.process_key('"', false);
+        state.process_key('!', false);
+        assert_eq!(state.register, None);
+        assert_eq!(state.pending_key, None);
+    }
+
     #[test]
     fn yank_text_returns_none_in_normal() {
         let cells = make_cells(&["hello"], 20);
@@ -1658,6 +2327,56 @@ mod tests {
         assert_eq!(state.yank_text(&cells, 20, 0), None);
     }
 
+    // ── Search highlighting ─────────────────────────────────────────
+
+    fn search_match(row: i32, start_col: usize, end_col: usize) -> SearchMatch {
+        SearchMatch { row, start_col, end_row: row, end_col }
+    }
+
+    #[test]
+    fn search_highlight_follows_search_keys() {
+        let mut state = ViState::new(0, 0);
+        assert!(!state.search_highlight);
+        state.process_key('/', false);
+        assert!(state.search_highlight);
+        state.process_key('x', false);
+        state.process_key('\r', false);
+        assert!(state.search_highlight);
+        state.process_key('l', true); // Ctrl+L
+        assert!(!state.search_highlight);
+        assert_eq!(state.search_query, "x");
+        state.process_key('N', false);
+        assert!(state.search_highlight);
+        state.process_key('?', false);
+        state.process_key('\x1b', false);
+        assert!(!state.search_highlight);
+    }
+
+    #[test]
+    fn highlighted_match_while_typing_is_the_next_one() {
+        let matches = [search_match(1, 0, 3), search_match(4, 2, 5), search_match(4, 8, 11)];
+        let mut state = ViState::new(4, 3);
+        state.process_key('/', false);
+        assert_eq!(state.highlighted_match(&matches), Some(2));
+        state.cursor = CursorPos { row: 9, col: 0 };
+        assert_eq!(state.highlighted_match(&matches), Some(0)); // wraps
+        state.process_key('\x1b', false);
+        state.process_key('?', false);
+        state.cursor = CursorPos { row: 4, col: 3 };
+        assert_eq!(state.highlighted_match(&matches), Some(1));
+        state.cursor = CursorPos { row: 0, col: 0 };
+        assert_eq!(state.highlighted_match(&matches), Some(2)); // wraps
+    }
+
+    #[test]
+    fn highlighted_match_after_search_is_under_the_cursor() {
+        let matches = [search_match(1, 0, 3), search_match(4, 2, 5)];
+        let mut state = ViState::new(4, 4);
+        assert_eq!(state.highlighted_match(&matches), Some(1));
+        state.cursor.col = 5;
+        assert_eq!(state.highlighted_match(&matches), None);
+    }
+
     // ── Search input mode ─────────────────────────────────────────────
 
     #[test]
@@ -1920,4 +2639,257 @@ mod tests {
         state.process_key('\r', false);
         assert_eq!(state.status_text(), "-- NORMAL --");
     }
+
+
+    // ── Wide characters ─────────────────────────────────────────────
+
+    /// Cells and spacer map for `text`, expanding double-width characters
+    /// (here: any non-ASCII char) into a character cell plus a spacer.
+    fn wide_row(text: &str) -> (Vec<char>, Vec<bool>) {
+        let mut cells = Vec::new();
+        let mut spacers = Vec::new();
+        for ch in text.chars() {
+            cells.push(ch);
+            spacers.push(false);
+            if !ch.is_ascii() {
+                cells.push(' ');
+                spacers.push(true);
+            }
+        }
+        (cells, spacers)
+    }
+
+    macro_rules! wide_ctx {
+        ($cells:expr, $spacers:expr) => {{
+            let cells_ref: &[char] = &$cells;
+            let spacers_ref: &[bool] = &$spacers;
+            BufferContext {
+                total_rows: 1,
+                cols: cells_ref.len(),
+                viewport_top: 0,
+                viewport_rows: 1,
+                char_at_fn: &|row, col| if row == 0 { cells_ref.get(col).copied() } else { None },
+                wrapped_fn: &|_| false,
+                wide_spacer_fn: &|row, col| row == 0 && spacers_ref.get(col).copied().unwrap_or(false),
+            }
+        }};
+    }
+
+    #[test]
+    fn char_motions_step_over_wide_chars() {
+        let (cells, spacers) = wide_row("a日本b");
+        let ctx = wide_ctx!(cells, spacers);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::CharRight(1), &ctx);
+        assert_eq!(state.cursor.col, 1);
+        state.apply_motion(&Motion::CharRight(1), &ctx);
+        assert_eq!(state.cursor.col, 3);
+        state.apply_motion(&Motion::CharRight(2), &ctx);
+        assert_eq!(state.cursor.col, 5);
+        state.apply_motion(&Motion::CharLeft(2), &ctx);
+        assert_eq!(state.cursor.col, 1);
+    }
+
+    #[test]
+    fn motions_never_land_on_spacer() {
+        let (cells, spacers) = wide_row("ab日本");
+        let ctx = wide_ctx!(cells, spacers);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::LineEnd, &ctx);
+        assert_eq!(state.cursor.col, 4);
+        state.apply_motion(&Motion::WordBackward(1), &ctx);
+        assert_eq!(state.cursor.col, 0);
+    }
+
+    #[test]
+    fn word_motions_treat_wide_chars_whole() {
+        let (cells, spacers) = wide_row("日本 go");
+        let ctx = wide_ctx!(cells, spacers);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::WordEnd(1), &ctx);
+        assert_eq!(state.cursor.col, 2);
+        state.apply_motion(&Motion::WordForward(1), &ctx);
+        assert_eq!(state.cursor.col, 5);
+    }
+
+    // ── f/t motions ─────────────────────────────────────────────────
+
+    fn find(target: char, forward: bool, till: bool) -> CharSearch {
+        CharSearch { target, forward, till }
+    }
+
+    #[test]
+    fn f_and_t_take_a_target_and_count() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('f', false);
+        assert_eq!(state.process_key('x', false), ViAction::Motion(Motion::FindChar(find('x', true, false), 1)));
+        state.process_key('2', false);
+        state.process_key('T', false);
+        assert_eq!(state.process_key('a', false), ViAction::Motion(Motion::FindChar(find('a', false, true), 2)));
+        assert_eq!(state.count, None);
+        assert_eq!(state.last_find, Some(find('a', false, true)));
+    }
+
+    #[test]
+    fn semicolon_and_comma_repeat_last_find() {
+        let mut state = ViState::new(0, 0);
+        assert_eq!(state.process_key(';', false), ViAction::None);
+        state.process_key('t', false);
+        state.process_key('.', false);
+        assert_eq!(state.process_key(';', false), ViAction::Motion(Motion::RepeatFind(find('.', true, true), 1)));
+        state.process_key('3', false);
+        assert_eq!(state.process_key(',', false), ViAction::Motion(Motion::RepeatFind(find('.', false, true), 3)));
+    }
+
+    #[test]
+    fn escape_cancels_pending_find() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('f', false);
+        assert_eq!(state.process_key('\x1b', false), ViAction::None);
+        assert_eq!(state.last_find, None);
+        assert_eq!(state.pending_key, None);
+    }
+
+    #[test]
+    fn find_works_in_visual_mode() {
+        let mut state = ViState::new(0, 0);
+        state.process_key('v', false);
+        state.process_key('F', false);
+        assert_eq!(state.process_key('(', false), ViAction::Motion(Motion::FindChar(find('(', false, false), 1)));
+        assert_eq!(state.mode, ViMode::Visual);
+    }
+
+    #[test]
+    fn apply_find_char_forward_and_back() {
+        let lines = &["a.b.c.d"];
+        let (grid, cols) = make_ctx(lines, 0, 1);
+        let ctx = ctx_from!(&grid, cols, 0, 1);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::FindChar(find('.', true, false), 1), &ctx);
+        assert_eq!(state.cursor.col, 1);
+        state.apply_motion(&Motion::FindChar(find('.', true, false), 2), &ctx);
+        assert_eq!(state.cursor.col, 5);
+        state.apply_motion(&Motion::FindChar(find('a', false, false), 1), &ctx);
+        assert_eq!(state.cursor.col, 0);
+    }
+
+    #[test]
+    fn apply_find_char_missing_or_short_count_stays() {
+        let lines = &["a.b.c.d"];
+        let (grid, cols) = make_ctx(lines, 0, 1);
+        let ctx = ctx_from!(&grid, cols, 0, 1);
+        let mut state = ViState::new(0, 2);
+        state.apply_motion(&Motion::FindChar(find('z', true, false), 1), &ctx);
+        assert_eq!(state.cursor.col, 2);
+        state.apply_motion(&Motion::FindChar(find('.', true, false), 3), &ctx);
+        assert_eq!(state.cursor.col, 2);
+    }
+
+    #[test]
+    fn apply_till_stops_short_and_repeat_steps_past() {
+        let lines = &["a.b.c.d"];
+        let (grid, cols) = make_ctx(lines, 0, 1);
+        let ctx = ctx_from!(&grid, cols, 0, 1);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::FindChar(find('.', true, true), 1), &ctx);
+        assert_eq!(state.cursor.col, 0);
+        state.apply_motion(&Motion::RepeatFind(find('.', true, true), 1), &ctx);
+        assert_eq!(state.cursor.col, 2);
+        state.cursor.col = 6;
+        state.apply_motion(&Motion::FindChar(find('.', false, true), 1), &ctx);
+        assert_eq!(state.cursor.col, 6);
+        state.apply_motion(&Motion::RepeatFind(find('.', false, true), 1), &ctx);
+        assert_eq!(state.cursor.col, 4);
+    }
+
+    #[test]
+    fn find_counts_wide_chars_once() {
+        let (cells, spacers) = wide_row("a日x日y");
+        let ctx = wide_ctx!(cells, spacers);
+        let mut state = ViState::new(0, 0);
+        state.apply_motion(&Motion::FindChar(find('日', true, false), 2), &ctx);
+        assert_eq!(state.cursor.col, 4);
+        state.cursor.col = 7;
+        state.apply_motion(&Motion::FindChar(find('日', false, true), 1), &ctx);
+        assert_eq!(state.cursor.col, 6);
+    }
+
+    // ── Text objects ────────────────────────────────────────────────
+
+    /// Select `object` at (row, col) and return the (anchor, cursor) positions.
+    fn select(lines: &[&str], row: usize, col: usize, object: TextObject) -> ((usize, usize), (usize, usize)) {
+        let (grid, cols) = make_ctx(lines, 0, lines.len());
+        let ctx = ctx_from!(&grid, cols, 0, lines.len());
+        let mut state = ViState::new(row, col);
+        state.process_key('v', false);
+        state.select_text_object(&object, &ctx);
+        assert_eq!(state.mode, ViMode::Visual);
+        let anchor = state.anchor.unwrap();
+        ((anchor.row, anchor.col), (state.cursor.row, state.cursor.col))
+    }
+
+    fn word(around: bool, count: usize) -> TextObject {
+        TextObject::Word { around, count }
+    }
+
+    #[test]
+    fn text_objects_parse_in_visual_mode_only() {
+        let mut state = ViState::new(0, 0);
+        assert_eq!(state.process_key('i', false), ViAction::None);
+        assert_eq!(state.pending_key, None);
+        state.process_key('v', false);
+        state.process_key('i', false);
+        assert_eq!(state.process_key('w', false), ViAction::SelectTextObject(word(false, 1)));
+        state.process_key('2', false);
+        state.process_key('a', false);
+        assert_eq!(
+            state.process_key(')', false),
+            ViAction::SelectTextObject(TextObject::Bracket { open: '(', close: ')', around: true, count: 2 })
+        );
+        state.process_key('i', false);
+        assert_eq!(
+            state.process_key('"', false),
+            ViAction::SelectTextObject(TextObject::Quote { quote: '"', around: false })
+        );
+        state.process_key('i', false);
+        assert_eq!(state.process_key('z', false), ViAction::None);
+    }
+
+    #[test]
+    fn iw_selects_word_blank_or_punctuation_runs() {
+        let lines = &["foo  bar.baz qux"];
+        assert_eq!(select(lines, 0, 6, word(false, 1)), ((0, 5), (0, 7)));
+        assert_eq!(select(lines, 0, 8, word(false, 1)), ((0, 8), (0, 8)));
+        assert_eq!(select(lines, 0, 0, word(false, 3)), ((0, 0), (0, 7)));
+    }
+
+    #[test]
+    fn aw_takes_trailing_or_leading_blanks() {
+        let lines = &["foo  bar.baz qux"];
+        assert_eq!(select(lines, 0, 0, word(true, 1)), ((0, 0), (0, 4)));
+        assert_eq!(select(lines, 0, 14, word(true, 1)), ((0, 12), (0, 15)));
+        assert_eq!(select(lines, 0, 3, word(true, 1)), ((0, 3), (0, 7)));
+    }
+
+    #[test]
+    fn quote_objects_pair_from_row_start() {
+        let lines = &["say \"hi there\" and \"x\""];
+        let quote = |around| TextObject::Quote { quote: '"', around };
+        assert_eq!(select(lines, 0, 6, quote(false)), ((0, 5), (0, 12)));
+        assert_eq!(select(lines, 0, 6, quote(true)), ((0, 4), (0, 14)));
+        assert_eq!(select(lines, 0, 0, quote(false)), ((0, 5), (0, 12)));
+        assert_eq!(select(lines, 0, 16, quote(false)), ((0, 20), (0, 20)));
+    }
+
+    #[test]
+    fn bracket_objects_span_rows_and_nest() {
+        let lines = &["f(a, (b)", "  c) end"];
+        let paren = |around, count| TextObject::Bracket { open: '(', close: ')', around, count };
+        assert_eq!(select(lines, 1, 2, paren(false, 1)), ((0, 2), (1, 2)));
+        assert_eq!(select(lines, 1, 2, paren(true, 1)), ((0, 1), (1, 3)));
+        assert_eq!(select(lines, 0, 6, paren(false, 2)), ((0, 2), (1, 2)));
+        assert_eq!(select(lines, 0, 7, paren(false, 1)), ((0, 6), (0, 6)));
+        // Nothing encloses the cursor: the selection stays put
+        assert_eq!(select(lines, 1, 6, paren(false, 1)), ((1, 6), (1, 6)));
+    }
 }
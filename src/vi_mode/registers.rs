@@ -0,0 +1,135 @@
+// Vi-mode registers: `"a` to `"z` before a yank store the text in a named
+// register instead of the clipboard, so several snippets can be collected
+// at once; `"A` to `"Z` append to the register. A plain yank (or `""`,
+// `"+`, `"*`) goes to the unnamed register, which is the system clipboard.
+
+use std::collections::BTreeMap;
+
+/// The register a yank goes to, picked with a `"` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    /// The system clipboard.
+    Unnamed,
+    /// Replace the contents of `a`–`z`.
+    Named(char),
+    /// Append to `a`–`z` (typed as the uppercase letter).
+    Append(char),
+}
+
+impl Register {
+    /// The register named by the key after `"`, if it names one.
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            '"' | '+' | '*' => Some(Self::Unnamed),
+            'a'..='z' => Some(Self::Named(key)),
+            'A'..='Z' => Some(Self::Append(key.to_ascii_lowercase())),
+            _ => None,
+        }
+    }
+}
+
+/// Named register contents, shared by every pane in the window.
+#[derive(Debug, Clone, Default)]
+pub struct Registers {
+    named: BTreeMap<char, String>,
+}
+
+impl Registers {
+    /// Store a yank. Returns the text for the clipboard when it went to the
+    /// unnamed register.
+    pub fn yank(&mut self, register: Register, text: String) -> Option<String> {
+        match register {
+            Register::Unnamed => return Some(text),
+            Register::Named(name) => {
+                self.named.insert(name, text);
+            }
+            Register::Append(name) => {
+                let contents = self.named.entry(name).or_default();
+                // Each appended snippet starts on its own line
+                if !contents.is_empty() && !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                contents.push_str(&text);
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, name: char) -> Option<&str> {
+        self.named.get(&name).map(String::as_str)
+    }
+
+    /// Non-empty registers in name order.
+    pub fn entries(&self) -> impl Iterator<Item = (char, &str)> {
+        self.named.iter().map(|(&name, text)| (name, text.as_str()))
+    }
+}
+
+/// One line standing in for a register's contents in the viewer.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let flat: String = text.trim_end_matches('\n').replace('\n', " \u{21B5} ");
+    match flat.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\u{2026}", &flat[..end]),
+        None => flat,
+    }
+}
+
+/// Selection state of the register viewer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegisterList {
+    pub selected: usize,
+}
+
+impl RegisterList {
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self, len: usize) {
+        if self.selected + 1 < len {
+            self.selected += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_keys() {
+        assert_eq!(Register::from_key('a'), Some(Register::Named('a')));
+        assert_eq!(Register::from_key('Q'), Some(Register::Append('q')));
+        assert_eq!(Register::from_key('"'), Some(Register::Unnamed));
+        assert_eq!(Register::from_key('+'), Some(Register::Unnamed));
+        assert_eq!(Register::from_key('1'), None);
+    }
+
+    #[test]
+    fn named_yanks_stay_off_the_clipboard() {
+        let mut registers = Registers::default();
+        assert_eq!(registers.yank(Register::Unnamed, "clip".into()).as_deref(), Some("clip"));
+        assert_eq!(registers.yank(Register::Named('a'), "one".into()), None);
+        assert_eq!(registers.yank(Register::Named('a'), "two".into()), None);
+        assert_eq!(registers.get('a'), Some("two"));
+        assert_eq!(registers.get('b'), None);
+    }
+
+    #[test]
+    fn uppercase_appends_on_a_new_line() {
+        let mut registers = Registers::default();
+        registers.yank(Register::Append('b'), "first".into());
+        registers.yank(Register::Append('b'), "second".into());
+        registers.yank(Register::Named('a'), "x".into());
+        assert_eq!(registers.get('b'), Some("first\nsecond"));
+        let names: Vec<char> = registers.entries().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!['a', 'b']);
+    }
+
+    #[test]
+    fn preview_flattens_and_truncates() {
+        assert_eq!(preview("a\nb\n", 20), "a \u{21B5} b");
+        assert_eq!(preview("abcdef", 3), "abc\u{2026}");
+        assert_eq!(preview("abc", 3), "abc");
+    }
+}
@@ -4,31 +4,43 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use winit::application::ApplicationHandler;
 use winit::dpi::{LogicalSize, PhysicalSize};
-use winit::event::{ElementState, MouseButton, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
+use winit::event::{ElementState, Ime, MouseButton, StartCause, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
 use winit::keyboard::{Key, ModifiersState, NamedKey};
 use winit::window::{CursorIcon, Window, WindowAttributes, WindowId};
 
-use crate::command_palette::{self, PaletteAction, PaletteState};
+use crate::command_palette::{self, PaletteState};
 use crate::config::theme::TerminalTheme;
-use crate::config::types::{Config, ConfigDelta};
+use crate::config::types::{Config, ConfigDelta, ProfileConfig};
 use crate::config::watcher::UserEvent;
+use crate::control::protocol::{Request as ControlRequest, Response as ControlResponse};
 use crate::header_bar::CHROME_BAR_HEIGHT;
+use crate::input::actions::Action;
+use crate::input::keybindings::Keymap;
+use crate::input::paste::{PastePrompt, SlowPaste};
+use crate::input::selection::CopyMode;
 use crate::input::{
-    match_app_command, match_overlay_command, match_pane_command, match_tab_command,
-    match_search_command, should_open_search, AppCommand, InputMode, OverlayCommand,
-    PaneCommand, SearchCommand, TabCommand,
+    match_search_command, AppCommand, EditCommand, InputMode, OverlayCommand, PaneCommand,
+    SearchCommand, TabCommand, ViewCommand,
 };
-use crate::link::opener::open_link;
+use crate::link::opener::{open_link, open_url};
+use crate::link::policy::{UrlPrompt, UrlVerdict};
 use crate::link::LinkDetector;
 use crate::search::SearchState;
 use crate::pane::header::PANE_HEADER_HEIGHT;
-use crate::pane::interaction::{CursorType, InteractionEffect, PaneInteraction};
+use crate::pane::interaction::{CursorType, InteractionEffect, PaneInteraction, ScrollbarInfo};
 use crate::pane::{PaneId, Rect, SplitDirection};
-use crate::renderer::iced_layer::{ActiveOverlay, DividerDisplay, MinimapPane, PaneInfo, SidebarTabInfo, TabInfo, UiMessage, UiState};
+use crate::profiles::{ProfilePicker, ProfileTarget};
+use crate::renderer::iced_layer::{ActiveOverlay, DividerDisplay, HintLabel, MinimapPane, PaneInfo, PromptIndicator, SidebarTabInfo, SuggestionStrip, TabInfo, UiMessage, UiState};
+use crate::renderer::subpixel::Subpixel;
 use crate::renderer::PaneRenderDescriptor;
+use crate::replay::TraceEvent;
+use crate::scripting::{ScriptCommand, ScriptContext, ScriptEvent, ScriptHost};
+use crate::scroll::ScrollbarMode;
+use crate::shell_integration::clipboard::{ClipboardPolicy, ClipboardRequest};
 use crate::status_bar::STATUS_BAR_HEIGHT;
 use crate::tab::TabManager;
+use crate::terminal::compat::Compat;
 
 /// Default window width in logical pixels.
 pub const DEFAULT_WIDTH: f64 = 1280.0;
@@ -37,6 +49,9 @@ pub const DEFAULT_HEIGHT: f64 = 720.0;
 /// Default window title.
 pub const DEFAULT_TITLE: &str = "VeloTerm";
 
+/// Default Wayland app-id / X11 WM_CLASS, matching `veloterm.desktop`.
+pub const DEFAULT_CLASS: &str = "veloterm";
+
 /// Configuration for the VeloTerm window.
 #[derive(Debug, Clone)]
 pub struct WindowConfig {
@@ -44,6 +59,18 @@ pub struct WindowConfig {
     pub height: f64,
     pub title: String,
     pub resizable: bool,
+    /// Wayland app-id and X11 WM_CLASS (general and instance name).
+    pub class: String,
+    /// Config file loaded at startup (`--config`, `--profile` or the
+    /// default) and watched for changes; None means the default.
+    pub config_path: Option<std::path::PathBuf>,
+    /// Socket of the daemon that owns this window's shells (`veloterm
+    /// attach`); None runs them in this process.
+    pub daemon_socket: Option<std::path::PathBuf>,
+    /// Create the window with an alpha channel (`window.opacity` below 1).
+    pub transparent: bool,
+    /// Ask the compositor to blur behind the window (`window.blur`).
+    pub blur: bool,
 }
 
 impl Default for WindowConfig {
@@ -58,6 +85,11 @@ impl Default for WindowConfig {
             height,
             title: DEFAULT_TITLE.to_string(),
             resizable: true,
+            class: DEFAULT_CLASS.to_string(),
+            config_path: None,
+            daemon_socket: None,
+            transparent: false,
+            blur: false,
         }
     }
 }
@@ -65,12 +97,23 @@ impl Default for WindowConfig {
 impl WindowConfig {
     /// Build a `WindowAttributes` from this configuration.
     pub fn to_window_attributes(&self) -> WindowAttributes {
-        Window::default_attributes()
+        let attrs = Window::default_attributes()
             .with_title(self.title.clone())
             .with_inner_size(LogicalSize::new(self.width, self.height))
             .with_resizable(self.resizable)
             .with_decorations(true)
-            .with_fullscreen(None)
+            .with_transparent(self.transparent)
+            .with_blur(self.blur)
+            .with_fullscreen(None);
+        // Window managers match rules and desktop entries on the app-id
+        // (Wayland) or WM_CLASS (X11); both backends read the same name
+        #[cfg(target_os = "linux")]
+        let attrs = winit::platform::wayland::WindowAttributesExtWayland::with_name(
+            attrs,
+            self.class.clone(),
+            self.class.clone(),
+        );
+        attrs
     }
 }
 
@@ -87,6 +130,12 @@ pub fn scaled_font_size(base_size: f32, scale_factor: f64) -> f32 {
     base_size * scale_factor as f32
 }
 
+/// The scale to rasterize the font at instead of the detected one: a
+/// runtime override, else a configured `font.scale_factor` (0 = detected).
+pub fn resolve_font_scale(runtime: Option<f64>, configured: f64) -> Option<f32> {
+    runtime.or((configured > 0.0).then_some(configured)).map(|scale| scale as f32)
+}
+
 /// Per-pane state: the terminal emulator and PTY session for a single pane.
 pub struct PaneState {
     pub terminal: crate::terminal::Terminal,
@@ -103,8 +152,63 @@ pub struct PaneState {
     pub shell_name: String,
     /// Per-pane markdown preview state. Some = overlay open.
     pub markdown_preview: Option<crate::markdown_preview::MarkdownPreviewState>,
+    /// Per-pane command-history suggestions mirrored from typed input.
+    pub suggestions: crate::suggestions::SuggestionState,
+    /// Per-pane color filter override. None = use `colors.filter` from config.
+    pub color_filter: Option<crate::renderer::color_filter::ColorFilter>,
+    /// Per-pane ruler/crosshair/soft wrap toggles. None = use `[guides]` from config.
+    pub guides: crate::renderer::guides::PaneGuides,
+    /// Display rows of the last frame while soft wrap is on, for mapping
+    /// mouse positions back to terminal cells.
+    pub soft_wrap: Option<crate::renderer::soft_wrap::SoftWrapLayout>,
+    /// Columns shown when the terminal is wider than the pane
+    /// (`panes.min_columns`, `panes.fixed_columns`).
+    pub h_scroll: crate::scroll::HorizontalScroll,
+    /// "Did you mean" correction after a command-not-found exit.
+    pub command_hint: Option<crate::command_hint::CommandHint>,
+    /// Badge from the `[[directory_rules]]` entry matching the pane's cwd.
+    pub badge: Option<crate::directory_rules::PaneBadge>,
+    /// Colors from the `[[directory_rules]]` entry matching the pane's cwd,
+    /// used over `theme` while the pane stays there.
+    pub directory_theme: Option<crate::config::theme::TerminalTheme>,
+    /// Find-in-scrollback query and matches, kept per pane across focus changes.
+    pub search: SearchState,
+    /// Shell liveness: exit without hangup, unanswered input.
+    pub watchdog: crate::pty::watchdog::Watchdog,
+    /// Output flood detection and the per-frame parse cap.
+    pub flood: crate::pty::flood::FloodGuard,
+    /// Set while output is shown with control bytes as hex codes.
+    pub control_bytes: Option<crate::terminal::control_bytes::ControlByteView>,
+    /// Set while the pane is untrusted or on the legacy preset: output loses
+    /// title, clipboard and query sequences, or modern features.
+    pub output_filter: Option<crate::terminal::sanitize::OutputFilter>,
+    /// Compatibility preset of the pane's shell.
+    pub compat: Compat,
+    /// Offer to restart the shell after its preset was switched.
+    pub restart_prompt: Option<String>,
+    /// Rasterized scrollback minimap, rebuilt after new output.
+    pub minimap: crate::renderer::minimap::MinimapCache,
+    /// OSC 52 request waiting for the user to allow or deny it.
+    pub clipboard_prompt: Option<ClipboardRequest>,
+    /// Notes attached to buffer lines.
+    pub notes: crate::notes::PaneNotes,
+    /// Note being written, shown across the pane bottom.
+    pub note_editor: Option<crate::notes::NoteEditor>,
+    /// Bookmarked lines, shown in the gutter and the bookmark list.
+    pub bookmarks: crate::bookmarks::PaneBookmarks,
+    /// `[[profiles]]` entry the pane was started from.
+    pub profile: Option<String>,
+    /// The profile's colors, used instead of the window's theme.
+    pub theme: Option<crate::config::theme::TerminalTheme>,
+    /// Foreground process, polled about once a second for the title.
+    pub process_name: Option<String>,
+    /// The pane's own title from OSC 0/2, its program or its cwd.
+    pub title: Option<String>,
 }
 
+/// Opens a link or URL, given the activation token if one was granted.
+type PendingOpen = Box<dyn FnOnce(Option<&str>)>;
+
 /// Main application state implementing the winit event loop handler.
 pub struct App {
     config: WindowConfig,
@@ -114,15 +218,38 @@ pub struct App {
     tab_manager: TabManager,
     pane_states: HashMap<PaneId, PaneState>,
     modifiers: ModifiersState,
+    /// Which Option keys are held, for `macos.option_as_alt`.
+    option_keys: crate::input::option_key::OptionKeys,
+    /// Last cursor area reported to the input method.
+    ime: crate::input::ime::ImeState,
+    /// Scrollback minimap column shown (`minimap.enabled`, toggled at runtime).
+    minimap_visible: bool,
+    /// Links and URLs waiting for an activation token before opening.
+    pending_opens: Vec<(winit::event_loop::AsyncRequestSerial, PendingOpen)>,
     interaction: PaneInteraction,
     link_detector: LinkDetector,
+    /// Key bindings: built-in shortcuts with `[keys]` overrides.
+    keymap: Keymap,
     link_hover_active: bool,
     input_mode: InputMode,
-    search_state: SearchState,
     current_font_size: f32,
     default_font_size: f32,
+    /// Font scale set with `veloterm ctl set-font-scale`, over
+    /// `font.scale_factor`; saved with the session.
+    font_scale_override: Option<f64>,
     event_proxy: Option<EventLoopProxy<UserEvent>>,
-    screenshot_requested: bool,
+    /// Screenshot to cut from the next frame.
+    pending_screenshot: Option<crate::screenshot::PendingScreenshot>,
+    /// Captured area flashing to confirm a screenshot, and when it stops.
+    screenshot_flash: Option<(Rect, std::time::Instant)>,
+    /// Pane being recorded as an animated GIF.
+    pane_recording: Option<crate::pane_recording::PaneRecording>,
+    /// The config script's key callbacks and event hooks.
+    scripts: Option<ScriptHost>,
+    /// Events for the script's hooks, fired after the frame's PTY drain.
+    script_events: Vec<(ScriptEvent, rhai::Map)>,
+    /// Active tab when `tab_switched` was last checked.
+    script_tab: usize,
     hovered_tab: Option<usize>,
     /// Tab drag state for drag-to-reorder.
     tab_drag_index: Option<usize>,
@@ -130,19 +257,58 @@ pub struct App {
     tab_drag_active: bool,
     /// Throttle foreground process name detection (FFI syscall).
     last_process_check: std::time::Instant,
-    last_process_name: Option<String>,
     /// Visual bell flash end time.
     bell_flash_until: Option<std::time::Instant>,
+    /// Day and night themes by the clock (`[colors.schedule]`), if enabled.
+    theme_scheduler: Option<crate::theme_schedule::ThemeScheduler>,
+    /// Which ticks of the frame loop draw, and when the loop ticks next.
+    frame_pacer: crate::renderer::pacing::FramePacer,
     /// Whether the window is hidden via quick terminal toggle.
     quick_terminal_hidden: bool,
     /// Command palette state (Some when palette is open).
     palette_state: Option<PaletteState>,
+    /// Bookmark list for the focused pane (Some when open).
+    bookmark_list: Option<crate::bookmarks::BookmarkList>,
+    /// Vi-mode named registers, shared by all panes.
+    vi_registers: crate::vi_mode::registers::Registers,
+    /// The vi register viewer, when open.
+    register_list: Option<crate::vi_mode::registers::RegisterList>,
+    /// Pane pairs that scroll together.
+    scroll_locks: crate::pane::scroll_lock::ScrollLocks,
+    /// Named pane groups and which of them broadcast input.
+    pane_groups: crate::pane::groups::PaneGroups,
+    /// Group name being typed for a pane, shown across its bottom.
+    group_editor: Option<crate::pane::groups::GroupEditor>,
+    /// Hint mode labels over a pane's URLs, paths, SHAs and addresses.
+    hint_mode: Option<(PaneId, crate::link::hints::HintMode)>,
+    /// Profile picker for a new tab or split (Some when open).
+    profile_picker: Option<crate::profiles::ProfilePicker>,
     /// Whether the tab sidebar is visible.
     sidebar_visible: bool,
     /// Which sidebar tab is currently hovered (for close button reveal).
     hovered_sidebar_tab: Option<usize>,
     /// Whether the theme selector popup is open.
     theme_selector_open: bool,
+    /// Multi-line clipboard text waiting for the paste prompt.
+    paste_prompt: Option<PastePrompt>,
+    /// URL outside `links.allowed_schemes`/`allowed_hosts` waiting for the
+    /// user to confirm it.
+    url_prompt: Option<UrlPrompt>,
+    /// Slow paste in progress; dropping it stops the rest.
+    slow_paste: Option<SlowPaste>,
+    /// Restore the saved session on startup (`session.auto_restore` or
+    /// `--restore-session`).
+    restore_session: bool,
+    /// Command and directory for the first pane (`-e`, `--cwd`), as an
+    /// unnamed profile. Replaces session restore.
+    launch: Option<ProfileConfig>,
+    /// One profile per pane of the first tab's startup grid (`--grid`,
+    /// `panes.startup_grid`).
+    launch_grid: Vec<ProfileConfig>,
+    /// Title locked on the first tab (`--title`).
+    launch_title: Option<String>,
+    /// Keybinding cheatsheet overlay is showing.
+    cheatsheet_open: bool,
     /// Tab being renamed inline (index into sidebar tabs).
     editing_sidebar_tab: Option<usize>,
     /// Current text value in the rename field.
@@ -168,6 +334,15 @@ pub struct App {
     /// Whether a divider drag is in progress. When true, PTY/terminal resizes
     /// are deferred until drag ends to avoid flooding shells with SIGWINCH.
     is_dragging_divider: bool,
+    /// Button whose press was reported to the focused pane's application
+    /// (mouse reporting); its drags and release are reported too.
+    mouse_report_button: Option<crate::input::mouse_report::ReportButton>,
+    /// Last cell reported, so motion is sent once per cell.
+    mouse_report_cell: Option<(usize, usize)>,
+    /// Pixel wheel scrolling not yet reported as a whole line.
+    mouse_report_scroll_px: f32,
+    /// Secure Keyboard Entry toggled on (macOS; held only while focused).
+    secure_keyboard_entry: bool,
     /// File browser overlay state. Some = state exists (may or may not be visible).
     file_browser_state: Option<crate::file_browser::FileBrowserState>,
     /// Git review overlay state. Some = state exists (may or may not be visible).
@@ -181,12 +356,39 @@ pub struct App {
     /// Pending resize dimensions — coalesced across rapid resize events and
     /// applied on the next redraw to avoid flooding shells with SIGWINCH.
     pending_resize: Option<(u32, u32)>,
+    /// Cached git branch/dirty state per directory for the status bar.
+    git_segments: crate::git_segment::GitSegmentCache,
+    /// Live pinch-zoom magnification of the focused pane. None when no
+    /// gesture is in progress; snapped to a font size when it ends.
+    pinch_scale: Option<f32>,
+    /// Touchscreen gesture recognizer.
+    touch: crate::input::touch::TouchState,
+    /// Device of the most recent touch (for events synthesized from gestures).
+    touch_device: Option<winit::event::DeviceId>,
+    /// Show selection drag handles (touch input in use; hidden on mouse move).
+    touch_handles_visible: bool,
+    /// True while gesture-synthesized mouse events are being dispatched.
+    touch_dispatching: bool,
+    /// Panes whose shell failed to start, with the error shown in the pane.
+    spawn_failures: HashMap<PaneId, String>,
+    /// Last time pane shells were probed by the watchdog.
+    last_health_check: std::time::Instant,
+    /// Event trace being written (`--record-events`).
+    event_recorder: Option<crate::replay::EventRecorder>,
+    /// Secret patterns from `[redaction]`.
+    redactor: crate::redact::Redactor,
 }
 
 impl App {
     pub fn new(config: WindowConfig, app_config: Config) -> Self {
         let font_size = app_config.font.size as f32;
         let sidebar_default = app_config.sidebar.default_visible;
+        let git_ttl = std::time::Duration::from_secs(app_config.git_status.refresh_interval_secs);
+        let keymap = Keymap::from_config(&app_config.keys.bindings);
+        let restore_session = app_config.session.auto_restore;
+        let minimap_visible = app_config.minimap.enabled;
+        let redactor = crate::redact::Redactor::from_config(&app_config.redaction);
+        let theme_scheduler = crate::theme_schedule::ThemeScheduler::new(&app_config.colors.schedule);
         Self {
             config,
             app_config,
@@ -195,27 +397,54 @@ impl App {
             tab_manager: TabManager::new(),
             pane_states: HashMap::new(),
             modifiers: ModifiersState::empty(),
+            option_keys: Default::default(),
+            ime: Default::default(),
+            pending_opens: Vec::new(),
+            minimap_visible,
             interaction: PaneInteraction::new(),
             link_detector: LinkDetector::new(),
+            keymap,
             link_hover_active: false,
             input_mode: InputMode::default(),
-            search_state: SearchState::default(),
             current_font_size: font_size,
             default_font_size: font_size,
+            font_scale_override: None,
             event_proxy: None,
-            screenshot_requested: false,
+            pending_screenshot: None,
+            screenshot_flash: None,
+            pane_recording: None,
+            scripts: None,
+            script_events: Vec::new(),
+            script_tab: 0,
             hovered_tab: None,
             tab_drag_index: None,
             tab_drag_start_x: 0.0,
             tab_drag_active: false,
             last_process_check: std::time::Instant::now(),
-            last_process_name: None,
             bell_flash_until: None,
+            theme_scheduler,
+            frame_pacer: crate::renderer::pacing::FramePacer::new(),
             quick_terminal_hidden: false,
             palette_state: None,
+            bookmark_list: None,
+            vi_registers: Default::default(),
+            register_list: None,
+            scroll_locks: Default::default(),
+            pane_groups: Default::default(),
+            group_editor: None,
+            hint_mode: None,
+            profile_picker: None,
             sidebar_visible: sidebar_default,
             hovered_sidebar_tab: None,
             theme_selector_open: false,
+            paste_prompt: None,
+            slow_paste: None,
+            url_prompt: None,
+            restore_session,
+            launch: None,
+            launch_grid: Vec::new(),
+            launch_title: None,
+            cheatsheet_open: false,
             editing_sidebar_tab: None,
             editing_tab_value: String::new(),
             hovering_new_tab: false,
@@ -238,15 +467,128 @@ impl App {
             context_menu_position: (0.0, 0.0),
             context_menu_has_selection: false,
             is_dragging_divider: false,
+            mouse_report_button: None,
+            mouse_report_cell: None,
+            mouse_report_scroll_px: 0.0,
+            secure_keyboard_entry: false,
             file_browser_state: None,
             git_review_state: None,
             fb_last_click: None,
             hovering_file_browser_icon: false,
             hovering_git_review_icon: false,
             pending_resize: None,
+            git_segments: crate::git_segment::GitSegmentCache::new(git_ttl),
+            pinch_scale: None,
+            touch: crate::input::touch::TouchState::new(),
+            touch_device: None,
+            touch_handles_visible: false,
+            touch_dispatching: false,
+            spawn_failures: HashMap::new(),
+            last_health_check: std::time::Instant::now(),
+            event_recorder: None,
+            redactor,
+        }
+    }
+
+    /// Log input, PTY output, resizes and layout changes to `recorder` so
+    /// the session can be replayed with `veloterm play`.
+    pub fn record_events(&mut self, recorder: crate::replay::EventRecorder) {
+        self.event_recorder = Some(recorder);
+    }
+
+    fn record_event(&mut self, event: TraceEvent) {
+        if let Some(recorder) = &mut self.event_recorder {
+            recorder.record(event);
+        }
+    }
+
+    /// Record input typed into a pane, masked per `redaction.event_traces`:
+    /// whole keystrokes while the pane has echo off, secret patterns
+    /// otherwise.
+    fn record_input(&mut self, pane_id: PaneId, bytes: &[u8]) {
+        if self.event_recorder.is_none() {
+            return;
+        }
+        let event = if self.app_config.redaction.event_traces {
+            let echo = self.pane_states.get(&pane_id).is_none_or(|state| !state.pty.echo_disabled());
+            TraceEvent::input(pane_id, &self.redactor.redact_input(bytes, echo))
+        } else {
+            TraceEvent::input(pane_id, bytes)
+        };
+        self.record_event(event);
+    }
+
+    /// Record the active tab's pane layout.
+    fn record_layout(&mut self) {
+        if self.event_recorder.is_some() {
+            let event = TraceEvent::layout(&self.tab_manager.active_tab().pane_tree);
+            self.record_event(event);
+        }
+    }
+
+    /// Restore the saved session on startup even if `session.auto_restore`
+    /// is off (`--restore-session`).
+    pub fn request_session_restore(&mut self) {
+        self.restore_session = true;
+    }
+
+    /// Run `command` (program and arguments) in the first pane instead of
+    /// the shell, starting in `cwd`. Either may be empty (`-e`, `--cwd`).
+    pub fn launch_command(&mut self, command: Vec<String>, cwd: Option<std::path::PathBuf>) {
+        let mut command = command.into_iter();
+        self.launch = Some(ProfileConfig {
+            name: String::new(),
+            command: command.next(),
+            args: command.collect(),
+            env: Default::default(),
+            cwd: cwd.map(|dir| dir.to_string_lossy().into_owned()),
+            theme: None,
+            background: None,
+            trusted: None,
+            term: None,
+            compat: None,
+            color_filter: None,
+        });
+    }
+
+    /// Open the first tab as a grid of panes, each running one of
+    /// `commands` (`--grid`). Replaces session restore.
+    pub fn launch_grid(&mut self, commands: &[String], cwd: Option<std::path::PathBuf>) {
+        let cwd = cwd.map(|dir| dir.to_string_lossy().into_owned());
+        self.launch_grid = commands
+            .iter()
+            .map(|command| crate::profiles::command_profile(command, cwd.as_deref()))
+            .collect();
+    }
+
+    /// Split the first tab into the startup grid and start each pane's
+    /// command.
+    fn spawn_launch_grid(&mut self, width: f32, height: f32) {
+        let profiles = self.launch_grid.clone();
+        let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
+        let panes = pane_tree.split_into_grid(profiles.len());
+        let pgrid = self.pane_grid_bounds(width, height);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        for (pane_id, profile) in panes.into_iter().zip(&profiles) {
+            let Some((_, rect)) = layout.iter().find(|(id, _)| *id == pane_id) else {
+                continue;
+            };
+            let (cols, rows) = self.grid_dims_for_rect(rect);
+            self.spawn_pane_with_profile(pane_id, cols, rows, None, Some(profile));
         }
     }
 
+    /// Title the window and lock the first tab's title (`--title`).
+    pub fn set_title(&mut self, title: String) {
+        self.config.title = title.clone();
+        self.launch_title = Some(title);
+    }
+
+    /// Show the keybinding cheatsheet overlay (e.g. on first launch).
+    pub fn show_cheatsheet(&mut self) {
+        self.cheatsheet_open = true;
+    }
+
     /// Get the tab manager (for testing).
     pub fn tab_manager(&self) -> &TabManager {
         &self.tab_manager
@@ -262,55 +604,252 @@ impl App {
         &self.pane_states
     }
 
-    /// Spawn a PTY + Terminal for a new pane, using the given grid dimensions.
-    fn spawn_pane(&mut self, pane_id: PaneId, cols: u16, rows: u16) {
-        let scrollback = self.app_config.scrollback.lines as usize;
-        let shell = crate::pty::resolve_shell(&self.app_config.shell);
-        match crate::pty::PtySession::new_with_cwd(&shell, cols, rows, None, Some(&self.app_config.shell), Some(&self.app_config.colors.theme)) {
-            Ok(pty) => {
-                log::info!(
-                    "PTY spawned for pane {:?}: {shell} ({cols}x{rows})",
-                    pane_id
-                );
-                let terminal = crate::terminal::Terminal::new(
-                    cols as usize,
-                    rows as usize,
-                    scrollback,
-                );
-                let cursor = crate::renderer::cursor::CursorState::new();
-                let shell_name = crate::pty::basename_from_path(&shell).to_string();
-                self.pane_states.insert(pane_id, PaneState { terminal, pty, vi_state: None, cursor, mouse_selection: crate::input::mouse::MouseSelectionState::new(), scroll_state: crate::scroll::ScrollState::new(), shell_name, markdown_preview: None });
-            }
-            Err(e) => {
-                log::error!("Failed to spawn PTY for pane {:?}: {e}", pane_id);
+    /// Start a shell: in this process, or in the daemon for an attached
+    /// window. The daemon starts `shell` with its own settings, so only a
+    /// profile's command and directory reach it.
+    fn open_pty(&self, shell: &str, cols: u16, rows: u16, cwd: Option<&str>, shell_config: &crate::config::types::ShellConfig, profile: Option<&ProfileConfig>) -> Result<crate::pty::PtySession, crate::pty::PtyError> {
+        match &self.config.daemon_socket {
+            Some(socket) => crate::pty::PtySession::spawn_in_daemon(socket, shell, cols, rows, cwd),
+            None => {
+                let theme = profile.and_then(|p| p.theme.as_deref()).unwrap_or(&self.app_config.colors.theme);
+                crate::pty::PtySession::new_with_cwd(shell, cols, rows, cwd, Some(shell_config), Some((theme, &self.app_config.colors)))
             }
         }
     }
 
+    /// Spawn a PTY + Terminal for a new pane, using the given grid dimensions.
+    fn spawn_pane(&mut self, pane_id: PaneId, cols: u16, rows: u16) {
+        self.spawn_pane_with_cwd(pane_id, cols, rows, None);
+    }
+
     /// Spawn a PTY + Terminal for a new pane with an optional working directory.
     fn spawn_pane_with_cwd(&mut self, pane_id: PaneId, cols: u16, rows: u16, cwd: Option<&str>) {
-        let scrollback = self.app_config.scrollback.lines as usize;
-        let shell = crate::pty::resolve_shell(&self.app_config.shell);
-        match crate::pty::PtySession::new_with_cwd(&shell, cols, rows, cwd, Some(&self.app_config.shell), Some(&self.app_config.colors.theme)) {
+        self.spawn_pane_with_profile(pane_id, cols, rows, cwd, None);
+    }
+
+    /// Spawn a pane running `profile`'s command with its environment and
+    /// colors. `cwd` wins over the profile's directory (restarts keep the
+    /// pane where it was).
+    fn spawn_pane_with_profile(&mut self, pane_id: PaneId, cols: u16, rows: u16, cwd: Option<&str>, profile: Option<&ProfileConfig>) {
+        self.spawn_pane_with_compat(pane_id, cols, rows, cwd, profile, None);
+    }
+
+    /// Spawn a pane like `spawn_pane_with_profile`, with `compat` replacing
+    /// the preset of the profile or `shell.compat` (a pane restarted after
+    /// switching presets).
+    fn spawn_pane_with_compat(&mut self, pane_id: PaneId, cols: u16, rows: u16, cwd: Option<&str>, profile: Option<&ProfileConfig>, compat: Option<Compat>) {
+        let mut shell_config = match profile {
+            Some(profile) => crate::profiles::shell_config(profile, &self.app_config.shell),
+            None => self.app_config.shell.clone(),
+        };
+        if let Some(compat) = compat {
+            shell_config.compat = compat.as_str().to_string();
+        }
+        let compat = Compat::of(&shell_config);
+        let shell = crate::pty::resolve_shell(&shell_config);
+        let home = std::env::var("HOME").ok();
+        let cwd = cwd.map(str::to_string).or_else(|| profile.and_then(|p| crate::profiles::start_dir(p, home.as_deref())));
+        let cwd = cwd.as_deref();
+        let visible_cols = cols;
+        let cols = self.terminal_columns(cols);
+        match self.open_pty(&shell, cols, rows, cwd, &shell_config, profile) {
             Ok(pty) => {
                 log::info!(
-                    "PTY spawned for pane {:?}: {shell} ({cols}x{rows}) cwd={:?}",
+                    "PTY spawned for pane {:?}: {shell} ({cols}x{rows}) cwd={:?} profile={:?} compat={}",
                     pane_id,
-                    cwd
+                    cwd,
+                    profile.map(|p| &p.name),
+                    compat.as_str()
                 );
-                let terminal = crate::terminal::Terminal::new(
-                    cols as usize,
-                    rows as usize,
-                    scrollback,
-                );
-                let cursor = crate::renderer::cursor::CursorState::new();
                 let shell_name = crate::pty::basename_from_path(&shell).to_string();
-                self.pane_states.insert(pane_id, PaneState { terminal, pty, vi_state: None, cursor, mouse_selection: crate::input::mouse::MouseSelectionState::new(), scroll_state: crate::scroll::ScrollState::new(), shell_name, markdown_preview: None });
+                self.insert_pane_state(pane_id, pty, shell_name, cols, rows);
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    state.h_scroll.set_visible(visible_cols as usize, cols as usize);
+                    if let Some(profile) = profile {
+                        state.profile = Some(profile.name.clone());
+                        state.theme = crate::profiles::theme(profile, &self.app_config.colors);
+                    }
+                    let trusted = profile.and_then(|p| p.trusted).unwrap_or(self.app_config.security.trust_panes);
+                    state.compat = compat;
+                    state.output_filter = crate::terminal::sanitize::OutputFilter::for_pane(trusted, compat);
+                }
             }
             Err(e) => {
                 log::error!("Failed to spawn PTY for pane {:?}: {e}", pane_id);
+                self.spawn_failures.insert(pane_id, format!("{shell}: {e}"));
+            }
+        }
+    }
+
+    /// Forget a closed pane. Its shell ends, also one owned by the daemon:
+    /// only closing the window leaves those running.
+    fn discard_pane(&mut self, pane_id: PaneId) {
+        if let Some(state) = self.pane_states.remove(&pane_id) {
+            state.pty.close();
+        }
+        self.spawn_failures.remove(&pane_id);
+        self.scroll_locks.remove(pane_id);
+        self.pane_groups.remove(pane_id);
+        if self.group_editor.as_ref().is_some_and(|editor| editor.pane == pane_id) {
+            self.group_editor = None;
+        }
+        if self.pane_recording.as_ref().is_some_and(|r| r.pane() == pane_id) {
+            self.stop_pane_recording();
+        }
+    }
+
+    /// Open one tab per detached daemon pane, attached at the window's
+    /// size. Returns false if there were none to attach.
+    fn attach_daemon_panes(&mut self, width: f32, height: f32) -> bool {
+        let Some(socket) = self.config.daemon_socket.clone() else {
+            return false;
+        };
+        let panes = match crate::daemon::list_panes(&socket) {
+            Ok(panes) => panes,
+            Err(e) => {
+                log::warn!("Listing daemon panes on {}: {e}", socket.display());
+                return false;
+            }
+        };
+        let pgrid = self.pane_grid_bounds(width, height);
+        let (cols, rows) = self.grid_dims_for_rect(&Rect::new(0.0, 0.0, pgrid.width, pgrid.height));
+        let mut tabs = Vec::new();
+        for pane in panes.into_iter().filter(|p| !p.attached) {
+            let tab = crate::tab::Tab::from_pane_tree(pane.command.clone(), crate::pane::PaneTree::new());
+            let pane_id = tab.pane_tree.focused_pane_id();
+            match crate::pty::PtySession::attach_daemon(&socket, pane.id, cols, rows) {
+                Ok(pty) => {
+                    log::info!("Attached daemon pane {} ({}) as {:?}", pane.id, pane.command, pane_id);
+                    self.insert_pane_state(pane_id, pty, pane.command, cols, rows);
+                    tabs.push(tab);
+                }
+                Err(e) => log::warn!("Attaching daemon pane {}: {e}", pane.id),
+            }
+        }
+        if tabs.is_empty() {
+            return false;
+        }
+        self.tab_manager = TabManager::from_tabs(tabs, 0);
+        true
+    }
+
+    /// Set up a pane's terminal around its started shell.
+    fn insert_pane_state(&mut self, pane_id: PaneId, pty: crate::pty::PtySession, shell_name: String, cols: u16, rows: u16) {
+        let mut terminal = crate::terminal::Terminal::new(
+            cols as usize,
+            rows as usize,
+            self.app_config.scrollback.lines as usize,
+        );
+        terminal.set_background_reflow_lines(
+            self.app_config.performance.background_reflow_lines,
+        );
+        let mut cursor = crate::renderer::cursor::CursorState::new();
+        Self::apply_cursor_config(&mut terminal, &mut cursor, &self.app_config.cursor);
+        let flood = crate::pty::flood::FloodGuard::new(self.app_config.performance.flood_threshold_mb);
+        let output_filter = (!self.app_config.security.trust_panes).then(crate::terminal::sanitize::OutputFilter::new);
+        self.spawn_failures.remove(&pane_id);
+        self.pane_states.insert(pane_id, PaneState { terminal, pty, vi_state: None, cursor, mouse_selection: crate::input::mouse::MouseSelectionState::new(), scroll_state: crate::scroll::ScrollState::new(), shell_name, markdown_preview: None, suggestions: crate::suggestions::SuggestionState::new(), color_filter: None, guides: Default::default(), soft_wrap: None, h_scroll: Default::default(), command_hint: None, badge: None, directory_theme: None, search: SearchState::default(), watchdog: crate::pty::watchdog::Watchdog::new(), flood, control_bytes: None, output_filter, compat: Compat::Standard, restart_prompt: None, minimap: Default::default(), clipboard_prompt: None, notes: Default::default(), note_editor: None, bookmarks: Default::default(), profile: None, theme: None, process_name: None, title: None });
+    }
+
+    /// Give a pane's cursor the configured shape, blinking and blink interval.
+    fn apply_cursor_config(
+        terminal: &mut crate::terminal::Terminal,
+        cursor: &mut crate::renderer::cursor::CursorState,
+        config: &crate::config::types::CursorConfig,
+    ) {
+        let style = crate::renderer::cursor::CursorStyle::from_config_str(&config.style).unwrap_or(crate::renderer::cursor::CursorStyle::Block);
+        terminal.set_default_cursor_style(style, config.blink);
+        cursor.set_blink_rate(config.blink_interval);
+        cursor.set_animation(if config.animation { config.animation_duration } else { 0 });
+    }
+
+    /// The profile a pane was started from: the `-e` command, a startup
+    /// grid command or a `[[profiles]]` entry.
+    fn profile_named(&self, name: &str) -> Option<ProfileConfig> {
+        match &self.launch {
+            Some(launch) if name == launch.name => Some(launch.clone()),
+            _ => crate::profiles::find(&self.launch_grid, name)
+                .or_else(|| crate::profiles::find(&self.app_config.profiles, name))
+                .cloned(),
+        }
+    }
+
+    /// Respawn a pane's shell in its last known directory, replacing the
+    /// old session. Used for panes whose shell exited, hung or never started.
+    fn restart_pane(&mut self, pane_id: PaneId) {
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some((_, rect)) = layout.iter().find(|(id, _)| *id == pane_id) else {
+            return;
+        };
+        let (cols, rows) = self.grid_dims_for_rect(rect);
+        let (cwd, profile, compat) = self.pane_states.remove(&pane_id).map_or((None, None, None), |state| {
+            let cwd = state.terminal.shell_state().cwd.clone();
+            state.pty.close();
+            (cwd, state.profile, Some(state.compat))
+        });
+        let profile = profile.and_then(|name| self.profile_named(&name));
+        log::info!("Restarting shell for pane {:?}", pane_id);
+        self.spawn_pane_with_compat(pane_id, cols, rows, cwd.as_deref(), profile.as_ref(), compat);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.remove_pane_damage(pane_id);
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Whether a pane has no live shell to type into.
+    fn pane_is_dead(&self, pane_id: PaneId) -> bool {
+        self.spawn_failures.contains_key(&pane_id)
+            || self
+                .pane_states
+                .get(&pane_id)
+                .is_some_and(|state| state.watchdog.health().is_dead())
+    }
+
+    /// Probe every pane's shell for an exit the PTY didn't report and for
+    /// unanswered input. Throttled to `watchdog::CHECK_INTERVAL`.
+    fn check_pane_health(&mut self) {
+        let now = std::time::Instant::now();
+        if now.duration_since(self.last_health_check) < crate::pty::watchdog::CHECK_INTERVAL {
+            return;
+        }
+        self.last_health_check = now;
+        for (pane_id, state) in self.pane_states.iter_mut() {
+            let exit = state.pty.try_exit_status();
+            let reader_closed = state.pty.reader_closed() && state.pty.reader_rx.is_empty();
+            if state.watchdog.check(now, exit.as_ref(), reader_closed) {
+                log::warn!("Pane {:?} shell health: {:?}", pane_id, state.watchdog.health());
             }
         }
+        if let Some(renderer) = &self.renderer {
+            let stats = renderer.damage_stats();
+            log::debug!(
+                "Damage caches: {} panes, {} cells, {} instances, {} KiB ({} KiB on the GPU)",
+                stats.panes,
+                stats.cached_cells,
+                stats.cached_instances,
+                stats.bytes / 1024,
+                stats.gpu_bytes / 1024
+            );
+        }
+    }
+
+    /// Error banner for a pane, if its shell is dead or not responding.
+    fn pane_error_banner(&self, pane_id: PaneId) -> Option<String> {
+        let restart_combo = self
+            .keymap
+            .bindings()
+            .iter()
+            .find(|b| b.action == Action::Pane(PaneCommand::RestartShell))
+            .map(|b| b.combo.as_str());
+        if let Some(error) = self.spawn_failures.get(&pane_id) {
+            return crate::pty::watchdog::PaneHealth::SpawnFailed(error.clone()).banner(restart_combo);
+        }
+        self.pane_states.get(&pane_id)?.watchdog.health().banner(restart_combo)
     }
 
     /// Total chrome height above the content area (chrome bar + divider).
@@ -348,156 +887,973 @@ impl App {
         Rect::new(left, top, content_w, content_h)
     }
 
-    /// Handle a pane command (split, close, focus, zoom).
-    /// Process shell state updates after PTY drain: notifications and CWD tab titles.
-    fn process_shell_updates(&mut self) {
-        let focused = self
-            .tab_manager
-            .active_tab()
-            .pane_tree
-            .focused_pane_id();
-        let threshold = self.app_config.shell.notification_threshold_secs;
-        let shell_enabled = self.app_config.shell.integration_enabled;
+    /// Send the focused pane's visible screen, as reading-order text, to the
+    /// configured line view command or the clipboard.
+    fn export_line_view(&self) {
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get(&focused_id) else {
+            return;
+        };
+        let mut text = crate::terminal::grid_bridge::extract_line_view(&state.terminal);
+        if self.app_config.redaction.export {
+            text = self.redactor.redact(&text).into_owned();
+        }
+        match self.app_config.accessibility.line_view_command.as_deref() {
+            Some(command) if !command.trim().is_empty() => {
+                crate::accessibility::pipe_line_view(command, text);
+            }
+            _ => {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    if let Err(e) = clipboard.set_text(&text) {
+                        log::warn!("Line view clipboard error: {e}");
+                    }
+                }
+            }
+        }
+    }
 
-        // Collect pane IDs to avoid borrow conflict
-        let pane_ids: Vec<_> = self.pane_states.keys().copied().collect();
+    /// The color filter configured globally via `colors.filter`.
+    /// Whether typed input is mirrored per pane (history suggestions or
+    /// command-not-found hints need the submitted line, the prompt indicator
+    /// counts typed characters).
+    fn mirrors_input(&self) -> bool {
+        self.app_config.suggestions.enabled
+            || self.app_config.command_hints.enabled
+            || (self.app_config.prompt_indicator.enabled && self.app_config.prompt_indicator.char_count)
+    }
 
-        for pane_id in pane_ids {
-            let state = self.pane_states.get_mut(&pane_id).unwrap();
-            let shell = state.terminal.shell_state_mut();
+    /// Open a link or URL once the compositor hands out an activation
+    /// token, so the launched application can take focus. Opens right away
+    /// (without a token) where tokens aren't supported.
+    fn open_with_activation(&mut self, open: PendingOpen) {
+        #[cfg(target_os = "linux")]
+        if let Some(window) = &self.window {
+            use winit::platform::startup_notify::WindowExtStartupNotify;
+            if let Ok(serial) = window.request_activation_token() {
+                self.pending_opens.push((serial, open));
+                return;
+            }
+        }
+        open(None);
+    }
 
-            // Check for completed commands — notification for non-focused panes
-            if shell_enabled {
-                if let Some(duration) = shell.pending_completion.take() {
-                    if pane_id != focused && duration.as_secs() >= threshold {
-                        if let Some(tab_idx) = self.tab_manager.tab_index_for_pane(pane_id) {
-                            self.tab_manager.set_notification(tab_idx, true);
-                        }
-                    }
+    /// Open `url` if `[links]` allows it, ask in the focused pane first if
+    /// it's unusual, or refuse it.
+    fn open_url_checked(&mut self, url: String) {
+        match crate::link::policy::verdict(&url, &self.app_config.links) {
+            UrlVerdict::Open => self.open_with_activation(Box::new(move |token| open_url(&url, token))),
+            UrlVerdict::Ask => {
+                let pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                self.url_prompt = Some(UrlPrompt { pane, url });
+                if let Some(window) = &self.window {
+                    window.request_redraw();
                 }
             }
+            UrlVerdict::Block => log::warn!("Not opening blocked link: {url}"),
+        }
+    }
 
-            // Update tab title for focused pane of active tab:
-            // Priority: explicit title (OSC 0/2) > process name > CWD basename > "Shell"
-            if pane_id == focused {
-                let active_idx = self.tab_manager.active_index();
-
-                if shell.title_is_explicit {
-                    // Explicit title from OSC 0/2 — highest priority
-                    if let Some(title) = shell.title.clone() {
-                        self.tab_manager.set_title(active_idx, &title);
-                    }
-                } else {
-                    // Try foreground process name (throttled to 1Hz)
-                    let now = std::time::Instant::now();
-                    if now.duration_since(self.last_process_check).as_millis() >= 1000 {
-                        self.last_process_check = now;
-                        self.last_process_name = state.pty.child_pid().and_then(|pid| {
-                            crate::pty::foreground_process_name(pid)
-                        });
-                    }
-                    let process_title = self.last_process_name.clone();
-
-                    if let Some(ref proc_name) = process_title {
-                        if !crate::pty::is_shell_process(proc_name) {
-                            // Non-shell process (e.g., "vim", "claude") — use it
-                            self.tab_manager.set_title(active_idx, proc_name);
-                        } else if let Some(cwd) = shell.cwd.clone() {
-                            // Shell process — fall back to CWD basename
-                            let dir_name =
-                                crate::shell_integration::dir_name_from_path(&cwd);
-                            self.tab_manager.set_title(active_idx, dir_name);
-                        }
-                    } else if shell.cwd_changed {
-                        // No process detected — use CWD if changed
-                        if let Some(cwd) = shell.cwd.clone() {
-                            let dir_name =
-                                crate::shell_integration::dir_name_from_path(&cwd);
-                            self.tab_manager.set_title(active_idx, dir_name);
-                        }
-                    }
-                    if shell.cwd_changed {
-                        shell.cwd_changed = false;
-                    }
+    /// Answer the URL prompt in the focused pane: Enter opens, Escape
+    /// cancels. Returns true if the key was consumed.
+    fn answer_url_prompt(&mut self, key: &Key) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if !self.url_prompt.as_ref().is_some_and(|prompt| prompt.pane == focused) {
+            return false;
+        }
+        match key {
+            Key::Named(NamedKey::Enter) => {
+                if let Some(prompt) = self.url_prompt.take() {
+                    self.open_with_activation(Box::new(move |token| open_url(&prompt.url, token)));
                 }
             }
+            Key::Named(NamedKey::Escape) => self.url_prompt = None,
+            _ => return false,
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
         }
+        true
     }
 
-    /// Handle a shell integration command (prompt navigation).
-    /// Compute a new font size from the current size using ~10% steps, clamped to [8, 72].
-    fn compute_font_size(current: f32, command: AppCommand, default: f32) -> f32 {
-        const MIN_FONT: f32 = 8.0;
-        const MAX_FONT: f32 = 72.0;
-        let raw = match command {
-            AppCommand::IncreaseFontSize => (current * 1.1).round(),
-            AppCommand::DecreaseFontSize => (current / 1.1).round(),
-            AppCommand::ResetFontSize => default,
-            AppCommand::NewWindow | AppCommand::ClearScrollback => return current, // handled before compute
-        };
-        raw.clamp(MIN_FONT, MAX_FONT)
+    /// Width the minimap column takes from each pane's text area, in
+    /// physical pixels (0 when hidden).
+    fn minimap_reserved_width(&self) -> f32 {
+        if !self.minimap_visible {
+            return 0.0;
+        }
+        let scale = self.window.as_ref().map(|w| w.scale_factor() as f32).unwrap_or(1.0);
+        self.app_config.minimap.width as f32 * scale + crate::renderer::minimap::SCROLLBAR_GAP
     }
 
-    fn handle_app_command(&mut self, command: AppCommand) {
-        if command == AppCommand::NewWindow {
-            self.spawn_new_window();
+    /// Re-rasterize the minimaps of the active tab's panes whose content
+    /// changed since their image was built.
+    fn update_minimaps(&mut self) {
+        if !self.minimap_visible {
             return;
         }
-
-        if command == AppCommand::ClearScrollback {
-            let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-            if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                state.terminal.clear_scrollback();
-                log::info!("Cleared scrollback for pane {focused_id:?}");
-            }
-            if let Some(window) = &self.window {
-                window.request_redraw();
-            }
+        let Some(renderer) = &self.renderer else {
             return;
+        };
+        let theme = *renderer.theme();
+        let default_bg = crate::terminal::grid_bridge::default_bg(&theme);
+        let now = std::time::Instant::now();
+        for pane_id in self.tab_manager.active_tab().pane_tree.visible_panes() {
+            let Some(state) = self.pane_states.get_mut(&pane_id) else {
+                continue;
+            };
+            if !state.minimap.needs_rebuild(now) {
+                continue;
+            }
+            let terminal = &state.terminal;
+            let image = crate::renderer::minimap::rasterize(
+                terminal.history_size() + terminal.rows(),
+                terminal.columns(),
+                default_bg,
+                |line| crate::terminal::grid_bridge::buffer_line_cells(terminal, &theme, line),
+            );
+            state.minimap.store(image, now);
         }
+    }
 
-        let new_size = Self::compute_font_size(
-            self.current_font_size,
-            command,
-            self.default_font_size,
-        );
-        if (new_size - self.current_font_size).abs() < 0.5 {
-            return; // No effective change
+    /// Rebuild every pane's minimap (e.g. after a theme change).
+    fn invalidate_minimaps(&mut self) {
+        for state in self.pane_states.values_mut() {
+            state.minimap.mark_dirty();
         }
-        self.current_font_size = new_size;
-        log::info!("Font size changed to {new_size}px");
+    }
 
-        // Rebuild atlas and recalculate all pane dimensions
-        let font_family = self.app_config.font.family.clone();
-        let line_height = self.app_config.font.line_height as f32;
+    /// Jump to the line under a click on the focused pane's minimap.
+    /// Returns true if the click hit the minimap.
+    fn minimap_click(&mut self, x: f32, y: f32) -> bool {
+        if !self.minimap_visible {
+            return false;
+        }
+        let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
+        let scale = self.window.as_ref().map(|w| w.scale_factor() as f32).unwrap_or(1.0);
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some(rect) = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r) else {
+            return false;
+        };
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        let history_size = state.terminal.history_size();
+        let rows = state.terminal.rows();
+        let Some(minimap) = crate::renderer::minimap::minimap_layout(
+            rect.x + pgrid.x,
+            rect.y + pgrid.y,
+            rect.width - Self::PANE_GRID_PADDING * 2.0,
+            rect.height - Self::PANE_GRID_PADDING * 2.0,
+            padding,
+            self.app_config.minimap.width as f32 * scale,
+            self.app_config.minimap.line_height as f32 * scale,
+            history_size,
+            rows,
+            state.scroll_state.current_line_offset(),
+        ) else {
+            return false;
+        };
+        let Some(offset) = crate::renderer::minimap::offset_at(&minimap, x, y, history_size, rows) else {
+            return false;
+        };
+        state.scroll_state.set_target(offset, history_size);
+        state.terminal.set_display_offset(offset);
         if let Some(renderer) = &mut self.renderer {
-            renderer.rebuild_atlas(new_size, &font_family, line_height);
+            renderer.pane_damage_mut().force_full_damage_all();
         }
-        let (w, h) = self.window_size();
-        self.resize_all_panes(w, h);
-        if let Some(window) = &self.window {
-            window.request_redraw();
+        true
+    }
+
+    /// Apply the `[clipboard]` policy to an OSC 52 request from a pane:
+    /// carry it out, drop it, or hold it until the user answers the prompt.
+    /// A request that would prompt while another is waiting is denied.
+    fn handle_clipboard_request(
+        state: &mut PaneState,
+        request: ClipboardRequest,
+        config: &crate::config::types::ClipboardConfig,
+    ) {
+        if !request.within_limit(config.osc52_max_bytes) {
+            log::warn!("OSC 52: ignoring {request:?} over the {} byte limit", config.osc52_max_bytes);
+            return;
+        }
+        let write = ClipboardPolicy::from_config_str(&config.osc52_write).unwrap_or_default();
+        let read = ClipboardPolicy::from_config_str(&config.osc52_read).unwrap_or(ClipboardPolicy::Ask);
+        match request.policy(write, read) {
+            ClipboardPolicy::Allow => Self::perform_clipboard_request(state, request),
+            ClipboardPolicy::Ask if state.clipboard_prompt.is_some() => {
+                log::info!("OSC 52: denied {request:?}, another request awaits an answer");
+            }
+            ClipboardPolicy::Ask => state.clipboard_prompt = Some(request),
+            ClipboardPolicy::Deny => log::info!("OSC 52: denied {request:?}"),
         }
     }
 
-    /// Spawn a new VeloTerm window by launching a new process.
-    fn spawn_new_window(&self) {
-        match std::env::current_exe() {
-            Ok(exe_path) => {
-                match std::process::Command::new(&exe_path).spawn() {
-                    Ok(_) => log::info!("Spawned new VeloTerm window"),
-                    Err(e) => log::error!("Failed to spawn new window: {e}"),
+    /// Write to or read from the system clipboard for an OSC 52 request.
+    fn perform_clipboard_request(state: &mut PaneState, request: ClipboardRequest) {
+        match request {
+            ClipboardRequest::Store { target, text } => {
+                if let Err(e) = target.write(&text) {
+                    log::warn!("OSC 52: clipboard write failed: {e}");
+                }
+            }
+            ClipboardRequest::Load { target, reply } => {
+                let text = target.read().unwrap_or_else(|e| {
+                    log::warn!("OSC 52: clipboard read failed: {e}");
+                    String::new()
+                });
+                if let Err(e) = state.pty.write(reply(&text).as_bytes()) {
+                    log::warn!("PTY write error: {e}");
                 }
             }
-            Err(e) => log::error!("Failed to get current exe path: {e}"),
         }
     }
 
-    /// Get the CWD from the active pane's shell state, falling back to
-    /// VELOTERM_PROJECT_DIR, then process CWD, then HOME.
-    fn active_pane_cwd(&self) -> std::path::PathBuf {
+    /// Answer the focused pane's pending clipboard prompt: Enter allows,
+    /// Escape denies. Returns true if the key was consumed.
+    fn answer_clipboard_prompt(&mut self, key: &Key) -> bool {
         let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-        if let Some(state) = self.pane_states.get(&focused) {
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        if state.clipboard_prompt.is_none() {
+            return false;
+        }
+        match key {
+            Key::Named(NamedKey::Enter) => {
+                if let Some(request) = state.clipboard_prompt.take() {
+                    Self::perform_clipboard_request(state, request);
+                }
+            }
+            Key::Named(NamedKey::Escape) => {
+                if let Some(request) = state.clipboard_prompt.take() {
+                    log::info!("OSC 52: denied {request:?}");
+                }
+            }
+            _ => return false,
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+        true
+    }
+
+    /// Answer the focused pane's restart prompt: Enter restarts the shell
+    /// on its new preset, Escape keeps it running. Returns true if the key
+    /// was consumed.
+    fn answer_restart_prompt(&mut self, key: &Key) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        if state.restart_prompt.is_none() {
+            return false;
+        }
+        match key {
+            Key::Named(NamedKey::Enter) => self.restart_pane(focused),
+            Key::Named(NamedKey::Escape) => state.restart_prompt = None,
+            _ => return false,
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+        true
+    }
+
+    /// Answer the focused pane's output flood banner: Ctrl+S pauses or
+    /// resumes output, Ctrl+C interrupts the program and drops the output
+    /// already queued, Escape hides the banner. Returns true if the key was
+    /// consumed.
+    fn answer_flood_banner(&mut self, key: &Key) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let ctrl = self.modifiers.control_key();
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        if !state.flood.banner_visible() {
+            return false;
+        }
+        match key {
+            Key::Character(c) if ctrl && c.eq_ignore_ascii_case("s") => state.flood.toggle_pause(),
+            Key::Character(c) if ctrl && c.eq_ignore_ascii_case("c") => {
+                if let Err(e) = state.pty.write(b"\x03") {
+                    log::warn!("PTY write error: {e}");
+                }
+                // Output read before the interrupt is skipped, not parsed
+                for _ in 0..state.pty.reader_rx.len() {
+                    if state.pty.reader_rx.try_recv().is_err() {
+                        break;
+                    }
+                }
+                state.flood.reset();
+            }
+            Key::Named(NamedKey::Escape) => state.flood.hide(),
+            _ => return false,
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+        true
+    }
+
+    /// Paste the clipboard into the focused pane, typed slowly or at once.
+    /// With `clipboard.confirm_multiline_paste`, text spanning lines waits
+    /// for the paste prompt.
+    fn paste_clipboard(&mut self, slowly: bool) {
+        let Some(mut text) = arboard::Clipboard::new().ok().and_then(|mut clipboard| clipboard.get_text().ok()) else {
+            return;
+        };
+        if self.app_config.security.sanitize_paste {
+            if let std::borrow::Cow::Owned(clean) = crate::terminal::sanitize::sanitize_paste(&text) {
+                log::info!("Removed escape sequences and control characters from pasted text");
+                text = clean;
+            }
+        }
+        let pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if slowly {
+            self.start_slow_paste(pane, &text);
+        } else if self.app_config.clipboard.confirm_multiline_paste && text.trim_end().contains('\n') {
+            self.paste_prompt = Some(PastePrompt { pane, text });
+        } else {
+            self.paste_text(pane, &text);
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Paste `text` into a pane at once, bracketed if the program asked.
+    fn paste_text(&mut self, pane_id: PaneId, text: &str) {
+        let mirrors_input = self.mirrors_input();
+        if let Some(state) = self.pane_states.get_mut(&pane_id) {
+            let bracketed = state.terminal.is_bracketed_paste_enabled();
+            let bytes = crate::input::clipboard::paste_bytes(text, bracketed);
+            if let Err(e) = state.pty.write(&bytes) {
+                log::warn!("PTY paste write error: {e}");
+            }
+            if mirrors_input {
+                state.suggestions.on_input(&bytes);
+            }
+        }
+    }
+
+    /// Type `text` into a pane a chunk at a time, replacing any slow paste
+    /// still running. The chunks arrive as `UserEvent::PasteChunk`.
+    fn start_slow_paste(&mut self, pane_id: PaneId, text: &str) {
+        let Some(proxy) = self.event_proxy.clone() else {
+            return;
+        };
+        let config = &self.app_config.clipboard;
+        let delay = std::time::Duration::from_millis(config.slow_paste_delay_ms);
+        self.slow_paste = Some(SlowPaste::start(pane_id, text, config.slow_paste_chunk_size, delay, move |bytes, last| {
+            proxy.send_event(UserEvent::PasteChunk(pane_id, bytes, last)).is_ok()
+        }));
+    }
+
+    /// Write a slow paste chunk unless the paste was stopped or replaced.
+    fn write_paste_chunk(&mut self, pane_id: PaneId, bytes: &[u8], last: bool) {
+        if self.slow_paste.as_ref().map(SlowPaste::pane) != Some(pane_id) {
+            return;
+        }
+        let mirrors_input = self.mirrors_input();
+        match self.pane_states.get_mut(&pane_id) {
+            Some(state) => {
+                if let Err(e) = state.pty.write(bytes) {
+                    log::warn!("PTY paste write error: {e}");
+                    self.slow_paste = None;
+                } else if mirrors_input {
+                    state.suggestions.on_input(bytes);
+                }
+            }
+            None => self.slow_paste = None,
+        }
+        if last {
+            self.slow_paste = None;
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Answer the paste prompt in the focused pane (Enter pastes, S types
+    /// slowly, Escape cancels) or stop its slow paste with Escape. Returns
+    /// true if the key was consumed.
+    fn answer_paste_prompt(&mut self, key: &Key) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if self.paste_prompt.as_ref().is_some_and(|prompt| prompt.pane == focused) {
+            match key {
+                Key::Named(NamedKey::Enter) => {
+                    if let Some(prompt) = self.paste_prompt.take() {
+                        self.paste_text(prompt.pane, &prompt.text);
+                    }
+                }
+                Key::Character(c) if c.eq_ignore_ascii_case("s") => {
+                    if let Some(prompt) = self.paste_prompt.take() {
+                        self.start_slow_paste(prompt.pane, &prompt.text);
+                    }
+                }
+                Key::Named(NamedKey::Escape) => self.paste_prompt = None,
+                _ => return false,
+            }
+        } else if self.slow_paste.as_ref().is_some_and(|paste| paste.pane() == focused)
+            && matches!(key, Key::Named(NamedKey::Escape))
+        {
+            self.slow_paste = None;
+        } else {
+            return false;
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+        true
+    }
+
+    /// Send keys or committed IME text typed into `pane_id`, also to the
+    /// rest of its group while the group broadcasts.
+    fn send_keyboard_input(&mut self, pane_id: PaneId, bytes: &[u8]) {
+        self.send_typed_input(pane_id, bytes);
+        for target in self.pane_groups.broadcast_targets(pane_id) {
+            self.send_typed_input(target, bytes);
+        }
+    }
+
+    /// Write typed input (keys or committed IME text) to a pane's PTY and
+    /// return it to the live view.
+    fn send_typed_input(&mut self, pane_id: PaneId, bytes: &[u8]) {
+        let mirrors_input = self.mirrors_input();
+        let Some(state) = self.pane_states.get_mut(&pane_id) else {
+            return;
+        };
+        if let Err(e) = state.pty.write(bytes) {
+            log::warn!("PTY write error: {e}");
+        }
+        if mirrors_input {
+            state.suggestions.on_input(bytes);
+        }
+        state.command_hint = None;
+        state.watchdog.on_input(std::time::Instant::now());
+        state.cursor.on_keystroke();
+        state.mouse_selection.clear_selection();
+        // Snap scroll to bottom on keyboard input (return to live view)
+        state.scroll_state.snap_to_bottom();
+        state.terminal.set_display_offset(0);
+    }
+
+    /// Tell the input method where the focused pane's cursor is, so an
+    /// ibus/fcitx candidate window opens next to it. Only sent on change.
+    fn update_ime_cursor_area(&mut self) {
+        let (Some(window), Some(renderer)) = (&self.window, &self.renderer) else {
+            return;
+        };
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get(&focused) else {
+            return;
+        };
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some(rect) = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r) else {
+            return;
+        };
+        let (line, col) = state.terminal.cursor_position();
+        let row = line + state.terminal.display_offset();
+        if row >= state.terminal.rows() {
+            return;
+        }
+        let padding = renderer.padding();
+        let origin = (
+            pgrid.x + rect.x + padding[2],
+            pgrid.y + rect.y + PANE_HEADER_HEIGHT + padding[0],
+        );
+        let area = crate::input::ime::ImeCursorArea::for_cell(
+            origin,
+            renderer.cell_width(),
+            renderer.cell_height(),
+            row,
+            col,
+        );
+        if self.ime.update(area) {
+            window.set_ime_cursor_area(
+                winit::dpi::PhysicalPosition::new(area.x, area.y),
+                PhysicalSize::new(area.width, area.height),
+            );
+        }
+    }
+
+    fn global_color_filter(&self) -> crate::renderer::color_filter::ColorFilter {
+        crate::renderer::color_filter::ColorFilter::from_config_str(&self.app_config.colors.filter)
+            .unwrap_or_default()
+    }
+
+    /// The pane's filter: one picked with `cycle_color_filter`, else its
+    /// profile's, else `colors.filter`.
+    fn pane_color_filter(&self, state: &PaneState) -> crate::renderer::color_filter::ColorFilter {
+        state
+            .color_filter
+            .or_else(|| {
+                let profile = crate::profiles::find(&self.app_config.profiles, state.profile.as_deref()?)?;
+                crate::profiles::color_filter(profile)
+            })
+            .unwrap_or_else(|| self.global_color_filter())
+    }
+
+    /// Handle a pane command (split, close, focus, zoom).
+    /// Process shell state updates after PTY drain: notifications and CWD tab titles.
+    fn process_shell_updates(&mut self) {
+        let focused = self
+            .tab_manager
+            .active_tab()
+            .pane_tree
+            .focused_pane_id();
+        let threshold = self.app_config.shell.notification_threshold_secs;
+        let shell_enabled = self.app_config.shell.integration_enabled;
+        let mirrors_input = self.mirrors_input();
+        let git_enabled = self.app_config.git_status.enabled;
+        let mut focused_cwd = None;
+        let now = std::time::Instant::now();
+        let poll_processes = now.duration_since(self.last_process_check).as_millis() >= 1000;
+        if poll_processes {
+            self.last_process_check = now;
+        }
+
+        // Collect pane IDs to avoid borrow conflict
+        let pane_ids: Vec<_> = self.pane_states.keys().copied().collect();
+
+        for pane_id in pane_ids {
+            let state = self.pane_states.get_mut(&pane_id).unwrap();
+            let shell = state.terminal.shell_state_mut();
+
+            // Keep the input mirror aligned with the current prompt
+            if mirrors_input {
+                let prompt = if shell.is_command_running() {
+                    None
+                } else {
+                    shell.last_prompt_line()
+                };
+                state.suggestions.sync_prompt(prompt);
+            }
+
+            // Check for completed commands — notification for non-focused panes
+            if shell_enabled {
+                if let Some(duration) = shell.pending_completion.take() {
+                    // The command may have touched the repository
+                    if git_enabled {
+                        if let Some(cwd) = shell.cwd.as_deref() {
+                            self.git_segments.invalidate(cwd);
+                        }
+                    }
+                    if pane_id != focused && duration.as_secs() >= threshold {
+                        if let Some(tab_idx) = self.tab_manager.tab_index_for_pane(pane_id) {
+                            self.tab_manager.set_notification(tab_idx, true);
+                        }
+                    }
+                    if self.scripts.as_ref().is_some_and(|s| s.has_hooks(ScriptEvent::CommandFinished)) {
+                        let exit = shell.last_command().and_then(|c| c.exit_status);
+                        let mut data = rhai::Map::new();
+                        data.insert("pane".into(), (pane_id.0 as rhai::INT).into());
+                        data.insert("exit_code".into(), exit.map_or(rhai::Dynamic::UNIT, |code| (code as rhai::INT).into()));
+                        data.insert("duration_ms".into(), (duration.as_millis() as rhai::INT).into());
+                        data.insert("command".into(), state.suggestions.last_submitted().unwrap_or_default().into());
+                        self.script_events.push((ScriptEvent::CommandFinished, data));
+                    }
+                    // Command not found: offer the closest executable on PATH
+                    if self.app_config.command_hints.enabled {
+                        let exit = shell.last_command().and_then(|c| c.exit_status);
+                        state.command_hint = if exit == Some(crate::command_hint::EXIT_COMMAND_NOT_FOUND) {
+                            state.suggestions.last_submitted().and_then(|line| {
+                                crate::command_hint::CommandHint::for_command_line(
+                                    line,
+                                    crate::command_hint::path_index(),
+                                )
+                            })
+                        } else {
+                            None
+                        };
+                    }
+                }
+            }
+
+            // Directory badge follows the cwd reported via OSC 7
+            if shell.cwd_changed {
+                let rules = &self.app_config.directory_rules;
+                state.badge = shell.cwd.as_deref().and_then(|cwd| {
+                    crate::directory_rules::badge_for_cwd(rules, cwd)
+                });
+                let directory_theme = shell.cwd.as_deref().and_then(|cwd| {
+                    crate::directory_rules::theme_for_cwd(rules, cwd, &self.app_config.colors)
+                });
+                if directory_theme.is_some() || state.directory_theme.is_some() {
+                    state.directory_theme = directory_theme;
+                    state.minimap.mark_dirty();
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+                if git_enabled {
+                    if let Some(cwd) = shell.cwd.as_deref() {
+                        self.git_segments.invalidate(cwd);
+                    }
+                }
+            }
+            if pane_id == focused {
+                focused_cwd = shell.cwd.clone();
+            }
+
+            // Each pane keeps its own title: explicit title (OSC 0/2) >
+            // process name > running command > CWD basename. Foreground
+            // process names are polled about once a second.
+            if poll_processes && !shell.title_is_explicit {
+                state.process_name = state.pty.child_pid().and_then(crate::pty::foreground_process_name);
+            }
+            let running_command = shell
+                .is_command_running()
+                .then(|| state.suggestions.last_submitted())
+                .flatten();
+            state.title = crate::tab::auto_title(
+                shell.title.as_deref().filter(|_| shell.title_is_explicit),
+                state.process_name.as_deref(),
+                running_command,
+                shell.cwd.as_deref(),
+            );
+            shell.cwd_changed = false;
+        }
+
+        // Tabs show their focused pane's title; renamed tabs keep theirs
+        for index in 0..self.tab_manager.tab_count() {
+            let pane_id = self.tab_manager.tabs()[index].pane_tree.focused_pane_id();
+            if let Some(state) = self.pane_states.get(&pane_id) {
+                if let Some(title) = &state.title {
+                    let title = crate::directory_rules::badged_title(state.badge.as_ref(), title);
+                    self.tab_manager.set_title(index, &title);
+                }
+            }
+        }
+        self.update_window_title();
+
+        if let Some(cwd) = focused_cwd.filter(|_| git_enabled) {
+            self.refresh_git_segment(cwd);
+        }
+    }
+
+    /// Title the window from `window.title` with the active tab's custom
+    /// title or its focused pane's title. `--title` keeps the window's.
+    fn update_window_title(&mut self) {
+        if self.launch_title.is_some() || self.app_config.window.title.is_empty() {
+            return;
+        }
+        let tab = self.tab_manager.active_tab();
+        let pane_title = self
+            .pane_states
+            .get(&tab.pane_tree.focused_pane_id())
+            .and_then(|state| state.title.as_deref());
+        let title = if tab.title_is_manual { Some(tab.title.as_str()) } else { pane_title };
+        let title = crate::tab::window_title(&self.app_config.window.title, title.unwrap_or(DEFAULT_TITLE));
+        if title != self.config.title {
+            if let Some(window) = &self.window {
+                window.set_title(&title);
+            }
+            self.config.title = title;
+        }
+    }
+
+    /// Re-read git status for `cwd` on a worker thread if the cached entry is
+    /// due. The result arrives as `UserEvent::GitSegmentReady`.
+    fn refresh_git_segment(&mut self, cwd: String) {
+        let Some(proxy) = self.event_proxy.clone() else {
+            return;
+        };
+        if !self.git_segments.begin_refresh(&cwd, std::time::Instant::now()) {
+            return;
+        }
+        std::thread::spawn(move || {
+            let segment = crate::git_segment::GitSegment::read(std::path::Path::new(&cwd));
+            let _ = proxy.send_event(UserEvent::GitSegmentReady(cwd, segment));
+        });
+    }
+
+    /// Handle a shell integration command (prompt navigation).
+    const MIN_FONT_SIZE: f32 = 8.0;
+    const MAX_FONT_SIZE: f32 = 72.0;
+
+    /// Compute a new font size from the current size using ~10% steps, clamped to [8, 72].
+    fn compute_font_size(current: f32, command: AppCommand, default: f32) -> f32 {
+        let raw = match command {
+            AppCommand::IncreaseFontSize => (current * 1.1).round(),
+            AppCommand::DecreaseFontSize => (current / 1.1).round(),
+            AppCommand::ResetFontSize => default,
+            // Handled before compute
+            AppCommand::NewWindow
+            | AppCommand::ClearScrollback
+            | AppCommand::ToggleCheatsheet
+            | AppCommand::ShowHelp
+            | AppCommand::Quit
+            | AppCommand::ToggleSecureKeyboardEntry => {
+                return current;
+            }
+        };
+        raw.clamp(Self::MIN_FONT_SIZE, Self::MAX_FONT_SIZE)
+    }
+
+    /// Apply one pinch delta to the live zoom scale, keeping the size it
+    /// would snap to within the font size limits.
+    fn step_pinch_scale(scale: f32, delta: f64, current: f32) -> f32 {
+        let next = scale * (1.0 + delta as f32);
+        next.clamp(Self::MIN_FONT_SIZE / current, Self::MAX_FONT_SIZE / current)
+    }
+
+    /// Discrete font size a finished pinch snaps to.
+    fn pinch_font_size(current: f32, scale: f32) -> f32 {
+        (current * scale).round().clamp(Self::MIN_FONT_SIZE, Self::MAX_FONT_SIZE)
+    }
+
+    /// Handle a touchpad pinch: magnify the focused pane live (no reflow),
+    /// then snap to a font size and resize the PTYs when the gesture ends.
+    fn handle_pinch_gesture(&mut self, delta: f64, phase: winit::event::TouchPhase) {
+        use winit::event::TouchPhase;
+        match phase {
+            TouchPhase::Started | TouchPhase::Moved => {
+                let scale = self.pinch_scale.unwrap_or(1.0);
+                self.pinch_scale =
+                    Some(Self::step_pinch_scale(scale, delta, self.current_font_size));
+            }
+            TouchPhase::Ended => {
+                if let Some(scale) = self.pinch_scale.take() {
+                    let size = Self::pinch_font_size(self.current_font_size, scale);
+                    self.set_font_size(size);
+                }
+            }
+            TouchPhase::Cancelled => {
+                self.pinch_scale = None;
+            }
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    fn handle_app_command(&mut self, command: AppCommand) {
+        if command == AppCommand::NewWindow {
+            self.spawn_new_window();
+            return;
+        }
+
+        if command == AppCommand::ToggleCheatsheet {
+            self.cheatsheet_open = !self.cheatsheet_open;
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+            return;
+        }
+
+        if command == AppCommand::ShowHelp {
+            self.open_help();
+            return;
+        }
+
+        if command == AppCommand::ToggleSecureKeyboardEntry {
+            if cfg!(target_os = "macos") {
+                self.secure_keyboard_entry = !self.secure_keyboard_entry;
+                let focused = self.window.as_ref().is_some_and(|w| w.has_focus());
+                self.apply_secure_keyboard_entry(focused);
+            } else {
+                log::info!("Secure keyboard entry is only available on macOS");
+            }
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+            return;
+        }
+
+        if command == AppCommand::ClearScrollback {
+            let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+            if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                state.terminal.clear_scrollback();
+                state.minimap.mark_dirty();
+                state.notes.clear();
+                state.bookmarks.clear();
+                log::info!("Cleared scrollback for pane {focused_id:?}");
+            }
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+            return;
+        }
+
+        let new_size = Self::compute_font_size(
+            self.current_font_size,
+            command,
+            self.default_font_size,
+        );
+        self.set_font_size(new_size);
+    }
+
+    /// Carry out a recognized touch gesture by replaying it as the mouse
+    /// events it stands for, so taps, long-presses and drags share the mouse
+    /// code paths (iced widgets included).
+    fn handle_touch_action(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        action: crate::input::touch::TouchAction,
+    ) {
+        use crate::input::touch::TouchAction;
+        let Some(device_id) = self.touch_device else {
+            return;
+        };
+        let moved = |x: f32, y: f32| WindowEvent::CursorMoved {
+            device_id,
+            position: winit::dpi::PhysicalPosition::new(x as f64, y as f64),
+        };
+        let button = |state: ElementState, button: MouseButton| WindowEvent::MouseInput {
+            device_id,
+            state,
+            button,
+        };
+        let events = match action {
+            TouchAction::Tap { x, y } => vec![
+                moved(x, y),
+                button(ElementState::Pressed, MouseButton::Left),
+                button(ElementState::Released, MouseButton::Left),
+            ],
+            TouchAction::LongPress { x, y } => {
+                vec![moved(x, y), button(ElementState::Pressed, MouseButton::Right)]
+            }
+            TouchAction::DragStart { x, y } => {
+                if self.grab_selection_handle(x, y) {
+                    vec![moved(x, y)]
+                } else {
+                    vec![moved(x, y), button(ElementState::Pressed, MouseButton::Left)]
+                }
+            }
+            TouchAction::DragMove { x, y } => vec![moved(x, y)],
+            TouchAction::DragEnd { x, y } => {
+                vec![moved(x, y), button(ElementState::Released, MouseButton::Left)]
+            }
+            TouchAction::Scroll { dy } => vec![WindowEvent::MouseWheel {
+                device_id,
+                delta: winit::event::MouseScrollDelta::PixelDelta(
+                    winit::dpi::PhysicalPosition::new(0.0, dy as f64),
+                ),
+                phase: winit::event::TouchPhase::Moved,
+            }],
+        };
+        self.touch_dispatching = true;
+        for event in events {
+            self.window_event(event_loop, window_id, event);
+        }
+        self.touch_dispatching = false;
+    }
+
+    /// If a touch drag starts on one of the focused pane's selection handles,
+    /// start moving that end of the selection.
+    fn grab_selection_handle(&mut self, x: f32, y: f32) -> bool {
+        let Some(renderer) = &self.renderer else {
+            return false;
+        };
+        let (cell_width, cell_height) = (renderer.cell_width(), renderer.cell_height());
+        let padding = renderer.padding();
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let (win_w, win_h) = self.window_size();
+        let pgrid = self.pane_grid_bounds(win_w as f32, win_h as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some(rect) = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r) else {
+            return false;
+        };
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        let Some(sel) = state.mouse_selection.active_selection.as_ref() else {
+            return false;
+        };
+        let local_x = x - pgrid.x - rect.x - padding[2];
+        let local_y = y - pgrid.y - rect.y - PANE_HEADER_HEIGHT - padding[0];
+        let centers = crate::input::touch::handle_centers(
+            sel,
+            cell_width,
+            cell_height,
+            state.terminal.display_offset(),
+        );
+        match crate::input::touch::hit_handle(&centers, (local_x, local_y)) {
+            Some(handle) => state.mouse_selection.begin_handle_drag(handle),
+            None => false,
+        }
+    }
+
+    /// Switch the terminal font size: rebuild the atlas and resize every pane.
+    fn set_font_size(&mut self, new_size: f32) {
+        if (new_size - self.current_font_size).abs() < 0.5 {
+            return; // No effective change
+        }
+        self.current_font_size = new_size;
+        log::info!("Font size changed to {new_size}px");
+        self.rebuild_font_atlas();
+    }
+
+    /// Scale factor to rasterize the font at in place of the detected one:
+    /// the runtime override, else `font.scale_factor` unless it is 0.
+    fn font_scale(&self) -> Option<f32> {
+        resolve_font_scale(self.font_scale_override, self.app_config.font.scale_factor)
+    }
+
+    /// `veloterm ctl set-font-scale`: rasterize the font at `scale`, or
+    /// back at `font.scale_factor` for None.
+    fn set_font_scale_override(&mut self, scale: Option<f64>) -> Result<(), String> {
+        if let Some(scale) = scale.filter(|&s| !crate::config::types::is_valid_font_scale(s)) {
+            return Err(format!(
+                "font scale {scale} is outside {}-{}",
+                crate::config::types::MIN_FONT_SCALE,
+                crate::config::types::MAX_FONT_SCALE
+            ));
+        }
+        self.font_scale_override = scale;
+        log::info!("Font scale set to {:?}", self.font_scale());
+        self.rebuild_font_atlas();
+        Ok(())
+    }
+
+    /// Rebuild the atlas for the current font size and scale, and
+    /// recalculate all pane dimensions.
+    fn rebuild_font_atlas(&mut self) {
+        let font_scale = self.font_scale();
+        let font = &self.app_config.font;
+        let subpixel = Subpixel::from_config_str(&font.subpixel_antialiasing).unwrap_or_default();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_font_scale(font_scale);
+            renderer.rebuild_atlas(
+                self.current_font_size,
+                &font.family,
+                font.line_height as f32,
+                font.ligatures,
+                subpixel,
+            );
+        }
+        let (w, h) = self.window_size();
+        self.resize_all_panes(w, h);
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Spawn a new VeloTerm window by launching a new process.
+    fn spawn_new_window(&self) {
+        match std::env::current_exe() {
+            Ok(exe_path) => {
+                match std::process::Command::new(&exe_path).spawn() {
+                    Ok(_) => log::info!("Spawned new VeloTerm window"),
+                    Err(e) => log::error!("Failed to spawn new window: {e}"),
+                }
+            }
+            Err(e) => log::error!("Failed to get current exe path: {e}"),
+        }
+    }
+
+    /// Get the CWD from the active pane's shell state, falling back to
+    /// VELOTERM_PROJECT_DIR, then process CWD, then HOME.
+    fn active_pane_cwd(&self) -> std::path::PathBuf {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if let Some(state) = self.pane_states.get(&focused) {
             if let Some(cwd) = state.terminal.shell_state().cwd.as_ref() {
                 return std::path::PathBuf::from(cwd);
             }
@@ -688,44 +2044,13 @@ impl App {
         use crate::context_menu::ContextMenuAction;
         match action {
             ContextMenuAction::Copy => {
+                self.copy_selection(self.default_copy_mode(), self.app_config.redaction.copy);
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                let theme = self.renderer.as_ref().map(|r| r.theme()).unwrap_or(&crate::config::theme::DARK);
                 if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                    if let Some(ref sel) = state.mouse_selection.active_selection {
-                        let cells = crate::terminal::grid_bridge::extract_grid_cells(&state.terminal, theme);
-                        let cols = state.terminal.columns();
-                        let display_offset = state.terminal.display_offset();
-                        let text = match sel.selection_type {
-                            crate::input::selection::SelectionType::VisualBlock => {
-                                crate::input::selection::selected_text_block(&cells, sel, cols, display_offset)
-                            }
-                            crate::input::selection::SelectionType::Line => {
-                                crate::input::selection::selected_text_lines(&cells, sel, cols, display_offset)
-                            }
-                            _ => crate::input::selection::selected_text(&cells, sel, cols, display_offset),
-                        };
-                        if !text.is_empty() {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                let _ = clipboard.set_text(&text);
-                            }
-                        }
-                        state.mouse_selection.clear_selection();
-                    }
-                }
-            }
-            ContextMenuAction::Paste => {
-                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                    if let Ok(text) = clipboard.get_text() {
-                        if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                            let bytes = crate::input::clipboard::paste_bytes(&text, true);
-                            if let Err(e) = state.pty.write(&bytes) {
-                                log::warn!("PTY paste write error: {e}");
-                            }
-                        }
-                    }
+                    state.mouse_selection.clear_selection();
                 }
             }
+            ContextMenuAction::Paste => self.paste_clipboard(false),
             ContextMenuAction::SelectAll => {
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
                 if let Some(state) = self.pane_states.get_mut(&focused_id) {
@@ -784,8 +2109,11 @@ impl App {
             self.current_font_size = new_size;
             self.default_font_size = new_size;
 
+            let subpixel = Subpixel::from_config_str(&new_config.font.subpixel_antialiasing).unwrap_or_default();
+            let font_scale = resolve_font_scale(self.font_scale_override, new_config.font.scale_factor);
             if let Some(renderer) = &mut self.renderer {
-                renderer.rebuild_atlas(new_size, &new_family, new_lh);
+                renderer.set_font_scale(font_scale);
+                renderer.rebuild_atlas(new_size, &new_family, new_lh, new_config.font.ligatures, subpixel);
             }
         }
 
@@ -804,7 +2132,20 @@ impl App {
 
         self.app_config = new_config;
 
-        if delta.font_changed || delta.padding_changed {
+        if delta.colors_changed {
+            self.theme_scheduler = crate::theme_schedule::ThemeScheduler::new(&self.app_config.colors.schedule);
+        }
+
+        if delta.redaction_changed {
+            self.redactor = crate::redact::Redactor::from_config(&self.app_config.redaction);
+        }
+
+        if delta.minimap_changed {
+            self.minimap_visible = self.app_config.minimap.enabled;
+            self.invalidate_minimaps();
+        }
+
+        if delta.font_changed || delta.padding_changed || delta.minimap_changed || delta.panes_changed {
             let (w, h) = self.window_size();
             self.resize_all_panes(w, h);
             if let Some(window) = &self.window {
@@ -814,11 +2155,12 @@ impl App {
 
         if delta.colors_changed {
             if let Some(renderer) = &mut self.renderer {
-                let theme = TerminalTheme::from_name(&self.app_config.colors.theme)
+                let theme = self.app_config.colors.resolve_theme(&self.app_config.colors.theme)
                     .unwrap_or_else(TerminalTheme::warm_dark);
                 renderer.set_theme(theme);
                 renderer.pane_damage_mut().force_full_damage_all();
             }
+            self.apply_background();
             // Update macOS title bar to match new theme
             if let Some(window) = &self.window {
                 let theme = self.renderer.as_ref().map(|r| r.theme()).unwrap_or(&crate::config::theme::DARK);
@@ -832,15 +2174,211 @@ impl App {
                 window.request_redraw();
             }
             self.signal_theme_change_to_shells();
+            self.invalidate_minimaps();
+        }
+
+        if delta.cursor_changed {
+            for state in self.pane_states.values_mut() {
+                Self::apply_cursor_config(&mut state.terminal, &mut state.cursor, &self.app_config.cursor);
+            }
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+
+        if delta.keys_changed {
+            self.keymap = Keymap::from_config(&self.app_config.keys.bindings);
+            #[cfg(target_os = "macos")]
+            self.install_menu_bar();
+        }
+
+        // Any reload may come from the script, so re-run it for its callbacks
+        self.load_scripts();
+
+        if delta.performance_changed {
+            let lines = self.app_config.performance.background_reflow_lines;
+            let flood_threshold = self.app_config.performance.flood_threshold_mb;
+            for state in self.pane_states.values_mut() {
+                state.terminal.set_background_reflow_lines(lines);
+                state.flood.set_threshold(flood_threshold);
+            }
+        }
+
+        if delta.git_status_changed {
+            self.git_segments.set_ttl(std::time::Duration::from_secs(
+                self.app_config.git_status.refresh_interval_secs,
+            ));
+            self.git_segments.clear();
+        }
+
+        if delta.renderer_changed {
+            // The GPU adapter is chosen at startup; the present mode can change live
+            self.apply_present_mode();
+        }
+
+        if delta.window_changed {
+            self.apply_window_opacity();
+            if let Some(renderer) = &mut self.renderer {
+                renderer.pane_damage_mut().force_full_damage_all();
+            }
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+
+        if delta.directory_rules_changed || delta.colors_changed {
+            let rules = &self.app_config.directory_rules;
+            for state in self.pane_states.values_mut() {
+                let cwd = state.terminal.shell_state().cwd.as_deref();
+                state.badge = cwd.and_then(|cwd| crate::directory_rules::badge_for_cwd(rules, cwd));
+                state.directory_theme =
+                    cwd.and_then(|cwd| crate::directory_rules::theme_for_cwd(rules, cwd, &self.app_config.colors));
+                state.minimap.mark_dirty();
+            }
+            if let Some(renderer) = &mut self.renderer {
+                renderer.pane_damage_mut().force_full_damage_all();
+            }
+        }
+
+        // Profile panes keep their profile's colors; re-resolve them when the
+        // profile or the palette overrides change. A removed profile's panes
+        // follow the window's theme.
+        if delta.profiles_changed || delta.colors_changed {
+            let profiles = &self.app_config.profiles;
+            for state in self.pane_states.values_mut() {
+                let Some(name) = &state.profile else {
+                    continue;
+                };
+                state.theme = crate::profiles::find(profiles, name)
+                    .and_then(|profile| crate::profiles::theme(profile, &self.app_config.colors));
+            }
+            if let Some(renderer) = &mut self.renderer {
+                renderer.pane_damage_mut().force_full_damage_all();
+            }
+        }
+    }
+
+    /// Configure the surface from `renderer.present_mode` / `renderer.low_latency`.
+    fn apply_present_mode(&mut self) {
+        let config = &self.app_config.renderer;
+        let requested = crate::renderer::gpu::parse_present_mode(&config.present_mode)
+            .unwrap_or(wgpu::PresentMode::Fifo);
+        if let Some(renderer) = &mut self.renderer {
+            let mode = renderer.set_present_mode(requested, config.low_latency);
+            log::info!("Present mode: {mode:?} (low latency: {})", config.low_latency);
+        }
+    }
+
+    /// Apply `window.opacity` and `window.blur` to the window and surface.
+    fn apply_window_opacity(&mut self) {
+        let config = &self.app_config.window;
+        if let Some(window) = &self.window {
+            window.set_transparent(config.opacity < 1.0);
+            window.set_blur(config.blur);
+        }
+        if let Some(renderer) = &mut self.renderer {
+            let mode = renderer.set_opacity(config.opacity as f32);
+            log::info!("Window opacity: {} (alpha mode: {mode:?}, blur: {})", config.opacity, config.blur);
+        }
+    }
+
+    /// Draw the current theme's `colors.background` picture, if it has one.
+    fn apply_background(&mut self) {
+        let colors = &self.app_config.colors;
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_background(colors.resolve_background(&colors.theme));
+        }
+    }
+
+    /// Filter a pane's output for its trust and compatibility preset from
+    /// now on, passing on what the old filter still held.
+    fn set_output_filter(state: &mut PaneState, trusted: bool, compat: Compat) {
+        if let Some(mut filter) = state.output_filter.take() {
+            state.terminal.feed(&filter.finish());
+        }
+        state.output_filter = crate::terminal::sanitize::OutputFilter::for_pane(trusted, compat);
+    }
+
+    /// Parse a pane's output, filtered if the pane is untrusted or legacy and
+    /// shown as codes with the control byte view on.
+    fn feed_output(state: &mut PaneState, bytes: &[u8]) {
+        let filtered;
+        let bytes = match &mut state.output_filter {
+            Some(filter) => {
+                filtered = filter.filter(bytes);
+                &filtered[..]
+            }
+            None => bytes,
+        };
+        match &mut state.control_bytes {
+            Some(view) => state.terminal.feed(&view.transform(bytes)),
+            None => state.terminal.feed(bytes),
+        }
+    }
+
+    /// Switch to, or fade a step further toward, the theme `[colors.schedule]`
+    /// sets for this time of day, when that changed.
+    fn poll_theme_schedule(&mut self) {
+        let Some(scheduler) = &mut self.theme_scheduler else {
+            return;
+        };
+        let time = chrono::Local::now().time();
+        let Some(scheduled) = scheduler.poll(std::time::Instant::now(), time) else {
+            return;
+        };
+        let Some(theme) = scheduled.theme(&self.app_config.colors) else {
+            return;
+        };
+        if let Some(r) = &mut self.renderer {
+            r.set_theme(theme);
+            r.pane_damage_mut().force_full_damage_all();
+        }
+        self.frame_pacer.mark_dirty();
+        self.invalidate_minimaps();
+        // Shells hear about whole switches, not each step of a fade
+        if self.app_config.colors.theme != scheduled.name() {
+            self.app_config.colors.theme = scheduled.name().to_string();
+            self.apply_background();
+            self.signal_theme_change_to_shells();
         }
+    }
 
-        if delta.cursor_changed {
-            for state in self.pane_states.values_mut() {
-                state.cursor.set_style(crate::renderer::cursor::CursorStyle::Block);
-                state.cursor.set_blink_rate(0);
+    /// How long low-latency mode waits for a key's echo before drawing.
+    const INPUT_ECHO_WAIT: std::time::Duration = std::time::Duration::from_millis(3);
+
+    /// Low-latency mode: give the focused shell a moment to echo the key just
+    /// sent, so the frame drawn right away already shows it.
+    fn await_input_echo(&mut self) {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if let Some(state) = self.pane_states.get_mut(&focused) {
+            if state.flood.budget().is_some() {
+                return;
             }
-            if let Some(window) = &self.window {
-                window.request_redraw();
+            if let Ok(bytes) = state.pty.reader_rx.recv_timeout(Self::INPUT_ECHO_WAIT) {
+                Self::feed_output(state, &bytes);
+                state.minimap.mark_dirty();
+            }
+        }
+    }
+
+    /// Copy the output of the pane's most recent command to the clipboard.
+    fn copy_last_output(&self, pane_id: PaneId) {
+        let Some(state) = self.pane_states.get(&pane_id) else {
+            return;
+        };
+        let terminal = &state.terminal;
+        let current_line = terminal.history_size() + terminal.cursor_position().0;
+        let Some((first, last)) = terminal.shell_state().last_output_range(current_line) else {
+            log::info!("No command output to copy (needs shell integration)");
+            return;
+        };
+        let mut text = crate::terminal::grid_bridge::buffer_text(terminal, first, last);
+        if self.app_config.redaction.copy {
+            text = self.redactor.redact(&text).into_owned();
+        }
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            if let Err(e) = clipboard.set_text(&text) {
+                log::warn!("Copy last output clipboard error: {e}");
             }
         }
     }
@@ -859,6 +2397,10 @@ impl App {
                 crate::input::ShellCommand::NextPrompt => {
                     state.terminal.jump_to_next_prompt()
                 }
+                crate::input::ShellCommand::CopyLastOutput => {
+                    self.copy_last_output(focused);
+                    return;
+                }
             };
             if moved {
                 if let Some(renderer) = &mut self.renderer {
@@ -880,24 +2422,13 @@ impl App {
         let pgrid = self.pane_grid_bounds(width as f32, height as f32);
 
         match command {
-            PaneCommand::SplitVertical | PaneCommand::SplitHorizontal => {
-                let direction = match command {
-                    PaneCommand::SplitVertical => SplitDirection::Vertical,
-                    _ => SplitDirection::Horizontal,
-                };
-                let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
-                if let Some(new_id) = pane_tree.split_focused(direction) {
-                    let layout = pane_tree.calculate_layout(pgrid.width, pgrid.height);
-                    if let Some((_, rect)) = layout.iter().find(|(id, _)| *id == new_id) {
-                        let (cols, rows) = self.grid_dims_for_rect(rect);
-                        self.spawn_pane(new_id, cols, rows);
-                    }
-                    self.resize_all_panes(width, height);
-                    self.update_interaction_layout(width, height);
-                    if let Some(renderer) = &mut self.renderer {
-                        renderer.pane_damage_mut().force_full_damage_all();
-                    }
-                }
+            PaneCommand::SplitVertical => self.split_focused_pane(SplitDirection::Vertical, None),
+            PaneCommand::SplitHorizontal => self.split_focused_pane(SplitDirection::Horizontal, None),
+            PaneCommand::SplitVerticalWithProfile => {
+                self.profile_picker = Some(ProfilePicker::new(ProfileTarget::SplitRight));
+            }
+            PaneCommand::SplitHorizontalWithProfile => {
+                self.profile_picker = Some(ProfilePicker::new(ProfileTarget::SplitDown));
             }
             PaneCommand::ClosePane => {
                 let pane_tree = &self.tab_manager.active_tab().pane_tree;
@@ -910,7 +2441,7 @@ impl App {
                 let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
                 match pane_tree.close_focused() {
                     Some(_) => {
-                        self.pane_states.remove(&closing_id);
+                        self.discard_pane(closing_id);
                         if let Some(renderer) = &mut self.renderer {
                             renderer.remove_pane_damage(closing_id);
                             renderer.pane_damage_mut().force_full_damage_all();
@@ -940,6 +2471,539 @@ impl App {
                     renderer.pane_damage_mut().force_full_damage_all();
                 }
             }
+            PaneCommand::Resize(direction) => {
+                let step = self.app_config.panes.resize_step as f32;
+                let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
+                if pane_tree.resize_focused(direction, step) {
+                    self.resize_all_panes(width, height);
+                    self.update_interaction_layout(width, height);
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+            }
+            PaneCommand::Equalize => {
+                self.tab_manager.active_tab_mut().pane_tree.equalize_splits();
+                self.resize_all_panes(width, height);
+                self.update_interaction_layout(width, height);
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.pane_damage_mut().force_full_damage_all();
+                }
+            }
+            PaneCommand::Swap(_) | PaneCommand::RotateLayout => {
+                let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
+                let changed = match command {
+                    PaneCommand::Swap(direction) => pane_tree.swap_focused(direction, pgrid.width, pgrid.height),
+                    _ => pane_tree.rotate_layout(),
+                };
+                if changed {
+                    self.resize_all_panes(width, height);
+                    self.update_interaction_layout(width, height);
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+            }
+            PaneCommand::RestartShell => {
+                let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                self.restart_pane(pane_id);
+            }
+            PaneCommand::AddNote => {
+                let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    let line = Self::current_line(state);
+                    state.note_editor = Some(crate::notes::NoteEditor::new(line, &state.notes));
+                }
+            }
+            PaneCommand::NextNote | PaneCommand::PrevNote => {
+                let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let Some(state) = self.pane_states.get_mut(&pane_id) else {
+                    return;
+                };
+                let history = state.terminal.history_size();
+                let viewport_top = history.saturating_sub(state.terminal.display_offset());
+                let target = if command == PaneCommand::NextNote {
+                    state.notes.next_after(viewport_top)
+                } else {
+                    state.notes.prev_before(viewport_top)
+                };
+                if let Some(line) = target {
+                    Self::scroll_to_line(state, line);
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+            }
+            PaneCommand::ToggleBookmark => {
+                let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let Some(state) = self.pane_states.get_mut(&pane_id) else {
+                    return;
+                };
+                // The alternate screen has no scrollback to come back to
+                if state.terminal.is_alt_screen() {
+                    return;
+                }
+                let line = state.terminal.logical_line_start(Self::current_line(state));
+                let text = state
+                    .terminal
+                    .logical_line_text(line, crate::bookmarks::FINGERPRINT_CHARS)
+                    .unwrap_or_default();
+                state.bookmarks.toggle(line, text);
+            }
+            PaneCommand::ListBookmarks => {
+                self.bookmark_list = match self.bookmark_list {
+                    Some(_) => None,
+                    None => Some(crate::bookmarks::BookmarkList::default()),
+                };
+            }
+            PaneCommand::ToggleTrust => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let Some(state) = self.pane_states.get_mut(&focused_id) else {
+                    return;
+                };
+                let trusted = state.output_filter.as_ref().is_some_and(|filter| filter.is_untrusted());
+                Self::set_output_filter(state, trusted, state.compat);
+                log::info!("Pane {:?} trusted: {}", focused_id, trusted);
+            }
+            PaneCommand::ToggleCompat => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let profile = self.pane_states.get(&focused_id).and_then(|s| s.profile.clone()).and_then(|name| self.profile_named(&name));
+                let shell = &self.app_config.shell;
+                let mut shell_config = profile.map_or_else(|| shell.clone(), |p| crate::profiles::shell_config(&p, shell));
+                let Some(state) = self.pane_states.get_mut(&focused_id) else {
+                    return;
+                };
+                let compat = state.compat.toggled();
+                let trusted = !state.output_filter.as_ref().is_some_and(|filter| filter.is_untrusted());
+                Self::set_output_filter(state, trusted, compat);
+                state.compat = compat;
+                shell_config.compat = compat.as_str().to_string();
+                state.restart_prompt = Some(crate::terminal::compat::restart_prompt(compat, crate::terminal::compat::term_name(&shell_config)));
+                log::info!("Pane {:?} compat: {}", focused_id, compat.as_str());
+            }
+            PaneCommand::ToggleScrollLock => {
+                let pane_tree = &self.tab_manager.active_tab().pane_tree;
+                let focused_id = pane_tree.focused_pane_id();
+                // An existing link is undone from either side
+                let Some(other) = self.scroll_locks.partner(focused_id).or_else(|| pane_tree.split_neighbor()) else {
+                    return;
+                };
+                let locked = self.scroll_locks.toggle(focused_id, other);
+                log::info!("Scroll lock {:?} and {:?}: {}", focused_id, other, locked);
+            }
+            PaneCommand::AssignGroup => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                self.group_editor = Some(crate::pane::groups::GroupEditor::new(focused_id, &self.pane_groups));
+            }
+            PaneCommand::ToggleGroupBroadcast => {
+                let Some(group) = self.focused_group() else {
+                    return;
+                };
+                let broadcasting = self.pane_groups.toggle_broadcast(&group);
+                log::info!("Group {group} broadcast: {broadcasting}");
+            }
+            PaneCommand::CloseGroup => {
+                if let Some(group) = self.focused_group() {
+                    self.close_group(&group);
+                }
+            }
+            PaneCommand::CycleGroupTheme => {
+                if let Some(group) = self.focused_group() {
+                    self.cycle_group_theme(&group);
+                }
+            }
+            PaneCommand::RerunInGroup => {
+                let Some(group) = self.focused_group() else {
+                    return;
+                };
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let Some(command) = self
+                    .pane_states
+                    .get(&focused_id)
+                    .and_then(|state| state.suggestions.history().back().cloned())
+                else {
+                    log::info!("No command to re-run in group {group}");
+                    return;
+                };
+                let line = format!("{command}\r");
+                for pane_id in self.pane_groups.members(&group) {
+                    self.send_typed_input(pane_id, line.as_bytes());
+                }
+            }
+        }
+    }
+
+    /// Split the focused pane, starting `profile` (or the shell) in the new one.
+    fn split_focused_pane(&mut self, direction: SplitDirection, profile: Option<&ProfileConfig>) {
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let pane_tree = &mut self.tab_manager.active_tab_mut().pane_tree;
+        if let Some(new_id) = pane_tree.split_focused(direction) {
+            let layout = pane_tree.calculate_layout(pgrid.width, pgrid.height);
+            if let Some((_, rect)) = layout.iter().find(|(id, _)| *id == new_id) {
+                let (cols, rows) = self.grid_dims_for_rect(rect);
+                self.spawn_pane_with_profile(new_id, cols, rows, None, profile);
+            }
+            self.resize_all_panes(width, height);
+            self.update_interaction_layout(width, height);
+            if let Some(renderer) = &mut self.renderer {
+                renderer.pane_damage_mut().force_full_damage_all();
+            }
+        }
+    }
+
+    /// Open a tab running `profile` (or the shell) and make it active.
+    fn open_new_tab(&mut self, profile: Option<&ProfileConfig>) {
+        let (width, height) = self.window_size();
+        self.tab_manager.new_tab();
+        // Spawn PTY for the new tab's initial pane
+        let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let rect = Rect::new(0.0, 0.0, pgrid.width, pgrid.height);
+        let (cols, rows) = self.grid_dims_for_rect(&rect);
+        self.spawn_pane_with_profile(pane_id, cols, rows, None, profile);
+        self.update_interaction_layout(width, height);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+    }
+
+    /// Handle a key while the vi register viewer is open: arrows select,
+    /// Enter copies the selected register to the clipboard, Escape closes.
+    fn handle_register_list_key(&mut self, logical_key: &Key) {
+        let Some(list) = &mut self.register_list else {
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => self.register_list = None,
+            Key::Named(NamedKey::ArrowUp) => list.select_prev(),
+            Key::Named(NamedKey::ArrowDown) => list.select_next(self.vi_registers.entries().count()),
+            Key::Named(NamedKey::Enter) => {
+                if let Some((name, text)) = self.vi_registers.entries().nth(list.selected) {
+                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                        if let Err(e) = clipboard.set_text(text) {
+                            log::warn!("Clipboard write error: {e}");
+                        } else {
+                            log::info!("Copied vi register {name} to clipboard");
+                        }
+                    }
+                }
+                self.register_list = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the profile picker is open: arrows select, Enter
+    /// opens the selected profile, Escape closes. The first entry is the
+    /// plain shell.
+    fn handle_profile_picker_key(&mut self, logical_key: &Key) {
+        let count = self.app_config.profiles.len() + 1;
+        let Some(picker) = &mut self.profile_picker else {
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => self.profile_picker = None,
+            Key::Named(NamedKey::ArrowUp) => picker.select_prev(),
+            Key::Named(NamedKey::ArrowDown) => picker.select_next(count),
+            Key::Named(NamedKey::Enter) => {
+                let (target, selected) = (picker.target, picker.selected);
+                self.profile_picker = None;
+                let profile = selected
+                    .checked_sub(1)
+                    .and_then(|index| self.app_config.profiles.get(index))
+                    .cloned();
+                match target {
+                    ProfileTarget::NewTab => {
+                        self.open_new_tab(profile.as_ref());
+                        self.tab_manager.clear_active_notification();
+                    }
+                    ProfileTarget::SplitRight => self.split_focused_pane(SplitDirection::Vertical, profile.as_ref()),
+                    ProfileTarget::SplitDown => self.split_focused_pane(SplitDirection::Horizontal, profile.as_ref()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Label the focused pane's URLs, paths, SHAs and addresses for
+    /// choosing by keyboard. Does nothing when there are none.
+    fn start_hint_mode(&mut self, action: crate::link::hints::HintAction) {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get(&focused) else {
+            return;
+        };
+        let lines = crate::terminal::grid_bridge::extract_text_maps(&state.terminal);
+        self.hint_mode = crate::link::hints::HintMode::new(&lines, action).map(|mode| (focused, mode));
+        if self.hint_mode.is_none() {
+            log::info!("Hint mode: nothing to label");
+        }
+    }
+
+    /// Hint mode over the matches of the `[quick_select]` patterns, for
+    /// copying. Patterns were checked when the config loaded.
+    fn start_quick_select(&mut self) {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get(&focused) else {
+            return;
+        };
+        let patterns: Vec<regex::Regex> = self
+            .app_config
+            .quick_select
+            .patterns
+            .iter()
+            .filter_map(|pattern| regex::Regex::new(pattern).ok())
+            .collect();
+        let lines = crate::terminal::grid_bridge::extract_text_maps(&state.terminal);
+        self.hint_mode = crate::link::hints::HintMode::from_patterns(&lines, &patterns).map(|mode| (focused, mode));
+        if self.hint_mode.is_none() {
+            log::info!("Quick select: nothing matches");
+        }
+    }
+
+    /// Handle a key in hint mode: label keys narrow and choose, Backspace
+    /// takes one back, Escape leaves.
+    fn handle_hint_key(&mut self, logical_key: &Key) {
+        use crate::link::hints::{HintAction, HintInput, HintKind};
+
+        let Some((_, mode)) = &mut self.hint_mode else {
+            return;
+        };
+        let hint = match logical_key {
+            Key::Named(NamedKey::Escape) => {
+                self.hint_mode = None;
+                return;
+            }
+            Key::Named(NamedKey::Backspace) => {
+                mode.backspace();
+                return;
+            }
+            Key::Character(c) => match c.chars().next().map(|key| mode.push(key)) {
+                Some(HintInput::Chosen(hint)) => hint,
+                _ => return,
+            },
+            _ => return,
+        };
+        let action = mode.action;
+        self.hint_mode = None;
+        match (action, hint.kind) {
+            (HintAction::Open, HintKind::Url) => {
+                self.open_url_checked(hint.text);
+            }
+            (HintAction::Open, HintKind::Path) => {
+                let link = crate::link::DetectedLink {
+                    kind: crate::link::LinkKind::FilePath,
+                    start: (hint.row, hint.start),
+                    end: (hint.end_row, hint.end),
+                    text: hint.text,
+                };
+                self.open_with_activation(Box::new(move |token| open_link(&link, token)));
+            }
+            _ => {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    if let Err(e) = clipboard.set_text(&hint.text) {
+                        log::warn!("Hint copy clipboard error: {e}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Scroll the pane linked to `pane` by the `lines` it just scrolled.
+    fn follow_scroll_lock(&mut self, pane: PaneId, lines: isize) {
+        if lines == 0 {
+            return;
+        }
+        let Some(state) = self.scroll_locks.partner(pane).and_then(|other| self.pane_states.get_mut(&other)) else {
+            return;
+        };
+        let history_size = state.terminal.history_size();
+        let offset = crate::pane::scroll_lock::follow_offset(state.scroll_state.target_offset(), lines, history_size);
+        state.scroll_state.set_target(offset, history_size);
+        state.terminal.set_display_offset(offset);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+    }
+
+    /// Scroll so buffer `line` is at the top of the pane, like prompt jumps.
+    fn scroll_to_line(state: &mut PaneState, line: usize) {
+        let history = state.terminal.history_size();
+        let offset = history.saturating_sub(line);
+        state.scroll_state.set_target(offset, history);
+        state.terminal.set_display_offset(offset);
+    }
+
+    /// Re-find bookmarks after output or a resize moved their lines. Skipped
+    /// while a reflow or the alternate screen hides the real scrollback.
+    fn relocate_bookmarks(state: &mut PaneState) {
+        let terminal = &state.terminal;
+        if state.bookmarks.is_empty() || terminal.is_reflowing() || terminal.is_alt_screen() {
+            return;
+        }
+        state.bookmarks.relocate(terminal.total_rows(), |line| {
+            terminal.logical_line_text(line, crate::bookmarks::FINGERPRINT_CHARS)
+        });
+    }
+
+    /// Handle a key while the bookmark list is open: arrows select, Enter
+    /// jumps to the selected bookmark, Escape closes.
+    fn handle_bookmark_list_key(&mut self, logical_key: &Key) {
+        let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(list) = &mut self.bookmark_list else {
+            return;
+        };
+        let Some(state) = self.pane_states.get_mut(&pane_id) else {
+            self.bookmark_list = None;
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => self.bookmark_list = None,
+            Key::Named(NamedKey::ArrowUp) => list.select_prev(),
+            Key::Named(NamedKey::ArrowDown) => list.select_next(state.bookmarks.marks().len()),
+            Key::Named(NamedKey::Enter) => {
+                if let Some(mark) = state.bookmarks.marks().get(list.selected) {
+                    let line = mark.line;
+                    Self::scroll_to_line(state, line);
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+                self.bookmark_list = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Buffer line that notes and bookmarks act on: the vi cursor's line,
+    /// the cursor's line in the live view, or the top line when scrolled back.
+    fn current_line(state: &PaneState) -> usize {
+        let history = state.terminal.history_size();
+        let offset = state.terminal.display_offset();
+        let viewport_top = history.saturating_sub(offset);
+        if let Some(vi) = &state.vi_state {
+            viewport_top + vi.cursor.row
+        } else if offset == 0 {
+            history + state.terminal.cursor_position().0
+        } else {
+            viewport_top
+        }
+    }
+
+    /// The focused pane's group, if it is in one.
+    fn focused_group(&self) -> Option<String> {
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        self.pane_groups.group_of(focused_id).map(str::to_string)
+    }
+
+    /// Close every pane in `group`. A pane alone in its tab takes the tab
+    /// with it, unless it is the window's last pane.
+    fn close_group(&mut self, group: &str) {
+        for pane_id in self.pane_groups.members(group) {
+            let Some(index) = self.tab_manager.tab_index_for_pane(pane_id) else {
+                continue;
+            };
+            let closed = self.tab_manager.tab_mut(index).is_some_and(|tab| tab.pane_tree.close_pane(pane_id));
+            let pane_ids = if closed {
+                vec![pane_id]
+            } else if self.tab_manager.tab_count() > 1 {
+                self.tab_manager.close_tab(index).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            for pane_id in pane_ids {
+                self.discard_pane(pane_id);
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.remove_pane_damage(pane_id);
+                }
+            }
+        }
+        let (width, height) = self.window_size();
+        self.resize_all_panes(width, height);
+        self.update_interaction_layout(width, height);
+        self.sync_search_mode();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+    }
+
+    /// Give every pane in `group` the theme after the focused pane's.
+    fn cycle_group_theme(&mut self, group: &str) {
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let current_name = self
+            .pane_states
+            .get(&focused_id)
+            .and_then(|state| state.theme.as_ref())
+            .or_else(|| self.renderer.as_ref().map(|r| r.theme()))
+            .map_or("Warm Dark", |theme| theme.name);
+        let themes = TerminalTheme::available_themes();
+        let current_idx = themes.iter().position(|(_, name)| *name == current_name).unwrap_or(0);
+        let (config_name, _) = themes[(current_idx + 1) % themes.len()];
+        let Some(theme) = self.app_config.colors.resolve_theme(config_name) else {
+            return;
+        };
+        for pane_id in self.pane_groups.members(group) {
+            if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                state.theme = Some(theme);
+                state.minimap.mark_dirty();
+            }
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+    }
+
+    /// Handle a key in the group name editor: Enter assigns the group (an
+    /// empty name takes the pane out of its group), Escape cancels.
+    fn handle_group_editor_key(&mut self, logical_key: &Key, text: Option<&str>) {
+        let Some(editor) = &mut self.group_editor else {
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => self.group_editor = None,
+            Key::Named(NamedKey::Enter) => {
+                if let Some(editor) = self.group_editor.take() {
+                    self.pane_groups.assign(editor.pane, &editor.value);
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                editor.value.pop();
+            }
+            Key::Named(NamedKey::Space) => editor.value.push(' '),
+            Key::Character(s) => {
+                let t = text.unwrap_or(s.as_ref());
+                editor.value.extend(t.chars().filter(|c| !c.is_control()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key in the focused pane's note editor: Enter saves (an
+    /// empty note removes it), Escape cancels.
+    fn handle_note_editor_key(&mut self, logical_key: &Key, text: Option<&str>) {
+        let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get_mut(&pane_id) else {
+            return;
+        };
+        let Some(editor) = &mut state.note_editor else {
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => state.note_editor = None,
+            Key::Named(NamedKey::Enter) => {
+                if let Some(editor) = state.note_editor.take() {
+                    state.notes.set(editor.line, &editor.value);
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                editor.value.pop();
+            }
+            Key::Named(NamedKey::Space) => editor.value.push(' '),
+            Key::Character(s) => {
+                let t = text.unwrap_or(s.as_ref());
+                editor.value.extend(t.chars().filter(|c| !c.is_control()));
+            }
+            _ => {}
         }
     }
 
@@ -956,18 +3020,9 @@ impl App {
                 self.handle_close_active_tab(event_loop);
                 return; // handle_close_active_tab handles redraw/layout
             }
-            TabCommand::NewTab => {
-                self.tab_manager.new_tab();
-                // Spawn PTY for the new tab's initial pane
-                let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                let pgrid = self.pane_grid_bounds(width as f32, height as f32);
-                let rect = Rect::new(0.0, 0.0, pgrid.width, pgrid.height);
-                let (cols, rows) = self.grid_dims_for_rect(&rect);
-                self.spawn_pane(pane_id, cols, rows);
-                self.update_interaction_layout(width, height);
-                if let Some(renderer) = &mut self.renderer {
-                    renderer.pane_damage_mut().force_full_damage_all();
-                }
+            TabCommand::NewTab => self.open_new_tab(None),
+            TabCommand::NewTabWithProfile => {
+                self.profile_picker = Some(ProfilePicker::new(ProfileTarget::NewTab));
             }
             TabCommand::NextTab => {
                 self.tab_manager.next_tab();
@@ -1002,6 +3057,14 @@ impl App {
                     self.tab_manager.move_tab(idx, idx + 1);
                 }
             }
+            TabCommand::RenameTab => {
+                // Tabs are listed in the sidebar, so the editor opens there
+                if !self.sidebar_visible {
+                    self.sidebar_visible = true;
+                    self.resize_all_panes(width, height);
+                }
+                self.start_tab_rename(self.tab_manager.active_index());
+            }
         }
 
         // Clear notification badge on the newly active tab
@@ -1018,7 +3081,7 @@ impl App {
         match self.tab_manager.close_tab(index) {
             Some(pane_ids) => {
                 for pane_id in &pane_ids {
-                    self.pane_states.remove(pane_id);
+                    self.discard_pane(*pane_id);
                     if let Some(renderer) = &mut self.renderer {
                         renderer.remove_pane_damage(*pane_id);
                     }
@@ -1051,7 +3114,7 @@ impl App {
             }
             if let Some(pane_ids) = self.tab_manager.close_tab(i) {
                 for pane_id in &pane_ids {
-                    self.pane_states.remove(pane_id);
+                    self.discard_pane(*pane_id);
                     if let Some(renderer) = &mut self.renderer {
                         renderer.remove_pane_damage(*pane_id);
                     }
@@ -1069,20 +3132,35 @@ impl App {
     }
 
     /// Try to restore a previous session. Returns true if restored.
-    fn try_restore_session(&mut self, width: f32, height: f32) -> bool {
-        if !self.app_config.session.auto_restore {
-            return false;
+    /// Load the saved session if restoring is on (`session.auto_restore` or
+    /// `--restore-session`). The file is consumed so a crash during restore
+    /// doesn't loop.
+    fn session_to_restore(&self) -> Option<crate::session::SessionState> {
+        if !self.restore_session {
+            return None;
         }
-
         let path = crate::session::SessionState::default_path();
         let session = match crate::session::SessionState::load(&path) {
             Ok(s) => s,
             Err(e) => {
                 log::info!("No session to restore: {e}");
-                return false;
+                return None;
             }
         };
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove session file: {e}");
+        }
+        Some(session)
+    }
 
+    /// Recreate tabs, pane layouts and working directories from a saved
+    /// session. Returns true if restored.
+    fn try_restore_session(
+        &mut self,
+        session: crate::session::SessionState,
+        width: f32,
+        height: f32,
+    ) -> bool {
         log::info!(
             "Restoring session: {} tabs, {} panes",
             session.tabs.len(),
@@ -1100,16 +3178,20 @@ impl App {
                 crate::session::restore_pane_tree(&session_tab.pane_tree);
 
             // Build Tab with the restored pane tree
-            let first_pane = pane_spawns.first().map(|(id, _)| *id);
-            let tab = crate::tab::Tab::from_pane_tree(
+            let first_pane = pane_spawns.first().map(|(id, _, _)| *id);
+            let mut tab = crate::tab::Tab::from_pane_tree(
                 session_tab.title.clone(),
                 crate::pane::PaneTree::from_node(root_node, first_pane),
             );
+            tab.title_is_manual = session_tab.title_is_manual;
             new_tabs.push(tab);
 
-            // Spawn all panes with their saved CWDs
-            for (pane_id, cwd) in &pane_spawns {
-                self.spawn_pane_with_cwd(*pane_id, cols, rows, cwd.as_deref());
+            // Spawn all panes with their saved CWDs and notes
+            for (pane_id, cwd, notes) in pane_spawns {
+                self.spawn_pane_with_cwd(pane_id, cols, rows, cwd.as_deref());
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    state.notes = crate::notes::PaneNotes::from_notes(notes);
+                }
             }
         }
 
@@ -1120,26 +3202,35 @@ impl App {
         let active = session.active_tab.min(new_tabs.len() - 1);
         self.tab_manager = TabManager::from_tabs(new_tabs, active);
 
-        // Remove the session file after successful restore
-        if let Err(e) = std::fs::remove_file(&path) {
-            log::warn!("Failed to remove session file: {e}");
-        }
-
         true
     }
 
     /// Save the current session state to the session file.
     fn save_session(&self) {
+        // The daemon keeps these shells; restoring them elsewhere would
+        // start duplicates
+        if self.config.daemon_socket.is_some() {
+            return;
+        }
         let mut pane_cwds = std::collections::HashMap::new();
         for (&pane_id, state) in &self.pane_states {
             pane_cwds.insert(
                 pane_id,
                 crate::session::PaneCwdInfo {
                     cwd: state.terminal.shell_state().cwd.clone(),
+                    notes: state.notes.notes().to_vec(),
                 },
             );
         }
-        let session = crate::session::SessionState::capture(&self.tab_manager, &pane_cwds);
+        let mut session = crate::session::SessionState::capture(&self.tab_manager, &pane_cwds);
+        if let Some(window) = &self.window {
+            let size = window.inner_size().to_logical::<f64>(window.scale_factor());
+            session.window = Some(crate::session::SessionWindow {
+                width: size.width,
+                height: size.height,
+                font_scale: self.font_scale_override,
+            });
+        }
         let path = crate::session::SessionState::default_path();
         match session.save(&path) {
             Ok(()) => log::info!(
@@ -1152,6 +3243,17 @@ impl App {
         }
     }
 
+    /// Pace frames to the refresh rate of the monitor the window is on.
+    fn sync_refresh_rate(&mut self) {
+        let millihertz = self
+            .window
+            .as_ref()
+            .and_then(|window| window.current_monitor())
+            .and_then(|monitor| monitor.refresh_rate_millihertz());
+        self.frame_pacer.set_refresh_rate(millihertz);
+        log::debug!("Frame interval {:?}", self.frame_pacer.interval());
+    }
+
     /// Toggle window visibility for quick terminal mode.
     fn toggle_quick_terminal(&mut self) {
         if let Some(window) = &self.window {
@@ -1159,10 +3261,12 @@ impl App {
                 window.set_visible(true);
                 window.focus_window();
                 self.quick_terminal_hidden = false;
+                self.frame_pacer.set_minimized(false);
                 log::info!("Quick terminal: window shown");
             } else {
                 window.set_visible(false);
                 self.quick_terminal_hidden = true;
+                self.frame_pacer.set_minimized(true);
                 log::info!("Quick terminal: window hidden");
             }
         }
@@ -1176,7 +3280,7 @@ impl App {
             let cw = renderer.cell_width();
             let ch = renderer.cell_height();
             let [pad_top, pad_bottom, pad_left, pad_right] = renderer.padding();
-            let usable_w = (rect.width - pad_left - pad_right).max(0.0);
+            let usable_w = (rect.width - pad_left - pad_right - self.minimap_reserved_width()).max(0.0);
             let usable_h = (rect.height - PANE_HEADER_HEIGHT - pad_top - pad_bottom).max(0.0);
             let cols = (usable_w / cw).floor().max(1.0) as u16;
             let rows = (usable_h / ch).floor().max(1.0) as u16;
@@ -1190,20 +3294,30 @@ impl App {
         }
     }
 
+    /// Terminal columns for a pane with room for `cols`: `panes.fixed_columns`
+    /// or at least `panes.min_columns`, the rest scrolled horizontally.
+    fn terminal_columns(&self, cols: u16) -> u16 {
+        self.app_config.panes.terminal_columns(cols)
+    }
+
     /// Resize all pane terminals and PTYs to match their current layout rects.
     /// When `is_dragging_divider` is true, PTY/terminal resize is deferred to
     /// avoid flooding shells with SIGWINCH during continuous drag.
     fn resize_all_panes(&mut self, width: u32, height: u32) {
         let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        self.record_event(TraceEvent::Resize { width: pgrid.width, height: pgrid.height });
+        self.record_layout();
         let pane_tree = &self.tab_manager.active_tab().pane_tree;
         let layout = pane_tree.calculate_layout(pgrid.width, pgrid.height);
         for (pane_id, rect) in &layout {
-            let (cols, rows) = self.grid_dims_for_rect(rect);
+            let (visible_cols, rows) = self.grid_dims_for_rect(rect);
+            let cols = self.terminal_columns(visible_cols);
             if let Some(renderer) = &self.renderer {
                 eprintln!("[PTY-RESIZE] pane={:?} pty_cols={} pty_rows={} cell_w={:.1} cell_h={:.1} rect={:.0}x{:.0}",
                     pane_id, cols, rows, renderer.cell_width(), renderer.cell_height(), rect.width, rect.height);
             }
             if let Some(state) = self.pane_states.get_mut(pane_id) {
+                state.h_scroll.set_visible(visible_cols as usize, cols as usize);
                 // Skip panes whose dimensions haven't changed (avoids double
                 // resize on split and unnecessary SIGWINCH on existing panes)
                 let old_cols = state.terminal.columns();
@@ -1226,6 +3340,14 @@ impl App {
                 }
                 // Then resize terminal grid to match
                 state.terminal.resize(cols as usize, rows as usize);
+                Self::relocate_bookmarks(state);
+                if let Some(recorder) = &mut self.event_recorder {
+                    recorder.record(TraceEvent::PaneResize {
+                        pane: pane_id.0,
+                        cols: cols as usize,
+                        rows: rows as usize,
+                    });
+                }
             }
         }
     }
@@ -1273,7 +3395,76 @@ impl App {
                     window.request_redraw();
                 }
             }
+            InteractionEffect::ScrollTo { pane_id, offset } => {
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    let history_size = state.terminal.history_size();
+                    state.scroll_state.set_target(offset, history_size);
+                    state.terminal.set_display_offset(offset);
+                }
+                if let Some(window) = &self.window {
+                    window.request_redraw();
+                }
+            }
+            InteractionEffect::BeginScrollbarDrag { pane_id, y } => {
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    state.scroll_state.begin_drag(y);
+                }
+            }
+            InteractionEffect::ScrollbarDragTo { pane_id, y, track_height } => {
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    let history_size = state.terminal.history_size();
+                    state.scroll_state.update_drag(y, track_height, history_size);
+                    let offset = state.scroll_state.current_line_offset();
+                    state.terminal.set_display_offset(offset);
+                }
+                if let Some(window) = &self.window {
+                    window.request_redraw();
+                }
+            }
+            InteractionEffect::EndScrollbarDrag(pane_id) => {
+                if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                    state.scroll_state.end_drag();
+                }
+            }
+        }
+    }
+
+    /// Hand the active tab's pane scrollbars to the interaction state machine
+    /// so the next press can hit them. None with `scrollback.scrollbar = "never"`.
+    fn update_interaction_scrollbars(&mut self) {
+        let mode = ScrollbarMode::from_config_str(&self.app_config.scrollback.scrollbar)
+            .unwrap_or_default();
+        let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
+        if mode == ScrollbarMode::Never {
+            self.interaction.update_scrollbars(Vec::new(), padding);
+            return;
         }
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let scrollbars = layout
+            .iter()
+            .filter_map(|(pane_id, r)| {
+                let state = self.pane_states.get(pane_id)?;
+                if state.terminal.history_size() == 0 {
+                    return None;
+                }
+                Some(ScrollbarInfo {
+                    pane_id: *pane_id,
+                    // The drawn pane area, as for the search match strip
+                    rect: Rect::new(
+                        r.x + pgrid.x,
+                        r.y + pgrid.y,
+                        r.width - Self::PANE_GRID_PADDING * 2.0,
+                        r.height - Self::PANE_GRID_PADDING * 2.0,
+                    ),
+                    visible_rows: state.terminal.rows(),
+                    history_size: state.terminal.history_size(),
+                    display_offset: state.scroll_state.current_line_offset(),
+                })
+            })
+            .collect();
+        self.interaction.update_scrollbars(scrollbars, padding);
     }
 
     /// Update the interaction state machine's cached layout.
@@ -1295,10 +3486,10 @@ impl App {
             .unwrap_or((1280, 720))
     }
 
-    /// Write theme name to /tmp/veloterm-theme and send SIGUSR1 to all shell processes
+    /// Write theme name and COLORFGBG to /tmp/veloterm-* and send SIGUSR1 to all shell processes
     /// so p10k (and other tools) can re-read the theme and update their prompt.
     fn signal_theme_change_to_shells(&self) {
-        let _ = std::fs::write("/tmp/veloterm-theme", &self.app_config.colors.theme);
+        crate::pty::write_theme_files(&self.app_config.colors, &self.app_config.colors.theme);
         for state in self.pane_states.values() {
             if let Some(pid) = state.pty.child_pid() {
                 #[cfg(unix)]
@@ -1325,7 +3516,7 @@ impl App {
     fn rescan_links(&mut self) {
         let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
         if let Some(state) = self.pane_states.get(&focused) {
-            let lines = crate::terminal::grid_bridge::extract_text_lines(&state.terminal);
+            let lines = crate::terminal::grid_bridge::extract_text_maps(&state.terminal);
             self.link_detector.scan(&lines);
         }
     }
@@ -1370,7 +3561,7 @@ impl App {
     }
 
     /// Handle modifier+click on a link.
-    fn handle_link_click(&self, pixel_x: f32, pixel_y: f32) -> bool {
+    fn handle_link_click(&mut self, pixel_x: f32, pixel_y: f32) -> bool {
         if !self.is_link_modifier_held() {
             return false;
         }
@@ -1393,7 +3584,12 @@ impl App {
         let row = (adj_y / cell_height).floor() as usize;
 
         if let Some(link) = self.link_detector.link_at(row, col) {
-            open_link(link);
+            let link = link.clone();
+            if link.kind == crate::link::LinkKind::Url {
+                self.open_url_checked(link.text);
+            } else {
+                self.open_with_activation(Box::new(move |token| open_link(&link, token)));
+            }
             true
         } else {
             false
@@ -1407,238 +3603,947 @@ impl App {
 
     /// Handle a search command: update query, navigate matches, or close search.
     fn handle_search_command(&mut self, cmd: SearchCommand) {
+        let Some(search) = self.focused_search_mut() else {
+            return;
+        };
         match cmd {
             SearchCommand::InsertChar(ch) => {
-                self.search_state.query.push(ch);
-                self.run_incremental_search();
+                search.query.push(ch);
+                search.queue_query(std::time::Instant::now());
+                self.sync_linked_search();
+                self.advance_searches();
             }
             SearchCommand::DeleteChar => {
-                self.search_state.query.pop();
-                self.run_incremental_search();
+                search.query.pop();
+                search.queue_query(std::time::Instant::now());
+                self.sync_linked_search();
+                self.advance_searches();
             }
             SearchCommand::NextMatch => {
-                self.search_state.next_match();
+                search.next_match();
+                if let Some(linked) = self.linked_search_mut() {
+                    linked.next_match();
+                }
                 self.scroll_to_current_match();
             }
             SearchCommand::PrevMatch => {
-                self.search_state.prev_match();
+                search.prev_match();
+                if let Some(linked) = self.linked_search_mut() {
+                    linked.prev_match();
+                }
                 self.scroll_to_current_match();
             }
             SearchCommand::Close => {
+                self.close_search();
+            }
+            SearchCommand::Open => {
+                // Already handled by should_open_search
+            }
+            SearchCommand::ToggleCaseSensitive | SearchCommand::ToggleWholeWord | SearchCommand::ToggleRegex => {
+                let mut options = search.options();
+                match cmd {
+                    SearchCommand::ToggleCaseSensitive => options.case_sensitive = !options.case_sensitive,
+                    SearchCommand::ToggleWholeWord => options.whole_word = !options.whole_word,
+                    _ => options.literal = !options.literal,
+                }
+                search.set_options(options);
+                search.queue_query(std::time::Instant::now());
+                self.sync_linked_search();
+                self.advance_searches();
+            }
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Handle a key event while the command palette is open.
+    fn handle_palette_key(
+        &mut self,
+        logical_key: &Key,
+        text: Option<&str>,
+        event_loop: &ActiveEventLoop,
+    ) {
+        let palette = match &mut self.palette_state {
+            Some(p) => p,
+            None => return,
+        };
+
+        match logical_key {
+            Key::Named(NamedKey::Escape) => {
                 self.input_mode = InputMode::Normal;
-                self.search_state.is_active = false;
-                self.search_state.query.clear();
-                self.search_state.matches.clear();
+                self.palette_state = None;
+                self.context_menu_visible = false;
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                palette.select_prev();
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                palette.select_next();
+            }
+            Key::Named(NamedKey::Enter) => {
+                if let Some(action) = palette.selected_action() {
+                    self.input_mode = InputMode::Normal;
+                    self.palette_state = None;
+                    self.dispatch_action(action, event_loop);
+                }
+            }
+            Key::Named(NamedKey::Backspace) => {
+                palette.backspace();
+            }
+            Key::Character(s) => {
+                let t = text.unwrap_or(s.as_ref());
+                if let Some(ch) = t.chars().next() {
+                    if !ch.is_control() {
+                        palette.type_char(ch);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the title editor for the tab at `index`, starting from its
+    /// current title.
+    fn start_tab_rename(&mut self, index: usize) {
+        if let Some(tab) = self.tab_manager.tabs().get(index) {
+            self.editing_tab_value = tab.title.clone();
+            self.editing_sidebar_tab = Some(index);
+        }
+    }
+
+    /// Handle a key while a tab title is being edited: Enter keeps the
+    /// title (an empty one returns the tab to shell-derived titles), Escape
+    /// cancels.
+    fn handle_tab_rename_key(&mut self, logical_key: &Key, text: Option<&str>) {
+        let Some(index) = self.editing_sidebar_tab else {
+            return;
+        };
+        match logical_key {
+            Key::Named(NamedKey::Escape) => {
+                self.editing_sidebar_tab = None;
+                self.editing_tab_value.clear();
+            }
+            Key::Named(NamedKey::Enter) => {
+                self.tab_manager.rename_tab(index, &self.editing_tab_value);
+                self.editing_sidebar_tab = None;
+                self.editing_tab_value.clear();
+            }
+            Key::Named(NamedKey::Backspace) => {
+                self.editing_tab_value.pop();
+            }
+            Key::Named(NamedKey::Space) => self.editing_tab_value.push(' '),
+            Key::Character(s) => {
+                let t = text.unwrap_or(s.as_ref());
+                self.editing_tab_value.extend(t.chars().filter(|c| !c.is_control()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Run an action from a key binding or the command palette.
+    fn dispatch_action(&mut self, action: Action, event_loop: &ActiveEventLoop) {
+        if let Some(name) = action.name().filter(|_| self.event_recorder.is_some()) {
+            self.record_event(TraceEvent::Action { name });
+        }
+        match action {
+            Action::Pane(cmd) => self.handle_pane_command(cmd, event_loop),
+            Action::Tab(cmd) => self.handle_tab_command(cmd, event_loop),
+            Action::Search(SearchCommand::Open) => self.toggle_search(),
+            Action::Search(cmd) => self.handle_search_command(cmd),
+            Action::App(AppCommand::Quit) => self.quit(event_loop),
+            Action::App(cmd) => self.handle_app_command(cmd),
+            Action::Shell(cmd) => self.handle_shell_command(cmd),
+            Action::Overlay(cmd) => self.handle_overlay_command(cmd),
+            Action::View(cmd) => self.handle_view_command(cmd),
+            Action::Edit(cmd) => self.handle_edit_command(cmd),
+            Action::ToggleViMode => self.toggle_vi_mode(),
+            Action::Script(index) => self.run_script_binding(index, event_loop),
+        }
+        self.record_layout();
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Whether mouse events in the focused pane go to its application: it
+    /// enabled mouse reporting, no overlay or vi mode has the mouse, and
+    /// Shift (which forces selection) is up.
+    fn mouse_reporting(&self) -> bool {
+        if self.modifiers.shift_key()
+            || self.input_mode != InputMode::Normal
+            || self.context_menu_visible
+            || self.link_hover_active
+        {
+            return false;
+        }
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        self.pane_states.get(&focused).is_some_and(|state| {
+            state.vi_state.is_none() && state.terminal.mouse_protocol().is_active()
+        })
+    }
+
+    /// The focused pane's grid cell (col, row) under window position
+    /// (`x`, `y`). Outside the grid this is None, or the nearest cell when
+    /// `clamp` is set (drags leaving the pane).
+    fn focused_cell_at(&self, x: f32, y: f32, clamp: bool) -> Option<(usize, usize)> {
+        let renderer = self.renderer.as_ref()?;
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let state = self.pane_states.get(&focused)?;
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let rect = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r)?;
+        let padding = renderer.padding();
+        let local_x = x - pgrid.x - rect.x - padding[2];
+        let local_y = y - pgrid.y - rect.y - PANE_HEADER_HEIGHT - padding[0];
+        let col = (local_x / renderer.cell_width()).floor();
+        let row = (local_y / renderer.cell_height()).floor();
+        let (cols, rows) = (state.terminal.columns() as f32, state.terminal.rows() as f32);
+        if clamp {
+            Some((col.clamp(0.0, cols - 1.0) as usize, row.clamp(0.0, rows - 1.0) as usize))
+        } else if (0.0..cols).contains(&col) && (0.0..rows).contains(&row) {
+            Some((col as usize, row as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Write one mouse report to the focused pane's PTY. False when the
+    /// application's modes don't cover the event.
+    fn report_mouse(
+        &mut self,
+        button: crate::input::mouse_report::ReportButton,
+        kind: crate::input::mouse_report::ReportKind,
+        (col, row): (usize, usize),
+    ) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        let protocol = state.terminal.mouse_protocol();
+        let Some(bytes) =
+            crate::input::mouse_report::encode(protocol, button, kind, col, row, self.modifiers)
+        else {
+            return false;
+        };
+        if let Err(e) = state.pty.write(&bytes) {
+            log::warn!("PTY write error: {e}");
+        }
+        true
+    }
+
+    /// Report a button press or release. Returns true when the event went to
+    /// the application and must not select text or open the context menu.
+    fn report_mouse_button(&mut self, button: MouseButton, btn_state: ElementState) -> bool {
+        use crate::input::mouse_report::{ReportButton, ReportKind};
+        let button = match button {
+            MouseButton::Left => ReportButton::Left,
+            MouseButton::Middle => ReportButton::Middle,
+            MouseButton::Right => ReportButton::Right,
+            _ => return false,
+        };
+        let (x, y) = self.interaction.cursor_pos();
+        match btn_state {
+            ElementState::Pressed => {
+                if !self.mouse_reporting() {
+                    return false;
+                }
+                let Some(cell) = self.focused_cell_at(x, y, false) else {
+                    return false;
+                };
+                if !self.report_mouse(button, ReportKind::Press, cell) {
+                    return false;
+                }
+                self.mouse_report_button = Some(button);
+                self.mouse_report_cell = Some(cell);
+                true
+            }
+            ElementState::Released => {
+                // Only presses that were reported: a divider or scrollbar drag
+                // ending over the pane still ends normally
+                if self.mouse_report_button != Some(button) {
+                    return false;
+                }
+                self.mouse_report_button = None;
+                let cell = self.focused_cell_at(x, y, true).or(self.mouse_report_cell);
+                if let Some(cell) = cell {
+                    self.report_mouse(button, ReportKind::Release, cell);
+                }
+                true
+            }
+        }
+    }
+
+    /// Report pointer motion to a new cell: drags of a reported press
+    /// (DECSET 1002) or any motion (1003).
+    fn report_mouse_motion(&mut self, x: f32, y: f32) {
+        use crate::input::mouse_report::{ReportButton, ReportKind};
+        let held = self.mouse_report_button;
+        if held.is_none() && !self.mouse_reporting() {
+            return;
+        }
+        let Some(cell) = self.focused_cell_at(x, y, held.is_some()) else {
+            return;
+        };
+        if self.mouse_report_cell == Some(cell) {
+            return;
+        }
+        self.mouse_report_cell = Some(cell);
+        self.report_mouse(held.unwrap_or(ReportButton::None), ReportKind::Motion, cell);
+    }
+
+    /// Report wheel scrolling as wheel button presses, one per line, instead
+    /// of scrolling the scrollback.
+    fn report_mouse_wheel(&mut self, delta: winit::event::MouseScrollDelta) -> bool {
+        use crate::input::mouse_report::{ReportButton, ReportKind};
+        if !self.mouse_reporting() {
+            return false;
+        }
+        let (x, y) = self.interaction.cursor_pos();
+        let Some(cell) = self.focused_cell_at(x, y, false) else {
+            return false;
+        };
+        let lines = match delta {
+            winit::event::MouseScrollDelta::LineDelta(_, dy) => {
+                dy.signum() * dy.abs().ceil()
+            }
+            winit::event::MouseScrollDelta::PixelDelta(pos) => {
+                let cell_height = self.renderer.as_ref().map(|r| r.cell_height()).unwrap_or(20.0);
+                self.mouse_report_scroll_px += pos.y as f32;
+                let lines = (self.mouse_report_scroll_px / cell_height).trunc();
+                self.mouse_report_scroll_px -= lines * cell_height;
+                lines
+            }
+        };
+        let button = if lines > 0.0 { ReportButton::WheelUp } else { ReportButton::WheelDown };
+        for _ in 0..lines.abs() as usize {
+            self.report_mouse(button, ReportKind::Press, cell);
+        }
+        true
+    }
+
+    /// Save the session and leave the event loop.
+    fn quit(&mut self, event_loop: &ActiveEventLoop) {
+        self.save_session();
+        self.apply_secure_keyboard_entry(false);
+        event_loop.exit();
+    }
+
+    /// Whether the Option key now held sends ESC-prefixed input rather than
+    /// composing characters (`macos.option_as_alt`). Always false off macOS.
+    fn option_as_alt(&self) -> bool {
+        use crate::input::option_key::OptionAsAlt;
+        cfg!(target_os = "macos")
+            && OptionAsAlt::from_config_str(&self.app_config.macos.option_as_alt)
+                .unwrap_or_default()
+                .applies(self.option_keys, self.modifiers.alt_key())
+    }
+
+    /// Hold macOS secure event input while the toggle is on and the window
+    /// has focus. It is system-wide, so it is released while VeloTerm is in
+    /// the background.
+    fn apply_secure_keyboard_entry(&self, focused: bool) {
+        #[cfg(target_os = "macos")]
+        crate::platform::macos::set_secure_keyboard_entry(self.secure_keyboard_entry && focused);
+        #[cfg(not(target_os = "macos"))]
+        let _ = focused;
+    }
+
+    /// Open a new tab whose shell starts in `dir` (Finder's "New VeloTerm
+    /// Tab Here" service) and bring the window forward.
+    fn open_tab_at(&mut self, dir: &std::path::Path) {
+        let (width, height) = self.window_size();
+        self.tab_manager.new_tab();
+        let pane_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let rect = Rect::new(0.0, 0.0, pgrid.width, pgrid.height);
+        let (cols, rows) = self.grid_dims_for_rect(&rect);
+        self.spawn_pane_with_cwd(pane_id, cols, rows, dir.to_str());
+        self.update_interaction_layout(width, height);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+        if let Some(window) = &self.window {
+            window.focus_window();
+            window.request_redraw();
+        }
+    }
+
+    /// Carry out a `veloterm ctl` request.
+    fn handle_control(&mut self, request: ControlRequest, event_loop: &ActiveEventLoop) -> ControlResponse {
+        use crate::control::protocol::SplitSide;
+
+        match request {
+            ControlRequest::ListPanes => ControlResponse::Panes { panes: self.control_pane_list() },
+            ControlRequest::SplitPane { pane, side } => {
+                if let Err(response) = self.focus_control_target(pane) {
+                    return response;
+                }
+                let before = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let command = match side {
+                    SplitSide::Right => PaneCommand::SplitVertical,
+                    SplitSide::Down => PaneCommand::SplitHorizontal,
+                };
+                self.handle_pane_command(command, event_loop);
+                let new_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                if new_id == before {
+                    ControlResponse::error("the pane could not be split")
+                } else {
+                    ControlResponse::Pane { id: new_id.0 }
+                }
+            }
+            ControlRequest::SendText { pane, text } => {
+                let pane_id = pane.map_or_else(|| self.tab_manager.active_tab().pane_tree.focused_pane_id(), PaneId);
+                if !self.pane_states.contains_key(&pane_id) {
+                    return ControlResponse::error(format!("no pane {}", pane_id.0));
+                }
+                self.send_typed_input(pane_id, text.as_bytes());
+                ControlResponse::Ok
+            }
+            ControlRequest::ActivateTab { index } => {
+                if index >= self.tab_manager.tab_count() {
+                    return ControlResponse::error(format!("no tab {index}"));
+                }
+                self.handle_tab_command(TabCommand::SelectTab(index), event_loop);
+                ControlResponse::Ok
+            }
+            ControlRequest::ActivatePane { pane } => match self.focus_control_target(Some(pane)) {
+                Ok(_) => ControlResponse::Ok,
+                Err(response) => response,
+            },
+            ControlRequest::NewTab { cwd, title } => {
+                match cwd {
+                    Some(dir) => self.open_tab_at(std::path::Path::new(&dir)),
+                    None => self.handle_tab_command(TabCommand::NewTab, event_loop),
+                }
+                if let Some(title) = title {
+                    self.tab_manager.rename_tab(self.tab_manager.active_index(), &title);
+                }
+                ControlResponse::Pane { id: self.tab_manager.active_tab().pane_tree.focused_pane_id().0 }
             }
-            SearchCommand::Open => {
-                // Already handled by should_open_search
+            ControlRequest::ClosePane { pane } => {
+                if let Err(response) = self.focus_control_target(pane) {
+                    return response;
+                }
+                self.handle_pane_command(PaneCommand::ClosePane, event_loop);
+                ControlResponse::Ok
             }
+            ControlRequest::GetText { pane } => {
+                let pane_id = pane.map_or_else(|| self.tab_manager.active_tab().pane_tree.focused_pane_id(), PaneId);
+                let Some(state) = self.pane_states.get(&pane_id) else {
+                    return ControlResponse::error(format!("no pane {}", pane_id.0));
+                };
+                let terminal = &state.terminal;
+                let lines = (0..terminal.rows() as i32)
+                    .map(|row| terminal.line_text(row).trim_end().to_string())
+                    .collect();
+                ControlResponse::Text { lines }
+            }
+            ControlRequest::SetFontScale { scale } => match self.set_font_scale_override(scale) {
+                Ok(()) => ControlResponse::Ok,
+                Err(message) => ControlResponse::error(message),
+            },
         }
+    }
+
+    /// Focus the pane a control request names (switching to its tab), or
+    /// keep the focused pane when it names none.
+    fn focus_control_target(&mut self, pane: Option<u32>) -> Result<PaneId, ControlResponse> {
+        let Some(pane) = pane else {
+            return Ok(self.tab_manager.active_tab().pane_tree.focused_pane_id());
+        };
+        let pane_id = PaneId(pane);
+        let Some(index) = self.tab_manager.tab_index_for_pane(pane_id) else {
+            return Err(ControlResponse::error(format!("no pane {pane}")));
+        };
+        self.tab_manager.select_tab(index);
+        self.tab_manager.active_tab_mut().pane_tree.set_focus(pane_id);
+        let (width, height) = self.window_size();
+        self.update_interaction_layout(width, height);
         if let Some(renderer) = &mut self.renderer {
             renderer.pane_damage_mut().force_full_damage_all();
         }
-        if let Some(window) = &self.window {
-            window.request_redraw();
+        Ok(pane_id)
+    }
+
+    /// Every pane of every tab, for `veloterm ctl list-panes`.
+    fn control_pane_list(&self) -> Vec<crate::control::protocol::PaneEntry> {
+        let active = self.tab_manager.active_index();
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        self.tab_manager
+            .tabs()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, tab)| {
+                tab.pane_tree.pane_ids().into_iter().map(move |id| (index, tab, id))
+            })
+            .map(|(index, tab, id)| {
+                let state = self.pane_states.get(&id);
+                crate::control::protocol::PaneEntry {
+                    id: id.0,
+                    tab: index,
+                    tab_title: tab.title.clone(),
+                    cwd: state.and_then(|s| s.terminal.shell_state().cwd.clone()),
+                    cols: state.map_or(0, |s| s.terminal.columns()),
+                    rows: state.map_or(0, |s| s.terminal.rows()),
+                    focused: index == active && id == focused,
+                }
+            })
+            .collect()
+    }
+
+    /// (Re)load the config script beside the config file and bind its key
+    /// callbacks. A broken script is logged and leaves no callbacks.
+    fn load_scripts(&mut self) {
+        let script = self
+            .config
+            .config_path
+            .as_deref()
+            .and_then(|path| self.app_config.script_path(path));
+        self.scripts = script.and_then(|path| match ScriptHost::load(&path) {
+            Ok(host) => {
+                log::info!("Loaded config script {}", path.display());
+                Some(host)
+            }
+            Err(e) => {
+                log::warn!("Config script failed: {e}");
+                None
+            }
+        });
+        let combos: Vec<&str> = self.scripts.iter().flat_map(|s| s.binding_combos()).collect();
+        for combo in self.keymap.set_script_bindings(combos) {
+            log::warn!("Invalid key combo '{combo}' bound by config script");
         }
+        self.script_tab = self.tab_manager.active_index();
     }
 
-    /// Handle a key event while the command palette is open.
-    fn handle_palette_key(
-        &mut self,
-        logical_key: &Key,
-        text: Option<&str>,
-        event_loop: &ActiveEventLoop,
-    ) {
-        let palette = match &mut self.palette_state {
-            Some(p) => p,
-            None => return,
+    /// What the config script's callbacks see of the window.
+    fn script_context(&self) -> ScriptContext {
+        ScriptContext { panes: self.control_pane_list(), active_tab: self.tab_manager.active_index() }
+    }
+
+    /// Run a key callback registered by the config script.
+    fn run_script_binding(&mut self, index: usize, event_loop: &ActiveEventLoop) {
+        let Some(scripts) = &self.scripts else {
+            return;
         };
+        let commands = scripts.run_binding(index, self.script_context());
+        self.run_script_commands(commands, event_loop);
+    }
 
-        match logical_key {
-            Key::Named(NamedKey::Escape) => {
-                self.input_mode = InputMode::Normal;
-                self.palette_state = None;
-                self.context_menu_visible = false;
-            }
-            Key::Named(NamedKey::ArrowUp) => {
-                palette.select_prev();
-            }
-            Key::Named(NamedKey::ArrowDown) => {
-                palette.select_next();
-            }
-            Key::Named(NamedKey::Enter) => {
-                if let Some(action) = palette.selected_action() {
-                    self.input_mode = InputMode::Normal;
-                    self.palette_state = None;
-                    self.dispatch_palette_action(action, event_loop);
+    /// Run the hooks for queued events, plus `tab_switched` when the active
+    /// tab changed since the last frame.
+    fn fire_script_events(&mut self, event_loop: &ActiveEventLoop) {
+        let active = self.tab_manager.active_index();
+        if active != self.script_tab {
+            let mut data = rhai::Map::new();
+            data.insert("index".into(), (active as rhai::INT).into());
+            data.insert("previous".into(), (self.script_tab as rhai::INT).into());
+            self.script_tab = active;
+            self.script_events.push((ScriptEvent::TabSwitched, data));
+        }
+        let events = std::mem::take(&mut self.script_events);
+        let Some(scripts) = &self.scripts else {
+            return;
+        };
+        let mut commands = Vec::new();
+        for (event, data) in events.into_iter().filter(|(event, _)| scripts.has_hooks(*event)) {
+            commands.extend(scripts.emit(event, data, self.script_context()));
+        }
+        self.run_script_commands(commands, event_loop);
+    }
+
+    fn run_script_commands(&mut self, commands: Vec<ScriptCommand>, event_loop: &ActiveEventLoop) {
+        for command in commands {
+            match command {
+                ScriptCommand::Control(request) => {
+                    if let ControlResponse::Error { message } = self.handle_control(request, event_loop) {
+                        log::warn!("Config script: {message}");
+                    }
                 }
-            }
-            Key::Named(NamedKey::Backspace) => {
-                palette.backspace();
-            }
-            Key::Character(s) => {
-                let t = text.unwrap_or(s.as_ref());
-                if let Some(ch) = t.chars().next() {
-                    if !ch.is_control() {
-                        palette.type_char(ch);
+                ScriptCommand::SetClipboard(text) => {
+                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                        if let Err(e) = clipboard.set_text(&text) {
+                            log::warn!("Config script clipboard error: {e}");
+                        }
                     }
                 }
             }
-            _ => {}
         }
     }
 
-    /// Dispatch a command palette action to the appropriate handler.
-    fn dispatch_palette_action(
-        &mut self,
-        action: PaletteAction,
-        event_loop: &ActiveEventLoop,
-    ) {
-        match action {
-            PaletteAction::SplitVertical => {
-                self.handle_pane_command(PaneCommand::SplitVertical, event_loop);
-            }
-            PaletteAction::SplitHorizontal => {
-                self.handle_pane_command(PaneCommand::SplitHorizontal, event_loop);
-            }
-            PaletteAction::ClosePane => {
-                self.handle_pane_command(PaneCommand::ClosePane, event_loop);
-            }
-            PaletteAction::FocusNextPane => {
-                let (width, height) = self.window_size();
-                let pgrid = self.pane_grid_bounds(width as f32, height as f32);
-                self.tab_manager.active_tab_mut().pane_tree.focus_direction(
-                    crate::pane::FocusDirection::Right,
-                    pgrid.width,
-                    pgrid.height,
-                );
+    /// Open or close the file browser / git review overlay.
+    fn handle_overlay_command(&mut self, command: OverlayCommand) {
+        match command {
+            OverlayCommand::ToggleFileBrowser => {
+                self.input_mode = crate::file_browser::toggle_file_browser(self.input_mode);
+                if self.input_mode == InputMode::FileBrowser {
+                    let cwd = self.active_pane_cwd();
+                    let state = self.file_browser_state.get_or_insert_with(
+                        crate::file_browser::FileBrowserState::new,
+                    );
+                    state.open(cwd);
+                }
             }
-            PaletteAction::FocusPrevPane => {
-                let (width, height) = self.window_size();
-                let pgrid = self.pane_grid_bounds(width as f32, height as f32);
-                self.tab_manager.active_tab_mut().pane_tree.focus_direction(
-                    crate::pane::FocusDirection::Left,
-                    pgrid.width,
-                    pgrid.height,
-                );
+            OverlayCommand::ToggleGitReview => {
+                self.input_mode = crate::git_review::toggle_git_review(self.input_mode);
+                if self.input_mode == InputMode::GitReview {
+                    let cwd = self.active_pane_cwd();
+                    let state = self.git_review_state.get_or_insert_with(
+                        crate::git_review::GitReviewState::new,
+                    );
+                    state.open_from_cwd(&cwd);
+                }
             }
-            PaletteAction::NewTab => {
-                self.handle_tab_command(TabCommand::NewTab, event_loop);
+        }
+    }
+
+    /// Open the search bar, or close it if it is open.
+    fn toggle_search(&mut self) {
+        if self.input_mode == InputMode::Search {
+            self.close_search();
+            if let Some(renderer) = &mut self.renderer {
+                renderer.pane_damage_mut().force_full_damage_all();
             }
-            PaletteAction::CloseTab => {
-                self.handle_tab_command(TabCommand::CloseTab, event_loop);
+        } else {
+            // Open search (close palette if open)
+            if self.input_mode == InputMode::CommandPalette {
+                self.palette_state = None;
             }
-            PaletteAction::NextTab => {
-                self.handle_tab_command(TabCommand::NextTab, event_loop);
+            self.open_search();
+        }
+    }
+
+    /// Enter or leave vi mode in the focused pane (when vi mode is enabled).
+    fn toggle_vi_mode(&mut self) {
+        if !self.app_config.vi_mode.enabled {
+            return;
+        }
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if let Some(state) = self.pane_states.get_mut(&focused_id) {
+            if state.vi_state.is_some() {
+                state.vi_state = None;
+                log::info!("Vi-mode deactivated for pane {:?}", focused_id);
+            } else {
+                // Enter vi-mode at terminal cursor position
+                let (row, col) = state.terminal.cursor_position();
+                let mut vi = crate::vi_mode::ViState::new(row, col);
+                vi.logical_lines = self.app_config.vi_mode.logical_lines;
+                state.vi_state = Some(vi);
+                log::info!("Vi-mode activated for pane {:?}", focused_id);
             }
-            PaletteAction::PrevTab => {
-                self.handle_tab_command(TabCommand::PrevTab, event_loop);
+        }
+    }
+
+    fn handle_view_command(&mut self, command: ViewCommand) {
+        match command {
+            ViewCommand::ToggleMarkdownPreview => {
+                if self.input_mode == InputMode::MarkdownPreview {
+                    self.input_mode = InputMode::Normal;
+                    let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                    if let Some(state) = self.pane_states.get_mut(&focused) {
+                        state.markdown_preview = None;
+                    }
+                } else {
+                    self.open_markdown_preview(None);
+                }
             }
-            PaletteAction::IncreaseFontSize => {
-                self.handle_app_command(AppCommand::IncreaseFontSize);
+            ViewCommand::ToggleSidebar => {
+                self.sidebar_visible = !self.sidebar_visible;
+                let (w, h) = self.window_size();
+                self.resize_all_panes(w, h);
             }
-            PaletteAction::DecreaseFontSize => {
-                self.handle_app_command(AppCommand::DecreaseFontSize);
+            ViewCommand::CycleTheme => {
+                let themes = TerminalTheme::available_themes();
+                let current_name = self.renderer.as_ref().map(|r| r.theme().name).unwrap_or("Warm Dark");
+                let current_idx = themes.iter().position(|(_, name)| *name == current_name).unwrap_or(0);
+                let next_idx = (current_idx + 1) % themes.len();
+                let (config_name, _) = themes[next_idx];
+                if let Some(new_theme) = self.app_config.colors.resolve_theme(config_name) {
+                    if let Some(r) = &mut self.renderer {
+                        r.set_theme(new_theme);
+                        r.pane_damage_mut().force_full_damage_all();
+                    }
+                    self.app_config.colors.theme = config_name.to_string();
+                    self.apply_background();
+                    self.signal_theme_change_to_shells();
+                    self.invalidate_minimaps();
+                }
             }
-            PaletteAction::ResetFontSize => {
-                self.handle_app_command(AppCommand::ResetFontSize);
+            ViewCommand::CycleColorFilter => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let current = self.pane_states.get(&focused_id).map(|state| self.pane_color_filter(state));
+                if let (Some(state), Some(current)) = (self.pane_states.get_mut(&focused_id), current) {
+                    let next = current.next();
+                    state.color_filter = Some(next);
+                    log::info!("Color filter for pane {focused_id:?}: {}", next.as_str());
+                }
             }
-            PaletteAction::Copy => {
+            ViewCommand::ToggleColumnRulers => {
+                let default = self.app_config.guides.show_rulers;
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                let theme = self.renderer.as_ref().map(|r| r.theme()).unwrap_or(&crate::config::theme::DARK);
                 if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                    if let Some(ref sel) = state.mouse_selection.active_selection {
-                        let cells = crate::terminal::grid_bridge::extract_grid_cells(&state.terminal, theme);
-                        let cols = state.terminal.columns();
-                        let display_offset = state.terminal.display_offset();
-                        let text_str = crate::input::selection::selected_text(&cells, sel, cols, display_offset);
-                        if !text_str.is_empty() {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                let _ = clipboard.set_text(&text_str);
-                            }
-                        }
-                    }
+                    state.guides.rulers = Some(!state.guides.rulers.unwrap_or(default));
                 }
             }
-            PaletteAction::Paste => {
+            ViewCommand::ToggleCrosshair => {
+                let default = self.app_config.guides.crosshair;
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                    if let Ok(text_str) = clipboard.get_text() {
-                        if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                            let bytes = crate::input::clipboard::paste_bytes(&text_str, true);
-                            if let Err(e) = state.pty.write(&bytes) {
-                                log::warn!("PTY paste write error: {e}");
-                            }
-                        }
-                    }
+                if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                    state.guides.crosshair = Some(!state.guides.crosshair.unwrap_or(default));
                 }
             }
-            PaletteAction::SelectAll => {
+            ViewCommand::ToggleSoftWrap => {
+                let default = self.app_config.guides.soft_wrap;
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
                 if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                    let rows = state.terminal.rows();
-                    let cols = state.terminal.columns();
-                    state.mouse_selection.active_selection = Some(
-                        crate::input::selection::Selection {
-                            start: (0, 0),
-                            end: (rows.saturating_sub(1) as i32, cols.saturating_sub(1)),
-                            selection_type: crate::input::selection::SelectionType::Range,
-                            start_side: crate::input::selection::Side::Left,
-                            end_side: crate::input::selection::Side::Right,
-                        },
-                    );
+                    state.guides.soft_wrap = Some(!state.guides.soft_wrap.unwrap_or(default));
+                    state.mouse_selection.clear_selection();
+                }
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.pane_damage_mut().force_full_damage_all();
                 }
             }
-            PaletteAction::ClearScrollback => {
-                self.handle_app_command(AppCommand::ClearScrollback);
-            }
-            PaletteAction::OpenSearch => {
-                self.input_mode = InputMode::Search;
-                self.search_state.is_active = true;
-            }
-            PaletteAction::ToggleViMode => {
+            ViewCommand::ToggleControlBytes => {
                 let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                if self.app_config.vi_mode.enabled {
-                    if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                        if state.vi_state.is_some() {
-                            state.vi_state = None;
-                        } else {
-                            let (row, col) = state.terminal.cursor_position();
-                            state.vi_state = Some(crate::vi_mode::ViState::new(row, col));
-                        }
+                if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                    match state.control_bytes.take() {
+                        Some(mut view) => state.terminal.feed(&view.finish()),
+                        None => state.control_bytes = Some(crate::terminal::control_bytes::ControlByteView::new()),
                     }
+                    log::info!("Pane {:?} control byte view: {}", focused_id, state.control_bytes.is_some());
                 }
             }
-            PaletteAction::NewWindow => {
-                self.handle_app_command(AppCommand::NewWindow);
-            }
-            PaletteAction::ToggleSidebar => {
-                self.sidebar_visible = !self.sidebar_visible;
+            ViewCommand::ToggleMinimap => {
+                self.minimap_visible = !self.minimap_visible;
                 let (w, h) = self.window_size();
                 self.resize_all_panes(w, h);
             }
-            PaletteAction::PreviewMarkdown => {
-                self.open_markdown_preview(None);
+            ViewCommand::ExportLineView => {
+                self.export_line_view();
             }
-            PaletteAction::CycleTheme => {
-                let themes = TerminalTheme::available_themes();
-                let current_name = self.renderer.as_ref().map(|r| r.theme().name).unwrap_or("Warm Dark");
-                let current_idx = themes.iter().position(|(_, name)| *name == current_name).unwrap_or(0);
-                let next_idx = (current_idx + 1) % themes.len();
-                let (config_name, _) = themes[next_idx];
-                if let Some(new_theme) = TerminalTheme::from_name(config_name) {
-                    if let Some(r) = &mut self.renderer {
-                        r.set_theme(new_theme);
-                        r.pane_damage_mut().force_full_damage_all();
+            ViewCommand::Screenshot(scope, target) => {
+                self.request_screenshot(scope, target);
+            }
+            ViewCommand::TogglePaneRecording => {
+                if self.pane_recording.is_some() {
+                    self.stop_pane_recording();
+                } else {
+                    self.start_pane_recording();
+                }
+            }
+        }
+    }
+
+    /// A pane's rect on the surface, if it is shown in the active tab.
+    fn pane_surface_rect(&self, pane_id: PaneId) -> Option<Rect> {
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let (_, rect) = layout.iter().find(|(id, _)| *id == pane_id)?;
+        Some(Rect::new(rect.x + pgrid.x, rect.y + pgrid.y, rect.width, rect.height))
+    }
+
+    /// Start recording the focused pane into a GIF under the [recording]
+    /// folder.
+    fn start_pane_recording(&mut self) {
+        let config = &self.app_config.recording;
+        let home = std::env::var("HOME").map(std::path::PathBuf::from).unwrap_or_else(|_| ".".into());
+        let dir = crate::screenshot::output_dir(&config.directory, &home);
+        let name = crate::screenshot::file_name(
+            &config.filename,
+            crate::screenshot::ScreenshotScope::Pane,
+            chrono::Local::now().naive_local(),
+        );
+        let path = crate::screenshot::unique_path(dir.join(name));
+        let pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let max_duration = std::time::Duration::from_secs(config.max_duration.into());
+        let started = std::fs::create_dir_all(&dir).and_then(|()| {
+            crate::pane_recording::PaneRecording::start(pane, path.clone(), config.max_fps, max_duration)
+        });
+        match started {
+            Ok(recording) => {
+                log::info!("Recording pane to {}", path.display());
+                self.pane_recording = Some(recording);
+                // The first frame is taken whether or not the pane changed
+                if let Some(window) = &self.window {
+                    window.request_redraw();
+                }
+            }
+            Err(e) => log::error!("Could not start recording to {}: {e}", path.display()),
+        }
+    }
+
+    /// Stop the pane recording; its GIF is finished in the background.
+    fn stop_pane_recording(&mut self) {
+        let Some(recording) = self.pane_recording.take() else {
+            return;
+        };
+        recording.finish(|result| match result {
+            Ok((path, frames)) => log::info!("Recording saved: {} ({frames} frames)", path.display()),
+            Err(e) => log::error!("Recording failed: {e}"),
+        });
+    }
+
+    /// Queue a screenshot of `scope` for the next frame. The selection
+    /// scope needs a selection in view in the focused pane.
+    fn request_screenshot(&mut self, scope: crate::screenshot::ScreenshotScope, target: crate::screenshot::ScreenshotTarget) {
+        use crate::screenshot::ScreenshotScope;
+
+        let area = if scope == ScreenshotScope::Window {
+            None
+        } else {
+            let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+            let Some(pane) = self.pane_surface_rect(focused) else {
+                return;
+            };
+            if scope == ScreenshotScope::Pane {
+                Some(pane)
+            } else {
+                match self.selection_area(focused, &pane) {
+                    Some(area) => Some(area),
+                    None => {
+                        log::info!("Screenshot: no selection in view");
+                        return;
                     }
-                    self.app_config.colors.theme = config_name.to_string();
-                    self.signal_theme_change_to_shells();
+                }
+            }
+        };
+        self.pending_screenshot = Some(crate::screenshot::PendingScreenshot { scope, target, area });
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Surface pixels covered by a pane's selection, where `pane` is the
+    /// pane's rect on the surface.
+    fn selection_area(&self, pane_id: PaneId, pane: &Rect) -> Option<Rect> {
+        let state = self.pane_states.get(&pane_id)?;
+        let selection = state.mouse_selection.active_selection.as_ref()?;
+        let renderer = self.renderer.as_ref()?;
+        let (first_row, first_col, last_row, last_col) = crate::screenshot::selection_cells(
+            selection,
+            state.terminal.display_offset(),
+            state.terminal.rows(),
+            state.terminal.columns(),
+        )?;
+        let [pad_top, _, pad_left, _] = renderer.padding();
+        let (cell_width, cell_height) = (renderer.cell_width(), renderer.cell_height());
+        Some(Rect::new(
+            pane.x + pad_left + first_col as f32 * cell_width,
+            pane.y + PANE_HEADER_HEIGHT + pad_top + first_row as f32 * cell_height,
+            (last_col - first_col + 1) as f32 * cell_width,
+            (last_row - first_row + 1) as f32 * cell_height,
+        ))
+    }
+
+    /// Save or copy a screenshot cut from a rendered frame, then flash the
+    /// captured area.
+    fn finish_screenshot(&mut self, pending: crate::screenshot::PendingScreenshot, frame: image::RgbaImage) {
+        use crate::screenshot::{ScreenshotScope, ScreenshotTarget};
+
+        let (width, height) = frame.dimensions();
+        let area = pending.area.unwrap_or_else(|| Rect::new(0.0, 0.0, width as f32, height as f32));
+        let Some(image) = crate::screenshot::crop(&frame, &area) else {
+            return;
+        };
+        let result = match pending.target {
+            ScreenshotTarget::Clipboard => crate::screenshot::copy_to_clipboard(&image)
+                .map(|()| log::info!("Screenshot ({}) copied to the clipboard", pending.scope.name()))
+                .map_err(|e| e.to_string()),
+            ScreenshotTarget::File => {
+                let config = &self.app_config.screenshot;
+                let home = std::env::var("HOME").map(std::path::PathBuf::from).unwrap_or_else(|_| ".".into());
+                let dir = crate::screenshot::output_dir(&config.directory, &home);
+                let name = crate::screenshot::file_name(&config.filename, pending.scope, chrono::Local::now().naive_local());
+                let path = crate::screenshot::unique_path(dir.join(name));
+                std::fs::create_dir_all(&dir)
+                    .map_err(|e| e.to_string())
+                    .and_then(|()| image.save(&path).map_err(|e| e.to_string()))
+                    .map(|()| log::info!("Screenshot saved: {}", path.display()))
+                    .inspect(|()| {
+                        // take-screenshot.sh falls back to reading this copy
+                        if let (ScreenshotScope::Window, Ok(dir)) =
+                            (pending.scope, std::env::var("VELOTERM_PROJECT_DIR"))
+                        {
+                            let latest = std::path::PathBuf::from(dir).join("veloterm-latest.png");
+                            if let Err(e) = image.save(&latest) {
+                                log::warn!("Could not write {}: {e}", latest.display());
+                            }
+                        }
+                    })
+            }
+        };
+        match result {
+            Ok(()) if self.app_config.screenshot.flash => {
+                self.screenshot_flash = Some((area, std::time::Instant::now() + crate::screenshot::FLASH_DURATION));
+            }
+            Ok(()) => {}
+            Err(e) => log::error!("Screenshot failed: {e}"),
+        }
+    }
+
+    /// The copy mode set by `selection.copy_mode`.
+    fn default_copy_mode(&self) -> CopyMode {
+        CopyMode::from_config_str(&self.app_config.selection.copy_mode).unwrap_or(CopyMode::Logical)
+    }
+
+    /// Copy the focused pane's selection to the clipboard as `mode` text,
+    /// with secrets masked when `redact`. Reads the full grid, so selections
+    /// reaching into scrollback copy whole.
+    fn copy_selection(&mut self, mode: CopyMode, redact: bool) {
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let Some(state) = self.pane_states.get(&focused_id) else {
+            return;
+        };
+        let Some(ref sel) = state.mouse_selection.active_selection else {
+            return;
+        };
+        let text = crate::terminal::grid_bridge::grid_copy_text(&state.terminal, sel, mode);
+        let text = if redact { self.redactor.redact(&text) } else { text.as_str().into() };
+        if !text.is_empty() {
+            if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                let _ = clipboard.set_text(text.as_ref());
+            }
+        }
+    }
+
+    fn handle_edit_command(&mut self, command: EditCommand) {
+        match command {
+            EditCommand::Copy => self.copy_selection(self.default_copy_mode(), self.app_config.redaction.copy),
+            EditCommand::CopyAs(mode) => self.copy_selection(mode, self.app_config.redaction.copy),
+            EditCommand::CopyRedacted(redact) => self.copy_selection(self.default_copy_mode(), redact),
+            EditCommand::Paste => self.paste_clipboard(false),
+            EditCommand::PasteSlowly => self.paste_clipboard(true),
+            EditCommand::Hints(action) => self.start_hint_mode(action),
+            EditCommand::QuickSelect => self.start_quick_select(),
+            EditCommand::ListViRegisters => {
+                self.register_list = match self.register_list {
+                    Some(_) => None,
+                    None => Some(crate::vi_mode::registers::RegisterList::default()),
+                };
+            }
+            EditCommand::SelectAll => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                    let rows = state.terminal.rows();
+                    let cols = state.terminal.columns();
+                    state.mouse_selection.active_selection = Some(
+                        crate::input::selection::Selection {
+                            start: (0, 0),
+                            end: (rows.saturating_sub(1) as i32, cols.saturating_sub(1)),
+                            selection_type: crate::input::selection::SelectionType::Range,
+                            start_side: crate::input::selection::Side::Left,
+                            end_side: crate::input::selection::Side::Right,
+                        },
+                    );
                 }
             }
         }
-        if let Some(window) = &self.window {
-            window.request_redraw();
-        }
     }
 
     /// Open a markdown preview overlay for the focused pane.
@@ -1676,6 +4581,22 @@ impl App {
         }
     }
 
+    /// Show the help page in the focused pane's markdown preview overlay.
+    fn open_help(&mut self) {
+        let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let text = crate::help::help_markdown(&self.keymap, &self.app_config);
+        if let Some(pane) = self.pane_states.get_mut(&focused_id) {
+            pane.markdown_preview = Some(crate::markdown_preview::MarkdownPreviewState::from_markdown(
+                crate::help::HELP_TITLE,
+                &text,
+            ));
+            self.input_mode = InputMode::MarkdownPreview;
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
     /// Convert a winit key event to a character for vi-mode processing.
     fn key_to_vi_char(logical_key: &Key, text: Option<&str>) -> Option<char> {
         match logical_key {
@@ -1703,7 +4624,7 @@ impl App {
         use crate::vi_mode::ViAction;
 
         match action {
-            ViAction::Motion(motion) => {
+            ViAction::Motion(_) | ViAction::SelectTextObject(_) => {
                 if let Some(state) = self.pane_states.get_mut(&pane_id) {
                     if let Some(ref mut vi) = state.vi_state {
                         let cols = state.terminal.cols();
@@ -1728,8 +4649,15 @@ impl App {
                             char_at_fn: &|row, col| {
                                 grid.get(row).and_then(|r| r.get(col).copied())
                             },
+                            wrapped_fn: &|row| crate::terminal::grid_bridge::buffer_row_wraps(&state.terminal, row),
+                            wide_spacer_fn: &|row, col| state.terminal.is_wide_spacer(row, col),
                         };
-                        vi.apply_motion(&motion, &ctx);
+                        match action {
+                            ViAction::SelectTextObject(object) => vi.select_text_object(&object, &ctx),
+                            ViAction::Motion(motion) => vi.apply_motion(&motion, &ctx),
+                            _ => {}
+                        }
+                        state.h_scroll.reveal(vi.cursor.col, cols);
                     }
                 }
             }
@@ -1742,19 +4670,27 @@ impl App {
             ViAction::Yank => {
                 let theme = self.renderer.as_ref().map(|r| r.theme()).unwrap_or(&crate::config::theme::DARK);
                 if let Some(state) = self.pane_states.get_mut(&pane_id) {
-                    if let Some(ref vi) = state.vi_state {
+                    if let Some(ref mut vi) = state.vi_state {
                         let cols = state.terminal.cols();
                         let cells = crate::terminal::grid_bridge::extract_grid_cells(
                             &state.terminal, theme,
                         );
                         let display_offset = state.terminal.display_offset();
-                        if let Some(text) = vi.yank_text(&cells, cols, display_offset) {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                if let Err(e) = clipboard.set_text(&text) {
-                                    log::warn!("Clipboard write error: {e}");
-                                } else {
-                                    log::info!("Yanked {} bytes to clipboard", text.len());
+                        if let Some((register, mut text)) = vi.take_yank(&cells, cols, display_offset) {
+                            if self.app_config.redaction.copy {
+                                text = self.redactor.redact(&text).into_owned();
+                            }
+                            match self.vi_registers.yank(register, text) {
+                                Some(text) => {
+                                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                                        if let Err(e) = clipboard.set_text(&text) {
+                                            log::warn!("Clipboard write error: {e}");
+                                        } else {
+                                            log::info!("Yanked {} bytes to clipboard", text.len());
+                                        }
+                                    }
                                 }
+                                None => log::info!("Yanked to vi register {register:?}"),
                             }
                         }
                     }
@@ -1766,11 +4702,11 @@ impl App {
                     if let Some(ref mut vi) = state.vi_state {
                         let query = vi.search_query.clone();
                         if !query.is_empty() {
-                            let lines = crate::terminal::grid_bridge::extract_text_lines(
+                            let lines = crate::terminal::grid_bridge::extract_text_maps(
                                 &state.terminal,
                             );
                             let engine = crate::search::SearchEngine::new();
-                            let result = engine.search(&query, &lines);
+                            let result = engine.search_maps(&query, &lines);
                             if let Some(first) = result.matches.first() {
                                 vi.move_to_match(first.row as usize, first.start_col);
                             }
@@ -1783,11 +4719,11 @@ impl App {
                     if let Some(ref mut vi) = state.vi_state {
                         let query = vi.search_query.clone();
                         if !query.is_empty() {
-                            let lines = crate::terminal::grid_bridge::extract_text_lines(
+                            let lines = crate::terminal::grid_bridge::extract_text_maps(
                                 &state.terminal,
                             );
                             let engine = crate::search::SearchEngine::new();
-                            let result = engine.search(&query, &lines);
+                            let result = engine.search_maps(&query, &lines);
                             if !result.matches.is_empty() {
                                 let cursor_row = vi.cursor.row as i32;
                                 let cursor_col = vi.cursor.col;
@@ -1839,22 +4775,134 @@ impl App {
         }
     }
 
-    /// Re-run search after query changes (incremental search).
-    fn run_incremental_search(&mut self) {
+    /// Scan the next chunk of scrollback for every pane with a search in
+    /// progress, first restarting searches whose query was edited and then
+    /// left alone for `search.debounce_ms` (incremental search). The whole
+    /// scrollback is searched, newest lines first. Jumps to the focused
+    /// pane's first match once one is found.
+    fn advance_searches(&mut self) {
+        let group = self.search_group();
+        let search_config = &self.app_config.search;
+        let error_pattern = if search_config.match_strip {
+            search_config.error_pattern.as_str()
+        } else {
+            ""
+        };
+        let now = std::time::Instant::now();
+        let debounce = std::time::Duration::from_millis(search_config.debounce_ms);
+        let mut jump = false;
+        for (pane_id, state) in self.pane_states.iter_mut() {
+            if state.search.query_due(now, debounce) {
+                let query = state.search.query.clone();
+                let top = -(state.terminal.history_size() as i32);
+                let bottom = state.terminal.rows() as i32;
+                state.search.start_query(&query, error_pattern, top..bottom);
+            }
+            if !state.search.is_scanning() {
+                continue;
+            }
+            let had_match = state.search.current_match().is_some();
+            let terminal = &state.terminal;
+            state
+                .search
+                .advance(crate::search::SCAN_CHUNK_LINES, terminal);
+            if group.contains(pane_id) && !had_match && state.search.current_match().is_some() {
+                jump = true;
+            }
+        }
+        if jump {
+            self.scroll_to_current_match();
+        }
+    }
+
+    /// Search state of the focused pane.
+    fn focused_search_mut(&mut self) -> Option<&mut SearchState> {
         let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-        if let Some(state) = self.pane_states.get(&focused) {
-            let lines = crate::terminal::grid_bridge::extract_text_lines(&state.terminal);
-            self.search_state.set_query(&self.search_state.query.clone(), &lines);
+        self.pane_states.get_mut(&focused).map(|state| &mut state.search)
+    }
+
+    /// The focused pane and its scroll-locked partner, which searches
+    /// alongside it.
+    fn search_group(&self) -> Vec<PaneId> {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        std::iter::once(focused).chain(self.scroll_locks.partner(focused)).collect()
+    }
+
+    /// Search state of the focused pane's scroll-locked partner.
+    fn linked_search_mut(&mut self) -> Option<&mut SearchState> {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let partner = self.scroll_locks.partner(focused)?;
+        self.pane_states.get_mut(&partner).map(|state| &mut state.search)
+    }
+
+    /// Give the scroll-locked partner the focused pane's search: open or
+    /// closed, same query and options. A changed query is rerun there.
+    fn sync_linked_search(&mut self) {
+        let Some((active, query, options)) = self
+            .search_state()
+            .map(|search| (search.is_active, search.query.clone(), search.options()))
+        else {
+            return;
+        };
+        let Some(linked) = self.linked_search_mut() else {
+            return;
+        };
+        linked.is_active = active;
+        if linked.query != query || linked.options() != options {
+            linked.query = query;
+            linked.set_options(options);
+            linked.queue_query(std::time::Instant::now());
+        }
+    }
+
+    /// Open (or resume) search in the focused pane.
+    fn open_search(&mut self) {
+        if let Some(search) = self.focused_search_mut() {
+            search.is_active = true;
+            self.input_mode = InputMode::Search;
+            self.sync_linked_search();
+        }
+    }
+
+    /// Close search in the focused pane (and its scroll-locked partner),
+    /// dropping its query and matches.
+    fn close_search(&mut self) {
+        if let Some(search) = self.focused_search_mut() {
+            search.is_active = false;
+            search.clear();
+        }
+        if let Some(linked) = self.linked_search_mut() {
+            linked.is_active = false;
+            linked.clear();
+        }
+        if self.input_mode == InputMode::Search {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    /// Keep search input mode in step with the focused pane after focus
+    /// moves (click, tab switch, pane close): typing goes to the search bar
+    /// only if the newly focused pane has an open search.
+    fn sync_search_mode(&mut self) {
+        let active = self.search_state().is_some_and(|s| s.is_active);
+        match self.input_mode {
+            InputMode::Normal if active => self.input_mode = InputMode::Search,
+            InputMode::Search if !active => self.input_mode = InputMode::Normal,
+            _ => {}
         }
     }
 
-    /// Scroll the terminal viewport to show the current search match.
+    /// Scroll the terminal viewport to show the current search match, in
+    /// the focused pane and its scroll-locked partner.
     fn scroll_to_current_match(&mut self) {
-        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-        if let (Some(target_row), Some(state)) = (
-            self.search_state.scroll_target(),
-            self.pane_states.get_mut(&focused),
-        ) {
+        for pane in self.search_group() {
+            let Some((target_row, state)) = self
+                .pane_states
+                .get_mut(&pane)
+                .and_then(|state| state.search.scroll_target().map(|row| (row, state)))
+            else {
+                continue;
+            };
             let viewport_rows = state.terminal.rows();
             let current_offset = state.terminal.display_offset();
             let max_offset = state.terminal.history_size();
@@ -1863,20 +4911,96 @@ impl App {
                 viewport_rows,
                 current_offset,
                 max_offset,
+                crate::search::ScrollMargin::from_config(&self.app_config.search),
             ) {
                 state.terminal.set_display_offset(new_offset);
             }
         }
     }
 
+    /// Jump to the match strip tick under a click in the focused pane.
+    /// Returns true if the click hit a tick.
+    /// Whether a window point is over the focused pane's open search bar.
+    fn over_search_bar(&self, x: f32, y: f32) -> bool {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        if !self.pane_states.get(&focused).is_some_and(|state| state.search.is_active) {
+            return false;
+        }
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some(rect) = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r) else {
+            return false;
+        };
+        // Matches the iced search bar: 260x30 at the pane's top-right, 8px in
+        let bar = Rect::new(pgrid.x + rect.x + rect.width - 268.0, pgrid.y + rect.y + 8.0, 260.0, 30.0);
+        bar.contains_point(x, y)
+    }
+
+    fn match_strip_click(&mut self, x: f32, y: f32) -> bool {
+        if !self.app_config.search.match_strip {
+            return false;
+        }
+        let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let (width, height) = self.window_size();
+        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
+        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
+        let Some(rect) = layout.iter().find(|(id, _)| *id == focused).map(|(_, r)| *r) else {
+            return false;
+        };
+        let Some(state) = self.pane_states.get_mut(&focused) else {
+            return false;
+        };
+        if !state.search.is_active {
+            return false;
+        }
+        let history_size = state.terminal.history_size();
+        let Some(strip) = crate::search::minimap::match_strip(
+            rect.x + pgrid.x,
+            rect.y + pgrid.y,
+            rect.width - Self::PANE_GRID_PADDING * 2.0,
+            rect.height - Self::PANE_GRID_PADDING * 2.0,
+            padding,
+            history_size,
+            state.terminal.rows(),
+            &state.search.matches,
+            state.search.current_index,
+            &state.search.error_rows,
+        ) else {
+            return false;
+        };
+        let Some(marker) = crate::search::minimap::strip_hit_test(&strip, x, y) else {
+            return false;
+        };
+        if let Some(index) = marker.match_index {
+            state.search.current_index = index;
+        }
+        if let Some(offset) = crate::search::compute_scroll_offset(
+            marker.row,
+            state.terminal.rows(),
+            state.terminal.display_offset(),
+            history_size,
+            crate::search::ScrollMargin::from_config(&self.app_config.search),
+        ) {
+            state.scroll_state.set_target(offset, history_size);
+            state.terminal.set_display_offset(offset);
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.pane_damage_mut().force_full_damage_all();
+        }
+        true
+    }
+
     /// Get the current input mode (for testing).
     pub fn input_mode(&self) -> InputMode {
         self.input_mode
     }
 
-    /// Get the search state (for testing).
-    pub fn search_state(&self) -> &SearchState {
-        &self.search_state
+    /// Get the focused pane's search state (for testing).
+    pub fn search_state(&self) -> Option<&SearchState> {
+        let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+        self.pane_states.get(&focused).map(|state| &state.search)
     }
 
     /// Run the application event loop. This blocks until the window is closed.
@@ -1885,8 +5009,12 @@ impl App {
         let proxy = event_loop.create_proxy();
         self.event_proxy = Some(proxy.clone());
 
+        // Listen for `veloterm ctl`; shells find the socket in $VELOTERM_SOCKET
+        let _control_server = Self::start_control_server(proxy.clone());
+
         // Start config file watcher (best-effort — non-fatal if it fails)
-        let _config_watcher = Self::start_config_watcher(&self.app_config, proxy.clone());
+        let config_path = self.config.config_path.clone().unwrap_or_else(crate::config::paths::default_config_path);
+        let _config_watcher = Self::start_config_watcher(&self.app_config, &config_path, proxy.clone());
 
         // Start quick terminal global hotkey (if enabled)
         let _hotkey_manager = if self.app_config.quick_terminal.enabled {
@@ -1918,18 +5046,37 @@ impl App {
         Ok(())
     }
 
+    /// Listen on this window's control socket, passing requests to the
+    /// event loop.
+    fn start_control_server(proxy: EventLoopProxy<UserEvent>) -> Option<crate::control::server::ControlServer> {
+        let socket = crate::control::socket_path(std::process::id());
+        match crate::control::server::ControlServer::start(&socket, move |request, reply| {
+            let _ = proxy.send_event(UserEvent::Control(request, reply));
+        }) {
+            Ok(server) => {
+                std::env::set_var(crate::control::SOCKET_ENV, server.socket());
+                log::info!("Control socket listening on {}", socket.display());
+                Some(server)
+            }
+            Err(e) => {
+                log::warn!("Failed to start control socket on {}: {e}", socket.display());
+                None
+            }
+        }
+    }
+
     /// Start watching the config file and send reload events via the proxy.
     fn start_config_watcher(
         config: &Config,
+        config_path: &std::path::Path,
         proxy: EventLoopProxy<UserEvent>,
     ) -> Option<crate::config::watcher::ConfigWatcher> {
-        let config_path = Self::config_file_path();
         if !config_path.exists() {
             log::info!("No config file at {}, skipping watcher", config_path.display());
             return None;
         }
         match crate::config::watcher::ConfigWatcher::new(
-            &config_path,
+            config_path,
             config.clone(),
             move |new_config, delta| {
                 let _ = proxy.send_event(UserEvent::ConfigReloaded(new_config, delta));
@@ -1946,17 +5093,11 @@ impl App {
         }
     }
 
-    /// Get the config file path (~/.config/veloterm/config.toml).
-    fn config_file_path() -> std::path::PathBuf {
-        let home = std::env::var("HOME")
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(|_| std::path::PathBuf::from("."));
-        home.join(".config").join("veloterm").join("config.toml")
-    }
 }
 
 impl ApplicationHandler<UserEvent> for App {
-    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: UserEvent) {
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: UserEvent) {
+        self.frame_pacer.mark_dirty();
         match event {
             UserEvent::ConfigReloaded(new_config, delta) => {
                 self.handle_config_reload(new_config, delta);
@@ -1964,6 +5105,63 @@ impl ApplicationHandler<UserEvent> for App {
             UserEvent::QuickTerminalToggle => {
                 self.toggle_quick_terminal();
             }
+            UserEvent::GitSegmentReady(cwd, segment) => {
+                let changed = self.git_segments.complete(&cwd, segment, std::time::Instant::now());
+                if changed {
+                    if let Some(window) = &self.window {
+                        window.request_redraw();
+                    }
+                }
+            }
+            UserEvent::MenuAction(action) => {
+                self.dispatch_action(action, event_loop);
+            }
+            UserEvent::OpenAtFolder(dir) => {
+                self.open_tab_at(&dir);
+            }
+            UserEvent::PasteChunk(pane_id, bytes, last) => {
+                self.write_paste_chunk(pane_id, &bytes, last);
+            }
+            UserEvent::Control(request, reply) => {
+                let response = self.handle_control(request, event_loop);
+                let _ = reply.send(response);
+                if let Some(window) = &self.window {
+                    window.request_redraw();
+                }
+            }
+        }
+    }
+
+    /// Tick the frame loop when the next frame could be due.
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        if !matches!(cause, StartCause::ResumeTimeReached { .. }) {
+            return;
+        }
+        let Some(window) = &self.window else {
+            return;
+        };
+        if self.frame_pacer.is_hidden() {
+            // Hidden windows may never get a redraw; still read the shells
+            let window_id = window.id();
+            self.window_event(event_loop, window_id, WindowEvent::RedrawRequested);
+        } else {
+            window.request_redraw();
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            let next = self.frame_pacer.next_tick(std::time::Instant::now());
+            event_loop.set_control_flow(ControlFlow::WaitUntil(next));
+        }
+    }
+
+    /// Build the macOS menu bar from the live keymap so its Cmd shortcuts
+    /// match `[keys]`.
+    #[cfg(target_os = "macos")]
+    fn install_menu_bar(&self) {
+        if let Some(proxy) = &self.event_proxy {
+            crate::platform::macos::install_menu_bar(&self.keymap, proxy.clone());
         }
     }
 
@@ -1971,7 +5169,37 @@ impl ApplicationHandler<UserEvent> for App {
         if self.window.is_some() {
             return;
         }
-        let attrs = self.config.to_window_attributes();
+        if self.app_config.command_hints.enabled {
+            crate::command_hint::prewarm_path_index();
+        }
+        self.load_scripts();
+        // Read the saved session first so the window opens at its size. A
+        // window attached to the daemon shows its shells instead.
+        let session = if self.config.daemon_socket.is_some() || self.launch.is_some() || !self.launch_grid.is_empty() {
+            None
+        } else {
+            self.session_to_restore()
+        };
+        if let Some(saved) = session.as_ref().and_then(|s| s.window) {
+            self.config.width = saved.width;
+            self.config.height = saved.height;
+            self.font_scale_override = saved.font_scale;
+        }
+        #[allow(unused_mut)]
+        let mut attrs = self.config.to_window_attributes();
+        // A launcher's startup-notification token (XDG_ACTIVATION_TOKEN or
+        // DESKTOP_STARTUP_ID) lets the compositor focus the new window and
+        // end the launch feedback; clear it so shells don't inherit it
+        #[cfg(target_os = "linux")]
+        {
+            use winit::platform::startup_notify::{
+                EventLoopExtStartupNotify, WindowAttributesExtStartupNotify,
+            };
+            if let Some(token) = event_loop.read_token_from_env() {
+                attrs = attrs.with_activation_token(token);
+                winit::platform::startup_notify::reset_activation_token_env();
+            }
+        }
         match event_loop.create_window(attrs) {
             Ok(window) => {
                 let size = window.inner_size();
@@ -1983,10 +5211,14 @@ impl ApplicationHandler<UserEvent> for App {
                     scale
                 );
 
+                // Composed (CJK) input from ibus/fcitx arrives as Ime::Commit
+                #[cfg(target_os = "linux")]
+                window.set_ime_allowed(true);
+
                 let window = Arc::new(window);
 
                 // Resolve theme from config
-                let theme = TerminalTheme::from_name(&self.app_config.colors.theme).unwrap_or_else(|| {
+                let theme = self.app_config.colors.resolve_theme(&self.app_config.colors.theme).unwrap_or_else(|| {
                     log::warn!(
                         "Unknown theme '{}', falling back to warm_dark",
                         self.app_config.colors.theme
@@ -2002,10 +5234,14 @@ impl ApplicationHandler<UserEvent> for App {
                     theme.bg_surface.g as f64,
                     theme.bg_surface.b as f64,
                 );
+                // Replace winit's default menu, whose Quit skips session saving
+                #[cfg(target_os = "macos")]
+                self.install_menu_bar();
 
                 let font_size = self.app_config.font.size as f32;
                 let font_family = self.app_config.font.family.as_str();
                 let line_height = self.app_config.font.line_height as f32;
+                let gpu = crate::renderer::gpu::GpuPreference::from_config_str(&self.app_config.renderer.gpu);
 
                 // Initialize renderer
                 match pollster::block_on(crate::renderer::Renderer::new(
@@ -2014,6 +5250,10 @@ impl ApplicationHandler<UserEvent> for App {
                     font_size,
                     font_family,
                     line_height,
+                    self.app_config.font.ligatures,
+                    Subpixel::from_config_str(&self.app_config.font.subpixel_antialiasing).unwrap_or_default(),
+                    self.font_scale(),
+                    &gpu,
                 )) {
                     Ok(mut renderer) => {
                         log::info!("Renderer initialized");
@@ -2031,12 +5271,19 @@ impl ApplicationHandler<UserEvent> for App {
                         );
 
                         self.renderer = Some(renderer);
-
-                        // Try to restore a previous session first
-                        let restored = self.try_restore_session(
-                            size.width as f32,
-                            size.height as f32,
-                        );
+                        self.apply_present_mode();
+                        self.apply_window_opacity();
+                        self.apply_background();
+
+                        // Try to restore a previous session or the daemon's panes first
+                        let restored = match session {
+                            Some(session) => self.try_restore_session(
+                                session,
+                                size.width as f32,
+                                size.height as f32,
+                            ),
+                            None => self.attach_daemon_panes(size.width as f32, size.height as f32),
+                        };
 
                         if !restored {
                             // No session restored — spawn default initial pane
@@ -2045,7 +5292,20 @@ impl ApplicationHandler<UserEvent> for App {
                             let pgrid = self.pane_grid_bounds(size.width as f32, size.height as f32);
                             let rect = Rect::new(0.0, 0.0, pgrid.width, pgrid.height);
                             let (cols, rows) = self.grid_dims_for_rect(&rect);
-                            self.spawn_pane(initial_pane_id, cols, rows);
+                            if self.launch.is_none() && self.launch_grid.is_empty() {
+                                let commands = self.app_config.panes.startup_grid.clone();
+                                self.launch_grid(&commands, None);
+                            }
+                            match self.launch.clone() {
+                                Some(launch) => self.spawn_pane_with_profile(initial_pane_id, cols, rows, None, Some(&launch)),
+                                None if !self.launch_grid.is_empty() => {
+                                    self.spawn_launch_grid(size.width as f32, size.height as f32)
+                                }
+                                None => self.spawn_pane(initial_pane_id, cols, rows),
+                            }
+                            if let Some(title) = &self.launch_title {
+                                self.tab_manager.rename_tab(0, title);
+                            }
                         }
                     }
                     Err(e) => {
@@ -2056,6 +5316,7 @@ impl ApplicationHandler<UserEvent> for App {
                 }
 
                 self.window = Some(window);
+                self.sync_refresh_rate();
 
                 // Initialize interaction layout
                 let (w, h) = self.window_size();
@@ -2071,7 +5332,7 @@ impl ApplicationHandler<UserEvent> for App {
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
-        _window_id: WindowId,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
         // Forward events to iced layer for UI widget interaction
@@ -2079,29 +5340,107 @@ impl ApplicationHandler<UserEvent> for App {
             let scale = self.window.as_ref().map(|w| w.scale_factor() as f32).unwrap_or(1.0);
             renderer.iced_layer_mut().push_event(&event, scale, self.modifiers);
         }
+        if !matches!(event, WindowEvent::RedrawRequested) {
+            self.frame_pacer.mark_dirty();
+        }
+
+        // Low-latency mode draws the frame for a key press right away rather
+        // than on the next scheduled redraw.
+        let render_now = self.app_config.renderer.low_latency
+            && matches!(
+                &event,
+                WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed
+            );
+
+        // Applications that enabled mouse reporting get clicks, drags and
+        // motion instead of text selection
+        match &event {
+            WindowEvent::MouseInput { state, button, .. }
+                if self.report_mouse_button(*button, *state) =>
+            {
+                return;
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.report_mouse_motion(position.x as f32, position.y as f32);
+            }
+            _ => {}
+        }
 
         match event {
             WindowEvent::CloseRequested => {
                 log::info!("Window close requested");
-                self.save_session();
-                event_loop.exit();
+                self.quit(event_loop);
             }
             WindowEvent::ModifiersChanged(new_modifiers) => {
                 self.modifiers = new_modifiers.state();
+                self.option_keys.sync(self.modifiers.alt_key());
             }
             WindowEvent::KeyboardInput { event, .. } => {
+                self.option_keys.on_key(&event.logical_key, event.location, event.state);
                 if event.state == ElementState::Pressed {
-                    // Check for screenshot (Cmd+Shift+S on macOS, Ctrl+Shift+S elsewhere)
-                    let is_screenshot_key = matches!(event.logical_key, Key::Character(ref s) if s.as_str() == "s" || s.as_str() == "S")
-                        && self.modifiers.shift_key()
-                        && (self.modifiers.super_key() || self.modifiers.control_key());
+                    self.sync_search_mode();
+
+                    // The tab title editor takes all keys until Enter or Escape
+                    if self.editing_sidebar_tab.is_some() {
+                        self.handle_tab_rename_key(&event.logical_key, event.text.as_ref().map(|s| s.as_ref()));
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // The group name editor takes all keys until Enter or Escape
+                    if self.group_editor.is_some() {
+                        self.handle_group_editor_key(&event.logical_key, event.text.as_ref().map(|s| s.as_ref()));
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // The focused pane's note editor takes all keys until Enter or Escape
+                    let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                    if self.pane_states.get(&focused_id).is_some_and(|s| s.note_editor.is_some()) {
+                        self.handle_note_editor_key(&event.logical_key, event.text.as_ref().map(|s| s.as_ref()));
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // The bookmark list takes all keys until Enter or Escape
+                    if self.bookmark_list.is_some() {
+                        self.handle_bookmark_list_key(&event.logical_key);
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // So does the vi register viewer
+                    if self.register_list.is_some() {
+                        self.handle_register_list_key(&event.logical_key);
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // So does hint mode
+                    if self.hint_mode.is_some() {
+                        self.handle_hint_key(&event.logical_key);
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
 
-                    if is_screenshot_key {
-                        self.screenshot_requested = true;
+                    // So does the profile picker
+                    if self.profile_picker.is_some() {
+                        self.handle_profile_picker_key(&event.logical_key);
                         if let Some(window) = &self.window {
                             window.request_redraw();
                         }
-                        log::info!("📸 Screenshot requested (Cmd+Shift+S) - will capture on next frame");
                         return;
                     }
 
@@ -2114,9 +5453,7 @@ impl ApplicationHandler<UserEvent> for App {
                         } else {
                             // Open palette (close search if active)
                             if self.input_mode == InputMode::Search {
-                                self.search_state.is_active = false;
-                                self.search_state.query.clear();
-                                self.search_state.matches.clear();
+                                self.close_search();
                             }
                             self.input_mode = InputMode::CommandPalette;
                             self.palette_state = Some(PaletteState::new());
@@ -2127,20 +5464,6 @@ impl ApplicationHandler<UserEvent> for App {
                         return;
                     }
 
-                    // Check for sidebar toggle (Ctrl+Shift+B)
-                    if matches!(event.logical_key, Key::Character(ref s) if s.as_str() == "b" || s.as_str() == "B")
-                        && self.modifiers.control_key()
-                        && self.modifiers.shift_key()
-                    {
-                        self.sidebar_visible = !self.sidebar_visible;
-                        let (w, h) = self.window_size();
-                        self.resize_all_panes(w, h);
-                        if let Some(window) = &self.window {
-                            window.request_redraw();
-                        }
-                        return;
-                    }
-
                     // Check for conductor dashboard toggle (Cmd+Shift+D on macOS, Ctrl+Shift+D elsewhere)
                     #[cfg(target_os = "macos")]
                     let conductor_trigger = self.modifiers.super_key() && self.modifiers.shift_key();
@@ -2161,78 +5484,43 @@ impl ApplicationHandler<UserEvent> for App {
                         return;
                     }
 
-                    // Check for markdown preview toggle (Ctrl+Shift+M)
-                    if matches!(event.logical_key, Key::Character(ref s) if s.as_str() == "m" || s.as_str() == "M")
-                        && self.modifiers.control_key()
-                        && self.modifiers.shift_key()
+                    // A pending OSC 52, paste or URL prompt in the focused pane
+                    // takes Enter/Escape, Escape stops a slow paste, and the flood
+                    // banner takes Ctrl+S, Ctrl+C and Escape
+                    if self.input_mode == InputMode::Normal
+                        && (self.answer_clipboard_prompt(&event.logical_key)
+                            || self.answer_restart_prompt(&event.logical_key)
+                            || self.answer_paste_prompt(&event.logical_key)
+                            || self.answer_url_prompt(&event.logical_key)
+                            || self.answer_flood_banner(&event.logical_key))
                     {
-                        if self.input_mode == InputMode::MarkdownPreview {
-                            self.input_mode = InputMode::Normal;
-                            let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                            if let Some(state) = self.pane_states.get_mut(&focused) {
-                                state.markdown_preview = None;
-                            }
-                        } else {
-                            self.open_markdown_preview(None);
-                        }
-                        if let Some(window) = &self.window {
-                            window.request_redraw();
-                        }
                         return;
                     }
 
-                    // Check for overlay toggle (Ctrl+E, Ctrl+G — no Shift)
-                    if let Some(overlay_cmd) = match_overlay_command(&event.logical_key, self.modifiers, &self.app_config.keys.bindings) {
-                        match overlay_cmd {
-                            OverlayCommand::ToggleFileBrowser => {
-                                self.input_mode = crate::file_browser::toggle_file_browser(self.input_mode);
-                                if self.input_mode == InputMode::FileBrowser {
-                                    let cwd = self.active_pane_cwd();
-                                    let state = self.file_browser_state.get_or_insert_with(
-                                        crate::file_browser::FileBrowserState::new,
-                                    );
-                                    state.open(cwd);
-                                }
-                            }
-                            OverlayCommand::ToggleGitReview => {
-                                self.input_mode = crate::git_review::toggle_git_review(self.input_mode);
-                                if self.input_mode == InputMode::GitReview {
-                                    let cwd = self.active_pane_cwd();
-                                    let state = self.git_review_state.get_or_insert_with(
-                                        crate::git_review::GitReviewState::new,
-                                    );
-                                    state.open_from_cwd(&cwd);
-                                }
-                            }
-                        }
+                    // Resolve the key through the keymap (defaults + `[keys]`)
+                    let action = self.keymap.lookup(&event.logical_key, self.modifiers);
+
+                    // Any key closes the cheatsheet; Escape and its own toggle stop there
+                    if self.cheatsheet_open {
+                        self.cheatsheet_open = false;
                         if let Some(window) = &self.window {
                             window.request_redraw();
                         }
-                        return;
+                        if event.logical_key == Key::Named(NamedKey::Escape)
+                            || action == Some(Action::App(AppCommand::ToggleCheatsheet))
+                        {
+                            return;
+                        }
                     }
 
-                    // Check for search toggle (Ctrl+Shift+F) — works in any mode
-                    if should_open_search(&event.logical_key, self.modifiers) {
-                        if self.input_mode == InputMode::Search {
-                            // Close search
-                            self.input_mode = InputMode::Normal;
-                            self.search_state.is_active = false;
-                            self.search_state.query.clear();
-                            self.search_state.matches.clear();
-                            if let Some(renderer) = &mut self.renderer {
-                                renderer.pane_damage_mut().force_full_damage_all();
-                            }
-                        } else {
-                            // Open search (close palette if open)
-                            if self.input_mode == InputMode::CommandPalette {
-                                self.palette_state = None;
-                            }
-                            self.input_mode = InputMode::Search;
-                            self.search_state.is_active = true;
-                        }
-                        if let Some(window) = &self.window {
-                            window.request_redraw();
-                        }
+                    // Overlay, search, sidebar and markdown preview toggles work in any mode
+                    if let Some(
+                        global @ (Action::Overlay(_)
+                        | Action::Search(SearchCommand::Open)
+                        | Action::View(ViewCommand::ToggleSidebar | ViewCommand::ToggleMarkdownPreview)),
+                    ) = action
+                    {
+                        self.dispatch_action(global, event_loop);
                         return;
                     }
 
@@ -2366,6 +5654,10 @@ impl ApplicationHandler<UserEvent> for App {
 
                     // In search mode, intercept keys for search commands
                     if self.input_mode == InputMode::Search {
+                        if let Some(Action::Search(cmd)) = action {
+                            self.handle_search_command(cmd);
+                            return;
+                        }
                         if let Some(cmd) = match_search_command(
                             &event.logical_key,
                             event.text.as_ref().map(|s| s.as_ref()),
@@ -2377,69 +5669,21 @@ impl ApplicationHandler<UserEvent> for App {
                         return; // Consume all keys in search mode
                     }
 
-                    // Check for app-level commands (font size)
-                    if let Some(cmd) =
-                        match_app_command(&event.logical_key, self.modifiers)
-                    {
-                        self.handle_app_command(cmd);
-                        return;
-                    }
-
-                    // Check for tab commands first
-                    if let Some(cmd) =
-                        match_tab_command(&event.logical_key, self.modifiers)
-                    {
-                        self.handle_tab_command(cmd, event_loop);
-                        return;
-                    }
-
-                    // Then check for pane commands
-                    if let Some(cmd) =
-                        match_pane_command(&event.logical_key, self.modifiers)
-                    {
-                        self.handle_pane_command(cmd, event_loop);
-                        return;
-                    }
-
-                    // Then check for shell integration commands
-                    if let Some(cmd) =
-                        crate::input::match_shell_command(&event.logical_key, self.modifiers)
-                    {
-                        self.handle_shell_command(cmd);
-                        return;
+                    // Any other bound action (vi-mode toggle only while vi mode is enabled)
+                    match action {
+                        Some(Action::ToggleViMode) if !self.app_config.vi_mode.enabled => {}
+                        Some(action) => {
+                            self.dispatch_action(action, event_loop);
+                            return;
+                        }
+                        None => {}
                     }
 
-                    // Check for vi-mode toggle (Ctrl+Shift+Space)
                     let focused_id = self
                         .tab_manager
                         .active_tab()
                         .pane_tree
                         .focused_pane_id();
-                    if self.app_config.vi_mode.enabled
-                        && crate::input::should_toggle_vi_mode(
-                            &event.logical_key,
-                            self.modifiers,
-                        )
-                    {
-                        if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                            if state.vi_state.is_some() {
-                                // Exit vi-mode
-                                state.vi_state = None;
-                                log::info!("Vi-mode deactivated for pane {:?}", focused_id);
-                            } else {
-                                // Enter vi-mode at terminal cursor position
-                                let (row, col) = state.terminal.cursor_position();
-                                state.vi_state =
-                                    Some(crate::vi_mode::ViState::new(row, col));
-                                log::info!("Vi-mode activated for pane {:?}", focused_id);
-                            }
-                        }
-                        if let Some(window) = &self.window {
-                            window.request_redraw();
-                        }
-                        return;
-                    }
-
                     // If focused pane has vi-mode active, route keys there
                     if let Some(state) = self.pane_states.get_mut(&focused_id) {
                         if let Some(ref mut vi) = state.vi_state {
@@ -2460,24 +5704,9 @@ impl ApplicationHandler<UserEvent> for App {
 
                     // Check for clipboard commands (Cmd+C, Cmd+V, Cmd+A)
                     if crate::input::clipboard::is_copy_keybinding(&event.logical_key, self.modifiers) {
+                        self.copy_selection(self.default_copy_mode(), self.app_config.redaction.copy);
                         if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                            if let Some(ref sel) = state.mouse_selection.active_selection {
-                                let text = match sel.selection_type {
-                                    crate::input::selection::SelectionType::VisualBlock => {
-                                        crate::terminal::grid_bridge::grid_selected_text_block(&state.terminal, sel)
-                                    }
-                                    crate::input::selection::SelectionType::Line => {
-                                        crate::terminal::grid_bridge::grid_selected_text_lines(&state.terminal, sel)
-                                    }
-                                    _ => crate::terminal::grid_bridge::grid_selected_text(&state.terminal, sel),
-                                };
-                                if !text.is_empty() {
-                                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                        let _ = clipboard.set_text(&text);
-                                    }
-                                }
-                                state.mouse_selection.clear_selection();
-                            }
+                            state.mouse_selection.clear_selection();
                         }
                         if let Some(window) = &self.window {
                             window.request_redraw();
@@ -2485,17 +5714,7 @@ impl ApplicationHandler<UserEvent> for App {
                         return;
                     }
                     if crate::input::clipboard::is_paste_keybinding(&event.logical_key, self.modifiers) {
-                        if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                            if let Ok(text) = clipboard.get_text() {
-                                if let Some(state) = self.pane_states.get_mut(&focused_id) {
-                                    let bracketed = state.terminal.is_bracketed_paste_enabled();
-                                    let bytes = crate::input::clipboard::paste_bytes(&text, bracketed);
-                                    if let Err(e) = state.pty.write(&bytes) {
-                                        log::warn!("PTY paste write error: {e}");
-                                    }
-                                }
-                            }
-                        }
+                        self.paste_clipboard(false);
                         return;
                     }
                     if crate::input::clipboard::is_select_all_keybinding(&event.logical_key, self.modifiers) {
@@ -2519,28 +5738,96 @@ impl ApplicationHandler<UserEvent> for App {
                         return;
                     }
 
+                    // Alt+Enter types the corrected command from a "did you mean" hint
+                    if crate::input::is_command_hint_accept_key(
+                        &event.logical_key,
+                        self.modifiers,
+                        &self.app_config.keys.bindings,
+                    ) {
+                        if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                            if let Some(hint) = state.command_hint.take() {
+                                let bytes = hint.corrected.into_bytes();
+                                if let Err(e) = state.pty.write(&bytes) {
+                                    log::warn!("PTY write error: {e}");
+                                }
+                                state.suggestions.on_input(&bytes);
+                                state.cursor.on_keystroke();
+                                if let Some(window) = &self.window {
+                                    window.request_redraw();
+                                }
+                                return;
+                            }
+                        }
+                    }
+
+                    // Tab / Right arrow accepts the top history suggestion
+                    if self.app_config.suggestions.enabled
+                        && crate::input::is_suggestion_accept_key(&event.logical_key, self.modifiers)
+                    {
+                        if let Some(state) = self.pane_states.get_mut(&focused_id) {
+                            if let Some(bytes) = state.suggestions.accept() {
+                                if let Err(e) = state.pty.write(&bytes) {
+                                    log::warn!("PTY write error: {e}");
+                                }
+                                state.cursor.on_keystroke();
+                                if let Some(window) = &self.window {
+                                    window.request_redraw();
+                                }
+                                return;
+                            }
+                        }
+                    }
+
+                    // Enter restarts a pane whose shell is gone
+                    if self.pane_is_dead(focused_id) {
+                        if event.logical_key == Key::Named(NamedKey::Enter) {
+                            self.restart_pane(focused_id);
+                        }
+                        return;
+                    }
+
                     // Route normal keys to focused pane's PTY
+                    let alt_as_meta = self.option_as_alt();
+                    // Option acting as Alt sends the unmodified key, not the
+                    // character Option would compose
+                    #[cfg(target_os = "macos")]
+                    let (logical_key, text) = if alt_as_meta {
+                        use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+                        (event.key_without_modifiers(), None)
+                    } else {
+                        (event.logical_key.clone(), event.text.as_ref().map(|s| s.as_ref()))
+                    };
+                    #[cfg(not(target_os = "macos"))]
+                    let (logical_key, text) =
+                        (event.logical_key.clone(), event.text.as_ref().map(|s| s.as_ref()));
                     let bytes = crate::input::translate_key(
-                        &event.logical_key,
-                        event.text.as_ref().map(|s| s.as_ref()),
+                        &logical_key,
+                        text,
                         event.state,
                         self.modifiers,
+                        alt_as_meta,
                     );
-                    if let (Some(bytes), Some(state)) =
-                        (bytes, self.pane_states.get_mut(&focused_id))
-                    {
-                        if let Err(e) = state.pty.write(&bytes) {
-                            log::warn!("PTY write error: {e}");
-                        }
-                        state.cursor.on_keystroke();
-                        state.mouse_selection.clear_selection();
-                        // Snap scroll to bottom on keyboard input (return to live view)
-                        state.scroll_state.snap_to_bottom();
-                        state.terminal.set_display_offset(0);
+                    if let Some(bytes) = bytes {
+                        self.record_input(focused_id, &bytes);
+                        self.send_keyboard_input(focused_id, &bytes);
                     }
                 }
             }
+            WindowEvent::ActivationTokenDone { serial, token } => {
+                if let Some(i) = self.pending_opens.iter().position(|(s, _)| *s == serial) {
+                    let (_, open) = self.pending_opens.remove(i);
+                    open(Some(&token.into_raw()));
+                }
+            }
+            WindowEvent::Ime(Ime::Commit(text)) => {
+                let focused_id = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                self.record_input(focused_id, text.as_bytes());
+                self.send_keyboard_input(focused_id, text.as_bytes());
+            }
             WindowEvent::CursorMoved { position, .. } => {
+                if !self.touch_dispatching {
+                    self.touch_handles_visible = false;
+                }
                 let y = position.y as f32;
                 let chrome_top = self.chrome_top_height();
                 // No separate tab bar — chrome bar is fully iced-managed.
@@ -2637,34 +5924,17 @@ impl ApplicationHandler<UserEvent> for App {
                         }
                     }
 
-                    // Update scrollbar drag or text selection drag on focused pane (skip if in sidebar)
+                    // Update text selection drag on focused pane (skip if in sidebar)
                     if !in_sidebar {
                         let focused_pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                        let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
                         let (win_w, win_h) = self.window_size();
                         let pgrid_bounds = self.pane_grid_bounds(win_w as f32, win_h as f32);
                         let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid_bounds.width, pgrid_bounds.height);
                         let pane_rect = layout.iter().find(|(id, _)| *id == focused_pane).map(|(_, r)| *r);
 
-                        let mut scrollbar_dragging = false;
-                        // Check scrollbar drag first
-                        if let Some(state) = self.pane_states.get_mut(&focused_pane) {
-                            if state.scroll_state.is_dragging_scrollbar {
-                                scrollbar_dragging = true;
-                                if let Some(rect) = pane_rect {
-                                    let track_height = rect.height - padding[0] - padding[1];
-                                    let history_size = state.terminal.history_size();
-                                    state.scroll_state.update_drag(content_y, track_height, history_size);
-                                    let offset = state.scroll_state.current_line_offset();
-                                    state.terminal.set_display_offset(offset);
-                                }
-                                if let Some(window) = &self.window {
-                                    window.request_redraw();
-                                }
-                            }
-                        }
-                        // Fall back to text selection drag
-                        if !scrollbar_dragging {
+                        // A scrollbar drag is moved by the pane interaction above;
+                        // otherwise this is a text selection drag
+                        if !self.interaction.is_dragging_scrollbar() {
                             if let Some(renderer) = &self.renderer {
                                 let cell_width = renderer.cell_width();
                                 let cell_height = renderer.cell_height();
@@ -2673,8 +5943,13 @@ impl ApplicationHandler<UserEvent> for App {
                                     if state.mouse_selection.drag_phase != crate::input::mouse::DragPhase::Idle {
                                         let pane_origin_x = pgrid_bounds.x + pane_rect.map_or(0.0, |r| r.x);
                                         let pane_origin_y = pgrid_bounds.y + pane_rect.map_or(0.0, |r| r.y) + PANE_HEADER_HEIGHT;
-                                        let local_x = position.x as f32 - pane_origin_x - padding[2];
-                                        let local_y = y - pane_origin_y - padding[0];
+                                        let mut local_x = position.x as f32 - pane_origin_x - padding[2];
+                                        let mut local_y = y - pane_origin_y - padding[0];
+                                        if let Some(layout) = &state.soft_wrap {
+                                            (local_x, local_y) = layout.to_terminal_pixel(local_x, local_y, cell_width, cell_height);
+                                        } else {
+                                            local_x += state.h_scroll.offset() as f32 * cell_width;
+                                        }
                                         let cols = state.terminal.columns();
                                         let rows = state.terminal.rows();
                                         let cells = crate::terminal::grid_bridge::extract_grid_cells(&state.terminal, renderer.theme());
@@ -2730,6 +6005,18 @@ impl ApplicationHandler<UserEvent> for App {
                     }
                 }
 
+                // The command palette and bookmark list are modal; iced handles their clicks
+                if self.palette_state.is_some()
+                    || self.bookmark_list.is_some()
+                    || self.register_list.is_some()
+                    || self.profile_picker.is_some()
+                {
+                    if let Some(window) = &self.window {
+                        window.request_redraw();
+                    }
+                    return;
+                }
+
                 if false {
                     // Legacy tab bar click zone — no longer exists (chrome bar is iced-managed)
                 } else {
@@ -2741,55 +6028,43 @@ impl ApplicationHandler<UserEvent> for App {
                         }
                     }
 
-                    // Check for scrollbar interaction before text selection
-                    let mut scrollbar_handled = false;
+                    // The search bar is iced's (its option toggles)
+                    if self.over_search_bar(cursor_pos.0, cursor_pos.1) {
+                        if let Some(window) = &self.window {
+                            window.request_redraw();
+                        }
+                        return;
+                    }
+
+                    // Ticks on the search match strip jump to their row
+                    if btn_state == ElementState::Pressed
+                        && self.match_strip_click(cursor_pos.0, cursor_pos.1)
                     {
-                        let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
-                        let focused_pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
-                        let (width, height) = self.window_size();
-                        let pgrid = self.pane_grid_bounds(width as f32, height as f32);
-                        let layout = self.tab_manager.active_tab().pane_tree.calculate_layout(pgrid.width, pgrid.height);
-                        let screen_rect = layout.iter().find(|(id, _)| *id == focused_pane).map(|(_, r)| {
-                            Rect::new(r.x + pgrid.x, r.y + pgrid.y, r.width, r.height)
-                        });
-                        if let (Some(screen_rect), Some(state)) = (screen_rect, self.pane_states.get_mut(&focused_pane)) {
-                            let history_size = state.terminal.history_size();
-                            let visible_rows = state.terminal.rows();
-                            let display_offset = state.scroll_state.current_line_offset();
-                            match btn_state {
-                                ElementState::Pressed => {
-                                    let hit = crate::scroll::scrollbar_hit_test(
-                                        cursor_pos.0, cursor_pos.1,
-                                        screen_rect.x, screen_rect.y,
-                                        screen_rect.width, screen_rect.height,
-                                        padding, visible_rows, history_size, display_offset,
-                                    );
-                                    match hit {
-                                        crate::scroll::ScrollbarHit::Track(y) => {
-                                            let offset = crate::scroll::track_click_to_offset(
-                                                y, screen_rect.y, screen_rect.height,
-                                                padding, history_size,
-                                            );
-                                            state.scroll_state.set_target(offset, history_size);
-                                            state.terminal.set_display_offset(offset);
-                                            scrollbar_handled = true;
-                                        }
-                                        crate::scroll::ScrollbarHit::Thumb(y) => {
-                                            state.scroll_state.begin_drag(y);
-                                            scrollbar_handled = true;
-                                        }
-                                        crate::scroll::ScrollbarHit::None => {}
-                                    }
-                                }
-                                ElementState::Released => {
-                                    if state.scroll_state.is_dragging_scrollbar {
-                                        state.scroll_state.end_drag();
-                                        scrollbar_handled = true;
-                                    }
+                        return;
+                    }
+
+                    // Clicks on the scrollback minimap jump to that line
+                    if btn_state == ElementState::Pressed
+                        && self.minimap_click(cursor_pos.0, cursor_pos.1)
+                    {
+                        return;
+                    }
+
+                    // Check for scrollbar interaction before text selection
+                    let scrollbar_handled = match btn_state {
+                        ElementState::Pressed => {
+                            self.update_interaction_scrollbars();
+                            match self.interaction.on_scrollbar_press() {
+                                Some(effect) => {
+                                    self.apply_interaction_effect(effect);
+                                    true
                                 }
+                                None => false,
                             }
                         }
-                    }
+                        // The release itself ends the drag in `on_mouse_release` below
+                        ElementState::Released => self.interaction.is_dragging_scrollbar(),
+                    };
 
                     // Handle text selection on focused pane (if not handled by scrollbar)
                     if !scrollbar_handled {
@@ -2808,8 +6083,13 @@ impl ApplicationHandler<UserEvent> for App {
                                 // Convert from window-space to pane-local terminal coords
                                 let pane_origin_x = sel_pgrid.x + sel_pane_rect.map_or(0.0, |r| r.x);
                                 let pane_origin_y = sel_pgrid.y + sel_pane_rect.map_or(0.0, |r| r.y) + PANE_HEADER_HEIGHT;
-                                let local_x = cursor_pos.0 - pane_origin_x - padding[2];
-                                let local_y = cursor_pos.1 - pane_origin_y - padding[0];
+                                let mut local_x = cursor_pos.0 - pane_origin_x - padding[2];
+                                let mut local_y = cursor_pos.1 - pane_origin_y - padding[0];
+                                if let Some(layout) = &state.soft_wrap {
+                                    (local_x, local_y) = layout.to_terminal_pixel(local_x, local_y, cell_width, cell_height);
+                                } else {
+                                    local_x += state.h_scroll.offset() as f32 * cell_width;
+                                }
                                 match btn_state {
                                     ElementState::Pressed => {
                                         let cells = crate::terminal::grid_bridge::extract_grid_cells(&state.terminal, renderer.theme());
@@ -2909,6 +6189,28 @@ impl ApplicationHandler<UserEvent> for App {
                     }
                 }
             }
+            WindowEvent::Touch(touch) => {
+                self.touch_device = Some(touch.device_id);
+                self.touch_handles_visible = true;
+                let actions = self.touch.on_touch(
+                    touch.id,
+                    touch.phase,
+                    touch.location.x as f32,
+                    touch.location.y as f32,
+                    std::time::Instant::now(),
+                );
+                for action in actions {
+                    self.handle_touch_action(event_loop, window_id, action);
+                }
+                if self.touch.awaiting_long_press() {
+                    if let Some(window) = &self.window {
+                        window.request_redraw();
+                    }
+                }
+            }
+            WindowEvent::PinchGesture { delta, phase, .. } => {
+                self.handle_pinch_gesture(delta, phase);
+            }
             WindowEvent::MouseWheel { delta, .. } => {
                 // Route scroll to git review diff view when overlay is active
                 if self.input_mode == InputMode::GitReview {
@@ -2925,9 +6227,48 @@ impl ApplicationHandler<UserEvent> for App {
                     return;
                 }
 
+                if self.report_mouse_wheel(delta) {
+                    return;
+                }
+
                 let focused_pane = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                let mut scrolled = 0;
                 if let Some(state) = self.pane_states.get_mut(&focused_pane) {
+                    // Shift+wheel or a sideways swipe scrolls a pane showing
+                    // only part of its columns
+                    let total_cols = state.terminal.columns();
+                    if state.h_scroll.is_scrollable(total_cols) && state.soft_wrap.is_none() {
+                        let shift = self.modifiers.shift_key();
+                        let sideways = match delta {
+                            winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                                (shift || x.abs() > y.abs()).then_some(if shift { y } else { x })
+                            }
+                            winit::event::MouseScrollDelta::PixelDelta(pos) => {
+                                (shift || pos.x.abs() > pos.y.abs())
+                                    .then_some(if shift { pos.y as f32 } else { pos.x as f32 })
+                            }
+                        };
+                        if let Some(amount) = sideways {
+                            match delta {
+                                winit::event::MouseScrollDelta::LineDelta(..) => {
+                                    state.h_scroll.apply_line_delta(amount, total_cols);
+                                }
+                                winit::event::MouseScrollDelta::PixelDelta(_) => {
+                                    let cell_width = self.renderer.as_ref().map_or(10.0, |r| r.cell_width());
+                                    state.h_scroll.apply_pixel_delta(amount, cell_width, total_cols);
+                                }
+                            }
+                            if let Some(renderer) = &mut self.renderer {
+                                renderer.pane_damage_mut().force_full_damage_all();
+                            }
+                            if let Some(window) = &self.window {
+                                window.request_redraw();
+                            }
+                            return;
+                        }
+                    }
                     let history_size = state.terminal.history_size();
+                    let before = state.scroll_state.target_offset();
                     match delta {
                         winit::event::MouseScrollDelta::LineDelta(_, y) => {
                             state.scroll_state.apply_line_delta(y, history_size);
@@ -2945,6 +6286,7 @@ impl ApplicationHandler<UserEvent> for App {
                             );
                         }
                     }
+                    scrolled = state.scroll_state.target_offset() as isize - before as isize;
                     let offset = state.scroll_state.current_line_offset();
                     state.terminal.set_display_offset(offset);
                     // If dragging during scroll, update selection endpoint at new offset
@@ -2962,6 +6304,7 @@ impl ApplicationHandler<UserEvent> for App {
                         }
                     }
                 }
+                self.follow_scroll_lock(focused_pane, scrolled);
                 if let Some(window) = &self.window {
                     window.request_redraw();
                 }
@@ -2976,14 +6319,27 @@ impl ApplicationHandler<UserEvent> for App {
                 // coalesce into a single SIGWINCH per pane.
                 self.pending_resize = Some((size.width, size.height));
                 self.update_interaction_layout(size.width, size.height);
+                // Some platforms report minimizing as a resize to nothing
+                self.frame_pacer.set_minimized(size.width == 0 || size.height == 0);
             }
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 log::info!("Scale factor changed to {scale_factor:.2}");
                 if let Some(renderer) = &mut self.renderer {
                     renderer.update_scale_factor(scale_factor as f32);
                 }
+                self.sync_refresh_rate();
+            }
+            WindowEvent::Moved(_) => {
+                // Possibly onto a monitor with another refresh rate
+                self.sync_refresh_rate();
+            }
+            WindowEvent::Occluded(occluded) => {
+                log::debug!("Window {}", if occluded { "occluded" } else { "visible" });
+                self.frame_pacer.set_occluded(occluded);
             }
             WindowEvent::Focused(focused) => {
+                self.apply_secure_keyboard_entry(focused);
+                self.ime.reset();
                 for state in self.pane_states.values_mut() {
                     state.cursor.set_focused(focused);
                 }
@@ -3006,12 +6362,59 @@ impl ApplicationHandler<UserEvent> for App {
                     self.resize_all_panes(rw, rh);
                 }
 
+                // Focus may have moved to a pane with a different search state
+                self.sync_search_mode();
+
+                // Touch long-press fires on a timer checked every tick
+                if let Some(action) = self.touch.poll(std::time::Instant::now()) {
+                    self.handle_touch_action(event_loop, window_id, action);
+                }
+                self.poll_theme_schedule();
+
+                // Whether something moves on its own this frame
+                let mut animating = false;
+
                 // Drain PTY output into terminals for all panes, update cursor positions
                 let theme_for_queries = self.renderer.as_ref().map(|r| *r.theme());
                 let cell_dims = self.renderer.as_ref().map(|r| (r.cell_width(), r.cell_height()));
-                for state in self.pane_states.values_mut() {
-                    while let Ok(bytes) = state.pty.reader_rx.try_recv() {
-                        state.terminal.feed(&bytes);
+                let mut reflowed = false;
+                for (&pane_id, state) in self.pane_states.iter_mut() {
+                    // Background reflows finish here; output read meanwhile is replayed
+                    let mut grid_changed = false;
+                    if state.terminal.poll_reflow() {
+                        self.frame_pacer.mark_dirty();
+                        reflowed = true;
+                        grid_changed = true;
+                        state.minimap.mark_dirty();
+                    }
+                    // A flooding pane parses at most a frame's budget; a paused one nothing
+                    let now = std::time::Instant::now();
+                    let budget = state.flood.budget();
+                    let mut parsed = 0;
+                    while budget.is_none_or(|budget| parsed < budget) {
+                        let Ok(bytes) = state.pty.reader_rx.try_recv() else {
+                            break;
+                        };
+                        parsed += bytes.len();
+                        if state.flood.on_output(now, bytes.len()) {
+                            log::warn!("Pane {:?} output flood, parsing at most {} bytes per frame", pane_id, crate::pty::flood::FRAME_BUDGET);
+                        }
+                        if let Some(recorder) = &mut self.event_recorder {
+                            if self.app_config.redaction.event_traces {
+                                recorder.record(TraceEvent::output(pane_id, &self.redactor.redact_bytes(&bytes)));
+                            } else {
+                                recorder.record(TraceEvent::output(pane_id, &bytes));
+                            }
+                        }
+                        Self::feed_output(state, &bytes);
+                        state.watchdog.on_output();
+                        state.minimap.mark_dirty();
+                        grid_changed = true;
+                    }
+                    state.flood.tick(now);
+                    if grid_changed {
+                        self.frame_pacer.mark_dirty();
+                        Self::relocate_bookmarks(state);
                     }
                     // Process query responses (DA1, DA2, DSR, OSC 10/11, etc.)
                     let responses = state.terminal.drain_query_responses();
@@ -3021,13 +6424,10 @@ impl ApplicationHandler<UserEvent> for App {
                             QueryResponse::Direct(s) => Some(s),
                             QueryResponse::Color(index, formatter) => {
                                 if let Some(theme) = &theme_for_queries {
-                                    // alacritty_terminal uses internal indices:
-                                    // 256 = foreground (OSC 10), 257 = background (OSC 11)
-                                    let color = match index {
-                                        256 | 10 => theme.text_muted,
-                                        257 | 11 => theme.bg_deep,
-                                        _ => theme.text_muted,
-                                    };
+                                    // Match the colors actually rendered so programs can
+                                    // detect light/dark backgrounds via OSC 11.
+                                    let color =
+                                        crate::terminal::grid_bridge::query_color(index, theme);
                                     let rgb = alacritty_terminal::vte::ansi::Rgb {
                                         r: (color.r * 255.0) as u8,
                                         g: (color.g * 255.0) as u8,
@@ -3064,18 +6464,38 @@ impl ApplicationHandler<UserEvent> for App {
                             log::warn!("Failed to write image response to PTY: {}", e);
                         }
                     }
+                    // OSC 52 clipboard writes and reads, per the [clipboard] policy
+                    for request in state.terminal.drain_clipboard_requests() {
+                        Self::handle_clipboard_request(state, request, &self.app_config.clipboard);
+                    }
                     // Check for bell events
-                    if state.terminal.take_bell() && self.app_config.shell.bell_enabled {
-                        self.bell_flash_until = Some(
-                            std::time::Instant::now() + std::time::Duration::from_millis(150),
-                        );
+                    if state.terminal.take_bell() {
+                        if self.app_config.shell.bell_enabled {
+                            self.bell_flash_until = Some(
+                                std::time::Instant::now() + std::time::Duration::from_millis(150),
+                            );
+                        }
+                        if self.scripts.as_ref().is_some_and(|s| s.has_hooks(ScriptEvent::Bell)) {
+                            let mut data = rhai::Map::new();
+                            data.insert("pane".into(), (pane_id.0 as rhai::INT).into());
+                            self.script_events.push((ScriptEvent::Bell, data));
+                        }
                     }
                     // Sync cursor position from terminal state
                     let (row, col) = state.terminal.cursor_position();
                     state.cursor.update_position(row, col);
-                    state.cursor.tick_blink();
+                    // Shape and blinking as the application last asked (DECSCUSR)
+                    let (style, blinking) = state.terminal.cursor_style();
+                    if (style, blinking) != (state.cursor.style, state.cursor.blinking) {
+                        state.cursor.set_style(style);
+                        state.cursor.set_blinking(blinking);
+                        self.frame_pacer.mark_dirty();
+                    }
+                    if state.cursor.tick_blink() {
+                        self.frame_pacer.mark_dirty();
+                    }
                     // Tick scroll animation (~60fps assumed)
-                    state.scroll_state.tick(1.0 / 60.0);
+                    animating |= state.scroll_state.tick(1.0 / 60.0);
                     // Auto-scroll during active drag selection
                     if state.mouse_selection.drag_phase == crate::input::mouse::DragPhase::Active {
                         let speed = state.mouse_selection.auto_scroll_speed;
@@ -3098,10 +6518,7 @@ impl ApplicationHandler<UserEvent> for App {
                                     );
                                 }
                             }
-                            // Keep redrawing while auto-scrolling
-                            if let Some(window) = &self.window {
-                                window.request_redraw();
-                            }
+                            animating = true;
                         }
                     }
                     let offset = state.scroll_state.current_line_offset();
@@ -3109,39 +6526,93 @@ impl ApplicationHandler<UserEvent> for App {
                     // Clamp scroll to current history size (may have shrunk)
                     state.scroll_state.clamp_to_history(state.terminal.history_size());
                 }
+                if reflowed {
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.pane_damage_mut().force_full_damage_all();
+                    }
+                }
+
+                // Catch shells that died without a hangup or stopped answering
+                self.check_pane_health();
+
+                // Continue scrollback searches still in progress
+                self.advance_searches();
+                animating |= self.pane_states.values().any(|state| state.search.is_scanning());
 
                 // Process shell integration: notifications and CWD tab titles
                 self.process_shell_updates();
 
+                // Run the config script's hooks for what happened this frame
+                self.fire_script_events(event_loop);
+
                 // Rescan links for the focused pane after PTY drain
                 self.rescan_links();
 
+                // Keep an ibus/fcitx candidate window at the cursor
+                self.update_ime_cursor_area();
+
+                // Re-rasterize scrollback minimaps that have new content
+                self.update_minimaps();
+
+                // Panes scrolled sideways keep a moving cursor in view
+                for state in self.pane_states.values_mut() {
+                    if state.vi_state.is_none() && state.terminal.display_offset() == 0 {
+                        let (_, col) = state.terminal.cursor_position();
+                        state.h_scroll.follow(col, state.terminal.columns());
+                    }
+                }
+
+                // Flashes fade on their own until they end
+                let now = std::time::Instant::now();
+                animating |= self.bell_flash_until.is_some_and(|until| now < until)
+                    || self.screenshot_flash.is_some_and(|(_, until)| now < until)
+                    || self.pane_states.values().any(|state| state.cursor.is_animating(now));
+                // Nothing changed or moving, or the window can't be seen
+                if !self.frame_pacer.frame_due(now, animating) {
+                    return;
+                }
+
                 // Build render descriptors for active tab's visible panes
                 let pgrid = self.pane_grid_bounds(width as f32, height as f32);
                 let pane_tree = &self.tab_manager.active_tab().pane_tree;
                 let layout = pane_tree.calculate_layout(pgrid.width, pgrid.height);
                 let visible = pane_tree.visible_panes();
 
+                // Rows each visible terminal changed since the last frame, taken
+                // every frame so the feed stays in step with the damage cache
+                let mut row_damage = HashMap::new();
+                for pane_id in &visible {
+                    if let Some(state) = self.pane_states.get_mut(pane_id) {
+                        row_damage.insert(*pane_id, crate::terminal::grid_bridge::take_row_damage(&mut state.terminal));
+                    }
+                }
+
                 let focused_pane = pane_tree.focused_pane_id();
                 let render_theme = self.renderer.as_ref().unwrap().theme();
                 let mut pane_descs: Vec<PaneRenderDescriptor> = Vec::new();
+                let mut soft_wrap_layouts = Vec::new();
                 for (pane_id, rect) in &layout {
                     if !visible.contains(pane_id) {
                         continue;
                     }
                     if let Some(state) = self.pane_states.get(pane_id) {
+                        let pane_theme = state
+                            .directory_theme
+                            .as_ref()
+                            .or(state.theme.as_ref())
+                            .unwrap_or(render_theme);
                         let mut cells =
                             crate::terminal::grid_bridge::extract_grid_cells(
-                                &state.terminal, render_theme,
+                                &state.terminal, pane_theme,
                             );
 
-                        // Apply search highlights to the focused pane
-                        if self.search_state.is_active && *pane_id == focused_pane {
-                            let theme = render_theme;
+                        // Apply search highlights (each pane keeps its own search)
+                        if state.search.is_active {
+                            let theme = pane_theme;
                             let cols = state.terminal.columns();
                             let viewport_rows = state.terminal.rows() as i32;
                             let offset = state.terminal.display_offset() as i32;
-                            let visible_matches = self.search_state.visible_matches(
+                            let visible_matches = state.search.visible_matches(
                                 -offset,
                                 -offset + viewport_rows - 1,
                                 0,
@@ -3152,11 +6623,12 @@ impl ApplicationHandler<UserEvent> for App {
                                 .map(|m| crate::search::SearchMatch {
                                     row: m.row + offset,
                                     start_col: m.start_col,
+                                    end_row: m.end_row + offset,
                                     end_col: m.end_col,
                                 })
                                 .collect();
                             // Find the current match index in the viewport_matches
-                            let current_viewport_idx = if let Some(current) = self.search_state.current_match() {
+                            let current_viewport_idx = if let Some(current) = state.search.current_match() {
                                 viewport_matches.iter().position(|m| {
                                     m.row == current.row + offset
                                         && m.start_col == current.start_col
@@ -3175,6 +6647,43 @@ impl ApplicationHandler<UserEvent> for App {
                             );
                         }
 
+                        // Vi-mode search: every match of the query in view, updated as
+                        // it is typed
+                        if let Some(vi) = state.vi_state.as_ref().filter(|vi| vi.search_highlight && !vi.search_query.is_empty()) {
+                            let lines = crate::terminal::grid_bridge::extract_text_maps(&state.terminal);
+                            let matches = crate::search::SearchEngine::new().search_maps(&vi.search_query, &lines).matches;
+                            crate::search::highlight::apply_search_highlights(
+                                &mut cells,
+                                &matches,
+                                vi.highlighted_match(&matches).unwrap_or(usize::MAX),
+                                state.terminal.columns(),
+                                pane_theme.search_match,
+                                pane_theme.search_match_active,
+                            );
+                        }
+
+                        // Hint mode and quick select targets use the search match color
+                        if let Some((hint_pane, mode)) = &self.hint_mode {
+                            if hint_pane == pane_id {
+                                let targets: Vec<crate::search::SearchMatch> = mode.visible().map(|hint| {
+                                    crate::search::SearchMatch {
+                                        row: hint.row as i32,
+                                        start_col: hint.start,
+                                        end_row: hint.end_row as i32,
+                                        end_col: hint.end + 1,
+                                    }
+                                }).collect();
+                                crate::search::highlight::apply_search_highlights(
+                                    &mut cells,
+                                    &targets,
+                                    usize::MAX,
+                                    state.terminal.columns(),
+                                    pane_theme.search_match,
+                                    pane_theme.search_match_active,
+                                );
+                            }
+                        }
+
                         // Apply mouse selection highlight flags
                         if let Some(ref sel) = state.mouse_selection.active_selection {
                             let cols = state.terminal.columns();
@@ -3182,6 +6691,36 @@ impl ApplicationHandler<UserEvent> for App {
                             crate::input::selection::apply_selection_flags(&mut cells, sel, cols, display_offset);
                         }
 
+                        // Soft wrap rearranges the finished cells into display rows,
+                        // no wider than the pane shows
+                        let total_cols = state.terminal.columns();
+                        let soft_wrap = if state.guides.soft_wrap.unwrap_or(self.app_config.guides.soft_wrap) {
+                            let cursor = (state.terminal.display_offset() == 0)
+                                .then(|| state.terminal.cursor_position());
+                            crate::renderer::soft_wrap::SoftWrapLayout::new(
+                                &cells,
+                                total_cols,
+                                state.terminal.rows(),
+                                self.app_config.guides.soft_wrap_column.min(state.h_scroll.visible(total_cols)),
+                                cursor,
+                            )
+                        } else {
+                            None
+                        };
+                        if let Some(layout) = &soft_wrap {
+                            cells = layout.apply(&cells);
+                        }
+
+                        // A terminal wider than the pane shows its scrolled-to
+                        // columns; soft wrap keeps its rows within the pane
+                        let mut h_scroll = state.h_scroll;
+                        if soft_wrap.is_some() {
+                            h_scroll.scroll_columns(-(total_cols as isize), total_cols);
+                        }
+                        if h_scroll.is_scrollable(total_cols) {
+                            cells = h_scroll.crop(&cells, total_cols);
+                        }
+
                         // Offset rect by pane grid origin + pane header for screen-space rendering
                         let screen_rect = Rect::new(
                             rect.x + pgrid.x,
@@ -3195,16 +6734,59 @@ impl ApplicationHandler<UserEvent> for App {
                         } else {
                             None
                         };
+                        let cursor_instance = match &soft_wrap {
+                            Some(layout) => cursor_instance.and_then(|mut instance| {
+                                let [col, row] = instance.position;
+                                let (row, col) = layout.to_display(row as usize, col as usize)?;
+                                instance.position = [col as f32, row as f32];
+                                Some(instance)
+                            }),
+                            None => cursor_instance.and_then(|mut instance| {
+                                let col = h_scroll.to_display(instance.position[0] as usize, total_cols)?;
+                                instance.position[0] = col as f32;
+                                Some(instance)
+                            }),
+                        };
+                        // The damage feed describes the bare grid only
+                        let bare_grid = !state.search.is_active
+                            && state.vi_state.is_none()
+                            && self.hint_mode.as_ref().is_none_or(|(hint_pane, _)| hint_pane != pane_id)
+                            && state.mouse_selection.active_selection.is_none()
+                            && soft_wrap.is_none()
+                            && !h_scroll.is_scrollable(total_cols);
+                        let row_damage = row_damage.remove(pane_id).flatten().filter(|_| bare_grid);
+                        soft_wrap_layouts.push((*pane_id, soft_wrap));
+
+                        let color_filter = self.pane_color_filter(state);
 
                         pane_descs.push(PaneRenderDescriptor {
                             pane_id: *pane_id,
                             rect: screen_rect,
                             cells,
+                            row_damage,
                             cursor_instance,
+                            color_filter,
+                            zoom: if *pane_id == focused_pane {
+                                self.pinch_scale.unwrap_or(1.0)
+                            } else {
+                                1.0
+                            },
                         });
                     }
                 }
 
+                for (pane_id, soft_wrap) in soft_wrap_layouts {
+                    if let Some(state) = self.pane_states.get_mut(&pane_id) {
+                        state.soft_wrap = soft_wrap;
+                    }
+                }
+                // cursor.animation: cursors glide from where they were drawn
+                for desc in &mut pane_descs {
+                    if let Some(state) = self.pane_states.get_mut(&desc.pane_id) {
+                        desc.cursor_instance = state.cursor.animate(desc.cursor_instance.take(), now);
+                    }
+                }
+
                 // Build iced UI state from current application state (before mutable borrow)
                 let ui_tabs: Vec<TabInfo> = self.tab_manager.tabs().iter().enumerate().map(|(i, tab)| TabInfo {
                     title: tab.title.clone(),
@@ -3252,38 +6834,312 @@ impl ApplicationHandler<UserEvent> for App {
                     let chrome_top = self.chrome_top_height();
                     let layout = pane_tree.calculate_layout(pgrid.width, pgrid.height);
                     let padding = self.renderer.as_ref().map(|r| r.padding()).unwrap_or([0.0; 4]);
+                    let cell_height = self.renderer.as_ref().map(|r| r.cell_height()).unwrap_or(0.0);
+                    let cell_width = self.renderer.as_ref().map(|r| r.cell_width()).unwrap_or(0.0);
+                    let scrollbar_mode = ScrollbarMode::from_config_str(&self.app_config.scrollback.scrollbar)
+                        .unwrap_or_default();
+                    let ui_scale = self.window.as_ref().map(|w| w.scale_factor() as f32).unwrap_or(1.0);
+                    let chrome_theme = self.renderer.as_ref().map_or(crate::config::theme::DARK, |r| *r.theme());
                     let panes: Vec<PaneInfo> = layout.iter().enumerate().map(|(idx, (pane_id, rect))| {
                         let title = self.pane_states.get(pane_id)
-                            .and_then(|s| s.terminal.shell_state().cwd.clone())
-                            .map(|cwd| crate::shell_integration::dir_name_from_path(&cwd).to_string())
+                            .and_then(|s| s.title.clone())
                             .unwrap_or_else(|| "Shell".to_string());
 
-                        // Compute scrollbar thumb for this pane
-                        let (scrollbar_thumb, scrollbar_alpha) = if let Some(state) = self.pane_states.get(pane_id) {
-                            let alpha = state.scroll_state.scrollbar_alpha(now);
-                            if alpha > 0.0 {
-                                let history_size = state.terminal.history_size();
-                                let visible_rows = state.terminal.rows();
-                                let display_offset = state.scroll_state.current_line_offset();
-                                let content_rect = Rect::new(
+                        // Scrollbar track and thumb for this pane
+                        let scrollbar = self.pane_states.get(pane_id).map_or_else(Vec::new, |state| {
+                            let alpha = match scrollbar_mode {
+                                ScrollbarMode::Never => 0.0,
+                                ScrollbarMode::Auto => state.scroll_state.scrollbar_alpha(now),
+                                ScrollbarMode::Always => 0.3,
+                            };
+                            if alpha <= 0.0 {
+                                return Vec::new();
+                            }
+                            let (x, y, w, h) = (
+                                rect.x + Self::PANE_GRID_PADDING,
+                                rect.y + Self::PANE_GRID_PADDING,
+                                rect.width - Self::PANE_GRID_PADDING * 2.0,
+                                rect.height - Self::PANE_GRID_PADDING * 2.0,
+                            );
+                            let thumb = crate::scroll::scrollbar_thumb_rect(
+                                x, y, w, h, padding,
+                                state.terminal.rows(),
+                                state.terminal.history_size(),
+                                state.scroll_state.current_line_offset(),
+                            );
+                            let track = (scrollbar_mode == ScrollbarMode::Always)
+                                .then(|| crate::scroll::scrollbar_track_rect(x, y, w, h, padding))
+                                .flatten();
+                            thumb.map_or_else(Vec::new, |thumb| {
+                                crate::scroll::scrollbar_quads(&thumb, track, alpha)
+                            })
+                        });
+                        // Horizontal position bar, shown while columns are off screen
+                        let mut scrollbar = scrollbar;
+                        if let Some(state) = self.pane_states.get(pane_id).filter(|s| s.soft_wrap.is_none()) {
+                            let total_cols = state.terminal.columns();
+                            if let Some((track, thumb)) = crate::scroll::hscrollbar_rects(
+                                rect.x + Self::PANE_GRID_PADDING,
+                                rect.y + Self::PANE_GRID_PADDING,
+                                rect.width - Self::PANE_GRID_PADDING * 2.0,
+                                rect.height - Self::PANE_GRID_PADDING * 2.0,
+                                padding,
+                                state.h_scroll.visible(total_cols),
+                                total_cols,
+                                state.h_scroll.offset(),
+                            ) {
+                                scrollbar.extend(crate::scroll::scrollbar_quads(&thumb, Some(track), 0.6));
+                            }
+                        }
+
+                        // Scrollback minimap column, from the pane's cached image
+                        let minimap = self
+                            .pane_states
+                            .get(pane_id)
+                            .filter(|_| self.minimap_visible)
+                            .and_then(|state| {
+                                let image = state.minimap.handle()?.clone();
+                                let layout = crate::renderer::minimap::minimap_layout(
                                     rect.x + Self::PANE_GRID_PADDING,
                                     rect.y + Self::PANE_GRID_PADDING,
                                     rect.width - Self::PANE_GRID_PADDING * 2.0,
                                     rect.height - Self::PANE_GRID_PADDING * 2.0,
-                                );
-                                let thumb = crate::scroll::scrollbar_thumb_rect(
-                                    content_rect.x, content_rect.y,
-                                    content_rect.width, content_rect.height,
-                                    padding, visible_rows, history_size, display_offset,
-                                ).map(|t| (t.x, t.y, t.width, t.height));
-                                (thumb, alpha)
+                                    padding,
+                                    self.app_config.minimap.width as f32 * ui_scale,
+                                    self.app_config.minimap.line_height as f32 * ui_scale,
+                                    state.terminal.history_size(),
+                                    state.terminal.rows(),
+                                    state.scroll_state.current_line_offset(),
+                                )?;
+                                let marks = if self.app_config.search.match_strip && state.search.is_active {
+                                    crate::renderer::minimap::match_marks(
+                                        &layout,
+                                        state.terminal.history_size(),
+                                        &state.search.matches,
+                                        state.search.current_index,
+                                    )
+                                } else {
+                                    Vec::new()
+                                };
+                                Some(crate::renderer::minimap::MinimapView { layout, image, marks })
+                            });
+
+                        // History suggestions (or a "did you mean" hint) for the
+                        // focused pane's prompt line
+                        let suggestion_strip = if *pane_id == focused
+                            && self.input_mode == InputMode::Normal
+                        {
+                            self.pane_states.get(pane_id).and_then(|state| {
+                                if state.vi_state.is_some() || state.terminal.display_offset() != 0 {
+                                    return None;
+                                }
+                                let mut items: Vec<String> = Vec::new();
+                                let mut accept_label = "⇥".to_string();
+                                if self.app_config.suggestions.enabled {
+                                    items = state
+                                        .suggestions
+                                        .suggestions()
+                                        .into_iter()
+                                        .map(String::from)
+                                        .collect();
+                                }
+                                if items.is_empty() {
+                                    if let Some(hint) = &state.command_hint {
+                                        items.push(hint.label());
+                                        accept_label = self
+                                            .app_config
+                                            .keys
+                                            .bindings
+                                            .get("accept_command_hint")
+                                            .map(String::as_str)
+                                            .unwrap_or(crate::input::DEFAULT_COMMAND_HINT_ACCEPT)
+                                            .to_string();
+                                    }
+                                }
+                                if items.is_empty() {
+                                    return None;
+                                }
+                                let (cursor_row, _) = state.terminal.cursor_position();
+                                Some(SuggestionStrip {
+                                    x: rect.x + Self::PANE_GRID_PADDING + padding[2],
+                                    row_y: rect.y + Self::PANE_GRID_PADDING + padding[0]
+                                        + cursor_row as f32 * cell_height,
+                                    row_height: cell_height,
+                                    items,
+                                    accept_label,
+                                })
+                            })
+                        } else {
+                            None
+                        };
+
+                        // Row/column of the cursor in a long command line
+                        let prompt_indicator = if *pane_id == focused
+                            && self.input_mode == InputMode::Normal
+                            && self.app_config.prompt_indicator.enabled
+                        {
+                            self.pane_states.get(pane_id).and_then(|state| {
+                                if state.vi_state.is_some() || state.terminal.display_offset() != 0 {
+                                    return None;
+                                }
+                                let mut position = crate::prompt_indicator::prompt_position(&state.terminal)?;
+                                if self.app_config.prompt_indicator.char_count {
+                                    position.typed = state.suggestions.typed_len();
+                                }
+                                let (cursor_row, _) = state.terminal.cursor_position();
+                                Some(PromptIndicator {
+                                    row_y: rect.y + Self::PANE_GRID_PADDING + padding[0]
+                                        + cursor_row as f32 * cell_height,
+                                    row_height: cell_height,
+                                    label: position.label(),
+                                })
+                            })
+                        } else {
+                            None
+                        };
+
+                        // Column rulers, cursor crosshair and soft-wrap markers
+                        let guides = self.pane_states.get(pane_id).map(|state| {
+                            let show_rulers = state.guides.rulers.unwrap_or(self.app_config.guides.show_rulers);
+                            let show_crosshair = state.guides.crosshair.unwrap_or(self.app_config.guides.crosshair);
+                            let show_wraps = self.app_config.guides.wrap_indicator;
+                            if !show_rulers && !show_crosshair && !show_wraps {
+                                return Vec::new();
+                            }
+                            // Guides sit on the shown columns of a pane scrolled sideways
+                            let total_cols = state.terminal.columns();
+                            let h_offset = if state.soft_wrap.is_some() { 0 } else { state.h_scroll.offset() };
+                            let grid = crate::renderer::guides::GuideGrid {
+                                x: rect.x + Self::PANE_GRID_PADDING + padding[2],
+                                y: rect.y + Self::PANE_GRID_PADDING + padding[0],
+                                cell_width,
+                                cell_height,
+                                cols: state.h_scroll.visible(total_cols),
+                                rows: state.terminal.rows(),
+                            };
+                            let rulers: Vec<usize> = if show_rulers {
+                                self.app_config.guides.rulers.iter().filter_map(|r| r.checked_sub(h_offset)).collect()
                             } else {
-                                (None, 0.0)
+                                Vec::new()
+                            };
+                            let cursor = show_crosshair.then(|| {
+                                let (row, col) = state.terminal.cursor_position();
+                                (row + state.terminal.display_offset(), col)
+                            });
+                            let cursor = match &state.soft_wrap {
+                                Some(layout) => cursor.and_then(|(row, col)| layout.to_display(row, col)),
+                                None => cursor.map(|(row, col)| {
+                                    (row, col.checked_sub(h_offset).unwrap_or(usize::MAX))
+                                }),
+                            };
+                            let mut rects = crate::renderer::guides::guide_rects(&grid, &rulers, cursor);
+                            // Rows wrap at the terminal's right edge, marked only when it is in view
+                            if show_wraps && (state.soft_wrap.is_some() || h_offset + grid.cols >= total_cols) {
+                                let mut wrapped = crate::terminal::grid_bridge::wrapped_rows(&state.terminal);
+                                let mut marker_grid = grid;
+                                // Soft wrap marks its own breaks, after the wrap column
+                                if let Some(layout) = &state.soft_wrap {
+                                    let last_col = grid.cols.saturating_sub(1);
+                                    wrapped = wrapped
+                                        .into_iter()
+                                        .filter_map(|row| layout.to_display(row, last_col).map(|(row, _)| row))
+                                        .chain(layout.wrapped_rows().iter().copied())
+                                        .collect();
+                                    marker_grid.cols = self.app_config.guides.soft_wrap_column.min(grid.cols);
+                                }
+                                rects.extend(crate::renderer::guides::wrap_marker_rects(&marker_grid, &wrapped));
                             }
-                        } else {
-                            (None, 0.0)
+                            rects
+                        }).unwrap_or_default();
+                        let mut guides = guides;
+                        if self.touch_handles_visible && *pane_id == focused {
+                            let selection = self.pane_states.get(pane_id).and_then(|state| {
+                                let sel = state.mouse_selection.active_selection.as_ref()?;
+                                Some(crate::input::touch::handle_centers(
+                                    sel, cell_width, cell_height, state.terminal.display_offset(),
+                                ))
+                            });
+                            let size = crate::input::touch::HANDLE_SIZE;
+                            for (hx, hy) in selection.into_iter().flatten() {
+                                guides.push(crate::renderer::guides::GuideRect {
+                                    x: rect.x + Self::PANE_GRID_PADDING + padding[2] + hx - size / 2.0,
+                                    y: rect.y + Self::PANE_GRID_PADDING + padding[0] + hy - size / 2.0,
+                                    width: size,
+                                    height: size,
+                                    kind: crate::renderer::guides::GuideKind::SelectionHandle,
+                                });
+                            }
+                        }
+
+                        // Note markers in the left gutter beside their lines
+                        let notes = self.pane_states.get(pane_id).map(|state| {
+                            let first_line = state.terminal.history_size().saturating_sub(state.terminal.display_offset());
+                            let grid_x = rect.x + Self::PANE_GRID_PADDING + padding[2];
+                            let grid_y = rect.y + Self::PANE_GRID_PADDING + padding[0];
+                            state.notes.visible(first_line, state.terminal.rows()).map(|(row, note)| {
+                                crate::notes::NoteMarker {
+                                    x: grid_x - crate::notes::MARKER_WIDTH * 1.5,
+                                    y: grid_y + row as f32 * cell_height,
+                                    width: crate::notes::MARKER_WIDTH,
+                                    height: cell_height,
+                                    text: note.text.clone(),
+                                }
+                            }).collect()
+                        }).unwrap_or_default();
+
+                        // Hint mode labels over the first cell of each target
+                        let hints = match (&self.hint_mode, self.pane_states.get(pane_id)) {
+                            (Some((hint_pane, mode)), Some(state)) if hint_pane == pane_id => {
+                                let grid_x = rect.x + Self::PANE_GRID_PADDING + padding[2];
+                                let grid_y = rect.y + Self::PANE_GRID_PADDING + padding[0];
+                                let total_cols = state.terminal.columns();
+                                mode.visible().filter_map(|hint| {
+                                    let col = state.h_scroll.to_display(hint.start, total_cols)?;
+                                    Some(HintLabel {
+                                        x: grid_x + col as f32 * cell_width,
+                                        y: grid_y + hint.row as f32 * cell_height,
+                                        height: cell_height,
+                                        text: hint.label[mode.typed_len()..].to_string(),
+                                    })
+                                }).collect()
+                            }
+                            _ => Vec::new(),
                         };
 
+                        // Bookmark markers at the outer edge of the left gutter
+                        let bookmarks = self.pane_states.get(pane_id).map(|state| {
+                            let first_line = state.terminal.history_size().saturating_sub(state.terminal.display_offset());
+                            let grid_x = rect.x + Self::PANE_GRID_PADDING + padding[2];
+                            let grid_y = rect.y + Self::PANE_GRID_PADDING + padding[0];
+                            state.bookmarks.visible(first_line, state.terminal.rows()).map(|row| {
+                                crate::pane::Rect::new(
+                                    grid_x - crate::notes::MARKER_WIDTH * 3.0,
+                                    grid_y + row as f32 * cell_height,
+                                    crate::notes::MARKER_WIDTH,
+                                    cell_height,
+                                )
+                            }).collect()
+                        }).unwrap_or_default();
+
+                        // Search match positions on the right edge
+                        let match_strip = self.pane_states.get(pane_id).and_then(|state| {
+                            if !self.app_config.search.match_strip || !state.search.is_active {
+                                return None;
+                            }
+                            crate::search::minimap::match_strip(
+                                rect.x + Self::PANE_GRID_PADDING,
+                                rect.y + Self::PANE_GRID_PADDING,
+                                rect.width - Self::PANE_GRID_PADDING * 2.0,
+                                rect.height - Self::PANE_GRID_PADDING * 2.0,
+                                padding,
+                                state.terminal.history_size(),
+                                state.terminal.rows(),
+                                &state.search.matches,
+                                state.search.current_index,
+                                &state.search.error_rows,
+                            )
+                        });
+
                         PaneInfo {
                             // Positions relative to iced content area (which already accounts
                             // for sidebar offset via row layout). Only add PANE_GRID_PADDING.
@@ -3295,8 +7151,33 @@ impl ApplicationHandler<UserEvent> for App {
                             index: idx,
                             title,
                             shell_name: self.pane_states.get(pane_id).map(|s| s.shell_name.clone()).unwrap_or_else(|| "sh".to_string()),
-                            scrollbar_thumb,
-                            scrollbar_alpha,
+                            scrollbar,
+                            minimap,
+                            match_strip,
+                            suggestion_strip,
+                            prompt_indicator,
+                            guides,
+                            badge: self.pane_states.get(pane_id).and_then(|s| s.badge.clone()),
+                            scroll_locked: self.scroll_locks.is_locked(*pane_id),
+                            group: self.pane_groups.badge_text(*pane_id).zip(
+                                self.pane_groups.badge_color(*pane_id, &chrome_theme),
+                            ).map(|(text, color)| crate::directory_rules::PaneBadge { text, color }),
+                            error_banner: self.pane_error_banner(*pane_id),
+                            prompt: self.group_editor.as_ref().filter(|e| e.pane == *pane_id).map(|e| e.prompt())
+                            .or_else(|| self.pane_states.get(pane_id).and_then(|s| {
+                                s.note_editor
+                                    .as_ref()
+                                    .map(|editor| editor.prompt())
+                                    .or_else(|| s.clipboard_prompt.as_ref().map(|r| r.prompt()))
+                                    .or_else(|| s.restart_prompt.clone())
+                                    .or_else(|| s.flood.banner())
+                            }))
+                            .or_else(|| self.paste_prompt.as_ref().filter(|p| p.pane == *pane_id).map(PastePrompt::prompt))
+                            .or_else(|| self.url_prompt.as_ref().filter(|p| p.pane == *pane_id).map(UrlPrompt::prompt))
+                            .or_else(|| self.slow_paste.as_ref().filter(|p| p.pane() == *pane_id).map(SlowPaste::prompt)),
+                            notes,
+                            bookmarks,
+                            hints,
                         }
                     }).collect();
 
@@ -3404,7 +7285,16 @@ impl ApplicationHandler<UserEvent> for App {
                         (Vec::new(), None, Vec::new(), None, false, String::new(), 0.0)
                     };
 
+                let focused_search = self.pane_states.get(&focused_pane).map(|s| &s.search);
+                let recording_area = self
+                    .pane_recording
+                    .as_ref()
+                    .and_then(|r| self.pane_surface_rect(r.pane()));
                 if let Some(renderer) = &mut self.renderer {
+                    // Drop damage caches of panes that no longer exist, however
+                    // they went away (close, shell exit, session restore)
+                    let live = &self.pane_states;
+                    renderer.pane_damage_mut().retain_panes(|id| live.contains_key(&id));
                     let theme_clone = *renderer.theme();
                     let ui_state = UiState {
                         tabs: ui_tabs,
@@ -3418,28 +7308,70 @@ impl ApplicationHandler<UserEvent> for App {
                         window_width: width as f32,
                         window_height: height as f32,
                         scale_factor: ui_scale,
-                        search_active: self.search_state.is_active,
-                        search_query: self.search_state.query.clone(),
-                        search_current: self.search_state.current_index + 1,
-                        search_total: self.search_state.total_count(),
-                        search_error: self.search_state.error.is_some(),
+                        search_active: focused_search.is_some_and(|s| s.is_active),
+                        search_query: focused_search.map(|s| s.query.clone()).unwrap_or_default(),
+                        search_current: focused_search.map_or(0, |s| s.current_index) + 1,
+                        search_total: focused_search.map_or(0, |s| s.total_count()),
+                        search_error: focused_search.is_some_and(|s| s.error.is_some()),
+                        search_options: focused_search.map(|s| s.options()).unwrap_or_default(),
                         dividers: ui_dividers,
                         bell_flash: self.bell_flash_until.is_some_and(|t| std::time::Instant::now() < t),
+                        screenshot_flash: self
+                            .screenshot_flash
+                            .filter(|(_, until)| std::time::Instant::now() < *until)
+                            .map(|(area, _)| area),
                         palette_active: self.palette_state.is_some(),
                         palette_query: self.palette_state.as_ref().map(|p| p.query.clone()).unwrap_or_default(),
                         palette_items: self.palette_state.as_ref().map(|p| {
                             let registry = command_palette::command_registry();
                             p.filtered.iter().map(|&(idx, _)| {
                                 let entry = &registry[idx];
-                                (entry.name.to_string(), entry.description.to_string(), entry.keybinding.to_string())
+                                let keys: Vec<String> = self
+                                    .keymap
+                                    .combos_for(entry.action)
+                                    .map(crate::input::cheatsheet::display_combo)
+                                    .collect();
+                                (entry.name.to_string(), entry.description.to_string(), keys.join(" / "))
                             }).collect()
                         }).unwrap_or_default(),
                         palette_selected: self.palette_state.as_ref().map(|p| p.selected).unwrap_or(0),
+                        bookmark_list: self.bookmark_list.as_ref().map(|_| {
+                            let focused = self.tab_manager.active_tab().pane_tree.focused_pane_id();
+                            self.pane_states.get(&focused).map_or_else(Vec::new, |state| {
+                                state.bookmarks.marks().iter().map(|mark| {
+                                    let text = if mark.text.is_empty() { "(blank line)".to_string() } else { mark.text.clone() };
+                                    (format!("line {}", mark.line + 1), text)
+                                }).collect()
+                            })
+                        }),
+                        bookmark_selected: self.bookmark_list.as_ref().map_or(0, |list| list.selected),
+                        register_list: self.register_list.as_ref().map(|_| {
+                            self.vi_registers.entries().map(|(name, text)| {
+                                (format!("\"{name}"), crate::vi_mode::registers::preview(text, 120))
+                            }).collect()
+                        }),
+                        register_selected: self.register_list.as_ref().map_or(0, |list| list.selected),
+                        profile_picker: self.profile_picker.as_ref().map(|_| {
+                            let shell = crate::pty::resolve_shell(&self.app_config.shell);
+                            std::iter::once(("Default".to_string(), crate::pty::basename_from_path(&shell).to_string()))
+                                .chain(self.app_config.profiles.iter().map(|profile| {
+                                    let command = std::iter::once(profile.command.as_deref().unwrap_or("shell"))
+                                        .chain(profile.args.iter().map(String::as_str))
+                                        .collect::<Vec<_>>()
+                                        .join(" ");
+                                    (profile.name.clone(), command)
+                                }))
+                                .collect()
+                        }),
+                        profile_selected: self.profile_picker.as_ref().map_or(0, |picker| picker.selected),
                         sidebar_visible: self.sidebar_visible,
                         sidebar_tabs: ui_sidebar_tabs,
                         sidebar_width: self.app_config.sidebar.width,
                         hovered_sidebar_tab: self.hovered_sidebar_tab,
                         theme_selector_open: self.theme_selector_open,
+                        cheatsheet: self
+                            .cheatsheet_open
+                            .then(|| crate::input::cheatsheet::cheatsheet(&self.keymap)),
                         editing_tab: self.editing_sidebar_tab,
                         editing_tab_value: self.editing_tab_value.clone(),
                         hovering_new_tab: self.hovering_new_tab,
@@ -3519,22 +7451,37 @@ impl ApplicationHandler<UserEvent> for App {
                         git_review_current_hunk: self.git_review_state.as_ref()
                             .map(|s| s.current_hunk_index)
                             .unwrap_or(0),
+                        git_segment: if self.app_config.git_status.enabled {
+                            self.pane_states.get(&pane_tree.focused_pane_id())
+                                .and_then(|s| s.terminal.shell_state().cwd.as_deref())
+                                .and_then(|cwd| self.git_segments.get(cwd).cloned())
+                        } else {
+                            None
+                        },
+                        secure_keyboard_entry: self.secure_keyboard_entry,
+                        recording: self.pane_recording.as_ref().map(|r| r.label(std::time::Instant::now())),
                     };
 
                     let mut iced_msgs = Vec::new();
+                    let mut captured = None;
                     match renderer.render_panes(&mut pane_descs, &ui_state) {
                         Ok((surface_texture, messages)) => {
                             iced_msgs = messages;
-                            // Take screenshot if requested
-                            if self.screenshot_requested {
-                                self.screenshot_requested = false;
-                                let path = match std::env::var("VELOTERM_PROJECT_DIR") {
-                                    Ok(dir) => std::path::PathBuf::from(dir).join("veloterm-latest.png"),
-                                    Err(_) => std::path::PathBuf::from("veloterm-latest.png"),
-                                };
-                                match renderer.capture_screenshot(&surface_texture.texture, &path) {
-                                    Ok(_) => {},
-                                    Err(e) => log::error!("✗ Screenshot failed: {}", e),
+                            // Cut a requested screenshot from this frame
+                            if let Some(pending) = self.pending_screenshot.take() {
+                                match renderer.capture_image(&surface_texture.texture) {
+                                    Ok(frame) => captured = Some((pending, frame)),
+                                    Err(e) => log::error!("Screenshot failed: {e}"),
+                                }
+                            }
+                            // Add the recorded pane to its GIF when it changed
+                            if let (Some(recording), Some(area)) = (&mut self.pane_recording, recording_area) {
+                                let now = std::time::Instant::now();
+                                if recording.wants_frame(now, renderer.pane_damaged(recording.pane())) {
+                                    match renderer.capture_image(&surface_texture.texture) {
+                                        Ok(frame) => recording.push_frame(&frame, &area, now),
+                                        Err(e) => log::warn!("Recording frame failed: {e}"),
+                                    }
                                 }
                             }
                             surface_texture.present();
@@ -3550,8 +7497,17 @@ impl ApplicationHandler<UserEvent> for App {
                             log::warn!("Surface error: {e}");
                         }
                     }
+                    if let Some((pending, frame)) = captured {
+                        self.finish_screenshot(pending, frame);
+                    }
+                    if self.pane_recording.as_ref().is_some_and(|r| r.expired(std::time::Instant::now())) {
+                        self.stop_pane_recording();
+                    }
 
                     // Process iced UI messages (tab interactions)
+                    if !iced_msgs.is_empty() {
+                        self.frame_pacer.mark_dirty();
+                    }
                     for msg in iced_msgs {
                         match msg {
                             UiMessage::TabSelected(idx) => {
@@ -3565,7 +7521,7 @@ impl ApplicationHandler<UserEvent> for App {
                                 let pane_ids = self.tab_manager.close_tab(idx);
                                 if let Some(ids) = pane_ids {
                                     for id in &ids {
-                                        self.pane_states.remove(id);
+                                        self.discard_pane(*id);
                                         if let Some(r) = &mut self.renderer {
                                             r.remove_pane_damage(*id);
                                         }
@@ -3612,37 +7568,20 @@ impl ApplicationHandler<UserEvent> for App {
                                 }
                             }
                             UiMessage::SetTheme(ref name) => {
-                                if let Some(new_theme) = TerminalTheme::from_name(name) {
+                                if let Some(new_theme) = self.app_config.colors.resolve_theme(name) {
                                     if let Some(r) = &mut self.renderer {
                                         r.set_theme(new_theme);
                                         r.pane_damage_mut().force_full_damage_all();
                                     }
                                     self.app_config.colors.theme = name.clone();
+                                    self.apply_background();
                                     self.signal_theme_change_to_shells();
+                                    self.invalidate_minimaps();
                                 }
                                 self.theme_selector_open = false;
                             }
                             UiMessage::RenameTab(idx) => {
-                                let title = self.tab_manager.tabs().get(idx)
-                                    .map(|t| t.title.clone())
-                                    .unwrap_or_default();
-                                self.editing_sidebar_tab = Some(idx);
-                                self.editing_tab_value = title;
-                            }
-                            UiMessage::RenameTabInput(value) => {
-                                self.editing_tab_value = value;
-                            }
-                            UiMessage::RenameTabCommit(idx, title) => {
-                                let final_title = if title.trim().is_empty() {
-                                    self.tab_manager.tabs().get(idx)
-                                        .map(|t| t.title.clone())
-                                        .unwrap_or_else(|| "Shell".to_string())
-                                } else {
-                                    title
-                                };
-                                self.tab_manager.set_title(idx, &final_title);
-                                self.editing_sidebar_tab = None;
-                                self.editing_tab_value.clear();
+                                self.start_tab_rename(idx);
                             }
                             UiMessage::NewTabHovered(hovered) => {
                                 self.hovering_new_tab = hovered;
@@ -3693,19 +7632,7 @@ impl ApplicationHandler<UserEvent> for App {
                             }
                             UiMessage::MarkdownLinkClicked(url) => {
                                 log::info!("Markdown link clicked: {}", url);
-                                // Open URL in default browser
-                                #[cfg(target_os = "macos")]
-                                {
-                                    let _ = std::process::Command::new("open")
-                                        .arg(url.as_str())
-                                        .spawn();
-                                }
-                                #[cfg(not(target_os = "macos"))]
-                                {
-                                    let _ = std::process::Command::new("xdg-open")
-                                        .arg(url.as_str())
-                                        .spawn();
-                                }
+                                self.open_url_checked(url.to_string());
                             }
                             UiMessage::ContextMenuAction(action) => {
                                 self.context_menu_visible = false;
@@ -3714,6 +7641,54 @@ impl ApplicationHandler<UserEvent> for App {
                             UiMessage::ContextMenuDismiss => {
                                 self.context_menu_visible = false;
                             }
+                            UiMessage::CheatsheetDismiss => {
+                                self.cheatsheet_open = false;
+                            }
+                            UiMessage::PaletteItemClicked(index) => {
+                                let action = self.palette_state.as_mut().and_then(|palette| {
+                                    palette.select(index);
+                                    palette.selected_action()
+                                });
+                                self.input_mode = InputMode::Normal;
+                                self.palette_state = None;
+                                if let Some(action) = action {
+                                    self.dispatch_action(action, event_loop);
+                                }
+                            }
+                            UiMessage::BookmarkClicked(index) => {
+                                if let Some(list) = &mut self.bookmark_list {
+                                    list.selected = index;
+                                }
+                                self.handle_bookmark_list_key(&Key::Named(NamedKey::Enter));
+                            }
+                            UiMessage::Search(cmd) => {
+                                self.handle_search_command(cmd);
+                            }
+                            UiMessage::BookmarkListDismiss => {
+                                self.bookmark_list = None;
+                            }
+                            UiMessage::RegisterClicked(index) => {
+                                if let Some(list) = &mut self.register_list {
+                                    list.selected = index;
+                                }
+                                self.handle_register_list_key(&Key::Named(NamedKey::Enter));
+                            }
+                            UiMessage::RegisterListDismiss => {
+                                self.register_list = None;
+                            }
+                            UiMessage::ProfileClicked(index) => {
+                                if let Some(picker) = &mut self.profile_picker {
+                                    picker.selected = index;
+                                }
+                                self.handle_profile_picker_key(&Key::Named(NamedKey::Enter));
+                            }
+                            UiMessage::ProfilePickerDismiss => {
+                                self.profile_picker = None;
+                            }
+                            UiMessage::PaletteDismiss => {
+                                self.input_mode = InputMode::Normal;
+                                self.palette_state = None;
+                            }
                             UiMessage::ToggleFileBrowser => {
                                 self.input_mode = crate::file_browser::toggle_file_browser(self.input_mode);
                                 if self.input_mode == InputMode::FileBrowser {
@@ -3930,13 +7905,15 @@ impl ApplicationHandler<UserEvent> for App {
                         }
                     }
                 }
-                // Request next frame
-                if let Some(window) = &self.window {
-                    window.request_redraw();
-                }
             }
             _ => {}
         }
+
+        if render_now && self.renderer.is_some() {
+            self.await_input_echo();
+            self.frame_pacer.request_immediate();
+            self.window_event(event_loop, window_id, WindowEvent::RedrawRequested);
+        }
     }
 }
 
@@ -4350,6 +8327,13 @@ mod tests {
         assert_eq!(attrs.title, "VeloTerm");
     }
 
+    #[test]
+    fn window_config_default_class_matches_desktop_entry() {
+        assert_eq!(WindowConfig::default().class, "veloterm");
+        let entry = include_str!("../assets/veloterm.desktop");
+        assert!(entry.contains("\nStartupWMClass=veloterm\n"));
+    }
+
     #[test]
     fn window_attributes_is_resizable() {
         let cfg = WindowConfig::default();
@@ -4367,6 +8351,21 @@ mod tests {
         assert!(!attrs.resizable);
     }
 
+    #[test]
+    fn window_attributes_transparent_with_blur() {
+        let attrs = WindowConfig::default().to_window_attributes();
+        assert!(!attrs.transparent);
+        assert!(!attrs.blur);
+        let cfg = WindowConfig {
+            transparent: true,
+            blur: true,
+            ..Default::default()
+        };
+        let attrs = cfg.to_window_attributes();
+        assert!(attrs.transparent);
+        assert!(attrs.blur);
+    }
+
     #[test]
     fn window_attributes_custom_size() {
         let cfg = WindowConfig {
@@ -4420,6 +8419,14 @@ mod tests {
         assert!((size - 19.5).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn font_scale_override_beats_config() {
+        assert_eq!(resolve_font_scale(None, 0.0), None);
+        assert_eq!(resolve_font_scale(None, 1.0), Some(1.0));
+        assert_eq!(resolve_font_scale(Some(2.0), 1.0), Some(2.0));
+        assert_eq!(resolve_font_scale(Some(1.5), 0.0), Some(1.5));
+    }
+
     // ── App initialization and shutdown ──────────────────────────────
 
     #[test]
@@ -4447,12 +8454,40 @@ mod tests {
             height: 600.0,
             title: "Test".to_string(),
             resizable: false,
+            class: "test".to_string(),
+            config_path: None,
+            daemon_socket: None,
+            transparent: false,
+            blur: false,
         };
         let app = App::new(cfg.clone(), Config::default());
         assert_eq!(app.config.width, 800.0);
         assert_eq!(app.config.title, "Test");
     }
 
+    #[test]
+    fn launch_command_becomes_first_pane_profile() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        app.launch_command(vec!["git".into(), "mergetool".into()], Some("/tmp/repo".into()));
+        app.set_title("merge".into());
+        let launch = app.launch.as_ref().unwrap();
+        assert_eq!(launch.command.as_deref(), Some("git"));
+        assert_eq!(launch.args, ["mergetool"]);
+        assert_eq!(launch.cwd.as_deref(), Some("/tmp/repo"));
+        assert_eq!(app.config.title, "merge");
+        assert_eq!(app.launch_title.as_deref(), Some("merge"));
+    }
+
+    #[test]
+    fn launch_grid_gives_each_command_a_profile() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        app.launch_grid(&["htop".into(), "ping 1.1.1.1".into()], Some("/srv".into()));
+        let names: Vec<&str> = app.launch_grid.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["htop", "ping 1.1.1.1"]);
+        assert_eq!(app.launch_grid[1].args, ["-c", "ping 1.1.1.1"]);
+        assert!(app.launch_grid.iter().all(|p| p.cwd.as_deref() == Some("/srv")));
+    }
+
     #[test]
     fn app_stores_app_config() {
         let mut app_config = Config::default();
@@ -4463,6 +8498,16 @@ mod tests {
         assert_eq!(app.app_config.scrollback.lines, 5000);
     }
 
+    #[test]
+    fn secure_keyboard_entry_toggle_is_macos_only() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        assert!(!app.secure_keyboard_entry);
+        app.handle_app_command(AppCommand::ToggleSecureKeyboardEntry);
+        assert_eq!(app.secure_keyboard_entry, cfg!(target_os = "macos"));
+        app.handle_app_command(AppCommand::ToggleSecureKeyboardEntry);
+        assert!(!app.secure_keyboard_entry);
+    }
+
     #[test]
     fn app_config_defaults_match_expected_values() {
         let app = App::new(WindowConfig::default(), Config::default());
@@ -4950,6 +8995,30 @@ blink = false
                 scroll_state: crate::scroll::ScrollState::new(),
                 shell_name: "zsh".to_string(),
                 markdown_preview: None,
+                suggestions: crate::suggestions::SuggestionState::new(),
+                color_filter: None,
+                guides: Default::default(),
+                soft_wrap: None,
+                h_scroll: Default::default(),
+                command_hint: None,
+                badge: None,
+                directory_theme: None,
+                search: SearchState::default(),
+                watchdog: crate::pty::watchdog::Watchdog::new(),
+                flood: crate::pty::flood::FloodGuard::new(0),
+                control_bytes: None,
+                output_filter: None,
+                compat: Compat::Standard,
+                restart_prompt: None,
+                minimap: Default::default(),
+                clipboard_prompt: None,
+                notes: Default::default(),
+                note_editor: None,
+                bookmarks: Default::default(),
+            profile: None,
+            theme: None,
+            process_name: None,
+            title: None,
             },
         );
 
@@ -4982,6 +9051,30 @@ blink = false
                 scroll_state: crate::scroll::ScrollState::new(),
                 shell_name: "zsh".to_string(),
                 markdown_preview: None,
+                suggestions: crate::suggestions::SuggestionState::new(),
+                color_filter: None,
+                guides: Default::default(),
+                soft_wrap: None,
+                h_scroll: Default::default(),
+                command_hint: None,
+                badge: None,
+                directory_theme: None,
+                search: SearchState::default(),
+                watchdog: crate::pty::watchdog::Watchdog::new(),
+                flood: crate::pty::flood::FloodGuard::new(0),
+                control_bytes: None,
+                output_filter: None,
+                compat: Compat::Standard,
+                restart_prompt: None,
+                minimap: Default::default(),
+                clipboard_prompt: None,
+                notes: Default::default(),
+                note_editor: None,
+                bookmarks: Default::default(),
+            profile: None,
+            theme: None,
+            process_name: None,
+            title: None,
             },
         );
 
@@ -5162,6 +9255,25 @@ blink = false
         assert_eq!(result, 72.0); // 72 * 1.1 = 79.2 → round → 79 → clamp → 72
     }
 
+    #[test]
+    fn pinch_scale_accumulates_deltas() {
+        let scale = App::step_pinch_scale(1.0, 0.1, 13.0);
+        let scale = App::step_pinch_scale(scale, 0.1, 13.0);
+        assert!((scale - 1.21).abs() < 1e-5);
+    }
+
+    #[test]
+    fn pinch_scale_respects_font_limits() {
+        assert_eq!(App::step_pinch_scale(1.0, 10.0, 36.0), 2.0);
+        assert_eq!(App::step_pinch_scale(1.0, -0.9, 16.0), 0.5);
+    }
+
+    #[test]
+    fn pinch_snaps_to_whole_font_size() {
+        assert_eq!(App::pinch_font_size(13.0, 1.21), 16.0);
+        assert_eq!(App::pinch_font_size(13.0, 1.0), 13.0);
+    }
+
     #[test]
     fn font_size_tracks_in_app() {
         let app = App::new(WindowConfig::default(), Config::default());
@@ -5204,7 +9316,7 @@ blink = false
     }
 
     #[test]
-    fn config_reload_cursor_always_zero_blink_rate() {
+    fn config_reload_cursor_applies_blink_interval() {
         let mut app = App::new(WindowConfig::default(), Config::default());
         let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
         let terminal = crate::terminal::Terminal::new(80, 24, 10_000);
@@ -5219,25 +9331,49 @@ blink = false
                 scroll_state: crate::scroll::ScrollState::new(),
                 shell_name: "zsh".to_string(),
                 markdown_preview: None,
+                suggestions: crate::suggestions::SuggestionState::new(),
+                color_filter: None,
+                guides: Default::default(),
+                soft_wrap: None,
+                h_scroll: Default::default(),
+                command_hint: None,
+                badge: None,
+                directory_theme: None,
+                search: SearchState::default(),
+                watchdog: crate::pty::watchdog::Watchdog::new(),
+                flood: crate::pty::flood::FloodGuard::new(0),
+                control_bytes: None,
+                output_filter: None,
+                compat: Compat::Standard,
+                restart_prompt: None,
+                minimap: Default::default(),
+                clipboard_prompt: None,
+                notes: Default::default(),
+                note_editor: None,
+                bookmarks: Default::default(),
+            profile: None,
+            theme: None,
+            process_name: None,
+            title: None,
             },
         );
 
         // CursorState::new() defaults to 500ms blink
         assert_eq!(app.pane_states[&pane_id].cursor.blink_rate_ms, 500);
 
-        // After config reload requesting blink, rate is forced to 0
+        // After config reload the configured interval applies
         let mut new_config = Config::default();
         new_config.cursor.blink = true;
-        new_config.cursor.blink_rate = 750;
+        new_config.cursor.blink_interval = 750;
         let delta = app.app_config.diff(&new_config);
         assert!(delta.cursor_changed);
 
         app.handle_config_reload(new_config, delta);
-        assert_eq!(app.pane_states[&pane_id].cursor.blink_rate_ms, 0);
+        assert_eq!(app.pane_states[&pane_id].cursor.blink_rate_ms, 750);
     }
 
     #[test]
-    fn config_reload_cursor_always_block_no_blink() {
+    fn config_reload_cursor_style_yields_to_decscusr() {
         let mut app = App::new(WindowConfig::default(), Config::default());
         let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
         let terminal = crate::terminal::Terminal::new(80, 24, 10_000);
@@ -5252,22 +9388,47 @@ blink = false
                 scroll_state: crate::scroll::ScrollState::new(),
                 shell_name: "zsh".to_string(),
                 markdown_preview: None,
+                suggestions: crate::suggestions::SuggestionState::new(),
+                color_filter: None,
+                guides: Default::default(),
+                soft_wrap: None,
+                h_scroll: Default::default(),
+                command_hint: None,
+                badge: None,
+                directory_theme: None,
+                search: SearchState::default(),
+                watchdog: crate::pty::watchdog::Watchdog::new(),
+                flood: crate::pty::flood::FloodGuard::new(0),
+                control_bytes: None,
+                output_filter: None,
+                compat: Compat::Standard,
+                restart_prompt: None,
+                minimap: Default::default(),
+                clipboard_prompt: None,
+                notes: Default::default(),
+                note_editor: None,
+                bookmarks: Default::default(),
+            profile: None,
+            theme: None,
+            process_name: None,
+            title: None,
             },
         );
 
         let mut new_config = Config::default();
         new_config.cursor.style = "underline".to_string();
-        new_config.cursor.blink = true;
-        new_config.cursor.blink_rate = 500;
+        new_config.cursor.blink = false;
+        new_config.cursor.blink_interval = 500;
         let delta = app.app_config.diff(&new_config);
 
         app.handle_config_reload(new_config, delta);
-        // Cursor is always forced to Block with no blink regardless of config
-        assert_eq!(
-            app.pane_states[&pane_id].cursor.style,
-            crate::renderer::cursor::CursorStyle::Block
-        );
-        assert_eq!(app.pane_states[&pane_id].cursor.blink_rate_ms, 0);
+        // The configured style is the terminal's default...
+        let state = app.pane_states.get_mut(&pane_id).unwrap();
+        use crate::renderer::cursor::CursorStyle;
+        assert_eq!(state.terminal.cursor_style(), (CursorStyle::Underline, false));
+        // ...until the application asks for another shape
+        state.terminal.feed(b"\x1b[5 q");
+        assert_eq!(state.terminal.cursor_style(), (CursorStyle::Beam, true));
     }
 
     #[test]
@@ -5347,4 +9508,379 @@ blink = false
         // Content width shrinks
         assert!(bounds_with.width < bounds_without.width);
     }
+
+
+    // ── Per-pane search ─────────────────────────────────────────────
+
+    fn insert_plain_pane(app: &mut App, pane_id: PaneId) {
+        app.pane_states.insert(
+            pane_id,
+            PaneState {
+                terminal: crate::terminal::Terminal::new(80, 24, 10_000),
+                pty: crate::pty::PtySession::new(&crate::pty::default_shell(), 80, 24).unwrap(),
+                vi_state: None,
+                cursor: crate::renderer::cursor::CursorState::new(),
+                mouse_selection: crate::input::mouse::MouseSelectionState::new(),
+                scroll_state: crate::scroll::ScrollState::new(),
+                shell_name: "zsh".to_string(),
+                markdown_preview: None,
+                suggestions: crate::suggestions::SuggestionState::new(),
+                color_filter: None,
+                guides: Default::default(),
+                soft_wrap: None,
+                h_scroll: Default::default(),
+                command_hint: None,
+                badge: None,
+                directory_theme: None,
+                search: SearchState::default(),
+                watchdog: crate::pty::watchdog::Watchdog::new(),
+                flood: crate::pty::flood::FloodGuard::new(0),
+                control_bytes: None,
+                output_filter: None,
+                compat: Compat::Standard,
+                restart_prompt: None,
+                minimap: Default::default(),
+                clipboard_prompt: None,
+                notes: Default::default(),
+                note_editor: None,
+                bookmarks: Default::default(),
+            profile: None,
+            theme: None,
+            process_name: None,
+            title: None,
+            },
+        );
+    }
+
+    #[test]
+    fn clipboard_request_is_denied_while_a_prompt_is_pending() {
+        use crate::shell_integration::clipboard::ClipboardTarget;
+
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        insert_plain_pane(&mut app, pane_id);
+        let load = |target| ClipboardRequest::Load {
+            target,
+            reply: std::sync::Arc::new(|text: &str| text.to_string()),
+        };
+        let config = app.app_config.clipboard.clone();
+        let state = app.pane_states.get_mut(&pane_id).unwrap();
+        App::handle_clipboard_request(state, load(ClipboardTarget::Clipboard), &config);
+        App::handle_clipboard_request(state, load(ClipboardTarget::Selection), &config);
+        let prompt = format!("{:?}", state.clipboard_prompt);
+        assert_eq!(prompt, "Some(Load(Clipboard))");
+    }
+
+    #[test]
+    fn app_search_state_is_kept_per_pane() {
+        use crate::pane::interaction::InteractionEffect;
+
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let first_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let second_id = app
+            .tab_manager
+            .active_tab_mut()
+            .pane_tree
+            .split_focused(SplitDirection::Vertical)
+            .unwrap();
+        insert_plain_pane(&mut app, first_id);
+        insert_plain_pane(&mut app, second_id);
+
+        app.open_search();
+        app.handle_search_command(SearchCommand::InsertChar('x'));
+        assert_eq!(app.input_mode(), InputMode::Search);
+        assert_eq!(app.search_state().unwrap().query, "x");
+
+        // The other pane has no search; typing goes back to the shell
+        app.apply_interaction_effect(InteractionEffect::FocusPane(first_id));
+        app.sync_search_mode();
+        assert_eq!(app.input_mode(), InputMode::Normal);
+        assert!(!app.search_state().unwrap().is_active);
+
+        // Returning resumes the original query
+        app.apply_interaction_effect(InteractionEffect::FocusPane(second_id));
+        app.sync_search_mode();
+        assert_eq!(app.input_mode(), InputMode::Search);
+        assert_eq!(app.search_state().unwrap().query, "x");
+    }
+
+    #[test]
+    fn app_close_search_only_clears_focused_pane() {
+        use crate::pane::interaction::InteractionEffect;
+
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let first_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let second_id = app
+            .tab_manager
+            .active_tab_mut()
+            .pane_tree
+            .split_focused(SplitDirection::Vertical)
+            .unwrap();
+        insert_plain_pane(&mut app, first_id);
+        insert_plain_pane(&mut app, second_id);
+
+        app.open_search();
+        app.handle_search_command(SearchCommand::InsertChar('a'));
+        app.apply_interaction_effect(InteractionEffect::FocusPane(first_id));
+        app.sync_search_mode();
+        app.open_search();
+        app.handle_search_command(SearchCommand::InsertChar('b'));
+        app.handle_search_command(SearchCommand::Close);
+
+        assert_eq!(app.input_mode(), InputMode::Normal);
+        assert!(!app.pane_states[&first_id].search.is_active);
+        assert_eq!(app.pane_states[&second_id].search.query, "a");
+    }
+
+
+    #[test]
+    fn app_close_group_closes_its_panes_only() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let first_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let pane_tree = &mut app.tab_manager.active_tab_mut().pane_tree;
+        let second_id = pane_tree.split_focused(SplitDirection::Vertical).unwrap();
+        let third_id = pane_tree.split_focused(SplitDirection::Horizontal).unwrap();
+        for id in [first_id, second_id, third_id] {
+            insert_plain_pane(&mut app, id);
+        }
+        app.pane_groups.assign(first_id, "web");
+        app.pane_groups.assign(third_id, "web");
+        app.pane_groups.assign(second_id, "db");
+
+        app.close_group("web");
+        assert_eq!(app.tab_manager.active_tab().pane_tree.pane_ids(), vec![second_id]);
+        assert!(!app.pane_states.contains_key(&first_id));
+        assert!(app.pane_groups.members("web").is_empty());
+
+        // The window's last pane stays open
+        app.close_group("db");
+        assert_eq!(app.tab_manager.active_tab().pane_tree.pane_ids(), vec![second_id]);
+    }
+
+    #[test]
+    fn app_search_follows_scroll_lock_partner() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let first_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        let second_id = app
+            .tab_manager
+            .active_tab_mut()
+            .pane_tree
+            .split_focused(SplitDirection::Vertical)
+            .unwrap();
+        insert_plain_pane(&mut app, first_id);
+        insert_plain_pane(&mut app, second_id);
+        app.scroll_locks.toggle(first_id, second_id);
+
+        app.open_search();
+        app.handle_search_command(SearchCommand::InsertChar('a'));
+        app.handle_search_command(SearchCommand::ToggleWholeWord);
+        let linked = &app.pane_states[&first_id].search;
+        assert!(linked.is_active);
+        assert_eq!(linked.query, "a");
+        assert!(linked.options().whole_word);
+
+        app.handle_search_command(SearchCommand::Close);
+        assert!(!app.pane_states[&first_id].search.is_active);
+        assert!(!app.pane_states[&second_id].search.is_active);
+    }
+
+    #[test]
+    fn app_keymap_uses_config_overrides() {
+        let mut app_config = Config::default();
+        app_config
+            .keys
+            .bindings
+            .insert("alt+t".to_string(), "new_tab".to_string());
+        let app = App::new(WindowConfig::default(), app_config);
+        let action = app.keymap.lookup(&Key::Character("t".into()), ModifiersState::ALT);
+        assert_eq!(action, Some(Action::Tab(TabCommand::NewTab)));
+    }
+
+    #[test]
+    fn app_config_reload_rebuilds_keymap() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let mut new_config = Config::default();
+        new_config
+            .keys
+            .bindings
+            .insert("alt+t".to_string(), "new_tab".to_string());
+        let delta = app.app_config.diff(&new_config);
+        app.handle_config_reload(new_config, delta);
+        let action = app.keymap.lookup(&Key::Character("t".into()), ModifiersState::ALT);
+        assert_eq!(action, Some(Action::Tab(TabCommand::NewTab)));
+    }
+
+
+    #[test]
+    fn app_match_strip_click_selects_match() {
+        use crate::search::SearchMatch;
+
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        insert_plain_pane(&mut app, pane_id);
+        let matches: Vec<SearchMatch> = [0, 23]
+            .iter()
+            .map(|&row| SearchMatch { row, start_col: 0, end_row: row, end_col: 1 })
+            .collect();
+        {
+            let search = &mut app.pane_states.get_mut(&pane_id).unwrap().search;
+            search.matches = matches.clone();
+        }
+
+        // Inactive search: the strip is not shown, so clicks fall through
+        let pgrid = app.pane_grid_bounds(1280.0, 720.0);
+        let strip = crate::search::minimap::match_strip(
+            pgrid.x,
+            pgrid.y,
+            pgrid.width - App::PANE_GRID_PADDING * 2.0,
+            pgrid.height - App::PANE_GRID_PADDING * 2.0,
+            [0.0; 4],
+            0,
+            24,
+            &matches,
+            0,
+            &[],
+        )
+        .unwrap();
+        let last = strip.markers.last().unwrap();
+        let (x, y) = (strip.x + 1.0, last.y + 1.0);
+        assert!(!app.match_strip_click(x, y));
+
+        app.open_search();
+        assert!(app.match_strip_click(x, y));
+        assert_eq!(app.search_state().unwrap().current_index, 1);
+    }
+
+
+    #[test]
+    fn app_search_finds_matches_in_scrollback() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        insert_plain_pane(&mut app, pane_id);
+        {
+            let terminal = &mut app.pane_states.get_mut(&pane_id).unwrap().terminal;
+            terminal.feed(b"needle\r\n");
+            for i in 0..200 {
+                terminal.feed(format!("filler {i}\r\n").as_bytes());
+            }
+        }
+
+        app.open_search();
+        for ch in "needle".chars() {
+            app.handle_search_command(SearchCommand::InsertChar(ch));
+        }
+        while app.search_state().unwrap().is_scanning() {
+            app.advance_searches();
+        }
+
+        let search = app.search_state().unwrap();
+        assert_eq!(search.total_count(), 1);
+        assert!(search.matches[0].row < 0, "match should be in scrollback");
+        // Jumped to it
+        let terminal = &app.pane_states[&pane_id].terminal;
+        assert!(terminal.display_offset() > 0);
+    }
+
+    // ── Shell watchdog ──────────────────────────────────────────────
+
+    #[test]
+    fn app_spawn_failure_shows_banner_and_restarts() {
+        let mut config = Config::default();
+        config.shell.program = Some("/nonexistent/veloterm-shell".to_string());
+        let mut app = App::new(WindowConfig::default(), config);
+        let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        app.spawn_pane(pane_id, 80, 24);
+
+        assert!(!app.pane_states.contains_key(&pane_id));
+        assert!(app.pane_is_dead(pane_id));
+        let banner = app.pane_error_banner(pane_id).unwrap();
+        assert!(banner.starts_with("Could not start shell: /nonexistent/veloterm-shell"));
+        assert!(banner.contains("ctrl+alt+r"));
+
+        // Fixing the shell path and restarting brings the pane back
+        app.app_config.shell.program = Some("/bin/sh".to_string());
+        app.restart_pane(pane_id);
+        assert!(app.pane_states.contains_key(&pane_id));
+        assert!(!app.pane_is_dead(pane_id));
+        assert!(app.pane_error_banner(pane_id).is_none());
+    }
+
+    #[test]
+    fn app_watchdog_detects_exited_shell() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let pane_id = app.tab_manager.active_tab().pane_tree.focused_pane_id();
+        insert_plain_pane(&mut app, pane_id);
+        app.pane_states.get_mut(&pane_id).unwrap().pty.write(b"exit 4\n").unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while !app.pane_is_dead(pane_id) {
+            assert!(std::time::Instant::now() < deadline, "exit not detected");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            app.last_health_check -= crate::pty::watchdog::CHECK_INTERVAL;
+            app.check_pane_health();
+        }
+        let banner = app.pane_error_banner(pane_id).unwrap();
+        assert!(banner.starts_with("Shell exited with code 4"), "{banner}");
+    }
+
+    // ── Cheatsheet ──────────────────────────────────────────────────
+
+    #[test]
+    fn app_cheatsheet_toggles() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        assert!(!app.cheatsheet_open);
+        app.show_cheatsheet();
+        assert!(app.cheatsheet_open);
+        app.handle_app_command(AppCommand::ToggleCheatsheet);
+        assert!(!app.cheatsheet_open);
+        app.handle_app_command(AppCommand::ToggleCheatsheet);
+        assert!(app.cheatsheet_open);
+    }
+
+    // ── Session restore ─────────────────────────────────────────────
+
+    #[test]
+    fn app_restores_session_tabs_and_splits() {
+        use crate::session::{SessionPaneNode, SessionState, SessionTab};
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        let leaf = |cwd: &str| Box::new(SessionPaneNode::Leaf { cwd: Some(cwd.to_string()), notes: Vec::new() });
+        let session = SessionState {
+            tabs: vec![
+                SessionTab {
+                    title: "one".to_string(),
+                    title_is_manual: false,
+                    pane_tree: SessionPaneNode::Leaf { cwd: None, notes: Vec::new() },
+                },
+                SessionTab {
+                    title: "two".to_string(),
+                    title_is_manual: true,
+                    pane_tree: SessionPaneNode::Split {
+                        direction: "vertical".to_string(),
+                        ratio: 0.3,
+                        first: leaf("/tmp"),
+                        second: leaf("/"),
+                    },
+                },
+            ],
+            active_tab: 1,
+            window: None,
+        };
+
+        assert!(app.try_restore_session(session, 1280.0, 720.0));
+        assert_eq!(app.tab_manager.tab_count(), 2);
+        assert_eq!(app.tab_manager.active_index(), 1);
+        let tab = app.tab_manager.active_tab();
+        assert_eq!(tab.title, "two");
+        assert!(tab.title_is_manual);
+        assert_eq!(tab.pane_tree.pane_count(), 2);
+        assert_eq!(app.pane_states.len(), 3);
+    }
+
+    #[test]
+    fn app_restore_flag_overrides_config() {
+        let mut app = App::new(WindowConfig::default(), Config::default());
+        assert!(!app.restore_session);
+        app.request_session_restore();
+        assert!(app.restore_session);
+    }
 }
@@ -0,0 +1,228 @@
+// End-to-end tests: start VeloTerm in a window, drive it over its control
+// socket and read back what its panes show. Opening a window needs a display
+// and a GPU (or a software renderer), so those tests are ignored by default;
+// CI runs them under Xvfb:
+//
+//     xvfb-run -a cargo test --test control -- --include-ignored
+//
+// Each window runs with its own home, runtime dir and config, so the tests
+// neither see nor touch the user's sessions and windows.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use veloterm::control::protocol::{PaneEntry, Request, Response, SplitSide};
+
+/// How long a window gets to open its control socket.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a pane gets to show expected output.
+const OUTPUT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const CONFIG: &str = r#"
+[shell]
+program = "/bin/sh"
+integration_enabled = false
+
+[shell.env]
+PS1 = "$ "
+"#;
+
+/// A VeloTerm window under test, killed when dropped.
+struct Window {
+    child: Child,
+    socket: PathBuf,
+    log: PathBuf,
+    _root: tempfile::TempDir,
+}
+
+impl Window {
+    fn open() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        let dir = |name: &str| {
+            let path = root.path().join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            path
+        };
+        let config = root.path().join("config.toml");
+        std::fs::write(&config, CONFIG).unwrap();
+        let log = root.path().join("veloterm.log");
+        let runtime = dir("runtime");
+        let child = Command::new(env!("CARGO_BIN_EXE_veloterm"))
+            .arg("--config")
+            .arg(&config)
+            .args(["--log-level", "debug"])
+            .env("HOME", dir("home"))
+            .env("XDG_CONFIG_HOME", dir("config"))
+            .env("XDG_DATA_HOME", dir("data"))
+            .env("XDG_STATE_HOME", dir("state"))
+            .env("XDG_CACHE_HOME", dir("cache"))
+            .env("XDG_RUNTIME_DIR", &runtime)
+            .env_remove(veloterm::control::SOCKET_ENV)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(std::fs::File::create(&log).unwrap())
+            .spawn()
+            .expect("start veloterm");
+        let socket = runtime.join("veloterm").join(format!("ctl-{}.sock", child.id()));
+        let mut window = Self { child, socket, log, _root: root };
+        window.wait_for_socket();
+        window
+    }
+
+    fn wait_for_socket(&mut self) {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                panic!("veloterm exited with {status} before listening\n{}", self.logs());
+            }
+            if self.try_request(&Request::ListPanes).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        panic!("no control socket at {} after {STARTUP_TIMEOUT:?}\n{}", self.socket.display(), self.logs());
+    }
+
+    fn logs(&self) -> String {
+        std::fs::read_to_string(&self.log).unwrap_or_default()
+    }
+
+    fn try_request(&self, request: &Request) -> std::io::Result<Response> {
+        veloterm::control::request(&self.socket, request)
+    }
+
+    /// Send `request`, failing the test on an error response.
+    fn request(&self, request: Request) -> Response {
+        match self.try_request(&request) {
+            Ok(Response::Error { message }) => panic!("{request:?} failed: {message}"),
+            Ok(response) => response,
+            Err(e) => panic!("{request:?}: {e}\n{}", self.logs()),
+        }
+    }
+
+    fn new_pane(&self, request: Request) -> u32 {
+        match self.request(request) {
+            Response::Pane { id } => id,
+            other => panic!("expected a pane, got {other:?}"),
+        }
+    }
+
+    fn panes(&self) -> Vec<PaneEntry> {
+        match self.request(Request::ListPanes) {
+            Response::Panes { panes } => panes,
+            other => panic!("expected panes, got {other:?}"),
+        }
+    }
+
+    fn screen(&self, pane: u32) -> Vec<String> {
+        match self.request(Request::GetText { pane: Some(pane) }) {
+            Response::Text { lines } => lines,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    /// Wait until a row of `pane` reads exactly `line`.
+    fn wait_for_line(&self, pane: u32, line: &str) {
+        let deadline = Instant::now() + OUTPUT_TIMEOUT;
+        loop {
+            let screen = self.screen(pane);
+            if screen.iter().any(|row| row == line) {
+                return;
+            }
+            if Instant::now() > deadline {
+                panic!("pane {pane} never showed {line:?}; screen:\n{}", screen.join("\n"));
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Type a command into `pane`.
+    fn type_line(&self, pane: u32, command: &str) {
+        self.request(Request::SendText { pane: Some(pane), text: format!("{command}\n") });
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn focused(panes: &[PaneEntry]) -> u32 {
+    panes.iter().find(|pane| pane.focused).expect("a focused pane").id
+}
+
+/// Run `veloterm ctl` against `socket`.
+fn ctl(socket: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_veloterm"))
+        .arg("ctl")
+        .arg("--socket")
+        .arg(socket)
+        .args(args)
+        .output()
+        .expect("run veloterm ctl")
+}
+
+#[test]
+fn ctl_without_a_window_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("ctl-1.sock");
+    let output = ctl(&socket, &["list-panes"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&*socket.to_string_lossy()), "{stderr}");
+}
+
+#[test]
+#[ignore = "opens a window; run with --ignored on a display"]
+fn typed_commands_run_in_the_focused_pane() {
+    let window = Window::open();
+    let panes = window.panes();
+    assert_eq!(panes.len(), 1);
+    let pane = focused(&panes);
+    // The echoed command line differs from its output
+    window.type_line(pane, "echo velo$((40 + 2))");
+    window.wait_for_line(pane, "velo42");
+}
+
+#[test]
+#[ignore = "opens a window; run with --ignored on a display"]
+fn tabs_and_splits_keep_their_own_shells() {
+    let window = Window::open();
+    let first = focused(&window.panes());
+    let tab = window.new_pane(Request::NewTab { cwd: None, title: Some("second".into()) });
+    let split = window.new_pane(Request::SplitPane { pane: Some(tab), side: SplitSide::Down });
+
+    let panes = window.panes();
+    let tabs: Vec<(u32, usize)> = panes.iter().map(|pane| (pane.id, pane.tab)).collect();
+    assert_eq!(tabs, [(first, 0), (tab, 1), (split, 1)]);
+    assert_eq!(focused(&panes), split);
+    assert_eq!(panes[1].tab_title, "second");
+
+    window.type_line(split, "echo in-split");
+    window.type_line(first, "echo in-first");
+    window.wait_for_line(split, "in-split");
+    window.wait_for_line(first, "in-first");
+    assert!(!window.screen(split).iter().any(|row| row == "in-first"));
+
+    window.request(Request::ClosePane { pane: Some(split) });
+    let ids: Vec<u32> = window.panes().iter().map(|pane| pane.id).collect();
+    assert_eq!(ids, [first, tab]);
+}
+
+#[test]
+#[ignore = "opens a window; run with --ignored on a display"]
+fn ctl_reads_a_screen() {
+    let window = Window::open();
+    let pane = focused(&window.panes());
+    let output = ctl(&window.socket, &["send-text", "printf 'a%sc\\n' b\n"]);
+    assert!(output.status.success());
+    window.wait_for_line(pane, "abc");
+    let output = ctl(&window.socket, &["get-text", "--pane", &pane.to_string()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).lines().any(|line| line == "abc"));
+}